@@ -0,0 +1,13 @@
+use criterion::{criterion_group, criterion_main};
+
+use ha_ndarray::bench;
+
+criterion_group!(
+    benches,
+    bench::elementwise,
+    bench::reduce,
+    bench::matmul,
+    bench::transfer
+);
+
+criterion_main!(benches);
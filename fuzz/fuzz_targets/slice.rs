@@ -0,0 +1,107 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+
+use ha_ndarray::ops::SliceSpec;
+use ha_ndarray::{AxisRange, Range, Shape};
+
+const MAX_NDIM: usize = 8;
+const MAX_SIZE: usize = 1 << 16;
+
+#[derive(Arbitrary, Debug)]
+enum Bound {
+    At(u8),
+    In(u8, u8, u8),
+    Of(Vec<u8>),
+}
+
+impl From<Bound> for AxisRange {
+    fn from(bound: Bound) -> Self {
+        match bound {
+            Bound::At(i) => Self::At(i as usize),
+            Bound::In(start, stop, step) => Self::In(start as usize, stop as usize, step as usize),
+            Bound::Of(indices) => Self::Of(indices.into_iter().map(|i| i as usize).collect()),
+        }
+    }
+}
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    shape: Vec<u8>,
+    range: Vec<Bound>,
+}
+
+fuzz_target!(|input: Input| {
+    let shape: Shape = input
+        .shape
+        .iter()
+        .take(MAX_NDIM)
+        .map(|dim| *dim as usize)
+        .collect();
+    let source_size = shape.iter().product::<usize>();
+
+    if shape.is_empty() || source_size == 0 || source_size > MAX_SIZE {
+        return;
+    }
+
+    let range: Range = input
+        .range
+        .into_iter()
+        .take(MAX_NDIM)
+        .map(AxisRange::from)
+        .collect();
+
+    let spec = match SliceSpec::try_new(&shape, range) {
+        Ok(spec) => spec,
+        Err(_) => return,
+    };
+
+    assert_eq!(spec.range.len(), shape.len());
+
+    for offset in 0..spec.size() {
+        let actual = spec.source_offset(offset);
+        let expected = source_offset(&shape, &spec.range, &spec.shape, offset);
+        assert_eq!(
+            actual, expected,
+            "offset {offset} of slice {:?}",
+            spec.range
+        );
+        assert!(actual < source_size);
+    }
+});
+
+// a reference implementation which computes each coordinate explicitly
+fn source_offset(
+    source_shape: &[usize],
+    range: &[AxisRange],
+    shape: &[usize],
+    offset: usize,
+) -> usize {
+    let mut coord = [0; MAX_NDIM];
+    let mut remainder = offset;
+    for (x, dim) in shape.iter().enumerate().rev() {
+        coord[x] = remainder % dim;
+        remainder /= dim;
+    }
+
+    let mut coord = coord.into_iter();
+    let mut source_offset = 0;
+    for (x, bound) in range.iter().enumerate() {
+        let i = match bound {
+            AxisRange::At(i) => *i,
+            AxisRange::In(start, stop, step) => {
+                let i = start + coord.next().unwrap() * step;
+                assert!(i < *stop);
+                i
+            }
+            AxisRange::Of(indices) => indices[coord.next().unwrap()],
+            bound => unreachable!("unresolved axis range {bound:?}"),
+        };
+
+        assert!(i < source_shape[x]);
+        source_offset += i * source_shape[x + 1..].iter().product::<usize>();
+    }
+
+    source_offset
+}
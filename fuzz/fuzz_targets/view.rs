@@ -0,0 +1,124 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+
+use ha_ndarray::ops::ViewSpec;
+use ha_ndarray::Shape;
+
+const MAX_NDIM: usize = 8;
+const MAX_SIZE: usize = 1 << 16;
+
+#[derive(Arbitrary, Debug)]
+enum Input {
+    Broadcast {
+        source_shape: Vec<u8>,
+        shape: Vec<u8>,
+    },
+    Transpose {
+        source_shape: Vec<u8>,
+        axes: Vec<u8>,
+    },
+}
+
+fuzz_target!(|input: Input| {
+    match input {
+        Input::Broadcast {
+            source_shape,
+            shape,
+        } => {
+            let source_shape = match valid_shape(&source_shape) {
+                Some(shape) => shape,
+                None => return,
+            };
+
+            let shape = match valid_shape(&shape) {
+                Some(shape) => shape,
+                None => return,
+            };
+
+            let spec = match ViewSpec::try_broadcast(&source_shape, shape.clone()) {
+                Ok(spec) => spec,
+                Err(_) => return,
+            };
+
+            let offset = shape.len() - source_shape.len();
+
+            for i in 0..spec.size() {
+                let coord = coord_of(&shape, i);
+
+                let expected = (0..source_shape.len())
+                    .map(|x| {
+                        if source_shape[x] == 1 {
+                            0
+                        } else {
+                            coord[offset + x]
+                        }
+                    })
+                    .enumerate()
+                    .map(|(x, i)| i * source_shape[x + 1..].iter().product::<usize>())
+                    .sum::<usize>();
+
+                assert_eq!(
+                    spec.source_offset(i),
+                    expected,
+                    "broadcast {source_shape:?} into {shape:?}"
+                );
+            }
+        }
+        Input::Transpose { source_shape, axes } => {
+            let source_shape = match valid_shape(&source_shape) {
+                Some(shape) => shape,
+                None => return,
+            };
+
+            let axes = axes.into_iter().map(|x| x as usize).collect::<Vec<_>>();
+
+            let spec = match ViewSpec::try_transpose(&source_shape, &axes) {
+                Ok(spec) => spec,
+                Err(_) => return,
+            };
+
+            for i in 0..spec.size() {
+                let coord = coord_of(&spec.shape, i);
+
+                let expected = axes
+                    .iter()
+                    .zip(coord)
+                    .map(|(x, i)| i * source_shape[x + 1..].iter().product::<usize>())
+                    .sum::<usize>();
+
+                assert_eq!(
+                    spec.source_offset(i),
+                    expected,
+                    "transpose {source_shape:?} by {axes:?}"
+                );
+            }
+        }
+    }
+});
+
+fn valid_shape(dims: &[u8]) -> Option<Shape> {
+    if dims.is_empty() || dims.len() > MAX_NDIM {
+        return None;
+    }
+
+    let shape: Shape = dims.iter().map(|dim| *dim as usize).collect();
+
+    match shape.iter().product::<usize>() {
+        0 => None,
+        size if size > MAX_SIZE => None,
+        _ => Some(shape),
+    }
+}
+
+fn coord_of(shape: &[usize], offset: usize) -> [usize; MAX_NDIM] {
+    let mut coord = [0; MAX_NDIM];
+    let mut remainder = offset;
+    for (x, dim) in shape.iter().enumerate().rev() {
+        coord[x] = remainder % dim;
+        remainder /= dim;
+    }
+
+    coord
+}
@@ -4,9 +4,11 @@ use std::marker::PhantomData;
 use std::sync::Arc;
 
 use crate::buffer::{BufferConverter, BufferInstance, BufferMut};
-use crate::ops::{ReadOp, Write};
+use crate::config::{self, DebugChecks};
+use crate::introspect::{op_label, OpPlan};
+use crate::ops::{BroadcastDual, DualBroadcast, Op, ReadOp, Write};
 use crate::platform::PlatformInstance;
-use crate::{Buffer, CType, Error, Platform};
+use crate::{Buffer, CType, Constant, Error, Platform};
 
 /// A type which allows accessing array data
 pub trait Access<T: CType>: Send + Sync {
@@ -18,6 +20,11 @@ pub trait Access<T: CType>: Send + Sync {
 
     /// Return the data size.
     fn size(&self) -> usize;
+
+    /// Describe the op graph which would be executed to read these data, without executing it.
+    fn plan(&self) -> OpPlan {
+        OpPlan::buffer::<T>(self.size())
+    }
 }
 
 /// A type which allows accessing array data mutably
@@ -30,6 +37,11 @@ pub trait AccessMut<T: CType>: Access<T> + fmt::Debug {
         )))
     }
 
+    /// Borrow the array data as a mutable slice, or return an error if this is not in host memory.
+    fn host_buffer(&mut self) -> Result<&mut [T], Error> {
+        Err(Error::Unsupported(format!("not a host buffer: {self:?}")))
+    }
+
     /// Overwrite these data with the given `data`.
     fn write<'a>(&mut self, data: BufferConverter<'a, T>) -> Result<(), Error>;
 
@@ -124,6 +136,10 @@ where
         self.buffer.cl()
     }
 
+    fn host_buffer(&mut self) -> Result<&mut [T], Error> {
+        self.buffer.host()
+    }
+
     fn write<'a>(&mut self, data: BufferConverter<'a, T>) -> Result<(), Error> {
         self.buffer.write(data)
     }
@@ -143,7 +159,63 @@ impl<B: fmt::Debug> fmt::Debug for AccessBuf<B> {
     }
 }
 
+/// A constant value which provides n-dimensional access without allocating a buffer.
+/// The value is only materialized if the full data are read.
+#[derive(Copy, Clone)]
+pub struct AccessConst<T> {
+    value: T,
+    size: usize,
+}
+
+impl<T> AccessConst<T> {
+    /// Construct a new constant accessor of the given `size`.
+    pub fn new(value: T, size: usize) -> Self {
+        Self { value, size }
+    }
+
+    /// Borrow the constant value of this accessor.
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T: CType> Access<T> for AccessConst<T> {
+    fn read(&self) -> Result<BufferConverter<'static, T>, Error> {
+        Platform::select(self.size)
+            .constant(self.value, self.size)
+            .map(BufferConverter::from)
+    }
+
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        if offset < self.size {
+            Ok(self.value)
+        } else {
+            Err(Error::Bounds(format!(
+                "invalid offset {offset} for a constant of size {}",
+                self.size
+            )))
+        }
+    }
+
+    fn size(&self) -> usize {
+        self.size
+    }
+
+    fn plan(&self) -> OpPlan {
+        OpPlan::constant::<T>(self.size)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for AccessConst<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "access constant {:?} of size {}", self.value, self.size)
+    }
+}
+
 /// A struct which provides n-dimensional access to the result of an array operation.
+/// The operation is recomputed each time its data are read, and its result is never cached,
+/// so an op graph only holds memory for its inputs. Use [`crate::ArrayBuf::copy`] to materialize
+/// an intermediate result which is read more than once.
 pub struct AccessOp<O, P> {
     op: O,
     platform: PhantomData<P>,
@@ -163,6 +235,13 @@ impl<O, P> AccessOp<O, P> {
     }
 }
 
+impl<O: BroadcastDual, P> AccessOp<O, P> {
+    /// Read the inputs of this dual op through the given broadcast views.
+    pub fn broadcast_dual(self, broadcast: DualBroadcast) -> Result<Self, Error> {
+        self.op.broadcast(broadcast).map(Self::from)
+    }
+}
+
 impl<O, P> From<O> for AccessOp<O, P> {
     fn from(op: O) -> Self {
         Self {
@@ -179,16 +258,25 @@ where
     P: PlatformInstance,
 {
     fn read(&self) -> Result<BufferConverter<'static, T>, Error> {
-        self.op.enqueue().map(|buffer| buffer.into())
+        self.op
+            .enqueue()
+            .and_then(|buffer| check_output(&self.op, buffer.into()))
+            .map_err(|cause| trace(cause, &self.op))
     }
 
     fn read_value(&self, offset: usize) -> Result<T, Error> {
-        self.op.read_value(offset)
+        self.op
+            .read_value(offset)
+            .map_err(|cause| trace(cause, &self.op))
     }
 
     fn size(&self) -> usize {
         self.op.size()
     }
+
+    fn plan(&self) -> OpPlan {
+        OpPlan::op::<T>(self.op.name(), self.op.size(), self.op.inputs())
+    }
 }
 
 impl<'a, O, P, T> Access<T> for &'a AccessOp<O, P>
@@ -199,16 +287,25 @@ where
     BufferConverter<'static, T>: From<O::Buffer>,
 {
     fn read(&self) -> Result<BufferConverter<'static, T>, Error> {
-        self.op.enqueue().map(BufferConverter::from)
+        self.op
+            .enqueue()
+            .and_then(|buffer| check_output(&self.op, BufferConverter::from(buffer)))
+            .map_err(|cause| trace(cause, &self.op))
     }
 
     fn read_value(&self, offset: usize) -> Result<T, Error> {
-        self.op.read_value(offset)
+        self.op
+            .read_value(offset)
+            .map_err(|cause| trace(cause, &self.op))
     }
 
     fn size(&self) -> usize {
         self.op.size()
     }
+
+    fn plan(&self) -> OpPlan {
+        OpPlan::op::<T>(self.op.name(), self.op.size(), self.op.inputs())
+    }
 }
 
 impl<O, P, T> AccessMut<T> for AccessOp<O, P>
@@ -254,14 +351,17 @@ impl<T: CType> Access<T> for Accessor<T> {
     fn read(&self) -> Result<BufferConverter<T>, Error> {
         match self {
             Self::Buffer(buf) => Ok(buf.read()),
-            Self::Op(op) => op.enqueue().map(BufferConverter::from),
+            Self::Op(op) => op
+                .enqueue()
+                .and_then(|buffer| check_output(&**op, BufferConverter::from(buffer)))
+                .map_err(|cause| trace(cause, &**op)),
         }
     }
 
     fn read_value(&self, offset: usize) -> Result<T, Error> {
         match self {
             Self::Buffer(buf) => buf.read_value(offset),
-            Self::Op(op) => op.read_value(offset),
+            Self::Op(op) => op.read_value(offset).map_err(|cause| trace(cause, &**op)),
         }
     }
 
@@ -271,6 +371,13 @@ impl<T: CType> Access<T> for Accessor<T> {
             Self::Op(op) => op.size(),
         }
     }
+
+    fn plan(&self) -> OpPlan {
+        match self {
+            Self::Buffer(buf) => OpPlan::buffer::<T>(buf.len()),
+            Self::Op(op) => OpPlan::op::<T>(op.name(), op.size(), op.inputs()),
+        }
+    }
 }
 
 impl<T, B> From<AccessBuf<B>> for Accessor<T>
@@ -295,3 +402,42 @@ where
         Self::Op(op)
     }
 }
+
+/// Check that an op enqueued a buffer of its declared size, in a debug build
+/// or if enabled by [`config::debug_checks`], and optionally that its output is finite.
+#[inline]
+fn check_output<O, T>(
+    op: &O,
+    buffer: BufferConverter<'static, T>,
+) -> Result<BufferConverter<'static, T>, Error>
+where
+    O: Op + ?Sized,
+    T: CType,
+{
+    let checks = config::debug_checks();
+
+    if cfg!(debug_assertions) || checks >= DebugChecks::Size {
+        assert_eq!(
+            buffer.len(),
+            op.size(),
+            "{} enqueued a buffer of the wrong size",
+            op_label(op.name(), op.size())
+        );
+    }
+
+    if checks >= DebugChecks::NonFinite && T::IS_FLOAT {
+        if let Some((offset, n)) = buffer.find_non_finite()? {
+            return Err(Error::Bounds(format!(
+                "debug checks found the non-finite value {n} at offset {offset} of the output of {}",
+                op.name()
+            )));
+        }
+    }
+
+    Ok(buffer)
+}
+
+#[inline]
+fn trace<O: Op + ?Sized>(cause: Error, op: &O) -> Error {
+    cause.with_context(format!("in {}", op_label(op.name(), op.size())))
+}
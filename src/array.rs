@@ -1,21 +1,32 @@
 use std::borrow::{Borrow, BorrowMut};
 use std::fmt;
 use std::marker::PhantomData;
+use std::mem;
+use std::ops::{Index, IndexMut, RangeBounds};
+
+use xxhash_rust::xxh3::Xxh3;
 
 use crate::access::*;
 use crate::buffer::BufferInstance;
+use crate::conv::volumetric_shape;
 use crate::ops::*;
 use crate::platform::PlatformInstance;
 use crate::{
-    range_shape, shape, strides_for, Axes, AxisRange, BufferConverter, CType, Constant, Convert,
-    Error, Float, Platform, Range, Shape,
+    broadcast_shape, range_shape, resolve_range, shape, strides_for, ArrayAccess, ArrayBuf,
+    ArrayMeta, ArrayOp, Axes, AxisRange, BufferConverter, CType, Constant, ConvSpec, Convert,
+    Error, Float, Interpolation, IntoAxes, IntoRange, IntoShape, Platform, Range, Shape, Strides,
+    UlpDiff,
 };
 
+/// An n-dimensional array of elements of type `T`, accessed via `A`, on the platform `P`.
+/// An array is `'static` only if its accessor does not borrow its data;
+/// use [`crate::ArrayHandle`] to send any array across threads.
 pub struct Array<T, A, P> {
     shape: Shape,
     access: A,
     platform: P,
     dtype: PhantomData<T>,
+    meta: ArrayMeta,
 }
 
 impl<T, A: Clone, P: Clone> Clone for Array<T, A, P> {
@@ -25,6 +36,7 @@ impl<T, A: Clone, P: Clone> Clone for Array<T, A, P> {
             access: self.access.clone(),
             platform: self.platform.clone(),
             dtype: self.dtype,
+            meta: self.meta.clone(),
         }
     }
 }
@@ -42,29 +54,33 @@ impl<T, A, P> Array<T, A, P> {
             access,
             platform: self.platform,
             dtype: PhantomData,
+            meta: self.meta,
         })
     }
 
-    fn reduce_axes<Op>(
+    fn reduce_axes<U, O, Op>(
         self,
-        mut axes: Axes,
+        axes: impl IntoAxes,
         keepdims: bool,
         op: Op,
-    ) -> Result<Array<T, AccessOp<P::Op, P>, P>, Error>
+    ) -> Result<Array<U, AccessOp<O, P>, P>, Error>
     where
         T: CType,
         A: Access<T>,
         P: Transform<A, T> + ReduceAxes<Accessor<T>, T>,
-        Op: Fn(P, Accessor<T>, usize) -> Result<AccessOp<P::Op, P>, Error>,
+        Op: Fn(P, Accessor<T>, usize) -> Result<AccessOp<O, P>, Error>,
         Accessor<T>: From<A> + From<AccessOp<P::Transpose, P>>,
     {
+        let mut axes = axes.into_axes();
         axes.sort();
         axes.dedup();
 
-        let shape = reduce_axes(&self.shape, &axes, keepdims)?;
+        let shape = reduce_axes(&self.shape, &axes, keepdims).map_err(|cause| self.trace(cause))?;
         let size = shape.iter().product::<usize>();
         let stride = axes.iter().copied().map(|x| self.shape[x]).product();
+        check_lanes(&self.shape, &axes, stride).map_err(|cause| self.trace(cause))?;
         let platform = P::select(size);
+        let meta = reduce_meta(&self.meta, &axes, self.ndim(), keepdims);
 
         let access = permute_for_reduce(self.platform, self.access, self.shape, axes)?;
         let access = (op)(self.platform, access, stride)?;
@@ -74,22 +90,249 @@ impl<T, A, P> Array<T, A, P> {
             shape,
             platform,
             dtype: PhantomData,
+            meta,
+        })
+    }
+
+    /// Like `reduce_axes`, but reduce each empty lane to the given identity `id`
+    /// rather than returning an error.
+    #[allow(clippy::type_complexity)]
+    fn reduce_axes_or_identity<Op>(
+        self,
+        axes: impl IntoAxes,
+        keepdims: bool,
+        id: T,
+        op: Op,
+    ) -> Result<Array<T, AccessOp<P::Op, P>, P>, Error>
+    where
+        T: CType,
+        A: Access<T>,
+        P: Transform<A, T> + ReduceAxes<Accessor<T>, T>,
+        Op: Fn(P, Accessor<T>, usize) -> Result<AccessOp<P::Op, P>, Error>,
+        Accessor<T>: From<A> + From<AccessOp<P::Transpose, P>>,
+    {
+        let mut axes = axes.into_axes();
+        axes.sort();
+        axes.dedup();
+
+        if axes.iter().all(|x| self.shape.get(*x) != Some(&0)) {
+            return self.reduce_axes(axes, keepdims, op);
+        }
+
+        let shape = reduce_axes(&self.shape, &axes, keepdims).map_err(|cause| self.trace(cause))?;
+        let size = shape.iter().product::<usize>();
+        let platform = P::select(size);
+        let meta = reduce_meta(&self.meta, &axes, self.ndim(), keepdims);
+        let access = platform.identity(Accessor::from(self.access), size, id)?;
+
+        Ok(Array {
+            access,
+            shape,
+            platform,
+            dtype: PhantomData,
+            meta,
         })
     }
 
+    fn reduce_axes_with_index<Op>(
+        self,
+        axes: impl IntoAxes,
+        keepdims: bool,
+        op: Op,
+    ) -> Result<IndexedReduce<T, P>, Error>
+    where
+        T: CType,
+        A: Access<T>,
+        P: Transform<A, T> + ReduceAxesWithIndex<Accessor<T>, T>,
+        Op: Fn(
+            P,
+            Accessor<T>,
+            usize,
+        ) -> Result<(<P as Convert<T>>::Buffer, <P as Convert<u64>>::Buffer), Error>,
+        Accessor<T>: From<A> + From<AccessOp<P::Transpose, P>>,
+    {
+        let mut axes = axes.into_axes();
+        axes.sort();
+        axes.dedup();
+
+        let shape = reduce_axes(&self.shape, &axes, keepdims).map_err(|cause| self.trace(cause))?;
+        let stride = axes.iter().copied().map(|x| self.shape[x]).product();
+        check_lanes(&self.shape, &axes, stride).map_err(|cause| self.trace(cause))?;
+        let platform = self.platform;
+        let meta = reduce_meta(&self.meta, &axes, self.ndim(), keepdims);
+
+        let access = permute_for_reduce(self.platform, self.access, self.shape, axes)?;
+        let (values, indices) = (op)(platform, access, stride)?;
+
+        let values = Array {
+            shape: shape.clone(),
+            access: values.into(),
+            platform,
+            dtype: PhantomData,
+            meta: meta.clone(),
+        };
+
+        let indices = Array {
+            shape,
+            access: indices.into(),
+            platform,
+            dtype: PhantomData,
+            meta,
+        };
+
+        Ok((values, indices))
+    }
+
     pub fn access(&self) -> &A {
         &self.access
     }
 
+    /// Borrow the descriptive metadata of this array.
+    pub fn meta(&self) -> &ArrayMeta {
+        &self.meta
+    }
+
+    /// Return the name of this array, if any.
+    pub fn name(&self) -> Option<&str> {
+        self.meta.name()
+    }
+
+    /// Attach a descriptive `name` to this array, to identify it in debug and error output.
+    pub fn with_name<N: Into<String>>(mut self, name: N) -> Self {
+        self.meta = self.meta.with_name(name.into());
+        self
+    }
+
+    /// Attach a descriptive label to each axis of this array,
+    /// to identify them in debug and error output.
+    /// This will return an error if the number of `labels` does not match the number of axes.
+    pub fn with_axis_labels<L, I>(mut self, labels: I) -> Result<Self, Error>
+    where
+        L: Into<String>,
+        I: IntoIterator<Item = L>,
+    {
+        let labels = labels.into_iter().map(Into::into).collect();
+        self.meta = self.meta.with_axis_labels(labels, self.shape.len())?;
+        Ok(self)
+    }
+
+    /// Attach a coordinate to each element of the given `axis` of this array, for use with
+    /// [`Array::sel`]. The coordinates must be strictly increasing or decreasing.
+    pub fn with_coords(mut self, axis: usize, coords: Vec<f64>) -> Result<Self, Error> {
+        self.meta = self.meta.with_coords(axis, coords, &self.shape)?;
+        Ok(self)
+    }
+
     pub fn into_access(self) -> A {
         self.access
     }
+
+    /// Construct a proxy to read the element at the given `coord` of this array.
+    pub fn at<'a>(&'a self, coord: &'a [usize]) -> Element<'a, &'a Self> {
+        Element { array: self, coord }
+    }
+
+    /// Construct a proxy to read or write the element at the given `coord` of this array.
+    pub fn at_mut<'a>(&'a mut self, coord: &'a [usize]) -> Element<'a, &'a mut Self> {
+        Element { array: self, coord }
+    }
+}
+
+impl<T: CType, A: Access<T>, P> Array<T, A, P> {
+    /// Summarize the shape of this array and the chain of ops which produces it.
+    fn breadcrumb(&self) -> String {
+        format!(
+            "{} produced by {}",
+            self.meta.describe(&self.shape),
+            self.access.plan().breadcrumb()
+        )
+    }
+
+    /// Attach the op chain which produces this array to the given error.
+    fn trace(&self, cause: Error) -> Error {
+        cause.with_context(format!("array: {}", self.breadcrumb()))
+    }
+
+    /// Resolve the given axis `labels` to the indices of the labeled axes of this array,
+    /// in the order given.
+    /// This will return an error if a label does not match exactly one axis or is repeated.
+    pub fn axes_by(&self, labels: &[&str]) -> Result<Axes, Error> {
+        self.meta.axes_of(labels).map_err(|cause| self.trace(cause))
+    }
+
+    /// Check that this array has the given `shape`, where `None` matches any dimension.
+    /// This will return an error describing this array if its shape does not match.
+    pub fn expect_shape(self, shape: &[Option<usize>]) -> Result<Self, Error> {
+        let matches = self.shape.len() == shape.len()
+            && self
+                .shape
+                .iter()
+                .zip(shape)
+                .all(|(dim, expected)| expected.is_none_or(|expected| *dim == expected));
+
+        if matches {
+            Ok(self)
+        } else {
+            let expected = shape
+                .iter()
+                .map(|dim| dim.map_or_else(|| "_".to_string(), |dim| dim.to_string()))
+                .collect::<Vec<_>>();
+
+            Err(self.trace(Error::Bounds(format!(
+                "expected an array with shape [{}], not {:?}",
+                expected.join(", "),
+                self.shape
+            ))))
+        }
+    }
+
+    /// Check that this array is a square matrix, or a batch of square matrices.
+    pub fn expect_square(self) -> Result<Self, Error> {
+        let ndim = self.shape.len();
+
+        if ndim >= 2 && self.shape[ndim - 1] == self.shape[ndim - 2] {
+            Ok(self)
+        } else {
+            Err(self.trace(Error::Bounds(format!(
+                "expected a square matrix, not an array with shape {:?}",
+                self.shape
+            ))))
+        }
+    }
+
+    /// Check that this array has the same shape as `other`.
+    pub fn expect_same_shape<O: NDArray>(self, other: &O) -> Result<Self, Error> {
+        if self.shape.as_slice() == other.shape() {
+            Ok(self)
+        } else {
+            Err(self.trace(Error::Bounds(format!(
+                "expected an array with shape {:?}, not {:?}",
+                other.shape(),
+                self.shape
+            ))))
+        }
+    }
+
+    /// Check that the data type of this array is `dtype`, e.g. "float" or "ulong".
+    /// This is useful to verify an array whose type is only known via a generic parameter.
+    pub fn expect_dtype(self, dtype: &str) -> Result<Self, Error> {
+        if T::TYPE == dtype {
+            Ok(self)
+        } else {
+            Err(self.trace(Error::Bounds(format!(
+                "expected an array of {dtype}, not {}",
+                T::TYPE
+            ))))
+        }
+    }
 }
 
 impl<T, L, P> Array<T, L, P> {
-    fn apply_dual<O, OT, R, Op>(
+    /// Apply a dual op to this array and an `other` array, which may reside on another platform.
+    /// Data are transferred to this array's platform when the op is enqueued.
+    fn apply_dual<O, OT, R, RP, Op>(
         self,
-        other: Array<T, R, P>,
+        other: Array<T, R, RP>,
         op: Op,
     ) -> Result<Array<OT, AccessOp<O, P>, P>, Error>
     where
@@ -103,10 +346,80 @@ impl<T, L, P> Array<T, L, P> {
             access,
             platform: self.platform,
             dtype: PhantomData,
+            meta: self.meta.unnamed(),
+        })
+    }
+
+    /// Apply a dual op to this array and an `other` array, first broadcasting either or both
+    /// into their common shape if their shapes differ.
+    fn apply_dual_broadcast<O, R, RP, Op>(
+        self,
+        op_name: &'static str,
+        other: Array<T, R, RP>,
+        op: Op,
+    ) -> Result<Array<T, AccessOp<O, P>, P>, Error>
+    where
+        T: CType,
+        L: Access<T>,
+        R: Access<T>,
+        P: PlatformInstance,
+        O: BroadcastDual,
+        Op: Fn(P, L, R) -> Result<AccessOp<O, P>, Error>,
+    {
+        if self.shape == other.shape {
+            return self.apply_dual(other, op);
+        }
+
+        let shape = broadcast_shape(&self.shape, &other.shape).map_err(|cause| {
+            cause
+                .with_context(format!(
+                    "cannot {op_name} arrays with shapes {:?} and {:?}",
+                    self.shape, other.shape
+                ))
+                .with_context(format!("left: {}", self.breadcrumb()))
+                .with_context(format!("right: {}", other.breadcrumb()))
+        })?;
+
+        let view = |source: &[usize]| {
+            if source == shape.as_slice() {
+                None
+            } else {
+                Some(ViewSpec::broadcast(source, shape.clone()))
+            }
+        };
+
+        let broadcast = DualBroadcast {
+            left: view(&self.shape),
+            right: view(&other.shape),
+        };
+
+        let platform = P::select(shape.iter().product());
+        let meta = self.meta.broadcast(&self.shape, &shape).unnamed();
+        let access = (op)(platform, self.access, other.access)?.broadcast_dual(broadcast)?;
+
+        Ok(Array {
+            shape,
+            access,
+            platform,
+            dtype: PhantomData,
+            meta,
         })
     }
 }
 
+impl<T, O, P> Array<T, AccessOp<O, P>, P> {
+    /// Construct an array with the given `shape` from the output of an op on the `platform`.
+    pub(crate) fn from_op(shape: Shape, access: AccessOp<O, P>, platform: P) -> Self {
+        Self {
+            shape,
+            access,
+            platform,
+            dtype: PhantomData,
+            meta: ArrayMeta::default(),
+        }
+    }
+}
+
 // constructors
 impl<T: CType> Array<T, Accessor<T>, Platform> {
     pub fn from<A, P>(array: Array<T, A, P>) -> Self
@@ -119,6 +432,7 @@ impl<T: CType> Array<T, Accessor<T>, Platform> {
             access: array.access.into(),
             platform: array.platform.into(),
             dtype: array.dtype,
+            meta: array.meta,
         }
     }
 }
@@ -129,38 +443,39 @@ where
     B: BufferInstance<T>,
     P: PlatformInstance,
 {
-    fn new_inner(platform: P, buffer: B, shape: Shape) -> Result<Self, Error> {
-        if !shape.is_empty() && shape.iter().product::<usize>() == buffer.len() {
-            let access = buffer.into();
+    fn new_inner(constructor: &str, platform: P, buffer: B, shape: Shape) -> Result<Self, Error> {
+        check_shape(constructor, &shape, buffer.len())?;
 
-            Ok(Self {
-                shape,
-                access,
-                platform,
-                dtype: PhantomData,
-            })
-        } else {
-            Err(Error::Bounds(format!(
-                "cannot construct an array with shape {shape:?} from a buffer of size {}",
-                buffer.len(),
-            )))
-        }
+        Ok(Self {
+            shape,
+            access: buffer.into(),
+            platform,
+            dtype: PhantomData,
+            meta: ArrayMeta::default(),
+        })
     }
 
-    pub fn convert<'a, FB>(buffer: FB, shape: Shape) -> Result<Self, Error>
+    /// Construct a new array with the given `shape` from a `buffer` on any platform,
+    /// converting it to this array's platform if needed.
+    /// The length of the `buffer` is checked before it is converted.
+    pub fn convert<'a, FB>(buffer: FB, shape: impl IntoShape) -> Result<Self, Error>
     where
         FB: Into<BufferConverter<'a, T>>,
         P: Convert<T, Buffer = B>,
     {
+        let shape = shape.into_shape();
         let buffer = buffer.into();
+        check_shape("Array::convert", &shape, buffer.len())?;
+
         let platform = P::select(buffer.len());
         let buffer = platform.convert(buffer)?;
-        Self::new_inner(platform, buffer, shape)
+        Self::new_inner("Array::convert", platform, buffer, shape)
     }
 
-    pub fn new(buffer: B, shape: Shape) -> Result<Self, Error> {
+    pub fn new(buffer: B, shape: impl IntoShape) -> Result<Self, Error> {
+        let shape = shape.into_shape();
         let platform = P::select(buffer.len());
-        Self::new_inner(platform, buffer, shape)
+        Self::new_inner("Array::new", platform, buffer, shape)
     }
 }
 
@@ -169,7 +484,8 @@ where
     T: CType,
     P: Constant<T>,
 {
-    pub fn constant(value: T, shape: Shape) -> Result<Self, Error> {
+    pub fn constant(value: T, shape: impl IntoShape) -> Result<Self, Error> {
+        let shape = shape.into_shape();
         if !shape.is_empty() {
             let size = shape.iter().product();
             let platform = P::select(size);
@@ -181,6 +497,55 @@ where
                 access,
                 platform,
                 dtype: PhantomData,
+                meta: ArrayMeta::default(),
+            })
+        } else {
+            Err(Error::Bounds(
+                "cannot construct an array with an empty shape".to_string(),
+            ))
+        }
+    }
+
+    /// Construct a new array of zeros with the given `shape`.
+    pub fn zeros(shape: impl IntoShape) -> Result<Self, Error> {
+        Self::constant(T::ZERO, shape)
+    }
+
+    /// Construct a new array of ones with the given `shape`.
+    pub fn ones(shape: impl IntoShape) -> Result<Self, Error> {
+        Self::constant(T::ONE, shape)
+    }
+
+    /// Construct a new array with the same shape as the `other` array, filled with `value`.
+    pub fn full_like<A>(other: &Array<T, A, P>, value: T) -> Result<Self, Error> {
+        Self::constant(value, other.shape.clone())
+    }
+
+    /// Construct a new array of zeros with the same shape as the `other` array.
+    pub fn zeros_like<A>(other: &Array<T, A, P>) -> Result<Self, Error> {
+        Self::full_like(other, T::ZERO)
+    }
+
+    /// Construct a new array of ones with the same shape as the `other` array.
+    pub fn ones_like<A>(other: &Array<T, A, P>) -> Result<Self, Error> {
+        Self::full_like(other, T::ONE)
+    }
+}
+
+impl<T: CType, P: PlatformInstance> Array<T, AccessConst<T>, P> {
+    /// Construct an array filled with a single `value` without allocating a buffer.
+    /// Use this instead of [`Array::constant`] for a constant operand of another array op.
+    pub fn scalar(value: T, shape: impl IntoShape) -> Result<Self, Error> {
+        let shape = shape.into_shape();
+        if !shape.is_empty() {
+            let size = shape.iter().product();
+
+            Ok(Self {
+                shape,
+                access: AccessConst::new(value, size),
+                platform: P::select(size),
+                dtype: PhantomData,
+                meta: ArrayMeta::default(),
             })
         } else {
             Err(Error::Bounds(
@@ -205,8 +570,98 @@ where
             access: buffer.into(),
             platform: source.platform,
             dtype: source.dtype,
+            meta: source.meta.clone(),
         })
     }
+
+    /// Construct a new array with the given `shape` from raw little-endian `bytes`,
+    /// which need not be aligned.
+    pub fn from_le_bytes(bytes: &[u8], shape: impl IntoShape) -> Result<Self, Error> {
+        let shape = shape.into_shape();
+        let data = decode_bytes(bytes, &shape, true)?;
+        Self::convert(data, shape)
+    }
+
+    /// Construct a new array with the given `shape` from raw big-endian `bytes`,
+    /// which need not be aligned.
+    pub fn from_be_bytes(bytes: &[u8], shape: impl IntoShape) -> Result<Self, Error> {
+        let shape = shape.into_shape();
+        let data = decode_bytes(bytes, &shape, false)?;
+        Self::convert(data, shape)
+    }
+
+    /// Construct a new array from a message encoded by [`NDArrayRead::to_ipc_message`].
+    /// Use [`crate::io::ipc::read`] to read a message directly from a socket or other stream.
+    pub fn from_ipc_message(message: &[u8]) -> Result<Self, Error> {
+        let (header, data) = crate::io::ipc::Header::decode(message)?;
+        header.expect::<T>()?;
+        Self::from_le_bytes(data, header.into_shape())
+    }
+}
+
+/// Check that a buffer of `len` elements has the size of the given `shape`,
+/// naming the `constructor` which received it in the error if not.
+fn check_shape(constructor: &str, shape: &[usize], len: usize) -> Result<(), Error> {
+    let size = shape.iter().product::<usize>();
+
+    if shape.is_empty() {
+        Err(Error::Bounds(format!(
+            "{constructor} cannot construct an array with an empty shape from a buffer of size {len}"
+        )))
+    } else if size != len {
+        Err(Error::Bounds(format!(
+            "{constructor} cannot construct an array with shape {shape:?} (size {size}) \
+            from a buffer of size {len}"
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+pub(crate) fn decode_bytes<T: CType>(
+    bytes: &[u8],
+    shape: &[usize],
+    little_endian: bool,
+) -> Result<Vec<T>, Error> {
+    let size = crate::io::checked_size::<T>(shape)?;
+    let width = mem::size_of::<T>();
+
+    if bytes.len() != size * width {
+        return Err(Error::Bounds(format!(
+            "cannot construct an array of {size} elements of type {} from {} bytes",
+            T::TYPE,
+            bytes.len()
+        )));
+    }
+
+    let mut data = vec![T::ZERO; size];
+
+    // SAFETY: every CType is a primitive number, for which every bit pattern is valid
+    let dest = unsafe { std::slice::from_raw_parts_mut(data.as_mut_ptr() as *mut u8, bytes.len()) };
+
+    dest.copy_from_slice(bytes);
+
+    if little_endian != cfg!(target_endian = "little") {
+        dest.chunks_exact_mut(width).for_each(<[u8]>::reverse);
+    }
+
+    Ok(data)
+}
+
+pub(crate) fn encode_bytes<T: CType>(data: &[T], little_endian: bool) -> Vec<u8> {
+    // SAFETY: every CType is a primitive number, which has no padding bytes
+    let native =
+        unsafe { std::slice::from_raw_parts(data.as_ptr() as *const u8, mem::size_of_val(data)) };
+
+    let mut bytes = native.to_vec();
+
+    if little_endian != cfg!(target_endian = "little") {
+        bytes
+            .chunks_exact_mut(mem::size_of::<T>())
+            .for_each(<[u8]>::reverse);
+    }
+
+    bytes
 }
 
 // op constructors
@@ -214,7 +669,8 @@ impl<T: CType, P: PlatformInstance> Array<T, AccessOp<P::Range, P>, P>
 where
     P: Construct<T>,
 {
-    pub fn range(start: T, stop: T, shape: Shape) -> Result<Self, Error> {
+    pub fn range(start: T, stop: T, shape: impl IntoShape) -> Result<Self, Error> {
+        let shape = shape.into_shape();
         let size = shape.iter().product();
         let platform = P::select(size);
 
@@ -223,6 +679,41 @@ where
             access,
             platform,
             dtype: PhantomData,
+            meta: ArrayMeta::default(),
+        })
+    }
+}
+
+impl<T: CType, P: PlatformInstance> Array<T, AccessOp<P::Eye, P>, P>
+where
+    P: Construct<T>,
+{
+    /// Construct an identity matrix with shape `[dim, dim]`.
+    pub fn eye(dim: usize) -> Result<Self, Error> {
+        Self::eye_batch(Shape::new(), dim)
+    }
+
+    /// Construct a batch of identity matrices with shape `[..batch_shape, dim, dim]`.
+    pub fn eye_batch(batch_shape: impl IntoShape, dim: usize) -> Result<Self, Error> {
+        let batch_shape = batch_shape.into_shape();
+        if dim == 0 {
+            return Err(Error::Bounds(
+                "an identity matrix must have a positive dimension".to_string(),
+            ));
+        }
+
+        let batch_size = batch_shape.iter().product();
+        let platform = P::select(batch_size * dim * dim);
+
+        let mut shape = batch_shape;
+        shape.extend([dim, dim]);
+
+        platform.eye(batch_size, dim).map(|access| Self {
+            shape,
+            access,
+            platform,
+            dtype: PhantomData,
+            meta: ArrayMeta::default(),
         })
     }
 }
@@ -240,6 +731,7 @@ where
             access,
             platform,
             dtype: PhantomData,
+            meta: ArrayMeta::default(),
         })
     }
 }
@@ -257,6 +749,30 @@ where
             access,
             platform,
             dtype: PhantomData,
+            meta: ArrayMeta::default(),
+        })
+    }
+}
+
+#[cfg(feature = "opencl")]
+impl<T: CType, A: Access<T>, P> Array<T, A, P> {
+    /// Copy the data of this array into a new array on the OpenCL device at `device_id`,
+    /// an index into the devices of [`crate::opencl::OpenCL::context`],
+    /// so that a multi-device pipeline can control where its data reside.
+    pub fn copy_to_device(&self, device_id: usize) -> Result<crate::opencl::ArrayBuf<T>, Error> {
+        let copy = self
+            .access
+            .read()
+            .and_then(|buffer| buffer.to_cl().map_err(Error::from))
+            .and_then(|buffer| crate::opencl::OpenCL::copy_to_device(&buffer, device_id))
+            .map_err(|cause| self.trace(cause))?;
+
+        Ok(Array {
+            shape: self.shape.clone(),
+            access: copy.into(),
+            platform: crate::opencl::OpenCL,
+            dtype: PhantomData,
+            meta: self.meta.clone(),
         })
     }
 }
@@ -277,6 +793,7 @@ where
             access: self.access.as_mut(),
             platform: self.platform,
             dtype: PhantomData,
+            meta: self.meta.clone(),
         }
     }
 
@@ -289,6 +806,7 @@ where
             access: self.access.as_ref(),
             platform: self.platform,
             dtype: PhantomData,
+            meta: self.meta.clone(),
         }
     }
 }
@@ -308,6 +826,7 @@ where
             access: &mut self.access,
             platform: self.platform,
             dtype: PhantomData,
+            meta: self.meta.clone(),
         }
     }
 
@@ -317,6 +836,7 @@ where
             access: &self.access,
             platform: self.platform,
             dtype: PhantomData,
+            meta: self.meta.clone(),
         }
     }
 }
@@ -380,44 +900,109 @@ pub trait NDArrayRead: NDArray + fmt::Debug + Sized {
 
     /// Read the value at a specific `coord` in this [`NDArray`].
     fn read_value(&self, coord: &[usize]) -> Result<Self::DType, Error>;
-}
 
-impl<T, A, P> NDArrayRead for Array<T, A, P>
-where
-    T: CType,
-    A: Access<T>,
-    P: PlatformInstance,
-{
-    fn buffer(&self) -> Result<BufferConverter<T>, Error> {
-        self.access.read()
+    /// Copy the contents of this [`NDArray`] into the given `output` slice, which must have
+    /// the same size, without allocating an intermediate [`Vec`] for the result.
+    fn read_into(&self, output: &mut [Self::DType]) -> Result<(), Error> {
+        self.buffer()?.read_into(output)
     }
 
-    fn into_read(self) -> Result<Array<Self::DType, AccessBuf<P::Buffer>, Self::Platform>, Error>
+    /// Compute the distance in units in the last place between each element of this [`NDArray`]
+    /// and the corresponding element of `other`, e.g. to measure numeric drift between platforms.
+    fn ulp_diff<O>(&self, other: &O) -> Result<UlpDiff, Error>
     where
-        P: Convert<T>,
+        Self::DType: Float,
+        O: NDArrayRead<DType = Self::DType>,
     {
-        let buffer = self.buffer().and_then(|buf| self.platform.convert(buf))?;
-        debug_assert_eq!(buffer.len(), self.size());
+        UlpDiff::new(self, other)
+    }
 
-        Ok(Array {
-            shape: self.shape,
-            access: buffer.into(),
+    /// Copy the contents of this [`NDArray`] into a new [`Vec`] of little-endian bytes.
+    fn to_le_bytes(&self) -> Result<Vec<u8>, Error> {
+        let data = self.buffer()?.to_slice()?;
+        Ok(encode_bytes(&data, true))
+    }
+
+    /// Copy the contents of this [`NDArray`] into a new [`Vec`] of big-endian bytes.
+    fn to_be_bytes(&self) -> Result<Vec<u8>, Error> {
+        let data = self.buffer()?.to_slice()?;
+        Ok(encode_bytes(&data, false))
+    }
+
+    /// Encode the data type, shape, and contents of this [`NDArray`] as an IPC message.
+    /// Use [`crate::io::ipc::write`] to write a message directly to a socket or other stream.
+    fn to_ipc_message(&self) -> Result<Vec<u8>, Error> {
+        let mut message = Vec::new();
+        crate::io::ipc::write(self, &mut message)?;
+        Ok(message)
+    }
+
+    /// Compute a hash of the data type, shape, and contents of this [`NDArray`],
+    /// which is the same on every platform and in every process.
+    fn content_hash(&self) -> Result<u64, Error> {
+        let mut hasher = Xxh3::new();
+        hasher.update(Self::DType::TYPE.as_bytes());
+        hasher.update(&(self.ndim() as u64).to_le_bytes());
+
+        for dim in self.shape() {
+            hasher.update(&(*dim as u64).to_le_bytes());
+        }
+
+        let data = self.buffer()?.to_slice()?;
+
+        if cfg!(target_endian = "little") {
+            // SAFETY: every CType is a primitive number, which has no padding bytes
+            let bytes = unsafe {
+                std::slice::from_raw_parts(data.as_ptr() as *const u8, mem::size_of_val(&*data))
+            };
+
+            hasher.update(bytes);
+        } else {
+            let size = mem::size_of::<Self::DType>();
+            let mut bytes = vec![0u8; size];
+
+            for n in data.iter() {
+                // SAFETY: every CType is a primitive number, which has no padding bytes
+                let native =
+                    unsafe { std::slice::from_raw_parts(n as *const _ as *const u8, size) };
+                bytes.copy_from_slice(native);
+                bytes.reverse();
+                hasher.update(&bytes);
+            }
+        }
+
+        Ok(hasher.digest())
+    }
+}
+
+impl<T, A, P> NDArrayRead for Array<T, A, P>
+where
+    T: CType,
+    A: Access<T>,
+    P: PlatformInstance,
+{
+    fn buffer(&self) -> Result<BufferConverter<T>, Error> {
+        self.access.read()
+    }
+
+    fn into_read(self) -> Result<Array<Self::DType, AccessBuf<P::Buffer>, Self::Platform>, Error>
+    where
+        P: Convert<T>,
+    {
+        let buffer = self.buffer().and_then(|buf| self.platform.convert(buf))?;
+        debug_assert_eq!(buffer.len(), self.size());
+
+        Ok(Array {
+            shape: self.shape,
+            access: buffer.into(),
             platform: self.platform,
             dtype: self.dtype,
+            meta: self.meta,
         })
     }
 
     fn read_value(&self, coord: &[usize]) -> Result<T, Error> {
-        valid_coord(coord, self.shape())?;
-
-        let strides = strides_for(self.shape(), self.ndim());
-
-        let offset = coord
-            .iter()
-            .zip(strides)
-            .map(|(i, stride)| i * stride)
-            .sum();
-
+        let offset = offset_of(coord, self.shape())?;
         self.access.read_value(offset)
     }
 }
@@ -454,15 +1039,508 @@ where
     }
 
     fn write_value_at(&mut self, coord: &[usize], value: Self::DType) -> Result<(), Error> {
-        valid_coord(coord, self.shape())?;
+        let offset = offset_of(coord, self.shape())?;
+        self.access.write_value_at(offset, value)
+    }
+}
+
+impl<T: CType, A: AccessMut<T>, P: PlatformInstance> Array<T, A, P> {
+    /// Add `alpha * other` to this array in place (i.e. `self ← self + α·other`),
+    /// without allocating a temporary buffer for the product.
+    pub fn accumulate<O, OP>(&mut self, other: Array<T, O, OP>, alpha: T) -> Result<(), Error>
+    where
+        O: Access<T>,
+        P: Accumulate<O, T>,
+    {
+        same_shape_of("accumulate", self, &other)?;
+        self.platform.axpy(&mut self.access, alpha, other.access)
+    }
+
+    /// Overwrite the elements of this array at the given `indices` along `axis`
+    /// with the given `values`, the inverse of [`NDArrayGather::gather`].
+    /// The shape of `values` must be this array's shape with `axis` replaced by the shape
+    /// of `indices`. If an index is repeated, which of its values is written is unspecified.
+    /// An index out of bounds is an error, which leaves this array unchanged.
+    pub fn scatter<I, IP, V, VP>(
+        &mut self,
+        indices: Array<u64, I, IP>,
+        values: Array<T, V, VP>,
+        axis: usize,
+    ) -> Result<(), Error>
+    where
+        I: Access<u64>,
+        V: Access<T>,
+        P: ScatterAxis<I, V, T>,
+    {
+        let dims = self.scatter_dims("scatter", &indices, &values, axis)?;
+        (self.platform).scatter(&mut self.access, indices.access, values.access, dims)
+    }
+
+    /// Add the given `values` to the elements of this array at the given `indices` along `axis`,
+    /// like [`Self::scatter`] except that the values of a repeated index are summed.
+    pub fn scatter_add<I, IP, V, VP>(
+        &mut self,
+        indices: Array<u64, I, IP>,
+        values: Array<T, V, VP>,
+        axis: usize,
+    ) -> Result<(), Error>
+    where
+        I: Access<u64>,
+        V: Access<T>,
+        P: ScatterAxis<I, V, T>,
+    {
+        let dims = self.scatter_dims("scatter_add", &indices, &values, axis)?;
+        (self.platform).scatter_add(&mut self.access, indices.access, values.access, dims)
+    }
+
+    /// Overwrite the elements of this array where the given `mask` is nonzero with the 1-D
+    /// `values`, in row-major order, i.e. the inverse of [`Array::mask_select`].
+    /// The mask is evaluated immediately, to check the number of `values`.
+    pub fn mask_write<M, MP, V, VP>(
+        &mut self,
+        mask: Array<u8, M, MP>,
+        values: Array<T, V, VP>,
+    ) -> Result<(), Error>
+    where
+        Accessor<u8>: From<M>,
+        Platform: From<MP>,
+        V: Access<T>,
+        P: ScatterAxis<Accessor<u64>, V, T>,
+    {
+        let mask = ArrayAccess::from(mask);
+        same_shape_of("mask_write", self, &mask)?;
+
+        let (positions, count) = mask_positions(mask)?;
+
+        if values.shape.as_slice() != [count] {
+            return Err(Error::Bounds(format!(
+                "cannot write values with shape {:?} to the {count} elements of a mask",
+                values.shape
+            )));
+        } else if count == 0 {
+            return Ok(());
+        }
+
+        let size = self.size();
+        let indices = mask_indices(positions, size, count)?;
+
+        (self.platform).scatter(
+            &mut self.access,
+            indices.into_access(),
+            values.access,
+            [1, size, 1],
+        )
+    }
 
-        let offset = coord
+    fn scatter_dims<I, IP, V, VP>(
+        &self,
+        op_name: &'static str,
+        indices: &Array<u64, I, IP>,
+        values: &Array<T, V, VP>,
+        axis: usize,
+    ) -> Result<[usize; 3], Error> {
+        let (dim, inner) = scan_dims(&self.shape, axis).map_err(|cause| self.trace(cause))?;
+        let outer = self.shape[..axis].iter().product();
+
+        let expected = self.shape[..axis]
             .iter()
-            .zip(strides_for(self.shape(), self.ndim()))
-            .map(|(i, stride)| i * stride)
-            .sum();
+            .chain(&indices.shape)
+            .chain(&self.shape[(axis + 1)..])
+            .copied()
+            .collect::<Shape>();
 
-        self.access.write_value_at(offset, value)
+        if values.shape == expected {
+            Ok([outer, dim, inner])
+        } else {
+            Err(Error::Bounds(format!(
+                "cannot {op_name} values with shape {:?} along axis {axis} of an array with shape {:?} (expected {expected:?})",
+                values.shape, self.shape
+            )))
+        }
+    }
+}
+
+impl<T: CType, A: Access<T>, P: PlatformInstance> Array<T, A, P> {
+    /// Reinterpret the bits of each element of this array as a value of type `U`,
+    /// which must have the same size as `T`.
+    /// On the host, an owned buffer is reinterpreted in place without copying.
+    #[allow(clippy::type_complexity)]
+    pub fn bitcast<U: CType>(self) -> Result<Array<U, AccessOp<P::Op, P>, P>, Error>
+    where
+        P: ElementwiseBitcast<A, T, U>,
+    {
+        Ok(Array {
+            shape: self.shape,
+            access: self.platform.bitcast(self.access)?,
+            platform: self.platform,
+            dtype: PhantomData,
+            meta: self.meta,
+        })
+    }
+
+    /// Construct an operation to select the elements of this array where the given `mask`
+    /// is nonzero, in row-major order, as a 1-D array.
+    /// The mask is evaluated immediately, to determine the size of the output.
+    #[allow(clippy::type_complexity)]
+    pub fn mask_select<M, MP>(
+        self,
+        mask: Array<u8, M, MP>,
+    ) -> Result<Array<T, AccessOp<P::Op, P>, P>, Error>
+    where
+        Accessor<u8>: From<M>,
+        Platform: From<MP>,
+        P: GatherMask<A, Accessor<u64>, T>,
+    {
+        let mask = ArrayAccess::from(mask);
+        same_shape_of("mask_select", &self, &mask)?;
+
+        let (positions, count) = mask_positions(mask)?;
+        self.compact(positions, count)
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn compact(
+        self,
+        positions: ArrayAccess<u64>,
+        count: usize,
+    ) -> Result<Array<T, AccessOp<P::Op, P>, P>, Error>
+    where
+        P: GatherMask<A, Accessor<u64>, T>,
+    {
+        let access = (self.platform).compact(self.access, positions.into_access(), count)?;
+        Ok(Array::from_op(shape![count], access, self.platform))
+    }
+
+    /// Compress the contents of this array and write them to a new file at `path`.
+    /// Use [`crate::io::compress::load`] to read the file back.
+    #[cfg(feature = "zstd")]
+    pub fn save_compressed<PA: AsRef<std::path::Path>>(&self, path: PA) -> Result<(), Error> {
+        crate::io::compress::save(self, path, crate::io::compress::DEFAULT_LEVEL)
+    }
+}
+
+/// Array scan operations
+pub trait NDArrayScan: NDArray + fmt::Debug {
+    type Output: Access<Self::DType>;
+    type Cdf: Access<<Self::DType as CType>::Float>;
+
+    /// Construct an operation to replace each element with its rank-based empirical cumulative
+    /// distribution along the given `axis`, i.e. the fraction of the elements of its lane which
+    /// are less than or equal to it, in the range `(0, 1]`.
+    /// This equalizes the histogram of each lane, as for per-sample quantile normalization.
+    #[allow(clippy::type_complexity)]
+    fn cdf_normalize(
+        self,
+        axis: usize,
+    ) -> Result<Array<<Self::DType as CType>::Float, Self::Cdf, Self::Platform>, Error>;
+
+    /// Construct an operation to compute the cumulative product along the given `axis`.
+    /// The product of an integer type wraps on overflow.
+    #[allow(clippy::type_complexity)]
+    fn cumprod(
+        self,
+        axis: usize,
+    ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error>;
+
+    /// Construct an operation to compute the cumulative sum along the given `axis`.
+    /// The sum of an integer type wraps on overflow.
+    #[allow(clippy::type_complexity)]
+    fn cumsum(self, axis: usize)
+        -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error>;
+
+    /// Construct an operation to compute the logarithm of the cumulative sum of the exponentials
+    /// of the elements along the given `axis`, without overflowing for large inputs.
+    #[allow(clippy::type_complexity)]
+    fn logcumsumexp(
+        self,
+        axis: usize,
+    ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error>;
+}
+
+impl<T: CType, A: Access<T>, P: ScanAxis<A, T>> NDArrayScan for Array<T, A, P> {
+    type Output = AccessOp<P::Op, P>;
+    type Cdf = AccessOp<P::Cdf, P>;
+
+    fn cdf_normalize(self, axis: usize) -> Result<Array<T::Float, Self::Cdf, P>, Error> {
+        let (dim, inner) = scan_dims(&self.shape, axis).map_err(|cause| self.trace(cause))?;
+        self.apply(|platform, access| platform.cdf(access, dim, inner))
+    }
+
+    fn cumprod(self, axis: usize) -> Result<Array<T, Self::Output, P>, Error> {
+        let (dim, inner) = scan_dims(&self.shape, axis).map_err(|cause| self.trace(cause))?;
+        self.apply(|platform, access| platform.cumprod(access, dim, inner))
+    }
+
+    fn cumsum(self, axis: usize) -> Result<Array<T, Self::Output, P>, Error> {
+        let (dim, inner) = scan_dims(&self.shape, axis).map_err(|cause| self.trace(cause))?;
+        self.apply(|platform, access| platform.cumsum(access, dim, inner))
+    }
+
+    fn logcumsumexp(self, axis: usize) -> Result<Array<T, Self::Output, P>, Error> {
+        let (dim, inner) = scan_dims(&self.shape, axis).map_err(|cause| self.trace(cause))?;
+        self.apply(|platform, access| platform.logcumsumexp(access, dim, inner))
+    }
+}
+
+/// Array sorting methods
+pub trait NDArraySort: NDArray + fmt::Debug {
+    type Sort: Access<Self::DType>;
+    type ArgSort: Access<u64>;
+
+    /// Construct an operation to sort this array along the given `axis`.
+    /// The sort is stable, and places NaN last (or first, if `descending`).
+    #[allow(clippy::type_complexity)]
+    fn sort(
+        self,
+        axis: usize,
+        descending: bool,
+    ) -> Result<Array<Self::DType, Self::Sort, Self::Platform>, Error>;
+
+    /// Construct an operation to find the indices along the given `axis`
+    /// which would stably sort this array in ascending order.
+    fn argsort(self, axis: usize) -> Result<Array<u64, Self::ArgSort, Self::Platform>, Error>;
+}
+
+impl<T: CType, A: Access<T>, P: SortAxis<A, T>> NDArraySort for Array<T, A, P> {
+    type Sort = AccessOp<P::Sort, P>;
+    type ArgSort = AccessOp<P::ArgSort, P>;
+
+    fn sort(self, axis: usize, descending: bool) -> Result<Array<T, Self::Sort, P>, Error> {
+        let (dim, inner) = scan_dims(&self.shape, axis).map_err(|cause| self.trace(cause))?;
+        self.apply(|platform, access| platform.sort(access, dim, inner, descending))
+    }
+
+    fn argsort(self, axis: usize) -> Result<Array<u64, Self::ArgSort, P>, Error> {
+        let (dim, inner) = scan_dims(&self.shape, axis).map_err(|cause| self.trace(cause))?;
+        self.apply(|platform, access| platform.argsort(access, dim, inner))
+    }
+}
+
+impl<T: CType, A: Access<T>, P: Transform<A, T>> Array<T, A, P> {
+    /// Construct a view of this array with the given `shape` which reads the element at
+    /// `coord` from the offset `offset + sum(coord * strides)` of this array, in row-major order.
+    ///
+    /// This allows expressing views which the safe API does not cover, like a Toeplitz matrix
+    /// or a sliding window. Only the number of `strides` is checked.
+    ///
+    /// # Safety
+    /// Every offset reachable through the view must be less than the size of this array.
+    /// On the host an out-of-bounds offset will panic, but on other platforms the result of
+    /// reading out of bounds is undefined.
+    // named after NumPy's `as_strided`, and consumes `self` like the other views in this impl
+    #[allow(clippy::wrong_self_convention, clippy::type_complexity)]
+    pub unsafe fn as_strided(
+        self,
+        shape: Shape,
+        strides: Strides,
+        offset: usize,
+    ) -> Result<Array<T, AccessOp<P::Transpose, P>, P>, Error> {
+        if shape.len() != strides.len() {
+            return Err(self.trace(Error::Bounds(format!(
+                "strides {strides:?} do not match shape {shape:?}"
+            ))));
+        }
+
+        let platform = self.platform;
+        let access = platform.as_strided(self.access, shape.clone(), strides, offset)?;
+
+        Ok(Array {
+            shape,
+            access,
+            platform,
+            dtype: self.dtype,
+            meta: self.meta.unlabeled(),
+        })
+    }
+
+    /// Read only the elements of this array in the given `range` back into host memory,
+    /// e.g. to preview a large op output or read a single row of it.
+    ///
+    /// The slice is pushed into the op graph, so only the requested region is transferred.
+    /// A small region is computed one element at a time, so that an elementwise op computes only
+    /// the requested elements; otherwise, or if an op in the graph cannot compute an individual
+    /// element, the slice of its output is computed on its platform as usual.
+    pub fn read_range(self, range: impl IntoRange) -> Result<ArrayBuf<T, Vec<T>>, Error> {
+        let slice = self.slice(range)?;
+        let shape = Shape::from_slice(slice.shape());
+
+        if slice.size() < crate::host::VEC_MIN_SIZE {
+            let values = (0..slice.size())
+                .map(|offset| slice.access.read_value(offset))
+                .collect::<Result<Vec<T>, Error>>();
+
+            if let Ok(values) = values {
+                return ArrayBuf::new(values, shape);
+            }
+        }
+
+        let values = slice.buffer()?.to_slice()?.into_vec();
+        ArrayBuf::new(values, shape)
+    }
+
+    /// Construct a slice of the elements of this array whose coordinates along the given `axis`
+    /// lie within `bounds`, e.g. `array.sel(0, 1990.0..2000.0)`.
+    /// The coordinates of the slice are the selected coordinates.
+    /// This will return an error if the `axis` has no coordinates or none lie within `bounds`.
+    #[allow(clippy::type_complexity)]
+    pub fn sel<B: RangeBounds<f64>>(
+        self,
+        axis: usize,
+        bounds: B,
+    ) -> Result<Array<T, AccessOp<P::Slice, P>, P>, Error> {
+        let selected = self
+            .meta
+            .sel(axis, bounds)
+            .map_err(|cause| self.trace(cause))?;
+
+        let mut range = Range::with_capacity(axis + 1);
+        range.extend(
+            self.shape[..axis]
+                .iter()
+                .map(|dim| AxisRange::In(0, *dim, 1)),
+        );
+        range.push(selected);
+
+        self.slice(range)
+    }
+
+    /// Transpose this array so that its axes are in the order of the given axis `labels`,
+    /// which must name every axis of this array.
+    #[allow(clippy::type_complexity)]
+    pub fn transpose_by(
+        self,
+        labels: &[&str],
+    ) -> Result<Array<T, AccessOp<P::Transpose, P>, P>, Error> {
+        let permutation = self.axes_by(labels)?;
+        self.transpose(Some(permutation))
+    }
+}
+
+/// The values and indices of an extreme value along some axes of an array
+pub type IndexedReduce<T, P> = (
+    Array<T, AccessBuf<<P as Convert<T>>::Buffer>, P>,
+    Array<u64, AccessBuf<<P as Convert<u64>>::Buffer>, P>,
+);
+
+impl<T, A, P> Array<T, A, P>
+where
+    T: CType,
+    A: Access<T>,
+    P: Transform<A, T> + ReduceAxesWithIndex<Accessor<T>, T>,
+    Accessor<T>: From<A> + From<AccessOp<P::Transpose, P>>,
+{
+    /// Compute the maximum over the given `axes` together with its index, in a single pass.
+    /// Each index is an offset into the given `axes` in row-major order;
+    /// if the maximum occurs more than once, the index of its first occurrence is returned.
+    /// As with `max` and `min`, the first NaN in a lane is its extreme value.
+    pub fn max_with_index(
+        self,
+        axes: impl IntoAxes,
+        keepdims: bool,
+    ) -> Result<IndexedReduce<T, P>, Error> {
+        self.reduce_axes_with_index(axes, keepdims, |platform, access, stride| {
+            platform.max_with_index(access, stride)
+        })
+    }
+
+    /// Compute the minimum over the given `axes` together with its index, in a single pass.
+    /// Each index is an offset into the given `axes` in row-major order;
+    /// if the minimum occurs more than once, the index of its first occurrence is returned.
+    /// As with `max` and `min`, the first NaN in a lane is its extreme value.
+    pub fn min_with_index(
+        self,
+        axes: impl IntoAxes,
+        keepdims: bool,
+    ) -> Result<IndexedReduce<T, P>, Error> {
+        self.reduce_axes_with_index(axes, keepdims, |platform, access, stride| {
+            platform.min_with_index(access, stride)
+        })
+    }
+}
+
+/// A proxy for the element at a single coordinate of an array, constructed by [`Array::at`]
+pub struct Element<'a, A> {
+    array: A,
+    coord: &'a [usize],
+}
+
+impl<'a, A> Element<'a, A> {
+    /// Borrow the coordinate of this element.
+    pub fn coord(&self) -> &[usize] {
+        self.coord
+    }
+}
+
+impl<'a, A: NDArrayRead> Element<'a, &'a A> {
+    /// Read the value of this element.
+    pub fn get(&self) -> Result<A::DType, Error> {
+        self.array.read_value(self.coord)
+    }
+}
+
+impl<'a, A: NDArrayRead> Element<'a, &'a mut A> {
+    /// Read the value of this element.
+    pub fn get(&self) -> Result<A::DType, Error> {
+        self.array.read_value(self.coord)
+    }
+}
+
+impl<'a, A: NDArrayWrite> Element<'a, &'a mut A> {
+    /// Overwrite the value of this element.
+    pub fn set(&mut self, value: A::DType) -> Result<(), Error> {
+        self.array.write_value_at(self.coord, value)
+    }
+}
+
+// index ops for arrays in host memory
+
+impl<T, B, P> Index<&[usize]> for Array<T, AccessBuf<B>, P>
+where
+    T: CType,
+    B: Borrow<[T]>,
+{
+    type Output = T;
+
+    fn index(&self, coord: &[usize]) -> &T {
+        match offset_of(coord, &self.shape) {
+            Ok(offset) => &self.access.inner().borrow()[offset],
+            Err(cause) => panic!("{cause}"),
+        }
+    }
+}
+
+impl<T, B, P> IndexMut<&[usize]> for Array<T, AccessBuf<B>, P>
+where
+    T: CType,
+    B: BorrowMut<[T]>,
+{
+    fn index_mut(&mut self, coord: &[usize]) -> &mut T {
+        match offset_of(coord, &self.shape) {
+            Ok(offset) => &mut self.access.inner_mut().borrow_mut()[offset],
+            Err(cause) => panic!("{cause}"),
+        }
+    }
+}
+
+impl<T, B, P, const N: usize> Index<[usize; N]> for Array<T, AccessBuf<B>, P>
+where
+    T: CType,
+    B: Borrow<[T]>,
+{
+    type Output = T;
+
+    fn index(&self, coord: [usize; N]) -> &T {
+        &self[&coord[..]]
+    }
+}
+
+impl<T, B, P, const N: usize> IndexMut<[usize; N]> for Array<T, AccessBuf<B>, P>
+where
+    T: CType,
+    B: BorrowMut<[T]>,
+{
+    fn index_mut(&mut self, coord: [usize; N]) -> &mut T {
+        &mut self[&coord[..]]
     }
 }
 
@@ -491,39 +1569,132 @@ where
             access: self.platform.cast(self.access)?,
             platform: self.platform,
             dtype: PhantomData,
+            meta: self.meta,
         })
     }
 }
 
 /// Axis-wise array reduce operations
+///
+/// A `max`, `min`, `product`, or `sum` of a lane which contains NaN is NaN, regardless of the sign
+/// of the NaN, and the `argmax` or `argmin` of such a lane is the index of its first NaN. Each `*_ignore_nan` variant skips NaN instead: a lane of only NaN reduces to NaN
+/// with `max_ignore_nan` or `min_ignore_nan`, and to one or zero with `product_ignore_nan` or
+/// `sum_ignore_nan`. For an integer type, the `*_ignore_nan` variants are the plain reductions.
+///
+/// A `product` or `sum` of an empty lane (i.e. over an axis with dimension zero) is its identity,
+/// one or zero respectively. Every other reduction of an empty lane returns an error.
 pub trait NDArrayReduce: NDArray + fmt::Debug {
     type Output: Access<Self::DType>;
+    type Moment: Access<<Self::DType as CType>::Float>;
+    type Arg: Access<u64>;
 
     /// Construct a max-reduce operation over the given `axes`.
     fn max(
         self,
-        axes: Axes,
+        axes: impl IntoAxes,
         keepdims: bool,
     ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error>;
 
     /// Construct a min-reduce operation over the given `axes`.
     fn min(
         self,
-        axes: Axes,
+        axes: impl IntoAxes,
         keepdims: bool,
     ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error>;
 
     /// Construct a product-reduce operation over the given `axes`.
+    /// The product of an integer type wraps on overflow.
     fn product(
         self,
-        axes: Axes,
+        axes: impl IntoAxes,
         keepdims: bool,
     ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error>;
 
     /// Construct a sum-reduce operation over the given `axes`.
+    /// The sum of an integer type wraps on overflow, e.g. the sum of a `u8` mask wraps past 255;
+    /// use [`Array::sum_to`] to accumulate into a wider type.
     fn sum(
         self,
-        axes: Axes,
+        axes: impl IntoAxes,
+        keepdims: bool,
+    ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error>;
+
+    /// Construct a mean-reduce operation over the given `axes`.
+    /// An integer type is promoted to its floating-point type.
+    #[allow(clippy::type_complexity)]
+    fn mean(
+        self,
+        axes: impl IntoAxes,
+        keepdims: bool,
+    ) -> Result<Array<<Self::DType as CType>::Float, Self::Moment, Self::Platform>, Error>;
+
+    /// Construct an operation to compute the (population) variance over the given `axes`.
+    /// An integer type is promoted to its floating-point type.
+    #[allow(clippy::type_complexity)]
+    fn var(
+        self,
+        axes: impl IntoAxes,
+        keepdims: bool,
+    ) -> Result<Array<<Self::DType as CType>::Float, Self::Moment, Self::Platform>, Error>;
+
+    /// Construct an operation to compute the (population) standard deviation over the given
+    /// `axes`. An integer type is promoted to its floating-point type.
+    #[allow(clippy::type_complexity)]
+    fn std(
+        self,
+        axes: impl IntoAxes,
+        keepdims: bool,
+    ) -> Result<Array<<Self::DType as CType>::Float, Self::Moment, Self::Platform>, Error>;
+
+    /// Construct an operation to find the index of the maximum over the given `axes`.
+    /// Each index is an offset into the given `axes` in row-major order;
+    /// if the maximum occurs more than once, the index of its first occurrence is returned,
+    /// and if a lane contains NaN, the index of its first NaN is returned.
+    fn argmax(
+        self,
+        axes: impl IntoAxes,
+        keepdims: bool,
+    ) -> Result<Array<u64, Self::Arg, Self::Platform>, Error>;
+
+    /// Construct an operation to find the index of the minimum over the given `axes`.
+    /// Each index is an offset into the given `axes` in row-major order;
+    /// if the minimum occurs more than once, the index of its first occurrence is returned,
+    /// and if a lane contains NaN, the index of its first NaN is returned.
+    fn argmin(
+        self,
+        axes: impl IntoAxes,
+        keepdims: bool,
+    ) -> Result<Array<u64, Self::Arg, Self::Platform>, Error>;
+
+    /// Construct a max-reduce operation over the given `axes` which skips NaN.
+    #[allow(clippy::type_complexity)]
+    fn max_ignore_nan(
+        self,
+        axes: impl IntoAxes,
+        keepdims: bool,
+    ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error>;
+
+    /// Construct a min-reduce operation over the given `axes` which skips NaN.
+    #[allow(clippy::type_complexity)]
+    fn min_ignore_nan(
+        self,
+        axes: impl IntoAxes,
+        keepdims: bool,
+    ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error>;
+
+    /// Construct a product-reduce operation over the given `axes` which treats NaN as one.
+    #[allow(clippy::type_complexity)]
+    fn product_ignore_nan(
+        self,
+        axes: impl IntoAxes,
+        keepdims: bool,
+    ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error>;
+
+    /// Construct a sum-reduce operation over the given `axes` which treats NaN as zero.
+    #[allow(clippy::type_complexity)]
+    fn sum_ignore_nan(
+        self,
+        axes: impl IntoAxes,
         keepdims: bool,
     ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error>;
 }
@@ -536,10 +1707,12 @@ where
     Accessor<T>: From<A> + From<AccessOp<P::Transpose, P>>,
 {
     type Output = AccessOp<P::Op, P>;
+    type Moment = AccessOp<P::Moment, P>;
+    type Arg = AccessOp<P::Arg, P>;
 
     fn max(
         self,
-        axes: Axes,
+        axes: impl IntoAxes,
         keepdims: bool,
     ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error> {
         self.reduce_axes(axes, keepdims, |platform, access, stride| {
@@ -549,7 +1722,7 @@ where
 
     fn min(
         self,
-        axes: Axes,
+        axes: impl IntoAxes,
         keepdims: bool,
     ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error> {
         self.reduce_axes(axes, keepdims, |platform, access, stride| {
@@ -557,24 +1730,166 @@ where
         })
     }
 
-    fn product(
+    fn product(
+        self,
+        axes: impl IntoAxes,
+        keepdims: bool,
+    ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error> {
+        self.reduce_axes_or_identity(axes, keepdims, T::ONE, |platform, access, stride| {
+            ReduceAxes::product(platform, access, stride)
+        })
+    }
+
+    fn sum(
+        self,
+        axes: impl IntoAxes,
+        keepdims: bool,
+    ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error> {
+        self.reduce_axes_or_identity(axes, keepdims, T::ZERO, |platform, access, stride| {
+            ReduceAxes::sum(platform, access, stride)
+        })
+    }
+
+    fn mean(
+        self,
+        axes: impl IntoAxes,
+        keepdims: bool,
+    ) -> Result<Array<T::Float, Self::Moment, Self::Platform>, Error> {
+        self.reduce_axes(axes, keepdims, |platform, access, stride| {
+            ReduceAxes::mean(platform, access, stride)
+        })
+    }
+
+    fn var(
+        self,
+        axes: impl IntoAxes,
+        keepdims: bool,
+    ) -> Result<Array<T::Float, Self::Moment, Self::Platform>, Error> {
+        self.reduce_axes(axes, keepdims, |platform, access, stride| {
+            ReduceAxes::var(platform, access, stride)
+        })
+    }
+
+    fn std(
+        self,
+        axes: impl IntoAxes,
+        keepdims: bool,
+    ) -> Result<Array<T::Float, Self::Moment, Self::Platform>, Error> {
+        self.reduce_axes(axes, keepdims, |platform, access, stride| {
+            ReduceAxes::std(platform, access, stride)
+        })
+    }
+
+    fn argmax(
+        self,
+        axes: impl IntoAxes,
+        keepdims: bool,
+    ) -> Result<Array<u64, Self::Arg, Self::Platform>, Error> {
+        self.reduce_axes(axes, keepdims, |platform, access, stride| {
+            ReduceAxes::argmax(platform, access, stride)
+        })
+    }
+
+    fn argmin(
+        self,
+        axes: impl IntoAxes,
+        keepdims: bool,
+    ) -> Result<Array<u64, Self::Arg, Self::Platform>, Error> {
+        self.reduce_axes(axes, keepdims, |platform, access, stride| {
+            ReduceAxes::argmin(platform, access, stride)
+        })
+    }
+
+    fn max_ignore_nan(
+        self,
+        axes: impl IntoAxes,
+        keepdims: bool,
+    ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error> {
+        self.reduce_axes(axes, keepdims, |platform, access, stride| {
+            ReduceAxes::max_ignore_nan(platform, access, stride)
+        })
+    }
+
+    fn min_ignore_nan(
+        self,
+        axes: impl IntoAxes,
+        keepdims: bool,
+    ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error> {
+        self.reduce_axes(axes, keepdims, |platform, access, stride| {
+            ReduceAxes::min_ignore_nan(platform, access, stride)
+        })
+    }
+
+    fn product_ignore_nan(
+        self,
+        axes: impl IntoAxes,
+        keepdims: bool,
+    ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error> {
+        self.reduce_axes_or_identity(axes, keepdims, T::ONE, |platform, access, stride| {
+            ReduceAxes::product_ignore_nan(platform, access, stride)
+        })
+    }
+
+    fn sum_ignore_nan(
+        self,
+        axes: impl IntoAxes,
+        keepdims: bool,
+    ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error> {
+        self.reduce_axes_or_identity(axes, keepdims, T::ZERO, |platform, access, stride| {
+            ReduceAxes::sum_ignore_nan(platform, access, stride)
+        })
+    }
+}
+
+impl<T, A, P> Array<T, A, P>
+where
+    T: CType,
+    A: Access<T>,
+    P: Transform<A, T> + ReduceAxes<Accessor<T>, T>,
+    Accessor<T>: From<A> + From<AccessOp<P::Transpose, P>>,
+{
+    /// Construct a max-reduce operation over the axes with the given `labels`.
+    #[allow(clippy::type_complexity)]
+    pub fn max_by(
+        self,
+        labels: &[&str],
+        keepdims: bool,
+    ) -> Result<Array<T, AccessOp<P::Op, P>, P>, Error> {
+        let axes = self.axes_by(labels)?;
+        self.max(axes, keepdims)
+    }
+
+    /// Construct a min-reduce operation over the axes with the given `labels`.
+    #[allow(clippy::type_complexity)]
+    pub fn min_by(
+        self,
+        labels: &[&str],
+        keepdims: bool,
+    ) -> Result<Array<T, AccessOp<P::Op, P>, P>, Error> {
+        let axes = self.axes_by(labels)?;
+        self.min(axes, keepdims)
+    }
+
+    /// Construct a product-reduce operation over the axes with the given `labels`.
+    #[allow(clippy::type_complexity)]
+    pub fn product_by(
         self,
-        axes: Axes,
+        labels: &[&str],
         keepdims: bool,
-    ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error> {
-        self.reduce_axes(axes, keepdims, |platform, access, stride| {
-            ReduceAxes::product(platform, access, stride)
-        })
+    ) -> Result<Array<T, AccessOp<P::Op, P>, P>, Error> {
+        let axes = self.axes_by(labels)?;
+        self.product(axes, keepdims)
     }
 
-    fn sum(
+    /// Construct a sum-reduce operation over the axes with the given `labels`.
+    #[allow(clippy::type_complexity)]
+    pub fn sum_by(
         self,
-        axes: Axes,
+        labels: &[&str],
         keepdims: bool,
-    ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error> {
-        self.reduce_axes(axes, keepdims, |platform, access, stride| {
-            ReduceAxes::sum(platform, access, stride)
-        })
+    ) -> Result<Array<T, AccessOp<P::Op, P>, P>, Error> {
+        let axes = self.axes_by(labels)?;
+        self.sum(axes, keepdims)
     }
 }
 
@@ -592,21 +1907,25 @@ pub trait NDArrayTransform: NDArray + Sized + fmt::Debug {
     /// Broadcast this array into the given `shape`.
     fn broadcast(
         self,
-        shape: Shape,
+        shape: impl IntoShape,
     ) -> Result<Array<Self::DType, Self::Broadcast, Self::Platform>, Error>;
 
     /// Reshape this `array`.
-    fn reshape(self, shape: Shape) -> Result<Self, Error>;
+    fn reshape(self, shape: impl IntoShape) -> Result<Self, Error>;
 
     /// Construct a slice of this array.
-    fn slice(self, range: Range) -> Result<Array<Self::DType, Self::Slice, Self::Platform>, Error>;
+    #[allow(clippy::type_complexity)]
+    fn slice(
+        self,
+        range: impl IntoRange,
+    ) -> Result<Array<Self::DType, Self::Slice, Self::Platform>, Error>;
 
     /// Contract the given `axes` of this array.
     /// This will return an error if any of the `axes` have dimension > 1.
-    fn squeeze(self, axes: Axes) -> Result<Self, Error>;
+    fn squeeze(self, axes: impl IntoAxes) -> Result<Self, Error>;
 
     /// Expand the given `axes` of this array.
-    fn unsqueeze(self, axes: Axes) -> Result<Self, Error>;
+    fn unsqueeze(self, axes: impl IntoAxes) -> Result<Self, Error>;
 
     /// Transpose this array according to the given `permutation`.
     /// If no permutation is given, the array axes will be reversed.
@@ -614,6 +1933,141 @@ pub trait NDArrayTransform: NDArray + Sized + fmt::Debug {
         self,
         permutation: Option<Axes>,
     ) -> Result<Array<Self::DType, Self::Transpose, Self::Platform>, Error>;
+
+    /// Reverse the order of the elements of this array along the given `axes`, without copying.
+    #[allow(clippy::type_complexity)]
+    fn reverse(
+        self,
+        axes: impl IntoAxes,
+    ) -> Result<Array<Self::DType, Self::Transpose, Self::Platform>, Error>;
+
+    /// Reverse the order of the elements of this array along the given `axes`, without copying.
+    /// This is an alias of [`NDArrayTransform::reverse`].
+    #[allow(clippy::type_complexity)]
+    fn flip(
+        self,
+        axes: impl IntoAxes,
+    ) -> Result<Array<Self::DType, Self::Transpose, Self::Platform>, Error> {
+        self.reverse(axes)
+    }
+
+    /// Roll the elements of this array along the given `axis` by `shift`, without copying,
+    /// so that the element at index `i` moves to `(i + shift) % dim`, wrapping around.
+    /// A negative `shift` rolls the elements toward the start of the axis.
+    /// The coordinates of the rolled axis are rolled with it, and dropped if no longer monotonic.
+    #[allow(clippy::type_complexity)]
+    fn roll(
+        self,
+        shift: isize,
+        axis: usize,
+    ) -> Result<Array<Self::DType, Self::Transpose, Self::Platform>, Error>;
+
+    /// Construct an iterator over slices of this array with at most `batch_size` elements
+    /// along the given `axis`. The last slice may be smaller if `batch_size` does not evenly
+    /// divide the dimension of `axis`.
+    fn chunks(self, batch_size: usize, axis: usize) -> Result<Chunks<Self>, Error>
+    where
+        Self: Clone,
+    {
+        if axis >= self.ndim() {
+            Err(Error::Bounds(format!(
+                "invalid axis {axis} for an array with shape {:?}",
+                self.shape()
+            )))
+        } else if batch_size == 0 {
+            Err(Error::Bounds("batch size must be positive".to_string()))
+        } else {
+            Ok(Chunks {
+                array: self,
+                axis,
+                batch_size,
+                offset: 0,
+            })
+        }
+    }
+
+    /// Split this array into slices with the given `sizes` along the given `axis`.
+    /// The `sizes` must sum to the dimension of `axis`.
+    #[allow(clippy::type_complexity)]
+    fn split(
+        self,
+        sizes: &[usize],
+        axis: usize,
+    ) -> Result<Vec<Array<Self::DType, Self::Slice, Self::Platform>>, Error>
+    where
+        Self: Clone,
+    {
+        if axis >= self.ndim() {
+            return Err(Error::Bounds(format!(
+                "invalid axis {axis} for an array with shape {:?}",
+                self.shape()
+            )));
+        }
+
+        let dim = self.shape()[axis];
+
+        if sizes.iter().sum::<usize>() != dim {
+            return Err(Error::Bounds(format!(
+                "cannot split axis {axis} of dimension {dim} into {sizes:?}"
+            )));
+        }
+
+        let mut start = 0;
+        let mut slices = Vec::with_capacity(sizes.len());
+
+        for size in sizes {
+            let range = axis_range(self.shape(), axis, start, start + size);
+            slices.push(self.clone().slice(range)?);
+            start += size;
+        }
+
+        Ok(slices)
+    }
+}
+
+/// An iterator over fixed-size slices of an array, constructed by [`NDArrayTransform::chunks`]
+pub struct Chunks<A> {
+    array: A,
+    axis: usize,
+    batch_size: usize,
+    offset: usize,
+}
+
+impl<A> Iterator for Chunks<A>
+where
+    A: NDArrayTransform + Clone,
+{
+    type Item = Result<Array<A::DType, A::Slice, A::Platform>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let dim = self.array.shape()[self.axis];
+
+        if self.offset >= dim {
+            return None;
+        }
+
+        let start = self.offset;
+        let stop = Ord::min(start + self.batch_size, dim);
+        self.offset = stop;
+
+        let range = axis_range(self.array.shape(), self.axis, start, stop);
+        Some(self.array.clone().slice(range))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl<A> ExactSizeIterator for Chunks<A>
+where
+    A: NDArrayTransform + Clone,
+{
+    fn len(&self) -> usize {
+        let dim = self.array.shape()[self.axis];
+        dim.saturating_sub(self.offset).div_ceil(self.batch_size)
+    }
 }
 
 impl<T, A, P> NDArrayTransform for Array<T, A, P>
@@ -626,15 +2080,20 @@ where
     type Slice = AccessOp<P::Slice, P>;
     type Transpose = AccessOp<P::Transpose, P>;
 
-    fn broadcast(self, shape: Shape) -> Result<Array<T, AccessOp<P::Broadcast, P>, P>, Error> {
+    fn broadcast(
+        self,
+        shape: impl IntoShape,
+    ) -> Result<Array<T, AccessOp<P::Broadcast, P>, P>, Error> {
+        let shape = shape.into_shape();
         if !can_broadcast(self.shape(), &shape) {
-            return Err(Error::Bounds(format!(
+            return Err(self.trace(Error::Bounds(format!(
                 "cannot broadcast {self:?} into {shape:?}"
-            )));
+            ))));
         }
 
         let platform = P::select(shape.iter().product());
         let broadcast = Shape::from_slice(&shape);
+        let meta = self.meta.broadcast(&self.shape, &shape);
         let access = platform.broadcast(self.access, self.shape, broadcast)?;
 
         Ok(Array {
@@ -642,38 +2101,33 @@ where
             access,
             platform,
             dtype: self.dtype,
+            meta,
         })
     }
 
-    fn reshape(mut self, shape: Shape) -> Result<Self, Error> {
+    fn reshape(mut self, shape: impl IntoShape) -> Result<Self, Error> {
+        let shape = shape.into_shape();
         if shape.iter().product::<usize>() == self.size() {
+            if shape != self.shape {
+                self.meta = self.meta.unlabeled();
+            }
+
             self.shape = shape;
             Ok(self)
         } else {
-            Err(Error::Bounds(format!(
+            Err(self.trace(Error::Bounds(format!(
                 "cannot reshape an array with shape {:?} into {shape:?}",
                 self.shape
-            )))
+            ))))
         }
     }
 
-    fn slice(self, mut range: Range) -> Result<Array<T, AccessOp<P::Slice, P>, P>, Error> {
-        for (dim, range) in self.shape.iter().zip(&range) {
-            match range {
-                AxisRange::At(i) if i < dim => Ok(()),
-                AxisRange::In(start, stop, _step) if start < dim && stop <= dim => Ok(()),
-                AxisRange::Of(indices) if indices.iter().all(|i| i < dim) => Ok(()),
-                range => Err(Error::Bounds(format!(
-                    "invalid range {range:?} for dimension {dim}"
-                ))),
-            }?;
-        }
-
-        for dim in self.shape.iter().skip(range.len()).copied() {
-            range.push(AxisRange::In(0, dim, 1));
-        }
+    fn slice(self, range: impl IntoRange) -> Result<Array<T, AccessOp<P::Slice, P>, P>, Error> {
+        let range = range.into_range();
+        let range = resolve_range(&self.shape, range).map_err(|cause| self.trace(cause))?;
 
         let shape = range_shape(self.shape(), &range);
+        let meta = self.meta.slice(&range);
         let access = self.platform.slice(self.access, &self.shape, range)?;
         let platform = P::select(shape.iter().product());
 
@@ -682,16 +2136,20 @@ where
             access,
             platform,
             dtype: self.dtype,
+            meta,
         })
     }
 
-    fn squeeze(mut self, mut axes: Axes) -> Result<Self, Error> {
+    fn squeeze(mut self, axes: impl IntoAxes) -> Result<Self, Error> {
+        let mut axes = axes.into_axes();
         if axes.iter().copied().any(|x| x >= self.ndim()) {
-            return Err(Error::Bounds(format!("invalid contraction axes: {axes:?}")));
+            return Err(self.trace(Error::Bounds(format!("invalid contraction axes: {axes:?}"))));
         }
 
         axes.sort();
 
+        self.meta = self.meta.remove_axes(&axes, self.ndim());
+
         for x in axes.into_iter().rev() {
             self.shape.remove(x);
         }
@@ -699,13 +2157,16 @@ where
         Ok(self)
     }
 
-    fn unsqueeze(mut self, mut axes: Axes) -> Result<Self, Error> {
+    fn unsqueeze(mut self, axes: impl IntoAxes) -> Result<Self, Error> {
+        let mut axes = axes.into_axes();
         if axes.iter().copied().any(|x| x > self.ndim()) {
-            return Err(Error::Bounds(format!("invalid expansion axes: {axes:?}")));
+            return Err(self.trace(Error::Bounds(format!("invalid expansion axes: {axes:?}"))));
         }
 
         axes.sort();
 
+        self.meta = self.meta.insert_axes(&axes, self.ndim());
+
         for x in axes.into_iter().rev() {
             self.shape.insert(x, 1);
         }
@@ -726,16 +2187,17 @@ where
             {
                 Ok(axes)
             } else {
-                Err(Error::Bounds(format!(
+                Err(self.trace(Error::Bounds(format!(
                     "invalid permutation for shape {:?}: {:?}",
                     self.shape, axes
-                )))
+                ))))
             }
         } else {
             Ok((0..self.ndim()).into_iter().rev().collect())
         }?;
 
         let shape = permutation.iter().copied().map(|x| self.shape[x]).collect();
+        let meta = self.meta.transpose(&permutation);
         let platform = self.platform;
         let access = platform.transpose(self.access, self.shape, permutation)?;
 
@@ -744,6 +2206,68 @@ where
             access,
             platform,
             dtype: self.dtype,
+            meta,
+        })
+    }
+
+    fn reverse(self, axes: impl IntoAxes) -> Result<Array<T, AccessOp<P::Transpose, P>, P>, Error> {
+        let axes = axes.into_axes();
+        let valid = axes
+            .iter()
+            .enumerate()
+            .all(|(i, x)| *x < self.ndim() && !axes[..i].contains(x));
+
+        if !valid {
+            return Err(self.trace(Error::Bounds(format!(
+                "invalid axes to reverse for shape {:?}: {axes:?}",
+                self.shape
+            ))));
+        }
+
+        let shape = self.shape.clone();
+        let meta = self.meta.reverse(&axes);
+        let platform = self.platform;
+        let access = platform.reverse(self.access, self.shape, axes)?;
+
+        Ok(Array {
+            shape,
+            access,
+            platform,
+            dtype: self.dtype,
+            meta,
+        })
+    }
+
+    fn roll(
+        self,
+        shift: isize,
+        axis: usize,
+    ) -> Result<Array<T, AccessOp<P::Transpose, P>, P>, Error> {
+        if axis >= self.ndim() {
+            return Err(self.trace(Error::Bounds(format!(
+                "invalid axis {axis} to roll for shape {:?}",
+                self.shape
+            ))));
+        }
+
+        let dim = self.shape[axis];
+        let shift = if dim == 0 {
+            0
+        } else {
+            shift.rem_euclid(dim as isize) as usize
+        };
+
+        let shape = self.shape.clone();
+        let meta = self.meta.roll(axis, shift);
+        let platform = self.platform;
+        let access = platform.roll(self.access, self.shape, shift, axis)?;
+
+        Ok(Array {
+            shape,
+            access,
+            platform,
+            dtype: self.dtype,
+            meta,
         })
     }
 }
@@ -762,8 +2286,18 @@ pub trait NDArrayUnary: NDArray + Sized {
     /// Construct a natural logarithm operation.
     fn ln(self) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error>;
 
+    /// Construct an approximate reciprocal operation, which may differ from `1 / n`
+    /// by a few ulp on a GPU. The reciprocal of floating-point zero is infinite,
+    /// and the reciprocal of integer zero is zero, as with [`CType::div`].
+    #[allow(clippy::type_complexity)]
+    fn reciprocal_approx(self) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error>;
+
     /// Construct an integer rounding operation.
     fn round(self) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error>;
+
+    /// Construct a softplus operation, `ln(1 + e^x)`, which does not overflow for large inputs.
+    #[allow(clippy::type_complexity)]
+    fn softplus(self) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error>;
 }
 
 impl<T, A, P> NDArrayUnary for Array<T, A, P>
@@ -789,9 +2323,17 @@ where
         self.apply(|platform, access| platform.ln(access))
     }
 
+    fn reciprocal_approx(self) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error> {
+        self.apply(|platform, access| platform.reciprocal_approx(access))
+    }
+
     fn round(self) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error> {
         self.apply(|platform, access| platform.round(access))
     }
+
+    fn softplus(self) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error> {
+        self.apply(|platform, access| platform.softplus(access))
+    }
 }
 
 /// Unary boolean array operations
@@ -833,27 +2375,28 @@ where
     fn xor(self, other: O) -> Result<Array<u8, Self::Output, Self::Platform>, Error>;
 }
 
-impl<T, L, R, P> NDArrayBoolean<Array<T, R, P>> for Array<T, L, P>
+impl<T, L, R, P, RP> NDArrayBoolean<Array<T, R, RP>> for Array<T, L, P>
 where
     T: CType,
     L: Access<T>,
     R: Access<T>,
     P: ElementwiseBoolean<L, R, T>,
+    RP: PlatformInstance,
 {
     type Output = AccessOp<P::Op, P>;
 
-    fn and(self, other: Array<T, R, P>) -> Result<Array<u8, Self::Output, Self::Platform>, Error> {
-        same_shape("and", self.shape(), other.shape())?;
+    fn and(self, other: Array<T, R, RP>) -> Result<Array<u8, Self::Output, Self::Platform>, Error> {
+        same_shape_of("and", &self, &other)?;
         self.apply_dual(other, |platform, left, right| platform.and(left, right))
     }
 
-    fn or(self, other: Array<T, R, P>) -> Result<Array<u8, Self::Output, Self::Platform>, Error> {
-        same_shape("or", self.shape(), other.shape())?;
+    fn or(self, other: Array<T, R, RP>) -> Result<Array<u8, Self::Output, Self::Platform>, Error> {
+        same_shape_of("or", &self, &other)?;
         self.apply_dual(other, |platform, left, right| platform.or(left, right))
     }
 
-    fn xor(self, other: Array<T, R, P>) -> Result<Array<u8, Self::Output, Self::Platform>, Error> {
-        same_shape("xor", self.shape(), other.shape())?;
+    fn xor(self, other: Array<T, R, RP>) -> Result<Array<u8, Self::Output, Self::Platform>, Error> {
+        same_shape_of("xor", &self, &other)?;
         self.apply_dual(other, |platform, left, right| platform.xor(left, right))
     }
 }
@@ -934,42 +2477,43 @@ pub trait NDArrayCompare<O: NDArray<DType = Self::DType>>: NDArray + Sized {
     fn ne(self, other: O) -> Result<Array<u8, Self::Output, Self::Platform>, Error>;
 }
 
-impl<T, L, R, P> NDArrayCompare<Array<T, R, P>> for Array<T, L, P>
+impl<T, L, R, P, RP> NDArrayCompare<Array<T, R, RP>> for Array<T, L, P>
 where
     T: CType,
     L: Access<T>,
     R: Access<T>,
     P: ElementwiseCompare<L, R, T>,
+    RP: PlatformInstance,
 {
     type Output = AccessOp<P::Op, P>;
 
-    fn eq(self, other: Array<T, R, P>) -> Result<Array<u8, Self::Output, Self::Platform>, Error> {
-        same_shape("compare", self.shape(), other.shape())?;
+    fn eq(self, other: Array<T, R, RP>) -> Result<Array<u8, Self::Output, Self::Platform>, Error> {
+        same_shape_of("compare", &self, &other)?;
         self.apply_dual(other, |platform, left, right| platform.eq(left, right))
     }
 
-    fn ge(self, other: Array<T, R, P>) -> Result<Array<u8, Self::Output, Self::Platform>, Error> {
-        same_shape("compare", self.shape(), other.shape())?;
+    fn ge(self, other: Array<T, R, RP>) -> Result<Array<u8, Self::Output, Self::Platform>, Error> {
+        same_shape_of("compare", &self, &other)?;
         self.apply_dual(other, |platform, left, right| platform.ge(left, right))
     }
 
-    fn gt(self, other: Array<T, R, P>) -> Result<Array<u8, Self::Output, Self::Platform>, Error> {
-        same_shape("compare", self.shape(), other.shape())?;
+    fn gt(self, other: Array<T, R, RP>) -> Result<Array<u8, Self::Output, Self::Platform>, Error> {
+        same_shape_of("compare", &self, &other)?;
         self.apply_dual(other, |platform, left, right| platform.gt(left, right))
     }
 
-    fn le(self, other: Array<T, R, P>) -> Result<Array<u8, Self::Output, Self::Platform>, Error> {
-        same_shape("compare", self.shape(), other.shape())?;
+    fn le(self, other: Array<T, R, RP>) -> Result<Array<u8, Self::Output, Self::Platform>, Error> {
+        same_shape_of("compare", &self, &other)?;
         self.apply_dual(other, |platform, left, right| platform.le(left, right))
     }
 
-    fn lt(self, other: Array<T, R, P>) -> Result<Array<u8, Self::Output, Self::Platform>, Error> {
-        same_shape("compare", self.shape(), other.shape())?;
+    fn lt(self, other: Array<T, R, RP>) -> Result<Array<u8, Self::Output, Self::Platform>, Error> {
+        same_shape_of("compare", &self, &other)?;
         self.apply_dual(other, |platform, left, right| platform.lt(left, right))
     }
 
-    fn ne(self, other: Array<T, R, P>) -> Result<Array<u8, Self::Output, Self::Platform>, Error> {
-        same_shape("compare", self.shape(), other.shape())?;
+    fn ne(self, other: Array<T, R, RP>) -> Result<Array<u8, Self::Output, Self::Platform>, Error> {
+        same_shape_of("compare", &self, &other)?;
         self.apply_dual(other, |platform, left, right| platform.ne(left, right))
     }
 }
@@ -1066,7 +2610,8 @@ where
     }
 }
 
-/// Array arithmetic operations
+/// Array arithmetic operations.
+/// Operands of different shapes are broadcast into their common shape, as in NumPy.
 pub trait NDArrayMath<O: NDArray<DType = Self::DType>>: NDArray + Sized {
     type Output: Access<Self::DType>;
 
@@ -1079,6 +2624,10 @@ pub trait NDArrayMath<O: NDArray<DType = Self::DType>>: NDArray + Sized {
     /// Construct a logarithm operation with the given `base`.
     fn log(self, base: O) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error>;
 
+    /// Construct an operation to compute `ln(e^self + e^rhs)` without overflowing.
+    #[allow(clippy::type_complexity)]
+    fn logaddexp(self, rhs: O) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error>;
+
     /// Construct a multiplication operation with the given `rhs`.
     fn mul(self, rhs: O) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error>;
 
@@ -1090,71 +2639,102 @@ pub trait NDArrayMath<O: NDArray<DType = Self::DType>>: NDArray + Sized {
 
     /// Construct a modulo operation with the given `rhs`.
     fn rem(self, rhs: O) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error>;
+
+    /// Construct a step function which is zero where this array is less than `threshold`
+    /// and one elsewhere.
+    #[allow(clippy::type_complexity)]
+    fn step(self, threshold: O) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error>;
 }
 
-impl<T, L, R, P> NDArrayMath<Array<T, R, P>> for Array<T, L, P>
+impl<T, L, R, P, RP> NDArrayMath<Array<T, R, RP>> for Array<T, L, P>
 where
     T: CType,
     L: Access<T>,
     R: Access<T>,
     P: ElementwiseDual<L, R, T>,
+    RP: PlatformInstance,
 {
     type Output = AccessOp<P::Op, P>;
 
     fn add(
         self,
-        rhs: Array<T, R, P>,
+        rhs: Array<T, R, RP>,
     ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error> {
-        same_shape("add", self.shape(), rhs.shape())?;
-        self.apply_dual(rhs, |platform, left, right| platform.add(left, right))
+        self.apply_dual_broadcast("add", rhs, |platform, left, right| {
+            platform.add(left, right)
+        })
     }
 
     fn div(
         self,
-        rhs: Array<T, R, P>,
+        rhs: Array<T, R, RP>,
     ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error> {
-        same_shape("div", self.shape(), rhs.shape())?;
-        self.apply_dual(rhs, |platform, left, right| platform.div(left, right))
+        self.apply_dual_broadcast("div", rhs, |platform, left, right| {
+            platform.div(left, right)
+        })
     }
 
     fn log(
         self,
-        base: Array<T, R, P>,
+        base: Array<T, R, RP>,
+    ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error> {
+        self.apply_dual_broadcast("log", base, |platform, left, right| {
+            platform.log(left, right)
+        })
+    }
+
+    fn logaddexp(
+        self,
+        rhs: Array<T, R, RP>,
     ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error> {
-        same_shape("log", self.shape(), base.shape())?;
-        self.apply_dual(base, |platform, left, right| platform.log(left, right))
+        self.apply_dual_broadcast("logaddexp", rhs, |platform, left, right| {
+            platform.logaddexp(left, right)
+        })
     }
 
     fn mul(
         self,
-        rhs: Array<T, R, P>,
+        rhs: Array<T, R, RP>,
     ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error> {
-        same_shape("mul", self.shape(), rhs.shape())?;
-        self.apply_dual(rhs, |platform, left, right| platform.mul(left, right))
+        self.apply_dual_broadcast("mul", rhs, |platform, left, right| {
+            platform.mul(left, right)
+        })
     }
 
     fn pow(
         self,
-        exp: Array<T, R, P>,
+        exp: Array<T, R, RP>,
     ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error> {
-        same_shape("pow", self.shape(), exp.shape())?;
-        self.apply_dual(exp, |platform, left, right| platform.pow(left, right))
+        self.apply_dual_broadcast("pow", exp, |platform, left, right| {
+            platform.pow(left, right)
+        })
     }
 
     fn sub(
         self,
-        rhs: Array<T, R, P>,
+        rhs: Array<T, R, RP>,
     ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error> {
-        same_shape("sub", self.shape(), rhs.shape())?;
-        self.apply_dual(rhs, |platform, left, right| platform.sub(left, right))
+        self.apply_dual_broadcast("sub", rhs, |platform, left, right| {
+            platform.sub(left, right)
+        })
     }
 
     fn rem(
         self,
-        rhs: Array<T, R, P>,
+        rhs: Array<T, R, RP>,
+    ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error> {
+        self.apply_dual_broadcast("rem", rhs, |platform, left, right| {
+            platform.rem(left, right)
+        })
+    }
+
+    fn step(
+        self,
+        threshold: Array<T, R, RP>,
     ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error> {
-        same_shape("rem", self.shape(), rhs.shape())?;
-        self.apply_dual(rhs, |platform, left, right| platform.rem(left, right))
+        self.apply_dual_broadcast("step", threshold, |platform, left, right| {
+            platform.step(left, right)
+        })
     }
 }
 
@@ -1254,49 +2834,403 @@ where
         self.apply(|platform, arg| platform.pow_scalar(arg, exp))
     }
 
-    fn rem_scalar(
+    fn rem_scalar(
+        self,
+        rhs: Self::DType,
+    ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error> {
+        self.apply(|platform, left| platform.rem_scalar(left, rhs))
+    }
+
+    fn sub_scalar(
+        self,
+        rhs: Self::DType,
+    ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error> {
+        self.apply(|platform, left| platform.sub_scalar(left, rhs))
+    }
+}
+
+/// Float-specific array methods
+pub trait NDArrayNumeric: NDArray + Sized
+where
+    Self::DType: Float,
+{
+    type Output: Access<u8>;
+
+    /// Test which elements of this array are infinite.
+    fn is_inf(self) -> Result<Array<u8, Self::Output, Self::Platform>, Error>;
+
+    /// Test which elements of this array are not-a-number.
+    fn is_nan(self) -> Result<Array<u8, Self::Output, Self::Platform>, Error>;
+}
+
+impl<T, A, P> NDArrayNumeric for Array<T, A, P>
+where
+    T: Float,
+    A: Access<T>,
+    P: ElementwiseNumeric<A, T>,
+{
+    type Output = AccessOp<P::Op, P>;
+
+    fn is_inf(self) -> Result<Array<u8, Self::Output, Self::Platform>, Error> {
+        self.apply(|platform, access| platform.is_inf(access))
+    }
+
+    fn is_nan(self) -> Result<Array<u8, Self::Output, Self::Platform>, Error> {
+        self.apply(|platform, access| platform.is_nan(access))
+    }
+}
+
+/// Array normalization operations
+pub trait NDArrayNormalize<S, B>: NDArray + fmt::Debug + Sized
+where
+    Self::DType: Float,
+    S: NDArray<DType = Self::DType>,
+    B: NDArray<DType = Self::DType>,
+{
+    type Output: Access<Self::DType>;
+
+    /// Construct a fused layer normalization over the trailing `axes` of this array.
+    /// The `scale` and `bias` arrays must have the shape of the normalized axes.
+    #[allow(clippy::type_complexity)]
+    fn layer_norm(
+        self,
+        axes: impl IntoAxes,
+        eps: Self::DType,
+        scale: S,
+        bias: B,
+    ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error>;
+}
+
+impl<T, A, S, B, P> NDArrayNormalize<Array<T, S, P>, Array<T, B, P>> for Array<T, A, P>
+where
+    T: Float,
+    A: Access<T>,
+    S: Access<T>,
+    B: Access<T>,
+    P: Normalize<A, S, B, T>,
+{
+    type Output = AccessOp<P::Op, P>;
+
+    fn layer_norm(
+        self,
+        axes: impl IntoAxes,
+        eps: T,
+        scale: Array<T, S, P>,
+        bias: Array<T, B, P>,
+    ) -> Result<Array<T, Self::Output, P>, Error> {
+        let mut axes = axes.into_axes();
+        axes.sort();
+        axes.dedup();
+
+        if axes.is_empty()
+            || axes.len() > self.ndim()
+            || axes
+                .iter()
+                .copied()
+                .ne((self.ndim() - axes.len())..self.ndim())
+        {
+            return Err(Error::Bounds(format!(
+                "layer normalization requires trailing axes of {self:?}, not {axes:?}"
+            )));
+        }
+
+        let norm_shape = &self.shape[axes[0]..];
+        same_shape("scale", norm_shape, scale.shape())?;
+        same_shape("bias", norm_shape, bias.shape())?;
+
+        let stride = norm_shape.iter().product();
+
+        let access =
+            self.platform
+                .layer_norm(self.access, scale.access, bias.access, stride, eps)?;
+
+        Ok(Array {
+            shape: self.shape,
+            access,
+            platform: self.platform,
+            dtype: PhantomData,
+            meta: self.meta,
+        })
+    }
+}
+
+/// Array convolution operations
+pub trait NDArrayConv<K>: NDArray + fmt::Debug + Sized
+where
+    K: NDArray<DType = Self::DType> + fmt::Debug,
+{
+    type Conv: Access<Self::DType>;
+    type ConvTranspose: Access<Self::DType>;
+    type MatchedFilter: Access<<Self::DType as CType>::Float>;
+
+    /// Construct a 1D convolution of this array, with shape `[batch, in_channels, len]`,
+    /// and the given `kernel`, with shape `[out_channels, in_channels / groups, kernel_len]`.
+    #[allow(clippy::type_complexity)]
+    fn conv1d(
+        self,
+        kernel: K,
+        spec: ConvSpec<1>,
+    ) -> Result<Array<Self::DType, Self::Conv, Self::Platform>, Error>;
+
+    /// Construct a 2D convolution of this array, with shape `[batch, in_channels, height, width]`
+    /// (i.e. NCHW), and the given `kernel`, with shape
+    /// `[out_channels, in_channels / groups, kernel_height, kernel_width]`.
+    #[allow(clippy::type_complexity)]
+    fn conv2d(
+        self,
+        kernel: K,
+        spec: ConvSpec<2>,
+    ) -> Result<Array<Self::DType, Self::Conv, Self::Platform>, Error>;
+
+    /// Construct a 3D convolution of this array, with shape
+    /// `[batch, in_channels, depth, height, width]` (i.e. NCDHW), and the given `kernel`,
+    /// with shape `[out_channels, in_channels / groups, kernel_depth, kernel_height, kernel_width]`.
+    #[allow(clippy::type_complexity)]
+    fn conv3d(
+        self,
+        kernel: K,
+        spec: ConvSpec<3>,
+    ) -> Result<Array<Self::DType, Self::Conv, Self::Platform>, Error>;
+
+    /// Construct a 2D transposed convolution of this array, with shape
+    /// `[batch, in_channels, height, width]`, and the given `kernel`, with shape
+    /// `[in_channels, out_channels / groups, kernel_height, kernel_width]`.
+    /// This is the gradient of a 2D convolution with the same `spec` with respect to its input.
+    #[allow(clippy::type_complexity)]
+    fn conv_transpose2d(
+        self,
+        kernel: K,
+        spec: ConvSpec<2>,
+    ) -> Result<Array<Self::DType, Self::ConvTranspose, Self::Platform>, Error>;
+
+    /// Construct a matched filter of the last axis of this array with the given 1D `template`,
+    /// i.e. the dot product of each window of `template.size()` consecutive elements with the
+    /// `template`, divided by the product of their norms (or zero if either norm is zero).
+    /// The last axis of the output has dimension `len - template.size() + 1`.
+    #[allow(clippy::type_complexity)]
+    fn matched_filter(
+        self,
+        template: K,
+    ) -> Result<Array<<Self::DType as CType>::Float, Self::MatchedFilter, Self::Platform>, Error>;
+}
+
+impl<T, I, K, P, KP> NDArrayConv<Array<T, K, KP>> for Array<T, I, P>
+where
+    T: CType,
+    I: Access<T>,
+    K: Access<T>,
+    P: Convolve<I, K, T>,
+    KP: PlatformInstance,
+{
+    type Conv = AccessOp<P::Conv, P>;
+    type ConvTranspose = AccessOp<P::ConvTranspose, P>;
+    type MatchedFilter = AccessOp<P::MatchedFilter, P>;
+
+    fn conv1d(
+        self,
+        kernel: Array<T, K, KP>,
+        spec: ConvSpec<1>,
+    ) -> Result<Array<T, Self::Conv, P>, Error> {
+        self.convolve(kernel, spec)
+    }
+
+    fn conv2d(
+        self,
+        kernel: Array<T, K, KP>,
+        spec: ConvSpec<2>,
+    ) -> Result<Array<T, Self::Conv, P>, Error> {
+        self.convolve(kernel, spec)
+    }
+
+    fn conv3d(
+        self,
+        kernel: Array<T, K, KP>,
+        spec: ConvSpec<3>,
+    ) -> Result<Array<T, Self::Conv, P>, Error> {
+        self.convolve(kernel, spec)
+    }
+
+    fn conv_transpose2d(
+        self,
+        kernel: Array<T, K, KP>,
+        spec: ConvSpec<2>,
+    ) -> Result<Array<T, Self::ConvTranspose, P>, Error> {
+        let (input_shape, kernel_shape) = match (self.shape(), kernel.shape()) {
+            ([n, c, h, w], [kc, ko, kh, kw]) => ([*n, *c, *h, *w], [*kc, *ko, *kh, *kw]),
+            _ => {
+                return Err(Error::Bounds(format!(
+                    "a 2D transposed convolution requires a 4D input and kernel, not {:?} and {:?}",
+                    self.shape, kernel.shape
+                ))
+                .with_context(format!("input: {}", self.breadcrumb()))
+                .with_context(format!("kernel: {}", kernel.breadcrumb())))
+            }
+        };
+
+        let shape = spec
+            .transpose_output_shape(&input_shape, &kernel_shape)
+            .map_err(|cause| {
+                cause
+                    .with_context(format!("input: {}", self.breadcrumb()))
+                    .with_context(format!("kernel: {}", kernel.breadcrumb()))
+            })?;
+
+        let platform = P::select(shape.iter().product());
+
+        let access = platform.conv_transpose2d(
+            self.access,
+            kernel.access,
+            input_shape,
+            kernel_shape,
+            spec,
+        )?;
+
+        // the channel and spatial axes are resized, so only the batch axis keeps its coordinates
+        Ok(Array {
+            shape,
+            access,
+            platform,
+            dtype: self.dtype,
+            meta: self.meta.drop_coords(&[1, 2, 3]),
+        })
+    }
+
+    fn matched_filter(
         self,
-        rhs: Self::DType,
-    ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error> {
-        self.apply(|platform, left| platform.rem_scalar(left, rhs))
-    }
+        template: Array<T, K, KP>,
+    ) -> Result<Array<T::Float, Self::MatchedFilter, P>, Error> {
+        let len = self.shape.last().copied().unwrap_or(0);
+        let width = template.size();
 
-    fn sub_scalar(
-        self,
-        rhs: Self::DType,
-    ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error> {
-        self.apply(|platform, left| platform.sub_scalar(left, rhs))
+        if template.ndim() != 1 || width == 0 || width > len {
+            return Err(Error::Bounds(format!(
+                "cannot match a template with shape {:?} against the last axis of {:?}",
+                template.shape, self.shape
+            ))
+            .with_context(format!("input: {}", self.breadcrumb()))
+            .with_context(format!("template: {}", template.breadcrumb())));
+        }
+
+        let rows = self.size() / len;
+
+        let mut shape = self.shape;
+        *shape.last_mut().expect("dim") = len - width + 1;
+
+        let platform = P::select(shape.iter().product());
+        let access = platform.matched_filter(self.access, template.access, [rows, len, width])?;
+
+        Ok(Array {
+            shape,
+            access,
+            platform,
+            dtype: PhantomData,
+            meta: self.meta.unlabeled(),
+        })
     }
 }
 
-/// Float-specific array methods
-pub trait NDArrayNumeric: NDArray + Sized
+impl<T, I, P> Array<T, I, P>
 where
-    Self::DType: Float,
+    T: CType,
+    I: Access<T>,
 {
-    type Output: Access<u8>;
+    /// Construct a convolution over the `N` trailing axes of this array,
+    /// computed as a 3D convolution with unit leading spatial axes.
+    #[allow(clippy::type_complexity)]
+    fn convolve<K, KP, const N: usize>(
+        self,
+        kernel: Array<T, K, KP>,
+        spec: ConvSpec<N>,
+    ) -> Result<Array<T, AccessOp<P::Conv, P>, P>, Error>
+    where
+        K: Access<T>,
+        P: Convolve<I, K, T>,
+        KP: PlatformInstance,
+    {
+        let shape = spec
+            .output_shape(&self.shape, &kernel.shape)
+            .map_err(|cause| {
+                cause
+                    .with_context(format!("input: {}", self.breadcrumb()))
+                    .with_context(format!("kernel: {}", kernel.breadcrumb()))
+            })?;
 
-    /// Test which elements of this array are infinite.
-    fn is_inf(self) -> Result<Array<u8, Self::Output, Self::Platform>, Error>;
+        let platform = P::select(shape.iter().product());
 
-    /// Test which elements of this array are not-a-number.
-    fn is_nan(self) -> Result<Array<u8, Self::Output, Self::Platform>, Error>;
+        let access = platform.conv(
+            self.access,
+            kernel.access,
+            volumetric_shape(&self.shape),
+            volumetric_shape(&kernel.shape),
+            spec.volumetric(),
+        )?;
+
+        // the channel and spatial axes are resized, so only the batch axis keeps its coordinates
+        let meta = self.meta.drop_coords(&(1..shape.len()).collect::<Vec<_>>());
+
+        Ok(Array {
+            shape,
+            access,
+            platform,
+            dtype: self.dtype,
+            meta,
+        })
+    }
 }
 
-impl<T, A, P> NDArrayNumeric for Array<T, A, P>
+/// Array upsampling operations
+pub trait NDArrayUpsample: NDArray + fmt::Debug + Sized {
+    type Output: Access<Self::DType>;
+
+    /// Construct an operation to upsample the last two axes of this array
+    /// by a factor of `scale` along each axis, interpolating new elements according to `mode`.
+    #[allow(clippy::type_complexity)]
+    fn upsample(
+        self,
+        scale: [usize; 2],
+        mode: Interpolation,
+    ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error>;
+}
+
+impl<T, A, P> NDArrayUpsample for Array<T, A, P>
 where
-    T: Float,
+    T: CType,
     A: Access<T>,
-    P: ElementwiseNumeric<A, T>,
+    P: Resample<A, T>,
 {
     type Output = AccessOp<P::Op, P>;
 
-    fn is_inf(self) -> Result<Array<u8, Self::Output, Self::Platform>, Error> {
-        self.apply(|platform, access| platform.is_inf(access))
-    }
+    fn upsample(
+        self,
+        scale: [usize; 2],
+        mode: Interpolation,
+    ) -> Result<Array<T, Self::Output, P>, Error> {
+        let (height, width) = match self.shape.as_slice() {
+            [.., height, width] if scale[0] > 0 && scale[1] > 0 => (*height, *width),
+            _ => {
+                return Err(Error::Bounds(format!(
+                    "cannot upsample {self:?} by a factor of {scale:?}"
+                )))
+            }
+        };
 
-    fn is_nan(self) -> Result<Array<u8, Self::Output, Self::Platform>, Error> {
-        self.apply(|platform, access| platform.is_nan(access))
+        let dims = [self.size() / (height * width).max(1), height, width];
+
+        let mut shape = self.shape.clone();
+        shape[self.ndim() - 2] *= scale[0];
+        shape[self.ndim() - 1] *= scale[1];
+
+        let meta = self.meta.drop_coords(&[self.ndim() - 2, self.ndim() - 1]);
+        let platform = P::select(shape.iter().product());
+        let access = platform.upsample(self.access, dims, scale, mode)?;
+
+        Ok(Array {
+            shape,
+            access,
+            platform,
+            dtype: self.dtype,
+            meta,
+        })
     }
 }
 
@@ -1325,6 +3259,8 @@ where
 }
 
 /// Array reduce operations
+///
+/// These follow the same conventions as [`NDArrayReduce`] for NaN and for an empty array.
 pub trait NDArrayReduceAll: NDArrayRead {
     /// Return the maximum of all elements in this array.
     fn max_all(self) -> Result<Self::DType, Error>;
@@ -1333,12 +3269,52 @@ pub trait NDArrayReduceAll: NDArrayRead {
     fn min_all(self) -> Result<Self::DType, Error>;
 
     /// Return the product of all elements in this array.
+    /// The product of an integer type wraps on overflow.
     fn product_all(self) -> Result<Self::DType, Error>;
 
     /// Return the sum of all elements in this array.
+    /// The sum of an integer type wraps on overflow;
+    /// use [`Array::sum_all_to`] to accumulate into a wider type.
     fn sum_all(self) -> Result<Self::DType, Error>;
 }
 
+/// An array of type `T` accessed via `A` on platform `P` cast to type `U`
+type Cast<T, U, A, P> = Array<U, AccessOp<<P as ElementwiseCast<A, T, U>>::Op, P>, P>;
+
+impl<T, A, P> Array<T, A, P>
+where
+    T: CType,
+    A: Access<T>,
+    P: PlatformInstance,
+{
+    /// Construct a sum-reduce operation over the given `axes` which accumulates into type `U`,
+    /// e.g. `mask.sum_to::<u64>(axes![0], false)` to count the non-zero elements of a `u8` mask
+    /// without overflowing.
+    #[allow(clippy::type_complexity)]
+    pub fn sum_to<U>(
+        self,
+        axes: impl IntoAxes,
+        keepdims: bool,
+    ) -> Result<Array<U, <Cast<T, U, A, P> as NDArrayReduce>::Output, P>, Error>
+    where
+        U: CType,
+        P: ElementwiseCast<A, T, U>,
+        Cast<T, U, A, P>: NDArrayReduce<DType = U, Platform = P>,
+    {
+        NDArrayCast::<U>::cast(self)?.sum(axes, keepdims)
+    }
+
+    /// Return the sum of all elements in this array, accumulated in type `U`.
+    pub fn sum_all_to<U>(self) -> Result<U, Error>
+    where
+        U: CType,
+        P: ElementwiseCast<A, T, U>,
+        Cast<T, U, A, P>: NDArrayReduceAll<DType = U>,
+    {
+        NDArrayCast::<U>::cast(self)?.sum_all()
+    }
+}
+
 impl<'a, T, A, P> NDArrayReduceAll for Array<T, A, P>
 where
     T: CType,
@@ -1346,11 +3322,23 @@ where
     P: ReduceAll<A, T>,
 {
     fn max_all(self) -> Result<Self::DType, Error> {
-        self.platform.max(self.access)
+        if self.size() == 0 {
+            Err(self.trace(Error::Bounds(
+                "cannot find the maximum of an empty array".to_string(),
+            )))
+        } else {
+            self.platform.max(self.access)
+        }
     }
 
     fn min_all(self) -> Result<Self::DType, Error> {
-        self.platform.min(self.access)
+        if self.size() == 0 {
+            Err(self.trace(Error::Bounds(
+                "cannot find the minimum of an empty array".to_string(),
+            )))
+        } else {
+            self.platform.min(self.access)
+        }
     }
 
     fn product_all(self) -> Result<Self::DType, Error> {
@@ -1364,11 +3352,15 @@ where
 
 impl<T, A, P> fmt::Debug for Array<T, A, P> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(name) = self.meta.name() {
+            write!(f, "{name:?}, ")?;
+        }
+
         write!(
             f,
-            "a {} array of shape {:?}",
+            "a {} array of shape {}",
             std::any::type_name::<T>(),
-            self.shape
+            self.meta.unnamed().describe(&self.shape)
         )
     }
 }
@@ -1421,6 +3413,18 @@ pub trait NDArrayTrig: NDArray + Sized {
     fn tanh(
         self,
     ) -> Result<Array<<Self::DType as CType>::Float, Self::Output, Self::Platform>, Error>;
+
+    /// Construct an operation to convert these data from radians to degrees.
+    #[allow(clippy::type_complexity)]
+    fn to_degrees(
+        self,
+    ) -> Result<Array<<Self::DType as CType>::Float, Self::Output, Self::Platform>, Error>;
+
+    /// Construct an operation to convert these data from degrees to radians.
+    #[allow(clippy::type_complexity)]
+    fn to_radians(
+        self,
+    ) -> Result<Array<<Self::DType as CType>::Float, Self::Output, Self::Platform>, Error>;
 }
 
 impl<T, A, P> NDArrayTrig for Array<T, A, P>
@@ -1466,6 +3470,87 @@ where
     fn tanh(self) -> Result<Array<T::Float, Self::Output, Self::Platform>, Error> {
         self.apply(|platform, access| platform.tanh(access))
     }
+
+    fn to_degrees(self) -> Result<Array<T::Float, Self::Output, Self::Platform>, Error> {
+        self.apply(|platform, access| platform.to_degrees(access))
+    }
+
+    fn to_radians(self) -> Result<Array<T::Float, Self::Output, Self::Platform>, Error> {
+        self.apply(|platform, access| platform.to_radians(access))
+    }
+}
+
+/// Array trigonometry methods with a second array argument
+pub trait NDArrayTrigDual<O: NDArray<DType = Self::DType>>: NDArray + Sized {
+    type Output: Access<<Self::DType as CType>::Float>;
+
+    /// Construct a four-quadrant arctangent operation of this array (y) and `x`.
+    #[allow(clippy::type_complexity)]
+    fn atan2(
+        self,
+        x: O,
+    ) -> Result<Array<<Self::DType as CType>::Float, Self::Output, Self::Platform>, Error>;
+
+    /// Construct an operation to compute `sqrt(self^2 + other^2)` without intermediate overflow.
+    #[allow(clippy::type_complexity)]
+    fn hypot(
+        self,
+        other: O,
+    ) -> Result<Array<<Self::DType as CType>::Float, Self::Output, Self::Platform>, Error>;
+}
+
+impl<T, L, R, P, RP> NDArrayTrigDual<Array<T, R, RP>> for Array<T, L, P>
+where
+    T: CType,
+    L: Access<T>,
+    R: Access<T>,
+    P: ElementwiseTrigDual<L, R, T>,
+    RP: PlatformInstance,
+{
+    type Output = AccessOp<P::Op, P>;
+
+    fn atan2(
+        self,
+        x: Array<T, R, RP>,
+    ) -> Result<Array<T::Float, Self::Output, Self::Platform>, Error> {
+        same_shape_of("atan2", &self, &x)?;
+        self.apply_dual(x, |platform, y, x| platform.atan2(y, x))
+    }
+
+    fn hypot(
+        self,
+        other: Array<T, R, RP>,
+    ) -> Result<Array<T::Float, Self::Output, Self::Platform>, Error> {
+        same_shape_of("hypot", &self, &other)?;
+        self.apply_dual(other, |platform, left, right| platform.hypot(left, right))
+    }
+}
+
+impl<A: Access<u8>, P: PlatformInstance> Array<u8, A, P> {
+    /// Convert this boolean mask to an array of ones where `true` and zeros where `false`.
+    #[allow(clippy::type_complexity)]
+    pub fn into_dtype<T>(self) -> Result<Array<T, AccessOp<P::Op, P>, P>, Error>
+    where
+        T: CType,
+        P: GatherCondScalar<A, T>,
+    {
+        self.select_scalar(T::ONE, T::ZERO)
+    }
+
+    /// Construct a boolean selection operation with constant values.
+    /// The resulting array will equal `then` where `self` is `true` and `or_else` where `false`.
+    #[allow(clippy::type_complexity)]
+    pub fn select_scalar<T>(
+        self,
+        then: T,
+        or_else: T,
+    ) -> Result<Array<T, AccessOp<P::Op, P>, P>, Error>
+    where
+        T: CType,
+        P: GatherCondScalar<A, T>,
+    {
+        self.apply(|platform, access| platform.cond_scalar(access, then, or_else))
+    }
 }
 
 /// Conditional selection (boolean logic) methods
@@ -1481,33 +3566,225 @@ where
     fn cond(self, then: L, or_else: R) -> Result<Array<T, Self::Output, Self::Platform>, Error>;
 }
 
-impl<T, A, L, R, P> NDArrayWhere<T, Array<T, L, P>, Array<T, R, P>> for Array<u8, A, P>
-where
-    T: CType,
-    A: Access<u8>,
-    L: Access<T>,
-    R: Access<T>,
-    P: GatherCond<A, L, R, T>,
-{
-    type Output = AccessOp<P::Op, P>;
+impl<T, A, L, R, P> NDArrayWhere<T, Array<T, L, P>, Array<T, R, P>> for Array<u8, A, P>
+where
+    T: CType,
+    A: Access<u8>,
+    L: Access<T>,
+    R: Access<T>,
+    P: GatherCond<A, L, R, T>,
+{
+    type Output = AccessOp<P::Op, P>;
+
+    fn cond(
+        self,
+        then: Array<T, L, P>,
+        or_else: Array<T, R, P>,
+    ) -> Result<Array<T, Self::Output, Self::Platform>, Error> {
+        same_shape_of("cond", &self, &then)?;
+        same_shape_of("cond", &self, &or_else)?;
+
+        let access = self
+            .platform
+            .cond(self.access, then.access, or_else.access)?;
+
+        Ok(Array {
+            shape: self.shape,
+            access,
+            platform: self.platform,
+            dtype: PhantomData,
+            meta: self.meta.unnamed(),
+        })
+    }
+}
+
+/// Array indexing operations which select elements using an index array
+pub trait NDArrayGather<I>: NDArray + fmt::Debug
+where
+    I: NDArray<DType = u64>,
+{
+    type Output: Access<Self::DType>;
+
+    /// Construct an operation to select the elements of this array at the given `indices`
+    /// along `axis`, like `numpy.take`. The output shape is this array's shape with `axis`
+    /// replaced by the shape of `indices`.
+    /// An index out of bounds is an error.
+    #[allow(clippy::type_complexity)]
+    fn gather(
+        self,
+        indices: I,
+        axis: usize,
+    ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error>;
+}
+
+impl<T, A, I, P> NDArrayGather<Array<u64, I, P>> for Array<T, A, P>
+where
+    T: CType,
+    A: Access<T>,
+    I: Access<u64>,
+    P: GatherAxis<A, I, T>,
+{
+    type Output = AccessOp<P::Op, P>;
+
+    fn gather(
+        self,
+        indices: Array<u64, I, P>,
+        axis: usize,
+    ) -> Result<Array<T, Self::Output, Self::Platform>, Error> {
+        let (dim, inner) = scan_dims(&self.shape, axis)?;
+        let outer = self.shape[..axis].iter().product();
+
+        let shape = self.shape[..axis]
+            .iter()
+            .chain(indices.shape())
+            .chain(&self.shape[(axis + 1)..])
+            .copied()
+            .collect();
+
+        let access = self
+            .platform
+            .gather(self.access, indices.access, [outer, dim, inner])?;
+
+        Ok(Array {
+            shape,
+            access,
+            platform: self.platform,
+            dtype: PhantomData,
+            meta: self.meta.unnamed(),
+        })
+    }
+}
+
+/// Elementwise operations on three arrays, fused into a single pass
+pub trait NDArrayTernary<B, C>: NDArray + fmt::Debug
+where
+    B: NDArray<DType = Self::DType>,
+    C: NDArray<DType = Self::DType>,
+{
+    type Output: Access<Self::DType>;
+
+    /// Construct an operation to clamp each element of this array
+    /// to the range given by the corresponding elements of `min` and `max`.
+    #[allow(clippy::type_complexity)]
+    fn clamp(
+        self,
+        min: B,
+        max: C,
+    ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error>;
+
+    /// Construct an operation to interpolate linearly from this array to `other`
+    /// by the fraction `t`, i.e. `self + (other - self) * t`.
+    #[allow(clippy::type_complexity)]
+    fn lerp(
+        self,
+        other: B,
+        t: C,
+    ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error>;
+
+    /// Construct a fused multiply-add operation `self * mul + add`,
+    /// without constructing the intermediate product.
+    #[allow(clippy::type_complexity)]
+    fn mul_add(
+        self,
+        mul: B,
+        add: C,
+    ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error>;
+
+    /// Construct an operation which is zero where this array is at most `edge0`,
+    /// one where it is at least `edge1`, and smoothly interpolated in between.
+    #[allow(clippy::type_complexity)]
+    fn smoothstep(
+        self,
+        edge0: B,
+        edge1: C,
+    ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error>;
+}
+
+impl<T, A, B, C, P> NDArrayTernary<Array<T, B, P>, Array<T, C, P>> for Array<T, A, P>
+where
+    T: CType,
+    A: Access<T>,
+    B: Access<T>,
+    C: Access<T>,
+    P: ElementwiseTernary<A, B, C, T>,
+{
+    type Output = AccessOp<P::Op, P>;
+
+    fn clamp(
+        self,
+        min: Array<T, B, P>,
+        max: Array<T, C, P>,
+    ) -> Result<Array<T, Self::Output, Self::Platform>, Error> {
+        same_shape_of("clamp", &self, &min)?;
+        same_shape_of("clamp", &self, &max)?;
+
+        let access = self.platform.clamp(self.access, min.access, max.access)?;
+
+        Ok(Array {
+            shape: self.shape,
+            access,
+            platform: self.platform,
+            dtype: PhantomData,
+            meta: self.meta.unnamed(),
+        })
+    }
+
+    fn lerp(
+        self,
+        other: Array<T, B, P>,
+        t: Array<T, C, P>,
+    ) -> Result<Array<T, Self::Output, Self::Platform>, Error> {
+        same_shape_of("lerp", &self, &other)?;
+        same_shape_of("lerp", &self, &t)?;
+
+        let access = self.platform.lerp(self.access, other.access, t.access)?;
+
+        Ok(Array {
+            shape: self.shape,
+            access,
+            platform: self.platform,
+            dtype: PhantomData,
+            meta: self.meta.unnamed(),
+        })
+    }
+
+    fn mul_add(
+        self,
+        mul: Array<T, B, P>,
+        add: Array<T, C, P>,
+    ) -> Result<Array<T, Self::Output, Self::Platform>, Error> {
+        same_shape_of("mul_add", &self, &mul)?;
+        same_shape_of("mul_add", &self, &add)?;
+
+        let access = self.platform.mul_add(self.access, mul.access, add.access)?;
 
-    fn cond(
+        Ok(Array {
+            shape: self.shape,
+            access,
+            platform: self.platform,
+            dtype: PhantomData,
+            meta: self.meta.unnamed(),
+        })
+    }
+
+    fn smoothstep(
         self,
-        then: Array<T, L, P>,
-        or_else: Array<T, R, P>,
+        edge0: Array<T, B, P>,
+        edge1: Array<T, C, P>,
     ) -> Result<Array<T, Self::Output, Self::Platform>, Error> {
-        same_shape("cond", self.shape(), then.shape())?;
-        same_shape("cond", self.shape(), or_else.shape())?;
+        same_shape_of("smoothstep", &self, &edge0)?;
+        same_shape_of("smoothstep", &self, &edge1)?;
 
         let access = self
             .platform
-            .cond(self.access, then.access, or_else.access)?;
+            .smoothstep(self.access, edge0.access, edge1.access)?;
 
         Ok(Array {
             shape: self.shape,
             access,
             platform: self.platform,
             dtype: PhantomData,
+            meta: self.meta.unnamed(),
         })
     }
 }
@@ -1521,26 +3798,59 @@ where
 
     /// Construct an operation to multiply this matrix or batch of matrices with the `other`.
     fn matmul(self, other: O) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error>;
+
+    /// Construct an operation to multiply a batch of `batch_size` matrices, where the `i`th
+    /// matrix of this array and the `other` begins at offset `i * strides[0]` and
+    /// `i * strides[1]`, respectively. The dimensions of each matrix are given by the last two
+    /// dimensions of each array, and the product has shape `[batch_size, rows, columns]`.
+    /// A stride of zero shares one matrix across the whole batch without copying it.
+    #[allow(clippy::type_complexity)]
+    fn matmul_strided(
+        self,
+        other: O,
+        batch_size: usize,
+        strides: [usize; 2],
+    ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error>;
+
+    /// Construct the outer product of this vector with shape `[m]` and the `other` with shape
+    /// `[n]`, with shape `[m, n]`, or of each pair of vectors in a batch with the same shape.
+    #[allow(clippy::type_complexity)]
+    fn outer(self, other: O) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error>;
+
+    /// Construct the dot product of this array and the `other`, depending on their dimensions:
+    ///  - two vectors with shape `[n]` have a dot product with shape `[1]`
+    ///  - a matrix with shape `[m, n]` and a vector with shape `[n]` have a product with shape `[m]`
+    ///  - a vector with shape `[m]` and a matrix with shape `[m, n]` have a product with shape `[n]`
+    ///  - otherwise, this is the same as [`MatrixDual::matmul`]
+    ///
+    /// An operand with one fewer dimension than the other is a batch of vectors, so a batch of
+    /// matrices with shape `[batch..., m, n]` times one with shape `[batch..., n]` has shape
+    /// `[batch..., m]`.
+    #[allow(clippy::type_complexity)]
+    fn dot(self, other: O) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error>;
 }
 
-impl<T, L, R, P> MatrixDual<Array<T, R, P>> for Array<T, L, P>
+impl<T, L, R, P, RP> MatrixDual<Array<T, R, RP>> for Array<T, L, P>
 where
     T: CType,
     L: Access<T>,
     R: Access<T>,
     P: LinAlgDual<L, R, T>,
+    RP: PlatformInstance,
 {
     type Output = AccessOp<P::Op, P>;
 
     fn matmul(
         self,
-        other: Array<T, R, P>,
+        other: Array<T, R, RP>,
     ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error> {
         let dims = matmul_dims(&self.shape, &other.shape).ok_or_else(|| {
             Error::Bounds(format!(
                 "invalid dimensions for matrix multiply: {:?} and {:?}",
                 self.shape, other.shape
             ))
+            .with_context(format!("left: {}", self.breadcrumb()))
+            .with_context(format!("right: {}", other.breadcrumb()))
         })?;
 
         let mut shape = Shape::with_capacity(self.ndim());
@@ -1549,6 +3859,7 @@ where
         shape.push(dims[3]);
 
         let platform = P::select(dims.iter().product());
+        let meta = self.meta.matmul(&other.meta, self.ndim());
 
         let access = platform.matmul(self.access, other.access, dims)?;
 
@@ -1557,6 +3868,191 @@ where
             access,
             platform,
             dtype: self.dtype,
+            meta,
+        })
+    }
+
+    fn matmul_strided(
+        self,
+        other: Array<T, R, RP>,
+        batch_size: usize,
+        strides: [usize; 2],
+    ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error> {
+        let matrix_dims = |shape: &[usize]| match shape {
+            [.., rows, cols] => Some([*rows, *cols]),
+            _ => None,
+        };
+
+        // each matrix in the batch must lie within its source array
+        let fits = |size: usize, matrix: usize, stride: usize| {
+            batch_size > 0 && ((batch_size - 1) * stride) + matrix <= size
+        };
+
+        let dims = match (matrix_dims(&self.shape), matrix_dims(&other.shape)) {
+            (Some([a, b]), Some([b_other, c]))
+                if b == b_other
+                    && fits(self.size(), a * b, strides[0])
+                    && fits(other.size(), b * c, strides[1]) =>
+            {
+                Some([batch_size, a, b, c])
+            }
+            _ => None,
+        };
+
+        let dims = dims.ok_or_else(|| {
+            Error::Bounds(format!(
+                "invalid dimensions for a strided matrix multiply of {batch_size} matrices \
+                with strides {strides:?}: {:?} and {:?}",
+                self.shape, other.shape
+            ))
+            .with_context(format!("left: {}", self.breadcrumb()))
+            .with_context(format!("right: {}", other.breadcrumb()))
+        })?;
+
+        let shape = shape![batch_size, dims[1], dims[3]];
+        let platform = P::select(dims.iter().product());
+        let access = platform.matmul_strided(self.access, other.access, dims, strides)?;
+
+        Ok(Array {
+            shape,
+            access,
+            platform,
+            dtype: self.dtype,
+            meta: ArrayMeta::default(),
+        })
+    }
+
+    fn outer(
+        mut self,
+        mut other: Array<T, R, RP>,
+    ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error> {
+        let ndim = self.ndim();
+
+        if ndim == 0 || other.ndim() != ndim || self.shape[..ndim - 1] != other.shape[..ndim - 1] {
+            return Err(Error::Bounds(format!(
+                "invalid dimensions for an outer product: {:?} and {:?}",
+                self.shape, other.shape
+            ))
+            .with_context(format!("left: {}", self.breadcrumb()))
+            .with_context(format!("right: {}", other.breadcrumb())));
+        }
+
+        // a column vector times a row vector is a matrix product with an inner dimension of 1
+        self.meta = self.meta.insert_axes(&[ndim], ndim);
+        self.shape.push(1);
+
+        other.meta = other.meta.insert_axes(&[ndim - 1], ndim);
+        other.shape.insert(ndim - 1, 1);
+
+        self.matmul(other)
+    }
+
+    fn dot(
+        mut self,
+        mut other: Array<T, R, RP>,
+    ) -> Result<Array<Self::DType, Self::Output, Self::Platform>, Error> {
+        let (left, right) = (self.ndim(), other.ndim());
+
+        if left == 0 || right == 0 {
+            return Err(Error::Bounds(format!(
+                "invalid dimensions for a dot product: {:?} and {:?}",
+                self.shape, other.shape
+            ))
+            .with_context(format!("left: {}", self.breadcrumb()))
+            .with_context(format!("right: {}", other.breadcrumb())));
+        }
+
+        let vectors = left == 1 && right == 1;
+
+        // a row vector times a matrix
+        let row = vectors || left + 1 == right;
+        if row {
+            self.meta = self.meta.insert_axes(&[left - 1], left);
+            self.shape.insert(left - 1, 1);
+        }
+
+        // a matrix times a column vector
+        let column = vectors || right + 1 == left;
+        if column {
+            other.meta = other.meta.insert_axes(&[right], right);
+            other.shape.push(1);
+        }
+
+        let mut product = self.matmul(other)?;
+        let ndim = product.ndim();
+
+        // the product of two vectors is a 1x1 matrix, which becomes a vector with one element
+        if column {
+            product.meta = product.meta.remove_axes(&[ndim - 1], ndim);
+            product.shape.pop();
+        } else if row {
+            product.meta = product.meta.remove_axes(&[ndim - 2], ndim);
+            product.shape.remove(ndim - 2);
+        }
+
+        Ok(product)
+    }
+}
+
+/// Linear system solvers
+pub trait MatrixSolve<R>: NDArray + fmt::Debug
+where
+    R: NDArray<DType = Self::DType> + fmt::Debug,
+{
+    type Solve: Access<Self::DType>;
+
+    /// Construct an operation to solve the tridiagonal system or batch of systems with these
+    /// `diagonals` for the given `rhs`. The diagonals have shape `[batch..., 3, n]`, holding the
+    /// sub-, main, and super-diagonal of each system (the first element of the sub-diagonal and
+    /// the last element of the super-diagonal are ignored), and the `rhs` has shape
+    /// `[batch..., n]`, which is also the shape of the solution.
+    /// The systems are solved without pivoting, so each should be diagonally dominant.
+    #[allow(clippy::type_complexity)]
+    fn solve_tridiagonal(
+        self,
+        rhs: R,
+    ) -> Result<Array<Self::DType, Self::Solve, Self::Platform>, Error>;
+}
+
+impl<T, D, R, P, RP> MatrixSolve<Array<T, R, RP>> for Array<T, D, P>
+where
+    T: Float,
+    D: Access<T>,
+    R: Access<T>,
+    P: LinAlgSolve<D, R, T>,
+    RP: PlatformInstance,
+{
+    type Solve = AccessOp<P::Op, P>;
+
+    fn solve_tridiagonal(self, rhs: Array<T, R, RP>) -> Result<Array<T, Self::Solve, P>, Error> {
+        let valid = match self.shape.split_last() {
+            Some((dim, [batch @ .., 3])) => {
+                rhs.shape.split_last() == Some((dim, batch)) && *dim > 0
+            }
+            _ => false,
+        };
+
+        if !valid {
+            return Err(Error::Bounds(format!(
+                "invalid dimensions for a tridiagonal solve: {:?} and {:?}",
+                self.shape, rhs.shape
+            ))
+            .with_context(format!("diagonals: {}", self.breadcrumb()))
+            .with_context(format!("rhs: {}", rhs.breadcrumb())));
+        }
+
+        let dim = rhs.shape.last().copied().expect("dim");
+        let batch_size = rhs.size() / dim;
+
+        let platform = P::select(self.size());
+        let access = platform.solve_tridiagonal(self.access, rhs.access, batch_size, dim)?;
+
+        Ok(Array {
+            shape: rhs.shape,
+            access,
+            platform,
+            dtype: PhantomData,
+            meta: rhs.meta,
         })
     }
 }
@@ -1564,10 +4060,32 @@ where
 /// Matrix unary operations
 pub trait MatrixUnary: NDArray + fmt::Debug {
     type Diag: Access<Self::DType>;
+    type DiagFrom: Access<Self::DType>;
+    type Inverse: Access<<Self::DType as CType>::Float>;
+    type Trace: Access<Self::DType>;
 
     /// Construct an operation to read the diagonal(s) of this matrix or batch of matrices.
     /// This will return an error if the last two dimensions of the batch are unequal.
     fn diag(self) -> Result<Array<Self::DType, Self::Diag, Self::Platform>, Error>;
+
+    /// Construct a diagonal matrix from this vector, or a batch of diagonal matrices from
+    /// a batch of vectors, i.e. the inverse of [`MatrixUnary::diag`].
+    #[allow(clippy::type_complexity)]
+    fn diag_from(self) -> Result<Array<Self::DType, Self::DiagFrom, Self::Platform>, Error>;
+
+    /// Construct the inverse of this square matrix, or of each matrix in this batch,
+    /// via LU decomposition with partial pivoting.
+    /// The inverse of a singular matrix is not finite.
+    #[allow(clippy::type_complexity)]
+    fn inv(
+        self,
+    ) -> Result<Array<<Self::DType as CType>::Float, Self::Inverse, Self::Platform>, Error>;
+
+    /// Construct the sum of the diagonal of this square matrix, or of each matrix in this batch,
+    /// without first materializing the diagonal(s).
+    /// The trace of a single matrix has shape `[1]`.
+    #[allow(clippy::type_complexity)]
+    fn trace(self) -> Result<Array<Self::DType, Self::Trace, Self::Platform>, Error>;
 }
 
 impl<T, A, P> MatrixUnary for Array<T, A, P>
@@ -1577,6 +4095,9 @@ where
     P: LinAlgUnary<A, T>,
 {
     type Diag = AccessOp<P::Op, P>;
+    type DiagFrom = AccessOp<P::DiagFrom, P>;
+    type Inverse = AccessOp<P::Inverse, P>;
+    type Trace = AccessOp<P::Trace, P>;
 
     fn diag(self) -> Result<Array<T, AccessOp<P::Op, P>, P>, Error> {
         if self.ndim() >= 2 && self.shape.last() == self.shape.iter().nth_back(1) {
@@ -1585,6 +4106,7 @@ where
 
             let shape = self.shape.iter().rev().skip(1).rev().copied().collect();
             let platform = P::select(batch_size * dim * dim);
+            let meta = self.meta.remove_axes(&[self.ndim() - 1], self.ndim());
             let access = platform.diag(self.access, batch_size, dim)?;
 
             Ok(Array {
@@ -1592,14 +4114,322 @@ where
                 access,
                 platform,
                 dtype: PhantomData,
+                meta,
             })
         } else {
-            Err(Error::Bounds(format!(
-                "invalid shape for diagonal: {:?}",
-                self.shape
-            )))
+            Err(Self::trace(
+                &self,
+                Error::Bounds(format!("invalid shape for diagonal: {:?}", self.shape)),
+            ))
+        }
+    }
+
+    fn diag_from(self) -> Result<Array<T, AccessOp<P::DiagFrom, P>, P>, Error> {
+        match self.shape.last().copied() {
+            Some(dim) if dim > 0 => {
+                let batch_size = self.size() / dim;
+
+                let mut shape = self.shape.clone();
+                shape.push(dim);
+
+                let platform = P::select(batch_size * dim * dim);
+                let meta = self.meta.insert_axes(&[self.ndim()], self.ndim());
+                let access = platform.diag_from(self.access, batch_size, dim)?;
+
+                Ok(Array {
+                    shape,
+                    access,
+                    platform,
+                    dtype: PhantomData,
+                    meta,
+                })
+            }
+            _ => Err(Self::trace(
+                &self,
+                Error::Bounds(format!(
+                    "invalid shape to construct a diagonal matrix: {:?}",
+                    self.shape
+                )),
+            )),
+        }
+    }
+
+    fn inv(self) -> Result<Array<T::Float, AccessOp<P::Inverse, P>, P>, Error> {
+        if self.ndim() >= 2 && self.shape.last() == self.shape.iter().nth_back(1) {
+            let batch_size = self.shape.iter().rev().skip(2).product();
+            let dim = self.shape.last().copied().expect("dim");
+
+            // the rows of an inverse correspond to the columns of the original, and vice versa
+            let ndim = self.ndim();
+            let mut permutation = (0..ndim).collect::<Axes>();
+            permutation.swap(ndim - 2, ndim - 1);
+            let meta = self.meta.transpose(&permutation).unnamed();
+
+            let platform = P::select(batch_size * dim * dim);
+            let access = platform.inv(self.access, batch_size, dim)?;
+
+            Ok(Array {
+                shape: self.shape,
+                access,
+                platform,
+                dtype: PhantomData,
+                meta,
+            })
+        } else {
+            Err(Self::trace(
+                &self,
+                Error::Bounds(format!(
+                    "cannot invert an array with shape {:?}",
+                    self.shape
+                )),
+            ))
         }
     }
+
+    fn trace(self) -> Result<Array<T, AccessOp<P::Trace, P>, P>, Error> {
+        if self.ndim() >= 2 && self.shape.last() == self.shape.iter().nth_back(1) {
+            let ndim = self.ndim();
+            let batch_size = self.shape.iter().rev().skip(2).product();
+            let dim = self.shape.last().copied().expect("dim");
+
+            let axes = [ndim - 2, ndim - 1];
+            let shape = reduce_axes(&self.shape, &axes, false)?;
+            let platform = P::select(batch_size * dim * dim);
+            let meta = reduce_meta(&self.meta, &axes, ndim, false);
+            let access = platform.trace(self.access, batch_size, dim)?;
+
+            Ok(Array {
+                shape,
+                access,
+                platform,
+                dtype: PhantomData,
+                meta,
+            })
+        } else {
+            Err(Self::trace(
+                &self,
+                Error::Bounds(format!("invalid shape for trace: {:?}", self.shape)),
+            ))
+        }
+    }
+}
+
+/// Matrix decompositions
+pub trait MatrixDecompose: NDArray + fmt::Debug {
+    type Decompose: Access<<Self::DType as CType>::Float>;
+
+    /// Decompose this square matrix, or each matrix in this batch, into `(P, L, U)` with partial
+    /// pivoting, such that `A = PLU` where `P` is a permutation matrix, `L` is unit lower
+    /// triangular, and `U` is upper triangular. A singular matrix has a zero on the diagonal of `U`.
+    #[allow(clippy::type_complexity)]
+    fn lu(
+        self,
+    ) -> Result<
+        (
+            Array<<Self::DType as CType>::Float, Self::Decompose, Self::Platform>,
+            Array<<Self::DType as CType>::Float, Self::Decompose, Self::Platform>,
+            Array<<Self::DType as CType>::Float, Self::Decompose, Self::Platform>,
+        ),
+        Error,
+    >
+    where
+        Self: Clone;
+
+    /// Decompose this matrix with shape `[m, n]`, or each matrix in this batch, into `(Q, R)`
+    /// using Householder reflections, such that `A = QR` where `Q` has shape `[m, k]`
+    /// with orthonormal columns and `R` is upper triangular with shape `[k, n]`, for `k = min(m, n)`.
+    #[allow(clippy::type_complexity)]
+    fn qr(
+        self,
+    ) -> Result<
+        (
+            Array<<Self::DType as CType>::Float, Self::Decompose, Self::Platform>,
+            Array<<Self::DType as CType>::Float, Self::Decompose, Self::Platform>,
+        ),
+        Error,
+    >
+    where
+        Self: Clone;
+
+    /// Construct the lower triangular Cholesky factor `L` of this symmetric positive definite
+    /// matrix, or of each matrix in this batch, such that `A = LL^T`.
+    /// Only the lower triangle is read. The factor of a matrix which is not positive definite
+    /// is not finite.
+    #[allow(clippy::type_complexity)]
+    fn cholesky(
+        self,
+    ) -> Result<Array<<Self::DType as CType>::Float, Self::Decompose, Self::Platform>, Error>;
+
+    /// Decompose this real symmetric (i.e. Hermitian) matrix, or each matrix in this batch,
+    /// into `(w, V)` using Jacobi rotations, such that `A = V diag(w) V^T` where the eigenvalues
+    /// `w` are in ascending order and the columns of `V` are the corresponding orthonormal
+    /// eigenvectors. Only the lower triangle is read.
+    #[allow(clippy::type_complexity)]
+    fn eigh(
+        self,
+    ) -> Result<
+        (
+            Array<<Self::DType as CType>::Float, Self::Decompose, Self::Platform>,
+            Array<<Self::DType as CType>::Float, Self::Decompose, Self::Platform>,
+        ),
+        Error,
+    >
+    where
+        Self: Clone;
+}
+
+impl<T, A, P> MatrixDecompose for Array<T, A, P>
+where
+    T: CType,
+    A: Access<T>,
+    P: LinAlgUnary<A, T>,
+{
+    type Decompose = AccessOp<P::Decompose, P>;
+
+    fn lu(
+        self,
+    ) -> Result<
+        (
+            Array<T::Float, Self::Decompose, P>,
+            Array<T::Float, Self::Decompose, P>,
+            Array<T::Float, Self::Decompose, P>,
+        ),
+        Error,
+    >
+    where
+        Self: Clone,
+    {
+        let dims = decompose_dims(&self, "LU", true)?;
+        let factorization = Factorization::new();
+
+        Ok((
+            decompose(self.clone(), Factor::LuP, dims, &factorization)?,
+            decompose(self.clone(), Factor::LuL, dims, &factorization)?,
+            decompose(self, Factor::LuU, dims, &factorization)?,
+        ))
+    }
+
+    fn qr(
+        self,
+    ) -> Result<
+        (
+            Array<T::Float, Self::Decompose, P>,
+            Array<T::Float, Self::Decompose, P>,
+        ),
+        Error,
+    >
+    where
+        Self: Clone,
+    {
+        let dims = decompose_dims(&self, "QR", false)?;
+        let factorization = Factorization::new();
+
+        Ok((
+            decompose(self.clone(), Factor::QrQ, dims, &factorization)?,
+            decompose(self, Factor::QrR, dims, &factorization)?,
+        ))
+    }
+
+    fn cholesky(self) -> Result<Array<T::Float, Self::Decompose, P>, Error> {
+        let dims = decompose_dims(&self, "Cholesky", true)?;
+        decompose(self, Factor::Cholesky, dims, &Factorization::new())
+    }
+
+    fn eigh(
+        self,
+    ) -> Result<
+        (
+            Array<T::Float, Self::Decompose, P>,
+            Array<T::Float, Self::Decompose, P>,
+        ),
+        Error,
+    >
+    where
+        Self: Clone,
+    {
+        let dims = decompose_dims(&self, "eigen", true)?;
+        let factorization = Factorization::new();
+
+        Ok((
+            decompose(self.clone(), Factor::EighValues, dims, &factorization)?,
+            decompose(self, Factor::EighVectors, dims, &factorization)?,
+        ))
+    }
+}
+
+fn decompose_dims<T, A, P>(
+    array: &Array<T, A, P>,
+    name: &str,
+    square: bool,
+) -> Result<[usize; 2], Error>
+where
+    T: CType,
+    A: Access<T>,
+{
+    match array.shape.as_slice() {
+        [.., rows, cols] if *rows > 0 && *cols > 0 && (rows == cols || !square) => {
+            Ok([*rows, *cols])
+        }
+        shape => Err(array.trace(Error::Bounds(format!(
+            "cannot compute the {name} decomposition of an array with shape {shape:?}"
+        )))),
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn decompose<T, A, P>(
+    array: Array<T, A, P>,
+    factor: Factor,
+    dims: [usize; 2],
+    factorization: &Factorization<T>,
+) -> Result<Array<T::Float, AccessOp<P::Decompose, P>, P>, Error>
+where
+    T: CType,
+    A: Access<T>,
+    P: LinAlgUnary<A, T>,
+{
+    let ndim = array.ndim();
+    let batch_size = array.size() / (dims[0] * dims[1]);
+    let [rows, cols] = factor.shape(dims);
+
+    // the eigenvalues of each matrix are a vector
+    let factor_ndim = if factor == Factor::EighValues { 1 } else { 2 };
+
+    let mut shape = array.shape[..(ndim - 2)].iter().copied().collect::<Shape>();
+    shape.extend([rows, cols][(2 - factor_ndim)..].iter().copied());
+
+    // the rows and columns of a factor do not correspond to those of the original matrix
+    let mut meta = array.meta.remove_axes(&[ndim - 2, ndim - 1], ndim);
+    for axis in (ndim - 2)..(ndim - 2 + factor_ndim) {
+        meta = meta.insert_axes(&[axis], axis);
+    }
+    let meta = meta.unnamed();
+
+    let platform = P::select(array.size());
+    let access = platform.decompose(
+        array.access,
+        factor,
+        batch_size,
+        dims,
+        factorization.clone(),
+    )?;
+
+    Ok(Array {
+        shape,
+        access,
+        platform,
+        dtype: PhantomData,
+        meta,
+    })
+}
+
+#[inline]
+fn reduce_meta(meta: &ArrayMeta, axes: &[usize], ndim: usize, keepdims: bool) -> ArrayMeta {
+    if keepdims {
+        meta.clone()
+    } else {
+        meta.remove_axes(axes, ndim)
+    }
 }
 
 #[inline]
@@ -1672,6 +4502,32 @@ where
     }
 }
 
+// the dimension of the given `axis` and the number of elements in each step along it
+#[inline]
+fn scan_dims(shape: &[usize], axis: usize) -> Result<(usize, usize), Error> {
+    if axis < shape.len() {
+        Ok((shape[axis], shape[axis + 1..].iter().product()))
+    } else {
+        Err(Error::Bounds(format!(
+            "axis {axis} is out of bounds for {shape:?}"
+        )))
+    }
+}
+
+/// Return an error if the lanes of the given `axes` of an array with the given `shape` are empty,
+/// i.e. if their `stride` is zero, since a reduction with no identity is undefined for them.
+#[inline]
+fn check_lanes(shape: &[usize], axes: &[usize], stride: usize) -> Result<(), Error> {
+    if stride == 0 {
+        Err(Error::Bounds(format!(
+            "cannot reduce the empty axes {axes:?} of an array with shape {shape:?} \
+            with a reduction which has no identity"
+        )))
+    } else {
+        Ok(())
+    }
+}
+
 #[inline]
 fn reduce_axes(shape: &[usize], axes: &[usize], keepdims: bool) -> Result<Shape, Error> {
     let mut shape = Shape::from_slice(shape);
@@ -1710,11 +4566,76 @@ fn same_shape(op_name: &'static str, left: &[usize], right: &[usize]) -> Result<
     }
 }
 
+/// Compute the inclusive prefix sum of the nonzero elements of a `mask`, on its own platform,
+/// and return it together with the number of nonzero elements.
+fn mask_positions(mask: ArrayAccess<u8>) -> Result<(ArrayAccess<u64>, usize), Error> {
+    let size = mask.size();
+
+    if size == 0 {
+        let positions = ArrayBuf::new(vec![], shape![0])?;
+        return Ok((ArrayAccess::from(positions), 0));
+    }
+
+    let flags: ArrayAccess<u64> = ArrayAccess::from(mask.ne_scalar(0)?.cast()?);
+    let positions = flags.reshape(shape![size])?.cumsum(0)?;
+    let positions = ArrayAccess::from(ArrayBuf::copy(&positions)?);
+    let count = positions.read_value(&[size - 1])? as usize;
+
+    Ok((positions, count))
+}
+
+/// Select the offsets of the `count` nonzero elements of a mask with the given `positions`.
+fn mask_indices(
+    positions: ArrayAccess<u64>,
+    size: usize,
+    count: usize,
+) -> Result<ArrayAccess<u64>, Error> {
+    let offsets = ArrayOp::range(0, size as u64, shape![size])?;
+    offsets.compact(positions, count).map(ArrayAccess::from)
+}
+
+fn same_shape_of<LT, L, LP, RT, R, RP>(
+    op_name: &'static str,
+    left: &Array<LT, L, LP>,
+    right: &Array<RT, R, RP>,
+) -> Result<(), Error>
+where
+    LT: CType,
+    L: Access<LT>,
+    RT: CType,
+    R: Access<RT>,
+{
+    same_shape(op_name, &left.shape, &right.shape).map_err(|cause| {
+        cause
+            .with_context(format!("left: {}", left.breadcrumb()))
+            .with_context(format!("right: {}", right.breadcrumb()))
+    })
+}
+
+// the range of a slice from `start` to `stop` along the given `axis`
+#[inline]
+fn axis_range(shape: &[usize], axis: usize, start: usize, stop: usize) -> Range {
+    let mut range = shape[..axis]
+        .iter()
+        .copied()
+        .map(|dim| AxisRange::In(0, dim, 1))
+        .collect::<Range>();
+
+    range.push(AxisRange::In(start, stop, 1));
+    range
+}
+
 #[inline]
-fn valid_coord(coord: &[usize], shape: &[usize]) -> Result<(), Error> {
+fn offset_of(coord: &[usize], shape: &[usize]) -> Result<usize, Error> {
     if coord.len() == shape.len() {
         if coord.iter().zip(shape).all(|(i, dim)| i < dim) {
-            return Ok(());
+            let offset = coord
+                .iter()
+                .zip(strides_for(shape, shape.len()))
+                .map(|(i, stride)| i * stride)
+                .sum();
+
+            return Ok(offset);
         }
     }
 
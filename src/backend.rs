@@ -0,0 +1,457 @@
+//! Externally-registered backends
+//!
+//! The [`crate::Platform`] enum only dispatches to the platforms built into this crate.
+//! To provide another backend (e.g. Vulkan) from a separate crate, implement [`Backend`]
+//! for each supported data type, [`register`] it on startup, and construct arrays on the
+//! [`External`] platform. Ops which no registered backend accepts are executed on the host.
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::{Arc, RwLock};
+
+use lazy_static::lazy_static;
+
+use crate::access::{Access, AccessBuf, AccessOp};
+use crate::buffer::BufferConverter;
+use crate::host::Host;
+use crate::introspect::OpPlan;
+use crate::ops::{
+    BroadcastDual, DualBroadcast, ElementwiseDual, ElementwiseUnary, Enqueue, Op, ReadValue,
+    ReduceAll,
+};
+use crate::platform::{Constant, Convert, PlatformInstance};
+use crate::{CType, Error, Float};
+
+pub type ArrayBuf<T> = crate::array::Array<T, AccessBuf<Vec<T>>, External>;
+pub type ArrayOp<T, O> = crate::array::Array<T, AccessOp<O, External>, External>;
+
+lazy_static! {
+    static ref BACKENDS: RwLock<HashMap<TypeId, Box<dyn Any + Send + Sync>>> =
+        RwLock::new(HashMap::new());
+}
+
+/// An elementwise operation on two arrays
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum DualOp {
+    Add,
+    Div,
+    Log,
+    LogAddExp,
+    Mul,
+    Pow,
+    Rem,
+    Step,
+    Sub,
+}
+
+impl DualOp {
+    /// Apply this operation to a single pair of elements.
+    pub fn apply<T: CType>(self, left: T, right: T) -> T {
+        match self {
+            Self::Add => T::add(left, right),
+            Self::Div => T::div(left, right),
+            Self::Log => T::from_float(left.to_float().log(right.to_float())),
+            Self::LogAddExp => T::from_float(left.to_float().logaddexp(right.to_float())),
+            Self::Mul => T::mul(left, right),
+            Self::Pow => T::pow(left, right),
+            Self::Rem => T::rem(left, right),
+            Self::Step if left < right => T::ZERO,
+            Self::Step => T::ONE,
+            Self::Sub => T::sub(left, right),
+        }
+    }
+}
+
+/// An elementwise operation on a single array
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum UnaryOp {
+    Abs,
+    Exp,
+    Ln,
+    ReciprocalApprox,
+    Round,
+    Softplus,
+}
+
+impl UnaryOp {
+    /// Apply this operation to a single element.
+    pub fn apply<T: CType>(self, n: T) -> T {
+        match self {
+            Self::Abs => n.abs(),
+            Self::Exp => T::from_float(n.to_float().exp()),
+            Self::Ln => T::from_float(n.to_float().ln()),
+            Self::ReciprocalApprox => T::div(T::ONE, n),
+            Self::Round => n.round(),
+            Self::Softplus => T::from_float(n.to_float().softplus()),
+        }
+    }
+}
+
+/// A reduction of all the elements of an array to a single value
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ReduceOp {
+    Max,
+    Min,
+    Product,
+    Sum,
+}
+
+/// A third-party implementation of array ops on data of type `T`
+pub trait Backend<T: CType>: Send + Sync {
+    /// The name of this backend, for use in error messages.
+    fn name(&self) -> &str;
+
+    /// Return `true` if this backend should execute ops whose output has `size` elements.
+    fn accepts(&self, size: usize) -> bool {
+        let _ = size;
+        true
+    }
+
+    /// Apply `op` to each pair of elements of `left` and `right`, which have the same length.
+    fn dual(&self, op: DualOp, left: &[T], right: &[T]) -> Result<Vec<T>, Error>;
+
+    /// Apply `op` to each element of `input`.
+    fn unary(&self, op: UnaryOp, input: &[T]) -> Result<Vec<T>, Error>;
+
+    /// Reduce all the elements of `input` to a single value.
+    fn reduce(&self, op: ReduceOp, input: &[T]) -> Result<T, Error>;
+}
+
+type Registry<T> = Vec<Arc<dyn Backend<T>>>;
+
+/// Register a [`Backend`] for data of type `T`.
+/// Backends registered later take priority over those registered earlier.
+pub fn register<T: CType>(backend: Arc<dyn Backend<T>>) {
+    let mut backends = BACKENDS.write().expect("backend registry");
+
+    let registry = backends
+        .entry(TypeId::of::<T>())
+        .or_insert_with(|| Box::new(Registry::<T>::new()));
+
+    registry
+        .downcast_mut::<Registry<T>>()
+        .expect("backend registry")
+        .push(backend);
+}
+
+/// Return the names of the backends registered for data of type `T`, in order of priority.
+pub fn registered<T: CType>() -> Vec<String> {
+    let backends = BACKENDS.read().expect("backend registry");
+
+    backends
+        .get(&TypeId::of::<T>())
+        .and_then(|registry| registry.downcast_ref::<Registry<T>>())
+        .map(|registry| {
+            registry
+                .iter()
+                .rev()
+                .map(|backend| backend.name().to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn select<T: CType>(size: usize) -> Option<Arc<dyn Backend<T>>> {
+    let backends = BACKENDS.read().expect("backend registry");
+
+    backends
+        .get(&TypeId::of::<T>())
+        .and_then(|registry| registry.downcast_ref::<Registry<T>>())
+        .and_then(|registry| {
+            registry
+                .iter()
+                .rev()
+                .find(|backend| backend.accepts(size))
+                .cloned()
+        })
+}
+
+fn check_len<T: CType>(
+    backend: &dyn Backend<T>,
+    expected: usize,
+    output: Vec<T>,
+) -> Result<Vec<T>, Error> {
+    if output.len() == expected {
+        Ok(output)
+    } else {
+        Err(Error::Interface(format!(
+            "backend {} returned {} elements but {expected} were expected",
+            backend.name(),
+            output.len()
+        )))
+    }
+}
+
+/// The platform which dispatches ops to the registered [`Backend`]s
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct External;
+
+impl PlatformInstance for External {
+    fn select(_size_hint: usize) -> Self {
+        Self
+    }
+}
+
+impl<T: CType> Constant<T> for External {
+    type Buffer = Vec<T>;
+
+    fn constant(&self, value: T, size: usize) -> Result<Self::Buffer, Error> {
+        Ok(vec![value; size])
+    }
+}
+
+impl<T: CType> Convert<T> for External {
+    type Buffer = Vec<T>;
+
+    fn convert(&self, buffer: BufferConverter<T>) -> Result<Self::Buffer, Error> {
+        buffer.to_slice().map(|buf| buf.into_vec())
+    }
+}
+
+impl<L, R, T> ElementwiseDual<L, R, T> for External
+where
+    L: Access<T>,
+    R: Access<T>,
+    T: CType,
+{
+    type Op = Dual<L, R, T>;
+
+    fn add(self, left: L, right: R) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ok(Dual::new(left, right, DualOp::Add).into())
+    }
+
+    fn div(self, left: L, right: R) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ok(Dual::new(left, right, DualOp::Div).into())
+    }
+
+    fn log(self, arg: L, base: R) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ok(Dual::new(arg, base, DualOp::Log).into())
+    }
+
+    fn logaddexp(self, left: L, right: R) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ok(Dual::new(left, right, DualOp::LogAddExp).into())
+    }
+
+    fn mul(self, left: L, right: R) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ok(Dual::new(left, right, DualOp::Mul).into())
+    }
+
+    fn pow(self, arg: L, exp: R) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ok(Dual::new(arg, exp, DualOp::Pow).into())
+    }
+
+    fn rem(self, left: L, right: R) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ok(Dual::new(left, right, DualOp::Rem).into())
+    }
+
+    fn step(self, arg: L, threshold: R) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ok(Dual::new(arg, threshold, DualOp::Step).into())
+    }
+
+    fn sub(self, left: L, right: R) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ok(Dual::new(left, right, DualOp::Sub).into())
+    }
+}
+
+impl<A: Access<T>, T: CType> ElementwiseUnary<A, T> for External {
+    type Op = Unary<A, T>;
+
+    fn abs(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ok(Unary::new(access, UnaryOp::Abs).into())
+    }
+
+    fn exp(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ok(Unary::new(access, UnaryOp::Exp).into())
+    }
+
+    fn ln(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ok(Unary::new(access, UnaryOp::Ln).into())
+    }
+
+    fn reciprocal_approx(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ok(Unary::new(access, UnaryOp::ReciprocalApprox).into())
+    }
+
+    fn round(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ok(Unary::new(access, UnaryOp::Round).into())
+    }
+
+    fn softplus(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ok(Unary::new(access, UnaryOp::Softplus).into())
+    }
+}
+
+impl<A: Access<T>, T: CType> ReduceAll<A, T> for External {
+    fn all(self, access: A) -> Result<bool, Error> {
+        Host::select(access.size()).all(access)
+    }
+
+    fn any(self, access: A) -> Result<bool, Error> {
+        Host::select(access.size()).any(access)
+    }
+
+    fn max(self, access: A) -> Result<T, Error> {
+        reduce(access, ReduceOp::Max, |host, access| host.max(access))
+    }
+
+    fn min(self, access: A) -> Result<T, Error> {
+        reduce(access, ReduceOp::Min, |host, access| host.min(access))
+    }
+
+    fn product(self, access: A) -> Result<T, Error> {
+        reduce(access, ReduceOp::Product, |host, access| {
+            host.product(access)
+        })
+    }
+
+    fn sum(self, access: A) -> Result<T, Error> {
+        reduce(access, ReduceOp::Sum, |host, access| host.sum(access))
+    }
+}
+
+fn reduce<A, T, F>(access: A, op: ReduceOp, fallback: F) -> Result<T, Error>
+where
+    A: Access<T>,
+    T: CType,
+    F: FnOnce(Host, A) -> Result<T, Error>,
+{
+    let size = access.size();
+
+    if let Some(backend) = select::<T>(size) {
+        let input = access.read()?.to_slice()?;
+        backend.reduce(op, &input)
+    } else {
+        fallback(Host::select(size), access)
+    }
+}
+
+/// An elementwise dual op executed by a registered [`Backend`]
+pub struct Dual<L, R, T> {
+    left: L,
+    right: R,
+    op: DualOp,
+    broadcast: DualBroadcast,
+    dtype: PhantomData<T>,
+}
+
+impl<L, R, T> Dual<L, R, T> {
+    fn new(left: L, right: R, op: DualOp) -> Self {
+        Self {
+            left,
+            right,
+            op,
+            broadcast: DualBroadcast::default(),
+            dtype: PhantomData,
+        }
+    }
+}
+
+impl<L, R, T> BroadcastDual for Dual<L, R, T> {
+    fn broadcast(self, broadcast: DualBroadcast) -> Result<Self, Error> {
+        Ok(Self { broadcast, ..self })
+    }
+}
+
+impl<L: Access<T>, R: Access<T>, T: CType> Op for Dual<L, R, T> {
+    fn size(&self) -> usize {
+        self.broadcast.size(self.left.size())
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.left.plan(), self.right.plan()]
+    }
+}
+
+impl<L: Access<T>, R: Access<T>, T: CType> Enqueue<External, T> for Dual<L, R, T> {
+    type Buffer = Vec<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let left = self.left.read()?.to_slice()?;
+        let right = self.right.read()?.to_slice()?;
+
+        // a backend zips inputs of equal length, so materialize any broadcast first
+        let (left, right) = if self.broadcast.is_none() {
+            (left, right)
+        } else {
+            let offsets = (0..self.size())
+                .map(|offset| self.broadcast.offsets(offset))
+                .collect::<Vec<_>>();
+
+            let left = offsets.iter().map(|(l, _)| left[*l]).collect::<Vec<_>>();
+            let right = offsets.iter().map(|(_, r)| right[*r]).collect::<Vec<_>>();
+            (left.into(), right.into())
+        };
+
+        if let Some(backend) = select::<T>(left.len()) {
+            let output = backend.dual(self.op, &left, &right)?;
+            check_len(&*backend, left.len(), output)
+        } else {
+            let op = self.op;
+
+            Ok(left
+                .iter()
+                .zip(right.iter())
+                .map(|(l, r)| op.apply(*l, *r))
+                .collect())
+        }
+    }
+}
+
+impl<L: Access<T>, R: Access<T>, T: CType> ReadValue<External, T> for Dual<L, R, T> {
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        let (left, right) = self.broadcast.offsets(offset);
+        let left = self.left.read_value(left)?;
+        let right = self.right.read_value(right)?;
+        Ok(self.op.apply(left, right))
+    }
+}
+
+/// An elementwise unary op executed by a registered [`Backend`]
+pub struct Unary<A, T> {
+    access: A,
+    op: UnaryOp,
+    dtype: PhantomData<T>,
+}
+
+impl<A, T> Unary<A, T> {
+    fn new(access: A, op: UnaryOp) -> Self {
+        Self {
+            access,
+            op,
+            dtype: PhantomData,
+        }
+    }
+}
+
+impl<A: Access<T>, T: CType> Op for Unary<A, T> {
+    fn size(&self) -> usize {
+        self.access.size()
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan()]
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<External, T> for Unary<A, T> {
+    type Buffer = Vec<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let input = self.access.read()?.to_slice()?;
+
+        if let Some(backend) = select::<T>(input.len()) {
+            let output = backend.unary(self.op, &input)?;
+            check_len(&*backend, input.len(), output)
+        } else {
+            let op = self.op;
+            Ok(input.iter().copied().map(|n| op.apply(n)).collect())
+        }
+    }
+}
+
+impl<A: Access<T>, T: CType> ReadValue<External, T> for Unary<A, T> {
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        self.access.read_value(offset).map(|n| self.op.apply(n))
+    }
+}
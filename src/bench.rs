@@ -0,0 +1,170 @@
+//! Standardized micro-benchmarks, for measuring regressions and tuning platform thresholds
+//!
+//! Run them with `cargo bench --features=bench`, or call them from your own [`Criterion`] harness.
+//! Each benchmark is run on the host, on the automatically-selected [`Platform`],
+//! and (if enabled) on OpenCL, and each measurement includes reading the result into host memory.
+
+use criterion::measurement::WallTime;
+use criterion::{black_box, BatchSize, BenchmarkGroup, BenchmarkId, Criterion, Throughput};
+
+use crate::access::AccessBuf;
+use crate::array::Array;
+use crate::host::Host;
+#[cfg(feature = "opencl")]
+use crate::opencl::OpenCL;
+use crate::{
+    shape, Error, MatrixDual, NDArrayMath, NDArrayRead, NDArrayReduceAll, NDArrayUnary, Platform,
+    Shape,
+};
+
+/// The number of elements in each input of the elementwise, reduce, and transfer benchmarks
+pub const SIZES: [usize; 5] = [64, 1_024, 16_384, 262_144, 4_194_304];
+
+/// The dimension of each square input matrix of the matrix multiplication benchmarks
+pub const MATRIX_DIMS: [usize; 4] = [8, 32, 128, 512];
+
+type HostArray = Array<f32, AccessBuf<Vec<f32>>, Host>;
+type AutoArray = Array<f32, AccessBuf<Vec<f32>>, Platform>;
+#[cfg(feature = "opencl")]
+type CLArray = Array<f32, AccessBuf<ocl::Buffer<f32>>, OpenCL>;
+
+macro_rules! bench_platforms {
+    ($group:expr, $param:expr, $shape:expr, $op:expr) => {{
+        let shape: Shape = $shape;
+        let data = data(shape.iter().product());
+
+        let array = HostArray::new(data.clone(), shape.clone()).expect("host array");
+        bench_array(&mut $group, "host", $param, array, $op);
+
+        let array = AutoArray::new(data.clone(), shape.clone()).expect("array");
+        bench_array(&mut $group, "auto", $param, array, $op);
+
+        #[cfg(feature = "opencl")]
+        {
+            let buffer = OpenCL::copy_into_buffer(&data).expect("OpenCL buffer");
+            let array = CLArray::new(buffer, shape).expect("OpenCL array");
+            bench_array(&mut $group, "opencl", $param, array, $op);
+        }
+    }};
+}
+
+/// Benchmark elementwise addition and exponentiation.
+pub fn elementwise(c: &mut Criterion) {
+    let mut group = c.benchmark_group("add");
+
+    for size in SIZES {
+        group.throughput(Throughput::Elements(size as u64));
+        bench_platforms!(group, size, shape![size], |(left, right)| {
+            left.add(right).and_then(read)
+        });
+    }
+
+    group.finish();
+
+    let mut group = c.benchmark_group("exp");
+
+    for size in SIZES {
+        group.throughput(Throughput::Elements(size as u64));
+        bench_platforms!(group, size, shape![size], |(array, _)| {
+            array.exp().and_then(read)
+        });
+    }
+
+    group.finish();
+}
+
+/// Benchmark reducing all elements of an array.
+pub fn reduce(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sum_all");
+
+    for size in SIZES {
+        group.throughput(Throughput::Elements(size as u64));
+        bench_platforms!(group, size, shape![size], |(array, _)| array.sum_all());
+    }
+
+    group.finish();
+}
+
+/// Benchmark multiplying square matrices.
+pub fn matmul(c: &mut Criterion) {
+    let mut group = c.benchmark_group("matmul");
+
+    for dim in MATRIX_DIMS {
+        group.throughput(Throughput::Elements((dim * dim * dim) as u64));
+        bench_platforms!(group, dim, shape![dim, dim], |(left, right)| {
+            left.matmul(right).and_then(read)
+        });
+    }
+
+    group.finish();
+}
+
+/// Benchmark copying data between host and device memory.
+#[cfg(feature = "opencl")]
+pub fn transfer(c: &mut Criterion) {
+    let mut group = c.benchmark_group("transfer");
+
+    for size in SIZES {
+        let data = data(size);
+        group.throughput(Throughput::Bytes(
+            (size * std::mem::size_of::<f32>()) as u64,
+        ));
+
+        group.bench_with_input(BenchmarkId::new("upload", size), &data, |b, data| {
+            b.iter(|| black_box(OpenCL::copy_into_buffer(data).expect("upload")))
+        });
+
+        let buffer = OpenCL::copy_into_buffer(&data).expect("OpenCL buffer");
+        let array = CLArray::new(buffer, shape![size]).expect("OpenCL array");
+
+        group.bench_with_input(BenchmarkId::new("download", size), &array, |b, array| {
+            b.iter(|| black_box(read(array.clone()).expect("download")))
+        });
+    }
+
+    group.finish();
+}
+
+/// Benchmark copying data between host and device memory.
+#[cfg(not(feature = "opencl"))]
+pub fn transfer(_c: &mut Criterion) {
+    // there is no device memory to transfer to or from
+}
+
+/// Run every benchmark in this module.
+pub fn all(c: &mut Criterion) {
+    elementwise(c);
+    reduce(c);
+    matmul(c);
+    transfer(c);
+}
+
+fn bench_array<A, O, F>(
+    group: &mut BenchmarkGroup<WallTime>,
+    platform: &str,
+    param: usize,
+    array: A,
+    op: F,
+) where
+    A: Clone,
+    F: Fn((A, A)) -> Result<O, Error>,
+{
+    group.bench_with_input(BenchmarkId::new(platform, param), &array, |b, array| {
+        b.iter_batched(
+            || (array.clone(), array.clone()),
+            |inputs| black_box(op(inputs).expect("benchmark op")),
+            BatchSize::LargeInput,
+        )
+    });
+}
+
+fn data(size: usize) -> Vec<f32> {
+    (0..size).map(|n| (n % 256) as f32 / 256.).collect()
+}
+
+fn read<A: NDArrayRead<DType = f32>>(array: A) -> Result<usize, Error> {
+    array
+        .buffer()
+        .and_then(|buffer| buffer.to_slice())
+        .map(|slice| slice.len())
+}
@@ -4,9 +4,9 @@ use std::fmt;
 use destream::{de, en};
 use get_size::GetSize;
 
-#[cfg(feature = "opencl")]
-use crate::opencl;
 use crate::{host, CType, Error};
+#[cfg(feature = "opencl")]
+use crate::{introspect, opencl};
 
 /// A data buffer
 pub trait BufferInstance<T: CType>: Send + Sync {
@@ -30,6 +30,11 @@ pub trait BufferMut<T: CType>: BufferInstance<T> + fmt::Debug {
         )))
     }
 
+    /// Borrow this buffer as a mutable slice, or return an error if this is not a host buffer.
+    fn host(&mut self) -> Result<&mut [T], Error> {
+        Err(Error::Unsupported(format!("not a host buffer: {self:?}")))
+    }
+
     /// Overwrite this buffer.
     fn write<'a>(&mut self, data: BufferConverter<'a, T>) -> Result<(), Error>;
 
@@ -93,6 +98,14 @@ impl<T: CType> BufferMut<T> for Buffer<T> {
         }
     }
 
+    fn host(&mut self) -> Result<&mut [T], Error> {
+        match self {
+            #[cfg(feature = "opencl")]
+            Self::CL(buf) => buf.host(),
+            Self::Host(buf) => buf.host(),
+        }
+    }
+
     fn write<'a>(&mut self, data: BufferConverter<'a, T>) -> Result<(), Error> {
         match self {
             #[cfg(feature = "opencl")]
@@ -152,6 +165,10 @@ impl<'a, T: CType> BufferMut<T> for &'a mut Buffer<T> {
         Buffer::<T>::cl(&mut **self)
     }
 
+    fn host(&mut self) -> Result<&mut [T], Error> {
+        Buffer::<T>::host(&mut **self)
+    }
+
     fn write<'b>(&mut self, data: BufferConverter<'b, T>) -> Result<(), Error> {
         Buffer::<T>::write(*self, data)
     }
@@ -202,6 +219,10 @@ impl<FE: Send + Sync, T: CType> BufferMut<T> for freqfs::FileWriteGuardOwned<FE,
         BufferMut::cl(&mut **self)
     }
 
+    fn host(&mut self) -> Result<&mut [T], Error> {
+        BufferMut::host(&mut **self)
+    }
+
     fn write<'a>(&mut self, data: BufferConverter<'a, T>) -> Result<(), Error> {
         BufferMut::write(&mut **self, data)
     }
@@ -273,24 +294,72 @@ impl<'a, T: CType> BufferConverter<'a, T> {
         match self {
             Self::CL(buffer) => Ok(buffer),
             Self::Host(buffer) => {
+                introspect::record_upload::<T>(buffer.len());
                 opencl::OpenCL::copy_into_buffer(buffer.as_ref()).map(opencl::CLConverter::Owned)
             }
         }
     }
 
+    /// Return the offset and value of the first NaN or infinite element of this buffer, if any.
+    pub(crate) fn find_non_finite(&self) -> Result<Option<(usize, T)>, Error> {
+        let find = |slice: &[T]| {
+            slice
+                .iter()
+                .copied()
+                .enumerate()
+                .find(|(_, n)| !n.to_f64().is_finite())
+        };
+
+        match self {
+            #[cfg(feature = "opencl")]
+            Self::CL(buffer) => {
+                let copy = Self::CL(buffer.clone()).to_slice()?;
+                Ok(find(copy.as_ref()))
+            }
+            Self::Host(buffer) => Ok(find(buffer.as_ref())),
+        }
+    }
+
     /// Ensure that this buffer is in host memory by making a copy if necessary.
     #[inline]
     pub fn to_slice(self) -> Result<host::SliceConverter<'a, T>, Error> {
         match self {
             #[cfg(feature = "opencl")]
             Self::CL(buffer) => {
+                introspect::record_download::<T>(buffer.len());
                 let mut copy = vec![T::default(); buffer.len()];
+                crate::opencl::events::wait()?;
                 buffer.read(&mut copy[..]).enq()?;
                 Ok(host::SliceConverter::from(copy))
             }
             Self::Host(buffer) => Ok(buffer),
         }
     }
+
+    /// Copy the contents of this buffer into the given `output` slice, which must have
+    /// the same length, without allocating an intermediate buffer.
+    pub fn read_into(self, output: &mut [T]) -> Result<(), Error> {
+        if output.len() != self.len() {
+            return Err(Error::Bounds(format!(
+                "cannot read a buffer of length {} into a slice of length {}",
+                self.len(),
+                output.len()
+            )));
+        }
+
+        match self {
+            #[cfg(feature = "opencl")]
+            Self::CL(buffer) => {
+                introspect::record_download::<T>(buffer.len());
+                crate::opencl::events::wait()?;
+                buffer.read(output).enq().map_err(Error::from)
+            }
+            Self::Host(buffer) => {
+                output.copy_from_slice(buffer.as_ref());
+                Ok(())
+            }
+        }
+    }
 }
 
 impl<T: CType> From<Buffer<T>> for BufferConverter<'static, T> {
@@ -0,0 +1,161 @@
+//! Complex numbers
+//!
+//! There is no complex [`CType`], so a complex array is represented as a real array whose last
+//! axis has dimension 2 and holds the (real, imaginary) parts of each element in that order,
+//! i.e. the interleaved layout read and written by most FFT libraries.
+
+use crate::access::Accessor;
+use crate::array::Array;
+use crate::{
+    axes, shape, ArrayAccess, ArrayBuf, AxisRange, CType, Error, Float, NDArray, NDArrayMath,
+    NDArrayTransform, NDArrayTrig, NDArrayTrigDual, NDArrayWhere, Platform, Range, Shape,
+};
+
+/// Pack the `real` and `imag` parts of a complex array, which must have the same shape,
+/// into a complex array with an additional trailing axis of dimension 2.
+pub fn complex<T, RA, RP, IA, IP>(
+    real: Array<T, RA, RP>,
+    imag: Array<T, IA, IP>,
+) -> Result<ArrayAccess<T>, Error>
+where
+    T: CType,
+    Accessor<T>: From<RA> + From<IA>,
+    Platform: From<RP> + From<IP>,
+{
+    let real = ArrayAccess::from(real);
+    let imag = ArrayAccess::from(imag);
+
+    if real.shape() != imag.shape() {
+        return Err(Error::Bounds(format!(
+            "the real part of a complex array has shape {:?} but the imaginary part has shape {:?}",
+            real.shape(),
+            imag.shape()
+        )));
+    }
+
+    let ndim = real.ndim();
+    let shape = real.shape().iter().copied().chain([2]).collect::<Shape>();
+
+    let part = |array: ArrayAccess<T>| -> Result<ArrayAccess<T>, Error> {
+        let array = array.unsqueeze(axes![ndim])?.broadcast(shape.clone())?;
+        Ok(ArrayAccess::from(array))
+    };
+
+    let real = part(real)?;
+    let imag = part(imag)?;
+
+    let mask = ArrayBuf::new(vec![1u8, 0], shape![2])?.broadcast(shape)?;
+    let packed = ArrayAccess::from(mask).cond(real, imag)?;
+
+    Ok(ArrayAccess::from(packed))
+}
+
+/// Select the real part of the complex array `z`.
+pub fn real<T, A, P>(z: Array<T, A, P>) -> Result<ArrayAccess<T>, Error>
+where
+    T: CType,
+    Accessor<T>: From<A>,
+    Platform: From<P>,
+{
+    part(ArrayAccess::from(z), 0)
+}
+
+/// Select the imaginary part of the complex array `z`.
+pub fn imag<T, A, P>(z: Array<T, A, P>) -> Result<ArrayAccess<T>, Error>
+where
+    T: CType,
+    Accessor<T>: From<A>,
+    Platform: From<P>,
+{
+    part(ArrayAccess::from(z), 1)
+}
+
+/// Construct an operation to compute the magnitude of each element of the complex array `z`.
+pub fn abs<T, A, P>(z: Array<T, A, P>) -> Result<ArrayAccess<T::Float>, Error>
+where
+    T: CType,
+    Accessor<T>: From<A>,
+    Platform: From<P>,
+{
+    magnitude(ArrayAccess::from(z))
+}
+
+/// Construct an operation to compute the phase angle, in radians in the range `[-pi, pi]`,
+/// of each element of the complex array `z`.
+pub fn arg<T, A, P>(z: Array<T, A, P>) -> Result<ArrayAccess<T::Float>, Error>
+where
+    T: CType,
+    Accessor<T>: From<A>,
+    Platform: From<P>,
+{
+    phase(ArrayAccess::from(z))
+}
+
+/// Construct operations to compute the polar form `(abs(z), arg(z))` of the complex array `z`.
+#[allow(clippy::type_complexity)]
+pub fn polar<T, A, P>(
+    z: Array<T, A, P>,
+) -> Result<(ArrayAccess<T::Float>, ArrayAccess<T::Float>), Error>
+where
+    T: CType,
+    Accessor<T>: From<A>,
+    Platform: From<P>,
+{
+    let z = ArrayAccess::from(z);
+    Ok((magnitude(z.clone())?, phase(z)?))
+}
+
+/// Construct a complex array from the magnitude `r` and phase angle `theta`, in radians,
+/// of each of its elements, which must have the same shape.
+pub fn from_polar<T, RA, RP, TA, TP>(
+    r: Array<T, RA, RP>,
+    theta: Array<T, TA, TP>,
+) -> Result<ArrayAccess<T>, Error>
+where
+    T: Float,
+    Accessor<T>: From<RA> + From<TA>,
+    Platform: From<RP> + From<TP>,
+{
+    let r = ArrayAccess::from(r);
+    let theta = ArrayAccess::from(theta);
+
+    if r.shape() != theta.shape() {
+        return Err(Error::Bounds(format!(
+            "cannot construct a complex array from magnitudes with shape {:?} and angles with shape {:?}",
+            r.shape(),
+            theta.shape()
+        )));
+    }
+
+    let re = r.clone().mul(ArrayAccess::from(theta.clone().cos()?))?;
+    let im = r.mul(ArrayAccess::from(theta.sin()?))?;
+
+    complex(re, im)
+}
+
+/// Select the real (`i = 0`) or imaginary (`i = 1`) part of the complex array `z`.
+fn part<T: CType>(z: ArrayAccess<T>, i: usize) -> Result<ArrayAccess<T>, Error> {
+    if z.shape().last() != Some(&2) {
+        return Err(Error::Bounds(format!(
+            "a complex array must have a trailing axis of dimension 2, not shape {:?}",
+            z.shape()
+        )));
+    }
+
+    let range = (0..z.ndim() - 1)
+        .map(|_| AxisRange::Span(None, None, 1))
+        .chain([AxisRange::At(i)])
+        .collect::<Range>();
+
+    z.slice(range).map(ArrayAccess::from)
+}
+
+fn magnitude<T: CType>(z: ArrayAccess<T>) -> Result<ArrayAccess<T::Float>, Error> {
+    let (re, im) = (part(z.clone(), 0)?, part(z, 1)?);
+    re.hypot(im).map(ArrayAccess::from)
+}
+
+fn phase<T: CType>(z: ArrayAccess<T>) -> Result<ArrayAccess<T::Float>, Error> {
+    let (re, im) = (part(z.clone(), 0)?, part(z, 1)?);
+    im.atan2(re).map(ArrayAccess::from)
+}
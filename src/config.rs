@@ -0,0 +1,398 @@
+//! Runtime configuration
+//!
+//! Defaults are read once from these environment variables, and can be overridden programmatically:
+//!  - `DENSOR_PLATFORM`: `host` or `opencl` to force all ops onto one platform
+//!  - `DENSOR_DEVICE`: the index of the OpenCL device to use for all ops
+//!  - `DENSOR_GPU_MIN_SIZE`: the minimum number of elements to process on a GPU
+//!  - `DENSOR_KERNEL_CACHE`: a directory in which to cache compiled OpenCL kernels
+//!    and tuned kernel launch parameters
+//!  - `DENSOR_AUTOTUNE`: `true` or `false` to enable or disable tuning kernel launch parameters
+//!  - `DENSOR_OOM_POLICY`: `fail`, `retry`, or `host` to choose how to recover
+//!    when an OpenCL op fails to allocate device memory
+//!  - `DENSOR_DEBUG_CHECKS`: `1` to check the size of the output of every op,
+//!    or `2` to also scan every floating-point output for NaN and infinite values
+//!  - `DENSOR_REDUCE_ORDER`: `fast` or `pairwise` to choose the order in which
+//!    the elements of an array are accumulated when it is summed or multiplied to a single value
+//!  - `DENSOR_TRACK_MEMORY`: `true` or `false` to enable or disable tracking live OpenCL buffers
+//!  - `DENSOR_OUT_OF_ORDER`: `true` or `false` to enable or disable out-of-order OpenCL queues
+//!  - `DENSOR_FAST_MATH`: `true` or `false` to trade last-ulp accuracy for throughput
+//!
+//! Unrecognized values are ignored.
+
+use std::env;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicU8, AtomicUsize, Ordering};
+use std::sync::RwLock;
+
+use lazy_static::lazy_static;
+
+use crate::Error;
+
+const NONE: usize = usize::MAX;
+
+/// The platform on which to execute array ops
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum PlatformPreference {
+    /// Select a platform automatically based on data size
+    #[default]
+    Auto,
+    /// Execute all ops on the host
+    Host,
+    /// Execute all ops using OpenCL, if enabled
+    OpenCL,
+}
+
+impl PlatformPreference {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Self::Host,
+            2 => Self::OpenCL,
+            _ => Self::Auto,
+        }
+    }
+
+    fn to_u8(self) -> u8 {
+        match self {
+            Self::Auto => 0,
+            Self::Host => 1,
+            Self::OpenCL => 2,
+        }
+    }
+}
+
+impl FromStr for PlatformPreference {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "host" => Ok(Self::Host),
+            "opencl" | "cl" => Ok(Self::OpenCL),
+            other => Err(Error::Unsupported(format!("unknown platform: {other}"))),
+        }
+    }
+}
+
+/// How to recover when an OpenCL op fails to allocate device memory
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum OomPolicy {
+    /// Return the allocation error
+    Fail,
+    /// Retry the op on the same device a few times, waiting longer each time,
+    /// in case the memory pressure is transient (e.g. due to another process)
+    Retry,
+    /// Compute the op on the host instead, one element at a time,
+    /// which is much slower but lets a long-running job continue
+    #[default]
+    Host,
+}
+
+impl OomPolicy {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Self::Fail,
+            1 => Self::Retry,
+            _ => Self::Host,
+        }
+    }
+
+    fn to_u8(self) -> u8 {
+        match self {
+            Self::Fail => 0,
+            Self::Retry => 1,
+            Self::Host => 2,
+        }
+    }
+}
+
+impl FromStr for OomPolicy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "fail" => Ok(Self::Fail),
+            "retry" => Ok(Self::Retry),
+            "host" => Ok(Self::Host),
+            other => Err(Error::Unsupported(format!("unknown OOM policy: {other}"))),
+        }
+    }
+}
+
+/// The checks to run on the output of every op, to help track down the source of a bug
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, PartialOrd, Ord)]
+pub enum DebugChecks {
+    /// Only check the size of each output in a debug build
+    #[default]
+    Off,
+    /// Check the size of each output
+    Size,
+    /// Check the size of each output and return an error naming the op
+    /// if a floating-point output contains a NaN or infinite value
+    NonFinite,
+}
+
+impl DebugChecks {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Self::Size,
+            2 => Self::NonFinite,
+            _ => Self::Off,
+        }
+    }
+
+    fn to_u8(self) -> u8 {
+        match self {
+            Self::Off => 0,
+            Self::Size => 1,
+            Self::NonFinite => 2,
+        }
+    }
+}
+
+impl FromStr for DebugChecks {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "0" | "off" | "false" => Ok(Self::Off),
+            "1" | "size" | "true" => Ok(Self::Size),
+            "2" | "nan" | "non-finite" => Ok(Self::NonFinite),
+            other => Err(Error::Unsupported(format!("unknown debug checks: {other}"))),
+        }
+    }
+}
+
+/// The order in which to accumulate the elements of an array when reducing it to a single value
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum ReduceOrder {
+    /// Whichever order is fastest on each platform, which may vary between platforms and runs
+    #[default]
+    Fast,
+    /// A fixed-width pairwise tree, the same on the host as in the OpenCL reduce kernel,
+    /// so that a floating-point sum or product is the same on every platform
+    Pairwise,
+}
+
+impl ReduceOrder {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Self::Pairwise,
+            _ => Self::Fast,
+        }
+    }
+
+    fn to_u8(self) -> u8 {
+        match self {
+            Self::Fast => 0,
+            Self::Pairwise => 1,
+        }
+    }
+}
+
+impl FromStr for ReduceOrder {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "fast" => Ok(Self::Fast),
+            "pairwise" | "tree" => Ok(Self::Pairwise),
+            other => Err(Error::Unsupported(format!("unknown reduce order: {other}"))),
+        }
+    }
+}
+
+struct Config {
+    platform: AtomicU8,
+    device: AtomicUsize,
+    gpu_min_size: AtomicUsize,
+    kernel_cache: RwLock<Option<PathBuf>>,
+    autotune: AtomicBool,
+    oom_policy: AtomicU8,
+    debug_checks: AtomicU8,
+    reduce_order: AtomicU8,
+    track_memory: AtomicBool,
+    out_of_order_queues: AtomicBool,
+    fast_math: AtomicBool,
+}
+
+lazy_static! {
+    static ref CONFIG: Config = {
+        let platform = env_var::<PlatformPreference>("DENSOR_PLATFORM").unwrap_or_default();
+        let device = env_var::<usize>("DENSOR_DEVICE").unwrap_or(NONE);
+        let gpu_min_size =
+            env_var::<usize>("DENSOR_GPU_MIN_SIZE").unwrap_or_else(default_gpu_min_size);
+        let kernel_cache = env_var::<PathBuf>("DENSOR_KERNEL_CACHE");
+        let autotune = env_var::<bool>("DENSOR_AUTOTUNE").unwrap_or(true);
+        let oom_policy = env_var::<OomPolicy>("DENSOR_OOM_POLICY").unwrap_or_default();
+        let debug_checks = env_var::<DebugChecks>("DENSOR_DEBUG_CHECKS").unwrap_or_default();
+        let reduce_order = env_var::<ReduceOrder>("DENSOR_REDUCE_ORDER").unwrap_or_default();
+        let track_memory = env_var::<bool>("DENSOR_TRACK_MEMORY").unwrap_or(false);
+        let out_of_order_queues = env_var::<bool>("DENSOR_OUT_OF_ORDER").unwrap_or(false);
+        let fast_math = env_var::<bool>("DENSOR_FAST_MATH").unwrap_or(false);
+
+        Config {
+            platform: AtomicU8::new(platform.to_u8()),
+            device: AtomicUsize::new(device),
+            gpu_min_size: AtomicUsize::new(gpu_min_size),
+            kernel_cache: RwLock::new(kernel_cache),
+            autotune: AtomicBool::new(autotune),
+            oom_policy: AtomicU8::new(oom_policy.to_u8()),
+            debug_checks: AtomicU8::new(debug_checks.to_u8()),
+            reduce_order: AtomicU8::new(reduce_order.to_u8()),
+            track_memory: AtomicBool::new(track_memory),
+            out_of_order_queues: AtomicBool::new(out_of_order_queues),
+            fast_math: AtomicBool::new(fast_math),
+        }
+    };
+}
+
+/// Return the platform on which to execute array ops.
+pub fn platform() -> PlatformPreference {
+    PlatformPreference::from_u8(CONFIG.platform.load(Ordering::Relaxed))
+}
+
+/// Set the platform on which to execute array ops.
+pub fn set_platform(platform: PlatformPreference) {
+    CONFIG.platform.store(platform.to_u8(), Ordering::Relaxed)
+}
+
+/// Return the index of the OpenCL device to use for all ops, if any.
+pub fn device() -> Option<usize> {
+    match CONFIG.device.load(Ordering::Relaxed) {
+        NONE => None,
+        index => Some(index),
+    }
+}
+
+/// Set the index of the OpenCL device to use for all ops, or `None` to select automatically.
+pub fn set_device(index: Option<usize>) {
+    CONFIG
+        .device
+        .store(index.unwrap_or(NONE), Ordering::Relaxed)
+}
+
+/// Return the minimum number of elements to process on a GPU.
+pub fn gpu_min_size() -> usize {
+    CONFIG.gpu_min_size.load(Ordering::Relaxed)
+}
+
+/// Set the minimum number of elements to process on a GPU.
+pub fn set_gpu_min_size(size: usize) {
+    CONFIG.gpu_min_size.store(size, Ordering::Relaxed)
+}
+
+/// Return the directory in which compiled OpenCL kernels are cached, if any.
+pub fn kernel_cache() -> Option<PathBuf> {
+    CONFIG
+        .kernel_cache
+        .read()
+        .expect("kernel cache directory")
+        .clone()
+}
+
+/// Set the directory in which to cache compiled OpenCL kernels, or `None` to disable caching.
+/// Kernels which have already been compiled by this process will not be cached retroactively.
+pub fn set_kernel_cache(path: Option<PathBuf>) {
+    *CONFIG.kernel_cache.write().expect("kernel cache directory") = path;
+}
+
+/// Return `true` if kernel launch parameters are tuned on first use on each OpenCL device.
+pub fn autotune() -> bool {
+    CONFIG.autotune.load(Ordering::Relaxed)
+}
+
+/// Enable or disable tuning kernel launch parameters on first use on each OpenCL device.
+/// If disabled, launch parameters which have not already been tuned will use default values.
+pub fn set_autotune(autotune: bool) {
+    CONFIG.autotune.store(autotune, Ordering::Relaxed)
+}
+
+/// Return how to recover when an OpenCL op fails to allocate device memory.
+pub fn oom_policy() -> OomPolicy {
+    OomPolicy::from_u8(CONFIG.oom_policy.load(Ordering::Relaxed))
+}
+
+/// Set how to recover when an OpenCL op fails to allocate device memory.
+pub fn set_oom_policy(policy: OomPolicy) {
+    CONFIG.oom_policy.store(policy.to_u8(), Ordering::Relaxed)
+}
+
+/// Return the checks to run on the output of every op.
+pub fn debug_checks() -> DebugChecks {
+    DebugChecks::from_u8(CONFIG.debug_checks.load(Ordering::Relaxed))
+}
+
+/// Set the checks to run on the output of every op.
+/// Scanning for non-finite values reads every floating-point output, including from a GPU,
+/// so it is much slower than normal execution.
+pub fn set_debug_checks(checks: DebugChecks) {
+    CONFIG.debug_checks.store(checks.to_u8(), Ordering::Relaxed)
+}
+
+/// Return the order in which to accumulate the elements of an array when reducing it to a single value.
+pub fn reduce_order() -> ReduceOrder {
+    ReduceOrder::from_u8(CONFIG.reduce_order.load(Ordering::Relaxed))
+}
+
+/// Set the order in which to accumulate the elements of an array when reducing it to a single value.
+/// A pairwise sum of finite values computed on the host is bit-for-bit identical to one computed
+/// with OpenCL (unless the kernels are compiled with fast math), at some cost in speed on the host.
+pub fn set_reduce_order(order: ReduceOrder) {
+    CONFIG.reduce_order.store(order.to_u8(), Ordering::Relaxed)
+}
+
+/// Return `true` if live OpenCL buffers are tracked for `opencl::memory_report`.
+pub fn track_memory() -> bool {
+    CONFIG.track_memory.load(Ordering::Relaxed)
+}
+
+/// Enable or disable tracking live OpenCL buffers for `opencl::memory_report`.
+/// Only buffers allocated while tracking is enabled are reported.
+pub fn set_track_memory(track: bool) {
+    CONFIG.track_memory.store(track, Ordering::Relaxed)
+}
+
+/// Return `true` if OpenCL ops are enqueued on out-of-order queues, where a device supports them.
+pub fn out_of_order_queues() -> bool {
+    CONFIG.out_of_order_queues.load(Ordering::Relaxed)
+}
+
+/// Enable or disable enqueueing OpenCL ops on out-of-order queues, where a device supports them,
+/// so that the kernels of independent ops in an op graph can overlap.
+/// Each kernel then waits only for the events of its own inputs, at some cost in overhead.
+pub fn set_out_of_order_queues(out_of_order: bool) {
+    CONFIG
+        .out_of_order_queues
+        .store(out_of_order, Ordering::Relaxed)
+}
+
+/// Return `true` if newly-constructed ops trade last-ulp accuracy for throughput.
+pub fn fast_math() -> bool {
+    CONFIG.fast_math.load(Ordering::Relaxed)
+}
+
+/// Enable or disable fast math for ops constructed from now on. A fast OpenCL kernel uses the
+/// approximate `native_divide`, `native_exp`, and `native_log` builtins at single precision,
+/// and a fast host matrix product or `mul_add` uses fused multiply-add instructions.
+/// Each op reads this setting when it's constructed, so it can be enabled for a single op
+/// by constructing that op between two calls to this function.
+pub fn set_fast_math(fast_math: bool) {
+    CONFIG.fast_math.store(fast_math, Ordering::Relaxed)
+}
+
+#[cfg(feature = "opencl")]
+fn default_gpu_min_size() -> usize {
+    crate::opencl::GPU_MIN_SIZE
+}
+
+#[cfg(not(feature = "opencl"))]
+fn default_gpu_min_size() -> usize {
+    usize::MAX
+}
+
+fn env_var<T: FromStr>(name: &str) -> Option<T> {
+    env::var(name)
+        .ok()
+        .and_then(|value| value.trim().parse().ok())
+}
@@ -0,0 +1,445 @@
+//! Convolution specifications
+
+use crate::{CType, Error, Shape};
+
+/// The interpolation mode used to upsample an array
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum Interpolation {
+    /// Repeat the nearest source element
+    #[default]
+    Nearest,
+    /// Interpolate linearly between the nearest source elements along each axis
+    Bilinear,
+}
+
+/// The stride, zero padding, dilation, and channel grouping of a convolution
+/// over `N` spatial dimensions.
+///
+/// The input of a convolution has shape `[batch, in_channels, ...spatial]` and its kernel has
+/// shape `[out_channels, in_channels / groups, ...window]`. The channels are split into `groups`
+/// groups, and each output channel only reads the input channels of its own group,
+/// so a depthwise convolution is a grouped convolution with one group per input channel.
+///
+/// A transposed convolution uses the same specification as the convolution whose gradient
+/// it computes, so its `padding` is removed from (rather than added to) its output
+/// and its kernel has shape `[in_channels, out_channels / groups, ...window]`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ConvSpec<const N: usize> {
+    stride: [usize; N],
+    padding: [usize; N],
+    output_padding: [usize; N],
+    dilation: [usize; N],
+    groups: usize,
+}
+
+impl<const N: usize> Default for ConvSpec<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> ConvSpec<N> {
+    /// Construct a new [`ConvSpec`] with unit stride and dilation, no padding, and one group.
+    pub fn new() -> Self {
+        Self {
+            stride: [1; N],
+            padding: [0; N],
+            output_padding: [0; N],
+            dilation: [1; N],
+            groups: 1,
+        }
+    }
+
+    /// Construct a new [`ConvSpec`] for a depthwise convolution over `channels` channels.
+    pub fn depthwise(channels: usize) -> Self {
+        Self::new().with_groups(channels)
+    }
+
+    /// Set the stride of the convolution along each spatial axis.
+    pub fn with_stride(mut self, stride: [usize; N]) -> Self {
+        self.stride = stride;
+        self
+    }
+
+    /// Set the zero padding added to both sides of each spatial axis.
+    pub fn with_padding(mut self, padding: [usize; N]) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Set the padding added to one side of each spatial axis of the output of a transposed
+    /// convolution, to select among the output shapes which a strided convolution maps
+    /// to the same input shape. This must be less than the stride or the dilation.
+    pub fn with_output_padding(mut self, output_padding: [usize; N]) -> Self {
+        self.output_padding = output_padding;
+        self
+    }
+
+    /// Set the spacing between the elements of the kernel window along each spatial axis.
+    pub fn with_dilation(mut self, dilation: [usize; N]) -> Self {
+        self.dilation = dilation;
+        self
+    }
+
+    /// Set the number of groups into which the input and output channels are split.
+    pub fn with_groups(mut self, groups: usize) -> Self {
+        self.groups = groups;
+        self
+    }
+
+    /// Borrow the stride of the convolution along each spatial axis.
+    pub fn stride(&self) -> &[usize; N] {
+        &self.stride
+    }
+
+    /// Borrow the zero padding added to both sides of each spatial axis.
+    pub fn padding(&self) -> &[usize; N] {
+        &self.padding
+    }
+
+    /// Borrow the padding added to one side of each spatial axis of a transposed convolution.
+    pub fn output_padding(&self) -> &[usize; N] {
+        &self.output_padding
+    }
+
+    /// Borrow the spacing between the elements of the kernel window along each spatial axis.
+    pub fn dilation(&self) -> &[usize; N] {
+        &self.dilation
+    }
+
+    /// Return the number of groups into which the input and output channels are split.
+    pub fn groups(&self) -> usize {
+        self.groups
+    }
+
+    /// Compute the output shape of a convolution of an `input` with the given `kernel` shape,
+    /// or return an error if the shapes are not compatible with this specification.
+    pub fn output_shape(&self, input: &[usize], kernel: &[usize]) -> Result<Shape, Error> {
+        self.validate(input, kernel)?;
+
+        let (batch, in_channels) = (input[0], input[1]);
+        let (out_channels, group_channels) = (kernel[0], kernel[1]);
+
+        if self.groups == 0
+            || in_channels % self.groups != 0
+            || out_channels % self.groups != 0
+            || in_channels / self.groups != group_channels
+        {
+            return Err(Error::Bounds(format!(
+                "cannot split {in_channels} input channels and {out_channels} output channels into {} groups for a kernel with shape {kernel:?}",
+                self.groups
+            )));
+        }
+
+        let mut shape = Shape::with_capacity(N + 2);
+        shape.push(batch);
+        shape.push(out_channels);
+
+        for x in 0..N {
+            let padded = input[x + 2] + (2 * self.padding[x]);
+            let window = (self.dilation[x] * kernel[x + 2].saturating_sub(1)) + 1;
+
+            if kernel[x + 2] == 0 || window > padded {
+                return Err(Error::Bounds(format!(
+                    "a convolution window of {window} does not fit in a padded dimension of {padded}"
+                )));
+            }
+
+            shape.push(((padded - window) / self.stride[x]) + 1);
+        }
+
+        Ok(shape)
+    }
+
+    /// Compute the output shape of a transposed convolution of an `input` with the given `kernel`
+    /// shape, or return an error if the shapes are not compatible with this specification.
+    pub fn transpose_output_shape(
+        &self,
+        input: &[usize],
+        kernel: &[usize],
+    ) -> Result<Shape, Error> {
+        self.validate(input, kernel)?;
+
+        let (batch, in_channels) = (input[0], input[1]);
+        let (kernel_channels, group_channels) = (kernel[0], kernel[1]);
+
+        if self.groups == 0 || in_channels != kernel_channels || in_channels % self.groups != 0 {
+            return Err(Error::Bounds(format!(
+                "cannot split {in_channels} input channels into {} groups for a transposed kernel with shape {kernel:?}",
+                self.groups
+            )));
+        }
+
+        let mut shape = Shape::with_capacity(N + 2);
+        shape.push(batch);
+        shape.push(group_channels * self.groups);
+
+        for x in 0..N {
+            if self.output_padding[x] >= self.stride[x].max(self.dilation[x]) {
+                return Err(Error::Bounds(format!(
+                    "output padding {:?} must be less than the stride {:?} or dilation {:?}",
+                    self.output_padding, self.stride, self.dilation
+                )));
+            }
+
+            let window = (self.dilation[x] * kernel[x + 2].saturating_sub(1)) + 1;
+            let dim =
+                (input[x + 2].saturating_sub(1) * self.stride[x]) + window + self.output_padding[x];
+
+            if input[x + 2] == 0 || kernel[x + 2] == 0 || dim <= 2 * self.padding[x] {
+                return Err(Error::Bounds(format!(
+                    "a transposed convolution of a dimension of {} with a window of {window} is empty after removing padding of {}",
+                    input[x + 2], self.padding[x]
+                )));
+            }
+
+            shape.push(dim - (2 * self.padding[x]));
+        }
+
+        Ok(shape)
+    }
+
+    /// Extend this specification to three spatial axes by prepending unit axes,
+    /// so that a 1D or 2D convolution can be computed as a 3D convolution.
+    pub(crate) fn volumetric(&self) -> ConvSpec<3> {
+        debug_assert!(N <= 3);
+
+        let lift = |dims: &[usize; N], unit: usize| {
+            let mut lifted = [unit; 3];
+            lifted[(3 - N)..].copy_from_slice(dims);
+            lifted
+        };
+
+        ConvSpec {
+            stride: lift(&self.stride, 1),
+            padding: lift(&self.padding, 0),
+            output_padding: lift(&self.output_padding, 0),
+            dilation: lift(&self.dilation, 1),
+            groups: self.groups,
+        }
+    }
+
+    fn validate(&self, input: &[usize], kernel: &[usize]) -> Result<(), Error> {
+        if input.len() != N + 2 || kernel.len() != N + 2 {
+            return Err(Error::Bounds(format!(
+                "a {N}-D convolution requires an input and kernel with {} dimensions, not {input:?} and {kernel:?}",
+                N + 2
+            )));
+        }
+
+        if self.stride.contains(&0) || self.dilation.contains(&0) {
+            return Err(Error::Bounds(format!(
+                "invalid convolution stride {:?} or dilation {:?}",
+                self.stride, self.dilation
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+impl ConvSpec<3> {
+    /// Iterate over the offsets of the input and kernel elements which contribute to the element
+    /// at `offset` in the output of a convolution with the given shapes.
+    pub(crate) fn taps(
+        &self,
+        input: [usize; 5],
+        kernel: [usize; 5],
+        output: [usize; 5],
+        offset: usize,
+    ) -> impl Iterator<Item = (usize, usize)> {
+        let [_batch, out_channels, o_depth, o_height, o_width] = output;
+        let rows = kernel[1..].iter().product::<usize>();
+
+        let plane = o_depth * o_height * o_width;
+        let position = offset % plane;
+        let channel = (offset / plane) % out_channels;
+        let batch = offset / (plane * out_channels);
+
+        let group = channel / (out_channels / self.groups);
+        let spec = *self;
+
+        (0..rows).filter_map(move |row| {
+            let input_offset =
+                spec.window_source(input, kernel, output, batch, group, row, position)?;
+
+            Some((input_offset, (channel * rows) + row))
+        })
+    }
+
+    /// Return the offset of the input element at `row` and column `position` of the matrix
+    /// of windows (i.e. the "im2col" matrix) of one `group` of one `batch` of a convolution,
+    /// where each row is a (channel, kernel z, kernel y, kernel x) tuple and each column is an
+    /// output position, or `None` if that element falls in the zero padding.
+    #[allow(clippy::too_many_arguments)]
+    #[inline]
+    pub(crate) fn window_source(
+        &self,
+        input: [usize; 5],
+        kernel: [usize; 5],
+        output: [usize; 5],
+        batch: usize,
+        group: usize,
+        row: usize,
+        position: usize,
+    ) -> Option<usize> {
+        let [_batch, in_channels, depth, height, width] = input;
+        let [_out_channels, group_in, k_depth, k_height, k_width] = kernel;
+        let [_batch, _out_channels, _o_depth, o_height, o_width] = output;
+
+        let c_in = (group * group_in) + (row / (k_depth * k_height * k_width));
+        let kz = (row / (k_height * k_width)) % k_depth;
+        let ky = (row / k_width) % k_height;
+        let kx = row % k_width;
+
+        let z = position / (o_height * o_width);
+        let y = (position / o_width) % o_height;
+        let x = position % o_width;
+
+        let source = |axis: usize, o: usize, k: usize| {
+            ((o * self.stride[axis]) + (k * self.dilation[axis])).checked_sub(self.padding[axis])
+        };
+
+        let (iz, iy, ix) = (source(0, z, kz)?, source(1, y, ky)?, source(2, x, kx)?);
+
+        if iz < depth && iy < height && ix < width {
+            Some(((((batch * in_channels) + c_in) * depth + iz) * height + iy) * width + ix)
+        } else {
+            None
+        }
+    }
+}
+
+impl ConvSpec<2> {
+    /// Iterate over the offsets of the input and kernel elements which contribute to the element
+    /// at `offset` in the output of a transposed convolution with the given shapes.
+    pub(crate) fn transpose_taps(
+        &self,
+        input: [usize; 4],
+        kernel: [usize; 4],
+        output: [usize; 4],
+        offset: usize,
+    ) -> impl Iterator<Item = (usize, usize)> {
+        let [_batch, in_channels, height, width] = input;
+        let [_in_channels, group_out, k_height, k_width] = kernel;
+        let [_batch, out_channels, o_height, o_width] = output;
+
+        let x = offset % o_width;
+        let y = (offset / o_width) % o_height;
+        let channel = (offset / (o_width * o_height)) % out_channels;
+        let batch = offset / (o_width * o_height * out_channels);
+
+        let group_in = in_channels / self.groups;
+        let group = channel / group_out;
+        let channel = channel % group_out;
+
+        let [stride_y, stride_x] = self.stride;
+        let [pad_y, pad_x] = self.padding;
+        let [dilation_y, dilation_x] = self.dilation;
+
+        ((group * group_in)..((group + 1) * group_in)).flat_map(move |c_in| {
+            (0..k_height)
+                .filter_map(move |ky| {
+                    let iy = transpose_source(y + pad_y, ky * dilation_y, stride_y, height)?;
+                    Some((ky, iy))
+                })
+                .flat_map(move |(ky, iy)| {
+                    (0..k_width).filter_map(move |kx| {
+                        let ix = transpose_source(x + pad_x, kx * dilation_x, stride_x, width)?;
+                        let input_offset =
+                            (((batch * in_channels) + c_in) * height + iy) * width + ix;
+                        let kernel_offset =
+                            (((c_in * group_out) + channel) * k_height + ky) * k_width + kx;
+                        Some((input_offset, kernel_offset))
+                    })
+                })
+        })
+    }
+}
+
+/// Return the coordinate of the input element which a kernel element at `window_offset`
+/// maps to the padded output coordinate `coord`, if any.
+#[inline]
+fn transpose_source(
+    coord: usize,
+    window_offset: usize,
+    stride: usize,
+    dim: usize,
+) -> Option<usize> {
+    let coord = coord.checked_sub(window_offset)?;
+
+    if coord % stride == 0 && coord / stride < dim {
+        Some(coord / stride)
+    } else {
+        None
+    }
+}
+
+/// Compute the element at `offset` of a batch of matrices with shape `dims`
+/// upsampled by a factor of `scale`, using `read` to read an element of the source batch.
+/// Extend the `shape` of the input, kernel, or output of a convolution with up to three spatial
+/// axes to exactly three spatial axes by inserting unit axes after the channel axis.
+pub(crate) fn volumetric_shape(shape: &[usize]) -> [usize; 5] {
+    debug_assert!((3..=5).contains(&shape.len()));
+
+    let mut volumetric = [1; 5];
+    volumetric[..2].copy_from_slice(&shape[..2]);
+    volumetric[(7 - shape.len())..].copy_from_slice(&shape[2..]);
+    volumetric
+}
+
+pub(crate) fn upsample_value<T, F>(
+    mode: Interpolation,
+    dims: [usize; 3],
+    scale: [usize; 2],
+    offset: usize,
+    read: F,
+) -> Result<T, Error>
+where
+    T: CType,
+    F: Fn(usize) -> Result<T, Error>,
+{
+    let [_batch, height, width] = dims;
+    let [scale_y, scale_x] = scale;
+
+    let x = offset % (width * scale_x);
+    let y = (offset / (width * scale_x)) % (height * scale_y);
+    let start = (offset / (width * scale_x * height * scale_y)) * height * width;
+
+    let (y0, y1, wy) = upsample_source(mode, y, scale_y, height);
+    let (x0, x1, wx) = upsample_source(mode, x, scale_x, width);
+
+    if mode == Interpolation::Nearest {
+        return read(start + (y0 * width) + x0);
+    }
+
+    let [n00, n01, n10, n11] = [(y0, x0), (y0, x1), (y1, x0), (y1, x1)]
+        .map(|(y, x)| read(start + (y * width) + x).map(CType::to_f64));
+
+    let top = (n00? * (1. - wx)) + (n01? * wx);
+    let bottom = (n10? * (1. - wx)) + (n11? * wx);
+    let n = (top * (1. - wy)) + (bottom * wy);
+
+    Ok(T::from_f64(if T::IS_FLOAT { n } else { n.round() }))
+}
+
+/// Return the source coordinates and the weight of the second coordinate to sample
+/// when upsampling a dimension of `dim` elements by a factor of `scale`.
+#[inline]
+fn upsample_source(
+    mode: Interpolation,
+    coord: usize,
+    scale: usize,
+    dim: usize,
+) -> (usize, usize, f64) {
+    match mode {
+        Interpolation::Nearest => (coord / scale, coord / scale, 0.),
+        Interpolation::Bilinear => {
+            // align the centers of the source and output elements
+            let source = (((coord as f64 + 0.5) / scale as f64) - 0.5).max(0.);
+            let lo = (source.floor() as usize).min(dim - 1);
+            let hi = (lo + 1).min(dim - 1);
+            (lo, hi, source - lo as f64)
+        }
+    }
+}
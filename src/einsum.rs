@@ -0,0 +1,426 @@
+//! Einstein summation and tensor contraction
+
+use std::collections::HashMap;
+
+use crate::access::Accessor;
+use crate::array::Array;
+use crate::{
+    shape, ArrayAccess, Axes, CType, Error, IntoAxes, MatrixDual, NDArray, NDArrayReduce,
+    NDArrayTransform, Platform, Shape, Strides,
+};
+
+/// Construct the tensor contraction described by the Einstein summation `spec` of the given
+/// `operands`, e.g. `einsum("ij,jk->ik", [a, b])` for a matrix product.
+///
+/// Each operand is labeled by one letter per axis, and every axis which does not appear in the
+/// output after `->` is summed over. If there is no `->`, the output axes are the labels which
+/// appear exactly once, in alphabetical order. A label repeated within one operand selects its
+/// diagonal, e.g. `"ii->i"`. The output of a contraction over every axis has shape `[1]`.
+///
+/// The contraction is expressed in terms of transposes, reshapes, sum-reduces, and batched
+/// matrix multiplications, contracting the operands pairwise from left to right,
+/// so it runs on whichever platform those ops would.
+pub fn einsum<T, A, P, I>(spec: &str, operands: I) -> Result<ArrayAccess<T>, Error>
+where
+    T: CType,
+    I: IntoIterator<Item = Array<T, A, P>>,
+    Accessor<T>: From<A>,
+    Platform: From<P>,
+{
+    let operands = operands
+        .into_iter()
+        .map(ArrayAccess::from)
+        .collect::<Vec<_>>();
+
+    let spec = Spec::parse(spec)?;
+    let dims = spec.dims(&operands)?;
+
+    let mut operands = spec
+        .inputs
+        .iter()
+        .cloned()
+        .zip(operands)
+        .map(|(labels, array)| diagonal(Operand { labels, array }, &dims))
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    // sum over each axis which only one operand needs before contracting anything
+    for i in 0..operands.len() {
+        let needed = |label: &char| {
+            spec.output.contains(label)
+                || (operands.iter().enumerate())
+                    .any(|(j, other)| j != i && other.labels.contains(label))
+        };
+
+        let keep = operands[i].labels.iter().map(needed).collect::<Vec<_>>();
+
+        let operand = operands[i].clone();
+        operands[i] = sum_out(operand, &keep)?;
+    }
+
+    let mut operands = operands.into_iter();
+    let mut product = operands.next().expect("operand");
+
+    for (i, operand) in operands.enumerate() {
+        let later = &spec.inputs[(i + 2)..];
+        let needed = |label: &char| {
+            spec.output.contains(label) || later.iter().any(|labels| labels.contains(label))
+        };
+
+        product = contract(product, operand, needed, &dims)?;
+    }
+
+    let keep = product
+        .labels
+        .iter()
+        .map(|label| spec.output.contains(label))
+        .collect::<Vec<_>>();
+
+    let product = sum_out(product, &keep)?;
+    permute(product, &spec.output).map(|product| product.array)
+}
+
+/// Construct the contraction of the `left` and `right` arrays over each pair of axes in `axes`,
+/// e.g. `tensordot(a, b, [[1], [0]])` for a matrix product.
+///
+/// The `i`th axis in `axes[0]` of the `left` array is summed against the `i`th axis in `axes[1]`
+/// of the `right` array, so each pair must have the same dimension. The output has the remaining
+/// axes of the `left` array followed by the remaining axes of the `right` array, in order,
+/// or shape `[1]` if every axis is contracted.
+///
+/// The operands are permuted and reshaped into a single matrix multiplication,
+/// the same way as a contraction by [`einsum`].
+pub fn tensordot<T, LA, LP, RA, RP>(
+    left: Array<T, LA, LP>,
+    right: Array<T, RA, RP>,
+    axes: [impl IntoAxes; 2],
+) -> Result<ArrayAccess<T>, Error>
+where
+    T: CType,
+    Accessor<T>: From<LA> + From<RA>,
+    Platform: From<LP> + From<RP>,
+{
+    let [left_axes, right_axes] = axes.map(IntoAxes::into_axes);
+    let (left, right) = (ArrayAccess::from(left), ArrayAccess::from(right));
+
+    let valid = |axes: &[usize], ndim: usize| {
+        axes.iter().all(|x| *x < ndim) && !(1..axes.len()).any(|i| axes[i..].contains(&axes[i - 1]))
+    };
+
+    if left_axes.len() != right_axes.len()
+        || !valid(&left_axes, left.ndim())
+        || !valid(&right_axes, right.ndim())
+        || (left_axes.iter().zip(&right_axes)).any(|(l, r)| left.shape()[*l] != right.shape()[*r])
+    {
+        return Err(Error::Bounds(format!(
+            "invalid axes {left_axes:?} and {right_axes:?} to contract arrays with shapes {:?} and {:?}",
+            left.shape(),
+            right.shape()
+        )));
+    }
+
+    // label each axis of the left operand, and each axis of the right operand which is not
+    // contracted, uniquely, and each contracted axis of the right operand like its pair
+    let label = |n: usize| char::from_u32(n as u32).expect("label");
+
+    let left_labels = (0..left.ndim()).map(label).collect::<Vec<_>>();
+    let right_labels = (0..right.ndim())
+        .map(|x| match right_axes.iter().position(|r| *r == x) {
+            Some(i) => left_labels[left_axes[i]],
+            None => label(left.ndim() + x),
+        })
+        .collect::<Vec<_>>();
+
+    let dims = (left_labels.iter().zip(left.shape()))
+        .chain(right_labels.iter().zip(right.shape()))
+        .map(|(label, dim)| (*label, *dim))
+        .collect::<HashMap<_, _>>();
+
+    let contracted = left_axes
+        .iter()
+        .map(|x| left_labels[*x])
+        .collect::<Vec<_>>();
+
+    let left = Operand {
+        labels: left_labels,
+        array: left,
+    };
+
+    let right = Operand {
+        labels: right_labels,
+        array: right,
+    };
+
+    let product = contract(left, right, |label| !contracted.contains(label), &dims)?;
+    Ok(product.array)
+}
+
+/// A parsed Einstein summation specification
+struct Spec {
+    inputs: Vec<Vec<char>>,
+    output: Vec<char>,
+}
+
+impl Spec {
+    fn parse(spec: &str) -> Result<Self, Error> {
+        let spec = spec
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>();
+
+        if spec.contains("...") {
+            return Err(Error::Unsupported(format!(
+                "einsum does not support an ellipsis: {spec}"
+            )));
+        }
+
+        let (inputs, output) = match spec.split_once("->") {
+            Some((inputs, output)) => (inputs, Some(output)),
+            None => (spec.as_str(), None),
+        };
+
+        let inputs = inputs
+            .split(',')
+            .map(|labels| labels.chars().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        let valid = |labels: &[char]| labels.iter().all(|c| c.is_ascii_alphabetic());
+
+        if !inputs.iter().all(|labels| valid(labels)) {
+            return Err(Error::Bounds(format!(
+                "invalid einsum specification: {spec}"
+            )));
+        }
+
+        let count = |label: &char| inputs.iter().flatten().filter(|l| *l == label).count();
+
+        let output = if let Some(output) = output {
+            let output = output.chars().collect::<Vec<_>>();
+
+            if !valid(&output)
+                || (1..output.len()).any(|i| output[i..].contains(&output[i - 1]))
+                || output.iter().any(|label| count(label) == 0)
+            {
+                return Err(Error::Bounds(format!(
+                    "invalid einsum output {output:?} for inputs {inputs:?}"
+                )));
+            }
+
+            output
+        } else {
+            let mut output = inputs
+                .iter()
+                .flatten()
+                .copied()
+                .filter(|label| count(label) == 1)
+                .collect::<Vec<_>>();
+
+            output.sort();
+            output
+        };
+
+        Ok(Self { inputs, output })
+    }
+
+    /// Check the shapes of the `operands` against this spec and return the dimension of each label.
+    fn dims<T: CType>(&self, operands: &[ArrayAccess<T>]) -> Result<HashMap<char, usize>, Error> {
+        if operands.len() != self.inputs.len() {
+            return Err(Error::Bounds(format!(
+                "einsum expected {} operands but found {}",
+                self.inputs.len(),
+                operands.len()
+            )));
+        }
+
+        let mut dims = HashMap::new();
+
+        for (labels, operand) in self.inputs.iter().zip(operands) {
+            if labels.len() != operand.ndim() {
+                return Err(Error::Bounds(format!(
+                    "einsum labels {labels:?} do not match an operand with shape {:?}",
+                    operand.shape()
+                )));
+            }
+
+            for (label, dim) in labels.iter().zip(operand.shape()) {
+                let expected = *dims.entry(*label).or_insert(*dim);
+
+                if expected != *dim {
+                    return Err(Error::Bounds(format!(
+                        "einsum label {label:?} has dimension {expected} in one operand and {dim} in another"
+                    )));
+                }
+            }
+        }
+
+        Ok(dims)
+    }
+}
+
+/// An intermediate array together with the label of each of its axes
+#[derive(Clone)]
+struct Operand<T: CType> {
+    labels: Vec<char>,
+    array: ArrayAccess<T>,
+}
+
+/// Select the diagonal of each label which is repeated within the given `operand`.
+fn diagonal<T: CType>(
+    operand: Operand<T>,
+    dims: &HashMap<char, usize>,
+) -> Result<Operand<T>, Error> {
+    let Operand { labels, array } = operand;
+
+    let mut unique = Vec::with_capacity(labels.len());
+    for label in &labels {
+        if !unique.contains(label) {
+            unique.push(*label);
+        }
+    }
+
+    if unique.len() == labels.len() {
+        return Ok(Operand { labels, array });
+    }
+
+    let source_strides = (0..labels.len())
+        .map(|x| array.shape()[(x + 1)..].iter().product::<usize>())
+        .collect::<Vec<_>>();
+
+    let shape = unique.iter().map(|label| dims[label]).collect::<Shape>();
+    let strides = unique
+        .iter()
+        .map(|label| {
+            (labels.iter().zip(&source_strides))
+                .filter(|(l, _)| *l == label)
+                .map(|(_, stride)| stride)
+                .sum()
+        })
+        .collect::<Strides>();
+
+    // every coordinate of a repeated label is less than its dimension in each of its axes,
+    // so every offset of the view lies within the source array
+    let array = unsafe { array.as_strided(shape, strides, 0)? };
+
+    Ok(Operand {
+        labels: unique,
+        array: ArrayAccess::from(array),
+    })
+}
+
+/// Sum over each axis of the `operand` which is not marked to `keep`.
+fn sum_out<T: CType>(operand: Operand<T>, keep: &[bool]) -> Result<Operand<T>, Error> {
+    if keep.iter().all(|keep| *keep) {
+        return Ok(operand);
+    }
+
+    let axes = (0..keep.len()).filter(|x| !keep[*x]).collect::<Axes>();
+
+    let labels = (operand.labels.iter().zip(keep))
+        .filter(|(_, keep)| **keep)
+        .map(|(label, _)| *label)
+        .collect();
+
+    let array = operand.array.sum(axes, false)?;
+
+    Ok(Operand {
+        labels,
+        array: ArrayAccess::from(array),
+    })
+}
+
+/// Transpose the `operand` so that its axes are in the given `order` of labels.
+fn permute<T: CType>(operand: Operand<T>, order: &[char]) -> Result<Operand<T>, Error> {
+    debug_assert_eq!(operand.labels.len(), order.len());
+
+    if operand.labels == order {
+        return Ok(operand);
+    }
+
+    let permutation = order
+        .iter()
+        .map(|label| {
+            operand
+                .labels
+                .iter()
+                .position(|l| l == label)
+                .expect("label")
+        })
+        .collect();
+
+    let array = operand.array.transpose(Some(permutation))?;
+
+    Ok(Operand {
+        labels: order.to_vec(),
+        array: ArrayAccess::from(array),
+    })
+}
+
+/// Contract two operands with a batched matrix multiplication,
+/// keeping only their shared labels which are `needed` later.
+fn contract<T, N>(
+    left: Operand<T>,
+    right: Operand<T>,
+    needed: N,
+    dims: &HashMap<char, usize>,
+) -> Result<Operand<T>, Error>
+where
+    T: CType,
+    N: Fn(&char) -> bool,
+{
+    let keep = |operand: &Operand<T>, other: &Operand<T>| {
+        (operand.labels.iter())
+            .map(|label| needed(label) || other.labels.contains(label))
+            .collect::<Vec<_>>()
+    };
+
+    let keep_left = keep(&left, &right);
+    let left = sum_out(left, &keep_left)?;
+
+    let keep_right = keep(&right, &left);
+    let right = sum_out(right, &keep_right)?;
+
+    let shared = |label: &&char| right.labels.contains(label);
+
+    let batch = (left.labels.iter().filter(shared))
+        .filter(|label| needed(label))
+        .copied()
+        .collect::<Vec<_>>();
+
+    let contracted = (left.labels.iter().filter(shared))
+        .filter(|label| !needed(label))
+        .copied()
+        .collect::<Vec<_>>();
+
+    let rows = (left.labels.iter())
+        .filter(|label| !right.labels.contains(label))
+        .copied()
+        .collect::<Vec<_>>();
+
+    let cols = (right.labels.iter())
+        .filter(|label| !left.labels.contains(label))
+        .copied()
+        .collect::<Vec<_>>();
+
+    let size = |labels: &[char]| labels.iter().map(|label| dims[label]).product::<usize>();
+    let (batch_size, k) = (size(&batch), size(&contracted));
+
+    let left = permute(left, &[&batch[..], &rows, &contracted].concat())?;
+    let left = left.array.reshape(shape![batch_size, size(&rows), k])?;
+
+    let right = permute(right, &[&batch[..], &contracted, &cols].concat())?;
+    let right = right.array.reshape(shape![batch_size, k, size(&cols)])?;
+
+    let labels = [batch, rows, cols].concat();
+
+    let shape = if labels.is_empty() {
+        shape![1]
+    } else {
+        labels.iter().map(|label| dims[label]).collect()
+    };
+
+    let product = ArrayAccess::from(left.matmul(right)?).reshape(shape)?;
+
+    Ok(Operand {
+        labels,
+        array: product,
+    })
+}
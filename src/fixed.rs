@@ -0,0 +1,180 @@
+//! Arrays with fixed shapes
+//!
+//! A [`Vector`] or [`Matrix`] carries its dimensions as const generic parameters, so that
+//! a mismatch between the shapes of the operands of a matrix product or a broadcast is a compile
+//! time error. The shape of the underlying array is checked once, when it is wrapped.
+//! Elementwise arithmetic between operands with the same shape uses the [`std::ops`] operators,
+//! which return a `Result` like every other array op.
+//!
+//! ```compile_fail
+//! # use ha_ndarray::fixed::Matrix;
+//! # use ha_ndarray::*;
+//! # fn main() -> Result<(), Error> {
+//! let a = Matrix::<f32, 2, 3>::new(ArrayBuf::new(vec![0.; 6], shape![2, 3])?)?;
+//! let b = Matrix::<f32, 2, 3>::new(ArrayBuf::new(vec![0.; 6], shape![2, 3])?)?;
+//! let c = a.matmul(b)?; // a 2x3 matrix cannot be multiplied by a 2x3 matrix
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+use std::ops::{Add, Div, Mul, Sub};
+
+use crate::access::Accessor;
+use crate::array::Array;
+use crate::{
+    axes, shape, ArrayAccess, CType, Error, MatrixDual, NDArray, NDArrayMath, NDArrayReduceAll,
+    NDArrayTransform, Platform,
+};
+
+/// A 1D array with the fixed dimension `N`
+#[derive(Clone)]
+pub struct Vector<T: CType, const N: usize> {
+    array: ArrayAccess<T>,
+}
+
+impl<T: CType, const N: usize> Vector<T, N> {
+    /// Wrap the given `array`, which must have shape `[N]`.
+    pub fn new<A, P>(array: Array<T, A, P>) -> Result<Self, Error>
+    where
+        Accessor<T>: From<A>,
+        Platform: From<P>,
+    {
+        check_shape(&[N], ArrayAccess::from(array)).map(|array| Self { array })
+    }
+
+    /// Borrow the underlying array.
+    pub fn as_array(&self) -> &ArrayAccess<T> {
+        &self.array
+    }
+
+    /// Unwrap the underlying array.
+    pub fn into_inner(self) -> ArrayAccess<T> {
+        self.array
+    }
+
+    /// Compute the dot product of this vector and `other`.
+    pub fn dot(self, other: Self) -> Result<T, Error> {
+        NDArrayMath::mul(self.array, other.array)?.sum_all()
+    }
+
+    fn wrap(array: ArrayAccess<T>) -> Self {
+        debug_assert_eq!(array.shape(), &[N]);
+        Self { array }
+    }
+}
+
+impl<T: CType, const N: usize> fmt::Debug for Vector<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a vector of {N} elements of {}", T::TYPE)
+    }
+}
+
+/// A 2D array with the fixed dimensions `[M, N]`
+#[derive(Clone)]
+pub struct Matrix<T: CType, const M: usize, const N: usize> {
+    array: ArrayAccess<T>,
+}
+
+impl<T: CType, const M: usize, const N: usize> Matrix<T, M, N> {
+    /// Wrap the given `array`, which must have shape `[M, N]`.
+    pub fn new<A, P>(array: Array<T, A, P>) -> Result<Self, Error>
+    where
+        Accessor<T>: From<A>,
+        Platform: From<P>,
+    {
+        check_shape(&[M, N], ArrayAccess::from(array)).map(|array| Self { array })
+    }
+
+    /// Borrow the underlying array.
+    pub fn as_array(&self) -> &ArrayAccess<T> {
+        &self.array
+    }
+
+    /// Unwrap the underlying array.
+    pub fn into_inner(self) -> ArrayAccess<T> {
+        self.array
+    }
+
+    /// Add the given `row` to each row of this matrix.
+    pub fn add_row(self, row: Vector<T, N>) -> Result<Self, Error> {
+        let row = row.array.unsqueeze(axes![0])?.broadcast(shape![M, N])?;
+        let array = NDArrayMath::add(self.array, ArrayAccess::from(row))?;
+        Ok(Self::wrap(ArrayAccess::from(array)))
+    }
+
+    /// Add the given `column` to each column of this matrix.
+    pub fn add_column(self, column: Vector<T, M>) -> Result<Self, Error> {
+        let column = column.array.unsqueeze(axes![1])?.broadcast(shape![M, N])?;
+        let array = NDArrayMath::add(self.array, ArrayAccess::from(column))?;
+        Ok(Self::wrap(ArrayAccess::from(array)))
+    }
+
+    /// Construct the matrix product of this matrix and `other`.
+    pub fn matmul<const K: usize>(self, other: Matrix<T, N, K>) -> Result<Matrix<T, M, K>, Error> {
+        let array = self.array.matmul(other.array)?;
+        Ok(Matrix::wrap(ArrayAccess::from(array)))
+    }
+
+    /// Construct the product of this matrix and the column `vector`.
+    pub fn matvec(self, vector: Vector<T, N>) -> Result<Vector<T, M>, Error> {
+        let column = vector.array.unsqueeze(axes![1])?;
+        let product = self.array.matmul(column)?.squeeze(axes![1])?;
+        Ok(Vector::wrap(ArrayAccess::from(product)))
+    }
+
+    /// Construct the transpose of this matrix.
+    pub fn transpose(self) -> Result<Matrix<T, N, M>, Error> {
+        let array = self.array.transpose(None)?;
+        Ok(Matrix::wrap(ArrayAccess::from(array)))
+    }
+
+    fn wrap(array: ArrayAccess<T>) -> Self {
+        debug_assert_eq!(array.shape(), &[M, N]);
+        Self { array }
+    }
+}
+
+impl<T: CType, const M: usize, const N: usize> fmt::Debug for Matrix<T, M, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a {M}x{N} matrix of {}", T::TYPE)
+    }
+}
+
+macro_rules! impl_elementwise {
+    ($op:ident, $name:ident) => {
+        impl<T: CType, const N: usize> $op for Vector<T, N> {
+            type Output = Result<Self, Error>;
+
+            fn $name(self, other: Self) -> Self::Output {
+                let array = NDArrayMath::$name(self.array, other.array)?;
+                Ok(Self::wrap(ArrayAccess::from(array)))
+            }
+        }
+
+        impl<T: CType, const M: usize, const N: usize> $op for Matrix<T, M, N> {
+            type Output = Result<Self, Error>;
+
+            fn $name(self, other: Self) -> Self::Output {
+                let array = NDArrayMath::$name(self.array, other.array)?;
+                Ok(Self::wrap(ArrayAccess::from(array)))
+            }
+        }
+    };
+}
+
+impl_elementwise!(Add, add);
+impl_elementwise!(Div, div);
+impl_elementwise!(Mul, mul);
+impl_elementwise!(Sub, sub);
+
+fn check_shape<T: CType>(shape: &[usize], array: ArrayAccess<T>) -> Result<ArrayAccess<T>, Error> {
+    if array.shape() == shape {
+        Ok(array)
+    } else {
+        Err(Error::Bounds(format!(
+            "expected an array with shape {shape:?}, not {:?}",
+            array.shape()
+        )))
+    }
+}
@@ -0,0 +1,764 @@
+//! Capture and replay of op graphs, and serializable graph definitions
+//!
+//! A [`GraphDef`] is encoded as a header followed by its nodes in order,
+//! with every integer in little-endian byte order:
+//!
+//! | field    | size          | encoding                    |
+//! |----------|---------------|-----------------------------|
+//! | magic    | 4 bytes       | `DNSG`                      |
+//! | version  | 1 byte        | currently `1`               |
+//! | dtype    | 1 + n bytes   | length, then the type name  |
+//! | count    | 4 bytes       | `u32` number of nodes       |
+//! | nodes    | the rest      | a tag byte, then parameters |
+//!
+//! Each index, dimension, and axis is a `u64`, and each list of them is prefixed by a `u32` length.
+//! A scalar has the data type of the graph.
+
+use std::fmt;
+use std::mem;
+use std::sync::{Arc, Mutex, RwLock};
+
+use smallvec::{smallvec, SmallVec};
+
+use crate::access::{AccessOp, Accessor};
+use crate::array::{decode_bytes, encode_bytes, Array};
+use crate::backend::{DualOp, ReduceOp, UnaryOp};
+use crate::ops::{Enqueue, Op, ReadValue};
+use crate::{
+    ArrayAccess, ArrayBuf, Axes, AxisRange, Buffer, BufferInstance, CType, Error, IntoShape,
+    MatrixDual, NDArray, NDArrayMath, NDArrayMathScalar, NDArrayRead, NDArrayReduce,
+    NDArrayTransform, NDArrayUnary, Platform, PlatformInstance, Range, Shape,
+};
+
+/// An input of a captured [`Graph`] with a fixed shape, whose data are bound before each replay.
+///
+/// Cloning a placeholder does not copy its binding, so every clone reads the same data.
+#[derive(Clone)]
+pub struct Placeholder<T: CType> {
+    shape: Shape,
+    buffer: Arc<RwLock<Option<Buffer<T>>>>,
+}
+
+impl<T: CType> Placeholder<T> {
+    /// Construct a new placeholder with the given `shape`, with no data bound to it.
+    pub fn new(shape: impl IntoShape) -> Result<Self, Error> {
+        let shape = shape.into_shape();
+
+        if shape.is_empty() {
+            return Err(Error::Bounds(
+                "a placeholder cannot have an empty shape".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            shape,
+            buffer: Arc::new(RwLock::new(None)),
+        })
+    }
+
+    /// Borrow the shape of this placeholder.
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    /// Construct an array which reads the data bound to this placeholder when it is evaluated.
+    pub fn array(&self) -> ArrayAccess<T> {
+        let platform = Platform::select(self.size());
+        let array = Array::from_op(self.shape.clone(), AccessOp::from(self.clone()), platform);
+        ArrayAccess::from(array)
+    }
+
+    /// Bind the given `buffer`, which must have the same size as this placeholder,
+    /// to be read by the next replay of any [`Graph`] which reads this placeholder.
+    pub fn bind<B: Into<Buffer<T>>>(&self, buffer: B) -> Result<(), Error> {
+        let buffer = buffer.into();
+
+        if buffer.len() != self.size() {
+            return Err(Error::Bounds(format!(
+                "cannot bind a buffer of size {} to a placeholder with shape {:?}",
+                buffer.len(),
+                self.shape
+            )));
+        }
+
+        *self.buffer.write().expect("placeholder") = Some(buffer);
+
+        Ok(())
+    }
+
+    /// Release the data bound to this placeholder, if any.
+    pub fn unbind(&self) {
+        *self.buffer.write().expect("placeholder") = None;
+    }
+
+    fn replace(&self, buffer: Option<Buffer<T>>) -> Option<Buffer<T>> {
+        mem::replace(&mut *self.buffer.write().expect("placeholder"), buffer)
+    }
+
+    fn unbound(&self) -> Error {
+        Error::Interface(format!(
+            "no data are bound to a placeholder with shape {:?}",
+            self.shape
+        ))
+    }
+}
+
+impl<T: CType> Op for Placeholder<T> {
+    fn size(&self) -> usize {
+        self.shape.iter().product()
+    }
+}
+
+impl<T: CType> Enqueue<Platform, T> for Placeholder<T> {
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let buffer = self.buffer.read().expect("placeholder");
+        buffer.clone().ok_or_else(|| self.unbound())
+    }
+}
+
+impl<T: CType> ReadValue<Platform, T> for Placeholder<T> {
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        let buffer = self.buffer.read().expect("placeholder");
+        let buffer = buffer.as_ref().ok_or_else(|| self.unbound())?;
+        buffer.read_value(offset)
+    }
+}
+
+impl<T: CType> fmt::Debug for Placeholder<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "a placeholder of {} with shape {:?}",
+            T::TYPE,
+            self.shape
+        )
+    }
+}
+
+/// A captured op graph, which computes its output from the data bound to its [`Placeholder`]s.
+///
+/// Every op in the graph is constructed, and its platform selected, only once when the graph is
+/// captured, so replaying it in a loop only enqueues its ops. A graph must not be replayed
+/// concurrently with new bindings to its placeholders, except by [`execute`].
+pub struct Graph<T: CType> {
+    output: ArrayAccess<T>,
+    executing: Mutex<()>,
+}
+
+impl<T: CType> Graph<T> {
+    /// Capture the op graph which computes the given `output` array.
+    pub fn capture<A, P>(output: Array<T, A, P>) -> Self
+    where
+        Accessor<T>: From<A>,
+        Platform: From<P>,
+    {
+        Self {
+            output: ArrayAccess::from(output),
+            executing: Mutex::new(()),
+        }
+    }
+
+    /// Borrow the shape of the output of this graph.
+    pub fn shape(&self) -> &[usize] {
+        self.output.shape()
+    }
+
+    /// Evaluate this graph with the data currently bound to its placeholders.
+    pub fn replay(&self) -> Result<ArrayBuf<T, Buffer<T>>, Error> {
+        let buffer = self.output.buffer()?.into_buffer()?;
+        ArrayBuf::new(buffer, self.output.shape())
+    }
+}
+
+/// Evaluate the given `graph` with each buffer in `feeds` bound to its [`Placeholder`].
+///
+/// Each feed is bound only for this evaluation, after which the placeholder's previous binding
+/// (if any) is restored, so a placeholder which does not change between requests (e.g. a weight)
+/// can be bound once and omitted from the feeds. Concurrent executions of the same graph are
+/// evaluated one at a time.
+pub fn execute<T, B>(
+    graph: &Graph<T>,
+    feeds: Vec<(&Placeholder<T>, B)>,
+) -> Result<ArrayBuf<T, Buffer<T>>, Error>
+where
+    T: CType,
+    B: Into<Buffer<T>>,
+{
+    let feeds = feeds
+        .into_iter()
+        .map(|(placeholder, buffer)| {
+            let buffer = buffer.into();
+
+            if buffer.len() == placeholder.size() {
+                Ok((placeholder, buffer))
+            } else {
+                Err(Error::Bounds(format!(
+                    "cannot feed a buffer of size {} to a placeholder with shape {:?}",
+                    buffer.len(),
+                    placeholder.shape
+                )))
+            }
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let _executing = graph.executing.lock().expect("graph execution");
+
+    let previous = feeds
+        .into_iter()
+        .map(|(placeholder, buffer)| (placeholder, placeholder.replace(Some(buffer))))
+        .collect::<Vec<_>>();
+
+    let output = graph.replay();
+
+    // restore in reverse order, in case the same placeholder was fed more than once
+    for (placeholder, buffer) in previous.into_iter().rev() {
+        placeholder.replace(buffer);
+    }
+
+    output
+}
+
+impl<T: CType> fmt::Debug for Graph<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a captured graph of {:?}", self.output)
+    }
+}
+
+/// An op in a [`GraphDef`], which refers to each of its inputs by the index of an earlier node
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, PartialEq)]
+pub enum GraphNode<T> {
+    /// The next input of the graph, which must have the given shape
+    Input(Shape),
+    /// An elementwise op on one node
+    Unary(UnaryOp, usize),
+    /// An elementwise op on two nodes
+    Dual(DualOp, usize, usize),
+    /// An elementwise op on a node and a scalar
+    Scalar(DualOp, usize, T),
+    /// The matrix product of two nodes
+    MatMul(usize, usize),
+    /// A reduction of the given axes of a node, which keeps the reduced dimensions if `true`
+    Reduce(ReduceOp, usize, Axes, bool),
+    /// A broadcast of a node into the given shape
+    Broadcast(usize, Shape),
+    /// A reshape of a node into the given shape
+    Reshape(usize, Shape),
+    /// A slice of a node
+    Slice(usize, Range),
+    /// A transpose of a node, which reverses its axes if no permutation is given
+    Transpose(usize, Option<Axes>),
+}
+
+impl<T> GraphNode<T> {
+    fn inputs(&self) -> SmallVec<[usize; 2]> {
+        match self {
+            Self::Input(_) => smallvec![],
+            Self::Dual(_, left, right) | Self::MatMul(left, right) => smallvec![*left, *right],
+            Self::Unary(_, input)
+            | Self::Scalar(_, input, _)
+            | Self::Reduce(_, input, _, _)
+            | Self::Broadcast(input, _)
+            | Self::Reshape(input, _)
+            | Self::Slice(input, _)
+            | Self::Transpose(input, _) => smallvec![*input],
+        }
+    }
+}
+
+/// A definition of an op graph in terms of op kinds and their parameters, rather than typed ops,
+/// so that it can be encoded in one process and loaded against new input arrays in another.
+///
+/// The output of the graph is its last node.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GraphDef<T> {
+    nodes: Vec<GraphNode<T>>,
+}
+
+impl<T: CType> GraphDef<T> {
+    /// Construct a new graph definition with no nodes.
+    pub fn new() -> Self {
+        Self { nodes: Vec::new() }
+    }
+
+    /// Append the given `node` to this graph, returning its index.
+    pub fn push(&mut self, node: GraphNode<T>) -> Result<usize, Error> {
+        let index = self.nodes.len();
+
+        if let Some(input) = node.inputs().into_iter().find(|input| *input >= index) {
+            return Err(Error::Bounds(format!(
+                "node {index} of a graph cannot read node {input}"
+            )));
+        }
+
+        self.nodes.push(node);
+
+        Ok(index)
+    }
+
+    /// Borrow the nodes of this graph.
+    pub fn nodes(&self) -> &[GraphNode<T>] {
+        &self.nodes
+    }
+
+    /// Return the shapes of the inputs of this graph, in order.
+    pub fn input_shapes(&self) -> Vec<&[usize]> {
+        self.nodes
+            .iter()
+            .filter_map(|node| match node {
+                GraphNode::Input(shape) => Some(shape.as_slice()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Encode this graph definition.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        bytes.extend_from_slice(GRAPH_MAGIC);
+        bytes.push(GRAPH_VERSION);
+        bytes.push(T::TYPE.len() as u8);
+        bytes.extend_from_slice(T::TYPE.as_bytes());
+        bytes.extend_from_slice(&(self.nodes.len() as u32).to_le_bytes());
+
+        for node in &self.nodes {
+            encode_node(&mut bytes, node);
+        }
+
+        bytes
+    }
+
+    /// Decode a graph definition with data type `T` encoded by [`GraphDef::encode`].
+    pub fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        let mut reader = Reader { bytes };
+
+        if reader.take(GRAPH_MAGIC.len())? != GRAPH_MAGIC {
+            return Err(Error::Interface("not a graph definition".to_string()));
+        }
+
+        match reader.u8()? {
+            GRAPH_VERSION => {}
+            version => {
+                return Err(Error::Interface(format!(
+                    "unsupported graph definition version {version}"
+                )))
+            }
+        }
+
+        let len = reader.u8()? as usize;
+        let dtype = reader.take(len)?;
+
+        if dtype != T::TYPE.as_bytes() {
+            return Err(Error::Interface(format!(
+                "expected a graph definition with data type {} but found {}",
+                T::TYPE,
+                String::from_utf8_lossy(dtype)
+            )));
+        }
+
+        let mut graph = Self::new();
+
+        for _ in 0..reader.u32()? {
+            graph.push(decode_node(&mut reader)?)?;
+        }
+
+        if reader.bytes.is_empty() {
+            Ok(graph)
+        } else {
+            Err(Error::Interface(format!(
+                "{} trailing bytes after a graph definition",
+                reader.bytes.len()
+            )))
+        }
+    }
+
+    /// Construct the output of this graph from the given `inputs`,
+    /// which must have the shapes of its [`GraphNode::Input`]s in order.
+    pub fn load(&self, inputs: Vec<ArrayAccess<T>>) -> Result<ArrayAccess<T>, Error> {
+        let num_inputs = inputs.len();
+        let mut inputs = inputs.into_iter();
+        let mut arrays: Vec<ArrayAccess<T>> = Vec::with_capacity(self.nodes.len());
+
+        for node in &self.nodes {
+            let array = match node {
+                GraphNode::Input(shape) => {
+                    let input = inputs.next().ok_or_else(|| {
+                        Error::Bounds(format!(
+                            "a graph with {} inputs cannot be loaded with {num_inputs}",
+                            self.input_shapes().len()
+                        ))
+                    })?;
+
+                    if input.shape() == shape.as_slice() {
+                        input
+                    } else {
+                        return Err(Error::Bounds(format!(
+                            "expected a graph input with shape {shape:?}, not {:?}",
+                            input.shape()
+                        )));
+                    }
+                }
+                GraphNode::Unary(op, input) => {
+                    let input = arrays[*input].clone();
+
+                    ArrayAccess::from(match op {
+                        UnaryOp::Abs => input.abs()?,
+                        UnaryOp::Exp => input.exp()?,
+                        UnaryOp::Ln => input.ln()?,
+                        UnaryOp::ReciprocalApprox => input.reciprocal_approx()?,
+                        UnaryOp::Round => input.round()?,
+                        UnaryOp::Softplus => input.softplus()?,
+                    })
+                }
+                GraphNode::Dual(op, left, right) => {
+                    let (left, right) = (arrays[*left].clone(), arrays[*right].clone());
+
+                    ArrayAccess::from(match op {
+                        DualOp::Add => left.add(right)?,
+                        DualOp::Div => left.div(right)?,
+                        DualOp::Log => left.log(right)?,
+                        DualOp::LogAddExp => left.logaddexp(right)?,
+                        DualOp::Mul => left.mul(right)?,
+                        DualOp::Pow => left.pow(right)?,
+                        DualOp::Rem => left.rem(right)?,
+                        DualOp::Step => left.step(right)?,
+                        DualOp::Sub => left.sub(right)?,
+                    })
+                }
+                GraphNode::Scalar(op, input, scalar) => {
+                    let input = arrays[*input].clone();
+
+                    ArrayAccess::from(match op {
+                        DualOp::Add => input.add_scalar(*scalar)?,
+                        DualOp::Div => input.div_scalar(*scalar)?,
+                        DualOp::Log => input.log_scalar(*scalar)?,
+                        DualOp::Mul => input.mul_scalar(*scalar)?,
+                        DualOp::Pow => input.pow_scalar(*scalar)?,
+                        DualOp::Rem => input.rem_scalar(*scalar)?,
+                        DualOp::Sub => input.sub_scalar(*scalar)?,
+                        DualOp::LogAddExp | DualOp::Step => {
+                            return Err(Error::Unsupported(format!(
+                                "{op:?} of an array and a scalar"
+                            )))
+                        }
+                    })
+                }
+                GraphNode::MatMul(left, right) => {
+                    ArrayAccess::from(arrays[*left].clone().matmul(arrays[*right].clone())?)
+                }
+                GraphNode::Reduce(op, input, axes, keepdims) => {
+                    let (input, axes) = (arrays[*input].clone(), axes.clone());
+
+                    ArrayAccess::from(match op {
+                        ReduceOp::Max => input.max(axes, *keepdims)?,
+                        ReduceOp::Min => input.min(axes, *keepdims)?,
+                        ReduceOp::Product => input.product(axes, *keepdims)?,
+                        ReduceOp::Sum => input.sum(axes, *keepdims)?,
+                    })
+                }
+                GraphNode::Broadcast(input, shape) => {
+                    ArrayAccess::from(arrays[*input].clone().broadcast(shape.clone())?)
+                }
+                GraphNode::Reshape(input, shape) => {
+                    arrays[*input].clone().reshape(shape.clone())?
+                }
+                GraphNode::Slice(input, range) => {
+                    ArrayAccess::from(arrays[*input].clone().slice(range.clone())?)
+                }
+                GraphNode::Transpose(input, permutation) => {
+                    ArrayAccess::from(arrays[*input].clone().transpose(permutation.clone())?)
+                }
+            };
+
+            arrays.push(array);
+        }
+
+        if inputs.next().is_some() {
+            return Err(Error::Bounds(format!(
+                "a graph with {} inputs cannot be loaded with {num_inputs}",
+                self.input_shapes().len()
+            )));
+        }
+
+        arrays
+            .pop()
+            .ok_or_else(|| Error::Bounds("an empty graph has no output".to_string()))
+    }
+
+    /// Load this graph against a new [`Placeholder`] for each of its inputs, and capture it,
+    /// so that it can be replayed with new data bound to the placeholders.
+    pub fn compile(&self) -> Result<(Vec<Placeholder<T>>, Graph<T>), Error> {
+        let placeholders = self
+            .input_shapes()
+            .into_iter()
+            .map(Placeholder::new)
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let inputs = placeholders.iter().map(Placeholder::array).collect();
+        let output = self.load(inputs)?;
+
+        Ok((placeholders, Graph::capture(output)))
+    }
+}
+
+impl<T: CType> Default for GraphDef<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+const GRAPH_MAGIC: &[u8; 4] = b"DNSG";
+const GRAPH_VERSION: u8 = 1;
+
+// the code of each op is its position in these lists, so new ops must only be appended
+const UNARY_OPS: [UnaryOp; 6] = [
+    UnaryOp::Abs,
+    UnaryOp::Exp,
+    UnaryOp::Ln,
+    UnaryOp::Round,
+    UnaryOp::Softplus,
+    UnaryOp::ReciprocalApprox,
+];
+
+const DUAL_OPS: [DualOp; 9] = [
+    DualOp::Add,
+    DualOp::Div,
+    DualOp::Log,
+    DualOp::LogAddExp,
+    DualOp::Mul,
+    DualOp::Pow,
+    DualOp::Rem,
+    DualOp::Step,
+    DualOp::Sub,
+];
+
+const REDUCE_OPS: [ReduceOp; 4] = [
+    ReduceOp::Max,
+    ReduceOp::Min,
+    ReduceOp::Product,
+    ReduceOp::Sum,
+];
+
+fn op_code<O: PartialEq>(ops: &[O], op: &O) -> u8 {
+    ops.iter().position(|o| o == op).expect("op code") as u8
+}
+
+fn op_from_code<O: Copy>(ops: &[O], code: u8) -> Result<O, Error> {
+    ops.get(code as usize)
+        .copied()
+        .ok_or_else(|| Error::Interface(format!("unknown op code {code} in a graph definition")))
+}
+
+fn encode_node<T: CType>(bytes: &mut Vec<u8>, node: &GraphNode<T>) {
+    let put_usize =
+        |bytes: &mut Vec<u8>, n: usize| bytes.extend_from_slice(&(n as u64).to_le_bytes());
+
+    let put_list = |bytes: &mut Vec<u8>, list: &[usize]| {
+        bytes.extend_from_slice(&(list.len() as u32).to_le_bytes());
+        list.iter().for_each(|n| put_usize(bytes, *n));
+    };
+
+    match node {
+        GraphNode::Input(shape) => {
+            bytes.push(0);
+            put_list(bytes, shape);
+        }
+        GraphNode::Unary(op, input) => {
+            bytes.extend([1, op_code(&UNARY_OPS, op)]);
+            put_usize(bytes, *input);
+        }
+        GraphNode::Dual(op, left, right) => {
+            bytes.extend([2, op_code(&DUAL_OPS, op)]);
+            put_usize(bytes, *left);
+            put_usize(bytes, *right);
+        }
+        GraphNode::Scalar(op, input, scalar) => {
+            bytes.extend([3, op_code(&DUAL_OPS, op)]);
+            put_usize(bytes, *input);
+            bytes.extend(encode_bytes(&[*scalar], true));
+        }
+        GraphNode::MatMul(left, right) => {
+            bytes.push(4);
+            put_usize(bytes, *left);
+            put_usize(bytes, *right);
+        }
+        GraphNode::Reduce(op, input, axes, keepdims) => {
+            bytes.extend([5, op_code(&REDUCE_OPS, op)]);
+            put_usize(bytes, *input);
+            put_list(bytes, axes);
+            bytes.push(*keepdims as u8);
+        }
+        GraphNode::Broadcast(input, shape) => {
+            bytes.push(6);
+            put_usize(bytes, *input);
+            put_list(bytes, shape);
+        }
+        GraphNode::Reshape(input, shape) => {
+            bytes.push(7);
+            put_usize(bytes, *input);
+            put_list(bytes, shape);
+        }
+        GraphNode::Slice(input, range) => {
+            bytes.push(8);
+            put_usize(bytes, *input);
+            bytes.extend_from_slice(&(range.len() as u32).to_le_bytes());
+
+            for axis_range in range {
+                match axis_range {
+                    AxisRange::At(i) => {
+                        bytes.push(0);
+                        put_usize(bytes, *i);
+                    }
+                    AxisRange::In(start, stop, step) => {
+                        bytes.push(1);
+                        [*start, *stop, *step]
+                            .into_iter()
+                            .for_each(|n| put_usize(bytes, n));
+                    }
+                    AxisRange::Of(indices) => {
+                        bytes.push(2);
+                        put_list(bytes, indices);
+                    }
+                    AxisRange::Index(i) => {
+                        bytes.push(3);
+                        bytes.extend_from_slice(&(*i as i64).to_le_bytes());
+                    }
+                    AxisRange::Span(start, stop, step) => {
+                        bytes.push(4);
+
+                        for bound in [start, stop] {
+                            bytes.push(bound.is_some() as u8);
+                            let bound = bound.unwrap_or_default() as i64;
+                            bytes.extend_from_slice(&bound.to_le_bytes());
+                        }
+
+                        put_usize(bytes, *step);
+                    }
+                }
+            }
+        }
+        GraphNode::Transpose(input, permutation) => {
+            bytes.push(9);
+            put_usize(bytes, *input);
+            bytes.push(permutation.is_some() as u8);
+            put_list(bytes, permutation.as_deref().unwrap_or_default());
+        }
+    }
+}
+
+fn decode_node<T: CType>(reader: &mut Reader) -> Result<GraphNode<T>, Error> {
+    match reader.u8()? {
+        0 => Ok(GraphNode::Input(reader.list()?)),
+        1 => {
+            let op = op_from_code(&UNARY_OPS, reader.u8()?)?;
+            Ok(GraphNode::Unary(op, reader.usize()?))
+        }
+        2 => {
+            let op = op_from_code(&DUAL_OPS, reader.u8()?)?;
+            Ok(GraphNode::Dual(op, reader.usize()?, reader.usize()?))
+        }
+        3 => {
+            let op = op_from_code(&DUAL_OPS, reader.u8()?)?;
+            let input = reader.usize()?;
+            let scalar = decode_bytes::<T>(reader.take(mem::size_of::<T>())?, &[1], true)?;
+            Ok(GraphNode::Scalar(op, input, scalar[0]))
+        }
+        4 => Ok(GraphNode::MatMul(reader.usize()?, reader.usize()?)),
+        5 => {
+            let op = op_from_code(&REDUCE_OPS, reader.u8()?)?;
+            let input = reader.usize()?;
+            let axes = reader.list()?;
+            Ok(GraphNode::Reduce(op, input, axes, reader.u8()? != 0))
+        }
+        6 => Ok(GraphNode::Broadcast(reader.usize()?, reader.list()?)),
+        7 => Ok(GraphNode::Reshape(reader.usize()?, reader.list()?)),
+        8 => {
+            let input = reader.usize()?;
+
+            let range = (0..reader.u32()?)
+                .map(|_| match reader.u8()? {
+                    0 => Ok(AxisRange::At(reader.usize()?)),
+                    1 => Ok(AxisRange::In(
+                        reader.usize()?,
+                        reader.usize()?,
+                        reader.usize()?,
+                    )),
+                    2 => Ok(AxisRange::Of(reader.list()?)),
+                    3 => Ok(AxisRange::Index(reader.i64()? as isize)),
+                    4 => {
+                        let mut bound = || -> Result<Option<isize>, Error> {
+                            let is_some = reader.u8()? != 0;
+                            let bound = reader.i64()? as isize;
+                            Ok(Some(bound).filter(|_| is_some))
+                        };
+
+                        let (start, stop) = (bound()?, bound()?);
+                        Ok(AxisRange::Span(start, stop, reader.usize()?))
+                    }
+                    tag => Err(Error::Interface(format!(
+                        "unknown axis range tag {tag} in a graph definition"
+                    ))),
+                })
+                .collect::<Result<Range, Error>>()?;
+
+            Ok(GraphNode::Slice(input, range))
+        }
+        9 => {
+            let input = reader.usize()?;
+            let is_some = reader.u8()? != 0;
+            let permutation = reader.list()?;
+            Ok(GraphNode::Transpose(
+                input,
+                Some(permutation).filter(|_| is_some),
+            ))
+        }
+        tag => Err(Error::Interface(format!(
+            "unknown node tag {tag} in a graph definition"
+        ))),
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if len <= self.bytes.len() {
+            let (taken, rest) = self.bytes.split_at(len);
+            self.bytes = rest;
+            Ok(taken)
+        } else {
+            Err(Error::Interface(
+                "unexpected end of a graph definition".to_string(),
+            ))
+        }
+    }
+
+    fn u8(&mut self) -> Result<u8, Error> {
+        self.take(1).map(|bytes| bytes[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, Error> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes(bytes.try_into().expect("u32")))
+    }
+
+    fn i64(&mut self) -> Result<i64, Error> {
+        let bytes = self.take(8)?;
+        Ok(i64::from_le_bytes(bytes.try_into().expect("i64")))
+    }
+
+    fn usize(&mut self) -> Result<usize, Error> {
+        let bytes = self.take(8)?;
+        usize::try_from(u64::from_le_bytes(bytes.try_into().expect("u64")))
+            .map_err(|_| Error::Bounds("graph definition index is too large".into()))
+    }
+
+    fn list(&mut self) -> Result<SmallVec<[usize; 8]>, Error> {
+        (0..self.u32()?).map(|_| self.usize()).collect()
+    }
+}
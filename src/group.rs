@@ -0,0 +1,186 @@
+//! Group-by aggregation and joins
+
+use std::cmp::Ordering;
+
+use crate::access::Accessor;
+use crate::array::Array;
+use crate::host::ops::sort_order;
+use crate::{
+    shape, slice, ArrayAccess, ArrayBuf, AxisRange, CType, Error, NDArray, NDArrayCompare,
+    NDArrayGather, NDArrayMath, NDArrayRead, NDArraySort, NDArrayTransform, Platform, RaggedArray,
+};
+
+/// An aggregate of each group of values with the same key
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Aggregate {
+    /// The number of values in each group
+    Count,
+    /// The largest value in each group
+    Max,
+    /// The mean of each group, which is truncated for an integer type
+    Mean,
+    /// The smallest value in each group
+    Min,
+    /// The sum of each group
+    Sum,
+}
+
+/// Group the 1D `values` by the 1D `keys` with the same length, and aggregate each group with
+/// `agg`, returning the unique keys in ascending order together with the aggregate of each.
+///
+/// The values are stably sorted by key and reduced per group on their platform. Only the
+/// boundaries between groups are read back, to find the number of groups.
+pub fn group_aggregate<K, T, KA, KP, VA, VP>(
+    keys: Array<K, KA, KP>,
+    values: Array<T, VA, VP>,
+    agg: Aggregate,
+) -> Result<(ArrayAccess<K>, ArrayAccess<T>), Error>
+where
+    K: CType,
+    T: CType,
+    Accessor<K>: From<KA>,
+    Accessor<T>: From<VA>,
+    Platform: From<KP> + From<VP>,
+{
+    aggregate(ArrayAccess::from(keys), ArrayAccess::from(values), agg)
+}
+
+fn aggregate<K: CType, T: CType>(
+    keys: ArrayAccess<K>,
+    values: ArrayAccess<T>,
+    agg: Aggregate,
+) -> Result<(ArrayAccess<K>, ArrayAccess<T>), Error> {
+    if keys.ndim() != 1 || keys.shape() != values.shape() || keys.size() == 0 {
+        return Err(Error::Bounds(format!(
+            "cannot group values with shape {:?} by keys with shape {:?}",
+            values.shape(),
+            keys.shape()
+        )));
+    }
+
+    let size = keys.size();
+
+    let order = ArrayAccess::from(keys.clone().argsort(0)?);
+    let keys = ArrayBuf::copy(&keys.gather(order.clone(), 0)?)?;
+    let values = ArrayAccess::from(values.gather(order, 0)?);
+
+    // a group starts wherever a sorted key differs from the one before it
+    let offsets = if size == 1 {
+        vec![0, 1]
+    } else {
+        let next = keys.clone().slice(slice![AxisRange::In(1, size, 1)])?;
+        let prev = keys.clone().slice(slice![AxisRange::In(0, size - 1, 1)])?;
+        let starts = next.ne(prev)?;
+        let starts = starts.buffer()?.to_slice()?;
+
+        let mut offsets = vec![0u64];
+        offsets.extend((1..size as u64).filter(|i| starts[(*i - 1) as usize] != 0));
+        offsets.push(size as u64);
+        offsets
+    };
+
+    let groups = offsets.len() - 1;
+
+    let starts = ArrayBuf::new(offsets[..groups].to_vec(), shape![groups])?;
+    let unique = ArrayAccess::from(keys.gather(starts, 0)?);
+
+    let offsets = ArrayBuf::new(offsets, shape![groups + 1])?;
+    let ragged = RaggedArray::new(offsets, values)?;
+
+    let counts = || {
+        let counts = ragged.lengths()?;
+        let counts = counts.into_iter().map(T::from_usize).collect::<Vec<T>>();
+        ArrayBuf::new(counts, shape![groups])
+    };
+
+    let aggregates = match agg {
+        Aggregate::Count => ArrayAccess::from(counts()?),
+        Aggregate::Max => ArrayAccess::from(ragged.max()?),
+        Aggregate::Mean => ArrayAccess::from(ragged.sum()?.div(counts()?)?),
+        Aggregate::Min => ArrayAccess::from(ragged.min()?),
+        Aggregate::Sum => ArrayAccess::from(ragged.sum()?),
+    };
+
+    Ok((unique, aggregates))
+}
+
+/// Align the 1D `left` and `right` keys by value, returning the indices `(l, r)` of every pair
+/// of equal keys (i.e. an inner join) ordered by key, then by `l`, then by `r`.
+///
+/// Each array of indices can be passed to [`NDArrayGather::gather`] to align the rows of
+/// arrays keyed by `left` and `right`. Both key arrays are sorted on their platform and only
+/// the sorted keys and their order are read back to merge them. A NaN key never matches.
+pub fn inner_join<T, LA, LP, RA, RP>(
+    left: Array<T, LA, LP>,
+    right: Array<T, RA, RP>,
+) -> Result<(ArrayAccess<u64>, ArrayAccess<u64>), Error>
+where
+    T: CType,
+    Accessor<T>: From<LA> + From<RA>,
+    Platform: From<LP> + From<RP>,
+{
+    join(ArrayAccess::from(left), ArrayAccess::from(right))
+}
+
+fn join<T: CType>(
+    left: ArrayAccess<T>,
+    right: ArrayAccess<T>,
+) -> Result<(ArrayAccess<u64>, ArrayAccess<u64>), Error> {
+    if left.ndim() != 1 || right.ndim() != 1 {
+        return Err(Error::Bounds(format!(
+            "cannot join keys with shape {:?} to keys with shape {:?}",
+            left.shape(),
+            right.shape()
+        )));
+    }
+
+    let mut pairs = (vec![], vec![]);
+
+    if left.size() > 0 && right.size() > 0 {
+        let (l_order, l_keys) = sorted(left)?;
+        let (r_order, r_keys) = sorted(right)?;
+
+        let (l_order, l_keys) = (l_order.buffer()?, l_keys.buffer()?);
+        let (r_order, r_keys) = (r_order.buffer()?, r_keys.buffer()?);
+
+        let (l_order, l_keys) = (l_order.to_slice()?, l_keys.to_slice()?);
+        let (r_order, r_keys) = (r_order.to_slice()?, r_keys.to_slice()?);
+
+        let (mut i, mut j) = (0, 0);
+
+        while i < l_keys.len() && j < r_keys.len() {
+            match sort_order(&l_keys[i], &r_keys[j]) {
+                Ordering::Less => i += 1,
+                Ordering::Greater => j += 1,
+                Ordering::Equal => {
+                    let key = l_keys[i];
+                    let l_end = i + l_keys[i..].iter().take_while(|k| **k == key).count();
+                    let r_end = j + r_keys[j..].iter().take_while(|k| **k == key).count();
+
+                    for l in &l_order[i..l_end] {
+                        for r in &r_order[j..r_end] {
+                            pairs.0.push(*l);
+                            pairs.1.push(*r);
+                        }
+                    }
+
+                    // a NaN key is not equal to itself, so skip it rather than stall
+                    (i, j) = (l_end.max(i + 1), r_end.max(j + 1));
+                }
+            }
+        }
+    }
+
+    let size = pairs.0.len();
+    let left = ArrayBuf::new(pairs.0, shape![size])?;
+    let right = ArrayBuf::new(pairs.1, shape![size])?;
+
+    Ok((ArrayAccess::from(left), ArrayAccess::from(right)))
+}
+
+/// Sort the 1D `keys`, returning their (stable) order and the sorted keys.
+fn sorted<T: CType>(keys: ArrayAccess<T>) -> Result<(ArrayAccess<u64>, ArrayAccess<T>), Error> {
+    let order = ArrayAccess::from(ArrayBuf::copy(&keys.clone().argsort(0)?)?);
+    let keys = ArrayAccess::from(ArrayBuf::copy(&keys.gather(order.clone(), 0)?)?);
+    Ok((order, keys))
+}
@@ -0,0 +1,83 @@
+//! Owned array handles which can be sent between threads
+
+use std::fmt;
+use std::ops::Deref;
+
+use crate::access::{AccessBuf, Accessor};
+use crate::array::Array;
+use crate::platform::{Convert, Platform, PlatformInstance};
+use crate::{ArrayAccess, CType, Error, NDArrayRead};
+
+/// An owned, type-erased handle to an array which is `Send + Sync + 'static`,
+/// for sending arrays across threads and channels.
+///
+/// Every [`Array`] is `Send + Sync`, since every [`crate::Access`] implementation must be,
+/// but an array is only `'static` if its accessor does not borrow its data
+/// (e.g. an [`AccessBuf`] of a slice or an op which borrows another array).
+/// Use [`ArrayHandle::new`] to wrap an array which owns its data without copying,
+/// or [`ArrayHandle::materialize`] to copy the data of any array into a new handle.
+///
+/// Cloning a handle does not copy its data.
+#[derive(Clone)]
+pub struct ArrayHandle<T: CType> {
+    array: ArrayAccess<T>,
+}
+
+impl<T: CType> ArrayHandle<T> {
+    /// Wrap an array which owns its data in a new handle, without reading or copying it.
+    /// An unevaluated op will be evaluated lazily, on whichever thread reads it.
+    pub fn new<A, P>(array: Array<T, A, P>) -> Self
+    where
+        Accessor<T>: From<A>,
+        Platform: From<P>,
+    {
+        Self {
+            array: ArrayAccess::from(array),
+        }
+    }
+
+    /// Evaluate the given `array` and copy its data into a new handle.
+    /// This is the only way to construct a handle to an array which borrows its data.
+    pub fn materialize<A: NDArrayRead<DType = T>>(array: &A) -> Result<Self, Error> {
+        let buffer = array.buffer()?;
+        let platform = Platform::select(buffer.len());
+        let buffer = platform.convert(buffer)?;
+
+        let array = Array::<T, AccessBuf<_>, Platform>::new(buffer, array.shape())?;
+
+        Ok(Self::new(array))
+    }
+
+    /// Unwrap the array referenced by this handle.
+    pub fn into_inner(self) -> ArrayAccess<T> {
+        self.array
+    }
+}
+
+impl<T: CType> Deref for ArrayHandle<T> {
+    type Target = ArrayAccess<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.array
+    }
+}
+
+impl<T: CType> From<ArrayHandle<T>> for ArrayAccess<T> {
+    fn from(handle: ArrayHandle<T>) -> Self {
+        handle.array
+    }
+}
+
+impl<T: CType> fmt::Debug for ArrayHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a handle to {:?}", self.array)
+    }
+}
+
+// fail to compile if a change to an accessor would make a handle unsafe to send between threads
+const _: fn() = || {
+    fn assert_send_sync_static<H: Send + Sync + 'static>() {}
+
+    assert_send_sync_static::<ArrayHandle<f32>>();
+    assert_send_sync_static::<ArrayHandle<u64>>();
+};
@@ -1,4 +1,4 @@
-use std::borrow::Borrow;
+use std::borrow::{Borrow, BorrowMut};
 use std::fmt;
 use std::ops::Deref;
 
@@ -27,6 +27,10 @@ impl<T: CType> BufferInstance<T> for StackVec<T> {
 }
 
 impl<T: CType> BufferMut<T> for StackVec<T> {
+    fn host(&mut self) -> Result<&mut [T], Error> {
+        Ok(self.as_mut_slice())
+    }
+
     fn write<'a>(&mut self, data: BufferConverter<'a, T>) -> Result<(), Error> {
         self.as_mut_slice().write(data)
     }
@@ -55,6 +59,10 @@ impl<T: CType> BufferInstance<T> for Vec<T> {
 }
 
 impl<T: CType> BufferMut<T> for Vec<T> {
+    fn host(&mut self) -> Result<&mut [T], Error> {
+        Ok(self.as_mut_slice())
+    }
+
     fn write<'a>(&mut self, data: BufferConverter<'a, T>) -> Result<(), Error> {
         self.as_mut_slice().write(data)
     }
@@ -102,6 +110,10 @@ impl<'a, T: CType> BufferInstance<T> for &'a mut [T] {
 }
 
 impl<'a, T: CType> BufferMut<T> for &'a mut [T] {
+    fn host(&mut self) -> Result<&mut [T], Error> {
+        Ok(self)
+    }
+
     fn write<'b>(&mut self, data: BufferConverter<'b, T>) -> Result<(), Error> {
         if data.len() == self.len() {
             let data = data.to_slice()?;
@@ -165,6 +177,12 @@ impl<T> Borrow<[T]> for Buffer<T> {
     }
 }
 
+impl<T> BorrowMut<[T]> for Buffer<T> {
+    fn borrow_mut(&mut self) -> &mut [T] {
+        self.as_mut()
+    }
+}
+
 impl<T> AsMut<[T]> for Buffer<T> {
     fn as_mut(&mut self) -> &mut [T] {
         match self {
@@ -195,6 +213,10 @@ impl<T: CType> BufferInstance<T> for Buffer<T> {
 }
 
 impl<T: CType> BufferMut<T> for Buffer<T> {
+    fn host(&mut self) -> Result<&mut [T], Error> {
+        Ok(self.as_mut())
+    }
+
     fn write<'a>(&mut self, data: BufferConverter<'a, T>) -> Result<(), Error> {
         match self {
             Self::Heap(buf) => buf.write(data),
@@ -8,6 +8,8 @@ pub use platform::*;
 mod buffer;
 pub mod ops;
 mod platform;
+#[cfg(unix)]
+pub mod shared;
 
 pub type ArrayBuf<T> = crate::array::Array<T, AccessBuf<Buffer<T>>, Host>;
 
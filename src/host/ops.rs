@@ -1,16 +1,23 @@
+use std::cmp::Ordering;
 use std::f32::consts::PI;
 use std::iter;
 use std::marker::PhantomData;
+use std::mem::{self, ManuallyDrop};
 
 use rand::Rng;
-use rayon::join;
 use rayon::prelude::*;
-
-use crate::access::Access;
-use crate::ops::{Enqueue, Op, ReadValue, SliceSpec, ViewSpec};
+use rayon::{current_num_threads, join};
+
+use crate::access::{Access, AccessBuf};
+use crate::conv::upsample_value;
+use crate::introspect::OpPlan;
+use crate::ops::{
+    ignore_nan, ignore_nan_identity, max_identity, min_identity, BroadcastDual, DualBroadcast,
+    Enqueue, Factor, Factorization, Op, ReadValue, SliceSpec, ViewSpec,
+};
 use crate::{
-    stackvec, strides_for, AccessMut, Axes, BufferConverter, CType, Error, Float, Range, Shape,
-    Strides,
+    config, stackvec, AccessMut, Axes, BufferConverter, CType, ConvSpec, Error, Float,
+    Interpolation, Range, Shape,
 };
 
 use super::buffer::Buffer;
@@ -27,6 +34,89 @@ macro_rules! host_enqueue {
     };
 }
 
+pub struct Bitcast<A, IT, OT> {
+    access: A,
+    dtype: PhantomData<(IT, OT)>,
+}
+
+impl<A, IT: CType, OT: CType> Bitcast<A, IT, OT> {
+    pub fn new(access: A) -> Result<Self, Error> {
+        if mem::size_of::<IT>() == mem::size_of::<OT>()
+            && mem::align_of::<IT>() == mem::align_of::<OT>()
+        {
+            Ok(Self {
+                access,
+                dtype: PhantomData,
+            })
+        } else {
+            Err(Error::Unsupported(format!(
+                "cannot reinterpret {} as {} since they have different sizes",
+                IT::TYPE,
+                OT::TYPE
+            )))
+        }
+    }
+
+    fn cast_slice(slice: &[IT]) -> &[OT] {
+        // SAFETY: the constructor guarantees that IT and OT have the same size and alignment,
+        // and every CType is a primitive number for which every bit pattern is valid
+        unsafe { std::slice::from_raw_parts(slice.as_ptr() as *const OT, slice.len()) }
+    }
+}
+
+impl<A: Access<IT>, IT: CType, OT: CType> Op for Bitcast<A, IT, OT> {
+    fn size(&self) -> usize {
+        self.access.size()
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan()]
+    }
+}
+
+impl<A: Access<IT>, IT: CType, OT: CType> Enqueue<Heap, OT> for Bitcast<A, IT, OT> {
+    type Buffer = Vec<OT>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        match self.access.read()?.to_slice()? {
+            SliceConverter::Heap(vec) => {
+                // reinterpret an owned buffer in place, without copying
+                let mut vec = ManuallyDrop::new(vec);
+                let (ptr, len, capacity) = (vec.as_mut_ptr(), vec.len(), vec.capacity());
+
+                // SAFETY: the constructor guarantees that IT and OT have the same size and alignment
+                Ok(unsafe { Vec::from_raw_parts(ptr as *mut OT, len, capacity) })
+            }
+            slice => Ok(Self::cast_slice(&slice).to_vec()),
+        }
+    }
+}
+
+impl<A: Access<IT>, IT: CType, OT: CType> Enqueue<Stack, OT> for Bitcast<A, IT, OT> {
+    type Buffer = StackVec<OT>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let slice = self.access.read()?.to_slice()?;
+        Ok(StackVec::from_slice(Self::cast_slice(&slice)))
+    }
+}
+
+impl<A: Access<IT>, IT: CType, OT: CType> Enqueue<Host, OT> for Bitcast<A, IT, OT> {
+    type Buffer = Buffer<OT>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        host_enqueue!(self, self.size() < VEC_MIN_SIZE, OT)
+    }
+}
+
+impl<A: Access<IT>, IT: CType, OT: CType> ReadValue<Host, OT> for Bitcast<A, IT, OT> {
+    fn read_value(&self, offset: usize) -> Result<OT, Error> {
+        self.access
+            .read_value(offset)
+            .map(|n| Self::cast_slice(&[n])[0])
+    }
+}
+
 pub struct Cast<A, IT, OT> {
     access: A,
     dtype: PhantomData<(IT, OT)>,
@@ -45,6 +135,10 @@ impl<A: Access<IT>, IT: CType, OT: CType> Op for Cast<A, IT, OT> {
     fn size(&self) -> usize {
         self.access.size()
     }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan()]
+    }
 }
 
 impl<A: Access<IT>, IT: CType, OT: CType> Enqueue<Heap, OT> for Cast<A, IT, OT> {
@@ -101,9 +195,27 @@ impl<A: Access<IT>, IT: CType, OT: CType> ReadValue<Host, OT> for Cast<A, IT, OT
 pub struct Dual<L, R, IT, OT> {
     left: L,
     right: R,
+    broadcast: DualBroadcast,
     zip: fn(IT, IT) -> OT,
 }
 
+impl<L, R, IT, OT> Dual<L, R, IT, OT> {
+    fn new(left: L, right: R, zip: fn(IT, IT) -> OT) -> Self {
+        Self {
+            left,
+            right,
+            broadcast: DualBroadcast::default(),
+            zip,
+        }
+    }
+}
+
+impl<L, R, IT, OT> BroadcastDual for Dual<L, R, IT, OT> {
+    fn broadcast(self, broadcast: DualBroadcast) -> Result<Self, Error> {
+        Ok(Self { broadcast, ..self })
+    }
+}
+
 impl<L, R, IT, OT> Op for Dual<L, R, IT, OT>
 where
     L: Access<IT>,
@@ -112,150 +224,135 @@ where
     OT: CType,
 {
     fn size(&self) -> usize {
-        self.left.size()
+        self.broadcast.size(self.left.size())
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.left.plan(), self.right.plan()]
     }
 }
 
 // arithmetic
 impl<L, R, T: CType> Dual<L, R, T, T> {
     pub fn add(left: L, right: R) -> Self {
-        Self {
-            left,
-            right,
-            zip: T::add,
-        }
+        Self::new(left, right, T::add)
     }
 
     pub fn div(left: L, right: R) -> Self {
-        Self {
-            left,
-            right,
-            zip: T::div,
-        }
+        Self::new(left, right, T::div)
     }
 
     pub fn log(left: L, right: R) -> Self {
-        Self {
-            left,
-            right,
-            zip: |a, b| T::from_float(a.to_float().log(b.to_float())),
-        }
+        Self::new(left, right, |a, b| {
+            T::from_float(a.to_float().log(b.to_float()))
+        })
+    }
+
+    pub fn logaddexp(left: L, right: R) -> Self {
+        Self::new(left, right, |a, b| {
+            T::from_float(a.to_float().logaddexp(b.to_float()))
+        })
     }
 
     pub fn mul(left: L, right: R) -> Self {
-        Self {
-            left,
-            right,
-            zip: T::mul,
-        }
+        Self::new(left, right, T::mul)
     }
 
     pub fn pow(left: L, right: R) -> Self {
-        Self {
-            left,
-            right,
-            zip: T::pow,
-        }
+        Self::new(left, right, T::pow)
     }
 
     pub fn rem(left: L, right: R) -> Self {
-        Self {
-            left,
-            right,
-            zip: T::rem,
-        }
+        Self::new(left, right, T::rem)
+    }
+
+    pub fn step(arg: L, threshold: R) -> Self {
+        Self::new(
+            arg,
+            threshold,
+            |n, threshold| {
+                if n < threshold {
+                    T::ZERO
+                } else {
+                    T::ONE
+                }
+            },
+        )
     }
 
     pub fn sub(left: L, right: R) -> Self {
-        Self {
-            left,
-            right,
-            zip: T::sub,
-        }
+        Self::new(left, right, T::sub)
+    }
+}
+
+// trigonometry
+impl<L, R, T: CType> Dual<L, R, T, T::Float> {
+    pub fn atan2(y: L, x: R) -> Self {
+        Self::new(y, x, |y, x| y.to_float().atan2(x.to_float()))
+    }
+
+    pub fn hypot(left: L, right: R) -> Self {
+        Self::new(left, right, |l, r| l.to_float().hypot(r.to_float()))
     }
 }
 
 // boolean operations
 impl<L, R, T: CType> Dual<L, R, T, u8> {
     pub fn and(left: L, right: R) -> Self {
-        Self {
-            left,
-            right,
-            zip: |l, r| if l != T::ZERO && r != T::ZERO { 1 } else { 0 },
-        }
+        Self::new(left, right, |l, r| {
+            if l != T::ZERO && r != T::ZERO {
+                1
+            } else {
+                0
+            }
+        })
     }
 
     pub fn or(left: L, right: R) -> Self {
-        Self {
-            left,
-            right,
-            zip: |l, r| if l != T::ZERO || r != T::ZERO { 1 } else { 0 },
-        }
+        Self::new(left, right, |l, r| {
+            if l != T::ZERO || r != T::ZERO {
+                1
+            } else {
+                0
+            }
+        })
     }
 
     pub fn xor(left: L, right: R) -> Self {
-        Self {
-            left,
-            right,
-            zip: |l, r| {
-                if (l != T::ZERO) ^ (r != T::ZERO) {
-                    1
-                } else {
-                    0
-                }
-            },
-        }
+        Self::new(left, right, |l, r| {
+            if (l != T::ZERO) ^ (r != T::ZERO) {
+                1
+            } else {
+                0
+            }
+        })
     }
 }
 
 // comparison
 impl<L, R, T: CType> Dual<L, R, T, u8> {
     pub fn eq(left: L, right: R) -> Self {
-        Self {
-            left,
-            right,
-            zip: |l, r| if l == r { 1 } else { 0 },
-        }
+        Self::new(left, right, |l, r| if l == r { 1 } else { 0 })
     }
 
     pub fn ge(left: L, right: R) -> Self {
-        Self {
-            left,
-            right,
-            zip: |l, r| if l >= r { 1 } else { 0 },
-        }
+        Self::new(left, right, |l, r| if l >= r { 1 } else { 0 })
     }
 
     pub fn gt(left: L, right: R) -> Self {
-        Self {
-            left,
-            right,
-            zip: |l, r| if l > r { 1 } else { 0 },
-        }
+        Self::new(left, right, |l, r| if l > r { 1 } else { 0 })
     }
 
     pub fn le(left: L, right: R) -> Self {
-        Self {
-            left,
-            right,
-            zip: |l, r| if l <= r { 1 } else { 0 },
-        }
+        Self::new(left, right, |l, r| if l <= r { 1 } else { 0 })
     }
 
     pub fn lt(left: L, right: R) -> Self {
-        Self {
-            left,
-            right,
-            zip: |l, r| if l < r { 1 } else { 0 },
-        }
+        Self::new(left, right, |l, r| if l < r { 1 } else { 0 })
     }
 
     pub fn ne(left: L, right: R) -> Self {
-        Self {
-            left,
-            right,
-            zip: |l, r| if l != r { 1 } else { 0 },
-        }
+        Self::new(left, right, |l, r| if l != r { 1 } else { 0 })
     }
 }
 
@@ -271,7 +368,17 @@ where
     fn enqueue(&self) -> Result<Self::Buffer, Error> {
         let left = self.left.read()?.to_slice()?;
         let right = self.right.read()?.to_slice()?;
-        exec_dual(self.zip, left, right)
+
+        if self.broadcast.is_none() {
+            return exec_dual(self.zip, left, right);
+        }
+
+        let output = (0..self.size())
+            .map(|offset| self.broadcast.offsets(offset))
+            .map(|(l, r)| (self.zip)(left[l], right[r]))
+            .collect();
+
+        Ok(output)
     }
 }
 
@@ -286,7 +393,18 @@ where
 
     fn enqueue(&self) -> Result<Self::Buffer, Error> {
         let (left, right) = try_join_read(&self.left, &self.right)?;
-        exec_dual_parallel(self.zip, left, right)
+
+        if self.broadcast.is_none() {
+            return exec_dual_parallel(self.zip, left, right);
+        }
+
+        let output = (0..self.size())
+            .into_par_iter()
+            .map(|offset| self.broadcast.offsets(offset))
+            .map(|(l, r)| (self.zip)(left[l], right[r]))
+            .collect();
+
+        Ok(output)
     }
 }
 
@@ -312,7 +430,14 @@ where
     OT: CType,
 {
     fn read_value(&self, offset: usize) -> Result<OT, Error> {
-        try_join_value(&self.left, &self.right, offset).map(|(l, r)| (self.zip)(l, r))
+        let (left, right) = self.broadcast.offsets(offset);
+
+        let (l, r) = join(
+            || self.left.read_value(left),
+            || self.right.read_value(right),
+        );
+
+        Ok((self.zip)(l?, r?))
     }
 }
 
@@ -346,6 +471,10 @@ where
         debug_assert_eq!(self.cond.size(), self.or_else.size());
         self.cond.size()
     }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.cond.plan(), self.then.plan(), self.or_else.plan()]
+    }
 }
 
 impl<A, L, R, T> Enqueue<Stack, T> for Cond<A, L, R, T>
@@ -466,145 +595,233 @@ where
     }
 }
 
-pub struct Linear<T> {
-    start: T,
-    step: f64,
-    size: usize,
+pub struct CondScalar<A, T> {
+    cond: A,
+    then: T,
+    or_else: T,
 }
 
-impl<T> Linear<T> {
-    pub fn new(start: T, step: f64, size: usize) -> Self {
-        Self { start, step, size }
+impl<A, T> CondScalar<A, T> {
+    pub fn new(cond: A, then: T, or_else: T) -> Self {
+        Self {
+            cond,
+            then,
+            or_else,
+        }
     }
+}
 
+impl<A: Access<u8>, T: CType> CondScalar<A, T> {
     #[inline]
-    fn value_at(&self, offset: usize) -> T
-    where
-        T: CType,
-    {
-        T::add(self.start, T::from_f64((offset as f64) * self.step))
+    fn select(&self, cond: u8) -> T {
+        if cond != 0 {
+            self.then
+        } else {
+            self.or_else
+        }
     }
 }
 
-impl<T: Send + Sync> Op for Linear<T> {
+impl<A: Access<u8>, T: CType> Op for CondScalar<A, T> {
     fn size(&self) -> usize {
-        self.size
+        self.cond.size()
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.cond.plan()]
     }
 }
 
-impl<T: CType> Enqueue<Stack, T> for Linear<T> {
+impl<A: Access<u8>, T: CType> Enqueue<Stack, T> for CondScalar<A, T> {
     type Buffer = StackVec<T>;
 
     fn enqueue(&self) -> Result<Self::Buffer, Error> {
-        let start = self.start.to_f64();
-
-        let buffer = (0..self.size)
-            .into_iter()
-            .map(|i| i as f64)
-            .map(|i| i * self.step)
-            .map(|o| start + o)
-            .map(T::from_f64)
-            .collect();
-
-        Ok(buffer)
+        let cond = self.cond.read()?.to_slice()?;
+        let output = cond.iter().copied().map(|cond| self.select(cond)).collect();
+        Ok(output)
     }
 }
 
-impl<T: CType> Enqueue<Heap, T> for Linear<T> {
+impl<A: Access<u8>, T: CType> Enqueue<Heap, T> for CondScalar<A, T> {
     type Buffer = Vec<T>;
 
     fn enqueue(&self) -> Result<Self::Buffer, Error> {
-        let buffer = (0..self.size)
+        let cond = self.cond.read()?.to_slice()?;
+
+        let output = cond
             .into_par_iter()
-            .map(|offset| self.value_at(offset))
+            .copied()
+            .map(|cond| self.select(cond))
             .collect();
 
-        Ok(buffer)
+        Ok(output)
     }
 }
 
-impl<T: CType> Enqueue<Host, T> for Linear<T> {
+impl<A: Access<u8>, T: CType> Enqueue<Host, T> for CondScalar<A, T> {
     type Buffer = Buffer<T>;
 
     fn enqueue(&self) -> Result<Self::Buffer, Error> {
-        host_enqueue!(self, self.size < VEC_MIN_SIZE, T)
+        host_enqueue!(self, self.size() < VEC_MIN_SIZE, T)
     }
 }
 
-impl<T: CType> ReadValue<Host, T> for Linear<T> {
+impl<A: Access<u8>, T: CType> ReadValue<Host, T> for CondScalar<A, T> {
     fn read_value(&self, offset: usize) -> Result<T, Error> {
-        Ok(self.value_at(offset))
+        self.cond.read_value(offset).map(|cond| self.select(cond))
     }
 }
 
-pub struct MatDiag<A, T> {
-    access: A,
-    dim: usize,
-    batch_size: usize,
+pub struct Conv<I, K, T> {
+    input: I,
+    kernel: K,
+    input_shape: [usize; 5],
+    kernel_shape: [usize; 5],
+    output_shape: [usize; 5],
+    spec: ConvSpec<3>,
     dtype: PhantomData<T>,
 }
 
-impl<A, T> MatDiag<A, T> {
-    pub fn new(access: A, batch_size: usize, dim: usize) -> Self {
-        Self {
-            access,
-            dim,
-            batch_size,
+impl<I, K, T> Conv<I, K, T> {
+    pub fn new(
+        input: I,
+        kernel: K,
+        input_shape: [usize; 5],
+        kernel_shape: [usize; 5],
+        spec: ConvSpec<3>,
+    ) -> Result<Self, Error> {
+        let output_shape = spec.output_shape(&input_shape, &kernel_shape)?;
+
+        Ok(Self {
+            input,
+            kernel,
+            input_shape,
+            kernel_shape,
+            output_shape: [
+                output_shape[0],
+                output_shape[1],
+                output_shape[2],
+                output_shape[3],
+                output_shape[4],
+            ],
+            spec,
             dtype: PhantomData,
-        }
+        })
+    }
+
+    #[inline]
+    fn taps(&self, offset: usize) -> impl Iterator<Item = (usize, usize)> {
+        self.spec.taps(
+            self.input_shape,
+            self.kernel_shape,
+            self.output_shape,
+            offset,
+        )
     }
 }
 
-impl<A: Access<T>, T: CType> Op for MatDiag<A, T> {
+impl<I, K, T> Op for Conv<I, K, T>
+where
+    I: Access<T>,
+    K: Access<T>,
+    T: CType,
+{
     fn size(&self) -> usize {
-        debug_assert_eq!(self.access.size(), self.batch_size * self.dim * self.dim);
-        self.batch_size * self.dim
+        self.output_shape.iter().product()
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.input.plan(), self.kernel.plan()]
     }
 }
 
-impl<A: Access<T>, T: CType> Enqueue<Heap, T> for MatDiag<A, T> {
-    type Buffer = Vec<T>;
+impl<I, K, T> Enqueue<Stack, T> for Conv<I, K, T>
+where
+    I: Access<T>,
+    K: Access<T>,
+    T: CType,
+{
+    type Buffer = StackVec<T>;
 
     fn enqueue(&self) -> Result<Self::Buffer, Error> {
-        let input = self.access.read()?.to_slice()?;
+        let input = self.input.read()?.to_slice()?;
+        let kernel = self.kernel.read()?.to_slice()?;
 
-        let diagonals = input
-            .par_chunks_exact(self.dim * self.dim)
-            .map(|matrix| {
-                matrix
-                    .par_chunks_exact(self.dim)
-                    .enumerate()
-                    .map(|(i, row)| row[i])
+        let output = (0..self.size())
+            .map(|offset| {
+                self.taps(offset).fold(T::ZERO, |sum, (i, k)| {
+                    T::add(sum, T::mul(input[i], kernel[k]))
+                })
             })
-            .flatten()
             .collect();
 
-        Ok(diagonals)
+        Ok(output)
     }
 }
 
-impl<A: Access<T>, T: CType> Enqueue<Stack, T> for MatDiag<A, T> {
-    type Buffer = StackVec<T>;
+impl<I, K, T> Enqueue<Heap, T> for Conv<I, K, T>
+where
+    I: Access<T>,
+    K: Access<T>,
+    T: CType,
+{
+    type Buffer = Vec<T>;
 
     fn enqueue(&self) -> Result<Self::Buffer, Error> {
-        let input = self.access.read()?.to_slice()?;
+        let (input, kernel) = try_join_read(&self.input, &self.kernel)?;
 
-        let diagonals = input
-            .chunks_exact(self.dim * self.dim)
-            .map(|matrix| {
-                matrix
-                    .chunks_exact(self.dim)
-                    .enumerate()
-                    .map(|(i, row)| row[i])
-            })
-            .flatten()
-            .collect();
+        let [batch, out_channels, ..] = self.output_shape;
+        let groups = self.spec.groups();
 
-        Ok(diagonals)
+        let group_out = out_channels / groups;
+        let rows = self.kernel_shape[1..].iter().product::<usize>();
+        let cols = self.output_shape[2..].iter().product::<usize>();
+
+        let mut output = Vec::with_capacity(self.size());
+
+        for b in 0..batch {
+            // lower the windows of each group into a [rows, cols] matrix (i.e. "im2col")
+            // so that the convolution of the group is its product with the kernel matrix
+            let windows = (0..(groups * rows * cols))
+                .into_par_iter()
+                .map(|i| {
+                    let (group, row, position) = (i / (rows * cols), (i / cols) % rows, i % cols);
+
+                    self.spec
+                        .window_source(
+                            self.input_shape,
+                            self.kernel_shape,
+                            self.output_shape,
+                            b,
+                            group,
+                            row,
+                            position,
+                        )
+                        .map(|offset| input[offset])
+                        .unwrap_or(T::ZERO)
+                })
+                .collect::<Vec<T>>();
+
+            let product = MatMul::strided(
+                AccessBuf::from(&kernel[..]),
+                AccessBuf::from(windows),
+                [groups, group_out, rows, cols],
+                [group_out * rows, rows * cols],
+            );
+
+            output.extend(Enqueue::<Heap, T>::enqueue(&product)?);
+        }
+
+        Ok(output)
     }
 }
 
-impl<A: Access<T>, T: CType> Enqueue<Host, T> for MatDiag<A, T> {
+impl<I, K, T> Enqueue<Host, T> for Conv<I, K, T>
+where
+    I: Access<T>,
+    K: Access<T>,
+    T: CType,
+{
     type Buffer = Buffer<T>;
 
     fn enqueue(&self) -> Result<Self::Buffer, Error> {
@@ -612,771 +829,4405 @@ impl<A: Access<T>, T: CType> Enqueue<Host, T> for MatDiag<A, T> {
     }
 }
 
-impl<A: Access<T>, T: CType> ReadValue<Host, T> for MatDiag<A, T> {
+impl<I, K, T> ReadValue<Host, T> for Conv<I, K, T>
+where
+    I: Access<T>,
+    K: Access<T>,
+    T: CType,
+{
     fn read_value(&self, offset: usize) -> Result<T, Error> {
-        let batch = offset / self.batch_size;
-        let i = offset % self.batch_size;
-        let source_offset = (batch * self.dim * self.dim) + (i * self.dim) + i;
-        self.access.read_value(source_offset)
+        self.taps(offset).try_fold(T::ZERO, |sum, (i, k)| {
+            let (input, kernel) = (self.input.read_value(i)?, self.kernel.read_value(k)?);
+            Ok(T::add(sum, T::mul(input, kernel)))
+        })
     }
 }
 
-pub struct MatMul<L, R, T> {
-    left: L,
-    right: R,
-    batch_size: usize,
-    dims: [usize; 3],
+pub struct ConvTranspose<I, K, T> {
+    input: I,
+    kernel: K,
+    input_shape: [usize; 4],
+    kernel_shape: [usize; 4],
+    output_shape: [usize; 4],
+    spec: ConvSpec<2>,
     dtype: PhantomData<T>,
 }
 
-impl<L, R, T> MatMul<L, R, T> {
-    pub fn new(left: L, right: R, dims: [usize; 4]) -> Self {
-        let [batch_size, a, b, c] = dims;
-
-        Self {
-            left,
-            right,
-            batch_size,
-            dims: [a, b, c],
+impl<I, K, T> ConvTranspose<I, K, T> {
+    pub fn new(
+        input: I,
+        kernel: K,
+        input_shape: [usize; 4],
+        kernel_shape: [usize; 4],
+        spec: ConvSpec<2>,
+    ) -> Result<Self, Error> {
+        let output_shape = spec.transpose_output_shape(&input_shape, &kernel_shape)?;
+
+        Ok(Self {
+            input,
+            kernel,
+            input_shape,
+            kernel_shape,
+            output_shape: [
+                output_shape[0],
+                output_shape[1],
+                output_shape[2],
+                output_shape[3],
+            ],
+            spec,
             dtype: PhantomData,
-        }
+        })
+    }
+
+    #[inline]
+    fn taps(&self, offset: usize) -> impl Iterator<Item = (usize, usize)> {
+        self.spec.transpose_taps(
+            self.input_shape,
+            self.kernel_shape,
+            self.output_shape,
+            offset,
+        )
     }
 }
 
-impl<L, R, T> Op for MatMul<L, R, T>
+impl<I, K, T> Op for ConvTranspose<I, K, T>
 where
-    L: Send + Sync,
-    R: Send + Sync,
-    T: Send + Sync,
+    I: Access<T>,
+    K: Access<T>,
+    T: CType,
 {
     fn size(&self) -> usize {
-        self.batch_size * self.dims[0] * self.dims[2]
+        self.output_shape.iter().product()
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.input.plan(), self.kernel.plan()]
     }
 }
 
-impl<L, R, T> Enqueue<Stack, T> for MatMul<L, R, T>
+impl<I, K, T> Enqueue<Stack, T> for ConvTranspose<I, K, T>
 where
-    L: Access<T>,
-    R: Access<T>,
+    I: Access<T>,
+    K: Access<T>,
     T: CType,
 {
     type Buffer = StackVec<T>;
 
     fn enqueue(&self) -> Result<Self::Buffer, Error> {
-        let left = self.left.read()?.to_slice()?;
-        let right = self.right.read()?.to_slice()?;
-
-        let [a, b, c] = self.dims;
+        let input = self.input.read()?.to_slice()?;
+        let kernel = self.kernel.read()?.to_slice()?;
 
-        let mut product = StackVec::with_capacity(self.batch_size * a * c);
+        let output = (0..self.size())
+            .map(|offset| {
+                self.taps(offset).fold(T::ZERO, |sum, (i, k)| {
+                    T::add(sum, T::mul(input[i], kernel[k]))
+                })
+            })
+            .collect();
 
-        for _batch in 0..self.batch_size {
-            for x in 0..a {
-                for z in 0..c {
-                    let mut sum = T::ZERO;
+        Ok(output)
+    }
+}
 
-                    for y in 0..b {
-                        let l_offset = (x * b) + y;
-                        let r_offset = (y * c) + z;
-                        sum = T::add(sum, T::mul(left[l_offset], right[r_offset]));
-                    }
+impl<I, K, T> Enqueue<Heap, T> for ConvTranspose<I, K, T>
+where
+    I: Access<T>,
+    K: Access<T>,
+    T: CType,
+{
+    type Buffer = Vec<T>;
 
-                    product.push(sum)
-                }
-            }
-        }
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let (input, kernel) = try_join_read(&self.input, &self.kernel)?;
 
-        debug_assert_eq!(product.len(), self.size());
+        let output = (0..self.size())
+            .into_par_iter()
+            .map(|offset| {
+                self.taps(offset).fold(T::ZERO, |sum, (i, k)| {
+                    T::add(sum, T::mul(input[i], kernel[k]))
+                })
+            })
+            .collect();
 
-        Ok(product)
+        Ok(output)
     }
 }
 
-impl<L, R, T> Enqueue<Heap, T> for MatMul<L, R, T>
+impl<I, K, T> Enqueue<Host, T> for ConvTranspose<I, K, T>
 where
-    L: Access<T>,
-    R: Access<T>,
+    I: Access<T>,
+    K: Access<T>,
     T: CType,
 {
-    type Buffer = Vec<T>;
+    type Buffer = Buffer<T>;
 
     fn enqueue(&self) -> Result<Self::Buffer, Error> {
-        let [a, b, c] = self.dims;
+        host_enqueue!(self, self.size() < VEC_MIN_SIZE, T)
+    }
+}
 
-        let (left, right) = try_join_read(&self.left, &self.right)?;
+impl<I, K, T> ReadValue<Host, T> for ConvTranspose<I, K, T>
+where
+    I: Access<T>,
+    K: Access<T>,
+    T: CType,
+{
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        self.taps(offset).try_fold(T::ZERO, |sum, (i, k)| {
+            let (input, kernel) = (self.input.read_value(i)?, self.kernel.read_value(k)?);
+            Ok(T::add(sum, T::mul(input, kernel)))
+        })
+    }
+}
 
-        // transpose the right matrices
-        let right_size = b * c;
-        let right_matrices = right.par_chunks_exact(right_size).map(|right| {
-            let mut right_t = vec![T::ZERO; right_size];
-            transpose::transpose(right, &mut right_t[..], c, b);
-            right_t
-        });
+pub struct MatchedFilter<I, K, T> {
+    input: I,
+    template: K,
+    dims: [usize; 3],
+    dtype: PhantomData<T>,
+}
 
-        let left_size = a * b;
-        let left_matrices = left.par_chunks_exact(left_size);
+impl<I, K, T> MatchedFilter<I, K, T> {
+    pub fn new(input: I, template: K, dims: [usize; 3]) -> Self {
+        Self {
+            input,
+            template,
+            dims,
+            dtype: PhantomData,
+        }
+    }
 
-        let output_size = a * c;
-        let mut output = Vec::<T>::with_capacity(self.batch_size * output_size);
-        let output_matrices = left_matrices
-            .zip(right_matrices)
-            .map(|(lm, rm)| {
-                let mut out = Vec::<T>::with_capacity(output_size);
+    #[inline]
+    fn window(&self, offset: usize) -> std::ops::Range<usize> {
+        matched_window(self.dims, offset)
+    }
+}
 
-                let product = lm
-                    .par_chunks_exact(b)
-                    .map(|row| {
-                        rm.par_chunks_exact(b).map(move |col| {
-                            // chunk the dot product to encourage the compiler to vectorize
-                            let col = col.par_chunks(8).map(|cc| cc.into_iter().copied());
+impl<I, K, T> Op for MatchedFilter<I, K, T>
+where
+    I: Access<T>,
+    K: Access<T>,
+    T: CType,
+{
+    fn size(&self) -> usize {
+        let [rows, len, width] = self.dims;
+        rows * (len - width + 1)
+    }
 
-                            row.par_chunks(8)
-                                .zip(col)
-                                .map(|(rc, cc)| {
-                                    rc.into_iter()
-                                        .copied()
-                                        .zip(cc)
-                                        .map(|(r, c)| T::mul(r, c))
-                                        .reduce(T::add)
-                                        .expect("sum")
-                                })
-                                .reduce(|| T::ZERO, T::add)
-                        })
-                    })
-                    .flatten();
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.input.plan(), self.template.plan()]
+    }
+}
 
-                out.par_extend(product);
-                out
+impl<I, K, T> Enqueue<Stack, T::Float> for MatchedFilter<I, K, T>
+where
+    I: Access<T>,
+    K: Access<T>,
+    T: CType,
+{
+    type Buffer = StackVec<T::Float>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let input = self.input.read()?.to_slice()?;
+        let template = self.template.read()?.to_slice()?;
+
+        let output = (0..self.size())
+            .map(|offset| {
+                let window = &input[self.window(offset)];
+                normalized_dot(window.iter().copied().zip(template.iter().copied()))
             })
-            .flatten();
+            .collect();
 
-        output.par_extend(output_matrices);
+        Ok(output)
+    }
+}
 
-        debug_assert_eq!(output.len(), self.batch_size * output_size);
+impl<I, K, T> Enqueue<Heap, T::Float> for MatchedFilter<I, K, T>
+where
+    I: Access<T>,
+    K: Access<T>,
+    T: CType,
+{
+    type Buffer = Vec<T::Float>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let (input, template) = try_join_read(&self.input, &self.template)?;
+
+        let output = (0..self.size())
+            .into_par_iter()
+            .map(|offset| {
+                let window = &input[self.window(offset)];
+                normalized_dot(window.iter().copied().zip(template.iter().copied()))
+            })
+            .collect();
 
         Ok(output)
     }
 }
 
-impl<L, R, T> Enqueue<Host, T> for MatMul<L, R, T>
+impl<I, K, T> Enqueue<Host, T::Float> for MatchedFilter<I, K, T>
 where
-    L: Access<T>,
-    R: Access<T>,
+    I: Access<T>,
+    K: Access<T>,
     T: CType,
 {
-    type Buffer = Buffer<T>;
+    type Buffer = Buffer<T::Float>;
 
     fn enqueue(&self) -> Result<Self::Buffer, Error> {
-        host_enqueue!(
-            self,
-            self.left.size() < VEC_MIN_SIZE && self.right.size() < VEC_MIN_SIZE,
-            T
-        )
+        host_enqueue!(self, self.size() < VEC_MIN_SIZE, T::Float)
     }
 }
 
-impl<L, R, T> ReadValue<Host, T> for MatMul<L, R, T>
+impl<I, K, T> ReadValue<Host, T::Float> for MatchedFilter<I, K, T>
 where
-    L: Access<T>,
-    R: Access<T>,
+    I: Access<T>,
+    K: Access<T>,
     T: CType,
 {
-    fn read_value(&self, _offset: usize) -> Result<T, Error> {
-        Err(Error::Bounds(
-            "reading an individual value from a matrix multiplication is not implemented"
-                .to_string(),
-        ))
+    fn read_value(&self, offset: usize) -> Result<T::Float, Error> {
+        read_matched_filter(&self.input, &self.template, self.dims, offset)
     }
 }
 
-pub struct Scalar<A, IT, OT> {
+pub struct Compact<A, P, T> {
     access: A,
-    scalar: IT,
-    op: fn(IT, IT) -> OT,
+    positions: P,
+    size: usize,
+    dtype: PhantomData<T>,
 }
 
-impl<A, IT, OT> Scalar<A, IT, OT> {
-    fn new(access: A, scalar: IT, op: fn(IT, IT) -> OT) -> Self {
-        Self { access, scalar, op }
+impl<A, P, T> Compact<A, P, T> {
+    pub fn new(access: A, positions: P, size: usize) -> Self {
+        Self {
+            access,
+            positions,
+            size,
+            dtype: PhantomData,
+        }
     }
-}
 
-impl<A, T: CType> Scalar<A, T, T> {
-    pub fn add(access: A, scalar: T) -> Self {
-        Self::new(access, scalar, T::add)
+    // the element at offset `i` is selected if its position is greater than the one before it
+    #[inline]
+    fn selected(positions: &[u64], i: usize) -> bool {
+        positions[i] > if i == 0 { 0 } else { positions[i - 1] }
     }
+}
 
-    pub fn div(access: A, scalar: T) -> Self {
-        Self::new(access, scalar, T::div)
+impl<A, P, T> Op for Compact<A, P, T>
+where
+    A: Access<T>,
+    P: Access<u64>,
+    T: CType,
+{
+    fn size(&self) -> usize {
+        self.size
     }
 
-    pub fn log(access: A, scalar: T) -> Self {
-        Self::new(access, scalar, |a, b| {
-            T::from_float(a.to_float().log(b.to_float()))
-        })
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan(), self.positions.plan()]
     }
+}
 
-    pub fn mul(access: A, scalar: T) -> Self {
-        Self::new(access, scalar, T::mul)
-    }
+impl<A, P, T> Enqueue<Stack, T> for Compact<A, P, T>
+where
+    A: Access<T>,
+    P: Access<u64>,
+    T: CType,
+{
+    type Buffer = StackVec<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let input = self.access.read()?.to_slice()?;
+        let positions = self.positions.read()?.to_slice()?;
+        debug_assert_eq!(input.len(), positions.len());
+
+        let output = (0..input.len())
+            .filter(|i| Self::selected(&positions, *i))
+            .map(|i| input[i])
+            .collect::<StackVec<T>>();
+
+        debug_assert_eq!(output.len(), self.size);
+
+        Ok(output)
+    }
+}
+
+impl<A, P, T> Enqueue<Heap, T> for Compact<A, P, T>
+where
+    A: Access<T>,
+    P: Access<u64>,
+    T: CType,
+{
+    type Buffer = Vec<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let (input, positions) = join(
+            || self.access.read().and_then(|buf| buf.to_slice()),
+            || self.positions.read().and_then(|buf| buf.to_slice()),
+        );
+
+        let (input, positions) = (input?, positions?);
+        debug_assert_eq!(input.len(), positions.len());
+
+        let output = (0..input.len())
+            .into_par_iter()
+            .filter(|i| Self::selected(&positions, *i))
+            .map(|i| input[i])
+            .collect::<Vec<T>>();
+
+        debug_assert_eq!(output.len(), self.size);
+
+        Ok(output)
+    }
+}
+
+impl<A, P, T> Enqueue<Host, T> for Compact<A, P, T>
+where
+    A: Access<T>,
+    P: Access<u64>,
+    T: CType,
+{
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        host_enqueue!(self, self.access.size() < VEC_MIN_SIZE, T)
+    }
+}
+
+impl<A, P, T> ReadValue<Host, T> for Compact<A, P, T>
+where
+    A: Access<T>,
+    P: Access<u64>,
+    T: CType,
+{
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        read_compact(&self.access, &self.positions, self.size, offset)
+    }
+}
+
+/// Read the element at `offset` of the elements of `access` selected by a mask
+/// whose inclusive prefix sum is `positions`.
+pub(crate) fn read_compact<A, P, T>(
+    access: &A,
+    positions: &P,
+    size: usize,
+    offset: usize,
+) -> Result<T, Error>
+where
+    A: Access<T>,
+    P: Access<u64>,
+    T: CType,
+{
+    if offset >= size {
+        return Err(Error::Bounds(format!(
+            "invalid offset {offset} for a compacted array with size {size}"
+        )));
+    }
+
+    // binary search for the first element whose position is greater than the offset
+    let (mut lo, mut hi) = (0, positions.size());
+
+    while lo < hi {
+        let mid = lo + ((hi - lo) / 2);
+
+        if positions.read_value(mid)? as usize > offset {
+            hi = mid;
+        } else {
+            lo = mid + 1;
+        }
+    }
+
+    access.read_value(lo)
+}
+
+pub struct Gather<A, I, T> {
+    access: A,
+    indices: I,
+    dims: [usize; 3],
+    dtype: PhantomData<T>,
+}
+
+impl<A, I, T> Gather<A, I, T> {
+    pub fn new(access: A, indices: I, dims: [usize; 3]) -> Self {
+        Self {
+            access,
+            indices,
+            dims,
+            dtype: PhantomData,
+        }
+    }
+}
+
+impl<A, I, T> Gather<A, I, T>
+where
+    T: CType,
+{
+    // select the row of `inner` elements at position `i` of the gathered axis
+    fn row<'a>(
+        source: &'a [T],
+        indices: &[u64],
+        dims: [usize; 3],
+        i: usize,
+    ) -> Result<&'a [T], Error> {
+        let [_outer, dim, inner] = dims;
+        let (o, k) = (i / indices.len(), i % indices.len());
+
+        let index = indices[k] as usize;
+
+        if index < dim {
+            let start = ((o * dim) + index) * inner;
+            Ok(&source[start..(start + inner)])
+        } else {
+            Err(gather_bounds(index, dim))
+        }
+    }
+}
+
+impl<A, I, T> Op for Gather<A, I, T>
+where
+    A: Access<T>,
+    I: Access<u64>,
+    T: CType,
+{
+    fn size(&self) -> usize {
+        let [outer, _dim, inner] = self.dims;
+        outer * self.indices.size() * inner
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan(), self.indices.plan()]
+    }
+}
+
+impl<A, I, T> Enqueue<Stack, T> for Gather<A, I, T>
+where
+    A: Access<T>,
+    I: Access<u64>,
+    T: CType,
+{
+    type Buffer = StackVec<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let source = self.access.read()?.to_slice()?;
+        let indices = self.indices.read()?.to_slice()?;
+
+        let mut output = StackVec::with_capacity(self.size());
+
+        for i in 0..(self.dims[0] * indices.len()) {
+            let row = Self::row(&source, &indices, self.dims, i)?;
+            output.extend_from_slice(row);
+        }
+
+        Ok(output)
+    }
+}
+
+impl<A, I, T> Enqueue<Heap, T> for Gather<A, I, T>
+where
+    A: Access<T>,
+    I: Access<u64>,
+    T: CType,
+{
+    type Buffer = Vec<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let (source, indices) = join(
+            || self.access.read().and_then(|buf| buf.to_slice()),
+            || self.indices.read().and_then(|buf| buf.to_slice()),
+        );
+
+        let (source, indices) = (source?, indices?);
+
+        let rows = (0..(self.dims[0] * indices.len()))
+            .into_par_iter()
+            .map(|i| Self::row(&source, &indices, self.dims, i))
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(rows.concat())
+    }
+}
+
+impl<A, I, T> Enqueue<Host, T> for Gather<A, I, T>
+where
+    A: Access<T>,
+    I: Access<u64>,
+    T: CType,
+{
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        host_enqueue!(self, self.size() < VEC_MIN_SIZE, T)
+    }
+}
+
+impl<A, I, T> ReadValue<Host, T> for Gather<A, I, T>
+where
+    A: Access<T>,
+    I: Access<u64>,
+    T: CType,
+{
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        read_gather(&self.access, &self.indices, self.dims, offset)
+    }
+}
+
+/// Read the element at `offset` of the elements of `access` gathered at the given `indices`
+/// along the middle axis of `dims`.
+pub(crate) fn read_gather<A, I, T>(
+    access: &A,
+    indices: &I,
+    dims: [usize; 3],
+    offset: usize,
+) -> Result<T, Error>
+where
+    A: Access<T>,
+    I: Access<u64>,
+    T: CType,
+{
+    let [outer, dim, inner] = dims;
+    let num_indices = indices.size();
+
+    if offset >= outer * num_indices * inner {
+        return Err(Error::Bounds(format!(
+            "invalid offset {offset} for a gather with size {}",
+            outer * num_indices * inner
+        )));
+    }
+
+    let o = offset / (num_indices * inner);
+    let k = (offset / inner) % num_indices;
+    let j = offset % inner;
+
+    let index = indices.read_value(k)? as usize;
+
+    if index < dim {
+        access.read_value((((o * dim) + index) * inner) + j)
+    } else {
+        Err(gather_bounds(index, dim))
+    }
+}
+
+pub(crate) fn gather_bounds(index: usize, dim: usize) -> Error {
+    Error::Bounds(format!(
+        "cannot gather index {index} from an axis of dimension {dim}"
+    ))
+}
+
+pub(crate) fn scatter_bounds(index: usize, dim: usize) -> Error {
+    Error::Bounds(format!(
+        "cannot scatter to index {index} of an axis of dimension {dim}"
+    ))
+}
+
+pub struct LayerNorm<A, S, B, T> {
+    access: A,
+    scale: S,
+    bias: B,
+    stride: usize,
+    eps: T,
+}
+
+impl<A, S, B, T> LayerNorm<A, S, B, T> {
+    pub fn new(access: A, scale: S, bias: B, stride: usize, eps: T) -> Self {
+        Self {
+            access,
+            scale,
+            bias,
+            stride,
+            eps,
+        }
+    }
+}
+
+impl<A, S, B, T> LayerNorm<A, S, B, T>
+where
+    A: Access<T>,
+    S: Access<T>,
+    B: Access<T>,
+    T: Float,
+{
+    #[inline]
+    fn normalize<'a>(
+        &'a self,
+        row: &'a [T],
+        scale: &'a [T],
+        bias: &'a [T],
+    ) -> impl Iterator<Item = T> + 'a {
+        let (mean, std) = mean_std(row.iter().copied(), self.eps);
+
+        row.iter()
+            .copied()
+            .zip(scale.iter().copied().zip(bias.iter().copied()))
+            .map(move |(n, (scale, bias))| {
+                let n = (CType::to_f64(n) - mean) / std;
+                T::from_f64((n * CType::to_f64(scale)) + CType::to_f64(bias))
+            })
+    }
+}
+
+impl<A, S, B, T> Op for LayerNorm<A, S, B, T>
+where
+    A: Access<T>,
+    S: Access<T>,
+    B: Access<T>,
+    T: Float,
+{
+    fn size(&self) -> usize {
+        debug_assert_eq!(self.access.size() % self.stride, 0);
+        debug_assert_eq!(self.scale.size(), self.stride);
+        debug_assert_eq!(self.bias.size(), self.stride);
+        self.access.size()
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan(), self.scale.plan(), self.bias.plan()]
+    }
+}
+
+impl<A, S, B, T> Enqueue<Stack, T> for LayerNorm<A, S, B, T>
+where
+    A: Access<T>,
+    S: Access<T>,
+    B: Access<T>,
+    T: Float,
+{
+    type Buffer = StackVec<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let input = self.access.read()?.to_slice()?;
+        let scale = self.scale.read()?.to_slice()?;
+        let bias = self.bias.read()?.to_slice()?;
+
+        let output = input
+            .chunks_exact(self.stride)
+            .flat_map(|row| self.normalize(row, &scale, &bias))
+            .collect();
+
+        Ok(output)
+    }
+}
+
+impl<A, S, B, T> Enqueue<Heap, T> for LayerNorm<A, S, B, T>
+where
+    A: Access<T>,
+    S: Access<T>,
+    B: Access<T>,
+    T: Float,
+{
+    type Buffer = Vec<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let (input, params) = join(
+            || self.access.read().and_then(|buf| buf.to_slice()),
+            || try_join_read(&self.scale, &self.bias),
+        );
+
+        let (input, (scale, bias)) = (input?, params?);
+
+        let output = input
+            .par_chunks_exact(self.stride)
+            .flat_map_iter(|row| self.normalize(row, &scale, &bias))
+            .collect();
+
+        Ok(output)
+    }
+}
+
+impl<A, S, B, T> Enqueue<Host, T> for LayerNorm<A, S, B, T>
+where
+    A: Access<T>,
+    S: Access<T>,
+    B: Access<T>,
+    T: Float,
+{
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        host_enqueue!(self, self.size() < VEC_MIN_SIZE, T)
+    }
+}
+
+impl<A, S, B, T> ReadValue<Host, T> for LayerNorm<A, S, B, T>
+where
+    A: Access<T>,
+    S: Access<T>,
+    B: Access<T>,
+    T: Float,
+{
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        let start = (offset / self.stride) * self.stride;
+
+        let row = (start..(start + self.stride))
+            .into_par_iter()
+            .map(|offset| self.access.read_value(offset))
+            .collect::<Result<Vec<T>, Error>>()?;
+
+        let (mean, std) = mean_std(row.iter().copied(), self.eps);
+
+        let i = offset % self.stride;
+        let (scale, bias) = try_join_value(&self.scale, &self.bias, i)?;
+        let n = (CType::to_f64(row[i]) - mean) / std;
+
+        Ok(T::from_f64(
+            (n * CType::to_f64(scale)) + CType::to_f64(bias),
+        ))
+    }
+}
+
+pub struct Eye<T> {
+    batch_size: usize,
+    dim: usize,
+    dtype: PhantomData<T>,
+}
+
+impl<T> Eye<T> {
+    pub fn new(batch_size: usize, dim: usize) -> Self {
+        Self {
+            batch_size,
+            dim,
+            dtype: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn value_at(&self, offset: usize) -> T
+    where
+        T: CType,
+    {
+        let i = offset % (self.dim * self.dim);
+
+        if i / self.dim == i % self.dim {
+            T::ONE
+        } else {
+            T::ZERO
+        }
+    }
+}
+
+impl<T: Send + Sync> Op for Eye<T> {
+    fn size(&self) -> usize {
+        self.batch_size * self.dim * self.dim
+    }
+}
+
+impl<T: CType> Enqueue<Stack, T> for Eye<T> {
+    type Buffer = StackVec<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let buffer = (0..self.size())
+            .map(|offset| self.value_at(offset))
+            .collect();
+
+        Ok(buffer)
+    }
+}
+
+impl<T: CType> Enqueue<Heap, T> for Eye<T> {
+    type Buffer = Vec<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let buffer = (0..self.size())
+            .into_par_iter()
+            .map(|offset| self.value_at(offset))
+            .collect();
+
+        Ok(buffer)
+    }
+}
+
+impl<T: CType> Enqueue<Host, T> for Eye<T> {
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        host_enqueue!(self, self.size() < VEC_MIN_SIZE, T)
+    }
+}
+
+impl<T: CType> ReadValue<Host, T> for Eye<T> {
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        Ok(self.value_at(offset))
+    }
+}
+
+pub struct Linear<T> {
+    start: T,
+    step: f64,
+    size: usize,
+}
+
+impl<T> Linear<T> {
+    pub fn new(start: T, step: f64, size: usize) -> Self {
+        Self { start, step, size }
+    }
+
+    #[inline]
+    fn value_at(&self, offset: usize) -> T
+    where
+        T: CType,
+    {
+        if !T::IS_FLOAT && self.step.fract() == 0. {
+            // keep an integer offset exact rather than rounding it through an f64
+            T::add(
+                self.start,
+                T::mul(T::from_usize(offset), T::from_f64(self.step)),
+            )
+        } else {
+            T::add(self.start, T::from_f64((offset as f64) * self.step))
+        }
+    }
+}
+
+impl<T: Send + Sync> Op for Linear<T> {
+    fn size(&self) -> usize {
+        self.size
+    }
+}
+
+impl<T: CType> Enqueue<Stack, T> for Linear<T> {
+    type Buffer = StackVec<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let buffer = (0..self.size).map(|offset| self.value_at(offset)).collect();
+
+        Ok(buffer)
+    }
+}
+
+impl<T: CType> Enqueue<Heap, T> for Linear<T> {
+    type Buffer = Vec<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let buffer = (0..self.size)
+            .into_par_iter()
+            .map(|offset| self.value_at(offset))
+            .collect();
+
+        Ok(buffer)
+    }
+}
+
+impl<T: CType> Enqueue<Host, T> for Linear<T> {
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        host_enqueue!(self, self.size < VEC_MIN_SIZE, T)
+    }
+}
+
+impl<T: CType> ReadValue<Host, T> for Linear<T> {
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        Ok(self.value_at(offset))
+    }
+}
+
+pub struct MatDiag<A, T> {
+    access: A,
+    dim: usize,
+    batch_size: usize,
+    dtype: PhantomData<T>,
+}
+
+impl<A, T> MatDiag<A, T> {
+    pub fn new(access: A, batch_size: usize, dim: usize) -> Self {
+        Self {
+            access,
+            dim,
+            batch_size,
+            dtype: PhantomData,
+        }
+    }
+}
+
+impl<A: Access<T>, T: CType> Op for MatDiag<A, T> {
+    fn size(&self) -> usize {
+        debug_assert_eq!(self.access.size(), self.batch_size * self.dim * self.dim);
+        self.batch_size * self.dim
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan()]
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<Heap, T> for MatDiag<A, T> {
+    type Buffer = Vec<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let input = self.access.read()?.to_slice()?;
+
+        let diagonals = input
+            .par_chunks_exact(self.dim * self.dim)
+            .map(|matrix| {
+                matrix
+                    .par_chunks_exact(self.dim)
+                    .enumerate()
+                    .map(|(i, row)| row[i])
+            })
+            .flatten()
+            .collect();
+
+        Ok(diagonals)
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<Stack, T> for MatDiag<A, T> {
+    type Buffer = StackVec<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let input = self.access.read()?.to_slice()?;
+
+        let diagonals = input
+            .chunks_exact(self.dim * self.dim)
+            .map(|matrix| {
+                matrix
+                    .chunks_exact(self.dim)
+                    .enumerate()
+                    .map(|(i, row)| row[i])
+            })
+            .flatten()
+            .collect();
+
+        Ok(diagonals)
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<Host, T> for MatDiag<A, T> {
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        host_enqueue!(self, self.size() < VEC_MIN_SIZE, T)
+    }
+}
+
+impl<A: Access<T>, T: CType> ReadValue<Host, T> for MatDiag<A, T> {
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        let batch = offset / self.batch_size;
+        let i = offset % self.batch_size;
+        let source_offset = (batch * self.dim * self.dim) + (i * self.dim) + i;
+        self.access.read_value(source_offset)
+    }
+}
+
+pub struct MatTrace<A, T> {
+    access: A,
+    dim: usize,
+    batch_size: usize,
+    dtype: PhantomData<T>,
+}
+
+impl<A, T> MatTrace<A, T> {
+    pub fn new(access: A, batch_size: usize, dim: usize) -> Self {
+        Self {
+            access,
+            dim,
+            batch_size,
+            dtype: PhantomData,
+        }
+    }
+}
+
+impl<A: Access<T>, T: CType> Op for MatTrace<A, T> {
+    fn size(&self) -> usize {
+        debug_assert_eq!(self.access.size(), self.batch_size * self.dim * self.dim);
+        self.batch_size
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan()]
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<Heap, T> for MatTrace<A, T> {
+    type Buffer = Vec<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        // the trace of an empty matrix is zero
+        if self.dim == 0 {
+            return Ok(vec![T::ZERO; self.batch_size]);
+        }
+
+        let input = self.access.read()?.to_slice()?;
+
+        let traces = input
+            .par_chunks_exact(self.dim * self.dim)
+            .map(|matrix| trace(matrix, self.dim))
+            .collect();
+
+        Ok(traces)
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<Stack, T> for MatTrace<A, T> {
+    type Buffer = StackVec<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        // the trace of an empty matrix is zero
+        if self.dim == 0 {
+            return Ok(stackvec![T::ZERO; self.batch_size]);
+        }
+
+        let input = self.access.read()?.to_slice()?;
+
+        let traces = input
+            .chunks_exact(self.dim * self.dim)
+            .map(|matrix| trace(matrix, self.dim))
+            .collect();
+
+        Ok(traces)
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<Host, T> for MatTrace<A, T> {
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        host_enqueue!(self, self.access.size() < VEC_MIN_SIZE, T)
+    }
+}
+
+impl<A: Access<T>, T: CType> ReadValue<Host, T> for MatTrace<A, T> {
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        read_trace(&self.access, self.dim, offset)
+    }
+}
+
+/// Sum the diagonal of the row-major square `matrix` with dimension `dim`.
+#[inline]
+fn trace<T: CType>(matrix: &[T], dim: usize) -> T {
+    (0..dim).fold(T::ZERO, |sum, i| T::add(sum, matrix[(i * dim) + i]))
+}
+
+/// Read the trace of the matrix at `offset` in a batch of square matrices with dimension `dim`,
+/// one element of the diagonal at a time.
+pub(crate) fn read_trace<A, T>(access: &A, dim: usize, offset: usize) -> Result<T, Error>
+where
+    A: Access<T>,
+    T: CType,
+{
+    let start = offset * dim * dim;
+
+    (0..dim)
+        .map(|i| access.read_value(start + (i * dim) + i))
+        .try_fold(T::ZERO, |sum, n| n.map(|n| T::add(sum, n)))
+}
+
+pub struct DiagFrom<A, T> {
+    access: A,
+    dim: usize,
+    batch_size: usize,
+    dtype: PhantomData<T>,
+}
+
+impl<A, T> DiagFrom<A, T> {
+    pub fn new(access: A, batch_size: usize, dim: usize) -> Self {
+        Self {
+            access,
+            dim,
+            batch_size,
+            dtype: PhantomData,
+        }
+    }
+}
+
+impl<A: Access<T>, T: CType> Op for DiagFrom<A, T> {
+    fn size(&self) -> usize {
+        debug_assert_eq!(self.access.size(), self.batch_size * self.dim);
+        self.batch_size * self.dim * self.dim
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan()]
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<Heap, T> for DiagFrom<A, T> {
+    type Buffer = Vec<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let input = self.access.read()?.to_slice()?;
+        let mut matrices = vec![T::ZERO; self.size()];
+
+        matrices
+            .par_chunks_exact_mut(self.dim * self.dim)
+            .zip(input.par_chunks_exact(self.dim))
+            .for_each(|(matrix, diagonal)| {
+                for (i, value) in diagonal.iter().copied().enumerate() {
+                    matrix[(i * self.dim) + i] = value;
+                }
+            });
+
+        Ok(matrices)
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<Stack, T> for DiagFrom<A, T> {
+    type Buffer = StackVec<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let input = self.access.read()?.to_slice()?;
+        let mut matrices = stackvec![T::ZERO; self.size()];
+
+        for (matrix, diagonal) in matrices
+            .chunks_exact_mut(self.dim * self.dim)
+            .zip(input.chunks_exact(self.dim))
+        {
+            for (i, value) in diagonal.iter().copied().enumerate() {
+                matrix[(i * self.dim) + i] = value;
+            }
+        }
+
+        Ok(matrices)
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<Host, T> for DiagFrom<A, T> {
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        host_enqueue!(self, self.size() < VEC_MIN_SIZE, T)
+    }
+}
+
+impl<A: Access<T>, T: CType> ReadValue<Host, T> for DiagFrom<A, T> {
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        let batch = offset / (self.dim * self.dim);
+        let (row, col) = ((offset / self.dim) % self.dim, offset % self.dim);
+
+        if row == col {
+            self.access.read_value((batch * self.dim) + row)
+        } else {
+            Ok(T::ZERO)
+        }
+    }
+}
+
+pub struct CsrMatMul<I, V, D, T> {
+    offsets: I,
+    indices: I,
+    values: V,
+    dense: D,
+    dims: [usize; 3],
+    dtype: PhantomData<T>,
+}
+
+impl<I, V, D, T> CsrMatMul<I, V, D, T> {
+    pub fn new(offsets: I, indices: I, values: V, dense: D, dims: [usize; 3]) -> Self {
+        Self {
+            offsets,
+            indices,
+            values,
+            dense,
+            dims,
+            dtype: PhantomData,
+        }
+    }
+}
+
+impl<I, V, D, T> CsrMatMul<I, V, D, T>
+where
+    I: Access<u64>,
+    V: Access<T>,
+    D: Access<T>,
+    T: CType,
+{
+    // compute each element of one row of the product
+    fn row<'a>(
+        offsets: &'a [u64],
+        indices: &'a [u64],
+        values: &'a [T],
+        dense: &'a [T],
+        n: usize,
+        row: usize,
+    ) -> impl Iterator<Item = T> + 'a {
+        let nonzero = (offsets[row] as usize)..(offsets[row + 1] as usize);
+
+        (0..n).map(move |j| {
+            nonzero
+                .clone()
+                .map(|k| T::mul(values[k], dense[(indices[k] as usize * n) + j]))
+                .fold(T::ZERO, T::add)
+        })
+    }
+}
+
+impl<I, V, D, T> Op for CsrMatMul<I, V, D, T>
+where
+    I: Access<u64>,
+    V: Access<T>,
+    D: Access<T>,
+    T: CType,
+{
+    fn size(&self) -> usize {
+        self.dims[0] * self.dims[2]
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![
+            self.offsets.plan(),
+            self.indices.plan(),
+            self.values.plan(),
+            self.dense.plan(),
+        ]
+    }
+}
+
+impl<I, V, D, T> Enqueue<Stack, T> for CsrMatMul<I, V, D, T>
+where
+    I: Access<u64>,
+    V: Access<T>,
+    D: Access<T>,
+    T: CType,
+{
+    type Buffer = StackVec<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let offsets = self.offsets.read()?.to_slice()?;
+        let indices = self.indices.read()?.to_slice()?;
+        let values = self.values.read()?.to_slice()?;
+        let dense = self.dense.read()?.to_slice()?;
+
+        let [rows, _, n] = self.dims;
+
+        let output = (0..rows)
+            .flat_map(|row| Self::row(&offsets, &indices, &values, &dense, n, row))
+            .collect();
+
+        Ok(output)
+    }
+}
+
+impl<I, V, D, T> Enqueue<Heap, T> for CsrMatMul<I, V, D, T>
+where
+    I: Access<u64>,
+    V: Access<T>,
+    D: Access<T>,
+    T: CType,
+{
+    type Buffer = Vec<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let (offsets, indices) = try_join_read(&self.offsets, &self.indices)?;
+        let (values, dense) = try_join_read(&self.values, &self.dense)?;
+
+        let [rows, _, n] = self.dims;
+
+        // one task per row balances the load of rows with different numbers of non-zero elements
+        let output = (0..rows)
+            .into_par_iter()
+            .flat_map_iter(|row| Self::row(&offsets, &indices, &values, &dense, n, row))
+            .collect();
+
+        Ok(output)
+    }
+}
+
+impl<I, V, D, T> Enqueue<Host, T> for CsrMatMul<I, V, D, T>
+where
+    I: Access<u64>,
+    V: Access<T>,
+    D: Access<T>,
+    T: CType,
+{
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let nnz = self.values.size();
+        host_enqueue!(self, nnz < VEC_MIN_SIZE && self.size() < VEC_MIN_SIZE, T)
+    }
+}
+
+impl<I, V, D, T> ReadValue<Host, T> for CsrMatMul<I, V, D, T>
+where
+    I: Access<u64>,
+    V: Access<T>,
+    D: Access<T>,
+    T: CType,
+{
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        read_csr_matmul(
+            &self.offsets,
+            &self.indices,
+            &self.values,
+            &self.dense,
+            self.dims,
+            offset,
+        )
+    }
+}
+
+/// Read the element at `offset` of the product of a CSR matrix and a dense matrix.
+pub(crate) fn read_csr_matmul<I, V, D, T>(
+    offsets: &I,
+    indices: &I,
+    values: &V,
+    dense: &D,
+    dims: [usize; 3],
+    offset: usize,
+) -> Result<T, Error>
+where
+    I: Access<u64>,
+    V: Access<T>,
+    D: Access<T>,
+    T: CType,
+{
+    let [rows, _, n] = dims;
+    let (row, j) = (offset / n, offset % n);
+
+    if row >= rows {
+        return Err(Error::Bounds(format!(
+            "invalid offset {offset} for a sparse matrix product with size {}",
+            rows * n
+        )));
+    }
+
+    let start = offsets.read_value(row)? as usize;
+    let stop = offsets.read_value(row + 1)? as usize;
+
+    (start..stop).try_fold(T::ZERO, |sum, k| {
+        let column = indices.read_value(k)? as usize;
+        let value = values.read_value(k)?;
+        let n = dense.read_value((column * n) + j)?;
+        Ok(T::add(sum, T::mul(value, n)))
+    })
+}
+
+pub struct MatMul<L, R, T> {
+    left: L,
+    right: R,
+    batch_size: usize,
+    dims: [usize; 3],
+    strides: [usize; 2],
+    fast_math: bool,
+    dtype: PhantomData<T>,
+}
+
+impl<L, R, T> MatMul<L, R, T> {
+    pub fn new(left: L, right: R, dims: [usize; 4]) -> Self {
+        let [_batch_size, a, b, c] = dims;
+        Self::strided(left, right, dims, [a * b, b * c])
+    }
+
+    pub fn strided(left: L, right: R, dims: [usize; 4], strides: [usize; 2]) -> Self {
+        let [batch_size, a, b, c] = dims;
+
+        Self {
+            left,
+            right,
+            batch_size,
+            dims: [a, b, c],
+            strides,
+            fast_math: config::fast_math(),
+            dtype: PhantomData,
+        }
+    }
+}
+
+impl<L, R, T> Op for MatMul<L, R, T>
+where
+    L: Access<T>,
+    R: Access<T>,
+    T: CType,
+{
+    fn size(&self) -> usize {
+        self.batch_size * self.dims[0] * self.dims[2]
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.left.plan(), self.right.plan()]
+    }
+}
+
+impl<L, R, T> Enqueue<Stack, T> for MatMul<L, R, T>
+where
+    L: Access<T>,
+    R: Access<T>,
+    T: CType,
+{
+    type Buffer = StackVec<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let left = self.left.read()?.to_slice()?;
+        let right = self.right.read()?.to_slice()?;
+
+        let [a, b, c] = self.dims;
+        let [left_stride, right_stride] = self.strides;
+
+        let mut product = StackVec::with_capacity(self.batch_size * a * c);
+
+        for batch in 0..self.batch_size {
+            for x in 0..a {
+                for z in 0..c {
+                    let mut sum = T::ZERO;
+
+                    for y in 0..b {
+                        let l_offset = (batch * left_stride) + (x * b) + y;
+                        let r_offset = (batch * right_stride) + (y * c) + z;
+
+                        sum = if self.fast_math {
+                            T::mul_add(left[l_offset], right[r_offset], sum)
+                        } else {
+                            T::add(sum, T::mul(left[l_offset], right[r_offset]))
+                        };
+                    }
+
+                    product.push(sum)
+                }
+            }
+        }
+
+        debug_assert_eq!(product.len(), self.size());
+
+        Ok(product)
+    }
+}
+
+impl<L, R, T> Enqueue<Heap, T> for MatMul<L, R, T>
+where
+    L: Access<T>,
+    R: Access<T>,
+    T: CType,
+{
+    type Buffer = Vec<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let [a, b, c] = self.dims;
+        let [left_stride, right_stride] = self.strides;
+        let fast_math = self.fast_math;
+
+        let (left, right) = try_join_read(&self.left, &self.right)?;
+
+        // transpose the right matrices
+        let right_size = b * c;
+        let right_matrices = (0..self.batch_size).into_par_iter().map(|i| {
+            let start = i * right_stride;
+            let mut right_t = vec![T::ZERO; right_size];
+            transpose::transpose(&right[start..start + right_size], &mut right_t[..], c, b);
+            right_t
+        });
+
+        let left_size = a * b;
+        let left_matrices = (0..self.batch_size).into_par_iter().map(|i| {
+            let start = i * left_stride;
+            &left[start..start + left_size]
+        });
+
+        let output_size = a * c;
+        let mut output = Vec::<T>::with_capacity(self.batch_size * output_size);
+        let output_matrices = left_matrices
+            .zip(right_matrices)
+            .map(|(lm, rm)| {
+                let mut out = Vec::<T>::with_capacity(output_size);
+
+                let product = lm
+                    .par_chunks_exact(b)
+                    .map(|row| {
+                        rm.par_chunks_exact(b).map(move |col| {
+                            // chunk the dot product to encourage the compiler to vectorize
+                            let col = col.par_chunks(8).map(|cc| cc.into_iter().copied());
+
+                            row.par_chunks(8)
+                                .zip(col)
+                                .map(|(rc, cc)| {
+                                    let pairs = rc.iter().copied().zip(cc);
+
+                                    if fast_math {
+                                        pairs.fold(T::ZERO, |sum, (r, c)| T::mul_add(r, c, sum))
+                                    } else {
+                                        pairs
+                                            .map(|(r, c)| T::mul(r, c))
+                                            .reduce(T::add)
+                                            .expect("sum")
+                                    }
+                                })
+                                .reduce(|| T::ZERO, T::add)
+                        })
+                    })
+                    .flatten();
+
+                out.par_extend(product);
+                out
+            })
+            .flatten();
+
+        output.par_extend(output_matrices);
+
+        debug_assert_eq!(output.len(), self.batch_size * output_size);
+
+        Ok(output)
+    }
+}
+
+impl<L, R, T> Enqueue<Host, T> for MatMul<L, R, T>
+where
+    L: Access<T>,
+    R: Access<T>,
+    T: CType,
+{
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        host_enqueue!(
+            self,
+            self.left.size() < VEC_MIN_SIZE && self.right.size() < VEC_MIN_SIZE,
+            T
+        )
+    }
+}
+
+impl<L, R, T> ReadValue<Host, T> for MatMul<L, R, T>
+where
+    L: Access<T>,
+    R: Access<T>,
+    T: CType,
+{
+    fn read_value(&self, _offset: usize) -> Result<T, Error> {
+        Err(Error::Bounds(
+            "reading an individual value from a matrix multiplication is not implemented"
+                .to_string(),
+        ))
+    }
+}
+
+pub struct Scalar<A, IT, OT> {
+    access: A,
+    scalar: IT,
+    op: fn(IT, IT) -> OT,
+}
+
+impl<A, IT, OT> Scalar<A, IT, OT> {
+    fn new(access: A, scalar: IT, op: fn(IT, IT) -> OT) -> Self {
+        Self { access, scalar, op }
+    }
+}
+
+impl<A, T: CType> Scalar<A, T, T> {
+    pub fn add(access: A, scalar: T) -> Self {
+        Self::new(access, scalar, T::add)
+    }
+
+    pub fn div(access: A, scalar: T) -> Self {
+        Self::new(access, scalar, T::div)
+    }
+
+    pub fn log(access: A, scalar: T) -> Self {
+        Self::new(access, scalar, |a, b| {
+            T::from_float(a.to_float().log(b.to_float()))
+        })
+    }
+
+    pub fn mul(access: A, scalar: T) -> Self {
+        Self::new(access, scalar, T::mul)
+    }
 
     pub fn pow(access: A, scalar: T) -> Self {
         Self::new(access, scalar, T::pow)
     }
 
-    pub fn rem(access: A, scalar: T) -> Self {
-        Self::new(access, scalar, T::rem)
+    pub fn rem(access: A, scalar: T) -> Self {
+        Self::new(access, scalar, T::rem)
+    }
+
+    pub fn sub(access: A, scalar: T) -> Self {
+        Self::new(access, scalar, T::sub)
+    }
+}
+
+impl<A, T> Scalar<A, T, u8> {
+    pub fn and(access: A, scalar: T) -> Self
+    where
+        T: CType,
+    {
+        Self::new(access, scalar, |l, r| {
+            if (l != T::ZERO) && (r != T::ZERO) {
+                1
+            } else {
+                0
+            }
+        })
+    }
+
+    pub fn or(access: A, scalar: T) -> Self
+    where
+        T: CType,
+    {
+        Self::new(access, scalar, |l, r| {
+            if (l != T::ZERO) || (r != T::ZERO) {
+                1
+            } else {
+                0
+            }
+        })
+    }
+
+    pub fn xor(access: A, scalar: T) -> Self
+    where
+        T: CType,
+    {
+        Self::new(access, scalar, |l, r| {
+            if (l != T::ZERO) ^ (r != T::ZERO) {
+                1
+            } else {
+                0
+            }
+        })
+    }
+
+    pub fn eq(access: A, scalar: T) -> Self
+    where
+        T: PartialEq,
+    {
+        Self::new(access, scalar, |l, r| if l == r { 1 } else { 0 })
+    }
+
+    pub fn ge(access: A, scalar: T) -> Self
+    where
+        T: PartialOrd,
+    {
+        Self::new(access, scalar, |l, r| if l >= r { 1 } else { 0 })
+    }
+
+    pub fn gt(access: A, scalar: T) -> Self
+    where
+        T: PartialOrd,
+    {
+        Self::new(access, scalar, |l, r| if l > r { 1 } else { 0 })
+    }
+
+    pub fn le(access: A, scalar: T) -> Self
+    where
+        T: PartialOrd,
+    {
+        Self::new(access, scalar, |l, r| if l <= r { 1 } else { 0 })
+    }
+
+    pub fn lt(access: A, scalar: T) -> Self
+    where
+        T: PartialOrd,
+    {
+        Self::new(access, scalar, |l, r| if l < r { 1 } else { 0 })
+    }
+
+    pub fn ne(access: A, scalar: T) -> Self
+    where
+        T: PartialEq,
+    {
+        Self::new(access, scalar, |l, r| if l != r { 1 } else { 0 })
+    }
+}
+
+impl<A, IT, OT> Op for Scalar<A, IT, OT>
+where
+    A: Access<IT>,
+    IT: CType,
+    OT: CType,
+{
+    fn size(&self) -> usize {
+        self.access.size()
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan()]
+    }
+}
+
+impl<A, IT, OT> Enqueue<Heap, OT> for Scalar<A, IT, OT>
+where
+    A: Access<IT>,
+    IT: CType,
+    OT: CType,
+{
+    type Buffer = Vec<OT>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        self.access
+            .read()
+            .and_then(|buf| buf.to_slice())
+            .map(|slice| {
+                slice
+                    .as_ref()
+                    .into_par_iter()
+                    .copied()
+                    .map(|l| (self.op)(l, self.scalar))
+                    .collect()
+            })
+    }
+}
+
+impl<A, IT, OT> Enqueue<Stack, OT> for Scalar<A, IT, OT>
+where
+    A: Access<IT>,
+    IT: CType,
+    OT: CType,
+{
+    type Buffer = StackVec<OT>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        self.access
+            .read()
+            .and_then(|buf| buf.to_slice())
+            .map(|slice| {
+                slice
+                    .as_ref()
+                    .into_iter()
+                    .copied()
+                    .map(|l| (self.op)(l, self.scalar))
+                    .collect()
+            })
+    }
+}
+
+impl<A, IT, OT> Enqueue<Host, OT> for Scalar<A, IT, OT>
+where
+    A: Access<IT>,
+    IT: CType,
+    OT: CType,
+{
+    type Buffer = Buffer<OT>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        host_enqueue!(self, self.size() < VEC_MIN_SIZE, OT)
+    }
+}
+
+impl<A, IT, OT> ReadValue<Host, OT> for Scalar<A, IT, OT>
+where
+    A: Access<IT>,
+    IT: CType,
+    OT: CType,
+{
+    fn read_value(&self, offset: usize) -> Result<OT, Error> {
+        self.access
+            .read_value(offset)
+            .map(|n| (self.op)(n, self.scalar))
+    }
+}
+
+pub struct RandomNormal {
+    size: usize,
+}
+
+impl RandomNormal {
+    pub fn new(size: usize) -> Self {
+        Self { size }
+    }
+
+    fn box_muller(u: [f32; 2]) -> [f32; 2] {
+        let [u1, u2] = u;
+        let r = (u1.ln() * -2.).sqrt();
+        let theta = 2. * PI * u2;
+        [r * theta.cos(), r * theta.sin()]
+    }
+}
+
+impl Op for RandomNormal {
+    fn size(&self) -> usize {
+        self.size
+    }
+}
+
+impl Enqueue<Heap, f32> for RandomNormal {
+    type Buffer = Vec<f32>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let mut u = vec![
+            0.0f32;
+            if self.size % 2 == 0 {
+                self.size
+            } else {
+                self.size + 1
+            }
+        ];
+
+        rand::thread_rng().fill(&mut u[..]);
+
+        let mut output = u
+            .par_chunks_exact(2)
+            .map(|u| {
+                let u: [f32; 2] = u.try_into().expect("u");
+                Self::box_muller(u)
+            })
+            .flatten()
+            .collect::<Vec<f32>>();
+
+        if output.len() > self.size {
+            output.pop();
+        }
+
+        debug_assert_eq!(output.len(), self.size);
+
+        Ok(output)
+    }
+}
+
+impl Enqueue<Stack, f32> for RandomNormal {
+    type Buffer = StackVec<f32>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let mut rng = rand::thread_rng();
+
+        let mut output = iter::repeat_with(|| [rng.gen(), rng.gen()])
+            .take(self.size.div_ceil(2))
+            .map(Self::box_muller)
+            .flatten()
+            .collect::<StackVec<f32>>();
+
+        if output.len() > self.size {
+            output.pop();
+        }
+
+        debug_assert_eq!(output.len(), self.size);
+
+        Ok(output)
+    }
+}
+
+impl Enqueue<Host, f32> for RandomNormal {
+    type Buffer = Buffer<f32>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        host_enqueue!(self, self.size < VEC_MIN_SIZE, f32)
+    }
+}
+
+impl ReadValue<Host, f32> for RandomNormal {
+    fn read_value(&self, _offset: usize) -> Result<f32, Error> {
+        Err(Error::Bounds(
+            "cannot calculate an individual value of a random normal distribution".to_string(),
+        ))
+    }
+}
+
+pub struct RandomUniform {
+    size: usize,
+}
+
+impl RandomUniform {
+    pub fn new(size: usize) -> Self {
+        Self { size }
+    }
+}
+
+impl Op for RandomUniform {
+    fn size(&self) -> usize {
+        self.size
+    }
+}
+
+impl Enqueue<Heap, f32> for RandomUniform {
+    type Buffer = Vec<f32>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let mut data = vec![0.; self.size];
+        rand::thread_rng().fill(&mut data[..]);
+        Ok(data)
+    }
+}
+
+impl Enqueue<Stack, f32> for RandomUniform {
+    type Buffer = StackVec<f32>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let mut data = stackvec![0.; self.size];
+        rand::thread_rng().fill(&mut data[..]);
+        Ok(data)
+    }
+}
+
+impl Enqueue<Host, f32> for RandomUniform {
+    type Buffer = Buffer<f32>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        host_enqueue!(self, self.size < VEC_MIN_SIZE, f32)
+    }
+}
+
+impl ReadValue<Host, f32> for RandomUniform {
+    fn read_value(&self, _offset: usize) -> Result<f32, Error> {
+        Ok(rand::thread_rng().gen())
+    }
+}
+
+pub struct Reduce<A, T> {
+    access: A,
+    stride: usize,
+    size: usize,
+    reduce: fn(T, T) -> T,
+    id: T,
+}
+
+impl<A, T> Reduce<A, T>
+where
+    A: Access<T>,
+    T: CType,
+{
+    fn new(access: A, stride: usize, reduce: fn(T, T) -> T, id: T) -> Self {
+        debug_assert_ne!(stride, 0);
+        debug_assert_eq!(access.size() % stride, 0);
+
+        Self {
+            size: access.size() / stride,
+            access,
+            stride,
+            reduce,
+            id,
+        }
+    }
+
+    pub fn max(access: A, stride: usize) -> Self {
+        Self::new(access, stride, CType::max, max_identity())
+    }
+
+    pub fn min(access: A, stride: usize) -> Self {
+        Self::new(access, stride, CType::min, min_identity())
+    }
+
+    pub fn product(access: A, stride: usize) -> Self {
+        Self::new(access, stride, T::mul, T::ONE)
+    }
+
+    pub fn sum(access: A, stride: usize) -> Self {
+        Self::new(access, stride, T::add, T::ZERO)
+    }
+
+    pub fn max_ignore_nan(access: A, stride: usize) -> Self {
+        let id = ignore_nan_identity(max_identity());
+        Self::new(access, stride, |l, r| ignore_nan(l, r, CType::max), id)
+    }
+
+    pub fn min_ignore_nan(access: A, stride: usize) -> Self {
+        let id = ignore_nan_identity(min_identity());
+        Self::new(access, stride, |l, r| ignore_nan(l, r, CType::min), id)
+    }
+
+    pub fn product_ignore_nan(access: A, stride: usize) -> Self {
+        Self::new(access, stride, |l, r| ignore_nan(l, r, T::mul), T::ONE)
+    }
+
+    pub fn sum_ignore_nan(access: A, stride: usize) -> Self {
+        Self::new(access, stride, |l, r| ignore_nan(l, r, T::add), T::ZERO)
+    }
+
+    /// Construct a reduction of `size` empty lanes, each of which is the identity `id`.
+    pub fn identity(access: A, size: usize, id: T) -> Self {
+        debug_assert_eq!(access.size(), 0);
+
+        Self {
+            access,
+            stride: 0,
+            size,
+            reduce: |l, _r| l,
+            id,
+        }
+    }
+}
+
+impl<A: Access<T>, T: CType> Op for Reduce<A, T> {
+    fn size(&self) -> usize {
+        self.size
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan()]
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<Heap, T> for Reduce<A, T> {
+    type Buffer = Vec<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        if self.stride == 0 {
+            return Ok(vec![self.id; self.size]);
+        }
+
+        self.access
+            .read()
+            .and_then(|buf| buf.to_slice())
+            .map(|slice| {
+                slice
+                    .chunks_exact(self.stride)
+                    .map(|chunk| {
+                        chunk
+                            // encourage the compiler to vectorize
+                            .par_chunks(8)
+                            .map(|chunk| chunk.iter().copied().fold(self.id, self.reduce))
+                            .reduce(|| self.id, self.reduce)
+                    })
+                    .collect()
+            })
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<Stack, T> for Reduce<A, T> {
+    type Buffer = StackVec<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        if self.stride == 0 {
+            return Ok(iter::repeat_n(self.id, self.size).collect());
+        }
+
+        self.access
+            .read()
+            .and_then(|buf| buf.to_slice())
+            .map(|slice| {
+                slice
+                    .chunks_exact(self.stride)
+                    .map(|chunk| chunk.iter().copied().fold(self.id, self.reduce))
+                    .collect()
+            })
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<Host, T> for Reduce<A, T> {
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        host_enqueue!(
+            self,
+            self.stride < VEC_MIN_SIZE && self.size() < VEC_MIN_SIZE,
+            T
+        )
+    }
+}
+
+impl<A: Access<T>, T: CType> ReadValue<Host, T> for Reduce<A, T> {
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        if offset >= self.size {
+            return Err(Error::Bounds(format!(
+                "invalid offset {offset} for a reduce op with size {}",
+                self.size()
+            )));
+        }
+
+        let offset = offset * self.stride;
+
+        (offset..(offset + self.stride))
+            .into_par_iter()
+            .map(|offset| self.access.read_value(offset))
+            .try_reduce(|| self.id, |r, v| Ok((self.reduce)(r, v)))
+    }
+}
+
+pub struct ReduceArg<A, T> {
+    access: A,
+    stride: usize,
+    replace: fn(&T, &T) -> bool,
+}
+
+impl<A, T: CType> ReduceArg<A, T> {
+    pub fn argmax(access: A, stride: usize) -> Self {
+        Self {
+            access,
+            stride,
+            replace: |n, best| n > best,
+        }
+    }
+
+    pub fn argmin(access: A, stride: usize) -> Self {
+        Self {
+            access,
+            stride,
+            replace: |n, best| n < best,
+        }
+    }
+}
+
+impl<A: Access<T>, T: CType> Op for ReduceArg<A, T> {
+    fn size(&self) -> usize {
+        debug_assert_eq!(self.access.size() % self.stride, 0);
+        self.access.size() / self.stride
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan()]
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<Heap, u64> for ReduceArg<A, T> {
+    type Buffer = Vec<u64>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        self.access
+            .read()
+            .and_then(|buf| buf.to_slice())
+            .map(|slice| {
+                slice
+                    .par_chunks_exact(self.stride)
+                    .map(|chunk| reduce_with_index(chunk, self.replace).1)
+                    .collect()
+            })
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<Stack, u64> for ReduceArg<A, T> {
+    type Buffer = StackVec<u64>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        self.access
+            .read()
+            .and_then(|buf| buf.to_slice())
+            .map(|slice| {
+                slice
+                    .chunks_exact(self.stride)
+                    .map(|chunk| reduce_with_index(chunk, self.replace).1)
+                    .collect()
+            })
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<Host, u64> for ReduceArg<A, T> {
+    type Buffer = Buffer<u64>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        host_enqueue!(
+            self,
+            self.stride < VEC_MIN_SIZE && self.size() < VEC_MIN_SIZE,
+            u64
+        )
+    }
+}
+
+impl<A: Access<T>, T: CType> ReadValue<Host, u64> for ReduceArg<A, T> {
+    fn read_value(&self, offset: usize) -> Result<u64, Error> {
+        read_arg(&self.access, self.stride, self.replace, offset)
+    }
+}
+
+/// Read the index of the first extreme value of the row of `stride` elements at `offset`.
+pub(crate) fn read_arg<A, T>(
+    access: &A,
+    stride: usize,
+    replace: fn(&T, &T) -> bool,
+    offset: usize,
+) -> Result<u64, Error>
+where
+    A: Access<T>,
+    T: CType,
+{
+    let start = offset * stride;
+
+    if start < access.size() {
+        let row = (start..(start + stride))
+            .map(|offset| access.read_value(offset))
+            .collect::<Result<Vec<T>, Error>>()?;
+
+        Ok(reduce_with_index(&row, replace).1)
+    } else {
+        Err(Error::Bounds(format!(
+            "invalid offset {offset} for a reduce op with size {}",
+            access.size() / stride
+        )))
+    }
+}
+
+// find the first extreme value in `chunk` and its offset, where `replace(n, best)` is a strict comparison;
+// the first NaN is the extreme value, like a max or min which propagates NaN
+#[inline]
+pub(crate) fn reduce_with_index<T: CType>(chunk: &[T], replace: fn(&T, &T) -> bool) -> (T, u64) {
+    let is_nan = |n: T| T::IS_FLOAT && CType::to_f64(n).is_nan();
+
+    let mut best = chunk[0];
+    let mut index = 0;
+
+    if is_nan(best) {
+        return (best, 0);
+    }
+
+    for (i, n) in chunk.iter().enumerate().skip(1) {
+        if is_nan(*n) {
+            return (*n, i as u64);
+        } else if replace(n, &best) {
+            best = *n;
+            index = i;
+        }
+    }
+
+    (best, index as u64)
+}
+
+/// The width of each node of the tree used by [`tree_reduce`], which is the OpenCL work group size
+pub(crate) const TREE_WIDTH: usize = 64;
+
+// reduce `values` in the same order as the OpenCL reduce kernel: each group of `TREE_WIDTH`
+// values is reduced by halving the stride, then the partial results are reduced the same way
+pub(crate) fn tree_reduce<T: CType>(values: &[T], id: T, reduce: fn(T, T) -> T) -> T {
+    let reduce_group = |group: &[T]| {
+        let mut partials = [id; TREE_WIDTH];
+        partials[..group.len()].copy_from_slice(group);
+
+        let mut stride = TREE_WIDTH / 2;
+        while stride > 0 {
+            for b in 0..stride.min(group.len().saturating_sub(stride)) {
+                partials[b] = reduce(partials[b], partials[b + stride]);
+            }
+
+            stride /= 2;
+        }
+
+        partials[0]
+    };
+
+    let mut partials = values
+        .par_chunks(TREE_WIDTH)
+        .map(reduce_group)
+        .collect::<Vec<T>>();
+
+    while partials.len() > 1 {
+        partials = partials.par_chunks(TREE_WIDTH).map(reduce_group).collect();
+    }
+
+    partials.pop().unwrap_or(id)
+}
+
+pub struct ReduceMoment<A, T: CType> {
+    access: A,
+    stride: usize,
+    reduce: fn(&[T]) -> T::Float,
+}
+
+impl<A, T: CType> ReduceMoment<A, T> {
+    pub fn mean(access: A, stride: usize) -> Self {
+        Self {
+            access,
+            stride,
+            reduce: moment_mean,
+        }
+    }
+
+    pub fn var(access: A, stride: usize) -> Self {
+        Self {
+            access,
+            stride,
+            reduce: moment_var,
+        }
+    }
+
+    pub fn std(access: A, stride: usize) -> Self {
+        Self {
+            access,
+            stride,
+            reduce: moment_std,
+        }
+    }
+}
+
+impl<A: Access<T>, T: CType> Op for ReduceMoment<A, T> {
+    fn size(&self) -> usize {
+        debug_assert_eq!(self.access.size() % self.stride, 0);
+        self.access.size() / self.stride
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan()]
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<Heap, T::Float> for ReduceMoment<A, T> {
+    type Buffer = Vec<T::Float>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        self.access
+            .read()
+            .and_then(|buf| buf.to_slice())
+            .map(|slice| {
+                slice
+                    .par_chunks_exact(self.stride)
+                    .map(self.reduce)
+                    .collect()
+            })
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<Stack, T::Float> for ReduceMoment<A, T> {
+    type Buffer = StackVec<T::Float>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        self.access
+            .read()
+            .and_then(|buf| buf.to_slice())
+            .map(|slice| slice.chunks_exact(self.stride).map(self.reduce).collect())
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<Host, T::Float> for ReduceMoment<A, T> {
+    type Buffer = Buffer<T::Float>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        host_enqueue!(
+            self,
+            self.stride < VEC_MIN_SIZE && self.size() < VEC_MIN_SIZE,
+            T::Float
+        )
+    }
+}
+
+impl<A: Access<T>, T: CType> ReadValue<Host, T::Float> for ReduceMoment<A, T> {
+    fn read_value(&self, offset: usize) -> Result<T::Float, Error> {
+        read_moment(&self.access, self.stride, self.reduce, offset)
+    }
+}
+
+/// Compute the mean of the given `row`, accumulated in double precision.
+pub(crate) fn moment_mean<T: CType>(row: &[T]) -> T::Float {
+    T::Float::from_f64(mean_f64(row))
+}
+
+/// Compute the population variance of the given `row`, accumulated in double precision.
+pub(crate) fn moment_var<T: CType>(row: &[T]) -> T::Float {
+    T::Float::from_f64(var_f64(row))
+}
+
+/// Compute the population standard deviation of the given `row`.
+pub(crate) fn moment_std<T: CType>(row: &[T]) -> T::Float {
+    T::Float::from_f64(var_f64(row).sqrt())
+}
+
+fn mean_f64<T: CType>(row: &[T]) -> f64 {
+    row.iter().copied().map(T::to_f64).sum::<f64>() / row.len() as f64
+}
+
+fn var_f64<T: CType>(row: &[T]) -> f64 {
+    let mean = mean_f64(row);
+
+    let sum = row
+        .iter()
+        .copied()
+        .map(|n| n.to_f64() - mean)
+        .map(|delta| delta * delta)
+        .sum::<f64>();
+
+    sum / row.len() as f64
+}
+
+/// Read the given moment of the row of `stride` elements which reduces to `offset`.
+pub(crate) fn read_moment<A, T>(
+    access: &A,
+    stride: usize,
+    reduce: fn(&[T]) -> T::Float,
+    offset: usize,
+) -> Result<T::Float, Error>
+where
+    A: Access<T>,
+    T: CType,
+{
+    let start = offset * stride;
+
+    if start < access.size() {
+        let row = (start..(start + stride))
+            .map(|offset| access.read_value(offset))
+            .collect::<Result<Vec<T>, Error>>()?;
+
+        Ok((reduce)(&row))
+    } else {
+        Err(Error::Bounds(format!(
+            "invalid offset {offset} for a reduce op with size {}",
+            access.size() / stride
+        )))
+    }
+}
+
+pub struct SegmentPad<O, A, T> {
+    offsets: O,
+    values: A,
+    segments: usize,
+    max_len: usize,
+    pad: T,
+}
+
+impl<O, A, T> SegmentPad<O, A, T> {
+    pub fn new(offsets: O, values: A, segments: usize, max_len: usize, pad: T) -> Self {
+        Self {
+            offsets,
+            values,
+            segments,
+            max_len,
+            pad,
+        }
+    }
+}
+
+impl<O, A, T> SegmentPad<O, A, T>
+where
+    T: CType,
+{
+    // copy one segment into a row of length `max_len`
+    fn row<'a>(
+        offsets: &'a [u64],
+        values: &'a [T],
+        max_len: usize,
+        pad: T,
+        i: usize,
+    ) -> impl Iterator<Item = T> + 'a {
+        let segment = &values[(offsets[i] as usize)..(offsets[i + 1] as usize)];
+
+        segment
+            .iter()
+            .copied()
+            .chain(iter::repeat(pad))
+            .take(max_len)
+    }
+}
+
+impl<O, A, T> Op for SegmentPad<O, A, T>
+where
+    O: Access<u64>,
+    A: Access<T>,
+    T: CType,
+{
+    fn size(&self) -> usize {
+        self.segments * self.max_len
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.offsets.plan(), self.values.plan()]
+    }
+}
+
+impl<O, A, T> Enqueue<Stack, T> for SegmentPad<O, A, T>
+where
+    O: Access<u64>,
+    A: Access<T>,
+    T: CType,
+{
+    type Buffer = StackVec<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let offsets = self.offsets.read()?.to_slice()?;
+        let values = self.values.read()?.to_slice()?;
+
+        let output = (0..self.segments)
+            .flat_map(|i| Self::row(&offsets, &values, self.max_len, self.pad, i))
+            .collect();
+
+        Ok(output)
+    }
+}
+
+impl<O, A, T> Enqueue<Heap, T> for SegmentPad<O, A, T>
+where
+    O: Access<u64>,
+    A: Access<T>,
+    T: CType,
+{
+    type Buffer = Vec<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let (offsets, values) = join(
+            || self.offsets.read().and_then(|buf| buf.to_slice()),
+            || self.values.read().and_then(|buf| buf.to_slice()),
+        );
+
+        let (offsets, values) = (offsets?, values?);
+
+        let output = (0..self.segments)
+            .into_par_iter()
+            .flat_map_iter(|i| Self::row(&offsets, &values, self.max_len, self.pad, i))
+            .collect();
+
+        Ok(output)
+    }
+}
+
+impl<O, A, T> Enqueue<Host, T> for SegmentPad<O, A, T>
+where
+    O: Access<u64>,
+    A: Access<T>,
+    T: CType,
+{
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        host_enqueue!(self, self.size() < VEC_MIN_SIZE, T)
+    }
+}
+
+impl<O, A, T> ReadValue<Host, T> for SegmentPad<O, A, T>
+where
+    O: Access<u64>,
+    A: Access<T>,
+    T: CType,
+{
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        read_segment_pad(
+            &self.offsets,
+            &self.values,
+            [self.segments, self.max_len],
+            self.pad,
+            offset,
+        )
+    }
+}
+
+/// Read the element at `offset` of the padded rows of a segmented array.
+pub(crate) fn read_segment_pad<O, A, T>(
+    offsets: &O,
+    values: &A,
+    dims: [usize; 2],
+    pad: T,
+    offset: usize,
+) -> Result<T, Error>
+where
+    O: Access<u64>,
+    A: Access<T>,
+    T: CType,
+{
+    let [segments, max_len] = dims;
+
+    if offset >= segments * max_len {
+        return Err(Error::Bounds(format!(
+            "invalid offset {offset} for padded segments with size {}",
+            segments * max_len
+        )));
+    }
+
+    let (i, j) = (offset / max_len, offset % max_len);
+
+    let start = offsets.read_value(i)? as usize;
+    let stop = offsets.read_value(i + 1)? as usize;
+
+    if start + j < stop {
+        values.read_value(start + j)
+    } else {
+        Ok(pad)
+    }
+}
+
+pub struct SegmentReduce<O, A, T> {
+    offsets: O,
+    values: A,
+    segments: usize,
+    reduce: fn(T, T) -> T,
+    id: T,
+}
+
+impl<O, A, T> SegmentReduce<O, A, T>
+where
+    T: CType,
+{
+    pub fn max(offsets: O, values: A, segments: usize) -> Self {
+        Self {
+            offsets,
+            values,
+            segments,
+            reduce: CType::max,
+            id: T::MIN,
+        }
+    }
+
+    pub fn min(offsets: O, values: A, segments: usize) -> Self {
+        Self {
+            offsets,
+            values,
+            segments,
+            reduce: CType::min,
+            id: T::MAX,
+        }
+    }
+
+    pub fn product(offsets: O, values: A, segments: usize) -> Self {
+        Self {
+            offsets,
+            values,
+            segments,
+            reduce: T::mul,
+            id: T::ONE,
+        }
+    }
+
+    pub fn sum(offsets: O, values: A, segments: usize) -> Self {
+        Self {
+            offsets,
+            values,
+            segments,
+            reduce: T::add,
+            id: T::ZERO,
+        }
+    }
+
+    // reduce one segment, or return the identity if it's empty
+    fn segment(&self, offsets: &[u64], values: &[T], i: usize) -> T {
+        values[(offsets[i] as usize)..(offsets[i + 1] as usize)]
+            .iter()
+            .copied()
+            .fold(self.id, self.reduce)
+    }
+}
+
+impl<O, A, T> Op for SegmentReduce<O, A, T>
+where
+    O: Access<u64>,
+    A: Access<T>,
+    T: CType,
+{
+    fn size(&self) -> usize {
+        self.segments
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.offsets.plan(), self.values.plan()]
+    }
+}
+
+impl<O, A, T> Enqueue<Stack, T> for SegmentReduce<O, A, T>
+where
+    O: Access<u64>,
+    A: Access<T>,
+    T: CType,
+{
+    type Buffer = StackVec<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let offsets = self.offsets.read()?.to_slice()?;
+        let values = self.values.read()?.to_slice()?;
+
+        let output = (0..self.segments)
+            .map(|i| self.segment(&offsets, &values, i))
+            .collect();
+
+        Ok(output)
+    }
+}
+
+impl<O, A, T> Enqueue<Heap, T> for SegmentReduce<O, A, T>
+where
+    O: Access<u64>,
+    A: Access<T>,
+    T: CType,
+{
+    type Buffer = Vec<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let (offsets, values) = join(
+            || self.offsets.read().and_then(|buf| buf.to_slice()),
+            || self.values.read().and_then(|buf| buf.to_slice()),
+        );
+
+        let (offsets, values) = (offsets?, values?);
+
+        let output = (0..self.segments)
+            .into_par_iter()
+            .map(|i| self.segment(&offsets, &values, i))
+            .collect();
+
+        Ok(output)
+    }
+}
+
+impl<O, A, T> Enqueue<Host, T> for SegmentReduce<O, A, T>
+where
+    O: Access<u64>,
+    A: Access<T>,
+    T: CType,
+{
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        host_enqueue!(self, self.values.size() < VEC_MIN_SIZE, T)
+    }
+}
+
+impl<O, A, T> ReadValue<Host, T> for SegmentReduce<O, A, T>
+where
+    O: Access<u64>,
+    A: Access<T>,
+    T: CType,
+{
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        read_segment_reduce(
+            &self.offsets,
+            &self.values,
+            self.segments,
+            self.reduce,
+            self.id,
+            offset,
+        )
+    }
+}
+
+/// Reduce the segment at `offset` of a segmented array, starting from the identity `id`.
+pub(crate) fn read_segment_reduce<O, A, T>(
+    offsets: &O,
+    values: &A,
+    segments: usize,
+    reduce: fn(T, T) -> T,
+    id: T,
+    offset: usize,
+) -> Result<T, Error>
+where
+    O: Access<u64>,
+    A: Access<T>,
+    T: CType,
+{
+    if offset >= segments {
+        return Err(Error::Bounds(format!(
+            "invalid offset {offset} for a segment reduce with size {segments}"
+        )));
+    }
+
+    let start = offsets.read_value(offset)? as usize;
+    let stop = offsets.read_value(offset + 1)? as usize;
+
+    (start..stop).try_fold(id, |reduced, i| {
+        values.read_value(i).map(|n| (reduce)(reduced, n))
+    })
+}
+
+pub struct Cdf<A, T> {
+    access: A,
+    dim: usize,
+    inner: usize,
+    dtype: PhantomData<T>,
+}
+
+impl<A, T> Cdf<A, T> {
+    pub fn new(access: A, dim: usize, inner: usize) -> Self {
+        Self {
+            access,
+            dim,
+            inner,
+            dtype: PhantomData,
+        }
+    }
+
+    /// The index of the lane of the element at `offset`.
+    #[inline]
+    fn lane(&self, offset: usize) -> usize {
+        (offset / (self.dim * self.inner)) * self.inner + (offset % self.inner)
+    }
+
+    // sort a copy of each lane, so that each element only needs a binary search of its lane
+    fn sorted_lane(&self, input: &[T], lane: usize) -> Vec<T>
+    where
+        T: CType,
+    {
+        let start = (lane / self.inner) * self.dim * self.inner + (lane % self.inner);
+
+        let mut sorted = (0..self.dim)
+            .map(|i| input[start + (i * self.inner)])
+            .collect::<Vec<T>>();
+
+        sorted.sort_by(sort_order);
+        sorted
+    }
+
+    #[inline]
+    fn fraction(&self, sorted: &[T], n: T) -> T::Float
+    where
+        T: CType,
+    {
+        let count = sorted.partition_point(|m| *m <= n);
+        T::Float::from_f64(count as f64 / self.dim as f64)
+    }
+}
+
+impl<A: Access<T>, T: CType> Op for Cdf<A, T> {
+    fn size(&self) -> usize {
+        self.access.size()
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan()]
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<Stack, T::Float> for Cdf<A, T> {
+    type Buffer = StackVec<T::Float>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let input = self.access.read()?.to_slice()?;
+
+        if input.is_empty() {
+            return Ok(StackVec::new());
+        }
+
+        let lanes = input.len() / self.dim;
+
+        let sorted = (0..lanes)
+            .map(|lane| self.sorted_lane(&input, lane))
+            .collect::<Vec<_>>();
+
+        let output = input
+            .iter()
+            .enumerate()
+            .map(|(offset, n)| self.fraction(&sorted[self.lane(offset)], *n))
+            .collect();
+
+        Ok(output)
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<Heap, T::Float> for Cdf<A, T> {
+    type Buffer = Vec<T::Float>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let input = self.access.read()?.to_slice()?;
+
+        if input.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let lanes = input.len() / self.dim;
+
+        let sorted = (0..lanes)
+            .into_par_iter()
+            .map(|lane| self.sorted_lane(&input, lane))
+            .collect::<Vec<_>>();
+
+        let output = input
+            .par_iter()
+            .enumerate()
+            .map(|(offset, n)| self.fraction(&sorted[self.lane(offset)], *n))
+            .collect();
+
+        Ok(output)
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<Host, T::Float> for Cdf<A, T> {
+    type Buffer = Buffer<T::Float>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        host_enqueue!(self, self.size() < VEC_MIN_SIZE, T::Float)
+    }
+}
+
+impl<A: Access<T>, T: CType> ReadValue<Host, T::Float> for Cdf<A, T> {
+    fn read_value(&self, offset: usize) -> Result<T::Float, Error> {
+        read_cdf(&self.access, self.dim, self.inner, offset)
+    }
+}
+
+pub struct Scan<A, T> {
+    access: A,
+    dim: usize,
+    inner: usize,
+    scan: fn(T, T) -> T,
+}
+
+impl<A, T: CType> Scan<A, T> {
+    pub fn cumprod(access: A, dim: usize, inner: usize) -> Self {
+        Self {
+            access,
+            dim,
+            inner,
+            scan: T::mul,
+        }
+    }
+
+    pub fn cumsum(access: A, dim: usize, inner: usize) -> Self {
+        Self {
+            access,
+            dim,
+            inner,
+            scan: T::add,
+        }
+    }
+
+    pub fn logcumsumexp(access: A, dim: usize, inner: usize) -> Self {
+        Self {
+            access,
+            dim,
+            inner,
+            scan: |acc, n| T::from_float(acc.to_float().logaddexp(n.to_float())),
+        }
+    }
+}
+
+impl<A: Access<T>, T: CType> Scan<A, T> {
+    // scan a contiguous block of `dim * inner` elements along its outer dimension
+    fn scan(&self, block: &mut [T]) {
+        for i in self.inner..block.len() {
+            block[i] = (self.scan)(block[i - self.inner], block[i]);
+        }
+    }
+
+    // scan a contiguous block in parallel by scanning runs of its outer dimension independently,
+    // then carrying the last row of each run into every row of the next
+    fn par_scan(&self, block: &mut [T]) {
+        let run = self.inner * self.dim.div_ceil(current_num_threads());
+
+        block.par_chunks_mut(run).for_each(|run| self.scan(run));
+
+        let mut carries = Vec::with_capacity(block.len().div_ceil(run));
+        let mut carry: Option<Vec<T>> = None;
+
+        for run in block.chunks(run) {
+            let last = &run[(run.len() - self.inner)..];
+
+            let next = match &carry {
+                Some(carry) => carry
+                    .iter()
+                    .zip(last)
+                    .map(|(acc, n)| (self.scan)(*acc, *n))
+                    .collect(),
+                None => last.to_vec(),
+            };
+
+            carries.push(carry.replace(next));
+        }
+
+        block
+            .par_chunks_mut(run)
+            .zip(carries)
+            .for_each(|(run, carry)| {
+                if let Some(carry) = carry {
+                    for row in run.chunks_exact_mut(self.inner) {
+                        for (n, acc) in row.iter_mut().zip(&carry) {
+                            *n = (self.scan)(*acc, *n);
+                        }
+                    }
+                }
+            });
+    }
+}
+
+impl<A: Access<T>, T: CType> Op for Scan<A, T> {
+    fn size(&self) -> usize {
+        self.access.size()
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan()]
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<Heap, T> for Scan<A, T> {
+    type Buffer = Vec<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let mut output = self.access.read()?.to_slice()?.into_vec();
+        let block = self.dim * self.inner;
+
+        if block == 0 {
+            return Ok(output);
+        }
+
+        if output.len() / block >= current_num_threads() {
+            output
+                .par_chunks_exact_mut(block)
+                .for_each(|block| self.scan(block));
+        } else {
+            // there are too few blocks to keep every thread busy, so scan each one in parallel
+            output
+                .chunks_exact_mut(block)
+                .for_each(|block| self.par_scan(block));
+        }
+
+        Ok(output)
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<Stack, T> for Scan<A, T> {
+    type Buffer = StackVec<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let mut output = self.access.read()?.to_slice()?.into_stackvec();
+        let block = self.dim * self.inner;
+
+        if block == 0 {
+            return Ok(output);
+        }
+
+        output
+            .chunks_exact_mut(block)
+            .for_each(|block| self.scan(block));
+
+        Ok(output)
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<Host, T> for Scan<A, T> {
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        host_enqueue!(self, self.size() < VEC_MIN_SIZE, T)
+    }
+}
+
+impl<A: Access<T>, T: CType> ReadValue<Host, T> for Scan<A, T> {
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        let block = self.dim * self.inner;
+        let start = offset - (offset % block) + (offset % self.inner);
+
+        (start..=offset)
+            .step_by(self.inner)
+            .map(|offset| self.access.read_value(offset))
+            .reduce(|acc, n| Ok((self.scan)(acc?, n?)))
+            .expect("scanned value")
+    }
+}
+
+/// The lanes along one axis of an array to sort, and the direction to sort them
+#[derive(Copy, Clone, Debug)]
+pub struct SortSpec {
+    pub dim: usize,
+    pub inner: usize,
+    pub descending: bool,
+}
+
+impl SortSpec {
+    /// The index of the lane of the element at `offset`.
+    #[inline]
+    fn lane(&self, offset: usize) -> usize {
+        (offset / (self.dim * self.inner)) * self.inner + (offset % self.inner)
+    }
+
+    /// The coordinate of the element at `offset` within its lane.
+    #[inline]
+    fn coord(&self, offset: usize) -> usize {
+        (offset / self.inner) % self.dim
+    }
+
+    /// The offset of the first element of the given `lane`.
+    #[inline]
+    fn start(&self, lane: usize) -> usize {
+        (lane / self.inner) * self.dim * self.inner + (lane % self.inner)
+    }
+
+    #[inline]
+    fn compare<T: CType>(&self, l: &T, r: &T) -> Ordering {
+        if self.descending {
+            sort_order(r, l)
+        } else {
+            sort_order(l, r)
+        }
+    }
+
+    fn values<T: CType>(&self, input: &[T], lane: usize) -> Vec<T> {
+        let start = self.start(lane);
+        (0..self.dim)
+            .map(|i| input[start + (i * self.inner)])
+            .collect()
+    }
+
+    fn read_values<A: Access<T>, T: CType>(
+        &self,
+        access: &A,
+        lane: usize,
+    ) -> Result<Vec<T>, Error> {
+        let start = self.start(lane);
+
+        (0..self.dim)
+            .map(|i| access.read_value(start + (i * self.inner)))
+            .collect()
+    }
+
+    fn sort<T: CType>(&self, mut values: Vec<T>, parallel: bool) -> Vec<T> {
+        if parallel {
+            values.par_sort_by(|l, r| self.compare(l, r));
+        } else {
+            values.sort_by(|l, r| self.compare(l, r));
+        }
+
+        values
+    }
+
+    fn argsort<T: CType>(&self, values: Vec<T>, parallel: bool) -> Vec<u64> {
+        let mut indices = (0..self.dim as u64).collect::<Vec<u64>>();
+        let order = |i: &u64, j: &u64| self.compare(&values[*i as usize], &values[*j as usize]);
+
+        if parallel {
+            indices.par_sort_by(order);
+        } else {
+            indices.sort_by(order);
+        }
+
+        indices
+    }
+
+    /// Sort each lane of the `input` with the given `sort`, keeping the layout of the `input`.
+    fn sort_lanes<T, U, F>(&self, input: &[T], sort: F) -> Vec<U>
+    where
+        T: CType,
+        U: CType,
+        F: Fn(Vec<T>, bool) -> Vec<U> + Send + Sync,
+    {
+        // an empty lane has nothing to sort
+        if input.is_empty() {
+            return Vec::new();
+        }
+
+        let lanes = input.len() / self.dim;
+
+        // sort each lane in parallel if there are too few lanes to keep every thread busy
+        let sorted = if lanes >= current_num_threads() {
+            (0..lanes)
+                .into_par_iter()
+                .map(|lane| sort(self.values(input, lane), false))
+                .collect::<Vec<_>>()
+        } else {
+            (0..lanes)
+                .map(|lane| sort(self.values(input, lane), true))
+                .collect::<Vec<_>>()
+        };
+
+        (0..input.len())
+            .into_par_iter()
+            .map(|offset| sorted[self.lane(offset)][self.coord(offset)])
+            .collect()
+    }
+}
+
+pub struct Sort<A, T> {
+    access: A,
+    spec: SortSpec,
+    dtype: PhantomData<T>,
+}
+
+impl<A, T> Sort<A, T> {
+    pub fn new(access: A, spec: SortSpec) -> Self {
+        Self {
+            access,
+            spec,
+            dtype: PhantomData,
+        }
+    }
+}
+
+impl<A: Access<T>, T: CType> Op for Sort<A, T> {
+    fn size(&self) -> usize {
+        self.access.size()
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan()]
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<Stack, T> for Sort<A, T> {
+    type Buffer = StackVec<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let mut output = self.access.read()?.to_slice()?.into_stackvec();
+
+        if output.is_empty() {
+            return Ok(output);
+        }
+
+        // sort contiguous lanes in place
+        if self.spec.inner == 1 {
+            for lane in output.chunks_exact_mut(self.spec.dim) {
+                lane.sort_by(|l, r| self.spec.compare(l, r));
+            }
+
+            return Ok(output);
+        }
+
+        let lanes = output.len() / self.spec.dim;
+        let sorted = (0..lanes)
+            .map(|lane| self.spec.sort(self.spec.values(&output, lane), false))
+            .collect::<Vec<_>>();
+
+        for (offset, n) in output.iter_mut().enumerate() {
+            *n = sorted[self.spec.lane(offset)][self.spec.coord(offset)];
+        }
+
+        Ok(output)
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<Heap, T> for Sort<A, T> {
+    type Buffer = Vec<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let input = self.access.read()?.to_slice()?;
+        Ok(self
+            .spec
+            .sort_lanes(&input, |values, parallel| self.spec.sort(values, parallel)))
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<Host, T> for Sort<A, T> {
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        host_enqueue!(self, self.size() < VEC_MIN_SIZE, T)
+    }
+}
+
+impl<A: Access<T>, T: CType> ReadValue<Host, T> for Sort<A, T> {
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        read_sort(&self.access, self.spec, offset)
+    }
+}
+
+pub struct ArgSort<A, T> {
+    access: A,
+    spec: SortSpec,
+    dtype: PhantomData<T>,
+}
+
+impl<A, T> ArgSort<A, T> {
+    pub fn new(access: A, spec: SortSpec) -> Self {
+        Self {
+            access,
+            spec,
+            dtype: PhantomData,
+        }
+    }
+}
+
+impl<A: Access<T>, T: CType> Op for ArgSort<A, T> {
+    fn size(&self) -> usize {
+        self.access.size()
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan()]
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<Stack, u64> for ArgSort<A, T> {
+    type Buffer = StackVec<u64>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let input = self.access.read()?.to_slice()?;
+
+        if input.is_empty() {
+            return Ok(StackVec::new());
+        }
+
+        let lanes = input.len() / self.spec.dim;
+
+        let sorted = (0..lanes)
+            .map(|lane| self.spec.argsort(self.spec.values(&input, lane), false))
+            .collect::<Vec<_>>();
+
+        let output = (0..input.len())
+            .map(|offset| sorted[self.spec.lane(offset)][self.spec.coord(offset)])
+            .collect();
+
+        Ok(output)
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<Heap, u64> for ArgSort<A, T> {
+    type Buffer = Vec<u64>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let input = self.access.read()?.to_slice()?;
+        Ok(self.spec.sort_lanes(&input, |values, parallel| {
+            self.spec.argsort(values, parallel)
+        }))
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<Host, u64> for ArgSort<A, T> {
+    type Buffer = Buffer<u64>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        host_enqueue!(self, self.size() < VEC_MIN_SIZE, u64)
+    }
+}
+
+impl<A: Access<T>, T: CType> ReadValue<Host, u64> for ArgSort<A, T> {
+    fn read_value(&self, offset: usize) -> Result<u64, Error> {
+        read_argsort(&self.access, self.spec, offset)
+    }
+}
+
+pub struct Slice<A, T> {
+    access: A,
+    spec: SliceSpec,
+    dtype: PhantomData<T>,
+}
+
+impl<A, T> Slice<A, T> {
+    pub fn new(access: A, shape: &[usize], range: Range) -> Self {
+        let spec = SliceSpec::new(shape, range);
+
+        Self {
+            access,
+            spec,
+            dtype: PhantomData,
+        }
+    }
+}
+
+impl<A: Send + Sync, T: Copy + Send + Sync> Slice<A, T> {
+    fn read(&self, source: &[T]) -> Result<StackVec<T>, Error> {
+        let output = (0..self.spec.size())
+            .map(|offset_out| self.spec.source_offset(offset_out))
+            .map(|offset_in| source[offset_in])
+            .collect();
+
+        Ok(output)
+    }
+
+    fn read_parallel(&self, source: &[T]) -> Result<Vec<T>, Error> {
+        let output = (0..self.spec.size())
+            .into_par_iter()
+            .map(|offset_out| self.spec.source_offset(offset_out))
+            .map(|offset_in| source[offset_in])
+            .collect();
+
+        Ok(output)
+    }
+}
+
+impl<A, T> Slice<A, T>
+where
+    T: CType,
+    A: AccessMut<T>,
+{
+    fn overwrite<'a>(&mut self, data: BufferConverter<'a, T>) -> Result<(), Error> {
+        if data.len() == self.size() {
+            let data = data.to_slice()?;
+
+            for (offset, value) in data.into_iter().copied().enumerate() {
+                let source_offset = self.spec.source_offset(offset);
+                self.access.write_value_at(source_offset, value)?;
+            }
+
+            Ok(())
+        } else {
+            Err(Error::Bounds(format!(
+                "cannot overwrite a slice of size {} with a buffer of size {}",
+                self.size(),
+                data.len(),
+            )))
+        }
+    }
+
+    fn overwrite_value(&mut self, value: T) -> Result<(), Error> {
+        for offset in 0..self.access.size() {
+            let source_offset = self.spec.source_offset(offset);
+            self.access.write_value_at(source_offset, value)?;
+        }
+
+        Ok(())
+    }
+
+    fn overwrite_value_at(&mut self, offset: usize, value: T) -> Result<(), Error> {
+        let source_offset = self.spec.source_offset(offset);
+        self.access.write_value_at(source_offset, value)
+    }
+}
+
+impl<A: Access<T>, T: CType> Op for Slice<A, T> {
+    fn size(&self) -> usize {
+        self.spec.size()
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan()]
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<Heap, T> for Slice<A, T> {
+    type Buffer = Vec<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        self.access
+            .read()
+            .and_then(|buf| buf.to_slice())
+            .and_then(|buf| self.read_parallel(&*buf))
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<Stack, T> for Slice<A, T> {
+    type Buffer = StackVec<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        self.access
+            .read()
+            .and_then(|buf| buf.to_slice())
+            .and_then(|buf| self.read(&*buf))
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<Host, T> for Slice<A, T> {
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        host_enqueue!(self, self.size() < VEC_MIN_SIZE, T)
+    }
+}
+
+impl<A: Access<T>, T: CType> ReadValue<Host, T> for Slice<A, T> {
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        let offset = self.spec.source_offset(offset);
+        self.access.read_value(offset)
+    }
+}
+
+impl<A, T> crate::ops::Write<Heap, T> for Slice<A, T>
+where
+    T: CType,
+    A: AccessMut<T>,
+{
+    fn write<'a>(&mut self, data: BufferConverter<'a, T>) -> Result<(), Error> {
+        self.overwrite(data)
+    }
+
+    fn write_value(&mut self, value: T) -> Result<(), Error> {
+        self.overwrite_value(value)
     }
 
-    pub fn sub(access: A, scalar: T) -> Self {
-        Self::new(access, scalar, T::sub)
+    fn write_value_at(&mut self, offset: usize, value: T) -> Result<(), Error> {
+        self.overwrite_value_at(offset, value)
     }
 }
 
-impl<A, T> Scalar<A, T, u8> {
-    pub fn and(access: A, scalar: T) -> Self
-    where
-        T: CType,
-    {
-        Self::new(access, scalar, |l, r| {
-            if (l != T::ZERO) && (r != T::ZERO) {
-                1
-            } else {
-                0
-            }
-        })
-    }
-
-    pub fn or(access: A, scalar: T) -> Self
-    where
-        T: CType,
-    {
-        Self::new(access, scalar, |l, r| {
-            if (l != T::ZERO) || (r != T::ZERO) {
-                1
-            } else {
-                0
-            }
-        })
+impl<A, T> crate::ops::Write<Stack, T> for Slice<A, T>
+where
+    T: CType,
+    A: AccessMut<T>,
+{
+    fn write<'a>(&mut self, data: BufferConverter<'a, T>) -> Result<(), Error> {
+        self.overwrite(data)
     }
 
-    pub fn xor(access: A, scalar: T) -> Self
-    where
-        T: CType,
-    {
-        Self::new(access, scalar, |l, r| {
-            if (l != T::ZERO) ^ (r != T::ZERO) {
-                1
-            } else {
-                0
-            }
-        })
+    fn write_value(&mut self, value: T) -> Result<(), Error> {
+        self.overwrite_value(value)
     }
 
-    pub fn eq(access: A, scalar: T) -> Self
-    where
-        T: PartialEq,
-    {
-        Self::new(access, scalar, |l, r| if l == r { 1 } else { 0 })
+    fn write_value_at(&mut self, offset: usize, value: T) -> Result<(), Error> {
+        self.overwrite_value_at(offset, value)
     }
+}
 
-    pub fn ge(access: A, scalar: T) -> Self
-    where
-        T: PartialOrd,
-    {
-        Self::new(access, scalar, |l, r| if l >= r { 1 } else { 0 })
+impl<A, T> crate::ops::Write<Host, T> for Slice<A, T>
+where
+    T: CType,
+    A: AccessMut<T>,
+{
+    fn write<'a>(&mut self, data: BufferConverter<'a, T>) -> Result<(), Error> {
+        self.overwrite(data)
     }
 
-    pub fn gt(access: A, scalar: T) -> Self
-    where
-        T: PartialOrd,
-    {
-        Self::new(access, scalar, |l, r| if l > r { 1 } else { 0 })
+    fn write_value(&mut self, value: T) -> Result<(), Error> {
+        self.overwrite_value(value)
     }
 
-    pub fn le(access: A, scalar: T) -> Self
-    where
-        T: PartialOrd,
-    {
-        Self::new(access, scalar, |l, r| if l <= r { 1 } else { 0 })
+    fn write_value_at(&mut self, offset: usize, value: T) -> Result<(), Error> {
+        self.overwrite_value_at(offset, value)
     }
+}
 
-    pub fn lt(access: A, scalar: T) -> Self
-    where
-        T: PartialOrd,
-    {
-        Self::new(access, scalar, |l, r| if l < r { 1 } else { 0 })
-    }
+pub struct SolveTridiagonal<D, R, T> {
+    diagonals: D,
+    rhs: R,
+    batch_size: usize,
+    dim: usize,
+    dtype: PhantomData<T>,
+}
 
-    pub fn ne(access: A, scalar: T) -> Self
-    where
-        T: PartialEq,
-    {
-        Self::new(access, scalar, |l, r| if l != r { 1 } else { 0 })
+impl<D, R, T> SolveTridiagonal<D, R, T> {
+    pub fn new(diagonals: D, rhs: R, batch_size: usize, dim: usize) -> Self {
+        Self {
+            diagonals,
+            rhs,
+            batch_size,
+            dim,
+            dtype: PhantomData,
+        }
     }
 }
 
-impl<A, IT, OT> Op for Scalar<A, IT, OT>
+impl<D, R, T> Op for SolveTridiagonal<D, R, T>
 where
-    A: Access<IT>,
-    IT: CType,
-    OT: CType,
+    D: Access<T>,
+    R: Access<T>,
+    T: Float,
 {
     fn size(&self) -> usize {
-        self.access.size()
+        self.batch_size * self.dim
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.diagonals.plan(), self.rhs.plan()]
     }
 }
 
-impl<A, IT, OT> Enqueue<Heap, OT> for Scalar<A, IT, OT>
+impl<D, R, T> Enqueue<Stack, T> for SolveTridiagonal<D, R, T>
 where
-    A: Access<IT>,
-    IT: CType,
-    OT: CType,
+    D: Access<T>,
+    R: Access<T>,
+    T: Float,
 {
-    type Buffer = Vec<OT>;
+    type Buffer = StackVec<T>;
 
     fn enqueue(&self) -> Result<Self::Buffer, Error> {
-        self.access
-            .read()
-            .and_then(|buf| buf.to_slice())
-            .map(|slice| {
-                slice
-                    .as_ref()
-                    .into_par_iter()
-                    .copied()
-                    .map(|l| (self.op)(l, self.scalar))
-                    .collect()
-            })
+        let diagonals = self.diagonals.read()?.to_slice()?;
+        let rhs = self.rhs.read()?.to_slice()?;
+
+        let mut output = stackvec![T::ZERO; self.size()];
+
+        for ((diagonals, rhs), output) in diagonals
+            .chunks_exact(3 * self.dim)
+            .zip(rhs.chunks_exact(self.dim))
+            .zip(output.chunks_exact_mut(self.dim))
+        {
+            solve_tridiagonal(diagonals, rhs, output);
+        }
+
+        Ok(output)
     }
 }
 
-impl<A, IT, OT> Enqueue<Stack, OT> for Scalar<A, IT, OT>
+impl<D, R, T> Enqueue<Heap, T> for SolveTridiagonal<D, R, T>
 where
-    A: Access<IT>,
-    IT: CType,
-    OT: CType,
+    D: Access<T>,
+    R: Access<T>,
+    T: Float,
 {
-    type Buffer = StackVec<OT>;
+    type Buffer = Vec<T>;
 
     fn enqueue(&self) -> Result<Self::Buffer, Error> {
-        self.access
-            .read()
-            .and_then(|buf| buf.to_slice())
-            .map(|slice| {
-                slice
-                    .as_ref()
-                    .into_iter()
-                    .copied()
-                    .map(|l| (self.op)(l, self.scalar))
-                    .collect()
-            })
+        let (diagonals, rhs) = try_join_read(&self.diagonals, &self.rhs)?;
+
+        let mut output = vec![T::ZERO; self.size()];
+
+        output
+            .par_chunks_exact_mut(self.dim)
+            .zip(diagonals.par_chunks_exact(3 * self.dim))
+            .zip(rhs.par_chunks_exact(self.dim))
+            .for_each(|((output, diagonals), rhs)| solve_tridiagonal(diagonals, rhs, output));
+
+        Ok(output)
     }
 }
 
-impl<A, IT, OT> Enqueue<Host, OT> for Scalar<A, IT, OT>
+impl<D, R, T> Enqueue<Host, T> for SolveTridiagonal<D, R, T>
 where
-    A: Access<IT>,
-    IT: CType,
-    OT: CType,
+    D: Access<T>,
+    R: Access<T>,
+    T: Float,
 {
-    type Buffer = Buffer<OT>;
+    type Buffer = Buffer<T>;
 
     fn enqueue(&self) -> Result<Self::Buffer, Error> {
-        host_enqueue!(self, self.size() < VEC_MIN_SIZE, OT)
+        host_enqueue!(self, self.size() < VEC_MIN_SIZE, T)
     }
 }
 
-impl<A, IT, OT> ReadValue<Host, OT> for Scalar<A, IT, OT>
+impl<D, R, T> ReadValue<Host, T> for SolveTridiagonal<D, R, T>
 where
-    A: Access<IT>,
-    IT: CType,
-    OT: CType,
+    D: Access<T>,
+    R: Access<T>,
+    T: Float,
 {
-    fn read_value(&self, offset: usize) -> Result<OT, Error> {
-        self.access
-            .read_value(offset)
-            .map(|n| (self.op)(n, self.scalar))
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        read_tridiagonal(&self.diagonals, &self.rhs, self.dim, offset)
     }
 }
 
-pub struct RandomNormal {
-    size: usize,
-}
+/// Solve the tridiagonal system with the given `diagonals` (the sub-, main, and super-diagonals
+/// in that order) and right-hand side `rhs` using the Thomas algorithm, writing the solution
+/// to `output`. The system is not pivoted, so it should be diagonally dominant to be stable.
+pub(crate) fn solve_tridiagonal<T: Float>(diagonals: &[T], rhs: &[T], output: &mut [T]) {
+    let dim = rhs.len();
+    debug_assert_eq!(diagonals.len(), 3 * dim);
+    debug_assert_eq!(output.len(), dim);
 
-impl RandomNormal {
-    pub fn new(size: usize) -> Self {
-        Self { size }
+    let (sub, rest) = diagonals.split_at(dim);
+    let (main, sup) = rest.split_at(dim);
+
+    // eliminate the sub-diagonal, normalizing each row by its pivot
+    let mut modified = vec![T::ZERO; dim];
+
+    for i in 0..dim {
+        let (pivot, prev) = if i == 0 {
+            (main[0], T::ZERO)
+        } else {
+            let pivot = T::sub(main[i], T::mul(sub[i], modified[i - 1]));
+            (pivot, T::mul(sub[i], output[i - 1]))
+        };
+
+        modified[i] = T::div(sup[i], pivot);
+        output[i] = T::div(T::sub(rhs[i], prev), pivot);
     }
 
-    fn box_muller(u: [f32; 2]) -> [f32; 2] {
-        let [u1, u2] = u;
-        let r = (u1.ln() * -2.).sqrt();
-        let theta = 2. * PI * u2;
-        [r * theta.cos(), r * theta.sin()]
+    // back-substitute
+    for i in (0..dim.saturating_sub(1)).rev() {
+        output[i] = T::sub(output[i], T::mul(modified[i], output[i + 1]));
     }
 }
 
-impl Op for RandomNormal {
+/// Solve the tridiagonal system which contains the element at `offset` of the solution
+/// of a batch of systems of `dim` equations, reading each of its inputs one element at a time.
+pub(crate) fn read_tridiagonal<D, R, T>(
+    diagonals: &D,
+    rhs: &R,
+    dim: usize,
+    offset: usize,
+) -> Result<T, Error>
+where
+    D: Access<T>,
+    R: Access<T>,
+    T: Float,
+{
+    let system = offset / dim;
+
+    let diagonals = ((system * 3 * dim)..((system + 1) * 3 * dim))
+        .map(|i| diagonals.read_value(i))
+        .collect::<Result<Vec<T>, Error>>()?;
+
+    let rhs = ((system * dim)..((system + 1) * dim))
+        .map(|i| rhs.read_value(i))
+        .collect::<Result<Vec<T>, Error>>()?;
+
+    let mut output = vec![T::ZERO; dim];
+    solve_tridiagonal(&diagonals, &rhs, &mut output);
+    Ok(output[offset % dim])
+}
+
+pub struct Inverse<A, T> {
+    access: A,
+    batch_size: usize,
+    dim: usize,
+    dtype: PhantomData<T>,
+}
+
+impl<A, T> Inverse<A, T> {
+    pub fn new(access: A, batch_size: usize, dim: usize) -> Self {
+        Self {
+            access,
+            batch_size,
+            dim,
+            dtype: PhantomData,
+        }
+    }
+}
+
+impl<A: Access<T>, T: CType> Op for Inverse<A, T> {
     fn size(&self) -> usize {
-        self.size
+        debug_assert_eq!(self.access.size(), self.batch_size * self.dim * self.dim);
+        self.batch_size * self.dim * self.dim
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan()]
     }
 }
 
-impl Enqueue<Heap, f32> for RandomNormal {
-    type Buffer = Vec<f32>;
+impl<A: Access<T>, T: CType> Enqueue<Stack, T::Float> for Inverse<A, T> {
+    type Buffer = StackVec<T::Float>;
 
     fn enqueue(&self) -> Result<Self::Buffer, Error> {
-        let mut u = vec![
-            0.0f32;
-            if self.size % 2 == 0 {
-                self.size
-            } else {
-                self.size + 1
-            }
-        ];
-
-        rand::thread_rng().fill(&mut u[..]);
+        let input = self.access.read()?.to_slice()?;
+        let matrix_size = self.dim * self.dim;
 
-        let mut output = u
-            .par_chunks_exact(2)
-            .map(|u| {
-                let u: [f32; 2] = u.try_into().expect("u");
-                Self::box_muller(u)
-            })
-            .flatten()
-            .collect::<Vec<f32>>();
+        let mut output = stackvec![T::Float::ZERO; self.size()];
 
-        if output.len() > self.size {
-            output.pop();
+        if matrix_size == 0 {
+            return Ok(output);
         }
 
-        debug_assert_eq!(output.len(), self.size);
+        for (matrix, output) in input
+            .chunks_exact(matrix_size)
+            .zip(output.chunks_exact_mut(matrix_size))
+        {
+            invert(matrix, self.dim, output);
+        }
 
         Ok(output)
     }
 }
 
-impl Enqueue<Stack, f32> for RandomNormal {
-    type Buffer = StackVec<f32>;
+impl<A: Access<T>, T: CType> Enqueue<Heap, T::Float> for Inverse<A, T> {
+    type Buffer = Vec<T::Float>;
 
     fn enqueue(&self) -> Result<Self::Buffer, Error> {
-        let mut rng = rand::thread_rng();
+        let input = self.access.read()?.to_slice()?;
+        let matrix_size = self.dim * self.dim;
 
-        let mut output = iter::repeat_with(|| [rng.gen(), rng.gen()])
-            .take(self.size.div_ceil(2))
-            .map(Self::box_muller)
-            .flatten()
-            .collect::<StackVec<f32>>();
+        let mut output = vec![T::Float::ZERO; self.size()];
 
-        if output.len() > self.size {
-            output.pop();
+        if matrix_size == 0 {
+            return Ok(output);
         }
 
-        debug_assert_eq!(output.len(), self.size);
+        output
+            .par_chunks_exact_mut(matrix_size)
+            .zip(input.par_chunks_exact(matrix_size))
+            .for_each(|(output, matrix)| invert(matrix, self.dim, output));
 
         Ok(output)
     }
 }
 
-impl Enqueue<Host, f32> for RandomNormal {
-    type Buffer = Buffer<f32>;
+impl<A: Access<T>, T: CType> Enqueue<Host, T::Float> for Inverse<A, T> {
+    type Buffer = Buffer<T::Float>;
 
     fn enqueue(&self) -> Result<Self::Buffer, Error> {
-        host_enqueue!(self, self.size < VEC_MIN_SIZE, f32)
+        host_enqueue!(self, self.size() < VEC_MIN_SIZE, T::Float)
     }
 }
 
-impl ReadValue<Host, f32> for RandomNormal {
-    fn read_value(&self, _offset: usize) -> Result<f32, Error> {
-        Err(Error::Bounds(
-            "cannot calculate an individual value of a random normal distribution".to_string(),
-        ))
+impl<A: Access<T>, T: CType> ReadValue<Host, T::Float> for Inverse<A, T> {
+    fn read_value(&self, offset: usize) -> Result<T::Float, Error> {
+        read_inverse(&self.access, self.dim, offset)
     }
 }
 
-pub struct RandomUniform {
-    size: usize,
-}
+/// Factor the row-major square `matrix` in place into `PA = LU` with partial pivoting,
+/// where `L` is unit lower triangular and `U` is upper triangular, recording in `pivots`
+/// the source row of each row of `PA`. A singular matrix has a zero on the diagonal of `U`.
+pub(crate) fn lu_factor<T: Float>(matrix: &mut [T], pivots: &mut [usize]) {
+    let dim = pivots.len();
+    debug_assert_eq!(matrix.len(), dim * dim);
+
+    pivots.iter_mut().enumerate().for_each(|(i, p)| *p = i);
+
+    for k in 0..dim {
+        // pivot on the row with the largest magnitude in column k
+        let pivot = (k..dim)
+            .max_by(|i, j| {
+                let (a, b) = (T::abs(matrix[i * dim + k]), T::abs(matrix[j * dim + k]));
+                a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+            })
+            .expect("pivot");
 
-impl RandomUniform {
-    pub fn new(size: usize) -> Self {
-        Self { size }
-    }
-}
+        if pivot != k {
+            for j in 0..dim {
+                matrix.swap(k * dim + j, pivot * dim + j);
+            }
 
-impl Op for RandomUniform {
-    fn size(&self) -> usize {
-        self.size
-    }
-}
+            pivots.swap(k, pivot);
+        }
 
-impl Enqueue<Heap, f32> for RandomUniform {
-    type Buffer = Vec<f32>;
+        let diagonal = matrix[k * dim + k];
 
-    fn enqueue(&self) -> Result<Self::Buffer, Error> {
-        let mut data = vec![0.; self.size];
-        rand::thread_rng().fill(&mut data[..]);
-        Ok(data)
+        for i in (k + 1)..dim {
+            let l = T::div(matrix[i * dim + k], diagonal);
+            matrix[i * dim + k] = l;
+
+            for j in (k + 1)..dim {
+                let u = T::mul(l, matrix[k * dim + j]);
+                matrix[i * dim + j] = T::sub(matrix[i * dim + j], u);
+            }
+        }
     }
 }
 
-impl Enqueue<Stack, f32> for RandomUniform {
-    type Buffer = StackVec<f32>;
+/// Compute the inverse of the row-major `matrix` with shape `[dim, dim]` via LU decomposition
+/// with partial pivoting, writing it to `output`. The inverse of a singular matrix is not finite.
+pub(crate) fn invert<T: CType>(matrix: &[T], dim: usize, output: &mut [T::Float]) {
+    debug_assert_eq!(dim * dim, matrix.len());
+    debug_assert_eq!(output.len(), matrix.len());
+
+    let mut lu = matrix.iter().copied().map(T::to_float).collect::<Vec<_>>();
+    let mut pivots = vec![0; dim];
+    lu_factor(&mut lu, &mut pivots);
+
+    // column j of the inverse solves LUx = Pe_j
+    for j in 0..dim {
+        for i in 0..dim {
+            let mut sum = if pivots[i] == j {
+                T::Float::ONE
+            } else {
+                T::Float::ZERO
+            };
 
-    fn enqueue(&self) -> Result<Self::Buffer, Error> {
-        let mut data = stackvec![0.; self.size];
-        rand::thread_rng().fill(&mut data[..]);
-        Ok(data)
-    }
-}
+            for k in 0..i {
+                sum = T::Float::sub(sum, T::Float::mul(lu[i * dim + k], output[k * dim + j]));
+            }
 
-impl Enqueue<Host, f32> for RandomUniform {
-    type Buffer = Buffer<f32>;
+            output[i * dim + j] = sum;
+        }
 
-    fn enqueue(&self) -> Result<Self::Buffer, Error> {
-        host_enqueue!(self, self.size < VEC_MIN_SIZE, f32)
+        for i in (0..dim).rev() {
+            let mut sum = output[i * dim + j];
+
+            for k in (i + 1)..dim {
+                sum = T::Float::sub(sum, T::Float::mul(lu[i * dim + k], output[k * dim + j]));
+            }
+
+            output[i * dim + j] = T::Float::div(sum, lu[i * dim + i]);
+        }
     }
 }
 
-impl ReadValue<Host, f32> for RandomUniform {
-    fn read_value(&self, _offset: usize) -> Result<f32, Error> {
-        Ok(rand::thread_rng().gen())
-    }
+/// Invert the matrix which contains the element at `offset` of the inverse of a batch of
+/// matrices with shape `[dim, dim]`, reading its input one element at a time.
+pub(crate) fn read_inverse<A, T>(access: &A, dim: usize, offset: usize) -> Result<T::Float, Error>
+where
+    A: Access<T>,
+    T: CType,
+{
+    let matrix_size = dim * dim;
+    let start = (offset / matrix_size) * matrix_size;
+
+    let matrix = (start..(start + matrix_size))
+        .map(|i| access.read_value(i))
+        .collect::<Result<Vec<T>, Error>>()?;
+
+    let mut output = vec![T::Float::ZERO; matrix_size];
+    invert(&matrix, dim, &mut output);
+    Ok(output[offset - start])
 }
 
-pub struct Reduce<A, T> {
+pub struct Decompose<A, T: CType> {
     access: A,
-    stride: usize,
-    reduce: fn(T, T) -> T,
-    id: T,
+    factor: Factor,
+    batch_size: usize,
+    dims: [usize; 2],
+    factorization: Factorization<T>,
 }
 
-impl<A, T> Reduce<A, T>
-where
-    T: CType,
-{
-    pub fn max(access: A, stride: usize) -> Self {
+impl<A, T: CType> Decompose<A, T> {
+    pub fn new(
+        access: A,
+        factor: Factor,
+        batch_size: usize,
+        dims: [usize; 2],
+        factorization: Factorization<T>,
+    ) -> Self {
         Self {
             access,
-            stride,
-            reduce: CType::max,
-            id: T::MIN,
+            factor,
+            batch_size,
+            dims,
+            factorization,
         }
     }
 
-    pub fn min(access: A, stride: usize) -> Self {
-        Self {
-            access,
-            stride,
-            reduce: CType::min,
-            id: T::MAX,
-        }
+    fn factor_size(&self) -> usize {
+        self.factor.shape(self.dims).iter().product()
     }
 
-    pub fn product(access: A, stride: usize) -> Self {
-        Self {
-            access,
-            stride,
-            reduce: T::mul,
-            id: T::ONE,
+    /// Factor each matrix in `input` once, returning this factor and caching its siblings.
+    fn decompose_batch(&self, input: &[T], parallel: bool) -> Vec<T::Float> {
+        if let Some(output) = self.factorization.take(0, input, self.factor) {
+            return output;
         }
-    }
 
-    pub fn sum(access: A, stride: usize) -> Self {
-        Self {
-            access,
-            stride,
-            reduce: T::add,
-            id: T::ZERO,
+        let (dims, factor) = (self.dims, self.factor);
+        let matrix_size = dims[0] * dims[1];
+
+        let matrices = if parallel {
+            input
+                .par_chunks_exact(matrix_size)
+                .map(|matrix| decompose(matrix, dims, factor))
+                .collect::<Vec<_>>()
+        } else {
+            input
+                .chunks_exact(matrix_size)
+                .map(|matrix| decompose(matrix, dims, factor))
+                .collect::<Vec<_>>()
+        };
+
+        let mut factors = self
+            .factor
+            .siblings()
+            .iter()
+            .enumerate()
+            .map(|(i, factor)| {
+                let output = matrices
+                    .iter()
+                    .flat_map(|factors| factors[i].iter().copied())
+                    .collect::<Vec<_>>();
+
+                (*factor, output)
+            })
+            .collect::<Vec<_>>();
+
+        let i = sibling_index(self.factor);
+        let (_, output) = factors.swap_remove(i);
+
+        if !factors.is_empty() {
+            self.factorization.insert(0, input.to_vec(), factors);
         }
+
+        output
     }
 }
 
-impl<A: Access<T>, T: CType> Op for Reduce<A, T> {
+impl<A: Access<T>, T: CType> Op for Decompose<A, T> {
     fn size(&self) -> usize {
-        debug_assert_eq!(self.access.size() % self.stride, 0);
-        self.access.size() / self.stride
+        debug_assert_eq!(
+            self.access.size(),
+            self.batch_size * self.dims[0] * self.dims[1]
+        );
+
+        self.batch_size * self.factor_size()
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan()]
     }
 }
 
-impl<A: Access<T>, T: CType> Enqueue<Heap, T> for Reduce<A, T> {
-    type Buffer = Vec<T>;
+impl<A: Access<T>, T: CType> Enqueue<Stack, T::Float> for Decompose<A, T> {
+    type Buffer = StackVec<T::Float>;
 
     fn enqueue(&self) -> Result<Self::Buffer, Error> {
-        self.access
-            .read()
-            .and_then(|buf| buf.to_slice())
-            .map(|slice| {
-                slice
-                    .chunks_exact(self.stride)
-                    .map(|chunk| {
-                        chunk
-                            // encourage the compiler to vectorize
-                            .par_chunks(8)
-                            .map(|chunk| {
-                                chunk.iter().copied().reduce(self.reduce).expect("reduced")
-                            })
-                            .reduce(|| self.id, self.reduce)
-                    })
-                    .collect()
-            })
+        let input = self.access.read()?.to_slice()?;
+        Ok(self.decompose_batch(&input, false).into())
     }
 }
 
-impl<A: Access<T>, T: CType> Enqueue<Stack, T> for Reduce<A, T> {
-    type Buffer = StackVec<T>;
+impl<A: Access<T>, T: CType> Enqueue<Heap, T::Float> for Decompose<A, T> {
+    type Buffer = Vec<T::Float>;
 
     fn enqueue(&self) -> Result<Self::Buffer, Error> {
-        self.access
-            .read()
-            .and_then(|buf| buf.to_slice())
-            .map(|slice| {
-                slice
-                    .chunks_exact(self.stride)
-                    .map(|chunk| chunk.iter().copied().reduce(self.reduce).expect("reduced"))
-                    .collect()
-            })
+        let input = self.access.read()?.to_slice()?;
+        Ok(self.decompose_batch(&input, true))
     }
 }
 
-impl<A: Access<T>, T: CType> Enqueue<Host, T> for Reduce<A, T> {
-    type Buffer = Buffer<T>;
+impl<A: Access<T>, T: CType> Enqueue<Host, T::Float> for Decompose<A, T> {
+    type Buffer = Buffer<T::Float>;
 
     fn enqueue(&self) -> Result<Self::Buffer, Error> {
-        host_enqueue!(
-            self,
-            self.stride < VEC_MIN_SIZE && self.size() < VEC_MIN_SIZE,
-            T
+        host_enqueue!(self, self.size() < VEC_MIN_SIZE, T::Float)
+    }
+}
+
+impl<A: Access<T>, T: CType> ReadValue<Host, T::Float> for Decompose<A, T> {
+    fn read_value(&self, offset: usize) -> Result<T::Float, Error> {
+        read_decompose(
+            &self.access,
+            self.factor,
+            self.dims,
+            &self.factorization,
+            offset,
         )
     }
 }
 
-impl<A: Access<T>, T: CType> ReadValue<Host, T> for Reduce<A, T> {
-    fn read_value(&self, offset: usize) -> Result<T, Error> {
-        let offset = offset * self.stride;
+/// The position of the given `factor` in [`Factor::siblings`]
+fn sibling_index(factor: Factor) -> usize {
+    factor
+        .siblings()
+        .iter()
+        .position(|sibling| *sibling == factor)
+        .expect("sibling factor")
+}
 
-        if offset < self.access.size() {
-            (offset..(offset + self.stride))
-                .into_par_iter()
-                .map(|offset| self.access.read_value(offset))
-                .try_reduce(|| self.id, |r, v| Ok((self.reduce)(r, v)))
-        } else {
-            Err(Error::Bounds(format!(
-                "invalid offset {offset} for a reduce op with size {}",
-                self.size()
-            )))
+/// A factored matrix, from which each factor of its decomposition can be read
+enum Factors<F> {
+    Lu {
+        a: Vec<F>,
+        pivots: Vec<usize>,
+    },
+    Qr {
+        r: Vec<F>,
+        q: Vec<F>,
+    },
+    Cholesky(Vec<F>),
+    Eigh {
+        a: Vec<F>,
+        v: Vec<F>,
+        order: Vec<usize>,
+    },
+}
+
+impl<F: Float> Factors<F> {
+    /// Factor the row-major `matrix` with shape `dims` for the decomposition of `factor`.
+    fn new<T: CType<Float = F>>(matrix: &[T], dims: [usize; 2], factor: Factor) -> Self {
+        let [rows, cols] = dims;
+        debug_assert_eq!(matrix.len(), rows * cols);
+
+        let mut a = matrix.iter().copied().map(T::to_float).collect::<Vec<_>>();
+
+        match factor {
+            Factor::LuP | Factor::LuL | Factor::LuU => {
+                let mut pivots = vec![0; rows];
+                lu_factor(&mut a, &mut pivots);
+                Self::Lu { a, pivots }
+            }
+            Factor::QrQ | Factor::QrR => {
+                let q = qr_factor(&mut a, dims);
+                Self::Qr { r: a, q }
+            }
+            Factor::Cholesky => {
+                let mut l = vec![F::ZERO; rows * rows];
+                cholesky_factor(&a, rows, &mut l);
+                Self::Cholesky(l)
+            }
+            Factor::EighValues | Factor::EighVectors => {
+                let v = eigh_factor(&mut a, rows);
+
+                let mut order = (0..rows).collect::<Vec<usize>>();
+                order.sort_by(|l, r| sort_order(&a[l * rows + l], &a[r * rows + r]));
+
+                Self::Eigh { a, v, order }
+            }
+        }
+    }
+
+    /// Write the given `factor` of a matrix with shape `dims` to `output`.
+    fn write(&self, [rows, cols]: [usize; 2], factor: Factor, output: &mut [F]) {
+        match self {
+            Self::Lu { a, pivots } => {
+                for i in 0..rows {
+                    for j in 0..rows {
+                        output[i * rows + j] = match factor {
+                            // row i of PA is row pivots[i] of A, so A = P^T LU
+                            Factor::LuP if pivots[j] == i => F::ONE,
+                            Factor::LuL if i == j => F::ONE,
+                            Factor::LuL if j < i => a[i * rows + j],
+                            Factor::LuU if j >= i => a[i * rows + j],
+                            _ => F::ZERO,
+                        };
+                    }
+                }
+            }
+            Self::Qr { r, q } => {
+                let k = rows.min(cols);
+
+                if factor == Factor::QrQ {
+                    for i in 0..rows {
+                        output[(i * k)..((i + 1) * k)]
+                            .copy_from_slice(&q[(i * rows)..(i * rows + k)]);
+                    }
+                } else {
+                    for i in 0..k {
+                        for j in 0..cols {
+                            output[i * cols + j] = if j < i { F::ZERO } else { r[i * cols + j] };
+                        }
+                    }
+                }
+            }
+            Self::Cholesky(l) => output.copy_from_slice(l),
+            Self::Eigh { a, v, order } => {
+                if factor == Factor::EighValues {
+                    for (j, k) in order.iter().enumerate() {
+                        output[j] = a[k * rows + k];
+                    }
+                } else {
+                    for i in 0..rows {
+                        for (j, k) in order.iter().enumerate() {
+                            output[i * rows + j] = v[i * rows + k];
+                        }
+                    }
+                }
+            }
         }
     }
 }
 
-pub struct Slice<A, T> {
-    access: A,
-    spec: SliceSpec,
-    dtype: PhantomData<T>,
+/// Compute every factor of the decomposition of `factor` of the row-major `matrix`
+/// with shape `dims`, in the order of [`Factor::siblings`], factoring it only once.
+/// The Cholesky factor of a matrix which is not positive definite,
+/// and the LU factors of a singular matrix, are not finite.
+fn decompose<T: CType>(matrix: &[T], dims: [usize; 2], factor: Factor) -> Vec<Vec<T::Float>> {
+    let factors = Factors::new(matrix, dims, factor);
+
+    factor
+        .siblings()
+        .iter()
+        .map(|sibling| {
+            let size = sibling.shape(dims).iter().product();
+            let mut output = vec![T::Float::ZERO; size];
+            factors.write(dims, *sibling, &mut output);
+            output
+        })
+        .collect()
 }
 
-impl<A, T> Slice<A, T> {
-    pub fn new(access: A, shape: &[usize], range: Range) -> Self {
-        let spec = SliceSpec::new(shape, range);
+/// Reduce the row-major `matrix` with shape `[rows, cols]` in place to the upper triangular `R`
+/// of a QR decomposition using Householder reflections, returning the square matrix `Q`.
+fn qr_factor<T: Float>(matrix: &mut [T], [rows, cols]: [usize; 2]) -> Vec<T> {
+    let mut q = vec![T::ZERO; rows * rows];
+    (0..rows).for_each(|i| q[i * rows + i] = T::ONE);
 
-        Self {
-            access,
-            spec,
-            dtype: PhantomData,
+    let mut v = vec![T::ZERO; rows];
+
+    for j in 0..rows.saturating_sub(1).min(cols) {
+        // reflect column j onto the diagonal with v = x - alpha * e_j
+        let mut norm = 0f64;
+        for i in j..rows {
+            v[i] = matrix[i * cols + j];
+            norm += CType::to_f64(v[i]) * CType::to_f64(v[i]);
+        }
+
+        let norm = T::from_f64(norm.sqrt());
+        let alpha = if v[j] > T::ZERO {
+            T::sub(T::ZERO, norm)
+        } else {
+            norm
+        };
+        v[j] = T::sub(v[j], alpha);
+
+        let vv = (j..rows).fold(T::ZERO, |sum, i| T::add(sum, T::mul(v[i], v[i])));
+
+        if vv == T::ZERO {
+            continue;
+        }
+
+        // R = HR, where H = I - 2vv^T / v^Tv
+        for c in 0..cols {
+            let dot = (j..rows).fold(T::ZERO, |sum, i| {
+                T::add(sum, T::mul(v[i], matrix[i * cols + c]))
+            });
+
+            let scale = T::div(T::add(dot, dot), vv);
+
+            for i in j..rows {
+                matrix[i * cols + c] = T::sub(matrix[i * cols + c], T::mul(scale, v[i]));
+            }
+        }
+
+        // Q = QH
+        for r in 0..rows {
+            let dot = (j..rows).fold(T::ZERO, |sum, i| T::add(sum, T::mul(q[r * rows + i], v[i])));
+            let scale = T::div(T::add(dot, dot), vv);
+
+            for i in j..rows {
+                q[r * rows + i] = T::sub(q[r * rows + i], T::mul(scale, v[i]));
+            }
         }
     }
+
+    q
 }
 
-impl<A: Send + Sync, T: Copy + Send + Sync> Slice<A, T> {
-    fn read(&self, source: &[T]) -> Result<StackVec<T>, Error> {
-        let output = (0..self.size())
-            .into_iter()
-            .map(|offset_out| self.spec.source_offset(offset_out))
-            .map(|offset_in| source[offset_in])
-            .collect();
+/// Compute the lower triangular Cholesky factor of the row-major `matrix` with shape `[dim, dim]`,
+/// reading only its lower triangle, and write it to `output`.
+fn cholesky_factor<T: Float>(matrix: &[T], dim: usize, output: &mut [T]) {
+    output.iter_mut().for_each(|n| *n = T::ZERO);
 
-        Ok(output)
+    for j in 0..dim {
+        for i in j..dim {
+            let mut sum = matrix[i * dim + j];
+
+            for k in 0..j {
+                sum = T::sub(sum, T::mul(output[i * dim + k], output[j * dim + k]));
+            }
+
+            output[i * dim + j] = if i == j {
+                T::from_f64(CType::to_f64(sum).sqrt())
+            } else {
+                T::div(sum, output[j * dim + j])
+            };
+        }
     }
+}
 
-    fn read_parallel(&self, source: &[T]) -> Result<Vec<T>, Error> {
-        let output = (0..self.size())
-            .into_par_iter()
-            .map(|offset_out| self.spec.source_offset(offset_out))
-            .map(|offset_in| source[offset_in])
-            .collect();
+/// The maximum number of sweeps of [`eigh_factor`], which in practice converges in well under 20
+pub(crate) const JACOBI_SWEEPS: usize = 64;
+
+/// Diagonalize the symmetric row-major `matrix` with shape `[dim, dim]` in place using cyclic
+/// Jacobi rotations, reading only its lower triangle, and return the matrix `V` whose columns are
+/// its eigenvectors, in the same order as the eigenvalues on the diagonal.
+fn eigh_factor<T: Float>(matrix: &mut [T], dim: usize) -> Vec<T> {
+    for i in 0..dim {
+        for j in (i + 1)..dim {
+            matrix[i * dim + j] = matrix[j * dim + i];
+        }
+    }
+
+    let mut v = vec![T::ZERO; dim * dim];
+    (0..dim).for_each(|i| v[i * dim + i] = T::ONE);
+
+    let rotate = |x: &mut [T], i: usize, j: usize, c: T, s: T| {
+        let (xi, xj) = (x[i], x[j]);
+        x[i] = T::sub(T::mul(c, xi), T::mul(s, xj));
+        x[j] = T::add(T::mul(s, xi), T::mul(c, xj));
+    };
+
+    for _sweep in 0..JACOBI_SWEEPS {
+        let mut rotated = false;
+
+        for p in 0..dim {
+            for q in (p + 1)..dim {
+                let (app, aqq, apq) = (
+                    matrix[p * dim + p],
+                    matrix[q * dim + q],
+                    matrix[p * dim + q],
+                );
+
+                // an element too small to change either diagonal element is already zero
+                let g = T::mul(T::from_f64(100.), apq.abs());
+                if T::add(app, g) == app && T::add(aqq, g) == aqq {
+                    matrix[p * dim + q] = T::ZERO;
+                    matrix[q * dim + p] = T::ZERO;
+                    continue;
+                }
+
+                // choose the smaller rotation angle which zeroes the element at (p, q)
+                let theta = (CType::to_f64(aqq) - CType::to_f64(app)) / (2. * CType::to_f64(apq));
+                let t = theta.signum() / (theta.abs() + theta.hypot(1.));
+                let c = 1. / t.hypot(1.);
+                let (c, s) = (T::from_f64(c), T::from_f64(t * c));
+
+                // A = J^T A J and V = VJ
+                for k in 0..dim {
+                    rotate(matrix, k * dim + p, k * dim + q, c, s);
+                    rotate(&mut v, k * dim + p, k * dim + q, c, s);
+                }
+
+                for k in 0..dim {
+                    rotate(matrix, p * dim + k, q * dim + k, c, s);
+                }
+
+                rotated = true;
+            }
+        }
 
-        Ok(output)
+        if !rotated {
+            break;
+        }
     }
+
+    v
 }
 
-impl<A, T> Slice<A, T>
+/// Decompose the matrix which contains the element at `offset` of the given `factor` of a batch
+/// of matrices with shape `dims`, reading its input one element at a time.
+/// The factors of the matrix are cached in `factorization` until another matrix is read.
+pub(crate) fn read_decompose<A, T>(
+    access: &A,
+    factor: Factor,
+    dims: [usize; 2],
+    factorization: &Factorization<T>,
+    offset: usize,
+) -> Result<T::Float, Error>
 where
+    A: Access<T>,
     T: CType,
-    A: AccessMut<T>,
 {
-    fn overwrite<'a>(&mut self, data: BufferConverter<'a, T>) -> Result<(), Error> {
-        if data.len() == self.size() {
-            let data = data.to_slice()?;
+    let matrix_size = dims[0] * dims[1];
+    let factor_size = factor.shape(dims).iter().product::<usize>();
+    let (m, start) = (offset / factor_size, (offset / factor_size) * matrix_size);
 
-            for (offset, value) in data.into_iter().copied().enumerate() {
-                let source_offset = self.spec.source_offset(offset);
-                self.access.write_value_at(source_offset, value)?;
-            }
+    let matrix = (start..(start + matrix_size))
+        .map(|i| access.read_value(i))
+        .collect::<Result<Vec<T>, Error>>()?;
 
-            Ok(())
-        } else {
-            Err(Error::Bounds(format!(
-                "cannot overwrite a slice of size {} with a buffer of size {}",
-                self.size(),
-                data.len(),
-            )))
+    let index = offset - m * factor_size;
+
+    if let Some(value) = factorization.get(start, &matrix, factor, index) {
+        return Ok(value);
+    }
+
+    let factors = decompose(&matrix, dims, factor);
+    let value = factors[sibling_index(factor)][index];
+
+    let factors = factor.siblings().iter().copied().zip(factors).collect();
+    factorization.insert(start, matrix, factors);
+
+    Ok(value)
+}
+
+pub struct Ternary<A, B, C, T> {
+    a: A,
+    b: B,
+    c: C,
+    op: fn(T, T, T) -> T,
+}
+
+impl<A, B, C, T: CType> Ternary<A, B, C, T> {
+    pub fn clamp(access: A, min: B, max: C) -> Self {
+        Self {
+            a: access,
+            b: min,
+            c: max,
+            op: |n, min, max| T::min(T::max(n, min), max),
         }
     }
 
-    fn overwrite_value(&mut self, value: T) -> Result<(), Error> {
-        for offset in 0..self.access.size() {
-            let source_offset = self.spec.source_offset(offset);
-            self.access.write_value_at(source_offset, value)?;
+    pub fn lerp(access: A, other: B, t: C) -> Self {
+        Self {
+            a: access,
+            b: other,
+            c: t,
+            op: lerp,
         }
+    }
 
-        Ok(())
+    pub fn mul_add(access: A, mul: B, add: C) -> Self {
+        Self {
+            a: access,
+            b: mul,
+            c: add,
+            op: if config::fast_math() {
+                T::mul_add
+            } else {
+                |n, mul, add| T::add(T::mul(n, mul), add)
+            },
+        }
     }
 
-    fn overwrite_value_at(&mut self, offset: usize, value: T) -> Result<(), Error> {
-        let source_offset = self.spec.source_offset(offset);
-        self.access.write_value_at(source_offset, value)
+    pub fn smoothstep(access: A, edge0: B, edge1: C) -> Self {
+        Self {
+            a: access,
+            b: edge0,
+            c: edge1,
+            op: smoothstep,
+        }
     }
 }
 
-impl<A: Send + Sync, T: Send + Sync> Op for Slice<A, T> {
+impl<A, B, C, T> Op for Ternary<A, B, C, T>
+where
+    A: Access<T>,
+    B: Access<T>,
+    C: Access<T>,
+    T: CType,
+{
     fn size(&self) -> usize {
-        self.spec.size()
+        debug_assert_eq!(self.a.size(), self.b.size());
+        debug_assert_eq!(self.a.size(), self.c.size());
+        self.a.size()
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.a.plan(), self.b.plan(), self.c.plan()]
     }
 }
 
-impl<A: Access<T>, T: CType> Enqueue<Heap, T> for Slice<A, T> {
-    type Buffer = Vec<T>;
+impl<A, B, C, T> Enqueue<Stack, T> for Ternary<A, B, C, T>
+where
+    A: Access<T>,
+    B: Access<T>,
+    C: Access<T>,
+    T: CType,
+{
+    type Buffer = StackVec<T>;
 
     fn enqueue(&self) -> Result<Self::Buffer, Error> {
-        self.access
-            .read()
-            .and_then(|buf| buf.to_slice())
-            .and_then(|buf| self.read_parallel(&*buf))
+        let a = self.a.read()?.to_slice()?;
+        let b = self.b.read()?.to_slice()?;
+        let c = self.c.read()?.to_slice()?;
+
+        let output = a
+            .iter()
+            .zip(b.iter().zip(c.iter()))
+            .map(|(a, (b, c))| (self.op)(*a, *b, *c))
+            .collect();
+
+        Ok(output)
     }
 }
 
-impl<A: Access<T>, T: CType> Enqueue<Stack, T> for Slice<A, T> {
-    type Buffer = StackVec<T>;
+impl<A, B, C, T> Enqueue<Heap, T> for Ternary<A, B, C, T>
+where
+    A: Access<T>,
+    B: Access<T>,
+    C: Access<T>,
+    T: CType,
+{
+    type Buffer = Vec<T>;
 
     fn enqueue(&self) -> Result<Self::Buffer, Error> {
-        self.access
-            .read()
-            .and_then(|buf| buf.to_slice())
-            .and_then(|buf| self.read(&*buf))
+        let (a, bc) = join(
+            || self.a.read().and_then(|buf| buf.to_slice()),
+            || try_join_read(&self.b, &self.c),
+        );
+
+        let (a, (b, c)) = (a?, bc?);
+
+        let output = a
+            .into_par_iter()
+            .zip(b.into_par_iter().zip(c.into_par_iter()))
+            .map(|(a, (b, c))| (self.op)(*a, *b, *c))
+            .collect();
+
+        Ok(output)
     }
 }
 
-impl<A: Access<T>, T: CType> Enqueue<Host, T> for Slice<A, T> {
+impl<A, B, C, T> Enqueue<Host, T> for Ternary<A, B, C, T>
+where
+    A: Access<T>,
+    B: Access<T>,
+    C: Access<T>,
+    T: CType,
+{
     type Buffer = Buffer<T>;
 
     fn enqueue(&self) -> Result<Self::Buffer, Error> {
@@ -1384,65 +5235,162 @@ impl<A: Access<T>, T: CType> Enqueue<Host, T> for Slice<A, T> {
     }
 }
 
-impl<A: Access<T>, T: CType> ReadValue<Host, T> for Slice<A, T> {
+impl<A, B, C, T> ReadValue<Host, T> for Ternary<A, B, C, T>
+where
+    A: Access<T>,
+    B: Access<T>,
+    C: Access<T>,
+    T: CType,
+{
     fn read_value(&self, offset: usize) -> Result<T, Error> {
-        let offset = self.spec.source_offset(offset);
-        self.access.read_value(offset)
+        read_ternary(&self.a, &self.b, &self.c, self.op, offset)
     }
 }
 
-impl<A, T> crate::ops::Write<Heap, T> for Slice<A, T>
+pub(crate) fn lerp<T: CType>(from: T, to: T, t: T) -> T {
+    let (from, to, t) = (from.to_float(), to.to_float(), t.to_float());
+    T::from_float(from.add(to.sub(from).mul(t)))
+}
+
+pub(crate) fn smoothstep<T: CType>(n: T, edge0: T, edge1: T) -> T {
+    let (n, edge0, edge1) = (n.to_float(), edge0.to_float(), edge1.to_float());
+
+    let t = n.sub(edge0).div(edge1.sub(edge0));
+    let t = CType::min(CType::max(t, T::Float::ZERO), T::Float::ONE);
+    let three = T::Float::ONE.add(T::Float::ONE).add(T::Float::ONE);
+
+    T::from_float(t.mul(t).mul(three.sub(t.add(t))))
+}
+
+/// Compute the dot product of the given pairs of window and template values, divided by the
+/// product of their norms, or zero if either norm is zero.
+pub(crate) fn normalized_dot<T, I>(pairs: I) -> T::Float
 where
     T: CType,
-    A: AccessMut<T>,
+    I: IntoIterator<Item = (T, T)>,
 {
-    fn write<'a>(&mut self, data: BufferConverter<'a, T>) -> Result<(), Error> {
-        self.overwrite(data)
-    }
+    let zero = T::Float::ZERO;
 
-    fn write_value(&mut self, value: T) -> Result<(), Error> {
-        self.overwrite_value(value)
-    }
+    let (dot, window, template) =
+        pairs
+            .into_iter()
+            .fold((zero, zero, zero), |(dot, window, template), (x, t)| {
+                let (x, t) = (x.to_float(), t.to_float());
+                (
+                    dot.add(x.mul(t)),
+                    window.add(x.mul(x)),
+                    template.add(t.mul(t)),
+                )
+            });
 
-    fn write_value_at(&mut self, offset: usize, value: T) -> Result<(), Error> {
-        self.overwrite_value_at(offset, value)
+    if window == zero || template == zero {
+        zero
+    } else {
+        dot.div(window.mul(template).pow(T::Float::from_f64(0.5)))
     }
 }
 
-impl<A, T> crate::ops::Write<Stack, T> for Slice<A, T>
+/// Read the element at `offset` of a matched filter with dimensions `[rows, len, width]`.
+pub(crate) fn read_matched_filter<I, K, T>(
+    input: &I,
+    template: &K,
+    dims: [usize; 3],
+    offset: usize,
+) -> Result<T::Float, Error>
 where
+    I: Access<T>,
+    K: Access<T>,
     T: CType,
-    A: AccessMut<T>,
 {
-    fn write<'a>(&mut self, data: BufferConverter<'a, T>) -> Result<(), Error> {
-        self.overwrite(data)
-    }
+    let pairs = matched_window(dims, offset)
+        .enumerate()
+        .map(|(j, i)| Ok((input.read_value(i)?, template.read_value(j)?)))
+        .collect::<Result<Vec<_>, Error>>()?;
 
-    fn write_value(&mut self, value: T) -> Result<(), Error> {
-        self.overwrite_value(value)
-    }
+    Ok(normalized_dot(pairs))
+}
 
-    fn write_value_at(&mut self, offset: usize, value: T) -> Result<(), Error> {
-        self.overwrite_value_at(offset, value)
-    }
+/// The range of input offsets in the window of output `offset` of a matched filter.
+#[inline]
+fn matched_window(dims: [usize; 3], offset: usize) -> std::ops::Range<usize> {
+    let [_rows, len, width] = dims;
+    let out_len = len - width + 1;
+    let start = (offset / out_len) * len + (offset % out_len);
+    start..(start + width)
 }
 
-impl<A, T> crate::ops::Write<Host, T> for Slice<A, T>
+/// Read the element at `offset` of the cumulative distribution of lanes of `dim` elements.
+pub(crate) fn read_cdf<A, T>(
+    access: &A,
+    dim: usize,
+    inner: usize,
+    offset: usize,
+) -> Result<T::Float, Error>
 where
+    A: Access<T>,
     T: CType,
-    A: AccessMut<T>,
 {
-    fn write<'a>(&mut self, data: BufferConverter<'a, T>) -> Result<(), Error> {
-        self.overwrite(data)
-    }
+    let n = access.read_value(offset)?;
+    let start = offset - (offset % (dim * inner)) + (offset % inner);
 
-    fn write_value(&mut self, value: T) -> Result<(), Error> {
-        self.overwrite_value(value)
+    let mut count = 0;
+    for i in 0..dim {
+        if access.read_value(start + (i * inner))? <= n {
+            count += 1;
+        }
     }
 
-    fn write_value_at(&mut self, offset: usize, value: T) -> Result<(), Error> {
-        self.overwrite_value_at(offset, value)
-    }
+    Ok(T::Float::from_f64(count as f64 / dim as f64))
+}
+
+/// Compare two values in ascending order, placing NaN after every other value
+/// so that sorting is well-defined for any input.
+#[inline]
+pub(crate) fn sort_order<T: CType>(l: &T, r: &T) -> Ordering {
+    l.partial_cmp(r).unwrap_or_else(|| {
+        #[allow(clippy::eq_op)]
+        let (l_nan, r_nan) = (l != l, r != r);
+        l_nan.cmp(&r_nan)
+    })
+}
+
+/// Read the element at `offset` of a sort op.
+pub(crate) fn read_sort<A, T>(access: &A, spec: SortSpec, offset: usize) -> Result<T, Error>
+where
+    A: Access<T>,
+    T: CType,
+{
+    let values = spec.read_values(access, spec.lane(offset))?;
+    Ok(spec.sort(values, false)[spec.coord(offset)])
+}
+
+/// Read the element at `offset` of an argsort op.
+pub(crate) fn read_argsort<A, T>(access: &A, spec: SortSpec, offset: usize) -> Result<u64, Error>
+where
+    A: Access<T>,
+    T: CType,
+{
+    let values = spec.read_values(access, spec.lane(offset))?;
+    Ok(spec.argsort(values, false)[spec.coord(offset)])
+}
+
+/// Read the element at `offset` of a ternary op.
+pub(crate) fn read_ternary<A, B, C, T>(
+    a: &A,
+    b: &B,
+    c: &C,
+    op: fn(T, T, T) -> T,
+    offset: usize,
+) -> Result<T, Error>
+where
+    A: Access<T>,
+    B: Access<T>,
+    C: Access<T>,
+    T: CType,
+{
+    let (a, bc) = join(|| a.read_value(offset), || try_join_value(b, c, offset));
+    let (a, (b, c)) = (a?, bc?);
+    Ok((op)(a, b, c))
 }
 
 pub struct Unary<A, IT, OT> {
@@ -1472,15 +5420,43 @@ impl<A: Access<T>, T: CType> Unary<A, T, T> {
         }
     }
 
+    pub fn reciprocal_approx(access: A) -> Self {
+        Self {
+            access,
+            op: |n| T::div(T::ONE, n),
+        }
+    }
+
     pub fn round(access: A) -> Self {
         Self {
             access,
             op: CType::round,
         }
     }
+
+    pub fn softplus(access: A) -> Self {
+        Self {
+            access,
+            op: |n| T::from_float(n.to_float().softplus()),
+        }
+    }
 }
 
 impl<A: Access<T>, T: CType> Unary<A, T, T::Float> {
+    pub fn to_degrees(access: A) -> Self {
+        Self {
+            access,
+            op: |n| n.to_float().to_degrees(),
+        }
+    }
+
+    pub fn to_radians(access: A) -> Self {
+        Self {
+            access,
+            op: |n| n.to_float().to_radians(),
+        }
+    }
+
     pub fn sin(access: A) -> Self {
         Self {
             access,
@@ -1579,6 +5555,10 @@ where
     fn size(&self) -> usize {
         self.access.size()
     }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan()]
+    }
 }
 
 impl<A, IT, OT> Enqueue<Heap, OT> for Unary<A, IT, OT>
@@ -1637,6 +5617,83 @@ where
     }
 }
 
+pub struct Upsample<A, T> {
+    access: A,
+    dims: [usize; 3],
+    scale: [usize; 2],
+    mode: Interpolation,
+    dtype: PhantomData<T>,
+}
+
+impl<A, T: CType> Upsample<A, T> {
+    pub fn new(access: A, dims: [usize; 3], scale: [usize; 2], mode: Interpolation) -> Self {
+        Self {
+            access,
+            dims,
+            scale,
+            mode,
+            dtype: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn sample<F>(&self, offset: usize, read: F) -> Result<T, Error>
+    where
+        F: Fn(usize) -> Result<T, Error>,
+    {
+        upsample_value(self.mode, self.dims, self.scale, offset, read)
+    }
+}
+
+impl<A: Access<T>, T: CType> Op for Upsample<A, T> {
+    fn size(&self) -> usize {
+        self.dims.iter().product::<usize>() * self.scale.iter().product::<usize>()
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan()]
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<Stack, T> for Upsample<A, T> {
+    type Buffer = StackVec<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let input = self.access.read()?.to_slice()?;
+
+        (0..self.size())
+            .map(|offset| self.sample(offset, |i| Ok(input[i])))
+            .collect()
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<Heap, T> for Upsample<A, T> {
+    type Buffer = Vec<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let input = self.access.read()?.to_slice()?;
+
+        (0..self.size())
+            .into_par_iter()
+            .map(|offset| self.sample(offset, |i| Ok(input[i])))
+            .collect()
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<Host, T> for Upsample<A, T> {
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        host_enqueue!(self, self.size() < VEC_MIN_SIZE, T)
+    }
+}
+
+impl<A: Access<T>, T: CType> ReadValue<Host, T> for Upsample<A, T> {
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        self.sample(offset, |i| self.access.read_value(i))
+    }
+}
+
 pub struct View<A, T> {
     access: A,
     spec: ViewSpec,
@@ -1644,24 +5701,42 @@ pub struct View<A, T> {
 }
 
 impl<A: Access<T>, T: CType> View<A, T> {
-    pub fn broadcast(access: A, shape: Shape, broadcast: Shape) -> Self {
-        let source_strides = strides_for(&shape, shape.len()).collect();
+    pub fn new(access: A, spec: ViewSpec) -> Self {
+        Self {
+            access,
+            spec,
+            dtype: PhantomData,
+        }
+    }
 
+    pub fn broadcast(access: A, shape: Shape, broadcast: Shape) -> Self {
         Self {
             access,
-            spec: ViewSpec::new(broadcast, source_strides),
+            spec: ViewSpec::broadcast(&shape, broadcast),
             dtype: PhantomData,
         }
     }
 
     pub fn transpose(access: A, shape: Shape, axes: Axes) -> Self {
-        let strides = strides_for(&shape, shape.len()).collect::<Strides>();
-        let shape = axes.iter().copied().map(|x| shape[x]).collect::<Strides>();
-        let source_strides = axes.into_iter().map(|x| strides[x]).collect::<Strides>();
+        Self {
+            access,
+            spec: ViewSpec::transpose(&shape, &axes),
+            dtype: PhantomData,
+        }
+    }
+
+    pub fn reverse(access: A, shape: Shape, axes: Axes) -> Self {
+        Self {
+            access,
+            spec: ViewSpec::reverse(&shape, &axes),
+            dtype: PhantomData,
+        }
+    }
 
+    pub fn roll(access: A, shape: Shape, shift: usize, axis: usize) -> Self {
         Self {
             access,
-            spec: ViewSpec::new(shape, source_strides),
+            spec: ViewSpec::roll(&shape, shift, axis),
             dtype: PhantomData,
         }
     }
@@ -1671,6 +5746,10 @@ impl<A: Access<T>, T: CType> Op for View<A, T> {
     fn size(&self) -> usize {
         self.spec.size()
     }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan()]
+    }
 }
 
 impl<A: Access<T>, T: CType> Enqueue<Stack, T> for View<A, T> {
@@ -1680,7 +5759,6 @@ impl<A: Access<T>, T: CType> Enqueue<Stack, T> for View<A, T> {
         let source = self.access.read().and_then(|source| source.to_slice())?;
 
         let buffer = (0..self.spec.size())
-            .into_iter()
             .map(|offset| self.spec.source_offset(offset))
             .map(|source_offset| source[source_offset])
             .collect();
@@ -1719,6 +5797,23 @@ impl<A: Access<T>, T: CType> ReadValue<Host, T> for View<A, T> {
     }
 }
 
+#[inline]
+fn mean_std<T: CType, I: Iterator<Item = T> + Clone>(values: I, eps: T) -> (f64, f64) {
+    let (sum, count) = values
+        .clone()
+        .fold((0., 0.), |(sum, count), n| (sum + n.to_f64(), count + 1.));
+
+    let mean = sum / count;
+
+    let var = values
+        .map(|n| n.to_f64() - mean)
+        .map(|n| n * n)
+        .sum::<f64>()
+        / count;
+
+    (mean, (var + eps.to_f64()).sqrt())
+}
+
 fn exec_dual<IT: CType, OT: CType>(
     zip: fn(IT, IT) -> OT,
     left: SliceConverter<IT>,
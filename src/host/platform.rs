@@ -1,16 +1,23 @@
 use rayon::prelude::*;
 
-use crate::access::{Access, AccessOp};
+use crate::access::{Access, AccessMut, AccessOp};
 use crate::buffer::BufferConverter;
+use crate::config::{self, ReduceOrder};
 use crate::host::StackVec;
 use crate::ops::{
-    Construct, ElementwiseBoolean, ElementwiseBooleanScalar, ElementwiseCast, ElementwiseCompare,
+    max_identity, min_identity, Accumulate, Construct, Convolve, ElementwiseBitcast,
+    ElementwiseBoolean, ElementwiseBooleanScalar, ElementwiseCast, ElementwiseCompare,
     ElementwiseDual, ElementwiseNumeric, ElementwiseScalar, ElementwiseScalarCompare,
-    ElementwiseTrig, ElementwiseUnary, ElementwiseUnaryBoolean, GatherCond, LinAlgDual,
-    LinAlgUnary, Random, ReduceAll, ReduceAxes, Transform,
+    ElementwiseTernary, ElementwiseTrig, ElementwiseTrigDual, ElementwiseUnary,
+    ElementwiseUnaryBoolean, Factor, Factorization, GatherAxis, GatherCond, GatherCondScalar,
+    GatherMask, LinAlgDual, LinAlgSolve, LinAlgSparse, LinAlgUnary, Normalize, Random, ReduceAll,
+    ReduceAxes, ReduceAxesWithIndex, Resample, ScanAxis, ScatterAxis, Segments, SortAxis,
+    Transform, ViewSpec,
 };
 use crate::platform::{Convert, PlatformInstance};
-use crate::{stackvec, Axes, CType, Constant, Error, Float, Range, Shape};
+use crate::{
+    stackvec, Axes, CType, Constant, ConvSpec, Error, Float, Interpolation, Range, Shape, Strides,
+};
 
 use super::buffer::Buffer;
 use super::ops::*;
@@ -42,6 +49,16 @@ impl<T: CType> Convert<T> for Stack {
     }
 }
 
+impl<A: Access<T>, T: CType> Accumulate<A, T> for Stack {
+    fn axpy<Y: AccessMut<T>>(self, y: &mut Y, alpha: T, x: A) -> Result<(), Error> {
+        accumulate(y, x, |y, x| {
+            y.iter_mut()
+                .zip(x)
+                .for_each(|(y, x)| *y = T::add(*y, T::mul(alpha, *x)))
+        })
+    }
+}
+
 impl<A, T> ReduceAll<A, T> for Stack
 where
     A: Access<T>,
@@ -65,28 +82,62 @@ where
         access
             .read()
             .and_then(|buf| buf.to_slice())
-            .map(|slice| slice.iter().copied().reduce(T::max).expect("max"))
+            .map(|slice| slice.iter().copied().fold(max_identity(), T::max))
     }
 
     fn min(self, access: A) -> Result<T, Error> {
         access
             .read()
             .and_then(|buf| buf.to_slice())
-            .map(|slice| slice.iter().copied().reduce(T::min).expect("min"))
+            .map(|slice| slice.iter().copied().fold(min_identity(), T::min))
     }
 
     fn product(self, access: A) -> Result<T, Error> {
         access
             .read()
             .and_then(|buf| buf.to_slice())
-            .map(|slice| slice.iter().copied().reduce(T::mul).expect("product"))
+            .map(|slice| match config::reduce_order() {
+                ReduceOrder::Fast => slice.iter().copied().fold(T::ONE, T::mul),
+                ReduceOrder::Pairwise => tree_reduce(&slice, T::ONE, T::mul),
+            })
     }
 
     fn sum(self, access: A) -> Result<T, Error> {
         access
             .read()
             .and_then(|buf| buf.to_slice())
-            .map(|slice| slice.iter().copied().reduce(T::add).expect("sum"))
+            .map(|slice| match config::reduce_order() {
+                ReduceOrder::Fast => slice.iter().copied().fold(T::ZERO, T::add),
+                ReduceOrder::Pairwise => tree_reduce(&slice, T::ZERO, T::add),
+            })
+    }
+}
+
+impl<A: Access<T>, T: CType> ReduceAxesWithIndex<A, T> for Stack {
+    fn max_with_index(
+        self,
+        access: A,
+        stride: usize,
+    ) -> Result<(StackVec<T>, StackVec<u64>), Error> {
+        let input = access.read()?.to_slice()?;
+
+        Ok(input
+            .chunks_exact(stride)
+            .map(|chunk| reduce_with_index(chunk, |n, best| n > best))
+            .unzip())
+    }
+
+    fn min_with_index(
+        self,
+        access: A,
+        stride: usize,
+    ) -> Result<(StackVec<T>, StackVec<u64>), Error> {
+        let input = access.read()?.to_slice()?;
+
+        Ok(input
+            .chunks_exact(stride)
+            .map(|chunk| reduce_with_index(chunk, |n, best| n < best))
+            .unzip())
     }
 }
 
@@ -115,6 +166,16 @@ impl<T: CType> Convert<T> for Heap {
     }
 }
 
+impl<A: Access<T>, T: CType> Accumulate<A, T> for Heap {
+    fn axpy<Y: AccessMut<T>>(self, y: &mut Y, alpha: T, x: A) -> Result<(), Error> {
+        accumulate(y, x, |y, x| {
+            y.par_iter_mut()
+                .zip(x)
+                .for_each(|(y, x)| *y = T::add(*y, T::mul(alpha, *x)))
+        })
+    }
+}
+
 impl<A, T> ReduceAll<A, T> for Heap
 where
     A: Access<T>,
@@ -138,28 +199,54 @@ where
         access
             .read()
             .and_then(|buf| buf.to_slice())
-            .map(|slice| slice.into_par_iter().copied().reduce(|| T::MIN, T::max))
+            .map(|slice| slice.into_par_iter().copied().reduce(max_identity, T::max))
     }
 
     fn min(self, access: A) -> Result<T, Error> {
         access
             .read()
             .and_then(|buf| buf.to_slice())
-            .map(|slice| slice.into_par_iter().copied().reduce(|| T::MAX, T::min))
+            .map(|slice| slice.into_par_iter().copied().reduce(min_identity, T::min))
     }
 
     fn product(self, access: A) -> Result<T, Error> {
         access
             .read()
             .and_then(|buf| buf.to_slice())
-            .map(|slice| slice.into_par_iter().copied().reduce(|| T::ONE, T::mul))
+            .map(|slice| match config::reduce_order() {
+                ReduceOrder::Fast => slice.into_par_iter().copied().reduce(|| T::ONE, T::mul),
+                ReduceOrder::Pairwise => tree_reduce(&slice, T::ONE, T::mul),
+            })
     }
 
     fn sum(self, access: A) -> Result<T, Error> {
         access
             .read()
             .and_then(|buf| buf.to_slice())
-            .map(|slice| slice.into_par_iter().copied().reduce(|| T::ZERO, T::add))
+            .map(|slice| match config::reduce_order() {
+                ReduceOrder::Fast => slice.into_par_iter().copied().reduce(|| T::ZERO, T::add),
+                ReduceOrder::Pairwise => tree_reduce(&slice, T::ZERO, T::add),
+            })
+    }
+}
+
+impl<A: Access<T>, T: CType> ReduceAxesWithIndex<A, T> for Heap {
+    fn max_with_index(self, access: A, stride: usize) -> Result<(Vec<T>, Vec<u64>), Error> {
+        let input = access.read()?.to_slice()?;
+
+        Ok(input
+            .par_chunks_exact(stride)
+            .map(|chunk| reduce_with_index(chunk, |n, best| n > best))
+            .unzip())
+    }
+
+    fn min_with_index(self, access: A, stride: usize) -> Result<(Vec<T>, Vec<u64>), Error> {
+        let input = access.read()?.to_slice()?;
+
+        Ok(input
+            .par_chunks_exact(stride)
+            .map(|chunk| reduce_with_index(chunk, |n, best| n < best))
+            .unzip())
     }
 }
 
@@ -214,8 +301,13 @@ impl From<Stack> for Host {
 }
 
 impl<T: CType> Construct<T> for Host {
+    type Eye = Eye<T>;
     type Range = Linear<T>;
 
+    fn eye(self, batch_size: usize, dim: usize) -> Result<AccessOp<Self::Eye, Self>, Error> {
+        Ok(Eye::new(batch_size, dim).into())
+    }
+
     fn range(self, start: T, stop: T, size: usize) -> Result<AccessOp<Self::Range, Self>, Error> {
         if start <= stop {
             let step = T::sub(stop, start).to_f64() / size as f64;
@@ -226,6 +318,56 @@ impl<T: CType> Construct<T> for Host {
     }
 }
 
+impl<I, K, T> Convolve<I, K, T> for Host
+where
+    I: Access<T>,
+    K: Access<T>,
+    T: CType,
+{
+    type Conv = Conv<I, K, T>;
+    type ConvTranspose = ConvTranspose<I, K, T>;
+    type MatchedFilter = MatchedFilter<I, K, T>;
+
+    fn conv(
+        self,
+        input: I,
+        kernel: K,
+        input_shape: [usize; 5],
+        kernel_shape: [usize; 5],
+        spec: ConvSpec<3>,
+    ) -> Result<AccessOp<Self::Conv, Self>, Error> {
+        Conv::new(input, kernel, input_shape, kernel_shape, spec).map(AccessOp::from)
+    }
+
+    fn conv_transpose2d(
+        self,
+        input: I,
+        kernel: K,
+        input_shape: [usize; 4],
+        kernel_shape: [usize; 4],
+        spec: ConvSpec<2>,
+    ) -> Result<AccessOp<Self::ConvTranspose, Self>, Error> {
+        ConvTranspose::new(input, kernel, input_shape, kernel_shape, spec).map(AccessOp::from)
+    }
+
+    fn matched_filter(
+        self,
+        input: I,
+        template: K,
+        dims: [usize; 3],
+    ) -> Result<AccessOp<Self::MatchedFilter, Self>, Error> {
+        Ok(MatchedFilter::new(input, template, dims).into())
+    }
+}
+
+impl<A: Access<IT>, IT: CType, OT: CType> ElementwiseBitcast<A, IT, OT> for Host {
+    type Op = Bitcast<A, IT, OT>;
+
+    fn bitcast(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Bitcast::new(access).map(AccessOp::from)
+    }
+}
+
 impl<A: Access<IT>, IT: CType, OT: CType> ElementwiseCast<A, IT, OT> for Host {
     type Op = Cast<A, IT, OT>;
 
@@ -234,6 +376,42 @@ impl<A: Access<IT>, IT: CType, OT: CType> ElementwiseCast<A, IT, OT> for Host {
     }
 }
 
+impl<A, I, T> GatherAxis<A, I, T> for Host
+where
+    A: Access<T>,
+    I: Access<u64>,
+    T: CType,
+{
+    type Op = Gather<A, I, T>;
+
+    fn gather(
+        self,
+        access: A,
+        indices: I,
+        dims: [usize; 3],
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ok(Gather::new(access, indices, dims).into())
+    }
+}
+
+impl<A, P, T> GatherMask<A, P, T> for Host
+where
+    A: Access<T>,
+    P: Access<u64>,
+    T: CType,
+{
+    type Op = Compact<A, P, T>;
+
+    fn compact(
+        self,
+        access: A,
+        positions: P,
+        size: usize,
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ok(Compact::new(access, positions, size).into())
+    }
+}
+
 impl<A, L, R, T> GatherCond<A, L, R, T> for Host
 where
     A: Access<u8>,
@@ -248,6 +426,14 @@ where
     }
 }
 
+impl<A: Access<u8>, T: CType> GatherCondScalar<A, T> for Host {
+    type Op = CondScalar<A, T>;
+
+    fn cond_scalar(self, cond: A, then: T, or_else: T) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ok(CondScalar::new(cond, then, or_else).into())
+    }
+}
+
 impl<L, R, T> ElementwiseBoolean<L, R, T> for Host
 where
     L: Access<T>,
@@ -366,6 +552,10 @@ where
         Ok(Dual::log(arg, base).into())
     }
 
+    fn logaddexp(self, left: L, right: R) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ok(Dual::logaddexp(left, right).into())
+    }
+
     fn mul(self, left: L, right: R) -> Result<AccessOp<Self::Op, Self>, Error> {
         Ok(Dual::mul(left, right).into())
     }
@@ -378,6 +568,10 @@ where
         Ok(Dual::rem(left, right).into())
     }
 
+    fn step(self, arg: L, threshold: R) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ok(Dual::step(arg, threshold).into())
+    }
+
     fn sub(self, left: L, right: R) -> Result<AccessOp<Self::Op, Self>, Error> {
         Ok(Dual::sub(left, right).into())
     }
@@ -427,6 +621,32 @@ impl<A: Access<T>, T: Float> ElementwiseNumeric<A, T> for Host {
     }
 }
 
+impl<A, B, C, T> ElementwiseTernary<A, B, C, T> for Host
+where
+    A: Access<T>,
+    B: Access<T>,
+    C: Access<T>,
+    T: CType,
+{
+    type Op = Ternary<A, B, C, T>;
+
+    fn clamp(self, access: A, min: B, max: C) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ok(Ternary::clamp(access, min, max).into())
+    }
+
+    fn lerp(self, access: A, other: B, t: C) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ok(Ternary::lerp(access, other, t).into())
+    }
+
+    fn mul_add(self, access: A, mul: B, add: C) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ok(Ternary::mul_add(access, mul, add).into())
+    }
+
+    fn smoothstep(self, access: A, edge0: B, edge1: C) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ok(Ternary::smoothstep(access, edge0, edge1).into())
+    }
+}
+
 impl<A: Access<T>, T: CType> ElementwiseTrig<A, T> for Host {
     type Op = Unary<A, T, T::Float>;
 
@@ -465,6 +685,31 @@ impl<A: Access<T>, T: CType> ElementwiseTrig<A, T> for Host {
     fn tanh(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
         Ok(Unary::tanh(access).into())
     }
+
+    fn to_degrees(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ok(Unary::to_degrees(access).into())
+    }
+
+    fn to_radians(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ok(Unary::to_radians(access).into())
+    }
+}
+
+impl<L, R, T> ElementwiseTrigDual<L, R, T> for Host
+where
+    L: Access<T>,
+    R: Access<T>,
+    T: CType,
+{
+    type Op = Dual<L, R, T, T::Float>;
+
+    fn atan2(self, y: L, x: R) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ok(Dual::atan2(y, x).into())
+    }
+
+    fn hypot(self, left: L, right: R) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ok(Dual::hypot(left, right).into())
+    }
 }
 
 impl<A: Access<T>, T: CType> ElementwiseUnary<A, T> for Host {
@@ -482,9 +727,17 @@ impl<A: Access<T>, T: CType> ElementwiseUnary<A, T> for Host {
         Ok(Unary::ln(access).into())
     }
 
+    fn reciprocal_approx(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ok(Unary::reciprocal_approx(access).into())
+    }
+
     fn round(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
         Ok(Unary::round(access).into())
     }
+
+    fn softplus(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ok(Unary::softplus(access).into())
+    }
 }
 
 impl<A: Access<T>, T: CType> ElementwiseUnaryBoolean<A, T> for Host {
@@ -511,10 +764,64 @@ where
     ) -> Result<AccessOp<Self::Op, Self>, Error> {
         Ok(MatMul::new(left, right, dims).into())
     }
+
+    fn matmul_strided(
+        self,
+        left: L,
+        right: R,
+        dims: [usize; 4],
+        strides: [usize; 2],
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ok(MatMul::strided(left, right, dims, strides).into())
+    }
+}
+
+impl<D, R, T> LinAlgSolve<D, R, T> for Host
+where
+    D: Access<T>,
+    R: Access<T>,
+    T: Float,
+{
+    type Op = SolveTridiagonal<D, R, T>;
+
+    fn solve_tridiagonal(
+        self,
+        diagonals: D,
+        rhs: R,
+        batch_size: usize,
+        dim: usize,
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ok(SolveTridiagonal::new(diagonals, rhs, batch_size, dim).into())
+    }
+}
+
+impl<I, V, D, T> LinAlgSparse<I, V, D, T> for Host
+where
+    I: Access<u64>,
+    V: Access<T>,
+    D: Access<T>,
+    T: CType,
+{
+    type Op = CsrMatMul<I, V, D, T>;
+
+    fn csr_matmul(
+        self,
+        offsets: I,
+        indices: I,
+        values: V,
+        dense: D,
+        dims: [usize; 3],
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ok(CsrMatMul::new(offsets, indices, values, dense, dims).into())
+    }
 }
 
 impl<A: Access<T>, T: CType> LinAlgUnary<A, T> for Host {
     type Op = MatDiag<A, T>;
+    type DiagFrom = DiagFrom<A, T>;
+    type Inverse = Inverse<A, T>;
+    type Decompose = Decompose<A, T>;
+    type Trace = MatTrace<A, T>;
 
     fn diag(
         self,
@@ -524,6 +831,65 @@ impl<A: Access<T>, T: CType> LinAlgUnary<A, T> for Host {
     ) -> Result<AccessOp<Self::Op, Self>, Error> {
         Ok(MatDiag::new(access, batch_size, dim).into())
     }
+
+    fn diag_from(
+        self,
+        access: A,
+        batch_size: usize,
+        dim: usize,
+    ) -> Result<AccessOp<Self::DiagFrom, Self>, Error> {
+        Ok(DiagFrom::new(access, batch_size, dim).into())
+    }
+
+    fn inv(
+        self,
+        access: A,
+        batch_size: usize,
+        dim: usize,
+    ) -> Result<AccessOp<Self::Inverse, Self>, Error> {
+        Ok(Inverse::new(access, batch_size, dim).into())
+    }
+
+    fn trace(
+        self,
+        access: A,
+        batch_size: usize,
+        dim: usize,
+    ) -> Result<AccessOp<Self::Trace, Self>, Error> {
+        Ok(MatTrace::new(access, batch_size, dim).into())
+    }
+
+    fn decompose(
+        self,
+        access: A,
+        factor: Factor,
+        batch_size: usize,
+        dims: [usize; 2],
+        factorization: Factorization<T>,
+    ) -> Result<AccessOp<Self::Decompose, Self>, Error> {
+        Ok(Decompose::new(access, factor, batch_size, dims, factorization).into())
+    }
+}
+
+impl<A, S, B, T> Normalize<A, S, B, T> for Host
+where
+    A: Access<T>,
+    S: Access<T>,
+    B: Access<T>,
+    T: Float,
+{
+    type Op = LayerNorm<A, S, B, T>;
+
+    fn layer_norm(
+        self,
+        access: A,
+        scale: S,
+        bias: B,
+        stride: usize,
+        eps: T,
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ok(LayerNorm::new(access, scale, bias, stride, eps).into())
+    }
 }
 
 impl Random for Host {
@@ -539,6 +905,56 @@ impl Random for Host {
     }
 }
 
+impl<A: Access<T>, T: CType> Resample<A, T> for Host {
+    type Op = Upsample<A, T>;
+
+    fn upsample(
+        self,
+        access: A,
+        dims: [usize; 3],
+        scale: [usize; 2],
+        mode: Interpolation,
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ok(Upsample::new(access, dims, scale, mode).into())
+    }
+}
+
+impl<A: Access<T>, T: CType> Accumulate<A, T> for Host {
+    fn axpy<Y: AccessMut<T>>(self, y: &mut Y, alpha: T, x: A) -> Result<(), Error> {
+        match self {
+            Self::Heap(heap) => heap.axpy(y, alpha, x),
+            Self::Stack(stack) => stack.axpy(y, alpha, x),
+        }
+    }
+}
+
+impl<I, A, T> ScatterAxis<I, A, T> for Host
+where
+    I: Access<u64>,
+    A: Access<T>,
+    T: CType,
+{
+    fn scatter<Y: AccessMut<T>>(
+        self,
+        y: &mut Y,
+        indices: I,
+        values: A,
+        dims: [usize; 3],
+    ) -> Result<(), Error> {
+        scatter(y, indices, values, dims, |y, value| *y = value)
+    }
+
+    fn scatter_add<Y: AccessMut<T>>(
+        self,
+        y: &mut Y,
+        indices: I,
+        values: A,
+        dims: [usize; 3],
+    ) -> Result<(), Error> {
+        scatter(y, indices, values, dims, |y, value| *y = T::add(*y, value))
+    }
+}
+
 impl<A: Access<T>, T: CType> ReduceAll<A, T> for Host {
     fn all(self, access: A) -> Result<bool, Error> {
         match self {
@@ -585,6 +1001,8 @@ impl<A: Access<T>, T: CType> ReduceAll<A, T> for Host {
 
 impl<A: Access<T>, T: CType> ReduceAxes<A, T> for Host {
     type Op = Reduce<A, T>;
+    type Moment = ReduceMoment<A, T>;
+    type Arg = ReduceArg<A, T>;
 
     fn max(self, access: A, stride: usize) -> Result<AccessOp<Self::Op, Self>, Error> {
         Ok(Reduce::max(access, stride).into())
@@ -601,6 +1019,203 @@ impl<A: Access<T>, T: CType> ReduceAxes<A, T> for Host {
     fn sum(self, access: A, stride: usize) -> Result<AccessOp<Self::Op, Self>, Error> {
         Ok(Reduce::sum(access, stride).into())
     }
+
+    fn mean(self, access: A, stride: usize) -> Result<AccessOp<Self::Moment, Self>, Error> {
+        Ok(ReduceMoment::mean(access, stride).into())
+    }
+
+    fn var(self, access: A, stride: usize) -> Result<AccessOp<Self::Moment, Self>, Error> {
+        Ok(ReduceMoment::var(access, stride).into())
+    }
+
+    fn std(self, access: A, stride: usize) -> Result<AccessOp<Self::Moment, Self>, Error> {
+        Ok(ReduceMoment::std(access, stride).into())
+    }
+
+    fn argmax(self, access: A, stride: usize) -> Result<AccessOp<Self::Arg, Self>, Error> {
+        Ok(ReduceArg::argmax(access, stride).into())
+    }
+
+    fn argmin(self, access: A, stride: usize) -> Result<AccessOp<Self::Arg, Self>, Error> {
+        Ok(ReduceArg::argmin(access, stride).into())
+    }
+
+    fn max_ignore_nan(self, access: A, stride: usize) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ok(Reduce::max_ignore_nan(access, stride).into())
+    }
+
+    fn min_ignore_nan(self, access: A, stride: usize) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ok(Reduce::min_ignore_nan(access, stride).into())
+    }
+
+    fn product_ignore_nan(
+        self,
+        access: A,
+        stride: usize,
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ok(Reduce::product_ignore_nan(access, stride).into())
+    }
+
+    fn sum_ignore_nan(self, access: A, stride: usize) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ok(Reduce::sum_ignore_nan(access, stride).into())
+    }
+
+    fn identity(self, access: A, size: usize, id: T) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ok(Reduce::identity(access, size, id).into())
+    }
+}
+
+impl<O, A, T> Segments<O, A, T> for Host
+where
+    O: Access<u64>,
+    A: Access<T>,
+    T: CType,
+{
+    type Pad = SegmentPad<O, A, T>;
+    type Reduce = SegmentReduce<O, A, T>;
+
+    fn pad_segments(
+        self,
+        offsets: O,
+        values: A,
+        segments: usize,
+        max_len: usize,
+        pad: T,
+    ) -> Result<AccessOp<Self::Pad, Self>, Error> {
+        Ok(SegmentPad::new(offsets, values, segments, max_len, pad).into())
+    }
+
+    fn segment_max(
+        self,
+        offsets: O,
+        values: A,
+        segments: usize,
+    ) -> Result<AccessOp<Self::Reduce, Self>, Error> {
+        Ok(SegmentReduce::max(offsets, values, segments).into())
+    }
+
+    fn segment_min(
+        self,
+        offsets: O,
+        values: A,
+        segments: usize,
+    ) -> Result<AccessOp<Self::Reduce, Self>, Error> {
+        Ok(SegmentReduce::min(offsets, values, segments).into())
+    }
+
+    fn segment_product(
+        self,
+        offsets: O,
+        values: A,
+        segments: usize,
+    ) -> Result<AccessOp<Self::Reduce, Self>, Error> {
+        Ok(SegmentReduce::product(offsets, values, segments).into())
+    }
+
+    fn segment_sum(
+        self,
+        offsets: O,
+        values: A,
+        segments: usize,
+    ) -> Result<AccessOp<Self::Reduce, Self>, Error> {
+        Ok(SegmentReduce::sum(offsets, values, segments).into())
+    }
+}
+
+impl<A: Access<T>, T: CType> SortAxis<A, T> for Host {
+    type Sort = Sort<A, T>;
+    type ArgSort = ArgSort<A, T>;
+
+    fn sort(
+        self,
+        access: A,
+        dim: usize,
+        inner: usize,
+        descending: bool,
+    ) -> Result<AccessOp<Self::Sort, Self>, Error> {
+        let spec = SortSpec {
+            dim,
+            inner,
+            descending,
+        };
+
+        Ok(Sort::new(access, spec).into())
+    }
+
+    fn argsort(
+        self,
+        access: A,
+        dim: usize,
+        inner: usize,
+    ) -> Result<AccessOp<Self::ArgSort, Self>, Error> {
+        let spec = SortSpec {
+            dim,
+            inner,
+            descending: false,
+        };
+
+        Ok(ArgSort::new(access, spec).into())
+    }
+}
+
+impl<A: Access<T>, T: CType> ScanAxis<A, T> for Host {
+    type Op = Scan<A, T>;
+    type Cdf = Cdf<A, T>;
+
+    fn cdf(self, access: A, dim: usize, inner: usize) -> Result<AccessOp<Self::Cdf, Self>, Error> {
+        Ok(Cdf::new(access, dim, inner).into())
+    }
+
+    fn cumprod(
+        self,
+        access: A,
+        dim: usize,
+        inner: usize,
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ok(Scan::cumprod(access, dim, inner).into())
+    }
+
+    fn cumsum(
+        self,
+        access: A,
+        dim: usize,
+        inner: usize,
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ok(Scan::cumsum(access, dim, inner).into())
+    }
+
+    fn logcumsumexp(
+        self,
+        access: A,
+        dim: usize,
+        inner: usize,
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ok(Scan::logcumsumexp(access, dim, inner).into())
+    }
+}
+
+impl<A: Access<T>, T: CType> ReduceAxesWithIndex<A, T> for Host {
+    fn max_with_index(self, access: A, stride: usize) -> Result<(Buffer<T>, Buffer<u64>), Error> {
+        match self {
+            Self::Heap(heap) => heap
+                .max_with_index(access, stride)
+                .map(|(values, indices)| (values.into(), indices.into())),
+            Self::Stack(stack) => stack
+                .max_with_index(access, stride)
+                .map(|(values, indices)| (values.into(), indices.into())),
+        }
+    }
+
+    fn min_with_index(self, access: A, stride: usize) -> Result<(Buffer<T>, Buffer<u64>), Error> {
+        match self {
+            Self::Heap(heap) => heap
+                .min_with_index(access, stride)
+                .map(|(values, indices)| (values.into(), indices.into())),
+            Self::Stack(stack) => stack
+                .min_with_index(access, stride)
+                .map(|(values, indices)| (values.into(), indices.into())),
+        }
+    }
 }
 
 impl<'a, A, T> Transform<A, T> for Host
@@ -638,4 +1253,91 @@ where
     ) -> Result<AccessOp<Self::Transpose, Self>, Error> {
         Ok(View::transpose(access, shape, permutation).into())
     }
+
+    fn reverse(
+        self,
+        access: A,
+        shape: Shape,
+        axes: Axes,
+    ) -> Result<AccessOp<Self::Transpose, Self>, Error> {
+        Ok(View::reverse(access, shape, axes).into())
+    }
+
+    fn roll(
+        self,
+        access: A,
+        shape: Shape,
+        shift: usize,
+        axis: usize,
+    ) -> Result<AccessOp<Self::Transpose, Self>, Error> {
+        Ok(View::roll(access, shape, shift, axis).into())
+    }
+
+    unsafe fn as_strided(
+        self,
+        access: A,
+        shape: Shape,
+        strides: Strides,
+        offset: usize,
+    ) -> Result<AccessOp<Self::Transpose, Self>, Error> {
+        Ok(View::new(access, ViewSpec::strided(shape, strides, offset)).into())
+    }
+}
+
+// update `y` in place if it's in host memory, otherwise read it, update it, and write it back
+fn scatter<I, A, T, Y>(
+    y: &mut Y,
+    indices: I,
+    values: A,
+    dims: [usize; 3],
+    update: fn(&mut T, T),
+) -> Result<(), Error>
+where
+    I: Access<u64>,
+    A: Access<T>,
+    T: CType,
+    Y: AccessMut<T>,
+{
+    let [_outer, dim, inner] = dims;
+    let indices = indices.read().and_then(|buf| buf.to_slice())?;
+
+    // check every index before updating anything, so that an error leaves `y` unchanged
+    if let Some(index) = indices.iter().find(|index| **index as usize >= dim) {
+        return Err(scatter_bounds(*index as usize, dim));
+    }
+
+    if indices.is_empty() || inner == 0 {
+        return Ok(());
+    }
+
+    accumulate(y, values, |y, values| {
+        for (i, row) in values.chunks_exact(inner).enumerate() {
+            let (o, k) = (i / indices.len(), i % indices.len());
+            let start = ((o * dim) + indices[k] as usize) * inner;
+
+            y[start..(start + inner)]
+                .iter_mut()
+                .zip(row)
+                .for_each(|(y, value)| update(y, *value));
+        }
+    })
+}
+
+fn accumulate<A, T, Y, F>(y: &mut Y, x: A, update: F) -> Result<(), Error>
+where
+    A: Access<T>,
+    T: CType,
+    Y: AccessMut<T>,
+    F: FnOnce(&mut [T], &[T]),
+{
+    let x = x.read().and_then(|buf| buf.to_slice())?;
+
+    if let Ok(y) = y.host_buffer() {
+        update(y, &x);
+        return Ok(());
+    }
+
+    let mut data = y.read().and_then(|buf| buf.to_slice())?.into_vec();
+    update(&mut data, &x);
+    y.write(data.into())
 }
@@ -0,0 +1,313 @@
+//! Host buffers backed by named shared memory, for exchanging arrays between processes
+//!
+//! A segment begins with a header page containing an [`crate::io::ipc::Header`],
+//! followed by the elements of the array in native byte order.
+//! Any process which knows the name of a segment can [`open`] it as an array without copying.
+//! Reads and writes are not synchronized between processes.
+
+use std::ffi::CString;
+use std::fmt;
+use std::io;
+use std::mem;
+use std::ptr::NonNull;
+
+use crate::access::AccessBuf;
+use crate::array::Array;
+use crate::buffer::{BufferConverter, BufferInstance, BufferMut};
+use crate::io::ipc::Header;
+use crate::{CType, Error, NDArrayRead, NDArrayWrite, Shape};
+
+use super::Host;
+
+/// An array backed by a named shared memory segment
+pub type SharedArray<T> = Array<T, AccessBuf<SharedBuffer<T>>, Host>;
+
+// the size of the header region at the start of each segment, which keeps the data page-aligned
+const HEADER_SIZE: usize = 4096;
+
+/// A host buffer backed by a memory-mapped shared memory segment
+pub struct SharedBuffer<T> {
+    name: String,
+    map: NonNull<libc::c_void>,
+    map_len: usize,
+    len: usize,
+    dtype: std::marker::PhantomData<T>,
+}
+
+// SAFETY: the mapping is owned by this buffer and unmapped only when it's dropped
+unsafe impl<T: Send> Send for SharedBuffer<T> {}
+unsafe impl<T: Sync> Sync for SharedBuffer<T> {}
+
+impl<T: CType> SharedBuffer<T> {
+    /// Borrow the name of the shared memory segment backing this buffer.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Borrow the contents of this buffer.
+    pub fn as_slice(&self) -> &[T] {
+        // SAFETY: the mapping is page-aligned and at least HEADER_SIZE + len * size_of::<T>()
+        unsafe { std::slice::from_raw_parts(self.data() as *const T, self.len) }
+    }
+
+    /// Borrow the contents of this buffer mutably.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        // SAFETY: the mapping is page-aligned and at least HEADER_SIZE + len * size_of::<T>()
+        unsafe { std::slice::from_raw_parts_mut(self.data(), self.len) }
+    }
+
+    fn data(&self) -> *mut T {
+        // SAFETY: the mapping is at least HEADER_SIZE bytes long
+        unsafe { (self.map.as_ptr() as *mut u8).add(HEADER_SIZE) as *mut T }
+    }
+}
+
+impl<T> Drop for SharedBuffer<T> {
+    fn drop(&mut self) {
+        // SAFETY: this buffer owns its mapping, which no slice can outlive
+        unsafe {
+            libc::munmap(self.map.as_ptr(), self.map_len);
+        }
+    }
+}
+
+impl<T: CType> BufferInstance<T> for SharedBuffer<T> {
+    fn read(&self) -> BufferConverter<'_, T> {
+        self.as_slice().into()
+    }
+
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        BufferInstance::read_value(&self.as_slice(), offset)
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<T: CType> BufferMut<T> for SharedBuffer<T> {
+    fn host(&mut self) -> Result<&mut [T], Error> {
+        Ok(self.as_mut_slice())
+    }
+
+    fn write<'a>(&mut self, data: BufferConverter<'a, T>) -> Result<(), Error> {
+        self.as_mut_slice().write(data)
+    }
+
+    fn write_value(&mut self, value: T) -> Result<(), Error> {
+        self.as_mut_slice().write_value(value)
+    }
+
+    fn write_value_at(&mut self, offset: usize, value: T) -> Result<(), Error> {
+        self.as_mut_slice().write_value_at(offset, value)
+    }
+}
+
+impl<T: CType> fmt::Debug for SharedBuffer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "a shared buffer {} of {} elements of type {}",
+            self.name,
+            self.len,
+            T::TYPE
+        )
+    }
+}
+
+/// Create a new shared memory segment with the given `name`, filled with zeros,
+/// and map it as an array with the given `shape`.
+/// The segment persists until it's [`unlink`]ed, even after every process has closed it.
+pub fn create<T: CType>(name: &str, shape: Shape) -> Result<SharedArray<T>, Error> {
+    if shape.is_empty() {
+        return Err(Error::Bounds(
+            "cannot construct an array with an empty shape".to_string(),
+        ));
+    }
+
+    let header = Header::new::<T>(shape.clone()).encode();
+
+    if header.len() > HEADER_SIZE {
+        return Err(Error::Bounds(format!(
+            "cannot share an array with {} dimensions",
+            shape.len()
+        )));
+    }
+
+    let len = crate::io::checked_size::<T>(&shape)?;
+    let map_len = (len * mem::size_of::<T>())
+        .checked_add(HEADER_SIZE)
+        .ok_or_else(|| Error::Bounds(format!("cannot share an array with shape {shape:?}")))?;
+
+    let path = segment_path(name)?;
+    // SAFETY: path is a valid C string
+    let fd = unsafe {
+        libc::shm_open(
+            path.as_ptr(),
+            libc::O_CREAT | libc::O_EXCL | libc::O_RDWR,
+            0o600,
+        )
+    };
+
+    if fd < 0 {
+        return Err(shm_error(name, io::Error::last_os_error()));
+    }
+
+    // SAFETY: fd is a valid file descriptor, which is closed exactly once
+    let map = unsafe {
+        let truncated = libc::ftruncate(fd, map_len as libc::off_t);
+        let result = if truncated == 0 {
+            map(fd, map_len)
+        } else {
+            Err(io::Error::last_os_error())
+        };
+
+        libc::close(fd);
+        result
+    };
+
+    let map = match map {
+        Ok(map) => map,
+        Err(cause) => {
+            let _ = unlink(name);
+            return Err(shm_error(name, cause));
+        }
+    };
+
+    // SAFETY: the mapping is at least HEADER_SIZE bytes long
+    unsafe {
+        std::ptr::copy_nonoverlapping(header.as_ptr(), map.as_ptr() as *mut u8, header.len());
+    }
+
+    let buffer = SharedBuffer {
+        name: name.to_string(),
+        map,
+        map_len,
+        len,
+        dtype: std::marker::PhantomData,
+    };
+
+    Array::new(buffer, shape)
+}
+
+/// Create a new shared memory segment with the given `name` and copy the given `array` into it.
+pub fn share<A: NDArrayRead>(name: &str, array: &A) -> Result<SharedArray<A::DType>, Error> {
+    let shape = array.shape().iter().copied().collect();
+    let mut shared = create(name, shape)?;
+
+    match shared.write(array) {
+        Ok(()) => Ok(shared),
+        Err(cause) => {
+            let _ = unlink(name);
+            Err(cause)
+        }
+    }
+}
+
+/// Open the existing shared memory segment with the given `name`, which must have data type `T`.
+pub fn open<T: CType>(name: &str) -> Result<SharedArray<T>, Error> {
+    let path = segment_path(name)?;
+
+    // SAFETY: path is a valid C string
+    let fd = unsafe { libc::shm_open(path.as_ptr(), libc::O_RDWR, 0) };
+
+    if fd < 0 {
+        return Err(shm_error(name, io::Error::last_os_error()));
+    }
+
+    // SAFETY: fd is a valid file descriptor, which is closed exactly once
+    let map = unsafe {
+        let mut stat: libc::stat = mem::zeroed();
+        let result = if libc::fstat(fd, &mut stat) == 0 {
+            let map_len = stat.st_size as usize;
+
+            if map_len < HEADER_SIZE {
+                Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "not a shared array segment",
+                ))
+            } else {
+                map(fd, map_len).map(|map| (map, map_len))
+            }
+        } else {
+            Err(io::Error::last_os_error())
+        };
+
+        libc::close(fd);
+        result
+    };
+
+    let (map, map_len) = map.map_err(|cause| shm_error(name, cause))?;
+
+    let mut buffer = SharedBuffer {
+        name: name.to_string(),
+        map,
+        map_len,
+        len: 0,
+        dtype: std::marker::PhantomData,
+    };
+
+    // SAFETY: the mapping is at least HEADER_SIZE bytes long
+    let header = unsafe { std::slice::from_raw_parts(map.as_ptr() as *const u8, HEADER_SIZE) };
+    let (header, _) = Header::decode(header)?;
+    header.expect::<T>()?;
+
+    // the header was written by another process, so its shape must be checked before it's used
+    let shape = header.into_shape();
+    let len = crate::io::checked_size::<T>(&shape)?;
+
+    if len * mem::size_of::<T>() > map_len - HEADER_SIZE {
+        return Err(Error::Bounds(format!(
+            "shared memory segment {name} is too small for an array with shape {shape:?}"
+        )));
+    }
+
+    buffer.len = len;
+    Array::new(buffer, shape)
+}
+
+/// Remove the shared memory segment with the given `name`.
+/// Processes which have already opened the segment can continue to use it.
+pub fn unlink(name: &str) -> Result<(), Error> {
+    let path = segment_path(name)?;
+
+    // SAFETY: path is a valid C string
+    if unsafe { libc::shm_unlink(path.as_ptr()) } == 0 {
+        Ok(())
+    } else {
+        Err(shm_error(name, io::Error::last_os_error()))
+    }
+}
+
+unsafe fn map(fd: libc::c_int, len: usize) -> io::Result<NonNull<libc::c_void>> {
+    let map = libc::mmap(
+        std::ptr::null_mut(),
+        len,
+        libc::PROT_READ | libc::PROT_WRITE,
+        libc::MAP_SHARED,
+        fd,
+        0,
+    );
+
+    if map == libc::MAP_FAILED {
+        Err(io::Error::last_os_error())
+    } else {
+        NonNull::new(map).ok_or_else(|| io::Error::other("mmap returned a null pointer"))
+    }
+}
+
+fn segment_path(name: &str) -> Result<CString, Error> {
+    let valid = !name.is_empty() && name.len() < 240 && !name.contains(['/', '\0']);
+
+    if valid {
+        Ok(CString::new(format!("/{name}")).expect("segment path"))
+    } else {
+        Err(Error::Bounds(format!(
+            "invalid shared memory segment name: {name:?}"
+        )))
+    }
+}
+
+fn shm_error(name: &str, cause: io::Error) -> Error {
+    Error::Interface(format!("shared memory segment {name}: {cause}"))
+}
@@ -0,0 +1,330 @@
+//! Introspection of array op graphs, without executing them
+
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::access::Access;
+use crate::array::Array;
+use crate::host::VEC_MIN_SIZE;
+use crate::platform::PlatformInstance;
+#[cfg(feature = "opencl")]
+use crate::{config, opencl::ACC_MIN_SIZE};
+use crate::{CType, NDArray, Shape};
+
+const BREADCRUMB_DEPTH: usize = 4;
+
+static UPLOADS: AtomicUsize = AtomicUsize::new(0);
+static UPLOAD_BYTES: AtomicUsize = AtomicUsize::new(0);
+static DOWNLOADS: AtomicUsize = AtomicUsize::new(0);
+static DOWNLOAD_BYTES: AtomicUsize = AtomicUsize::new(0);
+static DEVICE_COPIES: AtomicUsize = AtomicUsize::new(0);
+static DEVICE_COPY_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+/// Counters of the data transfers between host and device memory,
+/// and between devices, made by this process
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct TransferStats {
+    /// The number of copies from host memory into device memory
+    pub uploads: usize,
+    /// The number of bytes copied from host memory into device memory
+    pub upload_bytes: usize,
+    /// The number of copies from device memory into host memory
+    pub downloads: usize,
+    /// The number of bytes copied from device memory into host memory
+    pub download_bytes: usize,
+    /// The number of explicit copies from one device into another
+    pub device_copies: usize,
+    /// The number of bytes copied from one device into another
+    pub device_copy_bytes: usize,
+}
+
+impl fmt::Display for TransferStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} uploads ({} bytes), {} downloads ({} bytes), {} device copies ({} bytes)",
+            self.uploads,
+            self.upload_bytes,
+            self.downloads,
+            self.download_bytes,
+            self.device_copies,
+            self.device_copy_bytes
+        )
+    }
+}
+
+/// Return the number of data transfers between host and device memory, and between devices,
+/// made so far.
+pub fn transfer_stats() -> TransferStats {
+    TransferStats {
+        uploads: UPLOADS.load(Ordering::Relaxed),
+        upload_bytes: UPLOAD_BYTES.load(Ordering::Relaxed),
+        downloads: DOWNLOADS.load(Ordering::Relaxed),
+        download_bytes: DOWNLOAD_BYTES.load(Ordering::Relaxed),
+        device_copies: DEVICE_COPIES.load(Ordering::Relaxed),
+        device_copy_bytes: DEVICE_COPY_BYTES.load(Ordering::Relaxed),
+    }
+}
+
+#[cfg(feature = "opencl")]
+pub(crate) fn record_upload<T>(len: usize) {
+    UPLOADS.fetch_add(1, Ordering::Relaxed);
+    UPLOAD_BYTES.fetch_add(len * std::mem::size_of::<T>(), Ordering::Relaxed);
+}
+
+#[cfg(feature = "opencl")]
+pub(crate) fn record_download<T>(len: usize) {
+    DOWNLOADS.fetch_add(1, Ordering::Relaxed);
+    DOWNLOAD_BYTES.fetch_add(len * std::mem::size_of::<T>(), Ordering::Relaxed);
+}
+
+#[cfg(feature = "opencl")]
+pub(crate) fn record_device_copy<T>(len: usize) {
+    DEVICE_COPIES.fetch_add(1, Ordering::Relaxed);
+    DEVICE_COPY_BYTES.fetch_add(len * std::mem::size_of::<T>(), Ordering::Relaxed);
+}
+
+/// A description of a single node in an op graph
+#[derive(Clone, Debug)]
+pub struct OpPlan {
+    /// The name of this op, or "buffer" or "constant" if these data are already resident
+    pub name: String,
+    /// The platform which would execute this op, if any
+    pub platform: Option<&'static str>,
+    /// The device type which would be selected to execute this op, if any
+    pub device: Option<&'static str>,
+    /// The data type of the output of this op
+    pub dtype: &'static str,
+    /// The number of elements output by this op
+    pub size: usize,
+    /// The estimated number of bytes occupied by the output of this op
+    pub memory: usize,
+    /// The inputs of this op
+    pub inputs: Vec<OpPlan>,
+}
+
+impl OpPlan {
+    pub(crate) fn buffer<T: CType>(size: usize) -> Self {
+        Self {
+            name: "buffer".to_string(),
+            platform: None,
+            device: None,
+            dtype: T::TYPE,
+            size,
+            memory: size * std::mem::size_of::<T>(),
+            inputs: Vec::new(),
+        }
+    }
+
+    pub(crate) fn constant<T: CType>(size: usize) -> Self {
+        Self {
+            name: "constant".to_string(),
+            platform: None,
+            device: None,
+            dtype: T::TYPE,
+            size,
+            memory: 0,
+            inputs: Vec::new(),
+        }
+    }
+
+    pub(crate) fn op<T: CType>(name: &'static str, size: usize, inputs: Vec<OpPlan>) -> Self {
+        let (platform, device) = if cfg!(feature = "opencl") && name.contains("::opencl::") {
+            (Some("opencl"), Some(cl_device(size)))
+        } else if name.contains("::host::") {
+            let device = if size < VEC_MIN_SIZE { "stack" } else { "heap" };
+
+            (Some("host"), Some(device))
+        } else {
+            (None, None)
+        };
+
+        Self {
+            name: short_name(name),
+            platform,
+            device,
+            dtype: T::TYPE,
+            size,
+            memory: size * std::mem::size_of::<T>(),
+            inputs,
+        }
+    }
+
+    /// Return `true` if this node is an already-resident buffer or constant rather than an op.
+    pub fn is_buffer(&self) -> bool {
+        self.platform.is_none() && self.inputs.is_empty()
+    }
+
+    /// The estimated number of bytes which would be allocated to execute this op graph.
+    pub fn memory_required(&self) -> usize {
+        let inputs = self
+            .inputs
+            .iter()
+            .map(|input| input.memory_required())
+            .sum::<usize>();
+
+        if self.is_buffer() {
+            inputs
+        } else {
+            inputs + self.memory
+        }
+    }
+
+    /// The number of ops (not including resident buffers) in this op graph.
+    pub fn op_count(&self) -> usize {
+        let inputs = self
+            .inputs
+            .iter()
+            .map(|input| input.op_count())
+            .sum::<usize>();
+
+        if self.is_buffer() {
+            inputs
+        } else {
+            inputs + 1
+        }
+    }
+
+    /// Summarize this op graph on a single line, for use in error messages.
+    pub fn breadcrumb(&self) -> String {
+        let mut breadcrumb = String::new();
+        self.fmt_breadcrumb(&mut breadcrumb, 0).expect("breadcrumb");
+        breadcrumb
+    }
+
+    fn fmt_breadcrumb<W: fmt::Write>(&self, f: &mut W, depth: usize) -> fmt::Result {
+        write!(f, "{}[{}]", self.name, self.size)?;
+
+        if self.inputs.is_empty() {
+            return Ok(());
+        } else if depth >= BREADCRUMB_DEPTH {
+            return f.write_str("(..)");
+        }
+
+        f.write_char('(')?;
+
+        for (i, input) in self.inputs.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+
+            input.fmt_breadcrumb(f, depth + 1)?;
+        }
+
+        f.write_char(')')
+    }
+
+    fn fmt_indent(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        write!(
+            f,
+            "{:indent$}{} <{}> size {} ({} bytes)",
+            "",
+            self.name,
+            self.dtype,
+            self.size,
+            self.memory,
+            indent = depth * 2
+        )?;
+
+        if let (Some(platform), Some(device)) = (self.platform, self.device) {
+            write!(f, " on {platform} ({device})")?;
+        }
+
+        writeln!(f)?;
+
+        for input in &self.inputs {
+            input.fmt_indent(f, depth + 1)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for OpPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_indent(f, 0)
+    }
+}
+
+/// A description of the op graph of an array, as it would be executed
+#[derive(Clone, Debug)]
+pub struct Plan {
+    /// The shape of the array
+    pub shape: Shape,
+    /// The data type of the array
+    pub dtype: &'static str,
+    /// The platform which would be selected to compute the array
+    pub platform: String,
+    /// The root of the op graph
+    pub root: OpPlan,
+}
+
+impl Plan {
+    /// The estimated number of bytes which would be allocated to compute the array.
+    pub fn memory_required(&self) -> usize {
+        self.root.memory_required()
+    }
+
+    /// The number of ops which would be executed to compute the array.
+    pub fn op_count(&self) -> usize {
+        self.root.op_count()
+    }
+}
+
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "array <{}> with shape {:?} on {} ({} ops, {} bytes required)",
+            self.dtype,
+            self.shape,
+            self.platform,
+            self.op_count(),
+            self.memory_required()
+        )?;
+
+        self.root.fmt_indent(f, 1)
+    }
+}
+
+/// Describe the op graph of the given `array`, without executing it.
+pub fn plan<T, A, P>(array: &Array<T, A, P>) -> Plan
+where
+    T: CType,
+    A: Access<T>,
+    P: PlatformInstance,
+{
+    Plan {
+        shape: array.shape().into(),
+        dtype: T::TYPE,
+        platform: format!("{:?}", P::select(array.size())),
+        root: array.access().plan(),
+    }
+}
+
+#[cfg(feature = "opencl")]
+fn cl_device(size: usize) -> &'static str {
+    if size < config::gpu_min_size() {
+        "cpu"
+    } else if size < ACC_MIN_SIZE {
+        "gpu"
+    } else {
+        "accelerator"
+    }
+}
+
+#[cfg(not(feature = "opencl"))]
+fn cl_device(_size: usize) -> &'static str {
+    "none"
+}
+
+/// Label a single op, for use in error messages.
+pub(crate) fn op_label(name: &'static str, size: usize) -> String {
+    format!("{}[{size}]", short_name(name))
+}
+
+fn short_name(name: &str) -> String {
+    let name = name.split('<').next().unwrap_or(name);
+    let name = name.rsplit("::").next().unwrap_or(name);
+    name.to_string()
+}
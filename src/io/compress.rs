@@ -0,0 +1,121 @@
+//! Saving and loading arrays as zstd-compressed files
+//!
+//! A compressed file consists of an uncompressed header, with the data type and shape of the array,
+//! followed by a single zstd frame of its little-endian elements.
+//! Both saving and loading stream the data in chunks, so at most one copy of the array is in memory.
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use crate::array::encode_bytes;
+use crate::host::ArrayBuf;
+use crate::{CType, Error, NDArrayRead, Shape};
+
+use super::io_error;
+
+/// The default zstd compression level
+pub const DEFAULT_LEVEL: i32 = 3;
+
+const MAGIC: &[u8; 8] = b"densorz1";
+
+// the number of elements to encode and compress at a time
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Compress the contents of the given `array` and write them to a new file at `path`
+/// with the given zstd compression `level`.
+pub fn save<A, P>(array: &A, path: P, level: i32) -> Result<(), Error>
+where
+    A: NDArrayRead,
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let io_error = |cause| io_error(path, cause);
+
+    let data = array.buffer()?.to_slice()?;
+
+    let file = File::create(path).map_err(io_error)?;
+    let mut file = BufWriter::new(file);
+
+    let dtype = A::DType::TYPE.as_bytes();
+    let mut header = Vec::with_capacity(MAGIC.len() + 1 + dtype.len() + 8 * (array.ndim() + 1));
+    header.extend_from_slice(MAGIC);
+    header.push(dtype.len() as u8);
+    header.extend_from_slice(dtype);
+    header.extend_from_slice(&(array.ndim() as u64).to_le_bytes());
+
+    for dim in array.shape() {
+        header.extend_from_slice(&(*dim as u64).to_le_bytes());
+    }
+
+    file.write_all(&header).map_err(io_error)?;
+
+    let mut encoder = zstd::Encoder::new(file, level).map_err(io_error)?;
+
+    for chunk in data.chunks(CHUNK_SIZE) {
+        encoder
+            .write_all(&encode_bytes(chunk, true))
+            .map_err(io_error)?;
+    }
+
+    let mut file = encoder.finish().map_err(io_error)?;
+    file.flush().map_err(io_error)
+}
+
+/// Read and decompress the array saved at `path`, which must have data type `T`.
+pub fn load<T: CType, P: AsRef<Path>>(path: P) -> Result<ArrayBuf<T>, Error> {
+    let path = path.as_ref();
+    let io_error = |cause| io_error(path, cause);
+    let format_error = |msg: String| Error::Interface(format!("{}: {msg}", path.display()));
+
+    let file = File::open(path).map_err(io_error)?;
+    let mut file = BufReader::new(file);
+
+    let mut magic = [0u8; 8];
+    file.read_exact(&mut magic).map_err(io_error)?;
+
+    if &magic != MAGIC {
+        return Err(format_error("not a compressed array file".into()));
+    }
+
+    let mut len = [0u8; 1];
+    file.read_exact(&mut len).map_err(io_error)?;
+
+    let mut dtype = vec![0u8; len[0] as usize];
+    file.read_exact(&mut dtype).map_err(io_error)?;
+
+    if dtype != T::TYPE.as_bytes() {
+        return Err(format_error(format!(
+            "expected an array of {} but found {}",
+            T::TYPE,
+            String::from_utf8_lossy(&dtype)
+        )));
+    }
+
+    let ndim = read_u64(&mut file).map_err(io_error)?;
+    let shape = (0..ndim)
+        .map(|_| {
+            let dim = read_u64(&mut file).map_err(io_error)?;
+            usize::try_from(dim).map_err(|_| format_error(format!("dimension {dim} is too large")))
+        })
+        .collect::<Result<Shape, _>>()?;
+
+    let size = super::checked_size::<T>(&shape)?;
+
+    let mut decoder = zstd::Decoder::with_buffer(file).map_err(io_error)?;
+    let data = super::read_elements(&mut decoder, size).map_err(io_error)?;
+
+    if decoder.read(&mut [0u8]).map_err(io_error)? > 0 {
+        return Err(format_error(format!(
+            "found trailing data after {size} elements"
+        )));
+    }
+
+    ArrayBuf::new(data.into(), shape)
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> std::io::Result<u64> {
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
@@ -0,0 +1,109 @@
+//! Decoding and encoding images as arrays of shape `[height, width, channels]`
+//!
+//! Images are decoded to one byte per channel: 16-bit and floating-point images are converted.
+//! The number of channels is 1 (grayscale), 2 (grayscale with alpha), 3 (RGB), or 4 (RGBA).
+
+use std::fs;
+use std::io::Cursor;
+use std::path::Path;
+
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::PngEncoder;
+use image::{ExtendedColorType, ImageEncoder, ImageError};
+
+use crate::host::ArrayBuf;
+use crate::{shape, Error, NDArrayRead};
+
+use super::io_error;
+
+/// An image encoding
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ImageFormat {
+    /// Lossless PNG encoding
+    Png,
+    /// Lossy JPEG encoding with the given quality, from 1 to 100
+    Jpeg(u8),
+}
+
+/// Decode the given PNG or JPEG `bytes` into an array.
+pub fn decode(bytes: &[u8]) -> Result<ArrayBuf<u8>, Error> {
+    let image = image::load_from_memory(bytes).map_err(image_error)?;
+    let (width, height) = (image.width() as usize, image.height() as usize);
+
+    let (data, channels) = match image.color().channel_count() {
+        1 => (image.into_luma8().into_raw(), 1),
+        2 => (image.into_luma_alpha8().into_raw(), 2),
+        3 => (image.into_rgb8().into_raw(), 3),
+        _ => (image.into_rgba8().into_raw(), 4),
+    };
+
+    ArrayBuf::new(data.into(), shape![height, width, channels])
+}
+
+/// Read and decode the PNG or JPEG file at `path` into an array.
+pub fn load<P: AsRef<Path>>(path: P) -> Result<ArrayBuf<u8>, Error> {
+    let path = path.as_ref();
+    let bytes = fs::read(path).map_err(|cause| io_error(path, cause))?;
+    decode(&bytes)
+}
+
+/// Encode the given `array` of shape `[height, width, channels]` as an image.
+pub fn encode<A>(array: &A, format: ImageFormat) -> Result<Vec<u8>, Error>
+where
+    A: NDArrayRead<DType = u8>,
+{
+    let (height, width, color) = match array.shape() {
+        [height, width, channels] => {
+            let color = match channels {
+                1 => ExtendedColorType::L8,
+                2 => ExtendedColorType::La8,
+                3 => ExtendedColorType::Rgb8,
+                4 => ExtendedColorType::Rgba8,
+                other => {
+                    return Err(Error::Bounds(format!(
+                        "an image cannot have {other} channels"
+                    )))
+                }
+            };
+
+            (dimension(*height)?, dimension(*width)?, color)
+        }
+        other => {
+            return Err(Error::Bounds(format!(
+                "an image must have shape [height, width, channels], not {other:?}"
+            )))
+        }
+    };
+
+    let data = array.buffer()?.to_slice()?;
+    let mut encoded = Cursor::new(Vec::new());
+
+    match format {
+        ImageFormat::Png => PngEncoder::new(&mut encoded).write_image(&data, width, height, color),
+        ImageFormat::Jpeg(quality) => JpegEncoder::new_with_quality(&mut encoded, quality)
+            .write_image(&data, width, height, color),
+    }
+    .map_err(image_error)?;
+
+    Ok(encoded.into_inner())
+}
+
+/// Encode the given `array` of shape `[height, width, channels]` and write it to `path`.
+pub fn save<A, P>(array: &A, path: P, format: ImageFormat) -> Result<(), Error>
+where
+    A: NDArrayRead<DType = u8>,
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let encoded = encode(array, format)?;
+    fs::write(path, encoded).map_err(|cause| io_error(path, cause))
+}
+
+#[inline]
+fn dimension(dim: usize) -> Result<u32, Error> {
+    u32::try_from(dim).map_err(|_| Error::Bounds(format!("image dimension {dim} is too large")))
+}
+
+fn image_error(cause: ImageError) -> Error {
+    Error::Interface(format!("image codec error: {cause}"))
+}
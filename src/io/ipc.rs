@@ -0,0 +1,178 @@
+//! A lightweight wire format for sending arrays between processes
+//!
+//! A message consists of a [`Header`], with the data type and shape of the array,
+//! followed by its elements in little-endian byte order:
+//!
+//! | field    | size          | encoding                    |
+//! |----------|---------------|-----------------------------|
+//! | magic    | 4 bytes       | `DNSR`                      |
+//! | version  | 1 byte        | currently `1`               |
+//! | dtype    | 1 + n bytes   | length, then the type name  |
+//! | ndim     | 4 bytes       | little-endian `u32`         |
+//! | shape    | 8 * ndim bytes| little-endian `u64` each    |
+//! | data     | the rest      | little-endian elements      |
+
+use std::io::{Read, Write};
+use std::mem;
+
+use crate::array::encode_bytes;
+use crate::host::ArrayBuf;
+use crate::{CType, Error, NDArrayRead, Shape};
+
+const MAGIC: &[u8; 4] = b"DNSR";
+const VERSION: u8 = 1;
+
+/// The header of an IPC message
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Header {
+    dtype: String,
+    shape: Shape,
+}
+
+impl Header {
+    /// Construct the header of a message containing an array of type `T` with the given `shape`.
+    pub fn new<T: CType>(shape: Shape) -> Self {
+        Self {
+            dtype: T::TYPE.to_string(),
+            shape,
+        }
+    }
+
+    /// Construct the header of a message containing the given `array`.
+    pub fn of<A: NDArrayRead>(array: &A) -> Self {
+        Self::new::<A::DType>(array.shape().iter().copied().collect())
+    }
+
+    /// Borrow the name of the data type of this message.
+    pub fn dtype(&self) -> &str {
+        &self.dtype
+    }
+
+    /// Borrow the shape of the array in this message.
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    /// Consume this header and return the shape of the array in this message.
+    pub fn into_shape(self) -> Shape {
+        self.shape
+    }
+
+    /// Return an error if the data type of this message is not `T`.
+    pub fn expect<T: CType>(&self) -> Result<(), Error> {
+        if self.dtype == T::TYPE {
+            Ok(())
+        } else {
+            Err(Error::Interface(format!(
+                "expected a message with data type {} but found {}",
+                T::TYPE,
+                self.dtype
+            )))
+        }
+    }
+
+    /// Encode this header.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut header =
+            Vec::with_capacity(MAGIC.len() + 2 + self.dtype.len() + 4 + 8 * self.shape.len());
+
+        header.extend_from_slice(MAGIC);
+        header.push(VERSION);
+        header.push(self.dtype.len() as u8);
+        header.extend_from_slice(self.dtype.as_bytes());
+        header.extend_from_slice(&(self.shape.len() as u32).to_le_bytes());
+
+        for dim in self.shape.iter() {
+            header.extend_from_slice(&(*dim as u64).to_le_bytes());
+        }
+
+        header
+    }
+
+    /// Decode the header at the start of the given `message`,
+    /// and return it together with the remaining bytes of the message.
+    pub fn decode(message: &[u8]) -> Result<(Self, &[u8]), Error> {
+        let mut remaining = message;
+        let header = Self::read(&mut remaining)?;
+        Ok((header, remaining))
+    }
+
+    /// Read a header from the given `reader`.
+    pub fn read<R: Read>(reader: &mut R) -> Result<Self, Error> {
+        let mut prefix = [0u8; 6];
+        reader.read_exact(&mut prefix).map_err(transport_error)?;
+
+        if &prefix[..4] != MAGIC {
+            return Err(Error::Interface("not an array message".to_string()));
+        } else if prefix[4] != VERSION {
+            return Err(Error::Interface(format!(
+                "unsupported array message version {}",
+                prefix[4]
+            )));
+        }
+
+        let mut dtype = vec![0u8; prefix[5] as usize];
+        reader.read_exact(&mut dtype).map_err(transport_error)?;
+        let dtype = String::from_utf8(dtype)
+            .map_err(|cause| Error::Interface(format!("invalid data type name: {cause}")))?;
+
+        let mut ndim = [0u8; 4];
+        reader.read_exact(&mut ndim).map_err(transport_error)?;
+
+        let shape = (0..u32::from_le_bytes(ndim))
+            .map(|_| {
+                let mut dim = [0u8; 8];
+                reader.read_exact(&mut dim).map_err(transport_error)?;
+
+                usize::try_from(u64::from_le_bytes(dim))
+                    .map_err(|_| Error::Bounds("array message dimension is too large".into()))
+            })
+            .collect::<Result<Shape, Error>>()?;
+
+        Ok(Self { dtype, shape })
+    }
+}
+
+/// Write the given `array` as a message to `writer`, without buffering the whole message.
+pub fn write<A, W>(array: &A, writer: &mut W) -> Result<(), Error>
+where
+    A: NDArrayRead,
+    W: Write,
+{
+    let data = array.buffer()?.to_slice()?;
+
+    writer
+        .write_all(&Header::of(array).encode())
+        .map_err(transport_error)?;
+
+    if cfg!(target_endian = "little") {
+        writer.write_all(native_bytes(&data))
+    } else {
+        writer.write_all(&encode_bytes(&data, true))
+    }
+    .map_err(transport_error)
+}
+
+/// Read a message with data type `T` from `reader`,
+/// copying its data directly into the buffer of the new array.
+///
+/// The buffer grows in chunks as data are received, so a header which claims more data than
+/// the message contains results in an error rather than a large allocation.
+pub fn read<T: CType, R: Read>(reader: &mut R) -> Result<ArrayBuf<T>, Error> {
+    let header = Header::read(reader)?;
+    header.expect::<T>()?;
+
+    let size = super::checked_size::<T>(&header.shape)?;
+    let data = super::read_elements(reader, size).map_err(transport_error)?;
+
+    ArrayBuf::new(data.into(), header.shape)
+}
+
+fn native_bytes<T: CType>(data: &[T]) -> &[u8] {
+    // SAFETY: every CType is a primitive number, which has no padding bytes
+    unsafe { std::slice::from_raw_parts(data.as_ptr() as *const u8, mem::size_of_val(data)) }
+}
+
+fn transport_error(cause: std::io::Error) -> Error {
+    Error::Interface(format!("array message transport error: {cause}"))
+}
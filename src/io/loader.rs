@@ -0,0 +1,233 @@
+//! Chunked loaders which read and parse files on background threads
+//!
+//! A [`Loader`] reads its file on a dedicated thread, parses each chunk on the global thread pool,
+//! and buffers up to [`Options::prefetch`] parsed chunks ahead of the consumer,
+//! so that reading the next chunk from disk overlaps with computing on the current one.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::sync::mpsc;
+use std::{mem, thread};
+
+use rayon::prelude::*;
+
+use crate::host::ArrayBuf;
+use crate::{shape, CType, Error};
+
+use super::io_error;
+
+/// Options for a [`Loader`]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Options {
+    /// The maximum number of rows in each chunk
+    pub rows_per_chunk: usize,
+    /// The maximum number of parsed chunks to buffer ahead of the consumer
+    pub prefetch: usize,
+    /// The delimiter between fields in a CSV file
+    pub delimiter: char,
+    /// Whether the first line of a CSV file is a header to skip
+    pub header: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            rows_per_chunk: 4096,
+            prefetch: 2,
+            delimiter: ',',
+            header: false,
+        }
+    }
+}
+
+/// An iterator over the chunks of a file, each a host array with shape `[rows, columns]`
+pub struct Loader<T: CType> {
+    chunks: mpsc::Receiver<Result<ArrayBuf<T>, Error>>,
+}
+
+impl<T: CType> Loader<T> {
+    /// Load a CSV file of numbers, with the same number of fields on every line.
+    /// Empty lines are skipped.
+    pub fn csv<P: AsRef<Path>>(path: P, options: Options) -> Result<Self, Error> {
+        let path = path.as_ref().to_path_buf();
+        let file = File::open(&path).map_err(|cause| io_error(&path, cause))?;
+        let mut lines = BufReader::new(file).lines();
+
+        if options.header {
+            if let Some(Err(cause)) = lines.next() {
+                return Err(io_error(&path, cause));
+            }
+        }
+
+        let mut columns = None;
+        let mut line_num = options.header as usize;
+
+        Self::spawn(options, move || {
+            let mut rows = Vec::with_capacity(options.rows_per_chunk);
+
+            for line in lines.by_ref() {
+                line_num += 1;
+
+                match line {
+                    Ok(line) if line.trim().is_empty() => continue,
+                    Ok(line) => rows.push((line_num, line)),
+                    Err(cause) => return Some(Err(io_error(&path, cause))),
+                }
+
+                if rows.len() == options.rows_per_chunk {
+                    break;
+                }
+            }
+
+            if rows.is_empty() {
+                None
+            } else {
+                Some(parse_csv(&path, rows, options.delimiter, &mut columns))
+            }
+        })
+    }
+
+    /// Load a file of raw, native-endian elements of type `T`, with `columns` elements per row.
+    pub fn binary<P: AsRef<Path>>(
+        path: P,
+        columns: usize,
+        options: Options,
+    ) -> Result<Self, Error> {
+        if columns == 0 {
+            return Err(Error::Bounds(
+                "a row must have at least one column".to_string(),
+            ));
+        }
+
+        let path = path.as_ref().to_path_buf();
+        let file = File::open(&path).map_err(|cause| io_error(&path, cause))?;
+        let mut reader = BufReader::new(file);
+
+        let row_size = columns * mem::size_of::<T>();
+
+        Self::spawn(options, move || {
+            let mut bytes = vec![0u8; row_size * options.rows_per_chunk];
+            let len = match read_full(&mut reader, &mut bytes) {
+                Ok(0) => return None,
+                Ok(len) => len,
+                Err(cause) => return Some(Err(io_error(&path, cause))),
+            };
+
+            if len % row_size != 0 {
+                return Some(Err(Error::Bounds(format!(
+                    "{} ends with a partial row of {} bytes",
+                    path.display(),
+                    len % row_size,
+                ))));
+            }
+
+            bytes.truncate(len);
+
+            let data = bytes
+                .par_chunks_exact(mem::size_of::<T>())
+                .map(|bytes| {
+                    // SAFETY: CType is only implemented for primitive numeric types,
+                    // for which every bit pattern is a valid value
+                    unsafe { std::ptr::read_unaligned(bytes.as_ptr() as *const T) }
+                })
+                .collect::<Vec<T>>();
+
+            Some(ArrayBuf::new(data.into(), shape![len / row_size, columns]))
+        })
+    }
+
+    fn spawn<F>(options: Options, mut next_chunk: F) -> Result<Self, Error>
+    where
+        F: FnMut() -> Option<Result<ArrayBuf<T>, Error>> + Send + 'static,
+    {
+        if options.rows_per_chunk == 0 {
+            return Err(Error::Bounds(
+                "a chunk must have at least one row".to_string(),
+            ));
+        }
+
+        let (sender, chunks) = mpsc::sync_channel(options.prefetch);
+
+        thread::spawn(move || {
+            while let Some(chunk) = next_chunk() {
+                let failed = chunk.is_err();
+
+                // stop reading if the loader has been dropped or the file cannot be parsed
+                if sender.send(chunk).is_err() || failed {
+                    break;
+                }
+            }
+        });
+
+        Ok(Self { chunks })
+    }
+}
+
+impl<T: CType> Iterator for Loader<T> {
+    type Item = Result<ArrayBuf<T>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.chunks.recv().ok()
+    }
+}
+
+fn parse_csv<T: CType>(
+    path: &Path,
+    rows: Vec<(usize, String)>,
+    delimiter: char,
+    columns: &mut Option<usize>,
+) -> Result<ArrayBuf<T>, Error> {
+    let rows = rows
+        .into_par_iter()
+        .map(|(line_num, line)| {
+            line.split(delimiter)
+                .map(|field| {
+                    let number = field.trim().parse::<f64>().map_err(|cause| {
+                        Error::Interface(format!(
+                            "{}, line {line_num}: invalid number {field:?}: {cause}",
+                            path.display()
+                        ))
+                    })?;
+
+                    T::try_from_f64(number).map_err(|cause| {
+                        Error::Interface(format!("{}, line {line_num}: {cause}", path.display()))
+                    })
+                })
+                .collect::<Result<Vec<T>, Error>>()
+                .map(|row| (line_num, row))
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let num_columns = *columns.get_or_insert(rows[0].1.len());
+    let mut data = Vec::with_capacity(rows.len() * num_columns);
+
+    for (line_num, row) in &rows {
+        if row.len() == num_columns {
+            data.extend_from_slice(row);
+        } else {
+            return Err(Error::Bounds(format!(
+                "{}, line {line_num}: expected {num_columns} fields but found {}",
+                path.display(),
+                row.len()
+            )));
+        }
+    }
+
+    ArrayBuf::new(data.into(), shape![rows.len(), num_columns])
+}
+
+fn read_full<R: Read>(reader: &mut R, buffer: &mut [u8]) -> std::io::Result<usize> {
+    let mut len = 0;
+
+    while len < buffer.len() {
+        match reader.read(&mut buffer[len..]) {
+            Ok(0) => break,
+            Ok(read) => len += read,
+            Err(cause) if cause.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(cause) => return Err(cause),
+        }
+    }
+
+    Ok(len)
+}
@@ -0,0 +1,63 @@
+//! Reading and writing arrays from and to files
+
+use std::io::Read;
+use std::mem;
+use std::path::Path;
+
+use crate::{CType, Error};
+
+#[cfg(feature = "zstd")]
+pub mod compress;
+#[cfg(feature = "image")]
+pub mod image;
+pub mod ipc;
+pub mod loader;
+pub mod text;
+
+/// Compute the number of elements of an array with the given `shape`, read from untrusted input,
+/// returning an error instead of overflowing.
+pub(crate) fn checked_size<T>(shape: &[usize]) -> Result<usize, Error> {
+    shape
+        .iter()
+        .try_fold(1usize, |size, dim| size.checked_mul(*dim))
+        .filter(|size| size.checked_mul(std::mem::size_of::<T>()).is_some())
+        .ok_or_else(|| Error::Bounds(format!("an array with shape {shape:?} is too large")))
+}
+
+/// The maximum number of elements to allocate before they are received.
+const READ_CHUNK_SIZE: usize = 1 << 16;
+
+/// Read `size` little-endian elements from `reader`, growing the buffer in chunks as data are
+/// received, so that a corrupt size results in an error rather than a large allocation.
+pub(crate) fn read_elements<T: CType, R: Read>(
+    reader: &mut R,
+    size: usize,
+) -> std::io::Result<Vec<T>> {
+    let mut data = Vec::with_capacity(size.min(READ_CHUNK_SIZE));
+
+    while data.len() < size {
+        let start = data.len();
+        data.resize(size.min(start + READ_CHUNK_SIZE), T::ZERO);
+
+        let chunk = &mut data[start..];
+
+        // SAFETY: every CType is a primitive number, for which every bit pattern is valid
+        let bytes = unsafe {
+            std::slice::from_raw_parts_mut(chunk.as_mut_ptr() as *mut u8, mem::size_of_val(chunk))
+        };
+
+        reader.read_exact(bytes)?;
+
+        if cfg!(target_endian = "big") {
+            bytes
+                .chunks_exact_mut(mem::size_of::<T>())
+                .for_each(<[u8]>::reverse);
+        }
+    }
+
+    Ok(data)
+}
+
+fn io_error(path: &Path, cause: std::io::Error) -> Error {
+    Error::Interface(format!("I/O error on {}: {cause}", path.display()))
+}
@@ -0,0 +1,372 @@
+//! Reading and writing 2-D arrays as CSV or in the MatrixMarket exchange format
+//!
+//! Text is parsed and formatted on the global thread pool in chunks of rows (or lines),
+//! so that only one chunk of text is held in memory at a time. Each value is parsed as an `f64`
+//! and converted to the array's data type with [`CType::try_from_f64`], so a value which is out of
+//! range is an error rather than wrapping around.
+//!
+//! Only the `matrix` object of the MatrixMarket format is supported, with a `real`, `double`,
+//! `integer`, or `pattern` field. A matrix is always read into a dense array,
+//! and written in the dense `array` format.
+
+use std::fmt;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::Path;
+
+use rayon::prelude::*;
+
+use crate::host::ArrayBuf;
+use crate::{CType, Error, NDArray, NDArrayRead};
+
+use super::loader::{Loader, Options};
+
+/// The number of lines to parse, or rows to format, at a time
+const CHUNK_SIZE: usize = 4096;
+
+/// Read a whole CSV file into a single array with shape `[rows, columns]`,
+/// parsing it in chunks of [`Options::rows_per_chunk`] rows with a [`Loader`].
+pub fn read_csv<T: CType, P: AsRef<Path>>(path: P, options: Options) -> Result<ArrayBuf<T>, Error> {
+    let path = path.as_ref();
+    let (mut data, mut shape) = (Vec::new(), [0, 0]);
+
+    for chunk in Loader::<T>::csv(path, options)? {
+        let chunk = chunk?;
+        shape = [shape[0] + chunk.shape()[0], chunk.shape()[1]];
+        data.extend_from_slice(&chunk.buffer()?.to_slice()?);
+    }
+
+    if data.is_empty() {
+        Err(Error::Bounds(format!("{} has no rows", path.display())))
+    } else {
+        ArrayBuf::new(data.into(), shape)
+    }
+}
+
+/// Write the given 2-D `array` to `writer` as CSV with the given `delimiter`, one row per line.
+pub fn write_csv<A, W>(array: &A, writer: &mut W, delimiter: char) -> Result<(), Error>
+where
+    A: NDArrayRead,
+    W: Write,
+{
+    let [_rows, cols] = matrix_dims(array, "CSV")?;
+    let data = array.buffer()?.to_slice()?;
+
+    for chunk in data.chunks(CHUNK_SIZE * cols) {
+        let text = chunk
+            .par_chunks_exact(cols)
+            .map(|row| {
+                let mut line = row
+                    .iter()
+                    .map(|n| n.to_string())
+                    .collect::<Vec<_>>()
+                    .join(&delimiter.to_string());
+
+                line.push('\n');
+                line
+            })
+            .collect::<String>();
+
+        writer.write_all(text.as_bytes()).map_err(text_error)?;
+    }
+
+    Ok(())
+}
+
+/// The field type of a MatrixMarket file
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum Field {
+    Number,
+    Pattern,
+}
+
+/// The symmetry of a MatrixMarket file, which determines which entries are stored
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum Symmetry {
+    General,
+    Symmetric,
+    SkewSymmetric,
+}
+
+/// Read a matrix in the MatrixMarket format from `reader` into a dense array.
+///
+/// The entries of a `coordinate` matrix which appear more than once are summed,
+/// and the missing triangle of a `symmetric` or `skew-symmetric` matrix is filled in.
+pub fn read_mtx<T: CType, R: Read>(reader: R) -> Result<ArrayBuf<T>, Error> {
+    let mut lines = BufReader::new(reader).lines().enumerate();
+
+    let banner = match lines.next() {
+        Some((_, line)) => line.map_err(text_error)?,
+        None => return Err(mtx_error(1, "missing the %%MatrixMarket banner")),
+    };
+
+    let banner = banner.to_lowercase();
+    let (coordinate, field, symmetry) = match banner.split_whitespace().collect::<Vec<_>>()[..] {
+        ["%%matrixmarket", "matrix", format, field, symmetry] => {
+            let coordinate = match format {
+                "coordinate" => true,
+                "array" => false,
+                other => return Err(mtx_error(1, format!("unknown format {other:?}"))),
+            };
+
+            let field = match field {
+                "real" | "double" | "integer" => Field::Number,
+                "pattern" if coordinate => Field::Pattern,
+                other => {
+                    return Err(Error::Unsupported(format!(
+                        "MatrixMarket field {other:?} is not supported"
+                    )))
+                }
+            };
+
+            let symmetry = match symmetry {
+                "general" => Symmetry::General,
+                // a real Hermitian matrix is symmetric
+                "symmetric" | "hermitian" => Symmetry::Symmetric,
+                "skew-symmetric" => Symmetry::SkewSymmetric,
+                other => return Err(mtx_error(1, format!("unknown symmetry {other:?}"))),
+            };
+
+            (coordinate, field, symmetry)
+        }
+        _ => return Err(mtx_error(1, "invalid %%MatrixMarket banner")),
+    };
+
+    // comments and blank lines may appear anywhere after the banner
+    let mut lines = lines.filter_map(|(i, line)| match line {
+        Ok(line) if line.trim().is_empty() || line.starts_with('%') => None,
+        Ok(line) => Some(Ok((i + 1, line))),
+        Err(cause) => Some(Err(text_error(cause))),
+    });
+
+    let (size_line, size) = match lines.next() {
+        Some(line) => line?,
+        None => return Err(mtx_error(2, "missing the size line")),
+    };
+
+    let size = size
+        .split_whitespace()
+        .map(|n| n.parse::<usize>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|cause| mtx_error(size_line, format!("invalid size: {cause}")))?;
+
+    let (rows, cols, nnz) = match (coordinate, &size[..]) {
+        (true, [rows, cols, nnz]) => (*rows, *cols, Some(*nnz)),
+        (false, [rows, cols]) => (*rows, *cols, None),
+        _ => return Err(mtx_error(size_line, "wrong number of dimensions")),
+    };
+
+    if rows == 0 || cols == 0 || (symmetry != Symmetry::General && rows != cols) {
+        return Err(mtx_error(
+            size_line,
+            format!("invalid dimensions {rows}x{cols}"),
+        ));
+    }
+
+    if symmetry == Symmetry::SkewSymmetric && T::sub(T::ZERO, T::ONE) > T::ZERO {
+        return Err(Error::Unsupported(format!(
+            "cannot read a skew-symmetric matrix into unsigned type {}",
+            T::TYPE
+        )));
+    }
+
+    let size =
+        super::checked_size::<T>(&[rows, cols]).map_err(|cause| mtx_error(size_line, cause))?;
+
+    let mut data = Vec::new();
+    data.try_reserve_exact(size).map_err(|cause| {
+        mtx_error(
+            size_line,
+            format!("cannot allocate a {rows}x{cols} matrix: {cause}"),
+        )
+    })?;
+
+    data.resize(size, T::ZERO);
+
+    let len = nnz.unwrap_or_else(|| stored_len(rows, cols, symmetry));
+    let mut count = 0;
+
+    loop {
+        let chunk = lines
+            .by_ref()
+            .take(CHUNK_SIZE)
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        if chunk.is_empty() {
+            break;
+        } else if count + chunk.len() > len {
+            return Err(mtx_error(chunk[len - count].0, "too many entries"));
+        }
+
+        let entries = chunk
+            .into_par_iter()
+            .enumerate()
+            .map(|(i, (line_num, line))| {
+                if coordinate {
+                    parse_coordinate(&line, field, [rows, cols])
+                } else {
+                    let (i, j) = array_position(count + i, rows, symmetry);
+                    parse_value(line.trim()).map(|value| (i, j, value))
+                }
+                .map_err(|cause| mtx_error(line_num, cause))
+            })
+            .collect::<Result<Vec<(usize, usize, T)>, Error>>()?;
+
+        count += entries.len();
+
+        for (i, j, value) in entries {
+            data[i * cols + j] = T::add(data[i * cols + j], value);
+
+            if i != j {
+                match symmetry {
+                    Symmetry::General => {}
+                    Symmetry::Symmetric => data[j * cols + i] = T::add(data[j * cols + i], value),
+                    Symmetry::SkewSymmetric => {
+                        data[j * cols + i] = T::sub(data[j * cols + i], value)
+                    }
+                }
+            }
+        }
+    }
+
+    if count == len {
+        ArrayBuf::new(data.into(), [rows, cols])
+    } else {
+        Err(Error::Bounds(format!(
+            "MatrixMarket matrix has {count} entries but expected {len}"
+        )))
+    }
+}
+
+/// Write the given 2-D `array` to `writer` in the dense MatrixMarket `array` format,
+/// with an `integer` field for an integer type and a `real` field otherwise.
+pub fn write_mtx<A, W>(array: &A, writer: &mut W) -> Result<(), Error>
+where
+    A: NDArrayRead,
+    W: Write,
+{
+    let [rows, cols] = matrix_dims(array, "MatrixMarket")?;
+    let data = array.buffer()?.to_slice()?;
+
+    let field = if A::DType::IS_FLOAT {
+        "real"
+    } else {
+        "integer"
+    };
+
+    let header = format!("%%MatrixMarket matrix array {field} general\n{rows} {cols}\n");
+    writer.write_all(header.as_bytes()).map_err(text_error)?;
+
+    // the array format is column-major
+    for start in (0..cols).step_by(CHUNK_SIZE.div_ceil(rows)) {
+        let end = (start + CHUNK_SIZE.div_ceil(rows)).min(cols);
+
+        let text = (start..end)
+            .into_par_iter()
+            .map(|j| {
+                (0..rows)
+                    .map(|i| format!("{}\n", data[i * cols + j]))
+                    .collect::<String>()
+            })
+            .collect::<String>();
+
+        writer.write_all(text.as_bytes()).map_err(text_error)?;
+    }
+
+    Ok(())
+}
+
+/// The number of entries stored in the `array` format of a matrix with the given `symmetry`.
+fn stored_len(rows: usize, cols: usize, symmetry: Symmetry) -> usize {
+    match symmetry {
+        Symmetry::General => rows * cols,
+        Symmetry::Symmetric => rows * (rows + 1) / 2,
+        Symmetry::SkewSymmetric => rows * (rows - 1) / 2,
+    }
+}
+
+/// The position of the `n`th entry stored in the `array` format, which lists the lower triangle
+/// (excluding the diagonal, if skew-symmetric) of a matrix with a symmetry in column-major order.
+fn array_position(mut n: usize, rows: usize, symmetry: Symmetry) -> (usize, usize) {
+    let skip = match symmetry {
+        Symmetry::General => return (n % rows, n / rows),
+        Symmetry::Symmetric => 0,
+        Symmetry::SkewSymmetric => 1,
+    };
+
+    for j in 0.. {
+        let len = rows - j - skip;
+
+        if n < len {
+            return (j + skip + n, j);
+        }
+
+        n -= len;
+    }
+
+    unreachable!("the entry at offset {n} is past the end of the matrix")
+}
+
+fn parse_coordinate<T: CType>(
+    line: &str,
+    field: Field,
+    dims: [usize; 2],
+) -> Result<(usize, usize, T), String> {
+    let fields = line.split_whitespace().collect::<Vec<_>>();
+
+    let (i, j, value) = match (field, &fields[..]) {
+        (Field::Number, [i, j, value]) => (i, j, parse_value(value)?),
+        (Field::Pattern, [i, j]) => (i, j, T::ONE),
+        _ => return Err(format!("invalid entry {line:?}")),
+    };
+
+    let index = |index: &str, dim: usize| match index.parse::<usize>() {
+        Ok(index) if index > 0 && index <= dim => Ok(index - 1),
+        _ => Err(format!(
+            "index {index:?} is out of bounds for dimension {dim}"
+        )),
+    };
+
+    Ok((index(i, dims[0])?, index(j, dims[1])?, value))
+}
+
+fn parse_value<T: CType>(value: &str) -> Result<T, String> {
+    // an f64 cannot represent every i64 or u64, so parse an integer directly
+    if !T::IS_FLOAT {
+        if let Ok(int) = value.parse::<i128>() {
+            // the cast keeps the low bits of the integer, so it round-trips only if in range
+            let n = T::from_i64(int as i64);
+
+            return if n.to_string() == int.to_string() {
+                Ok(n)
+            } else {
+                Err(format!("{int} is out of range for {}", T::TYPE))
+            };
+        }
+    }
+
+    let number = value
+        .parse::<f64>()
+        .map_err(|cause| format!("invalid number {value:?}: {cause}"))?;
+
+    T::try_from_f64(number).map_err(|cause| cause.to_string())
+}
+
+fn matrix_dims<A: NDArrayRead>(array: &A, format: &str) -> Result<[usize; 2], Error> {
+    match array.shape() {
+        [rows, cols] if *rows > 0 && *cols > 0 => Ok([*rows, *cols]),
+        [rows, cols] => Err(Error::Bounds(format!(
+            "cannot write an empty {rows}x{cols} matrix as {format}"
+        ))),
+        shape => Err(Error::Bounds(format!(
+            "cannot write an array with shape {shape:?} as {format}, which requires a matrix"
+        ))),
+    }
+}
+
+fn mtx_error<M: fmt::Display>(line_num: usize, cause: M) -> Error {
+    Error::Interface(format!("MatrixMarket line {line_num}: {cause}"))
+}
+
+fn text_error(cause: std::io::Error) -> Error {
+    Error::Interface(format!("text I/O error: {cause}"))
+}
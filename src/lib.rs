@@ -3,7 +3,6 @@ use std::fmt;
 use std::ops::{Add, Div, Mul, Rem, Sub};
 
 pub use smallvec::smallvec as axes;
-pub use smallvec::smallvec as range;
 pub use smallvec::smallvec as slice;
 pub use smallvec::smallvec as shape;
 pub use smallvec::smallvec as stackvec;
@@ -11,23 +10,58 @@ use smallvec::SmallVec;
 
 pub use access::*;
 pub use array::{
-    MatrixDual, MatrixUnary, NDArray, NDArrayBoolean, NDArrayBooleanScalar, NDArrayCast,
-    NDArrayCompare, NDArrayCompareScalar, NDArrayMath, NDArrayMathScalar, NDArrayNumeric,
-    NDArrayRead, NDArrayReduce, NDArrayReduceAll, NDArrayReduceBoolean, NDArrayTransform,
-    NDArrayTrig, NDArrayUnary, NDArrayUnaryBoolean, NDArrayWhere, NDArrayWrite,
+    Chunks, Element, IndexedReduce, MatrixDecompose, MatrixDual, MatrixSolve, MatrixUnary, NDArray,
+    NDArrayBoolean, NDArrayBooleanScalar, NDArrayCast, NDArrayCompare, NDArrayCompareScalar,
+    NDArrayConv, NDArrayGather, NDArrayMath, NDArrayMathScalar, NDArrayNormalize, NDArrayNumeric,
+    NDArrayRead, NDArrayReduce, NDArrayReduceAll, NDArrayReduceBoolean, NDArrayScan, NDArraySort,
+    NDArrayTernary, NDArrayTransform, NDArrayTrig, NDArrayTrigDual, NDArrayUnary,
+    NDArrayUnaryBoolean, NDArrayUpsample, NDArrayWhere, NDArrayWrite,
 };
 pub use buffer::{Buffer, BufferConverter, BufferInstance, BufferMut};
+pub use conv::{ConvSpec, Interpolation};
+pub use einsum::{einsum, tensordot};
+pub use graph::{execute, Graph, GraphDef, GraphNode, Placeholder};
+pub use group::{group_aggregate, inner_join, Aggregate};
+pub use handle::ArrayHandle;
 pub use host::StackVec;
+pub use mask::Bool;
+pub use meta::ArrayMeta;
 pub use platform::*;
+pub use ragged::{RaggedArray, RaggedPad, RaggedReduce};
+pub use sparse::{CsrMatrix, CsrProduct};
+pub use stats::{StatsAccumulator, Summary, UlpDiff};
+pub use stream::reduce_stream;
+pub use warmup::{warmup, OpKind};
 
 mod access;
 mod array;
+pub mod backend;
+#[cfg(feature = "bench")]
+pub mod bench;
 mod buffer;
+pub mod complex;
+pub mod config;
+mod conv;
+mod einsum;
+pub mod fixed;
+mod graph;
+mod group;
+mod handle;
 pub mod host;
+pub mod introspect;
+pub mod io;
+mod mask;
+mod meta;
 #[cfg(feature = "opencl")]
 pub mod opencl;
 pub mod ops;
 mod platform;
+pub mod prelude;
+mod ragged;
+mod sparse;
+mod stats;
+mod stream;
+mod warmup;
 
 /// A numeric type supported by ha-ndarray
 #[cfg(feature = "opencl")]
@@ -65,6 +99,16 @@ pub trait CType:
     /// Construct an instance of this type from an instance of its floating-point type.
     fn from_float(float: Self::Float) -> Self;
 
+    /// Construct an instance of this type from a [`usize`], like an `as` cast.
+    fn from_usize(n: usize) -> Self;
+
+    /// Construct an instance of this type from an [`i64`], like an `as` cast.
+    fn from_i64(n: i64) -> Self;
+
+    /// Construct an instance of this type from a [`f64`], truncating toward zero if this is an
+    /// integer type, or return an error if the value is out of range (or NaN, for an integer).
+    fn try_from_f64(float: f64) -> Result<Self, Error>;
+
     // arithmetic
 
     /// Construct an instance of this type from a [`f64`].
@@ -79,6 +123,10 @@ pub trait CType:
     /// Multiply two instances of this type.
     fn mul(self, other: Self) -> Self;
 
+    /// Compute `(self * mul) + add`, with a single rounding step if this is a floating-point type
+    /// and the target supports fused multiply-add instructions.
+    fn mul_add(self, mul: Self, add: Self) -> Self;
+
     /// Subtract two instances of this type.
     fn sub(self, other: Self) -> Self;
 
@@ -87,10 +135,10 @@ pub trait CType:
 
     // comparisons
 
-    /// Return the minimum of two values of this type.
+    /// Return the minimum of two values of this type, or NaN if either is NaN.
     fn min(l: Self, r: Self) -> Self;
 
-    /// Return the maximum of two values of this type.
+    /// Return the maximum of two values of this type, or NaN if either is NaN.
     fn max(l: Self, r: Self) -> Self;
 
     // logarithms
@@ -146,6 +194,16 @@ pub trait CType:
     /// Construct an instance of this type from an instance of its floating-point type.
     fn from_float(float: Self::Float) -> Self;
 
+    /// Construct an instance of this type from a [`usize`], like an `as` cast.
+    fn from_usize(n: usize) -> Self;
+
+    /// Construct an instance of this type from an [`i64`], like an `as` cast.
+    fn from_i64(n: i64) -> Self;
+
+    /// Construct an instance of this type from a [`f64`], truncating toward zero if this is an
+    /// integer type, or return an error if the value is out of range (or NaN, for an integer).
+    fn try_from_f64(float: f64) -> Result<Self, Error>;
+
     // arithmetic
 
     /// Construct an instance of this type from a [`f64`].
@@ -160,6 +218,10 @@ pub trait CType:
     /// Multiply two instances of this type.
     fn mul(self, other: Self) -> Self;
 
+    /// Compute `(self * mul) + add`, with a single rounding step if this is a floating-point type
+    /// and the target supports fused multiply-add instructions.
+    fn mul_add(self, mul: Self, add: Self) -> Self;
+
     /// Subtract two instances of this type.
     fn sub(self, other: Self) -> Self;
 
@@ -168,10 +230,10 @@ pub trait CType:
 
     // comparisons
 
-    /// Return the minimum of two values of this type.
+    /// Return the minimum of two values of this type, or NaN if either is NaN.
     fn min(l: Self, r: Self) -> Self;
 
-    /// Return the maximum of two values of this type.
+    /// Return the maximum of two values of this type, or NaN if either is NaN.
     fn max(l: Self, r: Self) -> Self;
 
     // logarithms
@@ -192,7 +254,7 @@ pub trait CType:
 }
 
 macro_rules! c_type {
-    ($t:ty, $str:expr, $is_float:expr, $one:expr, $zero:expr, $float:ty, $abs:expr, $add:expr, $div:expr, $mul:expr, $sub:expr, $rem:expr, $round:expr, $pow:expr, $cmp_max:expr, $cmp_min:expr) => {
+    ($t:ty, $str:expr, $is_float:expr, $one:expr, $zero:expr, $float:ty, $abs:expr, $add:expr, $div:expr, $mul:expr, $mul_add:expr, $sub:expr, $rem:expr, $round:expr, $pow:expr, $cmp_max:expr, $cmp_min:expr, $try_from_f64:expr) => {
         impl CType for $t {
             const TYPE: &'static str = $str;
 
@@ -216,6 +278,18 @@ macro_rules! c_type {
                 float as $t
             }
 
+            fn from_usize(n: usize) -> Self {
+                n as $t
+            }
+
+            fn from_i64(n: i64) -> Self {
+                n as $t
+            }
+
+            fn try_from_f64(float: f64) -> Result<Self, Error> {
+                $try_from_f64(float)
+            }
+
             fn abs(self) -> Self {
                 $abs(self)
             }
@@ -232,6 +306,10 @@ macro_rules! c_type {
                 $mul(self, other)
             }
 
+            fn mul_add(self, mul: Self, add: Self) -> Self {
+                ($mul_add)(self, mul, add)
+            }
+
             fn sub(self, other: Self) -> Self {
                 $sub(self, other)
             }
@@ -278,12 +356,14 @@ c_type!(
     Add::add,
     Div::div,
     Mul::mul,
+    fma_f32,
     Sub::sub,
     Rem::rem,
     f32::round,
     f32::powf,
     max_f32,
-    min_f32
+    min_f32,
+    try_f32
 );
 
 c_type!(
@@ -297,12 +377,14 @@ c_type!(
     Add::add,
     Div::div,
     Mul::mul,
+    fma_f64,
     Sub::sub,
     Rem::rem,
     f64::round,
     f64::powf,
     max_f64,
-    min_f64
+    min_f64,
+    Ok
 );
 
 c_type!(
@@ -316,12 +398,14 @@ c_type!(
     Self::wrapping_add,
     |l, r| if r == 0 { 0 } else { Self::wrapping_div(l, r) },
     Self::wrapping_mul,
+    |n, mul, add| Self::wrapping_add(Self::wrapping_mul(n, mul), add),
     Self::wrapping_sub,
     Self::wrapping_rem,
     id,
     |a, e| f32::powi(a as f32, e as i32) as i8,
     Ord::max,
-    Ord::min
+    Ord::min,
+    try_int
 );
 
 c_type!(
@@ -335,12 +419,14 @@ c_type!(
     Self::wrapping_add,
     |l, r| if r == 0 { 0 } else { Self::wrapping_div(l, r) },
     Self::wrapping_mul,
+    |n, mul, add| Self::wrapping_add(Self::wrapping_mul(n, mul), add),
     Self::wrapping_sub,
     Self::wrapping_rem,
     id,
     |a, e| f32::powi(a as f32, e as i32) as i16,
     Ord::max,
-    Ord::min
+    Ord::min,
+    try_int
 );
 
 c_type!(
@@ -354,12 +440,14 @@ c_type!(
     Self::wrapping_add,
     |l, r| if r == 0 { 0 } else { Self::wrapping_div(l, r) },
     Self::wrapping_mul,
+    |n, mul, add| Self::wrapping_add(Self::wrapping_mul(n, mul), add),
     Self::wrapping_sub,
     Self::wrapping_rem,
     id,
     |a, e| f32::powi(a as f32, e) as i32,
     Ord::max,
-    Ord::min
+    Ord::min,
+    try_int
 );
 
 c_type!(
@@ -373,6 +461,7 @@ c_type!(
     Self::wrapping_add,
     |l, r| if r == 0 { 0 } else { Self::wrapping_div(l, r) },
     Self::wrapping_mul,
+    |n, mul, add| Self::wrapping_add(Self::wrapping_mul(n, mul), add),
     Self::wrapping_sub,
     Self::wrapping_rem,
     id,
@@ -381,7 +470,8 @@ c_type!(
         i32::try_from(e).unwrap_or_else(|_| if e >= 0 { i32::MAX } else { i32::MIN })
     ) as i64,
     Ord::max,
-    Ord::min
+    Ord::min,
+    try_int
 );
 
 c_type!(
@@ -395,12 +485,14 @@ c_type!(
     Self::wrapping_add,
     |l, r| if r == 0 { 0 } else { Self::wrapping_div(l, r) },
     Self::wrapping_mul,
+    |n, mul, add| Self::wrapping_add(Self::wrapping_mul(n, mul), add),
     Self::wrapping_sub,
     Self::wrapping_rem,
     id,
     |a, e| u8::pow(a, e as u32),
     Ord::max,
-    Ord::min
+    Ord::min,
+    try_int
 );
 
 c_type!(
@@ -414,12 +506,14 @@ c_type!(
     Self::wrapping_add,
     |l, r| if r == 0 { 0 } else { Self::wrapping_div(l, r) },
     Self::wrapping_mul,
+    |n, mul, add| Self::wrapping_add(Self::wrapping_mul(n, mul), add),
     Self::wrapping_sub,
     Self::wrapping_rem,
     id,
     |a, e| u16::pow(a, e as u32),
     Ord::max,
-    Ord::min
+    Ord::min,
+    try_int
 );
 
 c_type!(
@@ -433,12 +527,14 @@ c_type!(
     Self::wrapping_add,
     |l, r| if r == 0 { 0 } else { Self::wrapping_div(l, r) },
     Self::wrapping_mul,
+    |n, mul, add| Self::wrapping_add(Self::wrapping_mul(n, mul), add),
     Self::wrapping_sub,
     Self::wrapping_rem,
     id,
     |a, e| u32::pow(a, e),
     Ord::max,
-    Ord::min
+    Ord::min,
+    try_int
 );
 
 c_type!(
@@ -452,47 +548,110 @@ c_type!(
     Self::wrapping_add,
     |l, r| if r == 0 { 0 } else { Self::wrapping_div(l, r) },
     Self::wrapping_mul,
+    |n, mul, add| Self::wrapping_add(Self::wrapping_mul(n, mul), add),
     Self::wrapping_sub,
     Self::wrapping_rem,
     id,
     |a, e| u64::pow(a, u32::try_from(e).unwrap_or(u32::MAX)),
     Ord::max,
-    Ord::min
+    Ord::min,
+    try_int
 );
 
 fn id<T>(this: T) -> T {
     this
 }
 
+// without a hardware instruction, a fused multiply-add is computed in software, which is slow
+fn fma_f32(n: f32, mul: f32, add: f32) -> f32 {
+    if cfg!(target_feature = "fma") {
+        n.mul_add(mul, add)
+    } else {
+        (n * mul) + add
+    }
+}
+
+fn fma_f64(n: f64, mul: f64, add: f64) -> f64 {
+    if cfg!(target_feature = "fma") {
+        n.mul_add(mul, add)
+    } else {
+        (n * mul) + add
+    }
+}
+
+fn try_f32(float: f64) -> Result<f32, Error> {
+    if float.is_finite() && float.abs() > f32::MAX as f64 {
+        Err(out_of_range::<f32>(float))
+    } else {
+        Ok(float as f32)
+    }
+}
+
+fn try_int<T: CType + TryFrom<i128>>(float: f64) -> Result<T, Error> {
+    // a finite f64 too large for an i128 saturates, which is out of range of every CType anyway
+    Some(float)
+        .filter(|float| float.is_finite())
+        .and_then(|float| T::try_from(float.trunc() as i128).ok())
+        .ok_or_else(|| out_of_range::<T>(float))
+}
+
+fn out_of_range<T: CType>(float: f64) -> Error {
+    Error::Bounds(format!("{float} is out of range for {}", T::TYPE))
+}
+
 fn max_f32(l: f32, r: f32) -> f32 {
-    match l.total_cmp(&r) {
-        Ordering::Less => r,
-        Ordering::Equal => l,
-        Ordering::Greater => l,
+    if l.is_nan() {
+        l
+    } else if r.is_nan() {
+        r
+    } else {
+        match l.total_cmp(&r) {
+            Ordering::Less => r,
+            Ordering::Equal => l,
+            Ordering::Greater => l,
+        }
     }
 }
 
 fn min_f32(l: f32, r: f32) -> f32 {
-    match l.total_cmp(&r) {
-        Ordering::Less => l,
-        Ordering::Equal => l,
-        Ordering::Greater => r,
+    if l.is_nan() {
+        l
+    } else if r.is_nan() {
+        r
+    } else {
+        match l.total_cmp(&r) {
+            Ordering::Less => l,
+            Ordering::Equal => l,
+            Ordering::Greater => r,
+        }
     }
 }
 
 fn max_f64(l: f64, r: f64) -> f64 {
-    match l.total_cmp(&r) {
-        Ordering::Less => r,
-        Ordering::Equal => l,
-        Ordering::Greater => l,
+    if l.is_nan() {
+        l
+    } else if r.is_nan() {
+        r
+    } else {
+        match l.total_cmp(&r) {
+            Ordering::Less => r,
+            Ordering::Equal => l,
+            Ordering::Greater => l,
+        }
     }
 }
 
 fn min_f64(l: f64, r: f64) -> f64 {
-    match l.total_cmp(&r) {
-        Ordering::Less => l,
-        Ordering::Equal => l,
-        Ordering::Greater => r,
+    if l.is_nan() {
+        l
+    } else if r.is_nan() {
+        r
+    } else {
+        match l.total_cmp(&r) {
+            Ordering::Less => l,
+            Ordering::Equal => l,
+            Ordering::Greater => r,
+        }
     }
 }
 
@@ -515,6 +674,15 @@ pub trait Float: CType<Float = Self> {
     /// Calculate the logarithm of this [`Float`] w/r/t the given `base`.
     fn log(self, base: Self) -> Self;
 
+    /// Return `ln(1 + self)`, more accurately than if the operations were performed separately.
+    fn ln_1p(self) -> Self;
+
+    /// Return `ln(1 + e^self)` without overflowing for large values.
+    fn softplus(self) -> Self;
+
+    /// Return `ln(e^self + e^other)` without overflowing for large values.
+    fn logaddexp(self, other: Self) -> Self;
+
     // trigonometry
     /// Return the sine of this [`Float`] (in radians).
     fn sin(self) -> Self;
@@ -540,16 +708,32 @@ pub trait Float: CType<Float = Self> {
     /// Return the arctangent of this [`Float`] (in radians).
     fn atan(self) -> Self;
 
+    /// Return the four-quadrant arctangent of `self` (y) and `other` (x) (in radians).
+    fn atan2(self, other: Self) -> Self;
+
     /// Return the hyperbolic tangent of this [`Float`] (in radians).
     fn tanh(self) -> Self;
 
+    /// Return the length of the hypotenuse of a right triangle with sides `self` and `other`.
+    fn hypot(self, other: Self) -> Self;
+
+    /// Convert this [`Float`] from radians to degrees.
+    fn to_degrees(self) -> Self;
+
+    /// Convert this [`Float`] from degrees to radians.
+    fn to_radians(self) -> Self;
+
     // utility
     /// Cast this [`Float`] to an [`f64`].
     fn to_f64(self) -> f64;
+
+    /// Return the bits of this [`Float`] as an integer with the same ordering,
+    /// such that adjacent values differ by exactly one (and `0.0 == -0.0`).
+    fn to_ordered_bits(self) -> i64;
 }
 
 macro_rules! float_type {
-    ($t:ty) => {
+    ($t:ty, $i:ty) => {
         impl Float for $t {
             fn is_inf(self) -> bool {
                 <$t>::is_infinite(self)
@@ -571,6 +755,32 @@ macro_rules! float_type {
                 <$t>::log(self, base)
             }
 
+            fn ln_1p(self) -> Self {
+                <$t>::ln_1p(self)
+            }
+
+            fn softplus(self) -> Self {
+                if self > 0. {
+                    self + (-self).exp().ln_1p()
+                } else {
+                    self.exp().ln_1p()
+                }
+            }
+
+            fn logaddexp(self, other: Self) -> Self {
+                if self.is_nan() || other.is_nan() {
+                    self + other
+                } else {
+                    let max = <$t>::max(self, other);
+
+                    if max.is_infinite() {
+                        max
+                    } else {
+                        max + (-(self - other).abs()).exp().ln_1p()
+                    }
+                }
+            }
+
             fn sin(self) -> Self {
                 <$t>::sin(self)
             }
@@ -603,19 +813,45 @@ macro_rules! float_type {
                 <$t>::atan(self)
             }
 
+            fn atan2(self, other: Self) -> Self {
+                <$t>::atan2(self, other)
+            }
+
             fn tanh(self) -> Self {
                 <$t>::tanh(self)
             }
 
+            fn hypot(self, other: Self) -> Self {
+                <$t>::hypot(self, other)
+            }
+
+            fn to_degrees(self) -> Self {
+                <$t>::to_degrees(self)
+            }
+
+            fn to_radians(self) -> Self {
+                <$t>::to_radians(self)
+            }
+
             fn to_f64(self) -> f64 {
                 self as f64
             }
+
+            fn to_ordered_bits(self) -> i64 {
+                let bits = self.to_bits() as $i;
+
+                if bits < 0 {
+                    <$i>::MIN.wrapping_sub(bits) as i64
+                } else {
+                    bits as i64
+                }
+            }
         }
     };
 }
 
-float_type!(f32);
-float_type!(f64);
+float_type!(f32, i32);
+float_type!(f64, i64);
 
 /// An array math error
 pub enum Error {
@@ -626,6 +862,19 @@ pub enum Error {
     OCL(std::sync::Arc<ocl::Error>),
 }
 
+impl Error {
+    /// Append a line of `context`, such as the op chain which produced an array, to this error.
+    pub fn with_context<C: fmt::Display>(self, context: C) -> Self {
+        match self {
+            Self::Bounds(msg) => Self::Bounds(format!("{msg}\n  {context}")),
+            Self::Interface(msg) => Self::Interface(format!("{msg}\n  {context}")),
+            Self::Unsupported(msg) => Self::Unsupported(format!("{msg}\n  {context}")),
+            #[cfg(feature = "opencl")]
+            Self::OCL(cause) => Self::OCL(cause),
+        }
+    }
+}
+
 // Clone is required to support memoizing OpenCL programs
 // since constructing an [`ocl::Program`] may return an error
 impl Clone for Error {
@@ -640,17 +889,44 @@ impl Clone for Error {
     }
 }
 
+#[cfg(feature = "opencl")]
+impl Error {
+    /// Return `true` if this error was caused by a failure to allocate OpenCL memory.
+    pub(crate) fn is_out_of_memory(&self) -> bool {
+        match self {
+            Self::OCL(cause) => out_of_memory(cause),
+            _ => false,
+        }
+    }
+}
+
 #[cfg(feature = "opencl")]
 impl From<ocl::Error> for Error {
     fn from(cause: ocl::Error) -> Self {
+        // running out of device memory is recoverable, per the configured OOM policy
         #[cfg(debug_assertions)]
-        panic!("OpenCL error: {:?}", cause);
+        if !out_of_memory(&cause) {
+            panic!("OpenCL error: {:?}", cause);
+        }
 
-        #[cfg(not(debug_assertions))]
         Self::OCL(std::sync::Arc::new(cause))
     }
 }
 
+#[cfg(feature = "opencl")]
+fn out_of_memory(cause: &ocl::Error) -> bool {
+    use ocl::core::Status;
+
+    matches!(
+        cause.api_status(),
+        Some(
+            Status::CL_MEM_OBJECT_ALLOCATION_FAILURE
+                | Status::CL_OUT_OF_RESOURCES
+                | Status::CL_OUT_OF_HOST_MEMORY
+        )
+    )
+}
+
 impl fmt::Debug for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -689,6 +965,75 @@ pub type Shape = SmallVec<[usize; 8]>;
 /// The strides used to access an n-dimensional array
 pub type Strides = SmallVec<[usize; 8]>;
 
+macro_rules! into_dims {
+    ($name:ident, $method:ident, $t:ty, $what:literal) => {
+        #[doc = concat!("A list of ", $what, " which can be converted into [`", stringify!($t), "`],")]
+        #[doc = concat!("so that a caller can pass a `Vec` or array instead of a [`", stringify!($t), "`].")]
+        pub trait $name {
+            #[doc = concat!("Convert this list of ", $what, " into [`", stringify!($t), "`].")]
+            fn $method(self) -> $t;
+        }
+
+        impl $name for SmallVec<[usize; 8]> {
+            fn $method(self) -> $t {
+                self
+            }
+        }
+
+        impl $name for Vec<usize> {
+            fn $method(self) -> $t {
+                self.into()
+            }
+        }
+
+        impl<const N: usize> $name for [usize; N] {
+            fn $method(self) -> $t {
+                self.into_iter().collect()
+            }
+        }
+
+        impl $name for &[usize] {
+            fn $method(self) -> $t {
+                self.into()
+            }
+        }
+    };
+}
+
+into_dims!(IntoShape, into_shape, Shape, "dimensions");
+into_dims!(IntoAxes, into_axes, Axes, "axes");
+
+/// A list of bounds which can be converted into a [`Range`],
+/// so that a caller can pass a `Vec` or array of bounds instead of a [`Range`].
+pub trait IntoRange {
+    /// Convert this list of bounds into a [`Range`].
+    fn into_range(self) -> Range;
+}
+
+impl IntoRange for Range {
+    fn into_range(self) -> Range {
+        self
+    }
+}
+
+impl<B: Into<AxisRange>> IntoRange for Vec<B> {
+    fn into_range(self) -> Range {
+        self.into_iter().map(B::into).collect()
+    }
+}
+
+impl<B: Into<AxisRange>, const N: usize> IntoRange for [B; N] {
+    fn into_range(self) -> Range {
+        self.into_iter().map(B::into).collect()
+    }
+}
+
+impl<B: Into<AxisRange> + Clone> IntoRange for &[B] {
+    fn into_range(self) -> Range {
+        self.iter().cloned().map(B::into).collect()
+    }
+}
+
 /// An n-dimensional array on the top-level [`Platform`]
 pub type Array<T, A> = array::Array<T, A, Platform>;
 
@@ -704,30 +1049,70 @@ pub type ArrayAccess<T> = array::Array<T, Accessor<T>, Platform>;
 /// An accessor for the result of an n-dimensional array operation on the top-level [`Platform`]
 pub type AccessOp<Op> = access::AccessOp<Op, Platform>;
 
+/// Construct a [`Range`] from a list of bounds, each of which is converted into an [`AxisRange`].
+///
+/// Bounds may be indices, `..`, `a..b`, `a..=b`, `a..`, `..b`, or `(a..b).step_by(step)`,
+/// and negative indices count back from the end of their axis, e.g. `range![-1, 1.., ..]`.
+#[macro_export]
+macro_rules! range {
+    ($($bound:expr),* $(,)?) => {
+        <$crate::Range as ::std::iter::FromIterator<$crate::AxisRange>>::from_iter([
+            $($crate::AxisRange::from($bound)),*
+        ])
+    };
+}
+
 /// Bounds on an individual array axis
 #[derive(Clone, Eq, PartialEq, Hash)]
 pub enum AxisRange {
     At(usize),
     In(usize, usize, usize),
     Of(SmallVec<[usize; 8]>),
+    /// An index which counts back from the end of the axis if negative
+    Index(isize),
+    /// A range whose bounds count back from the end of the axis if negative,
+    /// or default to the start and end of the axis if `None`
+    Span(Option<isize>, Option<isize>, usize),
 }
 
 impl AxisRange {
     /// Return `true` if this is an index bound (i.e. not a slice)
     pub fn is_index(&self) -> bool {
         match self {
-            Self::At(_) => true,
+            Self::At(_) | Self::Index(_) => true,
             _ => false,
         }
     }
 
     /// Return the number of elements contained within this bound.
-    /// Returns `None` for an index bound.
+    /// Returns `None` for an index bound, or a bound which has not been resolved.
     pub fn size(&self) -> Option<usize> {
         match self {
-            Self::At(_) => None,
-            Self::In(start, stop, step) => Some((stop - start) / step),
+            Self::At(_) | Self::Index(_) => None,
+            Self::In(start, stop, step) => Some((stop - start).div_ceil(*step)),
             Self::Of(indices) => Some(indices.len()),
+            Self::Span(..) => None,
+        }
+    }
+
+    /// Resolve any negative or open bounds of this [`AxisRange`] against an axis of length `dim`.
+    pub fn resolve(self, dim: usize) -> Result<Self, Error> {
+        let offset = |i: isize| {
+            let resolved = if i < 0 { dim as isize + i } else { i };
+
+            usize::try_from(resolved).map_err(|_| {
+                Error::Bounds(format!("index {i} is out of bounds for dimension {dim}"))
+            })
+        };
+
+        match self {
+            Self::Index(i) => offset(i).map(Self::At),
+            Self::Span(start, stop, step) => {
+                let start = start.map(offset).transpose()?.unwrap_or(0);
+                let stop = stop.map(offset).transpose()?.unwrap_or(dim);
+                Ok(Self::In(start, stop, step))
+            }
+            resolved => Ok(resolved),
         }
     }
 }
@@ -744,12 +1129,139 @@ impl From<std::ops::Range<usize>> for AxisRange {
     }
 }
 
+impl From<std::ops::RangeInclusive<usize>> for AxisRange {
+    fn from(range: std::ops::RangeInclusive<usize>) -> Self {
+        Self::In(*range.start(), range.end() + 1, 1)
+    }
+}
+
+impl From<std::ops::RangeFrom<usize>> for AxisRange {
+    fn from(range: std::ops::RangeFrom<usize>) -> Self {
+        Self::Span(Some(range.start as isize), None, 1)
+    }
+}
+
+impl From<std::ops::RangeTo<usize>> for AxisRange {
+    fn from(range: std::ops::RangeTo<usize>) -> Self {
+        Self::In(0, range.end, 1)
+    }
+}
+
+impl From<std::ops::RangeToInclusive<usize>> for AxisRange {
+    fn from(range: std::ops::RangeToInclusive<usize>) -> Self {
+        Self::In(0, range.end + 1, 1)
+    }
+}
+
+impl From<std::iter::StepBy<std::ops::Range<usize>>> for AxisRange {
+    fn from(mut range: std::iter::StepBy<std::ops::Range<usize>>) -> Self {
+        let last = range.next_back();
+        let start = range.next();
+        let next = range.next();
+
+        match (last, start, next) {
+            (Some(last), Some(start), Some(next)) => Self::In(start, last + 1, next - start),
+            (Some(last), Some(start), None) => Self::In(start, last + 1, last - start),
+            (Some(last), None, _) => Self::In(last, last + 1, 1),
+            (None, _, _) => Self::In(0, 0, 1),
+        }
+    }
+}
+
+impl From<std::ops::RangeFull> for AxisRange {
+    fn from(_range: std::ops::RangeFull) -> Self {
+        Self::Span(None, None, 1)
+    }
+}
+
 impl From<SmallVec<[usize; 8]>> for AxisRange {
     fn from(indices: SmallVec<[usize; 8]>) -> Self {
         Self::Of(indices)
     }
 }
 
+// the end of an inclusive range, or `None` if it's the end of the axis
+#[inline]
+fn inclusive_stop(end: isize) -> Option<isize> {
+    if end == -1 {
+        None
+    } else {
+        Some(end + 1)
+    }
+}
+
+macro_rules! axis_range_from_signed {
+    ($($t:ty),+) => {
+        $(
+            impl From<$t> for AxisRange {
+                fn from(i: $t) -> Self {
+                    Self::Index(i as isize)
+                }
+            }
+
+            impl From<std::ops::Range<$t>> for AxisRange {
+                fn from(range: std::ops::Range<$t>) -> Self {
+                    Self::Span(Some(range.start as isize), Some(range.end as isize), 1)
+                }
+            }
+
+            impl From<std::ops::RangeInclusive<$t>> for AxisRange {
+                fn from(range: std::ops::RangeInclusive<$t>) -> Self {
+                    let stop = inclusive_stop(*range.end() as isize);
+                    Self::Span(Some(*range.start() as isize), stop, 1)
+                }
+            }
+
+            impl From<std::ops::RangeFrom<$t>> for AxisRange {
+                fn from(range: std::ops::RangeFrom<$t>) -> Self {
+                    Self::Span(Some(range.start as isize), None, 1)
+                }
+            }
+
+            impl From<std::ops::RangeTo<$t>> for AxisRange {
+                fn from(range: std::ops::RangeTo<$t>) -> Self {
+                    Self::Span(None, Some(range.end as isize), 1)
+                }
+            }
+
+            impl From<std::ops::RangeToInclusive<$t>> for AxisRange {
+                fn from(range: std::ops::RangeToInclusive<$t>) -> Self {
+                    Self::Span(None, inclusive_stop(range.end as isize), 1)
+                }
+            }
+        )+
+    };
+}
+
+axis_range_from_signed!(i32, i64, isize);
+
+macro_rules! axis_range_from_step_by {
+    ($($t:ty),+) => {
+        $(
+            impl From<std::iter::StepBy<std::ops::Range<$t>>> for AxisRange {
+                fn from(mut range: std::iter::StepBy<std::ops::Range<$t>>) -> Self {
+                    let last = range.next_back().map(|i| i as isize);
+                    let start = range.next().map(|i| i as isize);
+                    let next = range.next().map(|i| i as isize);
+
+                    match (last, start, next) {
+                        (Some(last), Some(start), Some(next)) => {
+                            Self::Span(Some(start), inclusive_stop(last), (next - start) as usize)
+                        }
+                        (Some(last), Some(start), None) => {
+                            Self::Span(Some(start), inclusive_stop(last), (last - start) as usize)
+                        }
+                        (Some(last), None, _) => Self::Span(Some(last), inclusive_stop(last), 1),
+                        (None, _, _) => Self::Span(Some(0), Some(0), 1),
+                    }
+                }
+            }
+        )+
+    };
+}
+
+axis_range_from_step_by!(i32, isize);
+
 impl fmt::Debug for AxisRange {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -757,6 +1269,24 @@ impl fmt::Debug for AxisRange {
             Self::In(start, stop, 1) => write!(f, "{}:{}", start, stop),
             Self::In(start, stop, step) => write!(f, "{}:{}:{}", start, stop, step),
             Self::Of(indices) => write!(f, "{:?}", indices),
+            Self::Index(i) => write!(f, "{}", i),
+            Self::Span(start, stop, step) => {
+                if let Some(start) = start {
+                    write!(f, "{}", start)?;
+                }
+
+                f.write_str(":")?;
+
+                if let Some(stop) = stop {
+                    write!(f, "{}", stop)?;
+                }
+
+                if *step == 1 {
+                    Ok(())
+                } else {
+                    write!(f, ":{}", step)
+                }
+            }
         }
     }
 }
@@ -775,7 +1305,8 @@ pub fn broadcast_shape(left: &[usize], right: &[usize]) -> Result<Shape, Error>
     let mut shape = Shape::with_capacity(left.len());
     shape.extend_from_slice(&left[..offset]);
 
-    for (l, r) in left.into_iter().copied().zip(right.into_iter().copied()) {
+    // the trailing dimensions of the left shape are aligned with the right shape
+    for (l, r) in left[offset..].iter().copied().zip(right.iter().copied()) {
         if r == 1 || r == l {
             shape.push(l);
         } else if l == 1 {
@@ -787,11 +1318,42 @@ pub fn broadcast_shape(left: &[usize], right: &[usize]) -> Result<Shape, Error>
         }
     }
 
-    debug_assert!(!shape.iter().any(|dim| *dim == 0));
-
     Ok(shape)
 }
 
+#[inline]
+fn resolve_range(source_shape: &[usize], range: Range) -> Result<Range, Error> {
+    if range.len() > source_shape.len() {
+        return Err(Error::Bounds(format!(
+            "range {range:?} has too many axes for shape {source_shape:?}"
+        )));
+    }
+
+    let mut resolved = Range::with_capacity(source_shape.len());
+
+    for (dim, bound) in source_shape.iter().zip(range) {
+        let bound = bound.resolve(*dim)?;
+
+        match &bound {
+            AxisRange::At(i) if i < dim => Ok(()),
+            // an empty range may start at the end of its axis, like `a[len..]`
+            AxisRange::In(start, stop, step) if start <= stop && stop <= dim && *step > 0 => Ok(()),
+            AxisRange::Of(indices) if indices.iter().all(|i| i < dim) => Ok(()),
+            bound => Err(Error::Bounds(format!(
+                "invalid range {bound:?} for dimension {dim}"
+            ))),
+        }?;
+
+        resolved.push(bound);
+    }
+
+    for dim in source_shape.iter().skip(resolved.len()).copied() {
+        resolved.push(AxisRange::In(0, dim, 1));
+    }
+
+    Ok(resolved)
+}
+
 #[inline]
 fn range_shape(source_shape: &[usize], range: &[AxisRange]) -> Shape {
     debug_assert_eq!(source_shape.len(), range.len());
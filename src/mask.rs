@@ -0,0 +1,115 @@
+//! Boolean arrays
+//!
+//! An op like [`NDArrayCompare::eq`] returns an array of `u8` with one byte per element,
+//! which is indistinguishable from numeric `u8` data. A [`Bool`] array holds only zero and one,
+//! so it can be passed to [`Bool::cond`] or combined with boolean logic without first checking
+//! whether it is really a mask.
+
+use std::fmt;
+
+use crate::access::Accessor;
+use crate::array::Array;
+use crate::{
+    ArrayAccess, CType, Error, NDArray, NDArrayBoolean, NDArrayCompareScalar, NDArrayReduceBoolean,
+    NDArrayUnaryBoolean, NDArrayWhere, Platform,
+};
+
+/// An array of boolean values, each stored as a `u8` which is either zero or one
+#[derive(Clone)]
+pub struct Bool {
+    mask: ArrayAccess<u8>,
+}
+
+impl Bool {
+    /// Construct a boolean array which is `true` wherever the given `array` is nonzero.
+    pub fn new<T, A, P>(array: Array<T, A, P>) -> Result<Self, Error>
+    where
+        T: CType,
+        Accessor<T>: From<A>,
+        Platform: From<P>,
+    {
+        Self::nonzero(ArrayAccess::from(array))
+    }
+
+    /// Borrow the underlying `u8` mask.
+    pub fn as_array(&self) -> &ArrayAccess<u8> {
+        &self.mask
+    }
+
+    /// Unwrap the underlying `u8` mask.
+    pub fn into_inner(self) -> ArrayAccess<u8> {
+        self.mask
+    }
+
+    /// Borrow the shape of this array.
+    pub fn shape(&self) -> &[usize] {
+        self.mask.shape()
+    }
+
+    /// Construct the logical negation of this array.
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(self) -> Result<Self, Error> {
+        self.mask.not().map(Self::wrap)
+    }
+
+    /// Construct the logical conjunction of this array and `other`.
+    pub fn and(self, other: Self) -> Result<Self, Error> {
+        self.mask.and(other.mask).map(Self::wrap)
+    }
+
+    /// Construct the logical disjunction of this array and `other`.
+    pub fn or(self, other: Self) -> Result<Self, Error> {
+        self.mask.or(other.mask).map(Self::wrap)
+    }
+
+    /// Construct the exclusive disjunction of this array and `other`.
+    pub fn xor(self, other: Self) -> Result<Self, Error> {
+        self.mask.xor(other.mask).map(Self::wrap)
+    }
+
+    /// Return `true` if every element of this array is `true`.
+    pub fn all(self) -> Result<bool, Error> {
+        self.mask.all()
+    }
+
+    /// Return `true` if any element of this array is `true`.
+    pub fn any(self) -> Result<bool, Error> {
+        self.mask.any()
+    }
+
+    /// Select the elements of `then` where this array is `true` and of `or_else` elsewhere.
+    pub fn cond<T, LA, LP, RA, RP>(
+        self,
+        then: Array<T, LA, LP>,
+        or_else: Array<T, RA, RP>,
+    ) -> Result<ArrayAccess<T>, Error>
+    where
+        T: CType,
+        Accessor<T>: From<LA> + From<RA>,
+        Platform: From<LP> + From<RP>,
+    {
+        let then = ArrayAccess::from(then);
+        let or_else = ArrayAccess::from(or_else);
+        self.mask.cond(then, or_else).map(ArrayAccess::from)
+    }
+
+    fn nonzero<T: CType>(array: ArrayAccess<T>) -> Result<Self, Error> {
+        array.ne_scalar(T::ZERO).map(Self::wrap)
+    }
+
+    fn wrap<A, P>(mask: Array<u8, A, P>) -> Self
+    where
+        Accessor<u8>: From<A>,
+        Platform: From<P>,
+    {
+        Self {
+            mask: ArrayAccess::from(mask),
+        }
+    }
+}
+
+impl fmt::Debug for Bool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a boolean array with shape {:?}", self.shape())
+    }
+}
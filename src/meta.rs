@@ -0,0 +1,449 @@
+//! Descriptive array metadata
+
+use std::fmt;
+use std::ops::{Bound, RangeBounds};
+use std::sync::Arc;
+
+use crate::{Axes, AxisRange, Error};
+
+/// An optional name, axis labels, and axis coordinates attached to an array.
+///
+/// Metadata never affects the result of an op, but it is carried through ops which preserve
+/// the meaning of an array's axes and included in the [`fmt::Debug`] output of an array
+/// and the context of its errors, so that a shape mismatch like `[64, 128]` vs `[128, 64]`
+/// can be traced back to the logical tensors and axes involved.
+///
+/// A unary op or transform keeps the name of its input, and a transform keeps the labels and
+/// coordinates of the axes which it moves or retains (e.g. a transpose permutes them, a slice
+/// selects the coordinates of its range, a reverse reverses them, and a reduction drops the
+/// reduced axes). An op which resizes an axis, like an upsample, drops its coordinates.
+/// A dual op keeps the axes of its left operand but not its name,
+/// since its output is a different logical tensor.
+/// An op which does not preserve the meaning of the axes, like a reshape, drops all axis metadata.
+#[derive(Clone, Default, PartialEq)]
+pub struct ArrayMeta {
+    name: Option<Arc<str>>,
+    axes: Option<Arc<[AxisMeta]>>,
+}
+
+/// The label and coordinates of one axis
+#[derive(Clone, Default, PartialEq)]
+struct AxisMeta {
+    label: Option<Arc<str>>,
+    coords: Option<Arc<[f64]>>,
+}
+
+impl ArrayMeta {
+    /// Return the name of the array, if any.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Return the label of the given `axis`, if any.
+    pub fn axis_label(&self, axis: usize) -> Option<&str> {
+        self.axis(axis).and_then(|axis| axis.label.as_deref())
+    }
+
+    /// Return the coordinates of the given `axis`, if any.
+    pub fn coords(&self, axis: usize) -> Option<&[f64]> {
+        self.axis(axis).and_then(|axis| axis.coords.as_deref())
+    }
+
+    /// Resolve the given axis `labels` to the indices of the labeled axes, in the order given.
+    /// This will return an error if a label does not match exactly one axis or is repeated.
+    pub fn axes_of(&self, labels: &[&str]) -> Result<Axes, Error> {
+        let mut axes = Axes::with_capacity(labels.len());
+
+        for (i, label) in labels.iter().enumerate() {
+            if labels[..i].contains(label) {
+                return Err(Error::Bounds(format!(
+                    "axis {label:?} is given more than once in {labels:?}"
+                )));
+            }
+
+            let mut matches = self
+                .axes
+                .iter()
+                .flat_map(|axes| axes.iter().enumerate())
+                .filter(|(_, axis)| axis.label.as_deref() == Some(*label))
+                .map(|(x, _)| x);
+
+            match (matches.next(), matches.next()) {
+                (Some(x), None) => axes.push(x),
+                (Some(_), Some(_)) => {
+                    return Err(Error::Bounds(format!(
+                        "more than one axis is labeled {label:?}"
+                    )))
+                }
+                (None, _) => {
+                    return Err(Error::Bounds(format!("there is no axis labeled {label:?}")))
+                }
+            }
+        }
+
+        Ok(axes)
+    }
+
+    /// Return `true` if this metadata has no name, axis labels, or coordinates.
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none() && self.axes.is_none()
+    }
+
+    fn axis(&self, axis: usize) -> Option<&AxisMeta> {
+        self.axes.as_ref().and_then(|axes| axes.get(axis))
+    }
+
+    /// Copy the metadata of each axis so that one of them can be replaced.
+    fn axes_mut(&self, ndim: usize) -> Vec<AxisMeta> {
+        match &self.axes {
+            Some(axes) => axes.to_vec(),
+            None => vec![AxisMeta::default(); ndim],
+        }
+    }
+
+    pub(crate) fn with_name(mut self, name: String) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub(crate) fn with_axis_labels(
+        mut self,
+        labels: Vec<String>,
+        ndim: usize,
+    ) -> Result<Self, Error> {
+        if labels.len() != ndim {
+            return Err(Error::Bounds(format!(
+                "cannot label the {ndim} axes of an array with {labels:?}"
+            )));
+        }
+
+        if (1..labels.len()).any(|i| labels[i..].contains(&labels[i - 1])) {
+            return Err(Error::Bounds(format!(
+                "axis labels must be unique, not {labels:?}"
+            )));
+        }
+
+        let mut axes = self.axes_mut(ndim);
+
+        for (axis, label) in axes.iter_mut().zip(labels) {
+            axis.label = Some(label.into());
+        }
+
+        self.axes = Some(axes.into());
+        Ok(self)
+    }
+
+    pub(crate) fn with_coords(
+        mut self,
+        axis: usize,
+        coords: Vec<f64>,
+        shape: &[usize],
+    ) -> Result<Self, Error> {
+        if axis >= shape.len() || coords.len() != shape[axis] {
+            return Err(Error::Bounds(format!(
+                "cannot assign {} coordinates to axis {axis} of an array with shape {shape:?}",
+                coords.len()
+            )));
+        }
+
+        if !is_monotonic(&coords) {
+            return Err(Error::Bounds(format!(
+                "coordinates must be strictly increasing or decreasing, not {coords:?}"
+            )));
+        }
+
+        let mut axes = self.axes_mut(shape.len());
+        axes[axis].coords = Some(coords.into());
+        self.axes = Some(axes.into());
+        Ok(self)
+    }
+
+    /// Resolve the elements of `axis` whose coordinates lie within `bounds`
+    /// to a contiguous range of indices.
+    pub(crate) fn sel<B: RangeBounds<f64>>(
+        &self,
+        axis: usize,
+        bounds: B,
+    ) -> Result<AxisRange, Error> {
+        let coords = self.coords(axis).ok_or_else(|| {
+            Error::Bounds(format!("axis {axis} has no coordinates to select from"))
+        })?;
+
+        let below = |c: f64| match bounds.start_bound() {
+            Bound::Included(start) => c < *start,
+            Bound::Excluded(start) => c <= *start,
+            Bound::Unbounded => false,
+        };
+
+        let above = |c: f64| match bounds.end_bound() {
+            Bound::Included(end) => c > *end,
+            Bound::Excluded(end) => c >= *end,
+            Bound::Unbounded => false,
+        };
+
+        // the coordinates within the bounds are contiguous since the coordinates are monotonic
+        let (start, stop) = if coords.len() < 2 || coords[0] < coords[1] {
+            let start = coords.partition_point(|c| below(*c));
+            (
+                start,
+                start + coords[start..].partition_point(|c| !above(*c)),
+            )
+        } else {
+            let start = coords.partition_point(|c| above(*c));
+            (
+                start,
+                start + coords[start..].partition_point(|c| !below(*c)),
+            )
+        };
+
+        if start < stop {
+            Ok(AxisRange::In(start, stop, 1))
+        } else {
+            Err(Error::Bounds(format!(
+                "no coordinate of axis {axis} lies within {:?}",
+                (bounds.start_bound(), bounds.end_bound())
+            )))
+        }
+    }
+
+    /// Drop the name of the array, keeping its axis metadata.
+    pub(crate) fn unnamed(&self) -> Self {
+        Self {
+            name: None,
+            axes: self.axes.clone(),
+        }
+    }
+
+    /// Drop the axis labels and coordinates of the array, keeping its name.
+    pub(crate) fn unlabeled(&self) -> Self {
+        Self {
+            name: self.name.clone(),
+            axes: None,
+        }
+    }
+
+    /// Map each output axis to the source axis whose metadata it keeps, if any.
+    fn map_axes<I: IntoIterator<Item = Option<usize>>>(&self, axes: I) -> Self {
+        let axes = self.axes.as_ref().map(|source| {
+            axes.into_iter()
+                .map(|x| x.map(|x| source[x].clone()).unwrap_or_default())
+                .collect()
+        });
+
+        Self {
+            name: self.name.clone(),
+            axes,
+        }
+    }
+
+    /// Prepend unlabeled axes to broadcast the array from `shape` into the `broadcast` shape.
+    /// The coordinates of an axis which is broadcast from a dimension of one are dropped.
+    pub(crate) fn broadcast(&self, shape: &[usize], broadcast: &[usize]) -> Self {
+        let new = broadcast.len() - shape.len();
+        let mut meta = self.map_axes((0..broadcast.len()).map(|x| x.checked_sub(new)));
+
+        if let Some(axes) = &mut meta.axes {
+            let mut copy = axes.to_vec();
+
+            for (axis, dim) in copy.iter_mut().zip(broadcast) {
+                if axis
+                    .coords
+                    .as_ref()
+                    .is_some_and(|coords| coords.len() != *dim)
+                {
+                    axis.coords = None;
+                }
+            }
+
+            *axes = copy.into();
+        }
+
+        meta
+    }
+
+    /// Keep the metadata of the axes of the resolved `range` which are not indexed,
+    /// and select the coordinates within the range of each axis.
+    pub(crate) fn slice(&self, range: &[AxisRange]) -> Self {
+        let Some(source) = &self.axes else {
+            return self.clone();
+        };
+
+        let axes = range
+            .iter()
+            .zip(source.iter())
+            .filter_map(|(ar, axis)| {
+                let coords = axis.coords.as_ref().and_then(|coords| {
+                    let selected = match ar {
+                        AxisRange::In(start, stop, step) => coords[*start..*stop]
+                            .iter()
+                            .step_by(*step)
+                            .copied()
+                            .collect(),
+                        AxisRange::Of(indices) => indices.iter().map(|i| coords[*i]).collect(),
+                        _ => return None,
+                    };
+
+                    Some(selected).filter(|selected: &Vec<f64>| is_monotonic(selected))
+                });
+
+                ar.size().map(|_| AxisMeta {
+                    label: axis.label.clone(),
+                    coords: coords.map(Arc::from),
+                })
+            })
+            .collect();
+
+        Self {
+            name: self.name.clone(),
+            axes: Some(axes),
+        }
+    }
+
+    /// Permute the axis metadata.
+    pub(crate) fn transpose(&self, permutation: &[usize]) -> Self {
+        self.map_axes(permutation.iter().copied().map(Some))
+    }
+
+    /// Reverse the coordinates of the given `axes`.
+    pub(crate) fn reverse(&self, axes: &[usize]) -> Self {
+        self.map_coords(axes, |coords| Some(coords.iter().rev().copied().collect()))
+    }
+
+    /// Rotate the coordinates of the given `axis` by `shift` elements, like a roll,
+    /// and drop them unless they're still monotonic.
+    pub(crate) fn roll(&self, axis: usize, shift: usize) -> Self {
+        self.map_coords(&[axis], |coords| {
+            let mut rolled = coords.to_vec();
+            rolled.rotate_right(shift);
+            Some(rolled).filter(|rolled| is_monotonic(rolled))
+        })
+    }
+
+    /// Drop the coordinates of the given `axes`, whose elements no longer correspond to those of
+    /// the source array, e.g. because the axes were resized by an upsample or a convolution.
+    pub(crate) fn drop_coords(&self, axes: &[usize]) -> Self {
+        self.map_coords(axes, |_| None)
+    }
+
+    /// Replace the coordinates of each of the given `axes` which has any.
+    fn map_coords<F>(&self, axes: &[usize], f: F) -> Self
+    where
+        F: Fn(&[f64]) -> Option<Vec<f64>>,
+    {
+        let Some(source) = &self.axes else {
+            return self.clone();
+        };
+
+        let axes = source
+            .iter()
+            .enumerate()
+            .map(|(x, axis)| AxisMeta {
+                label: axis.label.clone(),
+                coords: match &axis.coords {
+                    Some(coords) if axes.contains(&x) => f(coords).map(Arc::from),
+                    coords => coords.clone(),
+                },
+            })
+            .collect();
+
+        Self {
+            name: self.name.clone(),
+            axes: Some(axes),
+        }
+    }
+
+    /// Describe the axes of the product of matrices with `ndim` dimensions by the rows of this
+    /// array and the columns of the `other`, without a name.
+    pub(crate) fn matmul(&self, other: &Self, ndim: usize) -> Self {
+        if self.axes.is_none() && other.axes.is_none() {
+            return Self::default();
+        }
+
+        let axis = |meta: &Self, x: usize| meta.axis(x).cloned().unwrap_or_default();
+        let axes = (0..ndim - 1)
+            .map(|x| axis(self, x))
+            .chain([axis(other, ndim - 1)])
+            .collect();
+
+        Self {
+            name: None,
+            axes: Some(axes),
+        }
+    }
+
+    /// Drop the metadata of the given sorted `axes` of an array with `ndim` dimensions.
+    pub(crate) fn remove_axes(&self, axes: &[usize], ndim: usize) -> Self {
+        self.map_axes((0..ndim).filter(|x| !axes.contains(x)).map(Some))
+    }
+
+    /// Insert unlabeled axes at the given sorted `axes` of an array with `ndim` dimensions,
+    /// in the same order as [`crate::NDArrayTransform::unsqueeze`].
+    pub(crate) fn insert_axes(&self, axes: &[usize], ndim: usize) -> Self {
+        let mut source = (0..ndim).map(Some).collect::<Vec<_>>();
+
+        for x in axes.iter().rev() {
+            source.insert(*x, None);
+        }
+
+        self.map_axes(source)
+    }
+
+    /// Describe an array with the given `shape` and this metadata.
+    pub(crate) fn describe<'a>(&'a self, shape: &'a [usize]) -> Described<'a> {
+        Described { meta: self, shape }
+    }
+}
+
+impl fmt::Debug for ArrayMeta {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let axes = self.axes.as_ref().map(|axes| {
+            axes.iter()
+                .map(|axis| (axis.label.as_deref(), axis.coords.as_deref()))
+                .collect::<Vec<_>>()
+        });
+
+        f.debug_struct("ArrayMeta")
+            .field("name", &self.name())
+            .field("axes", &axes)
+            .finish()
+    }
+}
+
+/// A shape annotated with the name and axis labels of its array, e.g. `"weights" [64 (in), 128]`
+pub(crate) struct Described<'a> {
+    meta: &'a ArrayMeta,
+    shape: &'a [usize],
+}
+
+impl<'a> fmt::Display for Described<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(name) = self.meta.name() {
+            write!(f, "{name:?} ")?;
+        }
+
+        if self.meta.axes.is_none() {
+            return write!(f, "{:?}", self.shape);
+        }
+
+        f.write_str("[")?;
+
+        for (x, dim) in self.shape.iter().enumerate() {
+            if x > 0 {
+                f.write_str(", ")?;
+            }
+
+            match self.meta.axis_label(x) {
+                Some(label) => write!(f, "{dim} ({label})")?,
+                None => write!(f, "{dim}")?,
+            }
+        }
+
+        f.write_str("]")
+    }
+}
+
+#[inline]
+fn is_monotonic(coords: &[f64]) -> bool {
+    coords.windows(2).all(|pair| pair[0] < pair[1])
+        || coords.windows(2).all(|pair| pair[0] > pair[1])
+}
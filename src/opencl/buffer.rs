@@ -6,6 +6,8 @@ use crate::buffer::{BufferConverter, BufferInstance, BufferMut};
 use crate::opencl::OpenCL;
 use crate::{CType, Error};
 
+use super::{events, memory};
+
 impl<T: CType> BufferInstance<T> for Buffer<T> {
     fn read(&self) -> BufferConverter<T> {
         BufferConverter::CL(self.into())
@@ -37,7 +39,11 @@ impl<T: CType> BufferMut<T> for Buffer<T> {
     fn write<'a>(&mut self, data: BufferConverter<'a, T>) -> Result<(), Error> {
         if data.len() == self.len() {
             let data = data.to_cl()?;
-            data.copy(self, None, None).enq().map_err(Error::from)
+
+            events::enq_cmd(self.default_queue(), |wait, event| {
+                data.copy(self, None, None).ewait(wait).enew(event).enq()
+            })
+            .map_err(Error::from)
         } else {
             Err(Error::Bounds(format!(
                 "cannot overwrite a buffer of size {} with one of size {}",
@@ -54,6 +60,8 @@ impl<T: CType> BufferMut<T> for Buffer<T> {
             .fill_val(value)
             .build()?;
 
+        memory::track(&buf, || "write_value".to_string());
+
         *self = buf;
         Ok(())
     }
@@ -135,12 +143,16 @@ impl<'a, T: CType> CLConverter<'a, T> {
             Self::Owned(buffer) => Ok(buffer),
             Self::Borrowed(buffer) => {
                 let cl_queue = buffer.default_queue().expect("OpenCL queue");
-                let mut copy = Buffer::builder()
+                let copy = Buffer::builder()
                     .queue(cl_queue.clone())
                     .len(buffer.len())
                     .build()?;
 
-                buffer.copy(&mut copy, None, None).enq()?;
+                events::enq_cmd(Some(cl_queue), |wait, event| {
+                    buffer.copy(&copy, None, None).ewait(wait).enew(event).enq()
+                })?;
+
+                memory::track(&copy, || "into_buffer".to_string());
 
                 Ok(copy)
             }
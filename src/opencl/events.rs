@@ -0,0 +1,114 @@
+//! Dependencies between the kernels of an op graph enqueued on out-of-order queues
+//!
+//! While [`config::out_of_order_queues`] is enabled, each op enqueued on this thread pushes a
+//! frame of the events which its kernels must wait for. A kernel waits for every event in its
+//! frame and then replaces them, so the kernels of one op execute in order, and the last event
+//! of an op is passed up to the frame of the op which reads its output. The kernels of
+//! independent inputs of an op don't wait for one another, so they can overlap on the device.
+//! A buffer copy or write is recorded in its frame like a kernel, and the host waits for every
+//! event in the current frame before it reads a buffer.
+
+use std::cell::RefCell;
+
+use ocl::{Event, EventList, Kernel, Queue};
+
+use crate::{config, Error};
+
+thread_local! {
+    static FRAMES: RefCell<Vec<Vec<Event>>> = RefCell::new(Vec::new());
+}
+
+/// Call `enqueue` to enqueue one op of an op graph. The output of a whole op graph is complete
+/// when this returns, since the host may read it from any queue.
+pub(super) fn enqueue_op<T, F>(enqueue: F) -> Result<T, Error>
+where
+    F: FnOnce() -> Result<T, Error>,
+{
+    if !config::out_of_order_queues() {
+        return enqueue();
+    }
+
+    FRAMES.with(|frames| frames.borrow_mut().push(Vec::new()));
+
+    let output = enqueue();
+
+    let pending = FRAMES.with(|frames| {
+        let mut frames = frames.borrow_mut();
+        let events = frames.pop().expect("event frame");
+
+        if let Some(parent) = frames.last_mut() {
+            parent.extend(events);
+            Vec::new()
+        } else {
+            events
+        }
+    });
+
+    for event in pending {
+        event.wait_for().map_err(ocl::Error::from)?;
+    }
+
+    output
+}
+
+/// Enqueue the given `kernel` after every event in the current frame.
+///
+/// # Safety
+/// See [`Kernel::enq`].
+pub(super) unsafe fn enq(kernel: &Kernel) -> Result<(), ocl::Error> {
+    if !config::out_of_order_queues() {
+        return kernel.enq();
+    }
+
+    enq_cmd(kernel.default_queue(), |wait, event| unsafe {
+        kernel.cmd().ewait(wait).enew(event).enq()
+    })
+}
+
+/// Enqueue a buffer command on `queue` after every event in the current frame, like [`enq`].
+/// The `enqueue` callback must make its command wait for the given events,
+/// and store the new event of its command in the given destination.
+pub(super) fn enq_cmd<F>(queue: Option<&Queue>, enqueue: F) -> Result<(), ocl::Error>
+where
+    F: FnOnce(&EventList, &mut Event) -> Result<(), ocl::Error>,
+{
+    if !config::out_of_order_queues() {
+        return enqueue(&EventList::new(), &mut Event::empty());
+    }
+
+    let wait = FRAMES.with(|frames| {
+        let frames = frames.borrow();
+        let events = frames.last().cloned().unwrap_or_default();
+        EventList::from(events)
+    });
+
+    let mut event = Event::empty();
+    enqueue(&wait, &mut event)?;
+
+    // a command on another queue may wait for this one, which requires that it be submitted
+    if let Some(queue) = queue {
+        queue.flush()?;
+    }
+
+    FRAMES.with(|frames| match frames.borrow_mut().last_mut() {
+        // this command waits for every earlier event in its frame, so it supersedes them
+        Some(events) => {
+            *events = vec![event];
+            Ok(())
+        }
+        // outside of an op graph, commands execute in the order they're enqueued
+        None => event.wait_for().map_err(ocl::Error::from),
+    })
+}
+
+/// Block until every event in the current frame has completed,
+/// before reading a buffer on the host.
+pub(crate) fn wait() -> Result<(), ocl::Error> {
+    let events = FRAMES.with(|frames| frames.borrow().last().cloned().unwrap_or_default());
+
+    for event in events {
+        event.wait_for()?;
+    }
+
+    Ok(())
+}
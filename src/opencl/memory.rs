@@ -0,0 +1,169 @@
+//! Tracking of live OpenCL buffers, to diagnose a leak of device memory
+//!
+//! Tracking is disabled by default, and can be enabled with [`config::set_track_memory`]
+//! or the `DENSOR_TRACK_MEMORY` environment variable. While enabled, every buffer returned
+//! by an op, constructor, or copy is recorded until the OpenCL runtime releases it.
+//! With the `backtrace` feature, the stack at which each buffer was allocated is also recorded.
+
+use std::collections::HashMap;
+use std::ffi::c_void;
+use std::fmt;
+use std::ptr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use lazy_static::lazy_static;
+use ocl::ffi::{self, cl_mem};
+use ocl::Buffer;
+
+use crate::{config, CType};
+
+lazy_static! {
+    static ref LIVE: Mutex<HashMap<usize, Live>> = Mutex::new(HashMap::new());
+}
+
+struct Live {
+    origin: String,
+    dtype: &'static str,
+    len: usize,
+    bytes: usize,
+    created: Instant,
+    #[cfg(feature = "backtrace")]
+    backtrace: std::backtrace::Backtrace,
+}
+
+/// A live OpenCL buffer
+#[derive(Clone, Debug)]
+pub struct Allocation {
+    /// The op or method which allocated this buffer
+    pub origin: String,
+    /// The data type of this buffer
+    pub dtype: &'static str,
+    /// The number of elements in this buffer
+    pub len: usize,
+    /// The number of bytes occupied by this buffer
+    pub bytes: usize,
+    /// How long ago this buffer was allocated
+    pub age: Duration,
+    /// The stack at which this buffer was allocated
+    #[cfg(feature = "backtrace")]
+    pub backtrace: String,
+}
+
+impl fmt::Display for Allocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} bytes ({} x {}) allocated by {} {:.1?} ago",
+            self.bytes, self.len, self.dtype, self.origin, self.age
+        )?;
+
+        #[cfg(feature = "backtrace")]
+        write!(f, "\n{}", self.backtrace)?;
+
+        Ok(())
+    }
+}
+
+/// The OpenCL buffers which were allocated while tracking was enabled and are still live
+#[derive(Clone, Debug, Default)]
+pub struct MemoryReport {
+    /// The number of live buffers
+    pub count: usize,
+    /// The total number of bytes occupied by live buffers
+    pub bytes: usize,
+    /// Each live buffer, oldest first
+    pub allocations: Vec<Allocation>,
+}
+
+impl fmt::Display for MemoryReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} live OpenCL buffers ({} bytes)",
+            self.count, self.bytes
+        )?;
+
+        for allocation in &self.allocations {
+            write!(f, "\n  {allocation}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Report the OpenCL buffers which were allocated while tracking was enabled and are still live.
+pub fn memory_report() -> MemoryReport {
+    let live = LIVE.lock().expect("live OpenCL buffers");
+
+    let mut allocations = live
+        .values()
+        .map(|live| Allocation {
+            origin: live.origin.clone(),
+            dtype: live.dtype,
+            len: live.len,
+            bytes: live.bytes,
+            age: live.created.elapsed(),
+            #[cfg(feature = "backtrace")]
+            backtrace: live.backtrace.to_string(),
+        })
+        .collect::<Vec<_>>();
+
+    allocations.sort_by(|l, r| r.age.cmp(&l.age));
+
+    MemoryReport {
+        count: allocations.len(),
+        bytes: allocations.iter().map(|allocation| allocation.bytes).sum(),
+        allocations,
+    }
+}
+
+/// Record the given `buffer` until it's released, if [`config::track_memory`] is enabled.
+pub(crate) fn track<T, F>(buffer: &Buffer<T>, origin: F)
+where
+    T: CType,
+    F: FnOnce() -> String,
+{
+    if !config::track_memory() {
+        return;
+    }
+
+    let mem = buffer.as_core().as_ptr();
+
+    {
+        let mut live = LIVE.lock().expect("live OpenCL buffers");
+
+        // a buffer may be returned by more than one op, e.g. a no-op view
+        if live.contains_key(&(mem as usize)) {
+            return;
+        }
+
+        live.insert(
+            mem as usize,
+            Live {
+                origin: origin(),
+                dtype: T::TYPE,
+                len: buffer.len(),
+                bytes: buffer.len() * std::mem::size_of::<T>(),
+                created: Instant::now(),
+                #[cfg(feature = "backtrace")]
+                backtrace: std::backtrace::Backtrace::force_capture(),
+            },
+        );
+    }
+
+    let status =
+        unsafe { ffi::clSetMemObjectDestructorCallback(mem, Some(release), ptr::null_mut()) };
+
+    if status != ffi::CL_SUCCESS {
+        // without a destructor callback, there's no way to tell when this buffer is released
+        release(mem, ptr::null_mut());
+    }
+}
+
+extern "C" fn release(mem: cl_mem, _user_data: *mut c_void) {
+    // this is called by the OpenCL runtime, so it must not panic
+    if let Ok(mut live) = LIVE.lock() {
+        live.remove(&(mem as usize));
+    }
+}
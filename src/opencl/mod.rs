@@ -3,15 +3,21 @@
 use lazy_static::lazy_static;
 
 use crate::access::{AccessBuf, AccessOp};
+use crate::host::ops::TREE_WIDTH;
 use crate::host::VEC_MIN_SIZE;
 
 pub use buffer::*;
+pub use memory::{memory_report, Allocation, MemoryReport};
 pub use platform::{OpenCL, ACC_MIN_SIZE, GPU_MIN_SIZE};
 
 mod buffer;
+pub(crate) mod events;
+mod memory;
 pub mod ops;
 mod platform;
 mod programs;
+pub mod template;
+pub mod tune;
 
 const TILE_SIZE: usize = 8;
 
@@ -21,6 +27,7 @@ lazy_static! {
     pub static ref CL_PLATFORM: platform::CLPlatform = {
         assert!(VEC_MIN_SIZE < GPU_MIN_SIZE);
         assert!(GPU_MIN_SIZE < ACC_MIN_SIZE);
+        assert_eq!(WG_SIZE, TREE_WIDTH);
 
         platform::CLPlatform::default().expect("OpenCL platform")
     };
@@ -31,13 +38,38 @@ pub type ArrayOp<T, O> = crate::array::Array<T, AccessOp<O, OpenCL>, OpenCL>;
 
 #[cfg(test)]
 mod tests {
+    use std::sync::{Mutex, MutexGuard, PoisonError};
+
     use crate::{
-        shape, slice, AxisRange, Error, MatrixDual, NDArray, NDArrayCompare, NDArrayMath,
-        NDArrayRead, NDArrayReduceBoolean, NDArrayTransform, NDArrayWrite, Shape,
+        config, shape, slice, AxisRange, Error, MatrixDual, NDArray, NDArrayCompare, NDArrayMath,
+        NDArrayRead, NDArrayReduceAll, NDArrayReduceBoolean, NDArrayTransform, NDArrayWrite, Shape,
     };
 
     use super::*;
 
+    static CONFIG: Mutex<()> = Mutex::new(());
+
+    /// Serializes the tests which change the global config,
+    /// and calls `reset` when dropped, so that a failed test can't leave its setting enabled.
+    struct ConfigGuard {
+        _lock: MutexGuard<'static, ()>,
+        reset: fn(),
+    }
+
+    impl ConfigGuard {
+        fn new(set: fn(), reset: fn()) -> Self {
+            let lock = CONFIG.lock().unwrap_or_else(PoisonError::into_inner);
+            set();
+            Self { _lock: lock, reset }
+        }
+    }
+
+    impl Drop for ConfigGuard {
+        fn drop(&mut self) {
+            (self.reset)()
+        }
+    }
+
     #[test]
     fn test_add() -> Result<(), Error> {
         let shape = shape![1, 2, 3];
@@ -168,4 +200,142 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_template() -> Result<(), Error> {
+        let program = template::Template::new()
+            .dtype::<f32>("T")
+            .define("SCALE", 2)
+            .elementwise("scale", &[("x", "T")], "T", "x * SCALE + T_ONE")
+            .build()?;
+
+        let input = OpenCL::copy_into_buffer(&[1f32, 2., 3.])?;
+        let queue = OpenCL::queue(input.len(), &[input.default_queue()])?;
+
+        let output = ocl::Buffer::<f32>::builder()
+            .queue(queue.clone())
+            .len(input.len())
+            .build()?;
+
+        let kernel = ocl::Kernel::builder()
+            .name("scale")
+            .program(&program)
+            .queue(queue)
+            .global_work_size(input.len())
+            .arg(&input)
+            .arg(&output)
+            .build()?;
+
+        unsafe { kernel.enq()? };
+
+        let mut actual = vec![0f32; 3];
+        output.read(&mut actual).enq()?;
+        assert_eq!(actual, vec![3., 5., 7.]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_copy_to_device() -> Result<(), Error> {
+        let buf = OpenCL::copy_into_buffer(&[1, 2, 3, 4, 5, 6])?;
+        let array = ArrayBuf::new(buf, shape![2, 3])?;
+
+        let before = crate::introspect::transfer_stats();
+
+        for device_id in 0..OpenCL::context().devices().len() {
+            let copy = array.copy_to_device(device_id)?;
+            assert_eq!(copy.shape(), array.shape());
+            assert_eq!(&*copy.buffer()?.to_slice()?, &[1, 2, 3, 4, 5, 6]);
+        }
+
+        let after = crate::introspect::transfer_stats();
+        assert!(after.device_copies > before.device_copies);
+
+        assert!(array.copy_to_device(usize::MAX).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_queue_pool() -> Result<(), Error> {
+        let first = OpenCL::queue(GPU_MIN_SIZE, &[])?;
+        let second = OpenCL::queue(GPU_MIN_SIZE, &[])?;
+
+        if first.device() == second.device() {
+            assert_eq!(first.as_ptr(), second.as_ptr());
+        }
+
+        let device = first.device();
+        let other = std::thread::spawn(move || {
+            let queue = OpenCL::queue(GPU_MIN_SIZE, &[])?;
+            Ok::<_, Error>((queue.device() == device).then(|| queue.as_ptr() as usize))
+        })
+        .join()
+        .expect("thread")?;
+
+        if let Some(other) = other {
+            assert_ne!(other, first.as_ptr() as usize);
+        }
+
+        let buf = OpenCL::copy_into_buffer(&[1, 2, 3])?;
+        let array = ArrayBuf::new(buf, shape![3])?;
+        let sum = array.as_ref().add(array.as_ref())?;
+
+        OpenCL::flush()?;
+        OpenCL::finish()?;
+        OpenCL::release_queues();
+
+        assert_eq!(&*sum.buffer()?.to_slice()?, &[2, 4, 6]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_out_of_order() -> Result<(), Error> {
+        let _guard = ConfigGuard::new(
+            || config::set_out_of_order_queues(true),
+            || config::set_out_of_order_queues(false),
+        );
+
+        let size = GPU_MIN_SIZE * 4;
+        let left = ArrayOp::range(0., size as f32, shape![size])?;
+        let right = ArrayOp::range(0., size as f32, shape![size])?;
+
+        // the two products are independent, so their kernels may overlap
+        let actual = left
+            .as_ref()
+            .mul(left.as_ref())?
+            .sub(right.as_ref().mul(right.as_ref())?)?;
+        assert!(!actual.any()?);
+
+        let sum = left.add(right)?.sum_all()?;
+        assert_eq!(sum, (size * (size - 1)) as f32);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_memory_report() -> Result<(), Error> {
+        let _guard = ConfigGuard::new(
+            || config::set_track_memory(true),
+            || config::set_track_memory(false),
+        );
+
+        let buf = OpenCL::copy_into_buffer(&[1u64, 2, 3])?;
+        let array = ArrayBuf::new(buf, shape![3])?;
+        let sum = array.as_ref().add(array.as_ref())?;
+        let output = sum.buffer()?;
+
+        let report = memory_report();
+        assert!(report.count >= 2);
+        assert!(report.bytes >= 2 * 3 * std::mem::size_of::<u64>());
+        assert!(report
+            .allocations
+            .iter()
+            .any(|allocation| allocation.origin == "copy_into_buffer"));
+
+        assert_eq!(output.len(), 3);
+
+        Ok(())
+    }
 }
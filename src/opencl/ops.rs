@@ -1,16 +1,106 @@
 use std::borrow::BorrowMut;
 use std::fmt;
 use std::marker::PhantomData;
+use std::thread;
+use std::time::Duration;
 
 use ocl::{Buffer, Kernel, Program, Queue};
 use rand::{random, Rng};
+use rayon::prelude::*;
+
+use crate::access::{Access, AccessMut};
+use crate::config::{self, OomPolicy};
+use crate::conv::upsample_value;
+use crate::introspect::{op_label, OpPlan};
+use crate::ops::{
+    ignore_nan, ignore_nan_identity, max_identity, min_identity, BroadcastDual, DualBroadcast,
+    Enqueue, Factor, Factorization, Op, ReadValue, SliceSpec, ViewSpec, Write,
+};
+use crate::{
+    host, Axes, BufferConverter, CType, ConvSpec, Error, Float, Interpolation, Range, Shape,
+};
+
+use super::platform::{check_indices, reduce_all, OpenCL};
+use super::tune::{self, KernelFamily};
+use super::{events, memory};
+use super::{programs, WG_SIZE};
+
+const OOM_RETRIES: u32 = 3;
+const OOM_BACKOFF: Duration = Duration::from_millis(100);
+
+/// The largest system solved by cyclic reduction within a single work group
+const PCR_MAX_DIM: usize = 256;
+
+pub struct Bitcast<A, IT, OT> {
+    access: A,
+    program: Program,
+    dtype: PhantomData<(IT, OT)>,
+}
+
+impl<A, IT: CType, OT: CType> Bitcast<A, IT, OT> {
+    pub fn new(access: A) -> Result<Self, Error> {
+        if std::mem::size_of::<IT>() != std::mem::size_of::<OT>() {
+            return Err(Error::Unsupported(format!(
+                "cannot reinterpret {} as {} since they have different sizes",
+                IT::TYPE,
+                OT::TYPE
+            )));
+        }
+
+        programs::elementwise::bitcast(IT::TYPE, OT::TYPE).map(|program| Self {
+            access,
+            program,
+            dtype: PhantomData,
+        })
+    }
+}
+
+impl<A: Access<IT>, IT: CType, OT: CType> Op for Bitcast<A, IT, OT> {
+    fn size(&self) -> usize {
+        self.access.size()
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan()]
+    }
+}
+
+impl<A: Access<IT>, IT: CType, OT: CType> Enqueue<OpenCL, OT> for Bitcast<A, IT, OT> {
+    type Buffer = Buffer<OT>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let input = self.access.read()?.to_cl()?;
+        let queue = OpenCL::queue(input.len(), &[input.default_queue()])?;
+
+        let output = Buffer::builder()
+            .queue(queue.clone())
+            .len(input.len())
+            .build()?;
 
-use crate::access::{Access, AccessBuf, AccessMut};
-use crate::ops::{Enqueue, Op, ReadValue, ReduceAll, SliceSpec, ViewSpec, Write};
-use crate::{strides_for, Axes, BufferConverter, CType, Error, Float, Range, Shape, Strides};
+        let kernel = Kernel::builder()
+            .name("bitcast")
+            .program(&self.program)
+            .queue(queue)
+            .global_work_size(input.len())
+            .arg(&*input)
+            .arg(&output)
+            .build()?;
+
+        unsafe { events::enq(&kernel)? };
 
-use super::platform::OpenCL;
-use super::{programs, TILE_SIZE, WG_SIZE};
+        Ok(output)
+    }
+}
+
+impl<A: Access<IT>, IT: CType, OT: CType> ReadValue<OpenCL, OT> for Bitcast<A, IT, OT> {
+    fn read_value(&self, offset: usize) -> Result<OT, Error> {
+        let n = self.access.read_value(offset)?;
+
+        // SAFETY: the constructor guarantees that IT and OT have the same size,
+        // and every CType is a primitive number for which every bit pattern is valid
+        Ok(unsafe { std::mem::transmute_copy(&n) })
+    }
+}
 
 pub struct Cast<A, IT, OT> {
     access: A,
@@ -32,6 +122,10 @@ impl<A: Access<IT>, IT: CType, OT: CType> Op for Cast<A, IT, OT> {
     fn size(&self) -> usize {
         self.access.size()
     }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan()]
+    }
 }
 
 impl<A: Access<IT>, IT: CType, OT: CType> Enqueue<OpenCL, OT> for Cast<A, IT, OT> {
@@ -55,7 +149,7 @@ impl<A: Access<IT>, IT: CType, OT: CType> Enqueue<OpenCL, OT> for Cast<A, IT, OT
             .arg(&output)
             .build()?;
 
-        unsafe { kernel.enq()? };
+        unsafe { events::enq(&kernel)? };
 
         Ok(output)
     }
@@ -74,6 +168,8 @@ pub struct Dual<L, R, IT, OT> {
     left: L,
     right: R,
     program: Program,
+    broadcast: DualBroadcast,
+    views: [Option<Program>; 2],
     op: fn(IT, IT) -> OT,
 }
 
@@ -83,52 +179,100 @@ impl<L, R, IT, OT> Dual<L, R, IT, OT> {
             left,
             right,
             program,
+            broadcast: DualBroadcast::default(),
+            views: [None, None],
             op,
         })
     }
 }
 
+impl<L, R, IT: CType, OT> BroadcastDual for Dual<L, R, IT, OT> {
+    fn broadcast(self, broadcast: DualBroadcast) -> Result<Self, Error> {
+        let view = |spec: &Option<ViewSpec>| {
+            spec.clone()
+                .map(|spec| programs::view::view(IT::TYPE, spec))
+                .transpose()
+        };
+
+        let views = [view(&broadcast.left)?, view(&broadcast.right)?];
+
+        Ok(Self {
+            broadcast,
+            views,
+            ..self
+        })
+    }
+}
+
 // arithmetic
 impl<L, R, T: CType> Dual<L, R, T, T> {
     pub fn add(left: L, right: R) -> Result<Self, Error> {
-        let program = programs::elementwise::dual(T::TYPE, "add")?;
+        let program = programs::elementwise::dual(T::TYPE, "add", config::fast_math())?;
         Self::new(left, right, program, T::add)
     }
 
     pub fn div(left: L, right: R) -> Result<Self, Error> {
-        let program = programs::elementwise::dual(T::TYPE, "div")?;
+        let program = programs::elementwise::dual(T::TYPE, "div", config::fast_math())?;
         Self::new(left, right, program, T::div)
     }
 
     pub fn log(arg: L, exp: R) -> Result<Self, Error> {
-        let program = programs::elementwise::dual(T::TYPE, "_log")?;
+        let program = programs::elementwise::dual(T::TYPE, "_log", config::fast_math())?;
         Self::new(arg, exp, program, |a, e| {
             T::from_float(a.to_float().log(e.to_float()))
         })
     }
 
+    pub fn logaddexp(left: L, right: R) -> Result<Self, Error> {
+        let program = programs::elementwise::dual(T::TYPE, "_logaddexp", config::fast_math())?;
+        Self::new(left, right, program, |l, r| {
+            T::from_float(l.to_float().logaddexp(r.to_float()))
+        })
+    }
+
     pub fn mul(left: L, right: R) -> Result<Self, Error> {
-        let program = programs::elementwise::dual(T::TYPE, "mul")?;
+        let program = programs::elementwise::dual(T::TYPE, "mul", config::fast_math())?;
         Self::new(left, right, program, T::mul)
     }
 
     pub fn pow(left: L, right: R) -> Result<Self, Error> {
-        let program = programs::elementwise::dual(T::TYPE, "pow")?;
+        let program = programs::elementwise::dual(T::TYPE, "pow", config::fast_math())?;
         Self::new(left, right, program, T::pow)
     }
 
     pub fn rem(left: L, right: R) -> Result<Self, Error> {
         let program = if T::IS_FLOAT { "fmod" } else { "mod" };
-        let program = programs::elementwise::dual(T::TYPE, program)?;
+        let program = programs::elementwise::dual(T::TYPE, program, config::fast_math())?;
         Self::new(left, right, program, T::rem)
     }
 
+    pub fn step(arg: L, threshold: R) -> Result<Self, Error> {
+        let program = programs::elementwise::dual(T::TYPE, "_step", config::fast_math())?;
+        let op = |n, threshold| if n < threshold { T::ZERO } else { T::ONE };
+        Self::new(arg, threshold, program, op)
+    }
+
     pub fn sub(left: L, right: R) -> Result<Self, Error> {
-        let program = programs::elementwise::dual(T::TYPE, "sub")?;
+        let program = programs::elementwise::dual(T::TYPE, "sub", config::fast_math())?;
         Self::new(left, right, program, T::sub)
     }
 }
 
+// trigonometry
+impl<L, R, T: CType> Dual<L, R, T, T::Float> {
+    pub fn atan2(y: L, x: R) -> Result<Self, Error> {
+        let program = programs::elementwise::dual_float(T::TYPE, T::Float::TYPE, "atan2")?;
+        Self::new(y, x, program, |y, x| y.to_float().atan2(x.to_float()))
+    }
+
+    pub fn hypot(left: L, right: R) -> Result<Self, Error> {
+        let program = programs::elementwise::dual_float(T::TYPE, T::Float::TYPE, "hypot")?;
+        Self::new(left, right, program, |l, r| {
+            l.to_float().hypot(r.to_float())
+        })
+    }
+}
+
 // boolean operations
 impl<L, R, T: CType> Dual<L, R, T, u8> {
     pub fn and(left: L, right: R) -> Result<Self, Error> {
@@ -203,7 +347,11 @@ where
     OT: CType,
 {
     fn size(&self) -> usize {
-        self.left.size()
+        self.broadcast.size(self.left.size())
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.left.plan(), self.right.plan()]
     }
 }
 
@@ -219,6 +367,21 @@ where
     fn enqueue(&self) -> Result<Self::Buffer, Error> {
         let left = self.left.read()?.to_cl()?;
         let right = self.right.read()?.to_cl()?;
+
+        // materialize each broadcast input before zipping them
+        let size = self.size();
+        let view = |program: &Option<Program>, source: &Buffer<IT>| {
+            program
+                .as_ref()
+                .map(|program| enqueue_view(program, source, size))
+                .transpose()
+        };
+
+        let left_view = view(&self.views[0], &left)?;
+        let right_view = view(&self.views[1], &right)?;
+
+        let left = left_view.as_ref().unwrap_or(&*left);
+        let right = right_view.as_ref().unwrap_or(&*right);
         debug_assert_eq!(left.len(), right.len());
 
         let queue = OpenCL::queue(left.len(), &[left.default_queue(), right.default_queue()])?;
@@ -228,17 +391,27 @@ where
             .len(left.len())
             .build()?;
 
-        let kernel = Kernel::builder()
-            .name("dual")
+        let launch = tune::launch_config::<IT>(&queue, KernelFamily::Elementwise)?;
+
+        let mut builder = Kernel::builder();
+
+        builder
+            .name(format!("dual_vec{}", launch.vec_width))
             .program(&self.program)
             .queue(queue)
-            .global_work_size(left.len())
+            .global_work_size(launch.vec_work_size(left.len()))
+            .arg(left.len() as u64)
             .arg(&*left)
             .arg(&*right)
-            .arg(&output)
-            .build()?;
+            .arg(&output);
 
-        unsafe { kernel.enq()? }
+        if launch.wg_size > 0 {
+            builder.local_work_size(launch.wg_size);
+        }
+
+        let kernel = builder.build()?;
+
+        unsafe { events::enq(&kernel)? }
 
         Ok(output)
     }
@@ -252,8 +425,9 @@ where
     OT: CType,
 {
     fn read_value(&self, offset: usize) -> Result<OT, Error> {
-        let l = self.left.read_value(offset)?;
-        let r = self.right.read_value(offset)?;
+        let (left, right) = self.broadcast.offsets(offset);
+        let l = self.left.read_value(left)?;
+        let r = self.right.read_value(right)?;
         Ok((self.op)(l, r))
     }
 }
@@ -295,6 +469,10 @@ where
         debug_assert_eq!(self.cond.size(), self.or_else.size());
         self.cond.size()
     }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.cond.plan(), self.then.plan(), self.or_else.plan()]
+    }
 }
 
 impl<A, L, R, T> Enqueue<OpenCL, T> for Cond<A, L, R, T>
@@ -341,7 +519,7 @@ where
             .arg(&output)
             .build()?;
 
-        unsafe { kernel.enq()? }
+        unsafe { events::enq(&kernel)? }
 
         Ok(output)
     }
@@ -367,614 +545,2608 @@ where
     }
 }
 
-pub struct MatDiag<A, T> {
-    access: A,
-    dim: usize,
-    batch_size: usize,
+pub struct CondScalar<A, T> {
+    cond: A,
+    then: T,
+    or_else: T,
     program: Program,
-    dtype: PhantomData<T>,
 }
 
-impl<A, T: CType> MatDiag<A, T> {
-    pub fn new(access: A, batch_size: usize, dim: usize) -> Result<Self, Error> {
-        let program = programs::linalg::diagonal(T::TYPE)?;
+impl<A, T: CType> CondScalar<A, T> {
+    pub fn new(cond: A, then: T, or_else: T) -> Result<Self, Error> {
+        let program = programs::gather::gather_cond_scalar(T::TYPE)?;
 
         Ok(Self {
-            access,
-            batch_size,
-            dim,
+            cond,
+            then,
+            or_else,
             program,
-            dtype: PhantomData,
         })
     }
 }
 
-impl<A: Access<T>, T: CType> Op for MatDiag<A, T> {
+impl<A: Access<u8>, T: CType> Op for CondScalar<A, T> {
     fn size(&self) -> usize {
-        debug_assert_eq!(self.access.size(), self.batch_size * self.dim * self.dim);
-        self.batch_size * self.dim
+        self.cond.size()
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.cond.plan()]
     }
 }
 
-impl<A: Access<T>, T: CType> Enqueue<OpenCL, T> for MatDiag<A, T> {
+impl<A: Access<u8>, T: CType> Enqueue<OpenCL, T> for CondScalar<A, T> {
     type Buffer = Buffer<T>;
 
     fn enqueue(&self) -> Result<Self::Buffer, Error> {
-        let input = self.access.read()?.to_cl()?;
-
-        debug_assert_eq!(input.len(), self.batch_size * self.dim * self.dim);
-
-        let queue = OpenCL::queue(self.size(), &[input.default_queue()])?;
+        let cond = self.cond.read()?.to_cl()?;
+        let queue = OpenCL::queue(cond.len(), &[cond.default_queue()])?;
 
         let output = Buffer::builder()
             .queue(queue.clone())
-            .len(self.batch_size * self.dim)
+            .len(cond.len())
             .build()?;
 
         let kernel = Kernel::builder()
-            .name("diagonal")
-            .program(&self.program)
+            .name("gather_cond_scalar")
             .queue(queue)
-            .global_work_size((self.batch_size, self.dim))
-            .arg(&*input)
+            .program(&self.program)
+            .global_work_size(cond.len())
+            .arg(&*cond)
+            .arg(self.then)
+            .arg(self.or_else)
             .arg(&output)
             .build()?;
 
-        unsafe { kernel.enq()? };
+        unsafe { events::enq(&kernel)? }
 
         Ok(output)
     }
 }
 
-impl<A: Access<T>, T: CType> ReadValue<OpenCL, T> for MatDiag<A, T> {
+impl<A: Access<u8>, T: CType> ReadValue<OpenCL, T> for CondScalar<A, T> {
     fn read_value(&self, offset: usize) -> Result<T, Error> {
-        let batch = offset / self.batch_size;
-        let i = offset % self.batch_size;
-        let source_offset = (batch * self.dim * self.dim) + (i * self.dim) + i;
-        self.access.read_value(source_offset)
+        let cond = self.cond.read_value(offset)?;
+
+        if cond != 0 {
+            Ok(self.then)
+        } else {
+            Ok(self.or_else)
+        }
     }
 }
 
-pub struct MatMul<L, R, T> {
-    left: L,
-    right: R,
-    batch_size: usize,
-    dims: [usize; 3],
-    padded: [usize; 3],
-    pad_matrices: Program,
-    matmul: Program,
+pub struct Conv<I, K, T> {
+    input: I,
+    kernel: K,
+    input_shape: [usize; 5],
+    kernel_shape: [usize; 5],
+    output_shape: [usize; 5],
+    spec: ConvSpec<3>,
+    program: Program,
     dtype: PhantomData<T>,
 }
 
-impl<L, R, T> MatMul<L, R, T>
-where
-    T: CType,
-{
-    pub fn new(left: L, right: R, dims: [usize; 4]) -> Result<Self, Error> {
-        let pad_matrices = programs::linalg::pad_matrices(T::TYPE)?;
-        let matmul = programs::linalg::matmul(T::TYPE)?;
-
-        let [batch_size, a, b, c] = dims;
-        assert!(batch_size > 0);
-
-        let dims = [a, b, c];
-
-        let padded = [
-            pad_dim(a, TILE_SIZE),
-            pad_dim(b, TILE_SIZE),
-            pad_dim(c, TILE_SIZE),
-        ];
+impl<I, K, T: CType> Conv<I, K, T> {
+    pub fn new(
+        input: I,
+        kernel: K,
+        input_shape: [usize; 5],
+        kernel_shape: [usize; 5],
+        spec: ConvSpec<3>,
+    ) -> Result<Self, Error> {
+        let output_shape = spec.output_shape(&input_shape, &kernel_shape)?;
+        let program = programs::conv::conv(T::TYPE)?;
 
         Ok(Self {
-            left,
-            right,
-            batch_size,
-            dims,
-            padded,
-            pad_matrices,
-            matmul,
+            input,
+            kernel,
+            input_shape,
+            kernel_shape,
+            output_shape: [
+                output_shape[0],
+                output_shape[1],
+                output_shape[2],
+                output_shape[3],
+                output_shape[4],
+            ],
+            spec,
+            program,
             dtype: PhantomData,
         })
     }
+}
 
-    fn matmul(
-        &self,
-        left: &Buffer<T>,
-        right: &Buffer<T>,
-        dims: [usize; 3],
-    ) -> Result<Buffer<T>, Error> {
-        let [a, b, c] = dims;
-
-        assert_eq!(self.batch_size * a * b, left.len());
-        assert_eq!(self.batch_size * b * c, right.len());
-
-        let queue = OpenCL::queue(
-            self.batch_size * a * b * c,
-            &[left.default_queue(), right.default_queue()],
-        )?;
-
-        let output = Buffer::builder()
-            .queue(queue.clone())
-            .len(self.batch_size * a * c)
-            .fill_val(T::ZERO)
-            .build()?;
-
-        let dim4 = [a as u64, b as u64, c as u64, self.batch_size as u64];
-
-        let kernel = Kernel::builder()
-            .name("matmul")
-            .program(&self.matmul)
-            .queue(queue)
-            .global_work_size((
-                self.batch_size,
-                a.div_ceil(TILE_SIZE),
-                c.div_ceil(TILE_SIZE),
-            ))
-            .arg(ocl::core::Ulong4::from(dim4))
-            .arg(b.div_ceil(TILE_SIZE))
-            .arg(left)
-            .arg(right)
-            .arg(&output)
-            .build()?;
-
-        unsafe { kernel.enq()? }
+impl<I, K, T> Op for Conv<I, K, T>
+where
+    I: Access<T>,
+    K: Access<T>,
+    T: CType,
+{
+    fn size(&self) -> usize {
+        self.output_shape.iter().product()
+    }
 
-        Ok(output)
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.input.plan(), self.kernel.plan()]
     }
+}
 
-    fn pad_matrices<'a>(
-        &self,
-        batch: &Buffer<T>,
-        dims_in: [usize; 2],
-        dims_out: [usize; 2],
-    ) -> Result<Buffer<T>, Error> {
-        if dims_in == dims_out {
-            return Ok(batch.clone());
-        }
+impl<I, K, T> Enqueue<OpenCL, T> for Conv<I, K, T>
+where
+    I: Access<T>,
+    K: Access<T>,
+    T: CType,
+{
+    type Buffer = Buffer<T>;
 
-        assert_eq!(batch.len(), self.batch_size * dims_in[0] * dims_in[1]);
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let input = self.input.read()?.to_cl()?;
+        let kernel = self.kernel.read()?.to_cl()?;
 
-        let queue = OpenCL::queue(batch.len(), &[batch.default_queue()])?;
+        debug_assert_eq!(input.len(), self.input_shape.iter().product::<usize>());
+        debug_assert_eq!(kernel.len(), self.kernel_shape.iter().product::<usize>());
+
+        let queue = OpenCL::queue(
+            self.size(),
+            &[input.default_queue(), kernel.default_queue()],
+        )?;
 
         let output = Buffer::builder()
             .queue(queue.clone())
-            .len(self.batch_size * dims_out[0] * dims_out[1])
-            .fill_val(T::ZERO)
+            .len(self.size())
             .build()?;
 
-        let gws = if dims_in.iter().product::<usize>() <= dims_out.iter().product::<usize>() {
-            (self.batch_size, dims_in[0], dims_in[1])
-        } else {
-            (self.batch_size, dims_out[0], dims_out[1])
-        };
+        let [batch, out_channels, ..] = self.output_shape;
+        let planes = batch * out_channels;
+        let plane = self.output_shape[2..].iter().product::<usize>();
+        let padded = WG_SIZE * plane.div_ceil(WG_SIZE);
 
-        let strides_in = [(dims_in[0] * dims_in[1]) as u64, dims_in[1] as u64];
+        // the channel axes are passed separately so that each spatial shape fits in a ulong4
+        let spatial = |shape: [usize; 5]| {
+            ocl::core::Ulong4::from([shape[2] as u64, shape[3] as u64, shape[4] as u64, 1])
+        };
 
-        let strides_out = [(dims_out[0] * dims_out[1]) as u64, dims_out[1] as u64];
+        let triple = |dims: &[usize; 3]| {
+            ocl::core::Ulong4::from([dims[0] as u64, dims[1] as u64, dims[2] as u64, 0])
+        };
 
         let kernel = Kernel::builder()
-            .name("pad_matrices")
-            .program(&self.pad_matrices)
+            .name("conv")
+            .program(&self.program)
             .queue(queue)
-            .global_work_size(gws)
-            .arg(ocl::core::Ulong2::from(strides_in))
-            .arg(ocl::core::Ulong2::from(strides_out))
-            .arg(batch)
+            .global_work_size(planes * padded)
+            .local_work_size(WG_SIZE)
+            .arg(self.input_shape[1] as u64)
+            .arg(out_channels as u64)
+            .arg(self.kernel_shape[1] as u64)
+            .arg(self.spec.groups() as u64)
+            .arg(spatial(self.input_shape))
+            .arg(spatial(self.kernel_shape))
+            .arg(spatial(self.output_shape))
+            .arg(triple(self.spec.stride()))
+            .arg(triple(self.spec.padding()))
+            .arg(triple(self.spec.dilation()))
+            .arg(&*input)
+            .arg(&*kernel)
             .arg(&output)
+            .arg_local::<T>(WG_SIZE)
             .build()?;
 
-        unsafe { kernel.enq()? }
+        unsafe { events::enq(&kernel)? }
 
-        Ok(output.into())
+        Ok(output)
     }
 }
 
-impl<L, R, T> Op for MatMul<L, R, T>
+impl<I, K, T> ReadValue<OpenCL, T> for Conv<I, K, T>
 where
-    L: Send + Sync,
-    R: Send + Sync,
-    T: Send + Sync,
+    I: Access<T>,
+    K: Access<T>,
+    T: CType,
 {
-    fn size(&self) -> usize {
-        self.batch_size * self.dims[0] * self.dims[2]
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        self.spec
+            .taps(
+                self.input_shape,
+                self.kernel_shape,
+                self.output_shape,
+                offset,
+            )
+            .try_fold(T::ZERO, |sum, (i, k)| {
+                let (input, kernel) = (self.input.read_value(i)?, self.kernel.read_value(k)?);
+                Ok(T::add(sum, T::mul(input, kernel)))
+            })
     }
 }
 
-impl<L, R, T> Enqueue<OpenCL, T> for MatMul<L, R, T>
-where
-    L: Access<T>,
-    R: Access<T>,
-    T: CType,
-{
-    type Buffer = Buffer<T>;
+pub struct ConvTranspose<I, K, T> {
+    input: I,
+    kernel: K,
+    input_shape: [usize; 4],
+    kernel_shape: [usize; 4],
+    output_shape: [usize; 4],
+    spec: ConvSpec<2>,
+    program: Program,
+    dtype: PhantomData<T>,
+}
 
-    fn enqueue(&self) -> Result<Self::Buffer, Error> {
-        let [a, b, c] = self.dims;
-        let [a_pad, b_pad, c_pad] = self.padded;
+impl<I, K, T: CType> ConvTranspose<I, K, T> {
+    pub fn new(
+        input: I,
+        kernel: K,
+        input_shape: [usize; 4],
+        kernel_shape: [usize; 4],
+        spec: ConvSpec<2>,
+    ) -> Result<Self, Error> {
+        let output_shape = spec.transpose_output_shape(&input_shape, &kernel_shape)?;
+        let program = programs::conv::conv_transpose2d(T::TYPE)?;
 
-        let left = self.left.read()?.to_cl()?;
-        let right = self.right.read()?.to_cl()?;
+        Ok(Self {
+            input,
+            kernel,
+            input_shape,
+            kernel_shape,
+            output_shape: [
+                output_shape[0],
+                output_shape[1],
+                output_shape[2],
+                output_shape[3],
+            ],
+            spec,
+            program,
+            dtype: PhantomData,
+        })
+    }
+}
+
+impl<I, K, T> Op for ConvTranspose<I, K, T>
+where
+    I: Access<T>,
+    K: Access<T>,
+    T: CType,
+{
+    fn size(&self) -> usize {
+        self.output_shape.iter().product()
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.input.plan(), self.kernel.plan()]
+    }
+}
+
+impl<I, K, T> Enqueue<OpenCL, T> for ConvTranspose<I, K, T>
+where
+    I: Access<T>,
+    K: Access<T>,
+    T: CType,
+{
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let input = self.input.read()?.to_cl()?;
+        let kernel = self.kernel.read()?.to_cl()?;
+
+        debug_assert_eq!(input.len(), self.input_shape.iter().product::<usize>());
+        debug_assert_eq!(kernel.len(), self.kernel_shape.iter().product::<usize>());
+
+        let queue = OpenCL::queue(
+            self.size(),
+            &[input.default_queue(), kernel.default_queue()],
+        )?;
+
+        let output = Buffer::builder()
+            .queue(queue.clone())
+            .len(self.size())
+            .build()?;
 
-        assert_eq!(self.batch_size * a * b, left.len());
-        assert_eq!(self.batch_size * b * c, right.len());
+        let dims = |shape: [usize; 4]| ocl::core::Ulong4::from(shape.map(|dim| dim as u64));
+        let pair = |pair: &[usize; 2]| ocl::core::Ulong2::from(pair.map(|n| n as u64));
 
-        let left = self.pad_matrices(&left, [a, b], [a_pad, b_pad])?;
-        let right = self.pad_matrices(&right, [b, c], [b_pad, c_pad])?;
+        let kernel = Kernel::builder()
+            .name("conv_transpose2d")
+            .program(&self.program)
+            .queue(queue)
+            .global_work_size(self.size())
+            .arg(dims(self.input_shape))
+            .arg(dims(self.kernel_shape))
+            .arg(dims(self.output_shape))
+            .arg(pair(self.spec.stride()))
+            .arg(pair(self.spec.padding()))
+            .arg(pair(self.spec.dilation()))
+            .arg(self.spec.groups() as u64)
+            .arg(&*input)
+            .arg(&*kernel)
+            .arg(&output)
+            .build()?;
 
-        let product = self.matmul(&left, &right, self.padded)?;
+        unsafe { events::enq(&kernel)? }
 
-        self.pad_matrices(&product, [a_pad, c_pad], [a, c])
+        Ok(output)
     }
 }
 
-impl<L, R, T> ReadValue<OpenCL, T> for MatMul<L, R, T>
+impl<I, K, T> ReadValue<OpenCL, T> for ConvTranspose<I, K, T>
 where
-    L: Access<T>,
-    R: Access<T>,
+    I: Access<T>,
+    K: Access<T>,
     T: CType,
 {
-    fn read_value(&self, _offset: usize) -> Result<T, Error> {
-        Err(Error::Bounds(
-            "reading an individual value from a matrix multiplication is not implemented"
-                .to_string(),
-        ))
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        self.spec
+            .transpose_taps(
+                self.input_shape,
+                self.kernel_shape,
+                self.output_shape,
+                offset,
+            )
+            .try_fold(T::ZERO, |sum, (i, k)| {
+                let (input, kernel) = (self.input.read_value(i)?, self.kernel.read_value(k)?);
+                Ok(T::add(sum, T::mul(input, kernel)))
+            })
     }
 }
 
-pub struct Linear<T> {
-    start: T,
-    step: f64,
-    size: usize,
+pub struct MatchedFilter<I, K, T> {
+    input: I,
+    template: K,
+    dims: [usize; 3],
     program: Program,
+    dtype: PhantomData<T>,
 }
 
-impl<T: CType> Linear<T> {
-    pub fn new(start: T, step: f64, size: usize) -> Result<Self, Error> {
-        programs::constructors::range(T::TYPE).map(|program| Self {
-            start,
-            step,
-            size,
+impl<I, K, T: CType> MatchedFilter<I, K, T> {
+    pub fn new(input: I, template: K, dims: [usize; 3]) -> Result<Self, Error> {
+        let program = programs::conv::matched_filter(T::TYPE, T::Float::TYPE)?;
+
+        Ok(Self {
+            input,
+            template,
+            dims,
             program,
+            dtype: PhantomData,
         })
     }
 }
 
-impl<T: Send + Sync> Op for Linear<T> {
+impl<I, K, T> Op for MatchedFilter<I, K, T>
+where
+    I: Access<T>,
+    K: Access<T>,
+    T: CType,
+{
     fn size(&self) -> usize {
-        self.size
+        let [rows, len, width] = self.dims;
+        rows * (len - width + 1)
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.input.plan(), self.template.plan()]
     }
 }
 
-impl<T: CType> Enqueue<OpenCL, T> for Linear<T> {
-    type Buffer = Buffer<T>;
+impl<I, K, T> Enqueue<OpenCL, T::Float> for MatchedFilter<I, K, T>
+where
+    I: Access<T>,
+    K: Access<T>,
+    T: CType,
+{
+    type Buffer = Buffer<T::Float>;
 
     fn enqueue(&self) -> Result<Self::Buffer, Error> {
-        let queue = OpenCL::queue(self.size, &[])?;
+        let [_rows, len, width] = self.dims;
 
-        let buffer = Buffer::builder()
+        let input = self.input.read()?.to_cl()?;
+        let template = self.template.read()?.to_cl()?;
+
+        debug_assert_eq!(input.len(), self.dims[0] * len);
+        debug_assert_eq!(template.len(), width);
+
+        let queue = OpenCL::queue(
+            self.size(),
+            &[input.default_queue(), template.default_queue()],
+        )?;
+
+        let output = Buffer::builder()
             .queue(queue.clone())
-            .len(self.size)
+            .len(self.size())
             .build()?;
 
+        // every work item in a group helps to cache each tile of the template in local memory
         let kernel = Kernel::builder()
-            .name("range")
-            .queue(queue)
+            .name("matched_filter")
             .program(&self.program)
-            .global_work_size(self.size)
-            .arg(self.start)
-            .arg(self.step)
-            .arg(&buffer)
+            .queue(queue)
+            .global_work_size(WG_SIZE * self.size().div_ceil(WG_SIZE))
+            .local_work_size(WG_SIZE)
+            .arg(len as u64)
+            .arg(width as u64)
+            .arg(self.size() as u64)
+            .arg(&*input)
+            .arg(&*template)
+            .arg(&output)
+            .arg_local::<T::Float>(WG_SIZE)
             .build()?;
 
-        unsafe { kernel.enq()? }
+        unsafe { events::enq(&kernel)? }
 
-        Ok(buffer)
+        Ok(output)
     }
 }
 
-impl<T: CType> ReadValue<OpenCL, T> for Linear<T> {
-    fn read_value(&self, offset: usize) -> Result<T, Error> {
-        Ok(T::add(self.start, T::from_f64((offset as f64) * self.step)))
+impl<I, K, T> ReadValue<OpenCL, T::Float> for MatchedFilter<I, K, T>
+where
+    I: Access<T>,
+    K: Access<T>,
+    T: CType,
+{
+    fn read_value(&self, offset: usize) -> Result<T::Float, Error> {
+        host::ops::read_matched_filter(&self.input, &self.template, self.dims, offset)
     }
 }
 
-pub struct RandomNormal {
+pub struct MatDiag<A, T> {
+    access: A,
+    dim: usize,
+    batch_size: usize,
     program: Program,
-    size: usize,
+    dtype: PhantomData<T>,
 }
 
-impl RandomNormal {
-    pub fn new(size: usize) -> Result<Self, Error> {
-        programs::constructors::random_normal().map(|program| Self { program, size })
+impl<A, T: CType> MatDiag<A, T> {
+    pub fn new(access: A, batch_size: usize, dim: usize) -> Result<Self, Error> {
+        let program = programs::linalg::diagonal(T::TYPE)?;
+
+        Ok(Self {
+            access,
+            batch_size,
+            dim,
+            program,
+            dtype: PhantomData,
+        })
     }
 }
 
-impl Op for RandomNormal {
+impl<A: Access<T>, T: CType> Op for MatDiag<A, T> {
     fn size(&self) -> usize {
-        self.size
+        debug_assert_eq!(self.access.size(), self.batch_size * self.dim * self.dim);
+        self.batch_size * self.dim
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan()]
     }
 }
 
-impl Enqueue<OpenCL, f32> for RandomNormal {
-    type Buffer = Buffer<f32>;
+impl<A: Access<T>, T: CType> Enqueue<OpenCL, T> for MatDiag<A, T> {
+    type Buffer = Buffer<T>;
 
     fn enqueue(&self) -> Result<Self::Buffer, Error> {
-        let queue = OpenCL::queue(self.size, &[])?;
-        let seed: u32 = rand::thread_rng().gen();
+        let input = self.access.read()?.to_cl()?;
 
-        let buffer = Buffer::builder()
+        debug_assert_eq!(input.len(), self.batch_size * self.dim * self.dim);
+
+        let queue = OpenCL::queue(self.size(), &[input.default_queue()])?;
+
+        let output = Buffer::builder()
             .queue(queue.clone())
-            .len(WG_SIZE * self.size().div_ceil(WG_SIZE))
+            .len(self.batch_size * self.dim)
             .build()?;
 
         let kernel = Kernel::builder()
-            .name("random_normal")
-            .queue(queue.clone())
+            .name("diagonal")
             .program(&self.program)
-            .global_work_size(buffer.len())
-            .local_work_size(WG_SIZE)
-            .arg(u64::try_from(seed).expect("seed"))
-            .arg(&buffer)
-            .arg_local::<f32>(WG_SIZE)
+            .queue(queue)
+            .global_work_size((self.batch_size, self.dim))
+            .arg(&*input)
+            .arg(&output)
             .build()?;
 
-        unsafe { kernel.enq()? }
-
-        if buffer.len() == self.size {
-            Ok(buffer)
-        } else {
-            let output = Buffer::builder().queue(queue).len(self.size).build()?;
-
-            buffer.copy(&output, Some(0), Some(self.size)).enq()?;
+        unsafe { events::enq(&kernel)? };
 
-            Ok(output)
-        }
+        Ok(output)
     }
 }
 
-impl ReadValue<OpenCL, f32> for RandomNormal {
-    fn read_value(&self, _offset: usize) -> Result<f32, Error> {
-        Err(Error::Bounds(
-            "cannot read an individual value from a random normal distribution".to_string(),
-        ))
+impl<A: Access<T>, T: CType> ReadValue<OpenCL, T> for MatDiag<A, T> {
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        let batch = offset / self.batch_size;
+        let i = offset % self.batch_size;
+        let source_offset = (batch * self.dim * self.dim) + (i * self.dim) + i;
+        self.access.read_value(source_offset)
     }
 }
 
-pub struct RandomUniform {
+pub struct MatTrace<A, T> {
+    access: A,
+    dim: usize,
+    batch_size: usize,
     program: Program,
-    size: usize,
+    dtype: PhantomData<T>,
 }
 
-impl RandomUniform {
-    pub fn new(size: usize) -> Result<Self, Error> {
-        programs::constructors::random_uniform().map(|program| Self { program, size })
+impl<A, T: CType> MatTrace<A, T> {
+    pub fn new(access: A, batch_size: usize, dim: usize) -> Result<Self, Error> {
+        let program = programs::linalg::trace(T::TYPE)?;
+
+        Ok(Self {
+            access,
+            batch_size,
+            dim,
+            program,
+            dtype: PhantomData,
+        })
     }
 }
 
-impl Op for RandomUniform {
+impl<A: Access<T>, T: CType> Op for MatTrace<A, T> {
     fn size(&self) -> usize {
-        self.size
+        debug_assert_eq!(self.access.size(), self.batch_size * self.dim * self.dim);
+        self.batch_size
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan()]
     }
 }
 
-impl Enqueue<OpenCL, f32> for RandomUniform {
-    type Buffer = Buffer<f32>;
+impl<A: Access<T>, T: CType> Enqueue<OpenCL, T> for MatTrace<A, T> {
+    type Buffer = Buffer<T>;
 
     fn enqueue(&self) -> Result<Self::Buffer, Error> {
-        let queue = OpenCL::queue(self.size, &[])?;
-        let seed: u32 = random();
+        // the trace of an empty matrix is zero
+        if self.dim == 0 {
+            let output = Buffer::builder()
+                .context(OpenCL::context())
+                .len(self.batch_size)
+                .fill_val(T::ZERO)
+                .build()?;
+
+            return Ok(output);
+        }
+
+        let input = self.access.read()?.to_cl()?;
+
+        debug_assert_eq!(input.len(), self.batch_size * self.dim * self.dim);
+
+        let queue = OpenCL::queue(input.len(), &[input.default_queue()])?;
 
         let output = Buffer::builder()
             .queue(queue.clone())
-            .len(self.size)
+            .len(self.batch_size)
             .build()?;
 
         let kernel = Kernel::builder()
-            .name("random_uniform")
-            .queue(queue)
+            .name("trace")
             .program(&self.program)
-            .global_work_size(output.len())
-            .arg(seed as u64)
+            .queue(queue)
+            .global_work_size(self.batch_size)
+            .arg(self.dim as u64)
+            .arg(&*input)
             .arg(&output)
             .build()?;
 
-        unsafe { kernel.enq()? }
+        unsafe { events::enq(&kernel)? };
 
         Ok(output)
     }
 }
 
-impl ReadValue<OpenCL, f32> for RandomUniform {
-    fn read_value(&self, _offset: usize) -> Result<f32, Error> {
-        Ok(random())
+impl<A: Access<T>, T: CType> ReadValue<OpenCL, T> for MatTrace<A, T> {
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        crate::host::ops::read_trace(&self.access, self.dim, offset)
     }
 }
 
-pub struct Reduce<A, T: CType> {
+pub struct DiagFrom<A, T> {
     access: A,
-    stride: usize,
-    fold: Program,
-    reduce: Program,
-    reduce_all: fn(OpenCL, AccessBuf<Buffer<T>>) -> Result<T, Error>,
-    id: T,
+    dim: usize,
+    batch_size: usize,
+    program: Program,
+    dtype: PhantomData<T>,
 }
 
-impl<A, T: CType> Reduce<A, T> {
-    fn new(
-        access: A,
-        stride: usize,
-        reduce: &'static str,
-        reduce_all: fn(OpenCL, AccessBuf<Buffer<T>>) -> Result<T, Error>,
-        id: T,
-    ) -> Result<Self, Error> {
-        let fold = programs::reduce::fold_axis(T::TYPE, reduce)?;
-        let reduce = programs::reduce::reduce_axis(T::TYPE, reduce)?;
+impl<A, T: CType> DiagFrom<A, T> {
+    pub fn new(access: A, batch_size: usize, dim: usize) -> Result<Self, Error> {
+        let program = programs::linalg::diag_from(T::TYPE)?;
 
         Ok(Self {
             access,
-            stride,
-            fold,
-            reduce,
-            reduce_all,
-            id,
+            batch_size,
+            dim,
+            program,
+            dtype: PhantomData,
         })
     }
+}
 
-    pub fn max(access: A, stride: usize) -> Result<Self, Error> {
-        Self::new(
-            access,
-            stride,
-            "max",
-            <OpenCL as ReduceAll<AccessBuf<Buffer<T>>, T>>::max,
-            T::MIN,
-        )
+impl<A: Access<T>, T: CType> Op for DiagFrom<A, T> {
+    fn size(&self) -> usize {
+        debug_assert_eq!(self.access.size(), self.batch_size * self.dim);
+        self.batch_size * self.dim * self.dim
     }
 
-    pub fn min(access: A, stride: usize) -> Result<Self, Error> {
-        Self::new(
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan()]
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<OpenCL, T> for DiagFrom<A, T> {
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let input = self.access.read()?.to_cl()?;
+
+        debug_assert_eq!(input.len(), self.batch_size * self.dim);
+
+        let queue = OpenCL::queue(self.size(), &[input.default_queue()])?;
+
+        let output = Buffer::builder()
+            .queue(queue.clone())
+            .len(self.size())
+            .build()?;
+
+        let kernel = Kernel::builder()
+            .name("diag_from")
+            .program(&self.program)
+            .queue(queue)
+            .global_work_size(self.size())
+            .arg(self.dim as u64)
+            .arg(&*input)
+            .arg(&output)
+            .build()?;
+
+        unsafe { events::enq(&kernel)? };
+
+        Ok(output)
+    }
+}
+
+impl<A: Access<T>, T: CType> ReadValue<OpenCL, T> for DiagFrom<A, T> {
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        let batch = offset / (self.dim * self.dim);
+        let (row, col) = ((offset / self.dim) % self.dim, offset % self.dim);
+
+        if row == col {
+            self.access.read_value((batch * self.dim) + row)
+        } else {
+            Ok(T::ZERO)
+        }
+    }
+}
+
+pub struct Inverse<A, T> {
+    access: A,
+    batch_size: usize,
+    dim: usize,
+    program: Program,
+    dtype: PhantomData<T>,
+}
+
+impl<A, T: CType> Inverse<A, T> {
+    pub fn new(access: A, batch_size: usize, dim: usize) -> Result<Self, Error> {
+        let program = programs::linalg::inverse(T::TYPE, T::Float::TYPE)?;
+
+        Ok(Self {
             access,
-            stride,
-            "min",
-            <OpenCL as ReduceAll<AccessBuf<Buffer<T>>, T>>::min,
-            T::MAX,
-        )
+            batch_size,
+            dim,
+            program,
+            dtype: PhantomData,
+        })
+    }
+}
+
+impl<A: Access<T>, T: CType> Op for Inverse<A, T> {
+    fn size(&self) -> usize {
+        debug_assert_eq!(self.access.size(), self.batch_size * self.dim * self.dim);
+        self.batch_size * self.dim * self.dim
     }
 
-    pub fn product(access: A, stride: usize) -> Result<Self, Error> {
-        Self::new(
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan()]
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<OpenCL, T::Float> for Inverse<A, T> {
+    type Buffer = Buffer<T::Float>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let input = self.access.read()?.to_cl()?;
+
+        debug_assert_eq!(input.len(), self.size());
+
+        let queue = OpenCL::queue(self.size(), &[input.default_queue()])?;
+
+        let lu: Buffer<T::Float> = Buffer::builder()
+            .queue(queue.clone())
+            .len(self.size())
+            .build()?;
+
+        let pivots: Buffer<u64> = Buffer::builder()
+            .queue(queue.clone())
+            .len(self.batch_size * self.dim)
+            .build()?;
+
+        let output = Buffer::builder()
+            .queue(queue.clone())
+            .len(self.size())
+            .build()?;
+
+        // one work item factors and inverts each matrix
+        let kernel = Kernel::builder()
+            .name("inverse")
+            .program(&self.program)
+            .queue(queue)
+            .global_work_size(self.batch_size)
+            .arg(self.dim as u64)
+            .arg(&*input)
+            .arg(&lu)
+            .arg(&pivots)
+            .arg(&output)
+            .build()?;
+
+        unsafe { events::enq(&kernel)? };
+
+        Ok(output)
+    }
+}
+
+impl<A: Access<T>, T: CType> ReadValue<OpenCL, T::Float> for Inverse<A, T> {
+    fn read_value(&self, offset: usize) -> Result<T::Float, Error> {
+        host::ops::read_inverse(&self.access, self.dim, offset)
+    }
+}
+
+pub struct Decompose<A, T: CType> {
+    access: A,
+    factor: Factor,
+    batch_size: usize,
+    dims: [usize; 2],
+    factorization: Factorization<T>,
+    program: Program,
+}
+
+impl<A, T: CType> Decompose<A, T> {
+    pub fn new(
+        access: A,
+        factor: Factor,
+        batch_size: usize,
+        dims: [usize; 2],
+        factorization: Factorization<T>,
+    ) -> Result<Self, Error> {
+        let program = programs::linalg::decompose(T::TYPE, T::Float::TYPE, factor)?;
+
+        Ok(Self {
             access,
-            stride,
-            "mul",
-            <OpenCL as ReduceAll<AccessBuf<Buffer<T>>, T>>::product,
-            T::ONE,
+            factor,
+            batch_size,
+            dims,
+            factorization,
+            program,
+        })
+    }
+}
+
+impl<A: Access<T>, T: CType> Op for Decompose<A, T> {
+    fn size(&self) -> usize {
+        debug_assert_eq!(
+            self.access.size(),
+            self.batch_size * self.dims[0] * self.dims[1]
+        );
+
+        self.batch_size * self.factor.shape(self.dims).iter().product::<usize>()
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan()]
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<OpenCL, T::Float> for Decompose<A, T> {
+    type Buffer = Buffer<T::Float>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let input = self.access.read()?.to_cl()?;
+        let [rows, cols] = self.dims;
+
+        debug_assert_eq!(input.len(), self.batch_size * rows * cols);
+
+        let queue = OpenCL::queue(self.size(), &[input.default_queue()])?;
+
+        // the scratch space which each work item needs to factor its matrix
+        let (work, pivots) = match self.factor {
+            Factor::LuP | Factor::LuL | Factor::LuU => (rows * rows, rows),
+            Factor::QrQ | Factor::QrR => ((rows * cols) + (rows * rows) + rows, 1),
+            Factor::Cholesky => (1, 1),
+            Factor::EighValues | Factor::EighVectors => (2 * rows * rows, rows),
+        };
+
+        let work: Buffer<T::Float> = Buffer::builder()
+            .queue(queue.clone())
+            .len(self.batch_size * work)
+            .build()?;
+
+        let pivots: Buffer<u64> = Buffer::builder()
+            .queue(queue.clone())
+            .len(self.batch_size * pivots)
+            .build()?;
+
+        let output = Buffer::builder()
+            .queue(queue.clone())
+            .len(self.size())
+            .build()?;
+
+        // one work item factors each matrix
+        let kernel = Kernel::builder()
+            .name("decompose")
+            .program(&self.program)
+            .queue(queue)
+            .global_work_size(self.batch_size)
+            .arg(rows as u64)
+            .arg(cols as u64)
+            .arg(&*input)
+            .arg(&work)
+            .arg(&pivots)
+            .arg(&output)
+            .build()?;
+
+        unsafe { events::enq(&kernel)? };
+
+        Ok(output)
+    }
+}
+
+impl<A: Access<T>, T: CType> ReadValue<OpenCL, T::Float> for Decompose<A, T> {
+    fn read_value(&self, offset: usize) -> Result<T::Float, Error> {
+        host::ops::read_decompose(
+            &self.access,
+            self.factor,
+            self.dims,
+            &self.factorization,
+            offset,
         )
     }
+}
 
-    pub fn sum(access: A, stride: usize) -> Result<Self, Error> {
-        Self::new(
+pub struct ReduceArg<A, T> {
+    access: A,
+    stride: usize,
+    program: Program,
+    replace: fn(&T, &T) -> bool,
+}
+
+impl<A, T: CType> ReduceArg<A, T> {
+    fn new(
+        access: A,
+        stride: usize,
+        cmp: &'static str,
+        replace: fn(&T, &T) -> bool,
+    ) -> Result<Self, Error> {
+        let program = programs::reduce::reduce_arg(T::TYPE, cmp)?;
+
+        Ok(Self {
             access,
             stride,
-            "add",
-            <OpenCL as ReduceAll<AccessBuf<Buffer<T>>, T>>::sum,
-            T::ZERO,
-        )
+            program,
+            replace,
+        })
     }
 
-    fn fold(
-        &self,
-        queue: Queue,
-        input: &Buffer<T>,
-        reduce_dim: usize,
-        target_dim: usize,
-    ) -> Result<Buffer<T>, Error> {
-        let output_size = (input.len() / reduce_dim) * target_dim;
+    pub fn argmax(access: A, stride: usize) -> Result<Self, Error> {
+        Self::new(access, stride, ">", |n, best| n > best)
+    }
+
+    pub fn argmin(access: A, stride: usize) -> Result<Self, Error> {
+        Self::new(access, stride, "<", |n, best| n < best)
+    }
+}
+
+impl<A: Access<T>, T: CType> Op for ReduceArg<A, T> {
+    fn size(&self) -> usize {
+        debug_assert_eq!(self.access.size() % self.stride, 0);
+        self.access.size() / self.stride
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan()]
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<OpenCL, u64> for ReduceArg<A, T> {
+    type Buffer = Buffer<u64>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let input = self.access.read()?.to_cl()?;
+        debug_assert_eq!(input.len() % self.stride, 0);
+
+        let queue = OpenCL::queue(input.len(), &[input.default_queue()])?;
 
         let output = Buffer::builder()
             .queue(queue.clone())
-            .len(output_size)
-            .fill_val(T::ZERO)
+            .len(self.size())
             .build()?;
 
         let kernel = Kernel::builder()
-            .name("fold_axis")
-            .program(&self.fold)
+            .name("reduce_arg")
+            .program(&self.program)
             .queue(queue)
-            .global_work_size(output_size)
-            .arg(reduce_dim as u64)
-            .arg(target_dim as u64)
-            .arg(self.id)
-            .arg(input)
+            .global_work_size(self.size())
+            .arg(self.stride as u64)
+            .arg(&*input)
             .arg(&output)
             .build()?;
 
-        unsafe { kernel.enq()? }
+        unsafe { events::enq(&kernel)? };
 
         Ok(output)
     }
+}
 
-    fn reduce(
-        &self,
-        queue: Queue,
-        input: &Buffer<T>,
+impl<A: Access<T>, T: CType> ReadValue<OpenCL, u64> for ReduceArg<A, T> {
+    fn read_value(&self, offset: usize) -> Result<u64, Error> {
+        host::ops::read_arg(&self.access, self.stride, self.replace, offset)
+    }
+}
+
+pub struct ReduceMoment<A, T: CType> {
+    access: A,
+    stride: usize,
+    program: Program,
+    kernel: &'static str,
+    reduce: fn(&[T]) -> T::Float,
+}
+
+impl<A, T: CType> ReduceMoment<A, T> {
+    fn new(
+        access: A,
         stride: usize,
-        wg_size: usize,
-    ) -> Result<Buffer<T>, Error> {
-        debug_assert_eq!(input.len() % stride, 0);
+        kernel: &'static str,
+        reduce: fn(&[T]) -> T::Float,
+    ) -> Result<Self, Error> {
+        let program = programs::reduce::moment(T::TYPE, T::Float::TYPE)?;
 
-        let output = Buffer::builder()
-            .queue(queue.clone())
-            .len(input.len() / stride)
-            .fill_val(T::ZERO)
+        Ok(Self {
+            access,
+            stride,
+            program,
+            kernel,
+            reduce,
+        })
+    }
+
+    pub fn mean(access: A, stride: usize) -> Result<Self, Error> {
+        Self::new(access, stride, "reduce_mean", host::ops::moment_mean)
+    }
+
+    pub fn var(access: A, stride: usize) -> Result<Self, Error> {
+        Self::new(access, stride, "reduce_var", host::ops::moment_var)
+    }
+
+    pub fn std(access: A, stride: usize) -> Result<Self, Error> {
+        Self::new(access, stride, "reduce_std", host::ops::moment_std)
+    }
+}
+
+impl<A: Access<T>, T: CType> Op for ReduceMoment<A, T> {
+    fn size(&self) -> usize {
+        debug_assert_eq!(self.access.size() % self.stride, 0);
+        self.access.size() / self.stride
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan()]
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<OpenCL, T::Float> for ReduceMoment<A, T> {
+    type Buffer = Buffer<T::Float>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let input = self.access.read()?.to_cl()?;
+        debug_assert_eq!(input.len() % self.stride, 0);
+
+        let queue = OpenCL::queue(input.len(), &[input.default_queue()])?;
+
+        let output = Buffer::builder()
+            .queue(queue.clone())
+            .len(self.size())
+            .build()?;
+
+        let kernel = Kernel::builder()
+            .name(self.kernel)
+            .program(&self.program)
+            .queue(queue)
+            .global_work_size(self.size())
+            .arg(self.stride as u64)
+            .arg(&*input)
+            .arg(&output)
+            .build()?;
+
+        unsafe { events::enq(&kernel)? };
+
+        Ok(output)
+    }
+}
+
+impl<A: Access<T>, T: CType> ReadValue<OpenCL, T::Float> for ReduceMoment<A, T> {
+    fn read_value(&self, offset: usize) -> Result<T::Float, Error> {
+        host::ops::read_moment(&self.access, self.stride, self.reduce, offset)
+    }
+}
+
+pub struct Compact<A, P, T> {
+    access: A,
+    positions: P,
+    size: usize,
+    program: Program,
+    dtype: PhantomData<T>,
+}
+
+impl<A, P, T: CType> Compact<A, P, T> {
+    pub fn new(access: A, positions: P, size: usize) -> Result<Self, Error> {
+        let program = programs::gather::compact(T::TYPE)?;
+
+        Ok(Self {
+            access,
+            positions,
+            size,
+            program,
+            dtype: PhantomData,
+        })
+    }
+}
+
+impl<A, P, T> Op for Compact<A, P, T>
+where
+    A: Access<T>,
+    P: Access<u64>,
+    T: CType,
+{
+    fn size(&self) -> usize {
+        self.size
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan(), self.positions.plan()]
+    }
+}
+
+impl<A, P, T> Enqueue<OpenCL, T> for Compact<A, P, T>
+where
+    A: Access<T>,
+    P: Access<u64>,
+    T: CType,
+{
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let input = self.access.read()?.to_cl()?;
+        let positions = self.positions.read()?.to_cl()?;
+        debug_assert_eq!(input.len(), positions.len());
+
+        let queue = OpenCL::queue(
+            input.len(),
+            &[input.default_queue(), positions.default_queue()],
+        )?;
+
+        let output = Buffer::builder()
+            .queue(queue.clone())
+            .len(self.size)
+            .build()?;
+
+        let kernel = Kernel::builder()
+            .name("compact")
+            .program(&self.program)
+            .queue(queue)
+            .global_work_size(input.len())
+            .arg(&*input)
+            .arg(&*positions)
+            .arg(&output)
+            .build()?;
+
+        unsafe { events::enq(&kernel)? };
+
+        Ok(output)
+    }
+}
+
+impl<A, P, T> ReadValue<OpenCL, T> for Compact<A, P, T>
+where
+    A: Access<T>,
+    P: Access<u64>,
+    T: CType,
+{
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        host::ops::read_compact(&self.access, &self.positions, self.size, offset)
+    }
+}
+
+pub struct Gather<A, I, T> {
+    access: A,
+    indices: I,
+    dims: [usize; 3],
+    program: Program,
+    dtype: PhantomData<T>,
+}
+
+impl<A, I, T: CType> Gather<A, I, T> {
+    pub fn new(access: A, indices: I, dims: [usize; 3]) -> Result<Self, Error> {
+        let program = programs::gather::gather_axis(T::TYPE)?;
+
+        Ok(Self {
+            access,
+            indices,
+            dims,
+            program,
+            dtype: PhantomData,
+        })
+    }
+}
+
+impl<A, I, T> Op for Gather<A, I, T>
+where
+    A: Access<T>,
+    I: Access<u64>,
+    T: CType,
+{
+    fn size(&self) -> usize {
+        let [outer, _dim, inner] = self.dims;
+        outer * self.indices.size() * inner
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan(), self.indices.plan()]
+    }
+}
+
+impl<A, I, T> Enqueue<OpenCL, T> for Gather<A, I, T>
+where
+    A: Access<T>,
+    I: Access<u64>,
+    T: CType,
+{
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let [_outer, dim, inner] = self.dims;
+
+        let source = self.access.read()?.to_cl()?;
+        let indices = self.indices.read()?.to_cl()?;
+        check_indices(&indices, dim, host::ops::gather_bounds)?;
+
+        let queue = OpenCL::queue(
+            self.size(),
+            &[source.default_queue(), indices.default_queue()],
+        )?;
+
+        let output = Buffer::builder()
+            .queue(queue.clone())
+            .len(self.size())
+            .build()?;
+
+        let kernel = Kernel::builder()
+            .name("gather_axis")
+            .program(&self.program)
+            .queue(queue)
+            .global_work_size(self.size())
+            .arg(dim as u64)
+            .arg(inner as u64)
+            .arg(indices.len() as u64)
+            .arg(&*source)
+            .arg(&*indices)
+            .arg(&output)
+            .build()?;
+
+        unsafe { events::enq(&kernel)? };
+
+        Ok(output)
+    }
+}
+
+impl<A, I, T> ReadValue<OpenCL, T> for Gather<A, I, T>
+where
+    A: Access<T>,
+    I: Access<u64>,
+    T: CType,
+{
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        host::ops::read_gather(&self.access, &self.indices, self.dims, offset)
+    }
+}
+
+pub struct LayerNorm<A, S, B, T> {
+    access: A,
+    scale: S,
+    bias: B,
+    stride: usize,
+    eps: T,
+    program: Program,
+}
+
+impl<A, S, B, T: Float> LayerNorm<A, S, B, T> {
+    pub fn new(access: A, scale: S, bias: B, stride: usize, eps: T) -> Result<Self, Error> {
+        let program = programs::reduce::layer_norm(T::TYPE)?;
+
+        Ok(Self {
+            access,
+            scale,
+            bias,
+            stride,
+            eps,
+            program,
+        })
+    }
+}
+
+impl<A, S, B, T> Op for LayerNorm<A, S, B, T>
+where
+    A: Access<T>,
+    S: Access<T>,
+    B: Access<T>,
+    T: Float,
+{
+    fn size(&self) -> usize {
+        debug_assert_eq!(self.access.size() % self.stride, 0);
+        self.access.size()
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan(), self.scale.plan(), self.bias.plan()]
+    }
+}
+
+impl<A, S, B, T> Enqueue<OpenCL, T> for LayerNorm<A, S, B, T>
+where
+    A: Access<T>,
+    S: Access<T>,
+    B: Access<T>,
+    T: Float,
+{
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let input = self.access.read()?.to_cl()?;
+        let scale = self.scale.read()?.to_cl()?;
+        let bias = self.bias.read()?.to_cl()?;
+
+        debug_assert_eq!(scale.len(), self.stride);
+        debug_assert_eq!(bias.len(), self.stride);
+
+        let queue = OpenCL::queue(
+            input.len(),
+            &[
+                input.default_queue(),
+                scale.default_queue(),
+                bias.default_queue(),
+            ],
+        )?;
+
+        let output = Buffer::builder()
+            .queue(queue.clone())
+            .len(input.len())
+            .build()?;
+
+        let kernel = Kernel::builder()
+            .name("layer_norm")
+            .program(&self.program)
+            .queue(queue)
+            .global_work_size(input.len() / self.stride)
+            .arg(self.stride as u64)
+            .arg(self.eps)
+            .arg(&*input)
+            .arg(&*scale)
+            .arg(&*bias)
+            .arg(&output)
+            .build()?;
+
+        unsafe { events::enq(&kernel)? };
+
+        Ok(output)
+    }
+}
+
+impl<A, S, B, T> ReadValue<OpenCL, T> for LayerNorm<A, S, B, T>
+where
+    A: Access<T>,
+    S: Access<T>,
+    B: Access<T>,
+    T: Float,
+{
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        let start = (offset / self.stride) * self.stride;
+
+        let row = (start..(start + self.stride))
+            .map(|offset| self.access.read_value(offset).map(CType::to_f64))
+            .collect::<Result<Vec<f64>, Error>>()?;
+
+        let mean = row.iter().sum::<f64>() / self.stride as f64;
+        let var = row.iter().map(|n| (n - mean) * (n - mean)).sum::<f64>() / self.stride as f64;
+        let std = (var + CType::to_f64(self.eps)).sqrt();
+
+        let i = offset % self.stride;
+        let scale = self.scale.read_value(i).map(CType::to_f64)?;
+        let bias = self.bias.read_value(i).map(CType::to_f64)?;
+
+        Ok(T::from_f64((((row[i] - mean) / std) * scale) + bias))
+    }
+}
+
+pub struct MatMul<L, R, T> {
+    left: L,
+    right: R,
+    batch_size: usize,
+    dims: [usize; 3],
+    strides: [usize; 2],
+    pad_matrices: Program,
+    dtype: PhantomData<T>,
+}
+
+impl<L, R, T> MatMul<L, R, T>
+where
+    T: CType,
+{
+    pub fn new(left: L, right: R, dims: [usize; 4]) -> Result<Self, Error> {
+        let [_batch_size, a, b, c] = dims;
+        Self::strided(left, right, dims, [a * b, b * c])
+    }
+
+    pub fn strided(
+        left: L,
+        right: R,
+        dims: [usize; 4],
+        strides: [usize; 2],
+    ) -> Result<Self, Error> {
+        let pad_matrices = programs::linalg::pad_matrices(T::TYPE)?;
+
+        let [batch_size, a, b, c] = dims;
+        assert!(batch_size > 0);
+
+        Ok(Self {
+            left,
+            right,
+            batch_size,
+            dims: [a, b, c],
+            strides,
+            pad_matrices,
+            dtype: PhantomData,
+        })
+    }
+
+    fn matmul(
+        &self,
+        queue: Queue,
+        tile_size: usize,
+        left: (&Buffer<T>, usize),
+        right: (&Buffer<T>, usize),
+        dims: [usize; 3],
+    ) -> Result<Buffer<T>, Error> {
+        let [a, b, c] = dims;
+        let (left, left_stride) = left;
+        let (right, right_stride) = right;
+
+        let program = programs::linalg::matmul(T::TYPE, tile_size)?;
+
+        let output = Buffer::builder()
+            .queue(queue.clone())
+            .len(self.batch_size * a * c)
+            .fill_val(T::ZERO)
+            .build()?;
+
+        let dim4 = [a as u64, b as u64, c as u64, self.batch_size as u64];
+        let strides = [left_stride as u64, right_stride as u64];
+
+        let kernel = Kernel::builder()
+            .name("matmul")
+            .program(&program)
+            .queue(queue)
+            .global_work_size((
+                self.batch_size,
+                a.div_ceil(tile_size),
+                c.div_ceil(tile_size),
+            ))
+            .arg(ocl::core::Ulong4::from(dim4))
+            .arg(b.div_ceil(tile_size))
+            .arg(ocl::core::Ulong2::from(strides))
+            .arg(left)
+            .arg(right)
+            .arg(&output)
+            .build()?;
+
+        unsafe { events::enq(&kernel)? }
+
+        Ok(output)
+    }
+
+    /// Copy each matrix in the given `batch` into a matrix with dimensions `dims_out`,
+    /// truncating or padding it with zeros as needed, and return the new batch and its stride.
+    /// A batch with stride zero contains a single matrix shared by every batch element.
+    fn pad_matrices(
+        &self,
+        batch: &Buffer<T>,
+        stride: usize,
+        dims_in: [usize; 2],
+        dims_out: [usize; 2],
+    ) -> Result<(Buffer<T>, usize), Error> {
+        if dims_in == dims_out {
+            return Ok((batch.clone(), stride));
+        }
+
+        let (count, stride_out) = if stride == 0 {
+            (1, 0)
+        } else {
+            (self.batch_size, dims_out[0] * dims_out[1])
+        };
+
+        assert!(batch.len() >= ((count - 1) * stride) + (dims_in[0] * dims_in[1]));
+
+        let queue = OpenCL::queue(batch.len(), &[batch.default_queue()])?;
+
+        let output = Buffer::builder()
+            .queue(queue.clone())
+            .len(count * dims_out[0] * dims_out[1])
+            .fill_val(T::ZERO)
+            .build()?;
+
+        let gws = if dims_in.iter().product::<usize>() <= dims_out.iter().product::<usize>() {
+            (count, dims_in[0], dims_in[1])
+        } else {
+            (count, dims_out[0], dims_out[1])
+        };
+
+        let strides_in = [stride as u64, dims_in[1] as u64];
+
+        let strides_out = [(dims_out[0] * dims_out[1]) as u64, dims_out[1] as u64];
+
+        let kernel = Kernel::builder()
+            .name("pad_matrices")
+            .program(&self.pad_matrices)
+            .queue(queue)
+            .global_work_size(gws)
+            .arg(ocl::core::Ulong2::from(strides_in))
+            .arg(ocl::core::Ulong2::from(strides_out))
+            .arg(batch)
+            .arg(&output)
+            .build()?;
+
+        unsafe { events::enq(&kernel)? }
+
+        Ok((output, stride_out))
+    }
+}
+
+impl<L, R, T> Op for MatMul<L, R, T>
+where
+    L: Access<T>,
+    R: Access<T>,
+    T: CType,
+{
+    fn size(&self) -> usize {
+        self.batch_size * self.dims[0] * self.dims[2]
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.left.plan(), self.right.plan()]
+    }
+}
+
+impl<L, R, T> Enqueue<OpenCL, T> for MatMul<L, R, T>
+where
+    L: Access<T>,
+    R: Access<T>,
+    T: CType,
+{
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let [a, b, c] = self.dims;
+        let [left_stride, right_stride] = self.strides;
+
+        let left = self.left.read()?.to_cl()?;
+        let right = self.right.read()?.to_cl()?;
+
+        assert!(left.len() >= ((self.batch_size - 1) * left_stride) + (a * b));
+        assert!(right.len() >= ((self.batch_size - 1) * right_stride) + (b * c));
+
+        let queue = OpenCL::queue(
+            self.batch_size * a * b * c,
+            &[left.default_queue(), right.default_queue()],
+        )?;
+
+        let tile_size = tune::launch_config::<T>(&queue, KernelFamily::MatMul)?.tile_size;
+        let padded = [a, b, c].map(|dim| pad_dim(dim, tile_size));
+        let [a_pad, b_pad, c_pad] = padded;
+
+        let (left, left_stride) = self.pad_matrices(&left, left_stride, [a, b], [a_pad, b_pad])?;
+
+        let (right, right_stride) =
+            self.pad_matrices(&right, right_stride, [b, c], [b_pad, c_pad])?;
+
+        let product = self.matmul(
+            queue,
+            tile_size,
+            (&left, left_stride),
+            (&right, right_stride),
+            padded,
+        )?;
+
+        self.pad_matrices(&product, a_pad * c_pad, [a_pad, c_pad], [a, c])
+            .map(|(product, _stride)| product)
+    }
+}
+
+impl<L, R, T> ReadValue<OpenCL, T> for MatMul<L, R, T>
+where
+    L: Access<T>,
+    R: Access<T>,
+    T: CType,
+{
+    fn read_value(&self, _offset: usize) -> Result<T, Error> {
+        Err(Error::Bounds(
+            "reading an individual value from a matrix multiplication is not implemented"
+                .to_string(),
+        ))
+    }
+}
+
+pub struct Eye<T> {
+    batch_size: usize,
+    dim: usize,
+    program: Program,
+    dtype: PhantomData<T>,
+}
+
+impl<T: CType> Eye<T> {
+    pub fn new(batch_size: usize, dim: usize) -> Result<Self, Error> {
+        programs::constructors::eye(T::TYPE).map(|program| Self {
+            batch_size,
+            dim,
+            program,
+            dtype: PhantomData,
+        })
+    }
+}
+
+impl<T: Send + Sync> Op for Eye<T> {
+    fn size(&self) -> usize {
+        self.batch_size * self.dim * self.dim
+    }
+}
+
+impl<T: CType> Enqueue<OpenCL, T> for Eye<T> {
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let queue = OpenCL::queue(self.size(), &[])?;
+
+        let buffer = Buffer::builder()
+            .queue(queue.clone())
+            .len(self.size())
+            .build()?;
+
+        let kernel = Kernel::builder()
+            .name("eye")
+            .queue(queue)
+            .program(&self.program)
+            .global_work_size(self.size())
+            .arg(self.dim as u64)
+            .arg(&buffer)
+            .build()?;
+
+        unsafe { events::enq(&kernel)? }
+
+        Ok(buffer)
+    }
+}
+
+impl<T: CType> ReadValue<OpenCL, T> for Eye<T> {
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        let i = offset % (self.dim * self.dim);
+
+        if i / self.dim == i % self.dim {
+            Ok(T::ONE)
+        } else {
+            Ok(T::ZERO)
+        }
+    }
+}
+
+pub struct Linear<T> {
+    start: T,
+    step: f64,
+    size: usize,
+    program: Program,
+}
+
+impl<T: CType> Linear<T> {
+    pub fn new(start: T, step: f64, size: usize) -> Result<Self, Error> {
+        programs::constructors::range(T::TYPE).map(|program| Self {
+            start,
+            step,
+            size,
+            program,
+        })
+    }
+}
+
+impl<T: Send + Sync> Op for Linear<T> {
+    fn size(&self) -> usize {
+        self.size
+    }
+}
+
+impl<T: CType> Enqueue<OpenCL, T> for Linear<T> {
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let queue = OpenCL::queue(self.size, &[])?;
+
+        let buffer = Buffer::builder()
+            .queue(queue.clone())
+            .len(self.size)
+            .build()?;
+
+        let kernel = Kernel::builder()
+            .name("range")
+            .queue(queue)
+            .program(&self.program)
+            .global_work_size(self.size)
+            .arg(self.start)
+            .arg(self.step)
+            .arg(&buffer)
+            .build()?;
+
+        unsafe { events::enq(&kernel)? }
+
+        Ok(buffer)
+    }
+}
+
+impl<T: CType> ReadValue<OpenCL, T> for Linear<T> {
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        Ok(T::add(self.start, T::from_f64((offset as f64) * self.step)))
+    }
+}
+
+pub struct RandomNormal {
+    program: Program,
+    size: usize,
+}
+
+impl RandomNormal {
+    pub fn new(size: usize) -> Result<Self, Error> {
+        programs::constructors::random_normal().map(|program| Self { program, size })
+    }
+}
+
+impl Op for RandomNormal {
+    fn size(&self) -> usize {
+        self.size
+    }
+}
+
+impl Enqueue<OpenCL, f32> for RandomNormal {
+    type Buffer = Buffer<f32>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let queue = OpenCL::queue(self.size, &[])?;
+        let seed: u32 = rand::thread_rng().gen();
+
+        let buffer = Buffer::builder()
+            .queue(queue.clone())
+            .len(WG_SIZE * self.size().div_ceil(WG_SIZE))
+            .build()?;
+
+        let kernel = Kernel::builder()
+            .name("random_normal")
+            .queue(queue.clone())
+            .program(&self.program)
+            .global_work_size(buffer.len())
+            .local_work_size(WG_SIZE)
+            .arg(u64::try_from(seed).expect("seed"))
+            .arg(&buffer)
+            .arg_local::<f32>(WG_SIZE)
+            .build()?;
+
+        unsafe { events::enq(&kernel)? }
+
+        if buffer.len() == self.size {
+            Ok(buffer)
+        } else {
+            let output = Buffer::builder()
+                .queue(queue.clone())
+                .len(self.size)
+                .build()?;
+
+            events::enq_cmd(Some(&queue), |wait, event| {
+                buffer
+                    .copy(&output, Some(0), Some(self.size))
+                    .ewait(wait)
+                    .enew(event)
+                    .enq()
+            })?;
+
+            Ok(output)
+        }
+    }
+}
+
+impl ReadValue<OpenCL, f32> for RandomNormal {
+    fn read_value(&self, _offset: usize) -> Result<f32, Error> {
+        Err(Error::Bounds(
+            "cannot read an individual value from a random normal distribution".to_string(),
+        ))
+    }
+}
+
+pub struct RandomUniform {
+    program: Program,
+    size: usize,
+}
+
+impl RandomUniform {
+    pub fn new(size: usize) -> Result<Self, Error> {
+        programs::constructors::random_uniform().map(|program| Self { program, size })
+    }
+}
+
+impl Op for RandomUniform {
+    fn size(&self) -> usize {
+        self.size
+    }
+}
+
+impl Enqueue<OpenCL, f32> for RandomUniform {
+    type Buffer = Buffer<f32>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let queue = OpenCL::queue(self.size, &[])?;
+        let seed: u32 = random();
+
+        let output = Buffer::builder()
+            .queue(queue.clone())
+            .len(self.size)
+            .build()?;
+
+        let kernel = Kernel::builder()
+            .name("random_uniform")
+            .queue(queue)
+            .program(&self.program)
+            .global_work_size(output.len())
+            .arg(seed as u64)
+            .arg(&output)
+            .build()?;
+
+        unsafe { events::enq(&kernel)? }
+
+        Ok(output)
+    }
+}
+
+impl ReadValue<OpenCL, f32> for RandomUniform {
+    fn read_value(&self, _offset: usize) -> Result<f32, Error> {
+        Ok(random())
+    }
+}
+
+pub struct Reduce<A, T: CType> {
+    access: A,
+    stride: usize,
+    size: usize,
+    fold: Program,
+    reduce: Program,
+    name: &'static str,
+    host_reduce: fn(T, T) -> T,
+    id: T,
+}
+
+impl<A: Access<T>, T: CType> Reduce<A, T> {
+    fn new(
+        access: A,
+        stride: usize,
+        name: &'static str,
+        host_reduce: fn(T, T) -> T,
+        id: T,
+    ) -> Result<Self, Error> {
+        let fold = programs::reduce::fold_axis(T::TYPE, name)?;
+        let reduce = programs::reduce::reduce_axis(T::TYPE, name)?;
+
+        Ok(Self {
+            size: access.size() / stride,
+            access,
+            stride,
+            fold,
+            reduce,
+            name,
+            host_reduce,
+            id,
+        })
+    }
+
+    pub fn max(access: A, stride: usize) -> Result<Self, Error> {
+        Self::new(access, stride, "maximum", CType::max, max_identity())
+    }
+
+    pub fn min(access: A, stride: usize) -> Result<Self, Error> {
+        Self::new(access, stride, "minimum", CType::min, min_identity())
+    }
+
+    pub fn product(access: A, stride: usize) -> Result<Self, Error> {
+        Self::new(access, stride, "mul", T::mul, T::ONE)
+    }
+
+    pub fn sum(access: A, stride: usize) -> Result<Self, Error> {
+        Self::new(access, stride, "add", T::add, T::ZERO)
+    }
+
+    pub fn max_ignore_nan(access: A, stride: usize) -> Result<Self, Error> {
+        let id = ignore_nan_identity(max_identity());
+        let reduce = |l, r| ignore_nan(l, r, CType::max);
+        Self::new(access, stride, "maximum_ignore_nan", reduce, id)
+    }
+
+    pub fn min_ignore_nan(access: A, stride: usize) -> Result<Self, Error> {
+        let id = ignore_nan_identity(min_identity());
+        let reduce = |l, r| ignore_nan(l, r, CType::min);
+        Self::new(access, stride, "minimum_ignore_nan", reduce, id)
+    }
+
+    pub fn product_ignore_nan(access: A, stride: usize) -> Result<Self, Error> {
+        let reduce = |l, r| ignore_nan(l, r, T::mul);
+        Self::new(access, stride, "mul_ignore_nan", reduce, T::ONE)
+    }
+
+    pub fn sum_ignore_nan(access: A, stride: usize) -> Result<Self, Error> {
+        let reduce = |l, r| ignore_nan(l, r, T::add);
+        Self::new(access, stride, "add_ignore_nan", reduce, T::ZERO)
+    }
+
+    /// Construct a reduction of `size` empty lanes, each of which is the identity `id`.
+    pub fn identity(access: A, size: usize, id: T) -> Result<Self, Error> {
+        debug_assert_eq!(access.size(), 0);
+
+        let mut reduce = Self::new(access, 1, "add", T::add, id)?;
+        reduce.stride = 0;
+        reduce.size = size;
+        Ok(reduce)
+    }
+
+    fn fold(
+        &self,
+        queue: Queue,
+        input: &Buffer<T>,
+        reduce_dim: usize,
+        target_dim: usize,
+    ) -> Result<Buffer<T>, Error> {
+        let output_size = (input.len() / reduce_dim) * target_dim;
+
+        let output = Buffer::builder()
+            .queue(queue.clone())
+            .len(output_size)
+            .fill_val(T::ZERO)
+            .build()?;
+
+        let kernel = Kernel::builder()
+            .name("fold_axis")
+            .program(&self.fold)
+            .queue(queue)
+            .global_work_size(output_size)
+            .arg(reduce_dim as u64)
+            .arg(target_dim as u64)
+            .arg(self.id)
+            .arg(input)
+            .arg(&output)
+            .build()?;
+
+        unsafe { events::enq(&kernel)? }
+
+        Ok(output)
+    }
+
+    fn reduce(
+        &self,
+        queue: Queue,
+        input: &Buffer<T>,
+        stride: usize,
+        wg_size: usize,
+    ) -> Result<Buffer<T>, Error> {
+        debug_assert_eq!(input.len() % stride, 0);
+
+        let output = Buffer::builder()
+            .queue(queue.clone())
+            .len(input.len() / stride)
+            .fill_val(T::ZERO)
+            .build()?;
+
+        let kernel = Kernel::builder()
+            .name("reduce_axis")
+            .program(&self.reduce)
+            .queue(queue.clone())
+            .local_work_size(wg_size)
+            .global_work_size(input.len())
+            .arg(self.id)
+            .arg(input)
+            .arg(&output)
+            .arg_local::<T>(wg_size)
+            .build()?;
+
+        unsafe { events::enq(&kernel)? }
+
+        Ok(output)
+    }
+}
+
+impl<A: Access<T>, T: CType> Op for Reduce<A, T> {
+    fn size(&self) -> usize {
+        self.size
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan()]
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<OpenCL, T> for Reduce<A, T> {
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let input = self.access.read()?.to_cl()?;
+
+        let queue = OpenCL::queue(input.len(), &[input.default_queue()])?;
+
+        if self.stride == 0 {
+            return Buffer::builder()
+                .queue(queue)
+                .len(self.size)
+                .fill_val(self.id)
+                .build()
+                .map_err(Error::from);
+        }
+
+        let output_size = input.len() / self.stride;
+
+        let mut stride = self.stride;
+
+        let log = (stride as f32).log(WG_SIZE as f32).fract();
+        let target_dim = WG_SIZE.pow(log as u32);
+        let mut buffer = self.fold(queue.clone(), &*input, stride, target_dim)?;
+
+        stride = target_dim;
+        debug_assert_eq!(output_size * stride, buffer.len());
+
+        while buffer.len() > output_size {
+            let wg_size = if stride < WG_SIZE {
+                stride
+            } else {
+                debug_assert_eq!(stride % WG_SIZE, 0);
+                WG_SIZE
+            };
+
+            buffer = self.reduce(queue.clone(), &buffer, stride, wg_size)?;
+            stride /= wg_size;
+            debug_assert_eq!(output_size * stride, buffer.len());
+        }
+
+        assert_eq!(buffer.len(), output_size);
+
+        Ok(buffer)
+    }
+}
+
+impl<A: Access<T>, T: CType> ReadValue<OpenCL, T> for Reduce<A, T> {
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        if self.stride == 0 {
+            return Ok(self.id);
+        }
+
+        let input = self.access.read()?.to_cl()?;
+        let lane = input.create_sub_buffer(None, offset * self.stride, self.stride)?;
+        let partials = reduce_all(&lane, self.name, self.id)?;
+        Ok(partials.into_iter().fold(self.id, self.host_reduce))
+    }
+}
+
+pub struct SegmentPad<O, A, T> {
+    offsets: O,
+    values: A,
+    segments: usize,
+    max_len: usize,
+    pad: T,
+    program: Program,
+}
+
+impl<O, A, T: CType> SegmentPad<O, A, T> {
+    pub fn new(
+        offsets: O,
+        values: A,
+        segments: usize,
+        max_len: usize,
+        pad: T,
+    ) -> Result<Self, Error> {
+        let program = programs::gather::pad_segments(T::TYPE)?;
+
+        Ok(Self {
+            offsets,
+            values,
+            segments,
+            max_len,
+            pad,
+            program,
+        })
+    }
+}
+
+impl<O, A, T> Op for SegmentPad<O, A, T>
+where
+    O: Access<u64>,
+    A: Access<T>,
+    T: CType,
+{
+    fn size(&self) -> usize {
+        self.segments * self.max_len
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.offsets.plan(), self.values.plan()]
+    }
+}
+
+impl<O, A, T> Enqueue<OpenCL, T> for SegmentPad<O, A, T>
+where
+    O: Access<u64>,
+    A: Access<T>,
+    T: CType,
+{
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let offsets = self.offsets.read()?.to_cl()?;
+        let values = self.values.read()?.to_cl()?;
+
+        debug_assert_eq!(offsets.len(), self.segments + 1);
+
+        let queue = OpenCL::queue(
+            self.size(),
+            &[offsets.default_queue(), values.default_queue()],
+        )?;
+
+        let output = Buffer::builder()
+            .queue(queue.clone())
+            .len(self.size())
+            .build()?;
+
+        let kernel = Kernel::builder()
+            .name("pad_segments")
+            .program(&self.program)
+            .queue(queue)
+            .global_work_size(self.size())
+            .arg(self.max_len as u64)
+            .arg(self.pad)
+            .arg(&*offsets)
+            .arg(&*values)
+            .arg(&output)
+            .build()?;
+
+        unsafe { events::enq(&kernel)? };
+
+        Ok(output)
+    }
+}
+
+impl<O, A, T> ReadValue<OpenCL, T> for SegmentPad<O, A, T>
+where
+    O: Access<u64>,
+    A: Access<T>,
+    T: CType,
+{
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        host::ops::read_segment_pad(
+            &self.offsets,
+            &self.values,
+            [self.segments, self.max_len],
+            self.pad,
+            offset,
+        )
+    }
+}
+
+pub struct SegmentReduce<O, A, T> {
+    offsets: O,
+    values: A,
+    segments: usize,
+    program: Program,
+    reduce: fn(T, T) -> T,
+    id: T,
+}
+
+impl<O, A, T: CType> SegmentReduce<O, A, T> {
+    fn new(
+        offsets: O,
+        values: A,
+        segments: usize,
+        expr: &'static str,
+        reduce: fn(T, T) -> T,
+        id: T,
+    ) -> Result<Self, Error> {
+        let program = programs::reduce::reduce_segments(T::TYPE, expr)?;
+
+        Ok(Self {
+            offsets,
+            values,
+            segments,
+            program,
+            reduce,
+            id,
+        })
+    }
+
+    pub fn max(offsets: O, values: A, segments: usize) -> Result<Self, Error> {
+        let expr = "max(reduced, n)";
+        Self::new(offsets, values, segments, expr, CType::max, T::MIN)
+    }
+
+    pub fn min(offsets: O, values: A, segments: usize) -> Result<Self, Error> {
+        let expr = "min(reduced, n)";
+        Self::new(offsets, values, segments, expr, CType::min, T::MAX)
+    }
+
+    pub fn product(offsets: O, values: A, segments: usize) -> Result<Self, Error> {
+        Self::new(offsets, values, segments, "reduced * n", T::mul, T::ONE)
+    }
+
+    pub fn sum(offsets: O, values: A, segments: usize) -> Result<Self, Error> {
+        Self::new(offsets, values, segments, "reduced + n", T::add, T::ZERO)
+    }
+}
+
+impl<O, A, T> Op for SegmentReduce<O, A, T>
+where
+    O: Access<u64>,
+    A: Access<T>,
+    T: CType,
+{
+    fn size(&self) -> usize {
+        self.segments
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.offsets.plan(), self.values.plan()]
+    }
+}
+
+impl<O, A, T> Enqueue<OpenCL, T> for SegmentReduce<O, A, T>
+where
+    O: Access<u64>,
+    A: Access<T>,
+    T: CType,
+{
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let offsets = self.offsets.read()?.to_cl()?;
+        let values = self.values.read()?.to_cl()?;
+
+        debug_assert_eq!(offsets.len(), self.segments + 1);
+
+        let queue = OpenCL::queue(
+            values.len(),
+            &[offsets.default_queue(), values.default_queue()],
+        )?;
+
+        let output = Buffer::builder()
+            .queue(queue.clone())
+            .len(self.segments)
+            .build()?;
+
+        let kernel = Kernel::builder()
+            .name("reduce_segments")
+            .program(&self.program)
+            .queue(queue)
+            .global_work_size(self.segments)
+            .arg(self.id)
+            .arg(&*offsets)
+            .arg(&*values)
+            .arg(&output)
+            .build()?;
+
+        unsafe { events::enq(&kernel)? };
+
+        Ok(output)
+    }
+}
+
+impl<O, A, T> ReadValue<OpenCL, T> for SegmentReduce<O, A, T>
+where
+    O: Access<u64>,
+    A: Access<T>,
+    T: CType,
+{
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        host::ops::read_segment_reduce(
+            &self.offsets,
+            &self.values,
+            self.segments,
+            self.reduce,
+            self.id,
+            offset,
+        )
+    }
+}
+
+pub struct Cdf<A, T> {
+    access: A,
+    dim: usize,
+    inner: usize,
+    program: Program,
+    dtype: PhantomData<T>,
+}
+
+impl<A, T: CType> Cdf<A, T> {
+    pub fn new(access: A, dim: usize, inner: usize) -> Result<Self, Error> {
+        let program = programs::reduce::cdf(T::TYPE, T::Float::TYPE)?;
+
+        Ok(Self {
+            access,
+            dim,
+            inner,
+            program,
+            dtype: PhantomData,
+        })
+    }
+}
+
+impl<A: Access<T>, T: CType> Op for Cdf<A, T> {
+    fn size(&self) -> usize {
+        self.access.size()
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan()]
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<OpenCL, T::Float> for Cdf<A, T> {
+    type Buffer = Buffer<T::Float>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let input = self.access.read()?.to_cl()?;
+        let queue = OpenCL::queue(input.len(), &[input.default_queue()])?;
+
+        let output = Buffer::builder()
+            .queue(queue.clone())
+            .len(input.len())
+            .build()?;
+
+        let kernel = Kernel::builder()
+            .name("cdf")
+            .program(&self.program)
+            .queue(queue)
+            .global_work_size(input.len())
+            .arg(self.dim as u64)
+            .arg(self.inner as u64)
+            .arg(&*input)
+            .arg(&output)
+            .build()?;
+
+        unsafe { events::enq(&kernel)? };
+
+        Ok(output)
+    }
+}
+
+impl<A: Access<T>, T: CType> ReadValue<OpenCL, T::Float> for Cdf<A, T> {
+    fn read_value(&self, offset: usize) -> Result<T::Float, Error> {
+        host::ops::read_cdf(&self.access, self.dim, self.inner, offset)
+    }
+}
+
+pub struct Scan<A, T> {
+    access: A,
+    dim: usize,
+    inner: usize,
+    program: Program,
+    scan: fn(T, T) -> T,
+}
+
+impl<A, T: CType> Scan<A, T> {
+    fn new(
+        access: A,
+        dim: usize,
+        inner: usize,
+        scan_name: &'static str,
+        scan: fn(T, T) -> T,
+    ) -> Result<Self, Error> {
+        let program = programs::reduce::scan(T::TYPE, T::Float::TYPE, scan_name)?;
+
+        Ok(Self {
+            access,
+            dim,
+            inner,
+            program,
+            scan,
+        })
+    }
+
+    pub fn cumprod(access: A, dim: usize, inner: usize) -> Result<Self, Error> {
+        Self::new(access, dim, inner, "_mul", T::mul)
+    }
+
+    pub fn cumsum(access: A, dim: usize, inner: usize) -> Result<Self, Error> {
+        Self::new(access, dim, inner, "_add", T::add)
+    }
+
+    pub fn logcumsumexp(access: A, dim: usize, inner: usize) -> Result<Self, Error> {
+        Self::new(access, dim, inner, "_logaddexp", |acc, n| {
+            T::from_float(acc.to_float().logaddexp(n.to_float()))
+        })
+    }
+}
+
+impl<A: Access<T>, T: CType> Op for Scan<A, T> {
+    fn size(&self) -> usize {
+        self.access.size()
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan()]
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<OpenCL, T> for Scan<A, T> {
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let input = self.access.read()?.to_cl()?;
+        let queue = OpenCL::queue(input.len(), &[input.default_queue()])?;
+
+        let output = Buffer::builder()
+            .queue(queue.clone())
+            .len(input.len())
+            .build()?;
+
+        let sequences = input.len() / self.dim;
+
+        // scan a long sequence with a whole work-group, and otherwise with a single work item
+        let kernel = if self.dim > WG_SIZE {
+            Kernel::builder()
+                .name("scan_wg")
+                .program(&self.program)
+                .queue(queue)
+                .global_work_size(sequences * WG_SIZE)
+                .local_work_size(WG_SIZE)
+                .arg(self.dim as u64)
+                .arg(self.inner as u64)
+                .arg(&*input)
+                .arg(&output)
+                .arg_local::<T>(WG_SIZE)
+                .build()?
+        } else {
+            Kernel::builder()
+                .name("scan")
+                .program(&self.program)
+                .queue(queue)
+                .global_work_size(sequences)
+                .arg(self.dim as u64)
+                .arg(self.inner as u64)
+                .arg(&*input)
+                .arg(&output)
+                .build()?
+        };
+
+        unsafe { events::enq(&kernel)? };
+
+        Ok(output)
+    }
+}
+
+impl<A: Access<T>, T: CType> ReadValue<OpenCL, T> for Scan<A, T> {
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        let block = self.dim * self.inner;
+        let start = offset - (offset % block) + (offset % self.inner);
+
+        (start..=offset)
+            .step_by(self.inner)
+            .map(|offset| self.access.read_value(offset))
+            .reduce(|acc, n| Ok((self.scan)(acc?, n?)))
+            .expect("scanned value")
+    }
+}
+
+/// Sort each lane of the `input` by (value, index) with a bitonic sorting network,
+/// padding each lane to a power of two, and return the sorted keys and indices.
+fn bitonic_sort<T: CType>(
+    program: &Program,
+    input: &Buffer<T>,
+    spec: host::ops::SortSpec,
+) -> Result<(Queue, Buffer<T>, Buffer<u64>, usize), Error> {
+    let padded = spec.dim.next_power_of_two();
+    let size = (input.len() / spec.dim) * padded;
+    let queue = OpenCL::queue(size, &[input.default_queue()])?;
+
+    let keys = Buffer::<T>::builder()
+        .queue(queue.clone())
+        .len(size)
+        .build()?;
+
+    let indices = Buffer::<u64>::builder()
+        .queue(queue.clone())
+        .len(size)
+        .build()?;
+
+    let init = Kernel::builder()
+        .name("sort_init")
+        .program(program)
+        .queue(queue.clone())
+        .global_work_size(size)
+        .arg(spec.dim as u64)
+        .arg(spec.inner as u64)
+        .arg(padded as u64)
+        .arg(input)
+        .arg(&keys)
+        .arg(&indices)
+        .build()?;
+
+    unsafe { events::enq(&init)? };
+
+    // each step of the sorting network depends on the last, so they run in order on one queue
+    let mut k = 2;
+    while k <= padded {
+        let mut j = k / 2;
+
+        while j > 0 {
+            let step = Kernel::builder()
+                .name("bitonic_step")
+                .program(program)
+                .queue(queue.clone())
+                .global_work_size(size)
+                .arg(spec.dim as u64)
+                .arg(padded as u64)
+                .arg(k as u64)
+                .arg(j as u64)
+                .arg(spec.descending as u8)
+                .arg(&keys)
+                .arg(&indices)
+                .build()?;
+
+            unsafe { events::enq(&step)? };
+            j /= 2;
+        }
+
+        k *= 2;
+    }
+
+    Ok((queue, keys, indices, padded))
+}
+
+pub struct Sort<A, T> {
+    access: A,
+    spec: host::ops::SortSpec,
+    program: Program,
+    dtype: PhantomData<T>,
+}
+
+impl<A, T: CType> Sort<A, T> {
+    pub fn new(access: A, spec: host::ops::SortSpec) -> Result<Self, Error> {
+        let program = programs::sort::sort(T::TYPE)?;
+
+        Ok(Self {
+            access,
+            spec,
+            program,
+            dtype: PhantomData,
+        })
+    }
+}
+
+impl<A: Access<T>, T: CType> Op for Sort<A, T> {
+    fn size(&self) -> usize {
+        self.access.size()
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan()]
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<OpenCL, T> for Sort<A, T> {
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let input = self.access.read()?.to_cl()?;
+        let (queue, keys, _indices, padded) = bitonic_sort(&self.program, &input, self.spec)?;
+
+        let output = Buffer::builder()
+            .queue(queue.clone())
+            .len(input.len())
             .build()?;
 
         let kernel = Kernel::builder()
-            .name("reduce_axis")
-            .program(&self.reduce)
-            .queue(queue.clone())
-            .local_work_size(wg_size)
+            .name("sort_write")
+            .program(&self.program)
+            .queue(queue)
             .global_work_size(input.len())
-            .arg(self.id)
-            .arg(input)
+            .arg(self.spec.dim as u64)
+            .arg(self.spec.inner as u64)
+            .arg(padded as u64)
+            .arg(&keys)
             .arg(&output)
-            .arg_local::<T>(wg_size)
             .build()?;
 
-        unsafe { kernel.enq()? }
+        unsafe { events::enq(&kernel)? };
 
         Ok(output)
     }
 }
 
-impl<A: Access<T>, T: CType> Op for Reduce<A, T> {
-    fn size(&self) -> usize {
-        debug_assert_eq!(self.access.size() % self.stride, 0);
-        self.access.size() / self.stride
+impl<A: Access<T>, T: CType> ReadValue<OpenCL, T> for Sort<A, T> {
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        host::ops::read_sort(&self.access, self.spec, offset)
     }
 }
 
-impl<A: Access<T>, T: CType> Enqueue<OpenCL, T> for Reduce<A, T> {
-    type Buffer = Buffer<T>;
+pub struct ArgSort<A, T> {
+    access: A,
+    spec: host::ops::SortSpec,
+    program: Program,
+    dtype: PhantomData<T>,
+}
 
-    fn enqueue(&self) -> Result<Self::Buffer, Error> {
-        let input = self.access.read()?.to_cl()?;
+impl<A, T: CType> ArgSort<A, T> {
+    pub fn new(access: A, spec: host::ops::SortSpec) -> Result<Self, Error> {
+        let program = programs::sort::sort(T::TYPE)?;
 
-        let queue = OpenCL::queue(input.len(), &[input.default_queue()])?;
+        Ok(Self {
+            access,
+            spec,
+            program,
+            dtype: PhantomData,
+        })
+    }
+}
 
-        let output_size = input.len() / self.stride;
+impl<A: Access<T>, T: CType> Op for ArgSort<A, T> {
+    fn size(&self) -> usize {
+        self.access.size()
+    }
 
-        let mut stride = self.stride;
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan()]
+    }
+}
 
-        let log = (stride as f32).log(WG_SIZE as f32).fract();
-        let target_dim = WG_SIZE.pow(log as u32);
-        let mut buffer = self.fold(queue.clone(), &*input, stride, target_dim)?;
+impl<A: Access<T>, T: CType> Enqueue<OpenCL, u64> for ArgSort<A, T> {
+    type Buffer = Buffer<u64>;
 
-        stride = target_dim;
-        debug_assert_eq!(output_size * stride, buffer.len());
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let input = self.access.read()?.to_cl()?;
+        let (queue, _keys, indices, padded) = bitonic_sort(&self.program, &input, self.spec)?;
 
-        while buffer.len() > output_size {
-            let wg_size = if stride < WG_SIZE {
-                stride
-            } else {
-                debug_assert_eq!(stride % WG_SIZE, 0);
-                WG_SIZE
-            };
+        let output = Buffer::builder()
+            .queue(queue.clone())
+            .len(input.len())
+            .build()?;
 
-            buffer = self.reduce(queue.clone(), &buffer, stride, wg_size)?;
-            stride /= wg_size;
-            debug_assert_eq!(output_size * stride, buffer.len());
-        }
+        let kernel = Kernel::builder()
+            .name("argsort_write")
+            .program(&self.program)
+            .queue(queue)
+            .global_work_size(input.len())
+            .arg(self.spec.dim as u64)
+            .arg(self.spec.inner as u64)
+            .arg(padded as u64)
+            .arg(&indices)
+            .arg(&output)
+            .build()?;
 
-        assert_eq!(buffer.len(), output_size);
+        unsafe { events::enq(&kernel)? };
 
-        Ok(buffer)
+        Ok(output)
     }
 }
 
-impl<A: Access<T>, T: CType> ReadValue<OpenCL, T> for Reduce<A, T> {
-    fn read_value(&self, offset: usize) -> Result<T, Error> {
-        let input = self.access.read()?.to_cl()?;
-        let slice = input.create_sub_buffer(None, offset, offset + self.stride)?;
-        (self.reduce_all)(OpenCL, AccessBuf::from(slice))
+impl<A: Access<T>, T: CType> ReadValue<OpenCL, u64> for ArgSort<A, T> {
+    fn read_value(&self, offset: usize) -> Result<u64, Error> {
+        host::ops::read_argsort(&self.access, self.spec, offset)
     }
 }
 
@@ -992,7 +3164,7 @@ impl<A, T: CType> Scalar<A, T, T> {
         program: &'static str,
         op: fn(T, T) -> T,
     ) -> Result<Self, Error> {
-        programs::elementwise::dual(T::TYPE, program)
+        programs::elementwise::dual(T::TYPE, program, config::fast_math())
             .map(|program| Self {
                 access,
                 scalar,
@@ -1118,6 +3290,10 @@ where
     fn size(&self) -> usize {
         self.access.size()
     }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan()]
+    }
 }
 
 impl<A, IT, OT> Enqueue<OpenCL, OT> for Scalar<A, IT, OT>
@@ -1150,7 +3326,7 @@ where
             .arg(&output)
             .build()?;
 
-        unsafe { kernel.enq()? }
+        unsafe { events::enq(&kernel)? }
 
         Ok(output)
     }
@@ -1178,122 +3354,491 @@ pub struct Slice<A, T> {
     dtype: PhantomData<T>,
 }
 
-impl<A, T: CType> Slice<A, T> {
-    pub fn new(access: A, shape: &[usize], range: Range) -> Result<Self, Error> {
-        let spec = SliceSpec::new(shape, range);
+impl<A, T: CType> Slice<A, T> {
+    pub fn new(access: A, shape: &[usize], range: Range) -> Result<Self, Error> {
+        let spec = SliceSpec::new(shape, range);
+
+        let read = programs::slice::read_slice(T::TYPE, spec.clone())?;
+
+        Ok(Self {
+            access,
+            spec,
+            read,
+            write: None,
+            write_value: None,
+            dtype: PhantomData,
+        })
+    }
+}
+
+impl<A: Access<T>, T: CType> Op for Slice<A, T> {
+    fn size(&self) -> usize {
+        self.spec.size()
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan()]
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<OpenCL, T> for Slice<A, T> {
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let source = self.access.read()?.to_cl()?;
+        let queue = OpenCL::queue(self.size(), &[source.default_queue()])?;
+
+        let output = Buffer::builder()
+            .queue(queue.clone())
+            .len(self.size())
+            .build()?;
+
+        let kernel = Kernel::builder()
+            .name("read_slice")
+            .program(&self.read)
+            .queue(queue)
+            .global_work_size(output.len())
+            .arg(&*source)
+            .arg(&output)
+            .build()?;
+
+        unsafe { events::enq(&kernel)? }
+
+        Ok(output)
+    }
+}
+
+impl<A: Access<T>, T: CType> ReadValue<OpenCL, T> for Slice<A, T> {
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        self.access.read_value(self.spec.source_offset(offset))
+    }
+}
+
+impl<A, T> Write<OpenCL, T> for Slice<A, T>
+where
+    T: CType,
+    A: AccessMut<T> + fmt::Debug,
+{
+    fn write<'a>(&mut self, data: BufferConverter<'a, T>) -> Result<(), Error> {
+        let data = data.to_cl()?;
+        let size_hint = self.size();
+        let source = self.access.cl_buffer()?;
+
+        let queue = OpenCL::queue(size_hint, &[source.default_queue()])?;
+
+        if self.write.is_none() {
+            let program = programs::slice::write_to_slice(T::TYPE, self.spec.clone())?;
+            self.write = Some(program);
+        }
+
+        let kernel = Kernel::builder()
+            .name("write_slice")
+            .program(self.write.as_ref().expect("CL write op"))
+            .queue(queue)
+            .global_work_size(source.len())
+            .arg(source)
+            .arg(&*data)
+            .build()?;
+
+        unsafe { events::enq(&kernel)? }
+
+        Ok(())
+    }
+
+    fn write_value(&mut self, value: T) -> Result<(), Error> {
+        let size_hint = self.size();
+        let source = self.access.cl_buffer()?;
+
+        let queue = OpenCL::queue(size_hint, &[source.default_queue()])?;
+
+        if self.write.is_none() {
+            let program = programs::slice::write_value_to_slice(T::TYPE, self.spec.clone())?;
+            self.write_value = Some(program);
+        }
+
+        let kernel = Kernel::builder()
+            .name("write_slice_value")
+            .program(self.write_value.as_ref().expect("CL write op"))
+            .queue(queue)
+            .global_work_size(source.len())
+            .arg(source)
+            .arg(value)
+            .build()?;
+
+        unsafe { events::enq(&kernel)? }
+
+        Ok(())
+    }
+
+    fn write_value_at(&mut self, offset: usize, value: T) -> Result<(), Error> {
+        self.access
+            .borrow_mut()
+            .write_value_at(self.spec.source_offset(offset), value)
+    }
+}
+
+pub struct CsrMatMul<I, V, D, T> {
+    offsets: I,
+    indices: I,
+    values: V,
+    dense: D,
+    dims: [usize; 3],
+    program: Program,
+    dtype: PhantomData<T>,
+}
+
+impl<I, V, D, T: CType> CsrMatMul<I, V, D, T> {
+    pub fn new(
+        offsets: I,
+        indices: I,
+        values: V,
+        dense: D,
+        dims: [usize; 3],
+    ) -> Result<Self, Error> {
+        let program = programs::linalg::csr_matmul(T::TYPE)?;
+
+        Ok(Self {
+            offsets,
+            indices,
+            values,
+            dense,
+            dims,
+            program,
+            dtype: PhantomData,
+        })
+    }
+}
+
+impl<I, V, D, T> Op for CsrMatMul<I, V, D, T>
+where
+    I: Access<u64>,
+    V: Access<T>,
+    D: Access<T>,
+    T: CType,
+{
+    fn size(&self) -> usize {
+        self.dims[0] * self.dims[2]
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![
+            self.offsets.plan(),
+            self.indices.plan(),
+            self.values.plan(),
+            self.dense.plan(),
+        ]
+    }
+}
+
+impl<I, V, D, T> Enqueue<OpenCL, T> for CsrMatMul<I, V, D, T>
+where
+    I: Access<u64>,
+    V: Access<T>,
+    D: Access<T>,
+    T: CType,
+{
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let offsets = self.offsets.read()?.to_cl()?;
+        let indices = self.indices.read()?.to_cl()?;
+        let values = self.values.read()?.to_cl()?;
+        let dense = self.dense.read()?.to_cl()?;
+
+        debug_assert_eq!(offsets.len(), self.dims[0] + 1);
+        debug_assert_eq!(indices.len(), values.len());
+        debug_assert_eq!(dense.len(), self.dims[1] * self.dims[2]);
+
+        let queue = OpenCL::queue(
+            self.size(),
+            &[
+                offsets.default_queue(),
+                indices.default_queue(),
+                values.default_queue(),
+                dense.default_queue(),
+            ],
+        )?;
+
+        let output = Buffer::builder()
+            .queue(queue.clone())
+            .len(self.size())
+            .build()?;
+
+        let kernel = Kernel::builder()
+            .name("csr_matmul")
+            .program(&self.program)
+            .queue(queue)
+            .global_work_size(self.size() * WG_SIZE)
+            .local_work_size(WG_SIZE)
+            .arg(self.dims[2] as u64)
+            .arg(&*offsets)
+            .arg(&*indices)
+            .arg(&*values)
+            .arg(&*dense)
+            .arg(&output)
+            .arg_local::<T>(WG_SIZE)
+            .build()?;
+
+        unsafe { events::enq(&kernel)? };
+
+        Ok(output)
+    }
+}
+
+impl<I, V, D, T> ReadValue<OpenCL, T> for CsrMatMul<I, V, D, T>
+where
+    I: Access<u64>,
+    V: Access<T>,
+    D: Access<T>,
+    T: CType,
+{
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        host::ops::read_csr_matmul(
+            &self.offsets,
+            &self.indices,
+            &self.values,
+            &self.dense,
+            self.dims,
+            offset,
+        )
+    }
+}
+
+pub struct SolveTridiagonal<D, R, T> {
+    diagonals: D,
+    rhs: R,
+    batch_size: usize,
+    dim: usize,
+    program: Program,
+    dtype: PhantomData<T>,
+}
 
-        let read = programs::slice::read_slice(T::TYPE, spec.clone())?;
+impl<D, R, T: Float> SolveTridiagonal<D, R, T> {
+    pub fn new(diagonals: D, rhs: R, batch_size: usize, dim: usize) -> Result<Self, Error> {
+        let program = programs::linalg::solve_tridiagonal(T::TYPE)?;
 
         Ok(Self {
-            access,
-            spec,
-            read,
-            write: None,
-            write_value: None,
+            diagonals,
+            rhs,
+            batch_size,
+            dim,
+            program,
             dtype: PhantomData,
         })
     }
 }
 
-impl<A: Send + Sync, T: Send + Sync> Op for Slice<A, T> {
+impl<D, R, T> Op for SolveTridiagonal<D, R, T>
+where
+    D: Access<T>,
+    R: Access<T>,
+    T: Float,
+{
     fn size(&self) -> usize {
-        self.spec.size()
+        self.batch_size * self.dim
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.diagonals.plan(), self.rhs.plan()]
     }
 }
 
-impl<A: Access<T>, T: CType> Enqueue<OpenCL, T> for Slice<A, T> {
+impl<D, R, T> Enqueue<OpenCL, T> for SolveTridiagonal<D, R, T>
+where
+    D: Access<T>,
+    R: Access<T>,
+    T: Float,
+{
     type Buffer = Buffer<T>;
 
     fn enqueue(&self) -> Result<Self::Buffer, Error> {
-        let source = self.access.read()?.to_cl()?;
-        let queue = OpenCL::queue(self.size(), &[source.default_queue()])?;
+        let diagonals = self.diagonals.read()?.to_cl()?;
+        let rhs = self.rhs.read()?.to_cl()?;
+
+        debug_assert_eq!(diagonals.len(), 3 * self.size());
+        debug_assert_eq!(rhs.len(), self.size());
+
+        let queue = OpenCL::queue(
+            self.size(),
+            &[diagonals.default_queue(), rhs.default_queue()],
+        )?;
 
         let output = Buffer::builder()
             .queue(queue.clone())
             .len(self.size())
             .build()?;
 
-        let kernel = Kernel::builder()
-            .name("read_slice")
-            .program(&self.read)
-            .queue(queue)
-            .global_work_size(output.len())
-            .arg(&*source)
-            .arg(&output)
-            .build()?;
+        let kernel = if self.dim <= PCR_MAX_DIM {
+            Kernel::builder()
+                .name("solve_tridiagonal_pcr")
+                .program(&self.program)
+                .queue(queue)
+                .global_work_size(self.size())
+                .local_work_size(self.dim)
+                .arg(self.dim as u64)
+                .arg(&*diagonals)
+                .arg(&*rhs)
+                .arg(&output)
+                .arg_local::<T>(self.dim)
+                .arg_local::<T>(self.dim)
+                .arg_local::<T>(self.dim)
+                .arg_local::<T>(self.dim)
+                .build()?
+        } else {
+            let modified: Buffer<T> = Buffer::builder()
+                .queue(queue.clone())
+                .len(self.size())
+                .build()?;
+
+            Kernel::builder()
+                .name("solve_tridiagonal_thomas")
+                .program(&self.program)
+                .queue(queue)
+                .global_work_size(self.batch_size)
+                .arg(self.dim as u64)
+                .arg(&*diagonals)
+                .arg(&*rhs)
+                .arg(&modified)
+                .arg(&output)
+                .build()?
+        };
 
-        unsafe { kernel.enq()? }
+        unsafe { events::enq(&kernel)? };
 
         Ok(output)
     }
 }
 
-impl<A: Access<T>, T: CType> ReadValue<OpenCL, T> for Slice<A, T> {
+impl<D, R, T> ReadValue<OpenCL, T> for SolveTridiagonal<D, R, T>
+where
+    D: Access<T>,
+    R: Access<T>,
+    T: Float,
+{
     fn read_value(&self, offset: usize) -> Result<T, Error> {
-        self.access.read_value(self.spec.source_offset(offset))
+        host::ops::read_tridiagonal(&self.diagonals, &self.rhs, self.dim, offset)
     }
 }
 
-impl<A, T> Write<OpenCL, T> for Slice<A, T>
-where
-    T: CType,
-    A: AccessMut<T> + fmt::Debug,
-{
-    fn write<'a>(&mut self, data: BufferConverter<'a, T>) -> Result<(), Error> {
-        let data = data.to_cl()?;
-        let size_hint = self.size();
-        let source = self.access.cl_buffer()?;
+pub struct Ternary<A, B, C, T> {
+    a: A,
+    b: B,
+    c: C,
+    program: Program,
+    op: fn(T, T, T) -> T,
+}
 
-        let queue = OpenCL::queue(size_hint, &[source.default_queue()])?;
+impl<A, B, C, T: CType> Ternary<A, B, C, T> {
+    fn new(a: A, b: B, c: C, expr: &'static str, op: fn(T, T, T) -> T) -> Result<Self, Error> {
+        let program = programs::elementwise::ternary(T::TYPE, T::Float::TYPE, expr)?;
 
-        if self.write.is_none() {
-            let program = programs::slice::write_to_slice(T::TYPE, self.spec.clone())?;
-            self.write = Some(program);
-        }
+        Ok(Self {
+            a,
+            b,
+            c,
+            program,
+            op,
+        })
+    }
 
-        let kernel = Kernel::builder()
-            .name("write_slice")
-            .program(self.write.as_ref().expect("CL write op"))
-            .queue(queue)
-            .global_work_size(source.len())
-            .arg(source)
-            .arg(&*data)
-            .build()?;
+    pub fn clamp(access: A, min: B, max: C) -> Result<Self, Error> {
+        Self::new(access, min, max, "min(max(a, b), c)", |n, min, max| {
+            T::min(T::max(n, min), max)
+        })
+    }
 
-        unsafe { kernel.enq()? }
+    pub fn lerp(access: A, other: B, t: C) -> Result<Self, Error> {
+        Self::new(access, other, t, "_lerp(a, b, c)", host::ops::lerp)
+    }
 
-        Ok(())
+    pub fn mul_add(access: A, mul: B, add: C) -> Result<Self, Error> {
+        Self::new(access, mul, add, "(a * b) + c", |n, mul, add| {
+            T::add(T::mul(n, mul), add)
+        })
     }
 
-    fn write_value(&mut self, value: T) -> Result<(), Error> {
-        let size_hint = self.size();
-        let source = self.access.cl_buffer()?;
+    pub fn smoothstep(access: A, edge0: B, edge1: C) -> Result<Self, Error> {
+        Self::new(
+            access,
+            edge0,
+            edge1,
+            "_smoothstep(a, b, c)",
+            host::ops::smoothstep,
+        )
+    }
+}
 
-        let queue = OpenCL::queue(size_hint, &[source.default_queue()])?;
+impl<A, B, C, T> Op for Ternary<A, B, C, T>
+where
+    A: Access<T>,
+    B: Access<T>,
+    C: Access<T>,
+    T: CType,
+{
+    fn size(&self) -> usize {
+        debug_assert_eq!(self.a.size(), self.b.size());
+        debug_assert_eq!(self.a.size(), self.c.size());
+        self.a.size()
+    }
 
-        if self.write.is_none() {
-            let program = programs::slice::write_value_to_slice(T::TYPE, self.spec.clone())?;
-            self.write_value = Some(program);
-        }
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.a.plan(), self.b.plan(), self.c.plan()]
+    }
+}
+
+impl<A, B, C, T> Enqueue<OpenCL, T> for Ternary<A, B, C, T>
+where
+    A: Access<T>,
+    B: Access<T>,
+    C: Access<T>,
+    T: CType,
+{
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let a = self.a.read()?.to_cl()?;
+        let b = self.b.read()?.to_cl()?;
+        let c = self.c.read()?.to_cl()?;
+
+        debug_assert_eq!(a.len(), b.len());
+        debug_assert_eq!(a.len(), c.len());
+
+        let queue = OpenCL::queue(
+            a.len(),
+            &[a.default_queue(), b.default_queue(), c.default_queue()],
+        )?;
+
+        let output = Buffer::builder()
+            .queue(queue.clone())
+            .len(a.len())
+            .build()?;
 
         let kernel = Kernel::builder()
-            .name("write_slice_value")
-            .program(self.write_value.as_ref().expect("CL write op"))
+            .name("ternary")
             .queue(queue)
-            .global_work_size(source.len())
-            .arg(source)
-            .arg(value)
+            .program(&self.program)
+            .global_work_size(a.len())
+            .arg(&*a)
+            .arg(&*b)
+            .arg(&*c)
+            .arg(&output)
             .build()?;
 
-        unsafe { kernel.enq()? }
+        unsafe { events::enq(&kernel)? }
 
-        Ok(())
+        Ok(output)
     }
+}
 
-    fn write_value_at(&mut self, offset: usize, value: T) -> Result<(), Error> {
-        self.access
-            .borrow_mut()
-            .write_value_at(self.spec.source_offset(offset), value)
+impl<A, B, C, T> ReadValue<OpenCL, T> for Ternary<A, B, C, T>
+where
+    A: Access<T>,
+    B: Access<T>,
+    C: Access<T>,
+    T: CType,
+{
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        host::ops::read_ternary(&self.a, &self.b, &self.c, self.op, offset)
     }
 }
 
@@ -1306,7 +3851,13 @@ pub struct Unary<A, IT, OT> {
 
 impl<A, IT: CType, OT: CType> Unary<A, IT, OT> {
     fn new(access: A, program: &'static str, op: fn(IT) -> OT) -> Result<Self, Error> {
-        let program = programs::elementwise::unary(IT::Float::TYPE, IT::TYPE, OT::TYPE, program)?;
+        let program = programs::elementwise::unary(
+            IT::Float::TYPE,
+            IT::TYPE,
+            OT::TYPE,
+            program,
+            config::fast_math(),
+        )?;
 
         Ok(Self {
             access,
@@ -1323,19 +3874,37 @@ impl<A, T: CType> Unary<A, T, T> {
     }
 
     pub fn exp(access: A) -> Result<Self, Error> {
-        Self::new(access, "exp", |n| T::from_float(n.to_float().ln()))
+        Self::new(access, "_exp", |n| T::from_float(n.to_float().ln()))
     }
 
     pub fn ln(access: A) -> Result<Self, Error> {
         Self::new(access, "_log", |n| T::from_float(n.to_float().ln()))
     }
 
+    pub fn reciprocal_approx(access: A) -> Result<Self, Error> {
+        Self::new(access, "_recip", |n| T::div(T::ONE, n))
+    }
+
     pub fn round(access: A) -> Result<Self, Error> {
         Self::new(access, "round", |n| T::from_float(n.to_float().ln()))
     }
+
+    pub fn softplus(access: A) -> Result<Self, Error> {
+        Self::new(access, "_softplus", |n| {
+            T::from_float(n.to_float().softplus())
+        })
+    }
 }
 
 impl<A, T: CType> Unary<A, T, T::Float> {
+    pub fn to_degrees(access: A) -> Result<Self, Error> {
+        Self::new(access, "degrees", |n| n.to_float().to_degrees())
+    }
+
+    pub fn to_radians(access: A) -> Result<Self, Error> {
+        Self::new(access, "radians", |n| n.to_float().to_radians())
+    }
+
     pub fn sin(access: A) -> Result<Self, Error> {
         Self::new(access, "sin", |n| n.to_float().sin())
     }
@@ -1397,6 +3966,10 @@ where
     fn size(&self) -> usize {
         self.access.size()
     }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan()]
+    }
 }
 
 impl<A, IT, OT> Enqueue<OpenCL, OT> for Unary<A, IT, OT>
@@ -1416,16 +3989,26 @@ where
             .len(input.len())
             .build()?;
 
-        let kernel = Kernel::builder()
-            .name("unary")
+        let launch = tune::launch_config::<IT>(&queue, KernelFamily::Elementwise)?;
+
+        let mut builder = Kernel::builder();
+
+        builder
+            .name(format!("unary_vec{}", launch.vec_width))
             .program(&self.program)
             .queue(queue)
-            .global_work_size(input.len())
+            .global_work_size(launch.vec_work_size(input.len()))
+            .arg(input.len() as u64)
             .arg(&*input)
-            .arg(&output)
-            .build()?;
+            .arg(&output);
+
+        if launch.wg_size > 0 {
+            builder.local_work_size(launch.wg_size);
+        }
+
+        let kernel = builder.build()?;
 
-        unsafe { kernel.enq()? }
+        unsafe { events::enq(&kernel)? }
 
         Ok(output)
     }
@@ -1437,6 +4020,89 @@ impl<A: Access<IT>, IT: CType, OT: CType> ReadValue<OpenCL, OT> for Unary<A, IT,
     }
 }
 
+pub struct Upsample<A, T> {
+    access: A,
+    dims: [usize; 3],
+    scale: [usize; 2],
+    mode: Interpolation,
+    program: Program,
+    dtype: PhantomData<T>,
+}
+
+impl<A, T: CType> Upsample<A, T> {
+    pub fn new(
+        access: A,
+        dims: [usize; 3],
+        scale: [usize; 2],
+        mode: Interpolation,
+    ) -> Result<Self, Error> {
+        let program = programs::conv::upsample(T::TYPE)?;
+
+        Ok(Self {
+            access,
+            dims,
+            scale,
+            mode,
+            program,
+            dtype: PhantomData,
+        })
+    }
+}
+
+impl<A: Access<T>, T: CType> Op for Upsample<A, T> {
+    fn size(&self) -> usize {
+        self.dims.iter().product::<usize>() * self.scale.iter().product::<usize>()
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan()]
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<OpenCL, T> for Upsample<A, T> {
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        let input = self.access.read()?.to_cl()?;
+        let queue = OpenCL::queue(self.size(), &[input.default_queue()])?;
+
+        let output = Buffer::builder()
+            .queue(queue.clone())
+            .len(self.size())
+            .build()?;
+
+        let name = match self.mode {
+            Interpolation::Nearest => "upsample_nearest",
+            Interpolation::Bilinear => "upsample_bilinear",
+        };
+
+        let [_batch, height, width] = self.dims;
+
+        let kernel = Kernel::builder()
+            .name(name)
+            .program(&self.program)
+            .queue(queue)
+            .global_work_size(self.size())
+            .arg(ocl::core::Ulong2::from([height as u64, width as u64]))
+            .arg(ocl::core::Ulong2::from(self.scale.map(|n| n as u64)))
+            .arg(&*input)
+            .arg(&output)
+            .build()?;
+
+        unsafe { events::enq(&kernel)? }
+
+        Ok(output)
+    }
+}
+
+impl<A: Access<T>, T: CType> ReadValue<OpenCL, T> for Upsample<A, T> {
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        upsample_value(self.mode, self.dims, self.scale, offset, |i| {
+            self.access.read_value(i)
+        })
+    }
+}
+
 pub struct View<A, T> {
     access: A,
     program: Program,
@@ -1449,7 +4115,7 @@ impl<A, T> View<A, T>
 where
     T: CType,
 {
-    fn new(access: A, spec: ViewSpec) -> Result<Self, Error> {
+    pub fn new(access: A, spec: ViewSpec) -> Result<Self, Error> {
         let size = spec.shape.iter().product();
         let program = programs::view::view(T::TYPE, spec.clone())?;
 
@@ -1463,17 +4129,19 @@ where
     }
 
     pub fn broadcast(access: A, shape: Shape, broadcast: Shape) -> Result<Self, Error> {
-        let strides = strides_for(&shape, shape.len()).collect();
-        let spec = ViewSpec::new(broadcast, strides);
-        Self::new(access, spec)
+        Self::new(access, ViewSpec::broadcast(&shape, broadcast))
     }
 
     pub fn transpose(access: A, shape: Shape, axes: Axes) -> Result<Self, Error> {
-        let strides = strides_for(&shape, shape.len()).collect::<Strides>();
-        let shape = axes.iter().copied().map(|x| shape[x]).collect::<Shape>();
-        let strides = axes.into_iter().map(|x| strides[x]).collect::<Strides>();
-        let spec = ViewSpec::new(shape, strides);
-        Self::new(access, spec)
+        Self::new(access, ViewSpec::transpose(&shape, &axes))
+    }
+
+    pub fn reverse(access: A, shape: Shape, axes: Axes) -> Result<Self, Error> {
+        Self::new(access, ViewSpec::reverse(&shape, &axes))
+    }
+
+    pub fn roll(access: A, shape: Shape, shift: usize, axis: usize) -> Result<Self, Error> {
+        Self::new(access, ViewSpec::roll(&shape, shift, axis))
     }
 }
 
@@ -1485,6 +4153,10 @@ where
     fn size(&self) -> usize {
         self.size
     }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        vec![self.access.plan()]
+    }
 }
 
 impl<A: Access<T>, T: CType> Enqueue<OpenCL, T> for View<A, T> {
@@ -1492,32 +4164,83 @@ impl<A: Access<T>, T: CType> Enqueue<OpenCL, T> for View<A, T> {
 
     fn enqueue(&self) -> Result<Self::Buffer, Error> {
         let source = self.access.read()?.to_cl()?;
+        enqueue_view(&self.program, &source, self.size)
+    }
+}
 
-        let queue = OpenCL::queue(self.size, &[source.default_queue()])?;
+impl<A: Access<T>, T: CType> ReadValue<OpenCL, T> for View<A, T> {
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        self.access.read_value(self.spec.source_offset(offset))
+    }
+}
 
-        let output = Buffer::builder()
-            .queue(queue.clone())
-            .len(self.size)
-            .build()?;
+/// Enqueue the given `view` program to read `size` elements of the `source` buffer.
+fn enqueue_view<T: CType>(
+    program: &Program,
+    source: &Buffer<T>,
+    size: usize,
+) -> Result<Buffer<T>, Error> {
+    let queue = OpenCL::queue(size, &[source.default_queue()])?;
 
-        let kernel = Kernel::builder()
-            .name("view")
-            .program(&self.program)
-            .queue(queue)
-            .global_work_size(self.size)
-            .arg(&*source)
-            .arg(&output)
-            .build()?;
+    let output = Buffer::builder().queue(queue.clone()).len(size).build()?;
 
-        unsafe { kernel.enq()? }
+    let kernel = Kernel::builder()
+        .name("view")
+        .program(program)
+        .queue(queue)
+        .global_work_size(size)
+        .arg(source)
+        .arg(&output)
+        .build()?;
 
-        Ok(output)
-    }
+    unsafe { events::enq(&kernel)? }
+
+    Ok(output)
 }
 
-impl<A: Access<T>, T: CType> ReadValue<OpenCL, T> for View<A, T> {
-    fn read_value(&self, offset: usize) -> Result<T, Error> {
-        self.access.read_value(self.spec.source_offset(offset))
+/// Enqueue the given `op`, recovering from a failure to allocate device memory
+/// according to [`config::oom_policy`].
+pub(crate) fn enqueue_with_policy<O, T>(op: &O) -> Result<crate::Buffer<T>, Error>
+where
+    O: Enqueue<OpenCL, T, Buffer = Buffer<T>> + ReadValue<OpenCL, T>,
+    T: CType,
+{
+    let output = |buffer: Buffer<T>| {
+        memory::track(&buffer, || {
+            op_label(std::any::type_name::<O>(), buffer.len())
+        });
+        crate::Buffer::CL(buffer)
+    };
+
+    let cause = match events::enqueue_op(|| op.enqueue()) {
+        Ok(buffer) => return Ok(output(buffer)),
+        Err(cause) if cause.is_out_of_memory() => cause,
+        Err(cause) => return Err(cause),
+    };
+
+    match config::oom_policy() {
+        OomPolicy::Fail => Err(cause),
+        OomPolicy::Retry => {
+            for attempt in 0..OOM_RETRIES {
+                thread::sleep(OOM_BACKOFF * 2u32.pow(attempt));
+
+                match events::enqueue_op(|| op.enqueue()) {
+                    Ok(buffer) => return Ok(output(buffer)),
+                    Err(cause) if cause.is_out_of_memory() => continue,
+                    Err(cause) => return Err(cause),
+                }
+            }
+
+            Err(cause.with_context(format!("after {OOM_RETRIES} retries")))
+        }
+        OomPolicy::Host => (0..op.size())
+            .into_par_iter()
+            .map(|offset| op.read_value(offset))
+            .collect::<Result<Vec<T>, Error>>()
+            .map(|data| crate::Buffer::Host(host::Buffer::Heap(data)))
+            .map_err(|fallback| {
+                cause.with_context(format!("and the host fallback failed: {fallback}"))
+            }),
     }
 }
 
@@ -1,24 +1,36 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::sync::Arc;
 
-use ocl::core::{DeviceInfo, DeviceInfoResult};
+use ocl::core::{CommandQueueProperties, DeviceInfo, DeviceInfoResult};
 use ocl::{Buffer, Context, Device, DeviceType, Event, Kernel, Platform, Queue};
 use rayon::prelude::*;
 use smallvec::SmallVec;
 
-use crate::access::{Access, AccessOp};
+use crate::access::{Access, AccessMut, AccessOp};
 use crate::buffer::BufferConverter;
+use crate::config::ReduceOrder;
+use crate::host::ops::{scatter_bounds, tree_reduce, SortSpec};
 use crate::ops::{
-    Construct, ElementwiseBoolean, ElementwiseBooleanScalar, ElementwiseCast, ElementwiseCompare,
+    max_identity, min_identity, Accumulate, Construct, Convolve, ElementwiseBitcast,
+    ElementwiseBoolean, ElementwiseBooleanScalar, ElementwiseCast, ElementwiseCompare,
     ElementwiseDual, ElementwiseNumeric, ElementwiseScalar, ElementwiseScalarCompare,
-    ElementwiseTrig, ElementwiseUnary, ElementwiseUnaryBoolean, GatherCond, LinAlgDual,
-    LinAlgUnary, Random, ReduceAll, ReduceAxes, Transform,
+    ElementwiseTernary, ElementwiseTrig, ElementwiseTrigDual, ElementwiseUnary,
+    ElementwiseUnaryBoolean, Factor, Factorization, GatherAxis, GatherCond, GatherCondScalar,
+    GatherMask, LinAlgDual, LinAlgSolve, LinAlgSparse, LinAlgUnary, Normalize, Random, ReduceAll,
+    ReduceAxes, ReduceAxesWithIndex, Resample, ScanAxis, ScatterAxis, Segments, SortAxis,
+    Transform, ViewSpec,
 };
 use crate::platform::{Convert, PlatformInstance};
-use crate::{Axes, CType, Constant, Error, Float, Range, Shape};
+use crate::{
+    config, introspect, Axes, CType, Constant, ConvSpec, Error, Float, Interpolation, OpKind,
+    Range, Shape, Strides,
+};
 
 use super::ops::*;
 use super::programs;
-use super::{CL_PLATFORM, WG_SIZE};
+use super::{events, memory};
+use super::{CL_PLATFORM, TILE_SIZE, WG_SIZE};
 
 #[cfg(debug_assertions)]
 pub const GPU_MIN_SIZE: usize = 128;
@@ -32,6 +44,14 @@ pub const ACC_MIN_SIZE: usize = 1024; // 1 KiB
 #[cfg(not(debug_assertions))]
 pub const ACC_MIN_SIZE: usize = 2_147_483_648; // 1 GiB
 
+/// The number of elements per chunk of a copy between devices through host memory
+const BOUNCE_CHUNK_SIZE: usize = 1_048_576;
+
+thread_local! {
+    // each thread reuses one queue per device (and ordering), which is released when the thread exits
+    static QUEUE_POOL: RefCell<HashMap<(Device, bool), Queue>> = RefCell::new(HashMap::new());
+}
+
 #[derive(Clone)]
 struct DeviceList {
     devices: Vec<Device>,
@@ -82,6 +102,7 @@ pub struct CLPlatform {
     cl_cpus: DeviceList,
     cl_gpus: DeviceList,
     cl_accs: DeviceList,
+    fp64: bool,
 }
 
 impl CLPlatform {
@@ -103,7 +124,7 @@ impl CLPlatform {
     }
 
     fn select_device_type(&self, size_hint: usize) -> DeviceType {
-        if size_hint < GPU_MIN_SIZE {
+        if size_hint < config::gpu_min_size() {
             DeviceType::CPU
         } else if size_hint < ACC_MIN_SIZE {
             DeviceType::GPU
@@ -113,6 +134,12 @@ impl CLPlatform {
     }
 
     fn select_device(&self, device_type: DeviceType) -> Option<Device> {
+        if let Some(index) = config::device() {
+            if let Some(device) = self.cl_context.devices().get(index) {
+                return Some(*device);
+            }
+        }
+
         match device_type {
             DeviceType::CPU => self
                 .next_cpu()
@@ -132,6 +159,29 @@ impl CLPlatform {
             other => panic!("unsupported OpenCL device type: {other:?}"),
         }
     }
+
+    /// Borrow this thread's queue for the given `device`, creating it on first use.
+    fn pooled_queue(&self, device: Device) -> Result<Queue, ocl::Error> {
+        let out_of_order = config::out_of_order_queues();
+
+        QUEUE_POOL.with(|pool| {
+            let mut pool = pool.borrow_mut();
+
+            if let Some(queue) = pool.get(&(device, out_of_order)) {
+                return Ok(queue.clone());
+            }
+
+            let properties = if out_of_order && supports_out_of_order(&device)? {
+                Some(CommandQueueProperties::OUT_OF_ORDER_EXEC_MODE_ENABLE)
+            } else {
+                None
+            };
+
+            let queue = Queue::new(&self.cl_context, device, properties)?;
+            pool.insert((device, out_of_order), queue.clone());
+            Ok(queue)
+        })
+    }
 }
 
 impl TryFrom<Platform> for CLPlatform {
@@ -148,15 +198,73 @@ impl TryFrom<Platform> for CLPlatform {
         let cl_gpus = Device::list(cl_platform, Some(DeviceType::GPU))?;
         let cl_accs = Device::list(cl_platform, Some(DeviceType::ACCELERATOR))?;
 
+        let mut fp64 = true;
+        for device in &devices {
+            fp64 = fp64 && supports_fp64(device)?;
+        }
+
         Ok(Self {
             cl_cpus: cl_cpus.into(),
             cl_gpus: cl_gpus.into(),
             cl_accs: cl_accs.into(),
             cl_context,
+            fp64,
         })
     }
 }
 
+/// Copy `source` into `dest` through host memory, one chunk at a time,
+/// reading each chunk while the one before it is written.
+fn bounce<T: CType>(source: &Buffer<T>, dest: &Buffer<T>) -> Result<(), ocl::Error> {
+    let len = source.len();
+    let mut pending = Option::<(usize, Vec<T>)>::None;
+
+    for offset in (0..len).step_by(BOUNCE_CHUNK_SIZE) {
+        let mut chunk = vec![T::default(); BOUNCE_CHUNK_SIZE.min(len - offset)];
+
+        let (read, write) = rayon::join(
+            || source.read(&mut chunk[..]).offset(offset).enq(),
+            || match &pending {
+                Some((at, data)) => dest.write(&data[..]).offset(*at).enq(),
+                None => Ok(()),
+            },
+        );
+
+        read?;
+        write?;
+
+        pending = Some((offset, chunk));
+    }
+
+    if let Some((at, data)) = pending {
+        dest.write(&data[..]).offset(at).enq()?;
+    }
+
+    Ok(())
+}
+
+#[inline]
+fn supports_out_of_order(device: &Device) -> Result<bool, ocl::Error> {
+    if let DeviceInfoResult::QueueProperties(properties) =
+        device.info(DeviceInfo::QueueProperties)?
+    {
+        Ok(properties.contains(CommandQueueProperties::OUT_OF_ORDER_EXEC_MODE_ENABLE))
+    } else {
+        Ok(false)
+    }
+}
+
+#[inline]
+fn supports_fp64(device: &Device) -> Result<bool, ocl::Error> {
+    if let DeviceInfoResult::Extensions(extensions) = device.info(DeviceInfo::Extensions)? {
+        Ok(extensions
+            .split_whitespace()
+            .any(|ext| ext == "cl_khr_fp64" || ext == "cl_amd_fp64"))
+    } else {
+        Ok(false)
+    }
+}
+
 /// The OpenCL platform
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub struct OpenCL;
@@ -167,15 +275,169 @@ impl OpenCL {
         &CL_PLATFORM.cl_context
     }
 
+    /// Return `true` if every device on this platform can execute kernels on data of type `T`.
+    /// Devices without the `cl_khr_fp64` extension cannot execute kernels on [`f64`] data.
+    pub fn supports<T: CType>() -> bool {
+        T::TYPE != f64::TYPE || CL_PLATFORM.fp64
+    }
+
+    /// Submit every command enqueued on this thread's queues to its device,
+    /// without waiting for the commands to complete.
+    pub fn flush() -> Result<(), Error> {
+        QUEUE_POOL.with(|pool| {
+            for queue in pool.borrow().values() {
+                queue.flush()?;
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Block until every command enqueued on this thread's queues has completed.
+    pub fn finish() -> Result<(), Error> {
+        QUEUE_POOL.with(|pool| {
+            for queue in pool.borrow().values() {
+                queue.finish()?;
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Release this thread's queues, after submitting any pending commands.
+    /// New queues will be created as needed. This happens automatically when a thread exits,
+    /// so it's only needed to free driver resources held by an idle long-lived thread.
+    pub fn release_queues() {
+        QUEUE_POOL.with(|pool| pool.borrow_mut().clear())
+    }
+
     /// Copy the given `data` into a new [`Buffer`].
     pub fn copy_into_buffer<T: CType>(data: &[T]) -> Result<Buffer<T>, ocl::Error> {
         let queue = Self::queue(data.len(), &[])?;
 
-        ocl::builders::BufferBuilder::new()
+        let buffer = ocl::builders::BufferBuilder::new()
             .len(data.len())
             .queue(queue)
             .copy_host_slice(data)
-            .build()
+            .build()?;
+
+        memory::track(&buffer, || "copy_into_buffer".to_string());
+
+        Ok(buffer)
+    }
+
+    /// Copy the given `buffer` into a new [`Buffer`] on the device at `device_id`,
+    /// an index into the devices of this platform's [`Context`].
+    ///
+    /// The copy is enqueued directly between the devices, which share a context, if the driver
+    /// supports it. Otherwise the data are bounced through host memory in chunks, and each chunk
+    /// is read from the source device while the one before it is written to the destination.
+    pub fn copy_to_device<T: CType>(
+        buffer: &Buffer<T>,
+        device_id: usize,
+    ) -> Result<Buffer<T>, Error> {
+        let device = Self::context()
+            .devices()
+            .get(device_id)
+            .copied()
+            .ok_or_else(|| Error::Bounds(format!("there is no OpenCL device {device_id}")))?;
+
+        if let Some(source) = buffer.default_queue() {
+            source.finish()?;
+        }
+
+        let queue = CL_PLATFORM.pooled_queue(device)?;
+
+        let copy = Buffer::builder()
+            .queue(queue.clone())
+            .len(buffer.len())
+            .build()?;
+
+        let copied = events::enq_cmd(Some(&queue), |wait, event| {
+            buffer
+                .copy(&copy, None, None)
+                .queue(&queue)
+                .ewait(wait)
+                .enew(event)
+                .enq()
+        });
+
+        if copied.is_err() {
+            bounce(buffer, &copy)?;
+        }
+
+        queue.finish()?;
+
+        introspect::record_device_copy::<T>(buffer.len());
+        memory::track(&copy, || format!("copy_to_device({device_id})"));
+
+        Ok(copy)
+    }
+
+    /// Compile the kernels needed to execute the given kinds of `ops` on data of type `T`,
+    /// and create a queue and allocate a buffer on the device selected for each of the `sizes`.
+    pub fn warmup<T: CType>(ops: &[OpKind], sizes: &[usize]) -> Result<(), Error> {
+        for size in sizes.iter().copied() {
+            Self.constant(T::ZERO, size)?;
+        }
+
+        for op in ops {
+            match op {
+                OpKind::Construct => {
+                    programs::constructors::range(T::TYPE)?;
+                    programs::constructors::random_normal()?;
+                    programs::constructors::random_uniform()?;
+                }
+                OpKind::Dual => {
+                    for op in ["add", "div", "mul", "sub"] {
+                        programs::elementwise::dual(T::TYPE, op, config::fast_math())?;
+                    }
+                }
+                OpKind::Compare => {
+                    for op in ["eq", "ge", "gt", "le", "lt", "ne"] {
+                        programs::elementwise::dual_boolean(T::TYPE, op)?;
+                    }
+                }
+                OpKind::Unary => {
+                    for op in ["abs", "_exp", "_log", "round"] {
+                        programs::elementwise::unary(
+                            T::Float::TYPE,
+                            T::TYPE,
+                            T::TYPE,
+                            op,
+                            config::fast_math(),
+                        )?;
+                    }
+                }
+                OpKind::Trig => {
+                    for op in ["sin", "cos", "tan", "sinh", "cosh", "tanh"] {
+                        programs::elementwise::unary(
+                            T::Float::TYPE,
+                            T::TYPE,
+                            T::Float::TYPE,
+                            op,
+                            config::fast_math(),
+                        )?;
+                    }
+                }
+                OpKind::Reduce => {
+                    for op in ["add", "maximum", "minimum", "mul"] {
+                        programs::reduce::fold_axis(T::TYPE, op)?;
+                        programs::reduce::reduce_axis(T::TYPE, op)?;
+                        programs::reduce::reduce(T::TYPE, op)?;
+                    }
+                }
+                OpKind::MatMul => {
+                    programs::linalg::pad_matrices(T::TYPE)?;
+                    programs::linalg::matmul(T::TYPE, TILE_SIZE)?;
+                }
+                OpKind::Accumulate => {
+                    programs::elementwise::axpy(T::TYPE)?;
+                }
+            }
+        }
+
+        Ok(())
     }
 
     pub(crate) fn queue(size_hint: usize, options: &[Option<&Queue>]) -> Result<Queue, ocl::Error> {
@@ -214,15 +476,17 @@ impl OpenCL {
                 .select_device(device_type)
                 .expect("OpenCL device");
 
-            Queue::new(&CL_PLATFORM.cl_context, device, None)?
+            CL_PLATFORM.pooled_queue(device)?
         };
 
         if !deps.is_empty() {
             let events = deps
                 .into_iter()
                 .map(|dep| {
-                    dep.enqueue_marker::<Event>(None)
-                        .map(ocl::core::Event::from)
+                    let marker = dep.enqueue_marker::<Event>(None)?;
+                    // a pooled queue is never released, which would submit its commands implicitly
+                    dep.flush()?;
+                    Ok(ocl::core::Event::from(marker))
                 })
                 .collect::<Result<SmallVec<[ocl::core::Event; 3]>, ocl::Error>>()?;
 
@@ -246,12 +510,15 @@ impl<T: CType> Constant<T> for OpenCL {
     fn constant(&self, value: T, size: usize) -> Result<Self::Buffer, Error> {
         let queue = Self::queue(size, &[])?;
 
-        ocl::builders::BufferBuilder::new()
+        let buffer = ocl::builders::BufferBuilder::new()
             .len(size)
             .fill_val(value)
             .queue(queue)
-            .build()
-            .map_err(Error::from)
+            .build()?;
+
+        memory::track(&buffer, || "constant".to_string());
+
+        Ok(buffer)
     }
 }
 
@@ -267,8 +534,13 @@ impl<T: CType> Convert<T> for OpenCL {
 }
 
 impl<T: CType> Construct<T> for OpenCL {
+    type Eye = Eye<T>;
     type Range = Linear<T>;
 
+    fn eye(self, batch_size: usize, dim: usize) -> Result<AccessOp<Self::Eye, Self>, Error> {
+        Eye::new(batch_size, dim).map(AccessOp::from)
+    }
+
     fn range(self, start: T, stop: T, size: usize) -> Result<AccessOp<Self::Range, Self>, Error> {
         if start <= stop {
             let step = T::sub(stop, start).to_f64() / size as f64;
@@ -279,6 +551,84 @@ impl<T: CType> Construct<T> for OpenCL {
     }
 }
 
+impl<I, K, T> Convolve<I, K, T> for OpenCL
+where
+    I: Access<T>,
+    K: Access<T>,
+    T: CType,
+{
+    type Conv = Conv<I, K, T>;
+    type ConvTranspose = ConvTranspose<I, K, T>;
+    type MatchedFilter = MatchedFilter<I, K, T>;
+
+    fn conv(
+        self,
+        input: I,
+        kernel: K,
+        input_shape: [usize; 5],
+        kernel_shape: [usize; 5],
+        spec: ConvSpec<3>,
+    ) -> Result<AccessOp<Self::Conv, Self>, Error> {
+        Conv::new(input, kernel, input_shape, kernel_shape, spec).map(AccessOp::from)
+    }
+
+    fn conv_transpose2d(
+        self,
+        input: I,
+        kernel: K,
+        input_shape: [usize; 4],
+        kernel_shape: [usize; 4],
+        spec: ConvSpec<2>,
+    ) -> Result<AccessOp<Self::ConvTranspose, Self>, Error> {
+        ConvTranspose::new(input, kernel, input_shape, kernel_shape, spec).map(AccessOp::from)
+    }
+
+    fn matched_filter(
+        self,
+        input: I,
+        template: K,
+        dims: [usize; 3],
+    ) -> Result<AccessOp<Self::MatchedFilter, Self>, Error> {
+        MatchedFilter::new(input, template, dims).map(AccessOp::from)
+    }
+}
+
+impl<A, I, T> GatherAxis<A, I, T> for OpenCL
+where
+    A: Access<T>,
+    I: Access<u64>,
+    T: CType,
+{
+    type Op = Gather<A, I, T>;
+
+    fn gather(
+        self,
+        access: A,
+        indices: I,
+        dims: [usize; 3],
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Gather::new(access, indices, dims).map(AccessOp::from)
+    }
+}
+
+impl<A, P, T> GatherMask<A, P, T> for OpenCL
+where
+    A: Access<T>,
+    P: Access<u64>,
+    T: CType,
+{
+    type Op = Compact<A, P, T>;
+
+    fn compact(
+        self,
+        access: A,
+        positions: P,
+        size: usize,
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Compact::new(access, positions, size).map(AccessOp::from)
+    }
+}
+
 impl<A, L, R, T> GatherCond<A, L, R, T> for OpenCL
 where
     A: Access<u8>,
@@ -293,6 +643,14 @@ where
     }
 }
 
+impl<A: Access<u8>, T: CType> GatherCondScalar<A, T> for OpenCL {
+    type Op = CondScalar<A, T>;
+
+    fn cond_scalar(self, cond: A, then: T, or_else: T) -> Result<AccessOp<Self::Op, Self>, Error> {
+        CondScalar::new(cond, then, or_else).map(AccessOp::from)
+    }
+}
+
 impl<T, L, R> ElementwiseBoolean<L, R, T> for OpenCL
 where
     T: CType,
@@ -330,6 +688,14 @@ impl<A: Access<T>, T: CType> ElementwiseBooleanScalar<A, T> for OpenCL {
     }
 }
 
+impl<A: Access<IT>, IT: CType, OT: CType> ElementwiseBitcast<A, IT, OT> for OpenCL {
+    type Op = Bitcast<A, IT, OT>;
+
+    fn bitcast(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Bitcast::new(access).map(AccessOp::from)
+    }
+}
+
 impl<A: Access<IT>, IT: CType, OT: CType> ElementwiseCast<A, IT, OT> for OpenCL {
     type Op = Cast<A, IT, OT>;
 
@@ -419,6 +785,10 @@ where
         Dual::log(arg, base).map(AccessOp::from)
     }
 
+    fn logaddexp(self, left: L, right: R) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Dual::logaddexp(left, right).map(AccessOp::from)
+    }
+
     fn mul(self, left: L, right: R) -> Result<AccessOp<Self::Op, Self>, Error> {
         Dual::mul(left, right).map(AccessOp::from)
     }
@@ -431,6 +801,10 @@ where
         Dual::rem(left, right).map(AccessOp::from)
     }
 
+    fn step(self, arg: L, threshold: R) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Dual::step(arg, threshold).map(AccessOp::from)
+    }
+
     fn sub(self, left: L, right: R) -> Result<AccessOp<Self::Op, Self>, Error> {
         Dual::sub(left, right).map(AccessOp::from)
     }
@@ -480,6 +854,32 @@ impl<A: Access<T>, T: Float> ElementwiseNumeric<A, T> for OpenCL {
     }
 }
 
+impl<A, B, C, T> ElementwiseTernary<A, B, C, T> for OpenCL
+where
+    A: Access<T>,
+    B: Access<T>,
+    C: Access<T>,
+    T: CType,
+{
+    type Op = Ternary<A, B, C, T>;
+
+    fn clamp(self, access: A, min: B, max: C) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ternary::clamp(access, min, max).map(AccessOp::from)
+    }
+
+    fn lerp(self, access: A, other: B, t: C) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ternary::lerp(access, other, t).map(AccessOp::from)
+    }
+
+    fn mul_add(self, access: A, mul: B, add: C) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ternary::mul_add(access, mul, add).map(AccessOp::from)
+    }
+
+    fn smoothstep(self, access: A, edge0: B, edge1: C) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Ternary::smoothstep(access, edge0, edge1).map(AccessOp::from)
+    }
+}
+
 impl<A: Access<T>, T: CType> ElementwiseTrig<A, T> for OpenCL {
     type Op = Unary<A, T, T::Float>;
 
@@ -518,6 +918,31 @@ impl<A: Access<T>, T: CType> ElementwiseTrig<A, T> for OpenCL {
     fn tanh(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
         Unary::tanh(access).map(AccessOp::from)
     }
+
+    fn to_degrees(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Unary::to_degrees(access).map(AccessOp::from)
+    }
+
+    fn to_radians(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Unary::to_radians(access).map(AccessOp::from)
+    }
+}
+
+impl<L, R, T> ElementwiseTrigDual<L, R, T> for OpenCL
+where
+    L: Access<T>,
+    R: Access<T>,
+    T: CType,
+{
+    type Op = Dual<L, R, T, T::Float>;
+
+    fn atan2(self, y: L, x: R) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Dual::atan2(y, x).map(AccessOp::from)
+    }
+
+    fn hypot(self, left: L, right: R) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Dual::hypot(left, right).map(AccessOp::from)
+    }
 }
 
 impl<A: Access<T>, T: CType> ElementwiseUnary<A, T> for OpenCL {
@@ -535,9 +960,17 @@ impl<A: Access<T>, T: CType> ElementwiseUnary<A, T> for OpenCL {
         Unary::ln(access).map(AccessOp::from)
     }
 
+    fn reciprocal_approx(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Unary::reciprocal_approx(access).map(AccessOp::from)
+    }
+
     fn round(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
         Unary::round(access).map(AccessOp::from)
     }
+
+    fn softplus(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Unary::softplus(access).map(AccessOp::from)
+    }
 }
 
 impl<A: Access<T>, T: CType> ElementwiseUnaryBoolean<A, T> for OpenCL {
@@ -564,10 +997,64 @@ where
     ) -> Result<AccessOp<Self::Op, Self>, Error> {
         MatMul::new(left, right, dims).map(AccessOp::from)
     }
+
+    fn matmul_strided(
+        self,
+        left: L,
+        right: R,
+        dims: [usize; 4],
+        strides: [usize; 2],
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        MatMul::strided(left, right, dims, strides).map(AccessOp::from)
+    }
+}
+
+impl<D, R, T> LinAlgSolve<D, R, T> for OpenCL
+where
+    D: Access<T>,
+    R: Access<T>,
+    T: Float,
+{
+    type Op = SolveTridiagonal<D, R, T>;
+
+    fn solve_tridiagonal(
+        self,
+        diagonals: D,
+        rhs: R,
+        batch_size: usize,
+        dim: usize,
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        SolveTridiagonal::new(diagonals, rhs, batch_size, dim).map(AccessOp::from)
+    }
+}
+
+impl<I, V, D, T> LinAlgSparse<I, V, D, T> for OpenCL
+where
+    I: Access<u64>,
+    V: Access<T>,
+    D: Access<T>,
+    T: CType,
+{
+    type Op = CsrMatMul<I, V, D, T>;
+
+    fn csr_matmul(
+        self,
+        offsets: I,
+        indices: I,
+        values: V,
+        dense: D,
+        dims: [usize; 3],
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        CsrMatMul::new(offsets, indices, values, dense, dims).map(AccessOp::from)
+    }
 }
 
 impl<A: Access<T>, T: CType> LinAlgUnary<A, T> for OpenCL {
     type Op = MatDiag<A, T>;
+    type DiagFrom = DiagFrom<A, T>;
+    type Inverse = Inverse<A, T>;
+    type Decompose = Decompose<A, T>;
+    type Trace = MatTrace<A, T>;
 
     fn diag(
         self,
@@ -577,6 +1064,65 @@ impl<A: Access<T>, T: CType> LinAlgUnary<A, T> for OpenCL {
     ) -> Result<AccessOp<Self::Op, Self>, Error> {
         MatDiag::new(access, batch_size, dim).map(AccessOp::from)
     }
+
+    fn diag_from(
+        self,
+        access: A,
+        batch_size: usize,
+        dim: usize,
+    ) -> Result<AccessOp<Self::DiagFrom, Self>, Error> {
+        DiagFrom::new(access, batch_size, dim).map(AccessOp::from)
+    }
+
+    fn inv(
+        self,
+        access: A,
+        batch_size: usize,
+        dim: usize,
+    ) -> Result<AccessOp<Self::Inverse, Self>, Error> {
+        Inverse::new(access, batch_size, dim).map(AccessOp::from)
+    }
+
+    fn trace(
+        self,
+        access: A,
+        batch_size: usize,
+        dim: usize,
+    ) -> Result<AccessOp<Self::Trace, Self>, Error> {
+        MatTrace::new(access, batch_size, dim).map(AccessOp::from)
+    }
+
+    fn decompose(
+        self,
+        access: A,
+        factor: Factor,
+        batch_size: usize,
+        dims: [usize; 2],
+        factorization: Factorization<T>,
+    ) -> Result<AccessOp<Self::Decompose, Self>, Error> {
+        Decompose::new(access, factor, batch_size, dims, factorization).map(AccessOp::from)
+    }
+}
+
+impl<A, S, B, T> Normalize<A, S, B, T> for OpenCL
+where
+    A: Access<T>,
+    S: Access<T>,
+    B: Access<T>,
+    T: Float,
+{
+    type Op = LayerNorm<A, S, B, T>;
+
+    fn layer_norm(
+        self,
+        access: A,
+        scale: S,
+        bias: B,
+        stride: usize,
+        eps: T,
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        LayerNorm::new(access, scale, bias, stride, eps).map(AccessOp::from)
+    }
 }
 
 impl Random for OpenCL {
@@ -592,6 +1138,72 @@ impl Random for OpenCL {
     }
 }
 
+impl<A: Access<T>, T: CType> Resample<A, T> for OpenCL {
+    type Op = Upsample<A, T>;
+
+    fn upsample(
+        self,
+        access: A,
+        dims: [usize; 3],
+        scale: [usize; 2],
+        mode: Interpolation,
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Upsample::new(access, dims, scale, mode).map(AccessOp::from)
+    }
+}
+
+impl<A: Access<T>, T: CType> Accumulate<A, T> for OpenCL {
+    fn axpy<Y: AccessMut<T>>(self, y: &mut Y, alpha: T, x: A) -> Result<(), Error> {
+        let program = programs::elementwise::axpy(T::TYPE)?;
+
+        let x = x.read()?.to_cl()?;
+        let y = y.cl_buffer()?;
+
+        let queue = Self::queue(y.len(), &[y.default_queue(), x.default_queue()])?;
+
+        let kernel = Kernel::builder()
+            .name("axpy")
+            .queue(queue)
+            .program(&program)
+            .global_work_size(y.len())
+            .arg(&*y)
+            .arg(alpha)
+            .arg(&*x)
+            .build()?;
+
+        unsafe { events::enq(&kernel)? }
+
+        Ok(())
+    }
+}
+
+impl<I, A, T> ScatterAxis<I, A, T> for OpenCL
+where
+    I: Access<u64>,
+    A: Access<T>,
+    T: CType,
+{
+    fn scatter<Y: AccessMut<T>>(
+        self,
+        y: &mut Y,
+        indices: I,
+        values: A,
+        dims: [usize; 3],
+    ) -> Result<(), Error> {
+        scatter(y, indices, values, dims, "scatter")
+    }
+
+    fn scatter_add<Y: AccessMut<T>>(
+        self,
+        y: &mut Y,
+        indices: I,
+        values: A,
+        dims: [usize; 3],
+    ) -> Result<(), Error> {
+        scatter(y, indices, values, dims, "scatter_add")
+    }
+}
+
 impl<A: Access<T>, T: CType> ReduceAll<A, T> for OpenCL {
     fn all(self, access: A) -> Result<bool, Error> {
         let input = access.read()?.to_cl()?;
@@ -607,31 +1219,41 @@ impl<A: Access<T>, T: CType> ReduceAll<A, T> for OpenCL {
 
     fn max(self, access: A) -> Result<T, Error> {
         let input = access.read()?.to_cl()?;
-        let result = reduce_all(&*input, "max", T::MIN)?;
-        Ok(result.into_par_iter().reduce(|| T::MIN, T::max))
+        let result = reduce_all(&*input, "maximum", max_identity())?;
+        Ok(result.into_par_iter().reduce(max_identity, T::max))
     }
 
     fn min(self, access: A) -> Result<T, Error> {
         let input = access.read()?.to_cl()?;
-        let result = reduce_all(&*input, "min", T::MAX)?;
-        Ok(result.into_par_iter().reduce(|| T::MAX, T::min))
+        let result = reduce_all(&*input, "minimum", min_identity())?;
+        Ok(result.into_par_iter().reduce(min_identity, T::min))
     }
 
     fn product(self, access: A) -> Result<T, Error> {
         let input = access.read()?.to_cl()?;
         let result = reduce_all(&*input, "mul", T::ONE)?;
-        Ok(result.into_par_iter().reduce(|| T::ONE, T::mul))
+
+        Ok(match config::reduce_order() {
+            ReduceOrder::Fast => result.into_par_iter().reduce(|| T::ONE, T::mul),
+            ReduceOrder::Pairwise => tree_reduce(&result, T::ONE, T::mul),
+        })
     }
 
     fn sum(self, access: A) -> Result<T, Error> {
         let input = access.read()?.to_cl()?;
         let result = reduce_all(&*input, "add", T::ZERO)?;
-        Ok(result.into_par_iter().reduce(|| T::ZERO, T::add))
+
+        Ok(match config::reduce_order() {
+            ReduceOrder::Fast => result.into_par_iter().reduce(|| T::ZERO, T::add),
+            ReduceOrder::Pairwise => tree_reduce(&result, T::ZERO, T::add),
+        })
     }
 }
 
 impl<A: Access<T>, T: CType> ReduceAxes<A, T> for OpenCL {
     type Op = Reduce<A, T>;
+    type Moment = ReduceMoment<A, T>;
+    type Arg = ReduceArg<A, T>;
 
     fn max(self, access: A, stride: usize) -> Result<AccessOp<Self::Op, Self>, Error> {
         Reduce::max(access, stride).map(AccessOp::from)
@@ -648,6 +1270,189 @@ impl<A: Access<T>, T: CType> ReduceAxes<A, T> for OpenCL {
     fn sum(self, access: A, stride: usize) -> Result<AccessOp<Self::Op, Self>, Error> {
         Reduce::sum(access, stride).map(AccessOp::from)
     }
+
+    fn mean(self, access: A, stride: usize) -> Result<AccessOp<Self::Moment, Self>, Error> {
+        ReduceMoment::mean(access, stride).map(AccessOp::from)
+    }
+
+    fn var(self, access: A, stride: usize) -> Result<AccessOp<Self::Moment, Self>, Error> {
+        ReduceMoment::var(access, stride).map(AccessOp::from)
+    }
+
+    fn std(self, access: A, stride: usize) -> Result<AccessOp<Self::Moment, Self>, Error> {
+        ReduceMoment::std(access, stride).map(AccessOp::from)
+    }
+
+    fn argmax(self, access: A, stride: usize) -> Result<AccessOp<Self::Arg, Self>, Error> {
+        ReduceArg::argmax(access, stride).map(AccessOp::from)
+    }
+
+    fn argmin(self, access: A, stride: usize) -> Result<AccessOp<Self::Arg, Self>, Error> {
+        ReduceArg::argmin(access, stride).map(AccessOp::from)
+    }
+
+    fn max_ignore_nan(self, access: A, stride: usize) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Reduce::max_ignore_nan(access, stride).map(AccessOp::from)
+    }
+
+    fn min_ignore_nan(self, access: A, stride: usize) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Reduce::min_ignore_nan(access, stride).map(AccessOp::from)
+    }
+
+    fn product_ignore_nan(
+        self,
+        access: A,
+        stride: usize,
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Reduce::product_ignore_nan(access, stride).map(AccessOp::from)
+    }
+
+    fn sum_ignore_nan(self, access: A, stride: usize) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Reduce::sum_ignore_nan(access, stride).map(AccessOp::from)
+    }
+
+    fn identity(self, access: A, size: usize, id: T) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Reduce::identity(access, size, id).map(AccessOp::from)
+    }
+}
+
+impl<O, A, T> Segments<O, A, T> for OpenCL
+where
+    O: Access<u64>,
+    A: Access<T>,
+    T: CType,
+{
+    type Pad = SegmentPad<O, A, T>;
+    type Reduce = SegmentReduce<O, A, T>;
+
+    fn pad_segments(
+        self,
+        offsets: O,
+        values: A,
+        segments: usize,
+        max_len: usize,
+        pad: T,
+    ) -> Result<AccessOp<Self::Pad, Self>, Error> {
+        SegmentPad::new(offsets, values, segments, max_len, pad).map(AccessOp::from)
+    }
+
+    fn segment_max(
+        self,
+        offsets: O,
+        values: A,
+        segments: usize,
+    ) -> Result<AccessOp<Self::Reduce, Self>, Error> {
+        SegmentReduce::max(offsets, values, segments).map(AccessOp::from)
+    }
+
+    fn segment_min(
+        self,
+        offsets: O,
+        values: A,
+        segments: usize,
+    ) -> Result<AccessOp<Self::Reduce, Self>, Error> {
+        SegmentReduce::min(offsets, values, segments).map(AccessOp::from)
+    }
+
+    fn segment_product(
+        self,
+        offsets: O,
+        values: A,
+        segments: usize,
+    ) -> Result<AccessOp<Self::Reduce, Self>, Error> {
+        SegmentReduce::product(offsets, values, segments).map(AccessOp::from)
+    }
+
+    fn segment_sum(
+        self,
+        offsets: O,
+        values: A,
+        segments: usize,
+    ) -> Result<AccessOp<Self::Reduce, Self>, Error> {
+        SegmentReduce::sum(offsets, values, segments).map(AccessOp::from)
+    }
+}
+
+impl<A: Access<T>, T: CType> SortAxis<A, T> for OpenCL {
+    type Sort = Sort<A, T>;
+    type ArgSort = ArgSort<A, T>;
+
+    fn sort(
+        self,
+        access: A,
+        dim: usize,
+        inner: usize,
+        descending: bool,
+    ) -> Result<AccessOp<Self::Sort, Self>, Error> {
+        let spec = SortSpec {
+            dim,
+            inner,
+            descending,
+        };
+
+        Sort::new(access, spec).map(AccessOp::from)
+    }
+
+    fn argsort(
+        self,
+        access: A,
+        dim: usize,
+        inner: usize,
+    ) -> Result<AccessOp<Self::ArgSort, Self>, Error> {
+        let spec = SortSpec {
+            dim,
+            inner,
+            descending: false,
+        };
+
+        ArgSort::new(access, spec).map(AccessOp::from)
+    }
+}
+
+impl<A: Access<T>, T: CType> ScanAxis<A, T> for OpenCL {
+    type Op = Scan<A, T>;
+    type Cdf = Cdf<A, T>;
+
+    fn cdf(self, access: A, dim: usize, inner: usize) -> Result<AccessOp<Self::Cdf, Self>, Error> {
+        Cdf::new(access, dim, inner).map(AccessOp::from)
+    }
+
+    fn cumprod(
+        self,
+        access: A,
+        dim: usize,
+        inner: usize,
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Scan::cumprod(access, dim, inner).map(AccessOp::from)
+    }
+
+    fn cumsum(
+        self,
+        access: A,
+        dim: usize,
+        inner: usize,
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Scan::cumsum(access, dim, inner).map(AccessOp::from)
+    }
+
+    fn logcumsumexp(
+        self,
+        access: A,
+        dim: usize,
+        inner: usize,
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        Scan::logcumsumexp(access, dim, inner).map(AccessOp::from)
+    }
+}
+
+impl<A: Access<T>, T: CType> ReduceAxesWithIndex<A, T> for OpenCL {
+    fn max_with_index(self, access: A, stride: usize) -> Result<(Buffer<T>, Buffer<u64>), Error> {
+        reduce_with_index(access, stride, ">")
+    }
+
+    fn min_with_index(self, access: A, stride: usize) -> Result<(Buffer<T>, Buffer<u64>), Error> {
+        reduce_with_index(access, stride, "<")
+    }
 }
 
 impl<A: Access<T>, T: CType> Transform<A, T> for OpenCL {
@@ -681,15 +1486,105 @@ impl<A: Access<T>, T: CType> Transform<A, T> for OpenCL {
     ) -> Result<AccessOp<Self::Transpose, Self>, Error> {
         View::transpose(access, shape, permutation).map(AccessOp::from)
     }
+
+    fn reverse(
+        self,
+        access: A,
+        shape: Shape,
+        axes: Axes,
+    ) -> Result<AccessOp<Self::Transpose, Self>, Error> {
+        View::reverse(access, shape, axes).map(AccessOp::from)
+    }
+
+    fn roll(
+        self,
+        access: A,
+        shape: Shape,
+        shift: usize,
+        axis: usize,
+    ) -> Result<AccessOp<Self::Transpose, Self>, Error> {
+        View::roll(access, shape, shift, axis).map(AccessOp::from)
+    }
+
+    unsafe fn as_strided(
+        self,
+        access: A,
+        shape: Shape,
+        strides: Strides,
+        offset: usize,
+    ) -> Result<AccessOp<Self::Transpose, Self>, Error> {
+        View::new(access, ViewSpec::strided(shape, strides, offset)).map(AccessOp::from)
+    }
+}
+
+fn reduce_with_index<A, T>(
+    access: A,
+    stride: usize,
+    cmp: &'static str,
+) -> Result<(Buffer<T>, Buffer<u64>), Error>
+where
+    A: Access<T>,
+    T: CType,
+{
+    let program = programs::reduce::reduce_with_index(T::TYPE, cmp)?;
+
+    let input = access.read()?.to_cl()?;
+    let size = input.len() / stride;
+    let queue = OpenCL::queue(size, &[input.default_queue()])?;
+
+    let values = Buffer::builder().queue(queue.clone()).len(size).build()?;
+    let indices = Buffer::builder().queue(queue.clone()).len(size).build()?;
+
+    let kernel = Kernel::builder()
+        .name("reduce_with_index")
+        .program(&program)
+        .queue(queue)
+        .global_work_size(size)
+        .arg(stride as u64)
+        .arg(&*input)
+        .arg(&values)
+        .arg(&indices)
+        .build()?;
+
+    unsafe { events::enq(&kernel)? }
+
+    Ok((values, indices))
 }
 
-fn reduce_all<T: CType>(input: &Buffer<T>, reduce: &'static str, id: T) -> Result<Vec<T>, Error> {
+/// Check that each of the given `indices` is less than `dim` before enqueueing a kernel which
+/// reads them, since a kernel can't report an index out of bounds.
+pub(super) fn check_indices(
+    indices: &Buffer<u64>,
+    dim: usize,
+    bounds: fn(usize, usize) -> Error,
+) -> Result<(), Error> {
+    if indices.len() == 0 {
+        return Ok(());
+    }
+
+    let max = reduce_all(indices, "maximum", max_identity())?
+        .into_iter()
+        .fold(0, Ord::max);
+
+    if max < dim as u64 {
+        Ok(())
+    } else {
+        Err(bounds(max as usize, dim))
+    }
+}
+
+pub(super) fn reduce_all<T: CType>(
+    input: &Buffer<T>,
+    reduce: &'static str,
+    id: T,
+) -> Result<Vec<T>, Error> {
     const MIN_SIZE: usize = 8192;
 
     let min_size = MIN_SIZE * num_cpus::get();
 
     if input.len() < min_size {
         let mut result = vec![id; input.len()];
+        events::wait()?;
         input.read(result.as_mut_slice()).enq()?;
         return Ok(result);
     }
@@ -717,7 +1612,7 @@ fn reduce_all<T: CType>(input: &Buffer<T>, reduce: &'static str, id: T) -> Resul
             .arg_local::<T>(WG_SIZE)
             .build()?;
 
-        unsafe { kernel.enq()? };
+        unsafe { events::enq(&kernel)? };
 
         output
     };
@@ -743,12 +1638,67 @@ fn reduce_all<T: CType>(input: &Buffer<T>, reduce: &'static str, id: T) -> Resul
             .arg_local::<T>(WG_SIZE)
             .build()?;
 
-        unsafe { kernel.enq()? }
+        unsafe { events::enq(&kernel)? }
 
         buffer = output;
     }
 
     let mut result = vec![id; buffer.len()];
+    events::wait()?;
     buffer.read(&mut result).enq()?;
     Ok(result)
 }
+
+fn scatter<I, A, T, Y>(
+    y: &mut Y,
+    indices: I,
+    values: A,
+    dims: [usize; 3],
+    kernel_name: &'static str,
+) -> Result<(), Error>
+where
+    I: Access<u64>,
+    A: Access<T>,
+    T: CType,
+    Y: AccessMut<T>,
+{
+    let [_outer, dim, inner] = dims;
+    let program = programs::gather::scatter(T::TYPE)?;
+
+    let indices = indices.read()?.to_cl()?;
+    let values = values.read()?.to_cl()?;
+    let y = y.cl_buffer()?;
+
+    // check every index before updating anything, so that an error leaves `y` unchanged
+    check_indices(&indices, dim, scatter_bounds)?;
+
+    if values.len() == 0 {
+        return Ok(());
+    }
+
+    let queue = OpenCL::queue(
+        values.len(),
+        &[
+            y.default_queue(),
+            indices.default_queue(),
+            values.default_queue(),
+        ],
+    )?;
+
+    let kernel = Kernel::builder()
+        .name(kernel_name)
+        .queue(queue)
+        .program(&program)
+        .global_work_size(values.len())
+        .arg(dim as u64)
+        .arg(inner as u64)
+        .arg(indices.len() as u64)
+        .arg(&*indices)
+        .arg(&*values)
+        .arg(&*y)
+        .build()?;
+
+    unsafe { events::enq(&kernel)? }
+
+    Ok(())
+}
@@ -0,0 +1,151 @@
+//! An on-disk cache of compiled program binaries, to avoid recompiling kernels across processes
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use ocl::core::{DeviceInfo, ProgramInfo, ProgramInfoResult};
+use ocl::{Device, Program};
+
+use crate::Error;
+
+use super::OpenCL;
+
+const MAGIC: &[u8; 8] = b"densorcl";
+
+/// Load the cached binaries of the program with the given source, or compile and cache them.
+pub fn build(dir: &Path, src: &str) -> Result<Program, Error> {
+    let devices = OpenCL::context().devices();
+    let path = path(dir, src, &devices)?;
+
+    if let Some(program) = load(&path, &devices) {
+        return Ok(program);
+    }
+
+    let program = Program::builder()
+        .devices(devices.as_slice())
+        .source(src)
+        .build(OpenCL::context())?;
+
+    // failing to write to the cache should not prevent using the program
+    store(&path, &program);
+
+    Ok(program)
+}
+
+fn load(path: &Path, devices: &[Device]) -> Option<Program> {
+    let data = fs::read(path).ok()?;
+    let binaries = decode(&data)?;
+
+    if binaries.len() != devices.len() {
+        return None;
+    }
+
+    // a cached binary can be rejected, e.g. if the device driver has been updated since
+    Program::builder()
+        .devices(devices)
+        .binaries(&binaries)
+        .build(OpenCL::context())
+        .ok()
+}
+
+fn store(path: &Path, program: &Program) -> Option<()> {
+    let binaries = match program.info(ProgramInfo::Binaries).ok()? {
+        ProgramInfoResult::Binaries(binaries) => binaries,
+        _ => return None,
+    };
+
+    fs::create_dir_all(path.parent()?).ok()?;
+
+    // write to a temporary file first so that a concurrent process never reads a partial entry
+    let tmp = path.with_extension(format!("tmp{}", std::process::id()));
+    fs::write(&tmp, encode(&binaries)).ok()?;
+    fs::rename(&tmp, path).ok()
+}
+
+fn path(dir: &Path, src: &str, devices: &[Device]) -> Result<PathBuf, Error> {
+    let mut hash = Fnv::default();
+    hash.write(src.as_bytes());
+
+    for device in devices {
+        for info in [
+            DeviceInfo::Name,
+            DeviceInfo::Version,
+            DeviceInfo::DriverVersion,
+        ] {
+            let info = device.info(info).map_err(ocl::Error::from)?;
+            hash.write(info.to_string().as_bytes());
+        }
+    }
+
+    Ok(dir.join(format!("{:016x}.bin", hash.0)))
+}
+
+fn encode(binaries: &[Vec<u8>]) -> Vec<u8> {
+    let len = binaries
+        .iter()
+        .map(|binary| binary.len() + 8)
+        .sum::<usize>();
+    let mut data = Vec::with_capacity(MAGIC.len() + 8 + len);
+
+    data.extend_from_slice(MAGIC);
+    data.extend_from_slice(&(binaries.len() as u64).to_le_bytes());
+
+    for binary in binaries {
+        data.extend_from_slice(&(binary.len() as u64).to_le_bytes());
+        data.extend_from_slice(binary);
+    }
+
+    data
+}
+
+fn decode(data: &[u8]) -> Option<Vec<&[u8]>> {
+    let data = data.strip_prefix(MAGIC.as_slice())?;
+    let (count, mut data) = read_u64(data)?;
+
+    let mut binaries = Vec::with_capacity(count.min(16) as usize);
+
+    for _ in 0..count {
+        let (len, rest) = read_u64(data)?;
+        let len = usize::try_from(len).ok().filter(|len| *len <= rest.len())?;
+        let (binary, rest) = rest.split_at(len);
+        binaries.push(binary);
+        data = rest;
+    }
+
+    if data.is_empty() {
+        Some(binaries)
+    } else {
+        None
+    }
+}
+
+fn read_u64(data: &[u8]) -> Option<(u64, &[u8])> {
+    if data.len() >= 8 {
+        let (n, rest) = data.split_at(8);
+        Some((u64::from_le_bytes(n.try_into().ok()?), rest))
+    } else {
+        None
+    }
+}
+
+/// The 64-bit FNV-1a hash, which (unlike the default hasher) is stable across Rust versions
+struct Fnv(u64);
+
+impl Default for Fnv {
+    fn default() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+}
+
+impl Fnv {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+
+        // delimit each input so that e.g. ("ab", "c") and ("a", "bc") hash differently
+        self.0 ^= 0xff;
+        self.0 = self.0.wrapping_mul(0x100000001b3);
+    }
+}
@@ -94,6 +94,21 @@ pub fn random_uniform() -> Result<Program, Error> {
     build(&src)
 }
 
+#[memoize]
+pub fn eye(c_type: &'static str) -> Result<Program, Error> {
+    let src = format!(
+        r#"
+        __kernel void eye(const ulong dim, __global {c_type}* output) {{
+            const ulong offset = get_global_id(0);
+            const ulong i = offset % (dim * dim);
+            output[offset] = (i / dim == i % dim) ? 1 : 0;
+        }}
+        "#,
+    );
+
+    build(&src)
+}
+
 #[memoize]
 pub fn range(c_type: &'static str) -> Result<Program, Error> {
     let src = format!(
@@ -0,0 +1,310 @@
+use memoize::memoize;
+use ocl::Program;
+
+use crate::opencl::template::Template;
+use crate::Error;
+
+#[memoize]
+pub fn conv(c_type: &'static str) -> Result<Program, Error> {
+    // each plane of the output is padded to a whole number of work groups, so that every work
+    // item in a group reads the same output channel of the kernel, one tile at a time
+    let body = r#"
+    const ulong offset = get_global_id(0);
+    const ulong local_offset = get_local_id(0);
+    const ulong tile_size = get_local_size(0);
+
+    const ulong plane = dims_out.x * dims_out.y * dims_out.z;
+    const ulong padded = tile_size * ((plane + tile_size - 1) / tile_size);
+
+    const ulong position = offset % padded;
+    const ulong channel_out = (offset / padded) % out_channels;
+    const ulong batch = offset / (padded * out_channels);
+
+    const ulong z = position / (dims_out.y * dims_out.z);
+    const ulong y = (position / dims_out.z) % dims_out.y;
+    const ulong x = position % dims_out.z;
+
+    const ulong group = channel_out / (out_channels / groups);
+    const ulong window = dims_kernel.x * dims_kernel.y * dims_kernel.z;
+    const ulong rows = group_in * window;
+
+    T sum = 0;
+
+    for (ulong tile_start = 0; tile_start < rows; tile_start += tile_size) {
+        if (tile_start + local_offset < rows) {
+            tile[local_offset] = weights[(channel_out * rows) + tile_start + local_offset];
+        }
+
+        barrier(CLK_LOCAL_MEM_FENCE);
+
+        if (position < plane) {
+            const ulong tile_len = min(tile_size, rows - tile_start);
+
+            for (ulong j = 0; j < tile_len; j++) {
+                const ulong row = tile_start + j;
+
+                const ulong c_in = (group * group_in) + (row / window);
+                const ulong kz = (row / (dims_kernel.y * dims_kernel.z)) % dims_kernel.x;
+                const ulong ky = (row / dims_kernel.z) % dims_kernel.y;
+                const ulong kx = row % dims_kernel.z;
+
+                const ulong tz = (z * stride.x) + (kz * dilation.x);
+                const ulong ty = (y * stride.y) + (ky * dilation.y);
+                const ulong tx = (x * stride.z) + (kx * dilation.z);
+
+                if (tz < padding.x || ty < padding.y || tx < padding.z) {
+                    continue;
+                }
+
+                const ulong iz = tz - padding.x;
+                const ulong iy = ty - padding.y;
+                const ulong ix = tx - padding.z;
+
+                if (iz >= dims_in.x || iy >= dims_in.y || ix >= dims_in.z) {
+                    continue;
+                }
+
+                const ulong i = ((((batch * in_channels) + c_in) * dims_in.x + iz) * dims_in.y + iy) * dims_in.z + ix;
+
+                sum += input[i] * tile[j];
+            }
+        }
+
+        barrier(CLK_LOCAL_MEM_FENCE);
+    }
+
+    if (position < plane) {
+        output[(((batch * out_channels) + channel_out) * plane) + position] = sum;
+    }"#;
+
+    Template::new()
+        .define("T", c_type)
+        .kernel(
+            "conv",
+            &[
+                "const ulong in_channels",
+                "const ulong out_channels",
+                "const ulong group_in",
+                "const ulong groups",
+                "const ulong4 dims_in",
+                "const ulong4 dims_kernel",
+                "const ulong4 dims_out",
+                "const ulong4 stride",
+                "const ulong4 padding",
+                "const ulong4 dilation",
+                "__global const T* restrict input",
+                "__global const T* restrict weights",
+                "__global T* restrict output",
+                "__local T* tile",
+            ],
+            body,
+        )
+        .build()
+}
+
+#[memoize]
+pub fn conv_transpose2d(c_type: &'static str) -> Result<Program, Error> {
+    let body = r#"
+    const ulong offset = get_global_id(0);
+
+    const ulong x = offset % dims_out.w;
+    const ulong y = (offset / dims_out.w) % dims_out.z;
+    const ulong channel_out = (offset / (dims_out.w * dims_out.z)) % dims_out.y;
+    const ulong batch = offset / (dims_out.w * dims_out.z * dims_out.y);
+
+    const ulong group_in = dims_in.y / groups;
+    const ulong group = channel_out / dims_kernel.y;
+    const ulong channel = channel_out % dims_kernel.y;
+
+    T sum = 0;
+
+    for (ulong c_in = group * group_in; c_in < (group + 1) * group_in; c_in++) {
+        for (ulong ky = 0; ky < dims_kernel.z; ky++) {
+            const ulong ty = y + padding.x;
+
+            if (ty < ky * dilation.x || (ty - (ky * dilation.x)) % stride.x != 0) {
+                continue;
+            }
+
+            const ulong iy = (ty - (ky * dilation.x)) / stride.x;
+
+            if (iy >= dims_in.z) {
+                continue;
+            }
+
+            for (ulong kx = 0; kx < dims_kernel.w; kx++) {
+                const ulong tx = x + padding.y;
+
+                if (tx < kx * dilation.y || (tx - (kx * dilation.y)) % stride.y != 0) {
+                    continue;
+                }
+
+                const ulong ix = (tx - (kx * dilation.y)) / stride.y;
+
+                if (ix >= dims_in.w) {
+                    continue;
+                }
+
+                const ulong i = (((batch * dims_in.y) + c_in) * dims_in.z + iy) * dims_in.w + ix;
+                const ulong k = (((c_in * dims_kernel.y) + channel) * dims_kernel.z + ky) * dims_kernel.w + kx;
+
+                sum += input[i] * kernel[k];
+            }
+        }
+    }
+
+    output[offset] = sum;"#;
+
+    Template::new()
+        .define("T", c_type)
+        .kernel(
+            "conv_transpose2d",
+            &[
+                "const ulong4 dims_in",
+                "const ulong4 dims_kernel",
+                "const ulong4 dims_out",
+                "const ulong2 stride",
+                "const ulong2 padding",
+                "const ulong2 dilation",
+                "const ulong groups",
+                "__global const T* restrict input",
+                "__global const T* restrict kernel",
+                "__global T* restrict output",
+            ],
+            body,
+        )
+        .build()
+}
+
+#[memoize]
+pub fn matched_filter(c_type: &'static str, f_type: &'static str) -> Result<Program, Error> {
+    // the group loads the template one tile at a time, so every work item must reach each barrier
+    let body = r#"
+    const ulong offset = get_global_id(0);
+    const ulong local_offset = get_local_id(0);
+    const ulong tile_size = get_local_size(0);
+
+    const ulong out_len = len - width + 1;
+    const ulong start = (offset / out_len) * len + (offset % out_len);
+
+    F dot = 0;
+    F energy = 0;
+    F template_energy = 0;
+
+    for (ulong tile_start = 0; tile_start < width; tile_start += tile_size) {
+        if (tile_start + local_offset < width) {
+            tile[local_offset] = (F) pattern[tile_start + local_offset];
+        }
+
+        barrier(CLK_LOCAL_MEM_FENCE);
+
+        if (offset < size) {
+            const ulong tile_len = min(tile_size, width - tile_start);
+
+            for (ulong j = 0; j < tile_len; j++) {
+                const F x = (F) input[start + tile_start + j];
+                const F t = tile[j];
+
+                dot += x * t;
+                energy += x * x;
+                template_energy += t * t;
+            }
+        }
+
+        barrier(CLK_LOCAL_MEM_FENCE);
+    }
+
+    if (offset < size) {
+        if (energy == 0 || template_energy == 0) {
+            output[offset] = 0;
+        } else {
+            output[offset] = dot / sqrt(energy * template_energy);
+        }
+    }"#;
+
+    Template::new()
+        .define("T", c_type)
+        .define("F", f_type)
+        .kernel(
+            "matched_filter",
+            &[
+                "const ulong len",
+                "const ulong width",
+                "const ulong size",
+                "__global const T* restrict input",
+                "__global const T* restrict pattern",
+                "__global F* restrict output",
+                "__local F* tile",
+            ],
+            body,
+        )
+        .build()
+}
+
+#[memoize]
+pub fn upsample(c_type: &'static str) -> Result<Program, Error> {
+    let f_type = if c_type == "double" {
+        "double"
+    } else {
+        "float"
+    };
+
+    let round = if matches!(c_type, "half" | "float" | "double") {
+        "n"
+    } else {
+        "round(n)"
+    };
+
+    let nearest = r#"
+    const ulong offset = get_global_id(0);
+
+    const ulong x = offset % (dims.y * scale.y);
+    const ulong y = (offset / (dims.y * scale.y)) % (dims.x * scale.x);
+    const ulong start = (offset / (dims.y * scale.y * dims.x * scale.x)) * dims.x * dims.y;
+
+    output[offset] = input[start + ((y / scale.x) * dims.y) + (x / scale.y)];"#;
+
+    let bilinear = format!(
+        r#"
+    const ulong offset = get_global_id(0);
+
+    const ulong x = offset % (dims.y * scale.y);
+    const ulong y = (offset / (dims.y * scale.y)) % (dims.x * scale.x);
+    const ulong start = (offset / (dims.y * scale.y * dims.x * scale.x)) * dims.x * dims.y;
+
+    const F source_y = max((((F) y + 0.5) / (F) scale.x) - 0.5, (F) 0.);
+    const F source_x = max((((F) x + 0.5) / (F) scale.y) - 0.5, (F) 0.);
+
+    const ulong y0 = min((ulong) floor(source_y), dims.x - 1);
+    const ulong x0 = min((ulong) floor(source_x), dims.y - 1);
+    const ulong y1 = min(y0 + 1, dims.x - 1);
+    const ulong x1 = min(x0 + 1, dims.y - 1);
+
+    const F wy = source_y - (F) y0;
+    const F wx = source_x - (F) x0;
+
+    const F top = ((F) input[start + (y0 * dims.y) + x0] * (1 - wx))
+        + ((F) input[start + (y0 * dims.y) + x1] * wx);
+
+    const F bottom = ((F) input[start + (y1 * dims.y) + x0] * (1 - wx))
+        + ((F) input[start + (y1 * dims.y) + x1] * wx);
+
+    const F n = (top * (1 - wy)) + (bottom * wy);
+
+    output[offset] = (T) {round};"#
+    );
+
+    let params = [
+        "const ulong2 dims",
+        "const ulong2 scale",
+        "__global const T* restrict input",
+        "__global T* restrict output",
+    ];
+
+    Template::new()
+        .define("T", c_type)
+        .define("F", f_type)
+        .kernel("upsample_nearest", &params, nearest)
+        .kernel("upsample_bilinear", &params, &bilinear)
+        .build()
+}
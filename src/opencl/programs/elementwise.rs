@@ -1,20 +1,23 @@
 use memoize::memoize;
 use ocl::Program;
 
+use crate::opencl::template::Template;
+use crate::opencl::OpenCL;
 use crate::Error;
 
-use super::build;
+use super::{build, VEC_WIDTHS};
 
 #[memoize]
-pub fn cast(i_type: &'static str, o_type: &'static str) -> Result<Program, Error> {
+pub fn axpy(c_type: &'static str) -> Result<Program, Error> {
     let src = format!(
         r#"
-        __kernel void cast(
-            __global const {i_type}* restrict input,
-            __global {o_type}* restrict output)
+        __kernel void axpy(
+            __global {c_type}* restrict y,
+            const {c_type} alpha,
+            __global const {c_type}* restrict x)
         {{
             const ulong offset = get_global_id(0);
-            output[offset] = ({o_type}) input[offset];
+            y[offset] += alpha * x[offset];
         }}
         "#,
     );
@@ -22,6 +25,24 @@ pub fn cast(i_type: &'static str, o_type: &'static str) -> Result<Program, Error
     build(&src)
 }
 
+#[memoize]
+pub fn bitcast(i_type: &'static str, o_type: &'static str) -> Result<Program, Error> {
+    let expr = format!("as_{o_type}(input)");
+
+    Template::new()
+        .elementwise("bitcast", &[("input", i_type)], o_type, &expr)
+        .build()
+}
+
+#[memoize]
+pub fn cast(i_type: &'static str, o_type: &'static str) -> Result<Program, Error> {
+    let expr = format!("({o_type}) input");
+
+    Template::new()
+        .elementwise("cast", &[("input", i_type)], o_type, &expr)
+        .build()
+}
+
 #[memoize]
 pub fn dual_boolean(c_type: &'static str, op: &'static str) -> Result<Program, Error> {
     let src = format!(
@@ -79,18 +100,95 @@ pub fn dual_boolean(c_type: &'static str, op: &'static str) -> Result<Program, E
             const ulong offset = get_global_id(0);
             output[offset] = {op}(left[offset], right);
         }}
-        "#
+
+        {dual_vec}
+        "#,
+        dual_vec = vectorize(
+            "dual",
+            &[("left", c_type), ("right", c_type)],
+            "uchar",
+            op,
+            None
+        ),
     );
 
     build(&src)
 }
 
 #[memoize]
-pub fn dual(c_type: &'static str, op: &'static str) -> Result<Program, Error> {
+pub fn dual_float(
+    c_type: &'static str,
+    f_type: &'static str,
+    op: &'static str,
+) -> Result<Program, Error> {
+    // convert each input to its floating-point type before applying a floating-point builtin
     let src = format!(
         r#"
-        inline {c_type} _log(const double left, const double right) {{
-            return log(left) / log(right);
+        inline {f_type} _{op}(const {f_type} left, const {f_type} right) {{
+            return {op}(left, right);
+        }}
+
+        {dual_vec}
+        "#,
+        dual_vec = vectorize(
+            "dual",
+            &[("left", c_type), ("right", c_type)],
+            f_type,
+            &format!("_{op}"),
+            None
+        ),
+    );
+
+    build(&src)
+}
+
+#[memoize]
+pub fn dual(c_type: &'static str, op: &'static str, fast_math: bool) -> Result<Program, Error> {
+    // devices without 64-bit floating point support compute logarithms at single precision,
+    // as does fast math on single-precision inputs, since the native builtins are float-only
+    let log_type = if OpenCL::supports::<f64>() && !(fast_math && c_type == "float") {
+        "double"
+    } else {
+        "float"
+    };
+
+    let fast_math = fast_math && log_type == "float";
+
+    // division by zero is IEEE 754 for a floating-point type, but would be undefined for an integer
+    let div = match c_type {
+        "float" | "double" => "DIVIDE(left, right)",
+        _ => "right == 0 ? 0 : left / right",
+    };
+
+    // these ops can be applied to vector types directly, rather than to each lane
+    let native = match op {
+        "add" => Some("left_vec + right_vec"),
+        "div" if fast_math && c_type == "float" => Some("native_divide(left_vec, right_vec)"),
+        "mul" => Some("left_vec * right_vec"),
+        "sub" => Some("left_vec - right_vec"),
+        _ => None,
+    };
+
+    let src = format!(
+        r#"
+        {math}
+
+        inline {c_type} _log(const {log_type} left, const {log_type} right) {{
+            return DIVIDE(LOG(left), LOG(right));
+        }}
+
+        inline {c_type} _logaddexp(const {log_type} left, const {log_type} right) {{
+            if (isnan(left) || isnan(right)) {{
+                return left + right;
+            }}
+
+            const {log_type} max = fmax(left, right);
+
+            if (isinf(max)) {{
+                return max;
+            }} else {{
+                return max + log1p(EXP(-fabs(left - right)));
+            }}
         }}
 
         inline {c_type} add(const {c_type} left, const {c_type} right) {{
@@ -98,11 +196,7 @@ pub fn dual(c_type: &'static str, op: &'static str) -> Result<Program, Error> {
         }}
 
         inline {c_type} div(const {c_type} left, const {c_type} right) {{
-            if (right == 0) {{
-                return 0;
-            }} else {{
-                return left / right;
-            }}
+            return {div};
         }}
 
         inline {c_type} mul(const {c_type} left, const {c_type} right) {{
@@ -113,6 +207,10 @@ pub fn dual(c_type: &'static str, op: &'static str) -> Result<Program, Error> {
             return left - right;
         }}
 
+        inline {c_type} _step(const {c_type} left, const {c_type} right) {{
+            return left < right ? 0 : 1;
+        }}
+
         __kernel void dual(
             __global const {c_type}* restrict left,
             __global const {c_type}* restrict right,
@@ -130,20 +228,79 @@ pub fn dual(c_type: &'static str, op: &'static str) -> Result<Program, Error> {
             const ulong offset = get_global_id(0);
             output[offset] = {op}(left[offset], right);
         }}
+
+        {dual_vec}
         "#,
+        math = math(fast_math),
+        dual_vec = vectorize(
+            "dual",
+            &[("left", c_type), ("right", c_type)],
+            c_type,
+            op,
+            native
+        ),
     );
 
     build(&src)
 }
 
+#[memoize]
+pub fn ternary(
+    c_type: &'static str,
+    f_type: &'static str,
+    expr: &'static str,
+) -> Result<Program, Error> {
+    let inputs = [("a", c_type), ("b", c_type), ("c", c_type)];
+
+    let lerp = format!(
+        r#"
+        inline {c_type} _lerp(const {f_type} from, const {f_type} to, const {f_type} t) {{
+            return from + ((to - from) * t);
+        }}
+        "#
+    );
+
+    let smoothstep = format!(
+        r#"
+        inline {c_type} _smoothstep(const {f_type} n, const {f_type} edge0, const {f_type} edge1) {{
+            const {f_type} t = clamp((n - edge0) / (edge1 - edge0), ({f_type}) 0, ({f_type}) 1);
+            return t * t * (3 - (2 * t));
+        }}
+        "#
+    );
+
+    Template::new()
+        .helper(lerp)
+        .helper(smoothstep)
+        .elementwise("ternary", &inputs, c_type, expr)
+        .build()
+}
+
 pub fn unary(
     f_type: &'static str,
     i_type: &'static str,
     o_type: &'static str,
     op: &'static str,
+    fast_math: bool,
 ) -> Result<Program, Error> {
+    let recip = match i_type {
+        "float" => "native_recip(input)",
+        "double" => "1 / input",
+        _ => "input == 0 ? 0 : 1 / input",
+    };
+
     let src = format!(
         r#"
+        {math}
+
+        inline {f_type} _exp(const {f_type} input) {{
+            return EXP(input);
+        }}
+
+        inline {i_type} _recip(const {i_type} input) {{
+            return {recip};
+        }}
+
         inline uchar not(const {i_type} input) {{
             if (input == 0) {{
                 return 1;
@@ -153,15 +310,133 @@ pub fn unary(
         }}
 
         inline {f_type} _log(const {f_type} input) {{
-            return log(input);
+            return LOG(input);
+        }}
+
+        inline {f_type} _softplus(const {f_type} input) {{
+            if (input > 0) {{
+                return input + log1p(exp(-input));
+            }} else {{
+                return log1p(exp(input));
+            }}
         }}
 
         __kernel void unary(__global const {i_type}* input, __global {o_type}* output) {{
             const ulong offset = get_global_id(0);
             output[offset] = {op}(input[offset]);
         }}
+
+        {unary_vec}
         "#,
+        math = math(fast_math && f_type == "float"),
+        unary_vec = vectorize("unary", &[("input", i_type)], o_type, op, None),
     );
 
     build(&src)
 }
+
+/// Define the `DIVIDE`, `EXP`, and `LOG` functions used by a program, which are
+/// the approximate single-precision `native_*` builtins if `fast_math` is enabled.
+fn math(fast_math: bool) -> &'static str {
+    if fast_math {
+        r#"
+        #define DIVIDE(left, right) native_divide(left, right)
+        #define EXP(n) native_exp(n)
+        #define LOG(n) native_log(n)
+        "#
+    } else {
+        r#"
+        #define DIVIDE(left, right) ((left) / (right))
+        #define EXP(n) exp(n)
+        #define LOG(n) log(n)
+        "#
+    }
+}
+
+/// Render a kernel `{name}_vec{width}` for each of the [`VEC_WIDTHS`].
+/// See [`vectorize_width`].
+fn vectorize(
+    name: &str,
+    inputs: &[(&str, &str)],
+    o_type: &str,
+    op: &str,
+    native: Option<&str>,
+) -> String {
+    VEC_WIDTHS
+        .into_iter()
+        .map(|width| vectorize_width(name, inputs, o_type, op, native, width))
+        .collect()
+}
+
+/// Render a kernel `{name}_vec{width}` which applies `op` to `width` elements per work item
+/// using vector loads and stores, and to each remaining element of a buffer whose length is not
+/// a multiple of the vector width. The kernel takes the buffer length as its first argument.
+/// If given, the `native` expression is applied to the vectors `{input}_vec` as a whole.
+fn vectorize_width(
+    name: &str,
+    inputs: &[(&str, &str)],
+    o_type: &str,
+    op: &str,
+    native: Option<&str>,
+    width: usize,
+) -> String {
+    let params = inputs
+        .iter()
+        .map(|(input, c_type)| format!("__global const {c_type}* restrict {input},"))
+        .collect::<Vec<_>>()
+        .join("\n            ");
+
+    let loads = inputs
+        .iter()
+        .map(|(input, c_type)| {
+            format!("const {c_type}{width} {input}_vec = vload{width}(0, {input} + offset);")
+        })
+        .collect::<Vec<_>>()
+        .join("\n                ");
+
+    let expr = if let Some(native) = native {
+        native.to_string()
+    } else {
+        let lanes = (0..width)
+            .map(|lane| {
+                let args = inputs
+                    .iter()
+                    .map(|(input, _)| format!("{input}_vec.s{lane:x}"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                format!("{op}({args})")
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        format!("({o_type}{width})({lanes})")
+    };
+
+    let tail = inputs
+        .iter()
+        .map(|(input, _)| format!("{input}[i]"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    format!(
+        r#"
+        __kernel void {name}_vec{width}(
+            const ulong size,
+            {params}
+            __global {o_type}* restrict output)
+        {{
+            const ulong offset = get_global_id(0) * {width};
+
+            if (offset + {width} <= size) {{
+                {loads}
+                vstore{width}({expr}, 0, output + offset);
+            }} else {{
+                for (ulong i = offset; i < size; i++) {{
+                    output[i] = {op}({tail});
+                }}
+            }}
+        }}
+        "#
+    )
+}
@@ -1,26 +1,55 @@
 use memoize::memoize;
 use ocl::Program;
 
+use crate::opencl::template::Template;
 use crate::Error;
 
 use super::build;
 
 #[memoize]
 pub fn gather_cond(c_type: &'static str) -> Result<Program, Error> {
+    let inputs = [("cond", "uchar"), ("then", c_type), ("or_else", c_type)];
+
+    Template::new()
+        .elementwise("gather_cond", &inputs, c_type, "cond != 0 ? then : or_else")
+        .build()
+}
+
+#[memoize]
+pub fn gather_cond_scalar(c_type: &'static str) -> Result<Program, Error> {
     let src = format!(
         r#"
-        __kernel void gather_cond(
+        __kernel void gather_cond_scalar(
             __global const uchar* restrict cond,
-            __global const {c_type}* restrict then,
-            __global const {c_type}* restrict or_else,
+            const {c_type} then,
+            const {c_type} or_else,
             __global {c_type}* restrict output)
         {{
             const ulong offset = get_global_id(0);
+            output[offset] = cond[offset] != 0 ? then : or_else;
+        }}
+        "#,
+    );
+
+    build(&src)
+}
 
-            if (cond[offset] != 0) {{
-                output[offset] = then[offset];
-            }} else {{
-                output[offset] = or_else[offset];
+#[memoize]
+pub fn compact(c_type: &'static str) -> Result<Program, Error> {
+    let src = format!(
+        r#"
+        __kernel void compact(
+            __global const {c_type}* restrict input,
+            __global const ulong* restrict positions,
+            __global {c_type}* restrict output)
+        {{
+            const ulong offset = get_global_id(0);
+
+            const ulong position = positions[offset];
+            const ulong prev = offset == 0 ? 0 : positions[offset - 1];
+
+            if (position > prev) {{
+                output[position - 1] = input[offset];
             }}
         }}
         "#,
@@ -28,3 +57,134 @@ pub fn gather_cond(c_type: &'static str) -> Result<Program, Error> {
 
     build(&src)
 }
+
+#[memoize]
+pub fn gather_axis(c_type: &'static str) -> Result<Program, Error> {
+    let src = format!(
+        r#"
+        __kernel void gather_axis(
+            const ulong dim,
+            const ulong inner,
+            const ulong num_indices,
+            __global const {c_type}* restrict source,
+            __global const ulong* restrict indices,
+            __global {c_type}* restrict output)
+        {{
+            const ulong offset = get_global_id(0);
+
+            const ulong o = offset / (num_indices * inner);
+            const ulong index = indices[(offset / inner) % num_indices];
+
+            // a kernel cannot return an error, so an out-of-bounds index reads zero
+            output[offset] = index < dim ? source[(((o * dim) + index) * inner) + (offset % inner)] : 0;
+        }}
+        "#,
+    );
+
+    build(&src)
+}
+
+#[memoize]
+pub fn pad_segments(c_type: &'static str) -> Result<Program, Error> {
+    let src = format!(
+        r#"
+        __kernel void pad_segments(
+            const ulong max_len,
+            const {c_type} pad,
+            __global const ulong* restrict offsets,
+            __global const {c_type}* restrict values,
+            __global {c_type}* restrict output)
+        {{
+            const ulong offset = get_global_id(0);
+
+            const ulong start = offsets[offset / max_len];
+            const ulong i = start + (offset % max_len);
+
+            output[offset] = i < offsets[(offset / max_len) + 1] ? values[i] : pad;
+        }}
+        "#,
+    );
+
+    build(&src)
+}
+
+#[memoize]
+pub fn scatter(c_type: &'static str) -> Result<Program, Error> {
+    // an atomic add is a compare-and-swap loop over the bits of the element to update
+    let atomic = match c_type {
+        "float" | "int" | "uint" => Some(("", "uint", "atomic_cmpxchg")),
+        "double" | "long" | "ulong" => Some((
+            "#pragma OPENCL EXTENSION cl_khr_int64_base_atomics : enable",
+            "ulong",
+            "atom_cmpxchg",
+        )),
+        _ => None,
+    };
+
+    let scatter_add = if let Some((pragma, bits, cmpxchg)) = atomic {
+        format!(
+            r#"
+            {pragma}
+
+            __kernel void scatter_add(
+                const ulong dim,
+                const ulong inner,
+                const ulong num_indices,
+                __global const ulong* restrict indices,
+                __global const {c_type}* restrict values,
+                __global {c_type}* output)
+            {{
+                const ulong offset = get_global_id(0);
+
+                const ulong o = offset / (num_indices * inner);
+                const ulong index = indices[(offset / inner) % num_indices];
+
+                if (index >= dim) {{
+                    return;
+                }}
+
+                const ulong target = (((o * dim) + index) * inner) + (offset % inner);
+                volatile __global {bits}* bits = (volatile __global {bits}*) &output[target];
+
+                const {c_type} value = values[offset];
+                {bits} current = *bits;
+                {bits} expected;
+
+                do {{
+                    expected = current;
+                    current = {cmpxchg}(bits, expected, as_{bits}(as_{c_type}(expected) + value));
+                }} while (current != expected);
+            }}
+            "#
+        )
+    } else {
+        String::default()
+    };
+
+    let src = format!(
+        r#"
+        __kernel void scatter(
+            const ulong dim,
+            const ulong inner,
+            const ulong num_indices,
+            __global const ulong* restrict indices,
+            __global const {c_type}* restrict values,
+            __global {c_type}* output)
+        {{
+            const ulong offset = get_global_id(0);
+
+            const ulong o = offset / (num_indices * inner);
+            const ulong index = indices[(offset / inner) % num_indices];
+
+            // a kernel cannot return an error, so an out-of-bounds index is skipped
+            if (index < dim) {{
+                output[(((o * dim) + index) * inner) + (offset % inner)] = values[offset];
+            }}
+        }}
+
+        {scatter_add}
+        "#,
+    );
+
+    build(&src)
+}
@@ -1,9 +1,12 @@
 use memoize::memoize;
 use ocl::Program;
 
+use crate::host::ops::JACOBI_SWEEPS;
+use crate::opencl::template::Template;
+use crate::ops::Factor;
 use crate::Error;
 
-use super::{build, TILE_SIZE, WG_SIZE};
+use super::build;
 
 #[memoize]
 pub fn diagonal(c_type: &'static str) -> Result<Program, Error> {
@@ -23,6 +26,429 @@ pub fn diagonal(c_type: &'static str) -> Result<Program, Error> {
     build(&src)
 }
 
+#[memoize]
+pub fn trace(c_type: &'static str) -> Result<Program, Error> {
+    let src = format!(
+        r#"
+        __kernel void trace(
+            const ulong dim,
+            __global const {c_type}* restrict matrices,
+            __global {c_type}* restrict traces)
+        {{
+            const ulong m = get_global_id(0);
+            const ulong start = m * dim * dim;
+
+            {c_type} sum = 0;
+            for (ulong i = 0; i < dim; i++) {{
+                sum += matrices[start + (i * dim) + i];
+            }}
+
+            traces[m] = sum;
+        }}
+        "#,
+    );
+
+    build(&src)
+}
+
+#[memoize]
+pub fn diag_from(c_type: &'static str) -> Result<Program, Error> {
+    let src = format!(
+        r#"
+        __kernel void diag_from(
+            const ulong dim,
+            __global const {c_type}* restrict diagonals,
+            __global {c_type}* restrict matrices)
+        {{
+            const ulong offset = get_global_id(0);
+            const ulong m = offset / (dim * dim);
+            const ulong row = (offset / dim) % dim;
+            const ulong col = offset % dim;
+
+            matrices[offset] = (row == col) ? diagonals[(m * dim) + row] : 0;
+        }}
+        "#,
+    );
+
+    build(&src)
+}
+
+// factor the matrix at `input + start` into `a` and `p`, given `dim`, `start`, `a`, and `p`
+const LU_FACTOR: &str = r#"
+    for (ulong i = 0; i < dim * dim; i++) {
+        a[i] = (F) input[start + i];
+    }
+
+    for (ulong i = 0; i < dim; i++) {
+        p[i] = i;
+    }
+
+    // factor PA = LU, pivoting on the row with the largest magnitude in each column
+    for (ulong k = 0; k < dim; k++) {
+        ulong pivot = k;
+        F max = fabs(a[(k * dim) + k]);
+
+        for (ulong i = k + 1; i < dim; i++) {
+            const F n = fabs(a[(i * dim) + k]);
+            if (n > max) {
+                max = n;
+                pivot = i;
+            }
+        }
+
+        if (pivot != k) {
+            for (ulong j = 0; j < dim; j++) {
+                const F tmp = a[(k * dim) + j];
+                a[(k * dim) + j] = a[(pivot * dim) + j];
+                a[(pivot * dim) + j] = tmp;
+            }
+
+            const ulong tmp = p[k];
+            p[k] = p[pivot];
+            p[pivot] = tmp;
+        }
+
+        for (ulong i = k + 1; i < dim; i++) {
+            const F l = a[(i * dim) + k] / a[(k * dim) + k];
+            a[(i * dim) + k] = l;
+
+            for (ulong j = k + 1; j < dim; j++) {
+                a[(i * dim) + j] -= l * a[(k * dim) + j];
+            }
+        }
+    }
+"#;
+
+#[memoize]
+pub fn inverse(c_type: &'static str, f_type: &'static str) -> Result<Program, Error> {
+    let body = [
+        r#"
+    const ulong m = get_global_id(0);
+    const ulong start = m * dim * dim;
+
+    __global F* a = lu + start;
+    __global ulong* p = pivots + (m * dim);
+    __global F* x = output + start;
+"#,
+        LU_FACTOR,
+        r#"
+    // column j of the inverse solves LUx = Pe_j
+    for (ulong j = 0; j < dim; j++) {
+        for (ulong i = 0; i < dim; i++) {
+            F sum = p[i] == j ? 1 : 0;
+
+            for (ulong k = 0; k < i; k++) {
+                sum -= a[(i * dim) + k] * x[(k * dim) + j];
+            }
+
+            x[(i * dim) + j] = sum;
+        }
+
+        for (ulong i = dim; i-- > 0;) {
+            F sum = x[(i * dim) + j];
+
+            for (ulong k = i + 1; k < dim; k++) {
+                sum -= a[(i * dim) + k] * x[(k * dim) + j];
+            }
+
+            x[(i * dim) + j] = sum / a[(i * dim) + i];
+        }
+    }"#,
+    ]
+    .concat();
+
+    Template::new()
+        .define("T", c_type)
+        .define("F", f_type)
+        .kernel(
+            "inverse",
+            &[
+                "const ulong dim",
+                "__global const T* restrict input",
+                "__global F* restrict lu",
+                "__global ulong* restrict pivots",
+                "__global F* restrict output",
+            ],
+            &body,
+        )
+        .build()
+}
+
+#[memoize]
+pub fn decompose(
+    c_type: &'static str,
+    f_type: &'static str,
+    factor: Factor,
+) -> Result<Program, Error> {
+    let write = |value: &str| {
+        format!(
+            r#"
+    for (ulong i = 0; i < dim; i++) {{
+        for (ulong j = 0; j < dim; j++) {{
+            x[(i * dim) + j] = {value};
+        }}
+    }}"#
+        )
+    };
+
+    let body = match factor {
+        Factor::LuP | Factor::LuL | Factor::LuU => {
+            let value = match factor {
+                // row i of PA is row p[i] of A, so A = P^T LU
+                Factor::LuP => "p[j] == i ? 1 : 0",
+                Factor::LuL => "i == j ? 1 : (j < i ? a[(i * dim) + j] : 0)",
+                _ => "j >= i ? a[(i * dim) + j] : 0",
+            };
+
+            [
+                r#"
+    const ulong dim = rows;
+    const ulong m = get_global_id(0);
+    const ulong start = m * dim * dim;
+
+    __global F* a = work + start;
+    __global ulong* p = pivots + (m * dim);
+    __global F* x = output + start;
+"#,
+                LU_FACTOR,
+                &write(value),
+            ]
+            .concat()
+        }
+        Factor::QrQ | Factor::QrR => {
+            let output = if factor == Factor::QrQ {
+                r#"
+    for (ulong i = 0; i < rows; i++) {
+        for (ulong c = 0; c < k; c++) {
+            output[(m * rows * k) + (i * k) + c] = q[(i * rows) + c];
+        }
+    }"#
+            } else {
+                r#"
+    for (ulong i = 0; i < k; i++) {
+        for (ulong c = 0; c < cols; c++) {
+            output[(m * k * cols) + (i * cols) + c] = c < i ? 0 : r[(i * cols) + c];
+        }
+    }"#
+            };
+
+            [
+                r#"
+    const ulong m = get_global_id(0);
+    const ulong k = min(rows, cols);
+
+    __global F* r = work + (m * ((rows * cols) + (rows * rows) + rows));
+    __global F* q = r + (rows * cols);
+    __global F* v = q + (rows * rows);
+
+    for (ulong i = 0; i < rows * cols; i++) {
+        r[i] = (F) input[(m * rows * cols) + i];
+    }
+
+    for (ulong i = 0; i < rows * rows; i++) {
+        q[i] = (i / rows) == (i % rows) ? 1 : 0;
+    }
+
+    // reduce column j to the diagonal with the Householder reflection H = I - 2vv^T / v^Tv
+    for (ulong j = 0; j + 1 < rows && j < cols; j++) {
+        F norm = 0;
+        for (ulong i = j; i < rows; i++) {
+            v[i] = r[(i * cols) + j];
+            norm += v[i] * v[i];
+        }
+
+        norm = sqrt(norm);
+        v[j] -= v[j] > 0 ? -norm : norm;
+
+        F vv = 0;
+        for (ulong i = j; i < rows; i++) {
+            vv += v[i] * v[i];
+        }
+
+        if (vv == 0) {
+            continue;
+        }
+
+        // R = HR
+        for (ulong c = 0; c < cols; c++) {
+            F dot = 0;
+            for (ulong i = j; i < rows; i++) {
+                dot += v[i] * r[(i * cols) + c];
+            }
+
+            const F scale = (dot + dot) / vv;
+            for (ulong i = j; i < rows; i++) {
+                r[(i * cols) + c] -= scale * v[i];
+            }
+        }
+
+        // Q = QH
+        for (ulong row = 0; row < rows; row++) {
+            F dot = 0;
+            for (ulong i = j; i < rows; i++) {
+                dot += q[(row * rows) + i] * v[i];
+            }
+
+            const F scale = (dot + dot) / vv;
+            for (ulong i = j; i < rows; i++) {
+                q[(row * rows) + i] -= scale * v[i];
+            }
+        }
+    }
+"#,
+                output,
+            ]
+            .concat()
+        }
+        Factor::Cholesky => r#"
+    const ulong dim = rows;
+    const ulong start = get_global_id(0) * dim * dim;
+
+    __global F* x = output + start;
+
+    for (ulong i = 0; i < dim * dim; i++) {
+        x[i] = 0;
+    }
+
+    for (ulong j = 0; j < dim; j++) {
+        for (ulong i = j; i < dim; i++) {
+            F sum = (F) input[start + (i * dim) + j];
+
+            for (ulong k = 0; k < j; k++) {
+                sum -= x[(i * dim) + k] * x[(j * dim) + k];
+            }
+
+            x[(i * dim) + j] = i == j ? sqrt(sum) : sum / x[(j * dim) + j];
+        }
+    }"#
+        .to_string(),
+        Factor::EighValues | Factor::EighVectors => {
+            let output = if factor == Factor::EighValues {
+                r#"
+    for (ulong j = 0; j < dim; j++) {
+        output[(m * dim) + j] = a[(order[j] * dim) + order[j]];
+    }"#
+            } else {
+                r#"
+    for (ulong i = 0; i < dim; i++) {
+        for (ulong j = 0; j < dim; j++) {
+            output[(m * dim * dim) + (i * dim) + j] = v[(i * dim) + order[j]];
+        }
+    }"#
+            };
+
+            format!(
+                r#"
+    const ulong dim = rows;
+    const ulong m = get_global_id(0);
+    const ulong start = m * dim * dim;
+
+    __global F* a = work + (2 * start);
+    __global F* v = a + (dim * dim);
+    __global ulong* order = pivots + (m * dim);
+
+    // copy the lower triangle of the input to both triangles of a
+    for (ulong i = 0; i < dim; i++) {{
+        for (ulong j = 0; j <= i; j++) {{
+            a[(i * dim) + j] = (F) input[start + (i * dim) + j];
+            a[(j * dim) + i] = a[(i * dim) + j];
+        }}
+    }}
+
+    for (ulong i = 0; i < dim * dim; i++) {{
+        v[i] = (i / dim) == (i % dim) ? 1 : 0;
+    }}
+
+    // diagonalize a with cyclic Jacobi rotations A = J^T A J, accumulating V = VJ
+    for (ulong sweep = 0; sweep < {JACOBI_SWEEPS}; sweep++) {{
+        bool rotated = false;
+
+        for (ulong p = 0; p < dim; p++) {{
+            for (ulong q = p + 1; q < dim; q++) {{
+                const F app = a[(p * dim) + p];
+                const F aqq = a[(q * dim) + q];
+                const F apq = a[(p * dim) + q];
+
+                const F g = 100 * fabs(apq);
+                if (app + g == app && aqq + g == aqq) {{
+                    a[(p * dim) + q] = 0;
+                    a[(q * dim) + p] = 0;
+                    continue;
+                }}
+
+                const F theta = (aqq - app) / (2 * apq);
+                const F t = copysign((F) 1, theta) / (fabs(theta) + hypot(theta, (F) 1));
+                const F c = 1 / hypot(t, (F) 1);
+                const F s = t * c;
+
+                for (ulong k = 0; k < dim; k++) {{
+                    const F akp = a[(k * dim) + p];
+                    const F akq = a[(k * dim) + q];
+                    a[(k * dim) + p] = (c * akp) - (s * akq);
+                    a[(k * dim) + q] = (s * akp) + (c * akq);
+
+                    const F vkp = v[(k * dim) + p];
+                    const F vkq = v[(k * dim) + q];
+                    v[(k * dim) + p] = (c * vkp) - (s * vkq);
+                    v[(k * dim) + q] = (s * vkp) + (c * vkq);
+                }}
+
+                for (ulong k = 0; k < dim; k++) {{
+                    const F apk = a[(p * dim) + k];
+                    const F aqk = a[(q * dim) + k];
+                    a[(p * dim) + k] = (c * apk) - (s * aqk);
+                    a[(q * dim) + k] = (s * apk) + (c * aqk);
+                }}
+
+                rotated = true;
+            }}
+        }}
+
+        if (!rotated) {{
+            break;
+        }}
+    }}
+
+    // a stable insertion sort of the eigenvalues, with NaN last
+    for (ulong j = 0; j < dim; j++) {{
+        ulong i = j;
+        const F w = a[(j * dim) + j];
+
+        while (i > 0) {{
+            const F prev = a[(order[i - 1] * dim) + order[i - 1]];
+            if (isnan(w) || !(isnan(prev) || prev > w)) {{
+                break;
+            }}
+
+            order[i] = order[i - 1];
+            i--;
+        }}
+
+        order[i] = j;
+    }}
+"#
+            ) + output
+        }
+    };
+
+    Template::new()
+        .define("T", c_type)
+        .define("F", f_type)
+        .kernel(
+            "decompose",
+            &[
+                "const ulong rows",
+                "const ulong cols",
+                "__global const T* restrict input",
+                "__global F* restrict work",
+                "__global ulong* restrict pivots",
+                "__global F* restrict output",
+            ],
+            &body,
+        )
+        .build()
+}
+
 #[memoize]
 pub fn pad_matrices(c_type: &'static str) -> Result<Program, Error> {
     let src = format!(
@@ -50,14 +476,13 @@ pub fn pad_matrices(c_type: &'static str) -> Result<Program, Error> {
 }
 
 #[memoize]
-pub fn matmul(c_type: &'static str) -> Result<Program, Error> {
-    debug_assert_eq!(TILE_SIZE * TILE_SIZE, WG_SIZE);
-
+pub fn matmul(c_type: &'static str, tile_size: usize) -> Result<Program, Error> {
     let src = format!(
         r#"
         __kernel void matmul(
                 ulong4 const dims,
                 ulong const reduce_tiles,
+                ulong2 const strides,
                 __global const {c_type}* restrict left,
                 __global const {c_type}* restrict right,
                 __global {c_type}* restrict output)
@@ -71,26 +496,26 @@ pub fn matmul(c_type: &'static str) -> Result<Program, Error> {
             const ulong x_tile = get_global_id(1);
             const ulong z_tile = get_global_id(2);
 
-            const ulong x_offset = x_tile * {TILE_SIZE};
-            const ulong z_offset = z_tile * {TILE_SIZE};
-            const ulong left_offset = w * dims.x * dims.y;
-            const ulong right_offset = w * dims.y * dims.z;
+            const ulong x_offset = x_tile * {tile_size};
+            const ulong z_offset = z_tile * {tile_size};
+            const ulong left_offset = w * strides.x;
+            const ulong right_offset = w * strides.y;
 
-            {c_type} tile[{TILE_SIZE}][{TILE_SIZE}] = {{ 0 }};
+            {c_type} tile[{tile_size}][{tile_size}] = {{ 0 }};
 
             // initialize the local cache for the left and right tiles to zero
-            {c_type} left_tile[{TILE_SIZE}][{TILE_SIZE}] = {{ 0 }};
-            {c_type} right_tile[{TILE_SIZE}][{TILE_SIZE}] = {{ 0 }};
+            {c_type} left_tile[{tile_size}][{tile_size}] = {{ 0 }};
+            {c_type} right_tile[{tile_size}][{tile_size}] = {{ 0 }};
 
             // for each tile on the y axis
             for (ulong y_tile = 0; y_tile < reduce_tiles; y_tile++) {{
-                const ulong y_offset = y_tile * {TILE_SIZE};
+                const ulong y_offset = y_tile * {tile_size};
 
                 // read the left and right tiles into the local cache
                 #pragma unroll
-                for (uint i = 0; i < {TILE_SIZE}; i++) {{
+                for (uint i = 0; i < {tile_size}; i++) {{
                     #pragma unroll
-                    for (uint j = 0; j < {TILE_SIZE}; j++) {{
+                    for (uint j = 0; j < {tile_size}; j++) {{
                         ulong offset_l = left_offset + ((x_offset + i) * dims.y) + (y_offset + j);
                         left_tile[i][j] = left[offset_l];
 
@@ -101,11 +526,11 @@ pub fn matmul(c_type: &'static str) -> Result<Program, Error> {
 
                 // tile += left tile @ right tile
                 #pragma unroll
-                for (uint i = 0; i < {TILE_SIZE}; i++) {{
+                for (uint i = 0; i < {tile_size}; i++) {{
                     #pragma unroll
-                    for (uint j = 0; j < {TILE_SIZE}; j++) {{
+                    for (uint j = 0; j < {tile_size}; j++) {{
                         #pragma unroll
-                        for (uint k = 0; k < {TILE_SIZE}; k++) {{
+                        for (uint k = 0; k < {tile_size}; k++) {{
                             tile[i][k] += left_tile[i][j] * right_tile[j][k];
                         }}
                     }}
@@ -116,9 +541,9 @@ pub fn matmul(c_type: &'static str) -> Result<Program, Error> {
             ulong tile_offset = (w * dims.x * dims.z) + (x_offset * dims.z) + z_offset;
 
             #pragma unroll
-            for (uint i = 0; i < {TILE_SIZE}; i++) {{
+            for (uint i = 0; i < {tile_size}; i++) {{
                 #pragma unroll
-                for (uint j = 0; j < {TILE_SIZE}; j++) {{
+                for (uint j = 0; j < {tile_size}; j++) {{
                     if ((x_offset + i) < dims.x && (z_offset + j) < dims.z) {{
                         ulong offset = tile_offset + (i * dims.z) + j;
                         output[offset] = tile[i][j];
@@ -131,3 +556,160 @@ pub fn matmul(c_type: &'static str) -> Result<Program, Error> {
 
     build(&src)
 }
+
+#[memoize]
+pub fn solve_tridiagonal(c_type: &'static str) -> Result<Program, Error> {
+    let src = format!(
+        r#"
+        // parallel cyclic reduction, with one work group per system and one work item per row
+        __kernel void solve_tridiagonal_pcr(
+                const ulong dim,
+                __global const {c_type}* restrict diagonals,
+                __global const {c_type}* restrict rhs,
+                __global {c_type}* restrict output,
+                __local {c_type}* a,
+                __local {c_type}* b,
+                __local {c_type}* c,
+                __local {c_type}* d)
+        {{
+            const ulong system = get_group_id(0);
+            const uint i = get_local_id(0);
+            const ulong start = system * 3 * dim;
+
+            if (i < dim) {{
+                a[i] = i == 0 ? 0 : diagonals[start + i];
+                b[i] = diagonals[start + dim + i];
+                c[i] = i == dim - 1 ? 0 : diagonals[start + (2 * dim) + i];
+                d[i] = rhs[(system * dim) + i];
+            }}
+
+            // each step eliminates the rows at distance stride, doubling the stride
+            for (ulong stride = 1; stride < dim; stride <<= 1) {{
+                barrier(CLK_LOCAL_MEM_FENCE);
+
+                {c_type} a_i = 0;
+                {c_type} b_i = 1;
+                {c_type} c_i = 0;
+                {c_type} d_i = 0;
+
+                if (i < dim) {{
+                    b_i = b[i];
+                    d_i = d[i];
+
+                    if (i >= stride) {{
+                        const ulong j = i - stride;
+                        const {c_type} k = a[i] / b[j];
+                        a_i = -a[j] * k;
+                        b_i -= c[j] * k;
+                        d_i -= d[j] * k;
+                    }}
+
+                    if (i + stride < dim) {{
+                        const ulong j = i + stride;
+                        const {c_type} k = c[i] / b[j];
+                        c_i = -c[j] * k;
+                        b_i -= a[j] * k;
+                        d_i -= d[j] * k;
+                    }}
+                }}
+
+                barrier(CLK_LOCAL_MEM_FENCE);
+
+                if (i < dim) {{
+                    a[i] = a_i;
+                    b[i] = b_i;
+                    c[i] = c_i;
+                    d[i] = d_i;
+                }}
+            }}
+
+            if (i < dim) {{
+                output[(system * dim) + i] = d[i] / b[i];
+            }}
+        }}
+
+        // the Thomas algorithm, with one work item per system
+        __kernel void solve_tridiagonal_thomas(
+                const ulong dim,
+                __global const {c_type}* restrict diagonals,
+                __global const {c_type}* restrict rhs,
+                __global {c_type}* restrict modified,
+                __global {c_type}* restrict output)
+        {{
+            const ulong system = get_global_id(0);
+            const ulong offset = system * dim;
+
+            __global const {c_type}* sub = diagonals + (system * 3 * dim);
+            __global const {c_type}* diag = sub + dim;
+            __global const {c_type}* sup = diag + dim;
+
+            for (ulong i = 0; i < dim; i++) {{
+                {c_type} pivot = diag[i];
+                {c_type} prev = 0;
+
+                if (i > 0) {{
+                    pivot -= sub[i] * modified[offset + i - 1];
+                    prev = sub[i] * output[offset + i - 1];
+                }}
+
+                modified[offset + i] = sup[i] / pivot;
+                output[offset + i] = (rhs[offset + i] - prev) / pivot;
+            }}
+
+            for (ulong i = dim - 1; i > 0; i--) {{
+                output[offset + i - 1] -= modified[offset + i - 1] * output[offset + i];
+            }}
+        }}
+        "#,
+    );
+
+    build(&src)
+}
+
+#[memoize]
+pub fn csr_matmul(c_type: &'static str) -> Result<Program, Error> {
+    let src = format!(
+        r#"
+        // one work group per row of the sparse matrix and column of the dense matrix,
+        // so that the work items of each group share the non-zero elements of one row
+        __kernel void csr_matmul(
+                const ulong n,
+                __global const ulong* restrict offsets,
+                __global const ulong* restrict indices,
+                __global const {c_type}* restrict values,
+                __global const {c_type}* restrict dense,
+                __global {c_type}* restrict output,
+                __local {c_type}* partials)
+        {{
+            const ulong offset = get_group_id(0);
+            const ulong row = offset / n;
+            const ulong j = offset % n;
+
+            const uint i = get_local_id(0);
+            const uint group_size = get_local_size(0);
+
+            {c_type} sum = 0;
+            for (ulong k = offsets[row] + i; k < offsets[row + 1]; k += group_size) {{
+                sum += values[k] * dense[(indices[k] * n) + j];
+            }}
+
+            partials[i] = sum;
+            barrier(CLK_LOCAL_MEM_FENCE);
+
+            for (uint stride = group_size / 2; stride > 0; stride >>= 1) {{
+                if (i < stride) {{
+                    partials[i] += partials[i + stride];
+                }}
+
+                barrier(CLK_LOCAL_MEM_FENCE);
+            }}
+
+            if (i == 0) {{
+                output[offset] = partials[0];
+            }}
+        }}
+        "#,
+    );
+
+    build(&src)
+}
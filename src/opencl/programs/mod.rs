@@ -1,17 +1,34 @@
 use std::fmt;
 
-use crate::Error;
+use crate::{config, Error};
 
-use super::{OpenCL, TILE_SIZE, WG_SIZE};
+use super::OpenCL;
 
+mod cache;
 pub mod constructors;
+pub mod conv;
 pub mod elementwise;
 pub mod gather;
 pub mod linalg;
 pub mod reduce;
 pub mod slice;
+pub mod sort;
 pub mod view;
 
+/// The vector widths for which vectorized elementwise kernels are compiled
+pub const VEC_WIDTHS: [usize; 4] = [2, 4, 8, 16];
+
+/// Return the default number of elements of type `c_type` processed per work item
+/// by a vectorized kernel.
+pub fn vec_width(c_type: &str) -> usize {
+    match c_type {
+        "long" | "ulong" | "double" => 4,
+        _ => 8,
+    }
+}
+
+const FP64_PRAGMA: &str = "#pragma OPENCL EXTENSION cl_khr_fp64 : enable\n";
+
 struct ArrayFormat<'a, T> {
     arr: &'a [T],
 }
@@ -35,8 +52,27 @@ impl<'a, T: fmt::Display> fmt::Display for ArrayFormat<'a, T> {
 }
 
 #[inline]
-fn build(src: &str) -> Result<ocl::Program, Error> {
-    ocl::Program::builder()
+pub(super) fn build(src: &str) -> Result<ocl::Program, Error> {
+    let mut builder = ocl::Program::builder();
+
+    if src.contains("double") {
+        if OpenCL::supports::<f64>() {
+            if let Some(dir) = config::kernel_cache() {
+                let src = format!("{FP64_PRAGMA}{src}");
+                return cache::build(&dir, &src);
+            }
+
+            builder.source(FP64_PRAGMA);
+        } else {
+            return Err(Error::Unsupported(
+                "this OpenCL platform does not support 64-bit floating point numbers".to_string(),
+            ));
+        }
+    } else if let Some(dir) = config::kernel_cache() {
+        return cache::build(&dir, src);
+    }
+
+    builder
         .source(src)
         .build(OpenCL::context())
         .map_err(Error::from)
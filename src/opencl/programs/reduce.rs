@@ -5,9 +5,14 @@ use crate::Error;
 
 use super::build;
 
-#[memoize]
-pub fn fold_axis(c_type: &'static str, reduce: &'static str) -> Result<Program, Error> {
-    let src = format!(
+/// The in-place reduce functions which each reduce kernel may call by name.
+///
+/// `maximum`, `minimum`, `add`, and `mul` propagate NaN,
+/// and each `*_ignore_nan` variant skips NaN instead (NaN is the only value not equal to itself).
+/// A lane of only NaN reduces to NaN with `maximum_ignore_nan` or `minimum_ignore_nan`,
+/// since their identity is NaN.
+fn reducers(c_type: &'static str) -> String {
+    format!(
         r#"
         inline void add({c_type}* left, const {c_type} right) {{
             *left += right;
@@ -33,6 +38,69 @@ pub fn fold_axis(c_type: &'static str, reduce: &'static str) -> Result<Program,
             }}
         }}
 
+        inline void maximum({c_type}* left, const {c_type} right) {{
+            if (*left != *left) {{
+                // NaN propagates
+            }} else if (right != right || right > *left) {{
+                *left = right;
+            }}
+        }}
+
+        inline void minimum({c_type}* left, const {c_type} right) {{
+            if (*left != *left) {{
+                // NaN propagates
+            }} else if (right != right || right < *left) {{
+                *left = right;
+            }}
+        }}
+
+        inline void add_ignore_nan({c_type}* left, const {c_type} right) {{
+            if (right != right) {{
+                // skip NaN
+            }} else if (*left != *left) {{
+                *left = right;
+            }} else {{
+                *left += right;
+            }}
+        }}
+
+        inline void mul_ignore_nan({c_type}* left, const {c_type} right) {{
+            if (right != right) {{
+                // skip NaN
+            }} else if (*left != *left) {{
+                *left = right;
+            }} else {{
+                *left *= right;
+            }}
+        }}
+
+        inline void maximum_ignore_nan({c_type}* left, const {c_type} right) {{
+            if (right != right) {{
+                // skip NaN
+            }} else if (*left != *left || right > *left) {{
+                *left = right;
+            }}
+        }}
+
+        inline void minimum_ignore_nan({c_type}* left, const {c_type} right) {{
+            if (right != right) {{
+                // skip NaN
+            }} else if (*left != *left || right < *left) {{
+                *left = right;
+            }}
+        }}
+        "#
+    )
+}
+
+#[memoize]
+pub fn fold_axis(c_type: &'static str, reduce: &'static str) -> Result<Program, Error> {
+    let reducers = reducers(c_type);
+
+    let src = format!(
+        r#"
+        {reducers}
+
         __kernel void fold_axis(
             const ulong reduce_dim,
             const ulong target_dim,
@@ -67,33 +135,13 @@ pub fn fold_axis(c_type: &'static str, reduce: &'static str) -> Result<Program,
 }
 
 pub fn reduce_axis(c_type: &'static str, reduce: &'static str) -> Result<Program, Error> {
+    let reducers = reducers(c_type);
+
     let src = format!(
         r#"
-        inline void add({c_type}* left, const {c_type} right) {{
-            *left += right;
-        }}
-
-        inline void and({c_type}* left, const {c_type} right) {{
-            if (left == 0) {{
-                // no-op
-            }} else if (right == 0) {{
-                *left = 0;
-            }}
-        }}
-
-        inline void mul({c_type}* left, const {c_type} right) {{
-            *left *= right;
-        }}
-
-        inline void or({c_type}* left, const {c_type} right) {{
-            if (left != 0) {{
-                // no-op
-            }} else if (right != 0) {{
-                *left = right;
-            }}
-        }}
+        {reducers}
 
-        __kernel void reduce(
+        __kernel void reduce_axis(
                 {c_type} init,
                 __global const {c_type}* input,
                 __global {c_type}* output,
@@ -130,31 +178,11 @@ pub fn reduce_axis(c_type: &'static str, reduce: &'static str) -> Result<Program
 
 #[memoize]
 pub fn reduce(c_type: &'static str, reduce: &'static str) -> Result<Program, Error> {
+    let reducers = reducers(c_type);
+
     let src = format!(
         r#"
-        inline void add({c_type}* left, const {c_type} right) {{
-            *left += right;
-        }}
-
-        inline void and({c_type}* left, const {c_type} right) {{
-            if (left == 0) {{
-                // no-op
-            }} else if (right == 0) {{
-                *left = 0;
-            }}
-        }}
-
-        inline void mul({c_type}* left, const {c_type} right) {{
-            *left *= right;
-        }}
-
-        inline void or({c_type}* left, const {c_type} right) {{
-            if (left != 0) {{
-                // no-op
-            }} else if (right != 0) {{
-                *left = right;
-            }}
-        }}
+        {reducers}
 
         __kernel void reduce(
                 const ulong size,
@@ -168,17 +196,16 @@ pub fn reduce(c_type: &'static str, reduce: &'static str) -> Result<Program, Err
             const uint b = offset % group_size;
 
             // copy from global to local memory
-            partials[b] = input[offset];
+            if (offset < size) {{
+                partials[b] = input[offset];
+            }}
 
-            // reduce over local memory in parallel
+            // reduce over local memory in parallel, in a fixed order which the host can reproduce
             for (uint stride = group_size >> 1; stride > 0; stride = stride >> 1) {{
                 barrier(CLK_LOCAL_MEM_FENCE);
 
-                if (offset + stride < size) {{
-                    uint next = b + stride;
-                    if (next < group_size) {{
-                        {reduce}(&partials[b], partials[b + stride]);
-                    }}
+                if (b < stride && offset + stride < size) {{
+                    {reduce}(&partials[b], partials[b + stride]);
                 }}
             }}
 
@@ -191,3 +218,341 @@ pub fn reduce(c_type: &'static str, reduce: &'static str) -> Result<Program, Err
 
     build(&src)
 }
+
+#[memoize]
+pub fn cdf(c_type: &'static str, f_type: &'static str) -> Result<Program, Error> {
+    let src = format!(
+        r#"
+        __kernel void cdf(
+                const ulong dim,
+                const ulong inner,
+                __global const {c_type}* input,
+                __global {f_type}* output)
+        {{
+            const ulong offset = get_global_id(0);
+            const {c_type} n = input[offset];
+
+            // the offset of the first element of this element's sequence
+            const ulong start = offset - (offset % (dim * inner)) + (offset % inner);
+
+            ulong count = 0;
+
+            for (ulong d = 0; d < dim; d++) {{
+                if (input[start + (d * inner)] <= n) {{
+                    count++;
+                }}
+            }}
+
+            output[offset] = ({f_type}) count / ({f_type}) dim;
+        }}
+        "#,
+    );
+
+    build(&src)
+}
+
+#[memoize]
+pub fn scan(
+    c_type: &'static str,
+    f_type: &'static str,
+    scan: &'static str,
+) -> Result<Program, Error> {
+    let src = format!(
+        r#"
+        inline {c_type} _add(const {c_type} left, const {c_type} right) {{
+            return left + right;
+        }}
+
+        inline {c_type} _mul(const {c_type} left, const {c_type} right) {{
+            return left * right;
+        }}
+
+        inline {c_type} _logaddexp(const {f_type} left, const {f_type} right) {{
+            if (isnan(left) || isnan(right)) {{
+                return left + right;
+            }}
+
+            const {f_type} max = fmax(left, right);
+
+            if (isinf(max)) {{
+                return max;
+            }} else {{
+                return max + log1p(exp(-fabs(left - right)));
+            }}
+        }}
+
+        __kernel void scan(
+                const ulong dim,
+                const ulong inner,
+                __global const {c_type}* input,
+                __global {c_type}* output)
+        {{
+            const ulong offset = get_global_id(0);
+
+            // the offset of the first element of this sequence
+            ulong i = ((offset / inner) * dim * inner) + (offset % inner);
+
+            {c_type} acc = input[i];
+            output[i] = acc;
+
+            for (ulong d = 1; d < dim; d++) {{
+                i += inner;
+                acc = {scan}(acc, input[i]);
+                output[i] = acc;
+            }}
+        }}
+
+        __kernel void scan_wg(
+                const ulong dim,
+                const ulong inner,
+                __global const {c_type}* input,
+                __global {c_type}* output,
+                __local {c_type}* partials)
+        {{
+            const ulong sequence = get_group_id(0);
+            const uint id = get_local_id(0);
+            const uint wg_size = get_local_size(0);
+
+            // the offset of the first element of this sequence
+            const ulong start = ((sequence / inner) * dim * inner) + (sequence % inner);
+
+            // the range of this sequence to scan in this work item
+            const ulong chunk = (dim + wg_size - 1) / wg_size;
+            const ulong lo = min(id * chunk, dim);
+            const ulong hi = min(lo + chunk, dim);
+
+            // scan this work item's chunk
+            {c_type} acc = input[start];
+
+            if (lo < hi) {{
+                acc = input[start + (lo * inner)];
+                output[start + (lo * inner)] = acc;
+
+                for (ulong d = lo + 1; d < hi; d++) {{
+                    acc = {scan}(acc, input[start + (d * inner)]);
+                    output[start + (d * inner)] = acc;
+                }}
+            }}
+
+            partials[id] = acc;
+
+            // scan the last element of each chunk over local memory in parallel
+            for (uint stride = 1; stride < wg_size; stride = stride << 1) {{
+                barrier(CLK_LOCAL_MEM_FENCE);
+                const {c_type} prev = id >= stride ? partials[id - stride] : acc;
+                barrier(CLK_LOCAL_MEM_FENCE);
+
+                if (id >= stride) {{
+                    partials[id] = {scan}(prev, partials[id]);
+                }}
+            }}
+
+            barrier(CLK_LOCAL_MEM_FENCE);
+
+            // carry the scan of every preceding chunk into this one
+            if (id > 0 && lo < hi) {{
+                const {c_type} carry = partials[id - 1];
+
+                for (ulong d = lo; d < hi; d++) {{
+                    output[start + (d * inner)] = {scan}(carry, output[start + (d * inner)]);
+                }}
+            }}
+        }}
+        "#,
+    );
+
+    build(&src)
+}
+
+#[memoize]
+pub fn reduce_with_index(c_type: &'static str, cmp: &'static str) -> Result<Program, Error> {
+    let src = format!(
+        r#"
+        __kernel void reduce_with_index(
+                const ulong stride,
+                __global const {c_type}* input,
+                __global {c_type}* values,
+                __global ulong* indices)
+        {{
+            const ulong offset = get_global_id(0);
+            const ulong start = offset * stride;
+
+            {c_type} best = input[start];
+            ulong index = 0;
+
+            for (ulong i = 1; i < stride; i++) {{
+                const {c_type} n = input[start + i];
+
+                // the first NaN is the extreme value, like a max or min which propagates NaN
+                if (best == best && (n != n || n {cmp} best)) {{
+                    best = n;
+                    index = i;
+                }}
+            }}
+
+            values[offset] = best;
+            indices[offset] = index;
+        }}
+        "#,
+    );
+
+    build(&src)
+}
+
+#[memoize]
+pub fn reduce_arg(c_type: &'static str, cmp: &'static str) -> Result<Program, Error> {
+    let src = format!(
+        r#"
+        __kernel void reduce_arg(
+                const ulong stride,
+                __global const {c_type}* input,
+                __global ulong* indices)
+        {{
+            const ulong offset = get_global_id(0);
+            const ulong start = offset * stride;
+
+            {c_type} best = input[start];
+            ulong index = 0;
+
+            for (ulong i = 1; i < stride; i++) {{
+                const {c_type} n = input[start + i];
+
+                // the first NaN is the extreme value, like a max or min which propagates NaN
+                if (best == best && (n != n || n {cmp} best)) {{
+                    best = n;
+                    index = i;
+                }}
+            }}
+
+            indices[offset] = index;
+        }}
+        "#,
+    );
+
+    build(&src)
+}
+
+#[memoize]
+pub fn moment(c_type: &'static str, f_type: &'static str) -> Result<Program, Error> {
+    let src = format!(
+        r#"
+        inline {f_type} row_mean(const ulong stride, __global const {c_type}* restrict row) {{
+            {f_type} sum = 0;
+            for (ulong i = 0; i < stride; i++) {{
+                sum += ({f_type}) row[i];
+            }}
+
+            return sum / stride;
+        }}
+
+        inline {f_type} row_var(const ulong stride, __global const {c_type}* restrict row) {{
+            const {f_type} mean = row_mean(stride, row);
+
+            {f_type} var = 0;
+            for (ulong i = 0; i < stride; i++) {{
+                const {f_type} delta = (({f_type}) row[i]) - mean;
+                var += delta * delta;
+            }}
+
+            return var / stride;
+        }}
+
+        __kernel void reduce_mean(
+                const ulong stride,
+                __global const {c_type}* restrict input,
+                __global {f_type}* restrict output)
+        {{
+            const ulong offset = get_global_id(0);
+            output[offset] = row_mean(stride, input + (offset * stride));
+        }}
+
+        __kernel void reduce_var(
+                const ulong stride,
+                __global const {c_type}* restrict input,
+                __global {f_type}* restrict output)
+        {{
+            const ulong offset = get_global_id(0);
+            output[offset] = row_var(stride, input + (offset * stride));
+        }}
+
+        __kernel void reduce_std(
+                const ulong stride,
+                __global const {c_type}* restrict input,
+                __global {f_type}* restrict output)
+        {{
+            const ulong offset = get_global_id(0);
+            output[offset] = sqrt(row_var(stride, input + (offset * stride)));
+        }}
+        "#,
+    );
+
+    build(&src)
+}
+
+#[memoize]
+pub fn layer_norm(c_type: &'static str) -> Result<Program, Error> {
+    let src = format!(
+        r#"
+        __kernel void layer_norm(
+                const ulong stride,
+                const {c_type} eps,
+                __global const {c_type}* restrict input,
+                __global const {c_type}* restrict scale,
+                __global const {c_type}* restrict bias,
+                __global {c_type}* restrict output)
+        {{
+            // the offset of the first element in this row
+            const ulong start = get_global_id(0) * stride;
+
+            {c_type} mean = 0;
+            for (ulong i = 0; i < stride; i++) {{
+                mean += input[start + i];
+            }}
+            mean /= stride;
+
+            {c_type} var = 0;
+            for (ulong i = 0; i < stride; i++) {{
+                const {c_type} delta = input[start + i] - mean;
+                var += delta * delta;
+            }}
+            var /= stride;
+
+            const {c_type} std = sqrt(var + eps);
+
+            for (ulong i = 0; i < stride; i++) {{
+                const {c_type} norm = (input[start + i] - mean) / std;
+                output[start + i] = (norm * scale[i]) + bias[i];
+            }}
+        }}
+        "#,
+    );
+
+    build(&src)
+}
+
+#[memoize]
+pub fn reduce_segments(c_type: &'static str, reduce: &'static str) -> Result<Program, Error> {
+    let src = format!(
+        r#"
+        __kernel void reduce_segments(
+            {c_type} init,
+            __global const ulong* restrict offsets,
+            __global const {c_type}* restrict values,
+            __global {c_type}* restrict output)
+        {{
+            const ulong offset = get_global_id(0);
+
+            {c_type} reduced = init;
+
+            for (ulong i = offsets[offset]; i < offsets[offset + 1]; i++) {{
+                const {c_type} n = values[i];
+                reduced = {reduce};
+            }}
+
+            output[offset] = reduced;
+        }}
+        "#,
+    );
+
+    build(&src)
+}
@@ -21,6 +21,7 @@ impl<'a> RangeFormat<'a> {
                 AxisRange::At(_) => 1,
                 AxisRange::In(_, _, _) => 0,
                 AxisRange::Of(indices) => indices.len(),
+                bound => unreachable!("unresolved axis range {bound:?}"),
             })
             .fold(1, Ord::max)
     }
@@ -53,6 +54,7 @@ impl<'a> fmt::Display for RangeFormat<'a> {
 
                     f.write_str("} }}")?;
                 }
+                bound => unreachable!("unresolved axis range {bound:?}"),
             }
 
             f.write_str(" }, ")?;
@@ -0,0 +1,126 @@
+use memoize::memoize;
+use ocl::Program;
+
+use crate::Error;
+
+use super::build;
+
+#[memoize]
+pub fn sort(c_type: &'static str) -> Result<Program, Error> {
+    let src = format!(
+        r#"
+        // order two (value, index) pairs, placing padding after every element
+        // and NaN after (or, if descending, before) every other value
+        inline bool before(
+            const {c_type} l,
+            const ulong l_index,
+            const {c_type} r,
+            const ulong r_index,
+            const ulong dim,
+            const uchar descending)
+        {{
+            if (l_index >= dim || r_index >= dim) {{
+                return l_index < r_index;
+            }}
+
+            const bool l_nan = l != l;
+            const bool r_nan = r != r;
+
+            if (l_nan && r_nan) {{
+                return l_index < r_index;
+            }} else if (l_nan || r_nan) {{
+                return descending ? l_nan : r_nan;
+            }} else if (l == r) {{
+                // break ties by index so that the sort is stable
+                return l_index < r_index;
+            }} else {{
+                return descending ? l > r : l < r;
+            }}
+        }}
+
+        __kernel void sort_init(
+                const ulong dim,
+                const ulong inner,
+                const ulong padded,
+                __global const {c_type}* restrict input,
+                __global {c_type}* restrict keys,
+                __global ulong* restrict indices)
+        {{
+            const ulong offset = get_global_id(0);
+            const ulong lane = offset / padded;
+            const ulong i = offset % padded;
+
+            indices[offset] = i;
+
+            if (i < dim) {{
+                keys[offset] = input[((lane / inner) * dim * inner) + (lane % inner) + (i * inner)];
+            }} else {{
+                keys[offset] = 0;
+            }}
+        }}
+
+        __kernel void bitonic_step(
+                const ulong dim,
+                const ulong padded,
+                const ulong k,
+                const ulong j,
+                const uchar descending,
+                __global {c_type}* restrict keys,
+                __global ulong* restrict indices)
+        {{
+            const ulong offset = get_global_id(0);
+            const ulong i = offset % padded;
+            const ulong partner = i ^ j;
+
+            if (partner <= i) {{
+                return;
+            }}
+
+            const ulong a = offset;
+            const ulong b = offset - i + partner;
+
+            const {c_type} key_a = keys[a];
+            const {c_type} key_b = keys[b];
+            const ulong index_a = indices[a];
+            const ulong index_b = indices[b];
+
+            const bool swap = (i & k) == 0
+                ? before(key_b, index_b, key_a, index_a, dim, descending)
+                : before(key_a, index_a, key_b, index_b, dim, descending);
+
+            if (swap) {{
+                keys[a] = key_b;
+                keys[b] = key_a;
+                indices[a] = index_b;
+                indices[b] = index_a;
+            }}
+        }}
+
+        __kernel void sort_write(
+                const ulong dim,
+                const ulong inner,
+                const ulong padded,
+                __global const {c_type}* restrict keys,
+                __global {c_type}* restrict output)
+        {{
+            const ulong offset = get_global_id(0);
+            const ulong lane = ((offset / (dim * inner)) * inner) + (offset % inner);
+            output[offset] = keys[(lane * padded) + ((offset / inner) % dim)];
+        }}
+
+        __kernel void argsort_write(
+                const ulong dim,
+                const ulong inner,
+                const ulong padded,
+                __global const ulong* restrict indices,
+                __global ulong* restrict output)
+        {{
+            const ulong offset = get_global_id(0);
+            const ulong lane = ((offset / (dim * inner)) * inner) + (offset % inner);
+            output[offset] = indices[(lane * padded) + ((offset / inner) % dim)];
+        }}
+        "#,
+    );
+
+    build(&src)
+}
@@ -16,6 +16,24 @@ pub fn view(c_type: &'static str, spec: ViewSpec) -> Result<Program, Error> {
     let strides_out = ArrayFormat::from(spec.strides.as_slice());
     let dims_out = ArrayFormat::from(spec.shape.as_slice());
 
+    let reversed = (0..ndim_in)
+        .map(|x| spec.reversed.contains(&x) as u8)
+        .collect::<Vec<_>>();
+
+    let reversed = ArrayFormat::from(reversed.as_slice());
+
+    // a rolled axis reads each coordinate from a shifted source coordinate
+    let (shift, roll) = if spec.shift.is_empty() {
+        (String::new(), "")
+    } else {
+        let shift = ArrayFormat::from(spec.shift.as_slice());
+
+        (
+            format!("const ulong shift[{ndim_in}] = {shift};"),
+            "i = (i + shift[x_in]) % dims[x_out];",
+        )
+    };
+
     let src = format!(
         r#"
         const uint ndim_in = {ndim_in};
@@ -24,13 +42,15 @@ pub fn view(c_type: &'static str, spec: ViewSpec) -> Result<Program, Error> {
         const ulong strides_in[{ndim_in}] = {strides_in};
         const ulong strides_out[{ndim_out}] = {strides_out};
         const ulong dims[{ndim_out}] = {dims_out};
+        const uchar reversed[{ndim_in}] = {reversed};
+        {shift}
 
         __kernel void view(
                 __global const {c_type}* restrict input,
                 __global {c_type}* restrict output)
         {{
             ulong offset_out = get_global_id(0);
-            ulong offset_in = 0;
+            ulong offset_in = {offset};
 
             #pragma unroll
             for (uint x_in = 0; x_in < {ndim_in}; x_in++) {{
@@ -44,13 +64,20 @@ pub fn view(c_type: &'static str, spec: ViewSpec) -> Result<Program, Error> {
                     i = (offset_out / stride_out) % dims[x_out];
                 }}
 
-                offset_in += i * strides_in[x_in];
+                {roll}
+
+                if (reversed[x_in]) {{
+                    offset_in -= i * strides_in[x_in];
+                }} else {{
+                    offset_in += i * strides_in[x_in];
+                }}
             }}
 
             output[offset_out] = input[offset_in];
         }}
         "#,
-        ndim_offset = (ndim_out - ndim_in)
+        ndim_offset = (ndim_out - ndim_in),
+        offset = spec.offset,
     );
 
     build(&src)
@@ -0,0 +1,219 @@
+//! A small templating layer to assemble OpenCL kernel source code.
+//!
+//! A [`Template`] takes care of the scaffolding which every kernel needs (extension pragmas,
+//! data type aliases, constant arrays, and indexing helpers) so that a new kernel only needs
+//! to specify its own logic:
+//!
+//! ```ignore
+//! let program = Template::new()
+//!     .dtype::<f32>("T")
+//!     .elementwise("axpy", &[("y", "T"), ("x", "T")], "T", "alpha * x + y")
+//!     .define("alpha", "2.0f")
+//!     .build()?;
+//! ```
+
+use std::fmt::{self, Write};
+
+use ocl::Program;
+
+use crate::{CType, Error};
+
+use super::programs;
+
+const FP16_PRAGMA: &str = "#pragma OPENCL EXTENSION cl_khr_fp16 : enable\n";
+
+const INDEXING: &str = r#"
+inline ulong coord_of(const ulong offset, const ulong stride, const ulong dim) {
+    if (stride == 0) {
+        return 0;
+    } else {
+        return (offset / stride) % dim;
+    }
+}
+
+inline ulong strided_offset(
+    const ulong offset,
+    const uint ndim,
+    __constant const ulong* strides,
+    __constant const ulong* dims,
+    __constant const ulong* source_strides)
+{
+    ulong source_offset = 0;
+
+    for (uint x = 0; x < ndim; x++) {
+        source_offset += coord_of(offset, strides[x], dims[x]) * source_strides[x];
+    }
+
+    return source_offset;
+}
+"#;
+
+/// A builder for the source code of an OpenCL program
+#[derive(Clone, Default, Debug)]
+pub struct Template {
+    defines: Vec<(String, String)>,
+    dtypes: Vec<(String, &'static str)>,
+    arrays: Vec<String>,
+    indexing: bool,
+    helpers: Vec<String>,
+    kernels: Vec<String>,
+}
+
+impl Template {
+    /// Construct a new, empty [`Template`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declare `alias` as the OpenCL type of `T`, together with the macros
+    /// `{alias}_ZERO`, `{alias}_ONE`, `{alias}_MIN`, and `{alias}_MAX`.
+    pub fn dtype<T: CType>(mut self, alias: &str) -> Self {
+        self.dtypes.push((alias.to_string(), T::TYPE));
+        self
+    }
+
+    /// Define a preprocessor macro `name` with the given `value`.
+    pub fn define<V: fmt::Display>(mut self, name: &str, value: V) -> Self {
+        self.defines.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Declare a constant array `name` of type `c_type` with the given `values`.
+    pub fn array<V: fmt::Display>(mut self, c_type: &str, name: &str, values: &[V]) -> Self {
+        let mut decl = format!("__constant {c_type} {name}[{}] = {{ ", values.len().max(1));
+
+        if values.is_empty() {
+            decl.push('0');
+        }
+
+        for value in values {
+            write!(decl, "{value}, ").expect("array");
+        }
+
+        decl.push_str("};");
+
+        self.arrays.push(decl);
+        self
+    }
+
+    /// Include the helper functions `coord_of(offset, stride, dim)` and
+    /// `strided_offset(offset, ndim, strides, dims, source_strides)`,
+    /// which compute the coordinate and source offset of an element of a strided view.
+    pub fn indexing(mut self) -> Self {
+        self.indexing = true;
+        self
+    }
+
+    /// Include the given helper source code, e.g. an `inline` function.
+    pub fn helper<S: Into<String>>(mut self, src: S) -> Self {
+        self.helpers.push(src.into());
+        self
+    }
+
+    /// Add a kernel `name` with the given `params` and `body`.
+    pub fn kernel(mut self, name: &str, params: &[&str], body: &str) -> Self {
+        let params = params.join(",\n    ");
+        let kernel = format!("__kernel void {name}(\n    {params})\n{{\n{body}\n}}");
+        self.kernels.push(kernel);
+        self
+    }
+
+    /// Add a kernel `name` which computes `output[offset] = expr` for every offset,
+    /// where each of the named `inputs` is bound to its element at `offset` in `expr`.
+    pub fn elementwise(
+        self,
+        name: &str,
+        inputs: &[(&str, &str)],
+        o_type: &str,
+        expr: &str,
+    ) -> Self {
+        let mut params = inputs
+            .iter()
+            .map(|(input, c_type)| format!("__global const {c_type}* restrict _{input}"))
+            .collect::<Vec<_>>();
+
+        params.push(format!("__global {o_type}* restrict output"));
+
+        let mut body = "    const ulong offset = get_global_id(0);\n".to_string();
+
+        for (input, c_type) in inputs {
+            writeln!(body, "    const {c_type} {input} = _{input}[offset];").expect("input");
+        }
+
+        write!(body, "    output[offset] = {expr};").expect("output");
+
+        let params = params.iter().map(String::as_str).collect::<Vec<_>>();
+        self.kernel(name, &params, &body)
+    }
+
+    /// Render the source code of this template.
+    pub fn source(&self) -> String {
+        let mut src = String::new();
+
+        let uses_half = self.dtypes.iter().any(|(_, c_type)| *c_type == "half")
+            || self.kernels.iter().any(|kernel| kernel.contains("half"));
+
+        if uses_half {
+            src.push_str(FP16_PRAGMA);
+        }
+
+        for (name, value) in &self.defines {
+            writeln!(src, "#define {name} {value}").expect("define");
+        }
+
+        for (alias, c_type) in &self.dtypes {
+            let (min, max) = limits(c_type);
+
+            writeln!(src, "typedef {c_type} {alias};").expect("typedef");
+            writeln!(src, "#define {alias}_ZERO (({alias}) 0)").expect("zero");
+            writeln!(src, "#define {alias}_ONE (({alias}) 1)").expect("one");
+            writeln!(src, "#define {alias}_MIN (({alias}) {min})").expect("min");
+            writeln!(src, "#define {alias}_MAX (({alias}) {max})").expect("max");
+        }
+
+        for array in &self.arrays {
+            src.push_str(array);
+            src.push('\n');
+        }
+
+        if self.indexing {
+            src.push_str(INDEXING);
+        }
+
+        for helper in &self.helpers {
+            src.push_str(helper);
+            src.push('\n');
+        }
+
+        for kernel in &self.kernels {
+            src.push('\n');
+            src.push_str(kernel);
+            src.push('\n');
+        }
+
+        src
+    }
+
+    /// Compile this template into a [`Program`] on the OpenCL platform.
+    /// This will return an error if it uses `double` and a device does not support it.
+    pub fn build(&self) -> Result<Program, Error> {
+        programs::build(&self.source())
+    }
+}
+
+fn limits(c_type: &str) -> (&'static str, &'static str) {
+    match c_type {
+        "char" => ("CHAR_MIN", "CHAR_MAX"),
+        "uchar" => ("0", "UCHAR_MAX"),
+        "short" => ("SHRT_MIN", "SHRT_MAX"),
+        "ushort" => ("0", "USHRT_MAX"),
+        "int" => ("INT_MIN", "INT_MAX"),
+        "uint" => ("0", "UINT_MAX"),
+        "long" => ("LONG_MIN", "LONG_MAX"),
+        "ulong" => ("0", "ULONG_MAX"),
+        "half" => ("-HALF_MAX", "HALF_MAX"),
+        "float" => ("-FLT_MAX", "FLT_MAX"),
+        "double" => ("-DBL_MAX", "DBL_MAX"),
+        other => unreachable!("OpenCL type {other}"),
+    }
+}
@@ -0,0 +1,355 @@
+//! Tuning of kernel launch parameters per device.
+//!
+//! The first time a family of kernels is launched on a device for a given data type,
+//! a few variants of its launch parameters are benchmarked and the fastest is used from then on.
+//! If a kernel cache directory is configured, tuned parameters are persisted there
+//! so that each device only needs to be tuned once.
+//! Tuning can be disabled with [`crate::config::set_autotune`].
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+use lazy_static::lazy_static;
+use ocl::core::{DeviceInfo, DeviceInfoResult};
+use ocl::{Buffer, Device, Kernel, Queue};
+
+use crate::{config, CType, Error};
+
+use super::{programs, TILE_SIZE};
+
+const FILE_NAME: &str = "launch.tune";
+
+const ELEMENTWISE_SIZE: usize = 1 << 20;
+const MATMUL_DIM: usize = 256;
+const TRIALS: usize = 3;
+
+const WG_SIZES: [usize; 4] = [0, 64, 128, 256];
+const TILE_SIZES: [usize; 3] = [4, 8, 16];
+
+/// A family of kernels which share launch parameters
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum KernelFamily {
+    /// Elementwise unary and dual ops
+    Elementwise,
+    /// Matrix multiplication
+    MatMul,
+}
+
+impl KernelFamily {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Elementwise => "elementwise",
+            Self::MatMul => "matmul",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "elementwise" => Some(Self::Elementwise),
+            "matmul" => Some(Self::MatMul),
+            _ => None,
+        }
+    }
+}
+
+/// The launch parameters of a family of kernels on one device
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct LaunchConfig {
+    /// The local work size, or zero to let the device driver choose
+    pub wg_size: usize,
+    /// The number of elements processed per work item by a vectorized kernel
+    pub vec_width: usize,
+    /// The size of each tile of a tiled kernel
+    pub tile_size: usize,
+}
+
+impl LaunchConfig {
+    /// The default launch parameters for a kernel on data of type `T`.
+    pub fn default_for<T: CType>() -> Self {
+        Self {
+            wg_size: 0,
+            vec_width: programs::vec_width(T::TYPE),
+            tile_size: TILE_SIZE,
+        }
+    }
+
+    /// The global work size needed to launch a kernel with this configuration
+    /// over the given number of `items`, which is a multiple of the local work size.
+    pub fn global_work_size(&self, items: usize) -> usize {
+        if self.wg_size == 0 {
+            items
+        } else {
+            self.wg_size * items.div_ceil(self.wg_size)
+        }
+    }
+
+    /// The global work size needed to launch a vectorized kernel with this configuration
+    /// over the given number of elements.
+    pub fn vec_work_size(&self, size: usize) -> usize {
+        self.global_work_size(size.div_ceil(self.vec_width))
+    }
+}
+
+impl fmt::Display for LaunchConfig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} {}", self.wg_size, self.vec_width, self.tile_size)
+    }
+}
+
+type Key = (String, KernelFamily, &'static str);
+
+lazy_static! {
+    static ref TUNED: RwLock<Option<HashMap<Key, LaunchConfig>>> = RwLock::new(None);
+}
+
+/// Return the launch parameters of the given kernel `family` for data of type `T`
+/// on the device of the given `queue`, tuning them first if needed.
+pub fn launch_config<T: CType>(queue: &Queue, family: KernelFamily) -> Result<LaunchConfig, Error> {
+    let device = queue.device();
+    let key = (device_key(&device)?, family, T::TYPE);
+
+    {
+        let tuned = TUNED.read().expect("tuned launch parameters");
+
+        if let Some(config) = tuned.as_ref().and_then(|tuned| tuned.get(&key)) {
+            return Ok(*config);
+        }
+    }
+
+    let mut tuned = TUNED.write().expect("tuned launch parameters");
+    let tuned = tuned.get_or_insert_with(|| config::kernel_cache().map(load).unwrap_or_default());
+
+    if let Some(config) = tuned.get(&key) {
+        return Ok(*config);
+    }
+
+    if !config::autotune() {
+        return Ok(LaunchConfig::default_for::<T>());
+    }
+
+    let config = match family {
+        KernelFamily::Elementwise => tune_elementwise::<T>(queue, &device)?,
+        KernelFamily::MatMul => tune_matmul::<T>(queue)?,
+    };
+
+    tuned.insert(key, config);
+
+    if let Some(dir) = config::kernel_cache() {
+        // failing to persist the tuned parameters should not prevent using them
+        store(&dir, tuned);
+    }
+
+    Ok(config)
+}
+
+fn tune_elementwise<T: CType>(queue: &Queue, device: &Device) -> Result<LaunchConfig, Error> {
+    let max_wg_size = match device
+        .info(DeviceInfo::MaxWorkGroupSize)
+        .map_err(ocl::Error::from)?
+    {
+        DeviceInfoResult::MaxWorkGroupSize(size) => size,
+        _ => 0,
+    };
+
+    let program = programs::elementwise::dual(T::TYPE, "add", config::fast_math())?;
+    let left = buffer::<T>(queue, ELEMENTWISE_SIZE)?;
+    let right = buffer::<T>(queue, ELEMENTWISE_SIZE)?;
+    let output = buffer::<T>(queue, ELEMENTWISE_SIZE)?;
+
+    let mut best = (Duration::MAX, LaunchConfig::default_for::<T>());
+
+    for vec_width in programs::VEC_WIDTHS {
+        for wg_size in WG_SIZES.into_iter().filter(|size| *size <= max_wg_size) {
+            let config = LaunchConfig {
+                wg_size,
+                vec_width,
+                ..best.1
+            };
+
+            let mut builder = Kernel::builder();
+
+            builder
+                .name(format!("dual_vec{vec_width}"))
+                .program(&program)
+                .queue(queue.clone())
+                .global_work_size(config.vec_work_size(ELEMENTWISE_SIZE))
+                .arg(ELEMENTWISE_SIZE as u64)
+                .arg(&left)
+                .arg(&right)
+                .arg(&output);
+
+            if wg_size > 0 {
+                builder.local_work_size(wg_size);
+            }
+
+            // a variant which the device cannot launch is not a candidate
+            if let Some(elapsed) = builder.build().ok().and_then(|kernel| time(queue, &kernel)) {
+                if elapsed < best.0 {
+                    best = (elapsed, config);
+                }
+            }
+        }
+    }
+
+    Ok(best.1)
+}
+
+fn tune_matmul<T: CType>(queue: &Queue) -> Result<LaunchConfig, Error> {
+    let dim = MATMUL_DIM;
+    let left = buffer::<T>(queue, dim * dim)?;
+    let right = buffer::<T>(queue, dim * dim)?;
+    let output = buffer::<T>(queue, dim * dim)?;
+
+    let dims = [dim as u64, dim as u64, dim as u64, 1];
+    let strides = [(dim * dim) as u64, (dim * dim) as u64];
+
+    let mut best = (Duration::MAX, LaunchConfig::default_for::<T>());
+
+    for tile_size in TILE_SIZES {
+        let program = programs::linalg::matmul(T::TYPE, tile_size)?;
+        let tiles = dim.div_ceil(tile_size);
+
+        let kernel = Kernel::builder()
+            .name("matmul")
+            .program(&program)
+            .queue(queue.clone())
+            .global_work_size((1, tiles, tiles))
+            .arg(ocl::core::Ulong4::from(dims))
+            .arg(tiles as u64)
+            .arg(ocl::core::Ulong2::from(strides))
+            .arg(&left)
+            .arg(&right)
+            .arg(&output)
+            .build();
+
+        if let Some(elapsed) = kernel.ok().and_then(|kernel| time(queue, &kernel)) {
+            if elapsed < best.0 {
+                best = (
+                    elapsed,
+                    LaunchConfig {
+                        tile_size,
+                        ..best.1
+                    },
+                );
+            }
+        }
+    }
+
+    Ok(best.1)
+}
+
+fn buffer<T: CType>(queue: &Queue, size: usize) -> Result<Buffer<T>, Error> {
+    Buffer::builder()
+        .queue(queue.clone())
+        .len(size)
+        .fill_val(T::ONE)
+        .build()
+        .map_err(Error::from)
+}
+
+/// Return the fastest of several runs of the given `kernel`, or `None` if it fails to run.
+fn time(queue: &Queue, kernel: &Kernel) -> Option<Duration> {
+    // the first run may include one-time costs like transferring the inputs to the device
+    unsafe { kernel.enq().ok()? };
+    queue.finish().ok()?;
+
+    (0..TRIALS)
+        .map(|_| {
+            let start = Instant::now();
+            unsafe { kernel.enq().ok()? };
+            queue.finish().ok()?;
+            Some(start.elapsed())
+        })
+        .collect::<Option<Vec<_>>>()?
+        .into_iter()
+        .min()
+}
+
+fn device_key(device: &Device) -> Result<String, Error> {
+    let mut key = Vec::with_capacity(3);
+
+    for info in [
+        DeviceInfo::Name,
+        DeviceInfo::Vendor,
+        DeviceInfo::DriverVersion,
+    ] {
+        let info = device.info(info).map_err(ocl::Error::from)?;
+        key.push(info.to_string().replace(['\t', '\n'], " "));
+    }
+
+    Ok(key.join("/"))
+}
+
+fn load(dir: PathBuf) -> HashMap<Key, LaunchConfig> {
+    let mut tuned = HashMap::new();
+
+    let contents = match fs::read_to_string(dir.join(FILE_NAME)) {
+        Ok(contents) => contents,
+        Err(_) => return tuned,
+    };
+
+    // an unrecognized entry is skipped, e.g. for a data type this build does not support
+    for line in contents.lines() {
+        let mut fields = line.split('\t');
+
+        let (Some(device), Some(family), Some(dtype), Some(config)) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+
+        let Some(family) = KernelFamily::from_name(family) else {
+            continue;
+        };
+
+        let Some(dtype) = dtype_name(dtype) else {
+            continue;
+        };
+
+        let params = config
+            .split(' ')
+            .map(|param| param.parse::<usize>().ok())
+            .collect::<Option<Vec<_>>>();
+
+        if let Some([wg_size, vec_width, tile_size]) = params.as_deref() {
+            let config = LaunchConfig {
+                wg_size: *wg_size,
+                vec_width: *vec_width,
+                tile_size: *tile_size,
+            };
+
+            tuned.insert((device.to_string(), family, dtype), config);
+        }
+    }
+
+    tuned
+}
+
+fn store(dir: &Path, tuned: &HashMap<Key, LaunchConfig>) -> Option<()> {
+    let contents = tuned
+        .iter()
+        .map(|((device, family, dtype), config)| {
+            format!("{device}\t{}\t{dtype}\t{config}\n", family.name())
+        })
+        .collect::<String>();
+
+    fs::create_dir_all(dir).ok()?;
+
+    // write to a temporary file first so that a concurrent process never reads a partial file
+    let path = dir.join(FILE_NAME);
+    let tmp = path.with_extension(format!("tmp{}", std::process::id()));
+    fs::write(&tmp, contents).ok()?;
+    fs::rename(&tmp, path).ok()
+}
+
+fn dtype_name(name: &str) -> Option<&'static str> {
+    [
+        "char", "uchar", "short", "ushort", "int", "uint", "long", "ulong", "float", "double",
+    ]
+    .into_iter()
+    .find(|dtype| *dtype == name)
+}
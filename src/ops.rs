@@ -1,13 +1,16 @@
 //! Array operations
 
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
 use crate::access::*;
 use crate::buffer::Buffer;
+use crate::introspect::OpPlan;
 #[cfg(feature = "opencl")]
 use crate::opencl;
-use crate::platform::{Platform, PlatformInstance};
+use crate::platform::{Convert, Platform, PlatformInstance};
 use crate::{
-    host, range_shape, strides_for, Axes, AxisRange, BufferConverter, CType, Error, Range, Shape,
-    Strides,
+    host, range_shape, resolve_range, strides_for, Axes, AxisRange, BufferConverter, CType,
+    ConvSpec, Error, Float, Interpolation, Range, Shape, Strides,
 };
 
 macro_rules! op_dispatch {
@@ -24,7 +27,7 @@ macro_rules! op_enqueue {
     ($this:expr, $t:ty) => {
         match $this {
             #[cfg(feature = "opencl")]
-            Self::CL(op) => Enqueue::<opencl::OpenCL, $t>::enqueue(op).map(Buffer::CL),
+            Self::CL(op) => opencl::ops::enqueue_with_policy::<_, $t>(op),
             Self::Host(op) => Enqueue::<host::Host, $t>::enqueue(op).map(Buffer::Host),
         }
     };
@@ -32,6 +35,16 @@ macro_rules! op_enqueue {
 
 pub trait Op: Send + Sync {
     fn size(&self) -> usize;
+
+    /// The name of this op, for introspection.
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    /// Describe the inputs of this op, for introspection.
+    fn inputs(&self) -> Vec<OpPlan> {
+        Vec::new()
+    }
 }
 
 pub trait Enqueue<P: PlatformInstance, T: CType>: Op {
@@ -68,11 +81,61 @@ pub trait Write<P: PlatformInstance, T: CType>: Enqueue<P, T> {
 }
 
 pub trait Construct<T: CType>: PlatformInstance {
+    type Eye: Enqueue<Self, T>;
     type Range: Enqueue<Self, T>;
 
+    /// Construct a batch of `batch_size` identity matrices with shape `[dim, dim]`.
+    fn eye(self, batch_size: usize, dim: usize) -> Result<AccessOp<Self::Eye, Self>, Error>;
+
     fn range(self, start: T, stop: T, size: usize) -> Result<AccessOp<Self::Range, Self>, Error>;
 }
 
+pub trait Convolve<I, K, T>: PlatformInstance
+where
+    I: Access<T>,
+    K: Access<T>,
+    T: CType,
+{
+    type Conv: ReadOp<Self, T>;
+    type ConvTranspose: ReadOp<Self, T>;
+    type MatchedFilter: ReadOp<Self, T::Float>;
+
+    /// Construct a 3D convolution of an `input` with shape
+    /// `[batch, in_channels, depth, height, width]` and a `kernel` with shape
+    /// `[out_channels, in_channels / groups, kernel_depth, kernel_height, kernel_width]`.
+    /// A 1D or 2D convolution is a 3D convolution with unit leading spatial axes.
+    fn conv(
+        self,
+        input: I,
+        kernel: K,
+        input_shape: [usize; 5],
+        kernel_shape: [usize; 5],
+        spec: ConvSpec<3>,
+    ) -> Result<AccessOp<Self::Conv, Self>, Error>;
+
+    /// Construct a 2D transposed convolution of an `input` with shape
+    /// `[batch, in_channels, height, width]` and a `kernel` with shape
+    /// `[in_channels, out_channels / groups, kernel_height, kernel_width]`.
+    fn conv_transpose2d(
+        self,
+        input: I,
+        kernel: K,
+        input_shape: [usize; 4],
+        kernel_shape: [usize; 4],
+        spec: ConvSpec<2>,
+    ) -> Result<AccessOp<Self::ConvTranspose, Self>, Error>;
+
+    /// Construct the normalized sliding dot product of each of the `rows` of an `input` with
+    /// shape `[rows, len]` and a `template` with shape `[width]`, with shape
+    /// `[rows, len - width + 1]`.
+    fn matched_filter(
+        self,
+        input: I,
+        template: K,
+        dims: [usize; 3],
+    ) -> Result<AccessOp<Self::MatchedFilter, Self>, Error>;
+}
+
 pub trait ElementwiseBoolean<L, R, T>: PlatformInstance {
     type Op: ReadOp<Self, u8>;
 
@@ -93,6 +156,17 @@ pub trait ElementwiseBooleanScalar<A, T>: PlatformInstance {
     fn xor_scalar(self, left: A, right: T) -> Result<AccessOp<Self::Op, Self>, Error>;
 }
 
+pub trait ElementwiseBitcast<A, IT, OT>: PlatformInstance
+where
+    A: Access<IT>,
+    IT: CType,
+    OT: CType,
+{
+    type Op: ReadOp<Self, OT>;
+
+    fn bitcast(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error>;
+}
+
 pub trait ElementwiseCast<A, IT, OT>: PlatformInstance
 where
     A: Access<IT>,
@@ -136,13 +210,19 @@ pub trait ElementwiseScalarCompare<A, T>: PlatformInstance {
     fn ne_scalar(self, left: A, right: T) -> Result<AccessOp<Self::Op, Self>, Error>;
 }
 
+/// A dual op whose inputs may be broadcast into the shape of its output
+pub trait BroadcastDual: Sized {
+    /// Read the inputs of this op through the given views.
+    fn broadcast(self, broadcast: DualBroadcast) -> Result<Self, Error>;
+}
+
 pub trait ElementwiseDual<L, R, T>: PlatformInstance
 where
     L: Access<T>,
     R: Access<T>,
     T: CType,
 {
-    type Op: ReadOp<Self, T>;
+    type Op: ReadOp<Self, T> + BroadcastDual;
 
     fn add(self, left: L, right: R) -> Result<AccessOp<Self::Op, Self>, Error>;
 
@@ -150,12 +230,16 @@ where
 
     fn log(self, arg: L, base: R) -> Result<AccessOp<Self::Op, Self>, Error>;
 
+    fn logaddexp(self, left: L, right: R) -> Result<AccessOp<Self::Op, Self>, Error>;
+
     fn mul(self, left: L, right: R) -> Result<AccessOp<Self::Op, Self>, Error>;
 
     fn pow(self, arg: L, exp: R) -> Result<AccessOp<Self::Op, Self>, Error>;
 
     fn rem(self, left: L, right: R) -> Result<AccessOp<Self::Op, Self>, Error>;
 
+    fn step(self, arg: L, threshold: R) -> Result<AccessOp<Self::Op, Self>, Error>;
+
     fn sub(self, left: L, right: R) -> Result<AccessOp<Self::Op, Self>, Error>;
 }
 
@@ -193,6 +277,30 @@ where
     fn is_nan(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error>;
 }
 
+pub trait ElementwiseTernary<A, B, C, T>: PlatformInstance
+where
+    A: Access<T>,
+    B: Access<T>,
+    C: Access<T>,
+    T: CType,
+{
+    type Op: ReadOp<Self, T>;
+
+    /// Clamp each element of `access` to the range given by the elements of `min` and `max`.
+    fn clamp(self, access: A, min: B, max: C) -> Result<AccessOp<Self::Op, Self>, Error>;
+
+    /// Interpolate linearly from each element of `access` to the element of `other`
+    /// by the fraction `t`.
+    fn lerp(self, access: A, other: B, t: C) -> Result<AccessOp<Self::Op, Self>, Error>;
+
+    /// Compute `access * mul + add` in a single pass.
+    fn mul_add(self, access: A, mul: B, add: C) -> Result<AccessOp<Self::Op, Self>, Error>;
+
+    /// Interpolate smoothly (with a cubic Hermite polynomial) from zero to one
+    /// as each element of `access` goes from `edge0` to `edge1`.
+    fn smoothstep(self, access: A, edge0: B, edge1: C) -> Result<AccessOp<Self::Op, Self>, Error>;
+}
+
 pub trait ElementwiseTrig<A, T>: PlatformInstance
 where
     A: Access<T>,
@@ -217,6 +325,25 @@ where
     fn atan(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error>;
 
     fn tanh(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error>;
+
+    fn to_degrees(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error>;
+
+    fn to_radians(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error>;
+}
+
+pub trait ElementwiseTrigDual<L, R, T>: PlatformInstance
+where
+    L: Access<T>,
+    R: Access<T>,
+    T: CType,
+{
+    type Op: ReadOp<Self, T::Float>;
+
+    /// Compute the four-quadrant arctangent of `y / x`.
+    fn atan2(self, y: L, x: R) -> Result<AccessOp<Self::Op, Self>, Error>;
+
+    /// Compute `sqrt(left^2 + right^2)` without intermediate overflow.
+    fn hypot(self, left: L, right: R) -> Result<AccessOp<Self::Op, Self>, Error>;
 }
 
 pub trait ElementwiseUnary<A, T>: PlatformInstance
@@ -232,7 +359,12 @@ where
 
     fn ln(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error>;
 
+    /// Compute the approximate reciprocal `1 / n` of each element of `access`.
+    fn reciprocal_approx(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error>;
+
     fn round(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error>;
+
+    fn softplus(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error>;
 }
 
 pub trait ElementwiseUnaryBoolean<A, T>: PlatformInstance
@@ -245,6 +377,42 @@ where
     fn not(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error>;
 }
 
+pub trait GatherAxis<A, I, T>: PlatformInstance
+where
+    A: Access<T>,
+    I: Access<u64>,
+    T: CType,
+{
+    type Op: ReadOp<Self, T>;
+
+    /// Select the elements of `access` at the given `indices` along its middle axis,
+    /// where `dims` is `[outer, axis, inner]`.
+    fn gather(
+        self,
+        access: A,
+        indices: I,
+        dims: [usize; 3],
+    ) -> Result<AccessOp<Self::Op, Self>, Error>;
+}
+
+pub trait GatherMask<A, P, T>: PlatformInstance
+where
+    A: Access<T>,
+    P: Access<u64>,
+    T: CType,
+{
+    type Op: ReadOp<Self, T>;
+
+    /// Select the `size` elements of `access` at which the inclusive prefix sum `positions`
+    /// of a boolean mask increases, in order.
+    fn compact(
+        self,
+        access: A,
+        positions: P,
+        size: usize,
+    ) -> Result<AccessOp<Self::Op, Self>, Error>;
+}
+
 pub trait GatherCond<A, L, R, T>: PlatformInstance
 where
     A: Access<u8>,
@@ -257,6 +425,12 @@ where
     fn cond(self, cond: A, then: L, or_else: R) -> Result<AccessOp<Self::Op, Self>, Error>;
 }
 
+pub trait GatherCondScalar<A: Access<u8>, T: CType>: PlatformInstance {
+    type Op: ReadOp<Self, T>;
+
+    fn cond_scalar(self, cond: A, then: T, or_else: T) -> Result<AccessOp<Self::Op, Self>, Error>;
+}
+
 pub trait LinAlgDual<L, R, T>: PlatformInstance
 where
     L: Access<T>,
@@ -267,6 +441,210 @@ where
 
     fn matmul(self, left: L, right: R, dims: [usize; 4])
         -> Result<AccessOp<Self::Op, Self>, Error>;
+
+    /// Multiply a batch of matrices, where the `i`th matrix of `left` and `right` begins at
+    /// offset `i * strides[0]` and `i * strides[1]`, respectively.
+    fn matmul_strided(
+        self,
+        left: L,
+        right: R,
+        dims: [usize; 4],
+        strides: [usize; 2],
+    ) -> Result<AccessOp<Self::Op, Self>, Error>;
+}
+
+pub trait LinAlgSolve<D, R, T>: PlatformInstance
+where
+    D: Access<T>,
+    R: Access<T>,
+    T: Float,
+{
+    type Op: ReadOp<Self, T>;
+
+    /// Solve a batch of `batch_size` tridiagonal systems of `dim` equations each.
+    /// The `diagonals` of each system are its sub-, main, and super-diagonals in that order,
+    /// each of length `dim`, where the first element of the sub-diagonal
+    /// and the last element of the super-diagonal are ignored.
+    fn solve_tridiagonal(
+        self,
+        diagonals: D,
+        rhs: R,
+        batch_size: usize,
+        dim: usize,
+    ) -> Result<AccessOp<Self::Op, Self>, Error>;
+}
+
+pub trait LinAlgSparse<I, V, D, T>: PlatformInstance
+where
+    I: Access<u64>,
+    V: Access<T>,
+    D: Access<T>,
+    T: CType,
+{
+    type Op: ReadOp<Self, T>;
+
+    /// Multiply a sparse matrix in CSR format, with the given row `offsets`, column `indices`,
+    /// and `values`, by a `dense` matrix, where `dims` is `[rows, columns, dense columns]`.
+    fn csr_matmul(
+        self,
+        offsets: I,
+        indices: I,
+        values: V,
+        dense: D,
+        dims: [usize; 3],
+    ) -> Result<AccessOp<Self::Op, Self>, Error>;
+}
+
+/// A factor of a matrix decomposition
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Factor {
+    /// The permutation matrix `P` of an LU decomposition `A = PLU`
+    LuP,
+    /// The unit lower triangular matrix `L` of an LU decomposition `A = PLU`
+    LuL,
+    /// The upper triangular matrix `U` of an LU decomposition `A = PLU`
+    LuU,
+    /// The matrix `Q` with orthonormal columns of a (reduced) QR decomposition `A = QR`
+    QrQ,
+    /// The upper triangular matrix `R` of a (reduced) QR decomposition `A = QR`
+    QrR,
+    /// The lower triangular matrix `L` of a Cholesky decomposition `A = LL^T`
+    Cholesky,
+    /// The eigenvalues `w` of a symmetric matrix `A = V diag(w) V^T`, in ascending order
+    EighValues,
+    /// The orthonormal eigenvectors `V` of a symmetric matrix `A = V diag(w) V^T`, as columns
+    EighVectors,
+}
+
+impl Factor {
+    /// The shape of this factor of a matrix with shape `[rows, cols]`.
+    pub fn shape(self, [rows, cols]: [usize; 2]) -> [usize; 2] {
+        match self {
+            Self::LuP | Self::LuL | Self::LuU | Self::Cholesky | Self::EighVectors => [rows, rows],
+            Self::EighValues => [1, rows],
+            Self::QrQ => [rows, rows.min(cols)],
+            Self::QrR => [rows.min(cols), cols],
+        }
+    }
+
+    /// The factors of the same decomposition as this factor, including this factor itself.
+    pub fn siblings(self) -> &'static [Self] {
+        match self {
+            Self::LuP | Self::LuL | Self::LuU => &[Self::LuP, Self::LuL, Self::LuU],
+            Self::QrQ | Self::QrR => &[Self::QrQ, Self::QrR],
+            Self::Cholesky => &[Self::Cholesky],
+            Self::EighValues | Self::EighVectors => &[Self::EighValues, Self::EighVectors],
+        }
+    }
+}
+
+/// A cache shared by the factors of one decomposition, so that reading all of them
+/// only factors each matrix once. A cached factor is only reused if its input is unchanged.
+pub struct Factorization<T: CType> {
+    cache: Arc<Mutex<Option<Factored<T>>>>,
+}
+
+struct Factored<T: CType> {
+    offset: usize,
+    input: Vec<T>,
+    factors: Vec<(Factor, Vec<T::Float>)>,
+}
+
+impl<T: CType> Factored<T> {
+    fn matches(&self, offset: usize, input: &[T]) -> bool {
+        // compare floats bitwise, so that e.g. -0. is not mistaken for 0.
+        let eq = |l: &T, r: &T| {
+            if T::IS_FLOAT {
+                l.to_f64().to_bits() == r.to_f64().to_bits()
+            } else {
+                l == r
+            }
+        };
+
+        self.offset == offset
+            && self.input.len() == input.len()
+            && self.input.iter().zip(input).all(|(l, r)| eq(l, r))
+    }
+}
+
+impl<T: CType> Factorization<T> {
+    /// Construct a new, empty cache.
+    pub fn new() -> Self {
+        Self {
+            cache: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Remove and return the cached `factor` of the given `input`, which starts at `offset`.
+    pub(crate) fn take(&self, offset: usize, input: &[T], factor: Factor) -> Option<Vec<T::Float>> {
+        let mut cache = self.lock();
+        let factored = cache.as_mut().filter(|f| f.matches(offset, input))?;
+        let i = factored.factors.iter().position(|(f, _)| *f == factor)?;
+        let (_, output) = factored.factors.swap_remove(i);
+
+        if factored.factors.is_empty() {
+            *cache = None;
+        }
+
+        Some(output)
+    }
+
+    /// Read the element at `index` of the cached `factor` of the given `input`,
+    /// which starts at `offset`.
+    pub(crate) fn get(
+        &self,
+        offset: usize,
+        input: &[T],
+        factor: Factor,
+        index: usize,
+    ) -> Option<T::Float> {
+        let cache = self.lock();
+        let factored = cache.as_ref().filter(|f| f.matches(offset, input))?;
+
+        factored
+            .factors
+            .iter()
+            .find(|(f, _)| *f == factor)
+            .map(|(_, output)| output[index])
+    }
+
+    /// Cache the `factors` of the given `input`, which starts at `offset`,
+    /// replacing any previously cached factors.
+    pub(crate) fn insert(
+        &self,
+        offset: usize,
+        input: Vec<T>,
+        factors: Vec<(Factor, Vec<T::Float>)>,
+    ) {
+        *self.lock() = if factors.is_empty() {
+            None
+        } else {
+            Some(Factored {
+                offset,
+                input,
+                factors,
+            })
+        };
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Option<Factored<T>>> {
+        // a panic cannot leave the cache in an inconsistent state, so a poisoned lock is safe
+        self.cache.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T: CType> Clone for Factorization<T> {
+    fn clone(&self) -> Self {
+        Self {
+            cache: self.cache.clone(),
+        }
+    }
+}
+
+impl<T: CType> Default for Factorization<T> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 pub trait LinAlgUnary<A, T>: PlatformInstance
@@ -275,6 +653,10 @@ where
     T: CType,
 {
     type Op: ReadOp<Self, T>;
+    type DiagFrom: ReadOp<Self, T>;
+    type Inverse: ReadOp<Self, T::Float>;
+    type Decompose: ReadOp<Self, T::Float>;
+    type Trace: ReadOp<Self, T>;
 
     fn diag(
         self,
@@ -282,6 +664,63 @@ where
         batch_size: usize,
         dim: usize,
     ) -> Result<AccessOp<Self::Op, Self>, Error>;
+
+    /// Construct a batch of `batch_size` diagonal matrices with shape `[dim, dim]`
+    /// from the diagonals in `access`.
+    fn diag_from(
+        self,
+        access: A,
+        batch_size: usize,
+        dim: usize,
+    ) -> Result<AccessOp<Self::DiagFrom, Self>, Error>;
+
+    /// Construct the inverse of each of a batch of `batch_size` matrices with shape `[dim, dim]`.
+    fn inv(
+        self,
+        access: A,
+        batch_size: usize,
+        dim: usize,
+    ) -> Result<AccessOp<Self::Inverse, Self>, Error>;
+
+    /// Construct the sum of the diagonal of each of a batch of `batch_size` matrices
+    /// with shape `[dim, dim]`, without reading the diagonals into a separate buffer.
+    fn trace(
+        self,
+        access: A,
+        batch_size: usize,
+        dim: usize,
+    ) -> Result<AccessOp<Self::Trace, Self>, Error>;
+
+    /// Construct the given `factor` of the decomposition of each of a batch of `batch_size`
+    /// matrices with shape `dims`. An LU, Cholesky, or eigen decomposition requires square matrices.
+    /// The factors of one decomposition should share a `factorization`, to factor only once.
+    fn decompose(
+        self,
+        access: A,
+        factor: Factor,
+        batch_size: usize,
+        dims: [usize; 2],
+        factorization: Factorization<T>,
+    ) -> Result<AccessOp<Self::Decompose, Self>, Error>;
+}
+
+pub trait Normalize<A, S, B, T>: PlatformInstance
+where
+    A: Access<T>,
+    S: Access<T>,
+    B: Access<T>,
+    T: Float,
+{
+    type Op: ReadOp<Self, T>;
+
+    fn layer_norm(
+        self,
+        access: A,
+        scale: S,
+        bias: B,
+        stride: usize,
+        eps: T,
+    ) -> Result<AccessOp<Self::Op, Self>, Error>;
 }
 
 pub trait Random: PlatformInstance {
@@ -293,6 +732,45 @@ pub trait Random: PlatformInstance {
     fn random_uniform(self, size: usize) -> Result<AccessOp<Self::Uniform, Self>, Error>;
 }
 
+pub trait Resample<A: Access<T>, T: CType>: PlatformInstance {
+    type Op: ReadOp<Self, T>;
+
+    /// Upsample a batch of matrices with shape `dims` by a factor of `scale` along each axis.
+    fn upsample(
+        self,
+        access: A,
+        dims: [usize; 3],
+        scale: [usize; 2],
+        mode: Interpolation,
+    ) -> Result<AccessOp<Self::Op, Self>, Error>;
+}
+
+pub trait Accumulate<A, T: CType>: PlatformInstance {
+    fn axpy<Y: AccessMut<T>>(self, y: &mut Y, alpha: T, x: A) -> Result<(), Error>;
+}
+
+pub trait ScatterAxis<I, A, T: CType>: PlatformInstance {
+    /// Overwrite the elements of `y` at the given `indices` along its middle axis
+    /// with the elements of `values`, where `dims` is `[outer, axis, inner]`.
+    fn scatter<Y: AccessMut<T>>(
+        self,
+        y: &mut Y,
+        indices: I,
+        values: A,
+        dims: [usize; 3],
+    ) -> Result<(), Error>;
+
+    /// Add the elements of `values` to the elements of `y` at the given `indices`
+    /// along its middle axis, where `dims` is `[outer, axis, inner]`.
+    fn scatter_add<Y: AccessMut<T>>(
+        self,
+        y: &mut Y,
+        indices: I,
+        values: A,
+        dims: [usize; 3],
+    ) -> Result<(), Error>;
+}
+
 pub trait ReduceAll<A, T>: PlatformInstance {
     fn all(self, access: A) -> Result<bool, Error>;
 
@@ -309,6 +787,8 @@ pub trait ReduceAll<A, T>: PlatformInstance {
 
 pub trait ReduceAxes<A: Access<T>, T: CType>: PlatformInstance {
     type Op: ReadOp<Self, T>;
+    type Moment: ReadOp<Self, T::Float>;
+    type Arg: ReadOp<Self, u64>;
 
     fn max(self, access: A, stride: usize) -> Result<AccessOp<Self::Op, Self>, Error>;
 
@@ -317,93 +797,355 @@ pub trait ReduceAxes<A: Access<T>, T: CType>: PlatformInstance {
     fn product(self, access: A, stride: usize) -> Result<AccessOp<Self::Op, Self>, Error>;
 
     fn sum(self, access: A, stride: usize) -> Result<AccessOp<Self::Op, Self>, Error>;
-}
 
-pub trait Transform<A: Access<T>, T: CType>: PlatformInstance {
-    type Broadcast: ReadOp<Self, T>;
-    type Slice: ReadOp<Self, T>;
-    type Transpose: ReadOp<Self, T>;
+    fn mean(self, access: A, stride: usize) -> Result<AccessOp<Self::Moment, Self>, Error>;
 
-    fn broadcast(
-        self,
-        access: A,
-        shape: Shape,
-        broadcast: Shape,
-    ) -> Result<AccessOp<Self::Broadcast, Self>, Error>;
+    fn var(self, access: A, stride: usize) -> Result<AccessOp<Self::Moment, Self>, Error>;
 
-    fn slice(
-        self,
-        access: A,
-        shape: &[usize],
-        range: Range,
-    ) -> Result<AccessOp<Self::Slice, Self>, Error>;
+    fn std(self, access: A, stride: usize) -> Result<AccessOp<Self::Moment, Self>, Error>;
 
-    fn transpose(
+    fn argmax(self, access: A, stride: usize) -> Result<AccessOp<Self::Arg, Self>, Error>;
+
+    fn argmin(self, access: A, stride: usize) -> Result<AccessOp<Self::Arg, Self>, Error>;
+
+    fn max_ignore_nan(self, access: A, stride: usize) -> Result<AccessOp<Self::Op, Self>, Error>;
+
+    fn min_ignore_nan(self, access: A, stride: usize) -> Result<AccessOp<Self::Op, Self>, Error>;
+
+    fn product_ignore_nan(
         self,
         access: A,
-        shape: Shape,
-        permutation: Axes,
-    ) -> Result<AccessOp<Self::Transpose, Self>, Error>;
-}
+        stride: usize,
+    ) -> Result<AccessOp<Self::Op, Self>, Error>;
 
-pub enum Cast<A, IT, OT> {
-    #[cfg(feature = "opencl")]
-    CL(opencl::ops::Cast<A, IT, OT>),
-    Host(host::ops::Cast<A, IT, OT>),
-}
+    fn sum_ignore_nan(self, access: A, stride: usize) -> Result<AccessOp<Self::Op, Self>, Error>;
 
-impl<A: Access<IT>, IT: CType, OT: CType> Op for Cast<A, IT, OT> {
-    fn size(&self) -> usize {
-        op_dispatch!(self, op, op.size())
-    }
+    /// Construct the reduction of `size` empty lanes of `access`, each to the given identity `id`.
+    fn identity(self, access: A, size: usize, id: T) -> Result<AccessOp<Self::Op, Self>, Error>;
 }
 
-impl<A: Access<IT>, IT: CType, OT: CType> Enqueue<Platform, OT> for Cast<A, IT, OT> {
-    type Buffer = Buffer<OT>;
-
-    fn enqueue(&self) -> Result<Self::Buffer, Error> {
-        op_enqueue!(self, OT)
-    }
+/// The identity of a max-reduce: negative infinity, or the minimum value of an integer type.
+pub(crate) fn max_identity<T: CType>() -> T {
+    T::from_f64(f64::NEG_INFINITY)
 }
 
-impl<A: Access<IT>, IT: CType, OT: CType> ReadValue<Platform, OT> for Cast<A, IT, OT> {
-    fn read_value(&self, offset: usize) -> Result<OT, Error> {
-        op_dispatch!(self, op, op.read_value(offset))
-    }
+/// The identity of a min-reduce: positive infinity, or the maximum value of an integer type.
+pub(crate) fn min_identity<T: CType>() -> T {
+    T::from_f64(f64::INFINITY)
 }
 
-impl<A, IT, OT> From<host::ops::Cast<A, IT, OT>> for Cast<A, IT, OT> {
-    fn from(op: host::ops::Cast<A, IT, OT>) -> Cast<A, IT, OT> {
-        Self::Host(op)
+/// The identity of a max- or min-reduce which ignores NaN, if `T` is a floating-point type.
+/// This is NaN itself, so that a lane with no values other than NaN reduces to NaN.
+pub(crate) fn ignore_nan_identity<T: CType>(id: T) -> T {
+    if T::IS_FLOAT {
+        T::from_f64(f64::NAN)
+    } else {
+        id
     }
 }
 
-#[cfg(feature = "opencl")]
-impl<A, IT, OT> From<opencl::ops::Cast<A, IT, OT>> for Cast<A, IT, OT> {
-    fn from(op: opencl::ops::Cast<A, IT, OT>) -> Cast<A, IT, OT> {
-        Self::CL(op)
+/// Reduce `l` and `r` with the given `reduce` function unless either is NaN,
+/// in which case return the other.
+#[inline]
+pub(crate) fn ignore_nan<T: CType>(l: T, r: T, reduce: fn(T, T) -> T) -> T {
+    if r.to_f64().is_nan() {
+        l
+    } else if l.to_f64().is_nan() {
+        r
+    } else {
+        reduce(l, r)
     }
 }
 
-pub enum Cond<A, L, R, T> {
-    #[cfg(feature = "opencl")]
-    CL(opencl::ops::Cond<A, L, R, T>),
-    Host(host::ops::Cond<A, L, R, T>),
-}
-
-impl<A, L, R, T> Op for Cond<A, L, R, T>
+pub trait Segments<O, A, T>: PlatformInstance
 where
-    A: Access<u8>,
-    L: Access<T>,
-    R: Access<T>,
+    O: Access<u64>,
+    A: Access<T>,
     T: CType,
 {
-    fn size(&self) -> usize {
-        op_dispatch!(self, op, op.size())
-    }
-}
+    type Pad: ReadOp<Self, T>;
+    type Reduce: ReadOp<Self, T>;
 
-impl<A, L, R, T> Enqueue<Platform, T> for Cond<A, L, R, T>
+    /// Copy each of the `segments` of `values` delimited by `offsets` into a row of length
+    /// `max_len`, filling the remainder of each row with `pad`.
+    fn pad_segments(
+        self,
+        offsets: O,
+        values: A,
+        segments: usize,
+        max_len: usize,
+        pad: T,
+    ) -> Result<AccessOp<Self::Pad, Self>, Error>;
+
+    fn segment_max(
+        self,
+        offsets: O,
+        values: A,
+        segments: usize,
+    ) -> Result<AccessOp<Self::Reduce, Self>, Error>;
+
+    fn segment_min(
+        self,
+        offsets: O,
+        values: A,
+        segments: usize,
+    ) -> Result<AccessOp<Self::Reduce, Self>, Error>;
+
+    fn segment_product(
+        self,
+        offsets: O,
+        values: A,
+        segments: usize,
+    ) -> Result<AccessOp<Self::Reduce, Self>, Error>;
+
+    fn segment_sum(
+        self,
+        offsets: O,
+        values: A,
+        segments: usize,
+    ) -> Result<AccessOp<Self::Reduce, Self>, Error>;
+}
+
+pub trait ScanAxis<A: Access<T>, T: CType>: PlatformInstance {
+    type Op: ReadOp<Self, T>;
+    type Cdf: ReadOp<Self, T::Float>;
+
+    /// Construct the empirical cumulative distribution of each lane of `dim` elements,
+    /// i.e. the fraction of the elements of its lane less than or equal to each element.
+    fn cdf(self, access: A, dim: usize, inner: usize) -> Result<AccessOp<Self::Cdf, Self>, Error>;
+
+    fn cumprod(
+        self,
+        access: A,
+        dim: usize,
+        inner: usize,
+    ) -> Result<AccessOp<Self::Op, Self>, Error>;
+
+    fn cumsum(self, access: A, dim: usize, inner: usize)
+        -> Result<AccessOp<Self::Op, Self>, Error>;
+
+    fn logcumsumexp(
+        self,
+        access: A,
+        dim: usize,
+        inner: usize,
+    ) -> Result<AccessOp<Self::Op, Self>, Error>;
+}
+
+pub trait SortAxis<A: Access<T>, T: CType>: PlatformInstance {
+    type Sort: ReadOp<Self, T>;
+    type ArgSort: ReadOp<Self, u64>;
+
+    /// Construct a stable sort of each lane of `dim` elements, placing NaN last
+    /// (or first, if `descending`).
+    fn sort(
+        self,
+        access: A,
+        dim: usize,
+        inner: usize,
+        descending: bool,
+    ) -> Result<AccessOp<Self::Sort, Self>, Error>;
+
+    /// Construct the indices which would stably sort each lane of `dim` elements.
+    fn argsort(
+        self,
+        access: A,
+        dim: usize,
+        inner: usize,
+    ) -> Result<AccessOp<Self::ArgSort, Self>, Error>;
+}
+
+pub trait ReduceAxesWithIndex<A: Access<T>, T: CType>: Convert<T> + Convert<u64> {
+    #[allow(clippy::type_complexity)]
+    fn max_with_index(
+        self,
+        access: A,
+        stride: usize,
+    ) -> Result<(<Self as Convert<T>>::Buffer, <Self as Convert<u64>>::Buffer), Error>;
+
+    #[allow(clippy::type_complexity)]
+    fn min_with_index(
+        self,
+        access: A,
+        stride: usize,
+    ) -> Result<(<Self as Convert<T>>::Buffer, <Self as Convert<u64>>::Buffer), Error>;
+}
+
+pub trait Transform<A: Access<T>, T: CType>: PlatformInstance {
+    type Broadcast: ReadOp<Self, T>;
+    type Slice: ReadOp<Self, T>;
+    type Transpose: ReadOp<Self, T>;
+
+    fn broadcast(
+        self,
+        access: A,
+        shape: Shape,
+        broadcast: Shape,
+    ) -> Result<AccessOp<Self::Broadcast, Self>, Error>;
+
+    fn slice(
+        self,
+        access: A,
+        shape: &[usize],
+        range: Range,
+    ) -> Result<AccessOp<Self::Slice, Self>, Error>;
+
+    fn transpose(
+        self,
+        access: A,
+        shape: Shape,
+        permutation: Axes,
+    ) -> Result<AccessOp<Self::Transpose, Self>, Error>;
+
+    fn reverse(
+        self,
+        access: A,
+        shape: Shape,
+        axes: Axes,
+    ) -> Result<AccessOp<Self::Transpose, Self>, Error>;
+
+    fn roll(
+        self,
+        access: A,
+        shape: Shape,
+        shift: usize,
+        axis: usize,
+    ) -> Result<AccessOp<Self::Transpose, Self>, Error>;
+
+    /// Construct a view of `access` with the given `shape`, `strides`, and `offset`.
+    ///
+    /// # Safety
+    /// Every source offset reachable through the view must be less than the size of `access`.
+    unsafe fn as_strided(
+        self,
+        access: A,
+        shape: Shape,
+        strides: Strides,
+        offset: usize,
+    ) -> Result<AccessOp<Self::Transpose, Self>, Error>;
+}
+
+pub enum Bitcast<A, IT, OT> {
+    #[cfg(feature = "opencl")]
+    CL(opencl::ops::Bitcast<A, IT, OT>),
+    Host(host::ops::Bitcast<A, IT, OT>),
+}
+
+impl<A: Access<IT>, IT: CType, OT: CType> Op for Bitcast<A, IT, OT> {
+    fn size(&self) -> usize {
+        op_dispatch!(self, op, op.size())
+    }
+
+    fn name(&self) -> &'static str {
+        op_dispatch!(self, op, op.name())
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        op_dispatch!(self, op, op.inputs())
+    }
+}
+
+impl<A: Access<IT>, IT: CType, OT: CType> Enqueue<Platform, OT> for Bitcast<A, IT, OT> {
+    type Buffer = Buffer<OT>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        op_enqueue!(self, OT)
+    }
+}
+
+impl<A: Access<IT>, IT: CType, OT: CType> ReadValue<Platform, OT> for Bitcast<A, IT, OT> {
+    fn read_value(&self, offset: usize) -> Result<OT, Error> {
+        op_dispatch!(self, op, op.read_value(offset))
+    }
+}
+
+impl<A, IT, OT> From<host::ops::Bitcast<A, IT, OT>> for Bitcast<A, IT, OT> {
+    fn from(op: host::ops::Bitcast<A, IT, OT>) -> Bitcast<A, IT, OT> {
+        Self::Host(op)
+    }
+}
+
+#[cfg(feature = "opencl")]
+impl<A, IT, OT> From<opencl::ops::Bitcast<A, IT, OT>> for Bitcast<A, IT, OT> {
+    fn from(op: opencl::ops::Bitcast<A, IT, OT>) -> Bitcast<A, IT, OT> {
+        Self::CL(op)
+    }
+}
+
+pub enum Cast<A, IT, OT> {
+    #[cfg(feature = "opencl")]
+    CL(opencl::ops::Cast<A, IT, OT>),
+    Host(host::ops::Cast<A, IT, OT>),
+}
+
+impl<A: Access<IT>, IT: CType, OT: CType> Op for Cast<A, IT, OT> {
+    fn size(&self) -> usize {
+        op_dispatch!(self, op, op.size())
+    }
+
+    fn name(&self) -> &'static str {
+        op_dispatch!(self, op, op.name())
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        op_dispatch!(self, op, op.inputs())
+    }
+}
+
+impl<A: Access<IT>, IT: CType, OT: CType> Enqueue<Platform, OT> for Cast<A, IT, OT> {
+    type Buffer = Buffer<OT>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        op_enqueue!(self, OT)
+    }
+}
+
+impl<A: Access<IT>, IT: CType, OT: CType> ReadValue<Platform, OT> for Cast<A, IT, OT> {
+    fn read_value(&self, offset: usize) -> Result<OT, Error> {
+        op_dispatch!(self, op, op.read_value(offset))
+    }
+}
+
+impl<A, IT, OT> From<host::ops::Cast<A, IT, OT>> for Cast<A, IT, OT> {
+    fn from(op: host::ops::Cast<A, IT, OT>) -> Cast<A, IT, OT> {
+        Self::Host(op)
+    }
+}
+
+#[cfg(feature = "opencl")]
+impl<A, IT, OT> From<opencl::ops::Cast<A, IT, OT>> for Cast<A, IT, OT> {
+    fn from(op: opencl::ops::Cast<A, IT, OT>) -> Cast<A, IT, OT> {
+        Self::CL(op)
+    }
+}
+
+pub enum Cond<A, L, R, T> {
+    #[cfg(feature = "opencl")]
+    CL(opencl::ops::Cond<A, L, R, T>),
+    Host(host::ops::Cond<A, L, R, T>),
+}
+
+impl<A, L, R, T> Op for Cond<A, L, R, T>
+where
+    A: Access<u8>,
+    L: Access<T>,
+    R: Access<T>,
+    T: CType,
+{
+    fn size(&self) -> usize {
+        op_dispatch!(self, op, op.size())
+    }
+
+    fn name(&self) -> &'static str {
+        op_dispatch!(self, op, op.name())
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        op_dispatch!(self, op, op.inputs())
+    }
+}
+
+impl<A, L, R, T> Enqueue<Platform, T> for Cond<A, L, R, T>
 where
     A: Access<u8>,
     L: Access<T>,
@@ -442,137 +1184,893 @@ impl<A, L, R, T> From<opencl::ops::Cond<A, L, R, T>> for Cond<A, L, R, T> {
     }
 }
 
-pub enum Dual<L, R, IT, OT> {
+pub enum CondScalar<A, T> {
     #[cfg(feature = "opencl")]
-    CL(opencl::ops::Dual<L, R, IT, OT>),
-    Host(host::ops::Dual<L, R, IT, OT>),
+    CL(opencl::ops::CondScalar<A, T>),
+    Host(host::ops::CondScalar<A, T>),
 }
 
-impl<L, R, IT, OT> Op for Dual<L, R, IT, OT>
+impl<A: Access<u8>, T: CType> Op for CondScalar<A, T> {
+    fn size(&self) -> usize {
+        op_dispatch!(self, op, op.size())
+    }
+
+    fn name(&self) -> &'static str {
+        op_dispatch!(self, op, op.name())
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        op_dispatch!(self, op, op.inputs())
+    }
+}
+
+impl<A: Access<u8>, T: CType> Enqueue<Platform, T> for CondScalar<A, T> {
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        op_enqueue!(self, T)
+    }
+}
+
+impl<A: Access<u8>, T: CType> ReadValue<Platform, T> for CondScalar<A, T> {
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        op_dispatch!(self, op, op.read_value(offset))
+    }
+}
+
+impl<A, T> From<host::ops::CondScalar<A, T>> for CondScalar<A, T> {
+    fn from(op: host::ops::CondScalar<A, T>) -> Self {
+        Self::Host(op)
+    }
+}
+
+#[cfg(feature = "opencl")]
+impl<A, T> From<opencl::ops::CondScalar<A, T>> for CondScalar<A, T> {
+    fn from(op: opencl::ops::CondScalar<A, T>) -> Self {
+        Self::CL(op)
+    }
+}
+
+pub enum Conv<I, K, T> {
+    #[cfg(feature = "opencl")]
+    CL(opencl::ops::Conv<I, K, T>),
+    Host(host::ops::Conv<I, K, T>),
+}
+
+impl<I, K, T> Op for Conv<I, K, T>
 where
-    L: Access<IT>,
-    R: Access<IT>,
-    IT: CType,
-    OT: CType,
+    I: Access<T>,
+    K: Access<T>,
+    T: CType,
 {
     fn size(&self) -> usize {
         op_dispatch!(self, op, op.size())
     }
+
+    fn name(&self) -> &'static str {
+        op_dispatch!(self, op, op.name())
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        op_dispatch!(self, op, op.inputs())
+    }
 }
 
-impl<L, R, IT, OT> Enqueue<Platform, OT> for Dual<L, R, IT, OT>
+impl<I, K, T> Enqueue<Platform, T> for Conv<I, K, T>
 where
-    L: Access<IT>,
-    R: Access<IT>,
-    IT: CType,
-    OT: CType,
+    I: Access<T>,
+    K: Access<T>,
+    T: CType,
 {
-    type Buffer = Buffer<OT>;
+    type Buffer = Buffer<T>;
 
     fn enqueue(&self) -> Result<Self::Buffer, Error> {
-        op_enqueue!(self, OT)
+        op_enqueue!(self, T)
     }
 }
 
-impl<L, R, IT, OT> ReadValue<Platform, OT> for Dual<L, R, IT, OT>
+impl<I, K, T> ReadValue<Platform, T> for Conv<I, K, T>
 where
-    L: Access<IT>,
-    R: Access<IT>,
-    IT: CType,
-    OT: CType,
+    I: Access<T>,
+    K: Access<T>,
+    T: CType,
 {
-    fn read_value(&self, offset: usize) -> Result<OT, Error> {
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
         op_dispatch!(self, op, op.read_value(offset))
     }
 }
 
+impl<I, K, T> From<host::ops::Conv<I, K, T>> for Conv<I, K, T> {
+    fn from(op: host::ops::Conv<I, K, T>) -> Self {
+        Self::Host(op)
+    }
+}
+
 #[cfg(feature = "opencl")]
-impl<L, R, IT, OT> From<opencl::ops::Dual<L, R, IT, OT>> for Dual<L, R, IT, OT> {
-    fn from(op: opencl::ops::Dual<L, R, IT, OT>) -> Self {
+impl<I, K, T> From<opencl::ops::Conv<I, K, T>> for Conv<I, K, T> {
+    fn from(op: opencl::ops::Conv<I, K, T>) -> Self {
         Self::CL(op)
     }
 }
 
-impl<L, R, IT, OT> From<host::ops::Dual<L, R, IT, OT>> for Dual<L, R, IT, OT> {
-    fn from(op: host::ops::Dual<L, R, IT, OT>) -> Self {
+pub enum ConvTranspose<I, K, T> {
+    #[cfg(feature = "opencl")]
+    CL(opencl::ops::ConvTranspose<I, K, T>),
+    Host(host::ops::ConvTranspose<I, K, T>),
+}
+
+impl<I, K, T> Op for ConvTranspose<I, K, T>
+where
+    I: Access<T>,
+    K: Access<T>,
+    T: CType,
+{
+    fn size(&self) -> usize {
+        op_dispatch!(self, op, op.size())
+    }
+
+    fn name(&self) -> &'static str {
+        op_dispatch!(self, op, op.name())
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        op_dispatch!(self, op, op.inputs())
+    }
+}
+
+impl<I, K, T> Enqueue<Platform, T> for ConvTranspose<I, K, T>
+where
+    I: Access<T>,
+    K: Access<T>,
+    T: CType,
+{
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        op_enqueue!(self, T)
+    }
+}
+
+impl<I, K, T> ReadValue<Platform, T> for ConvTranspose<I, K, T>
+where
+    I: Access<T>,
+    K: Access<T>,
+    T: CType,
+{
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        op_dispatch!(self, op, op.read_value(offset))
+    }
+}
+
+impl<I, K, T> From<host::ops::ConvTranspose<I, K, T>> for ConvTranspose<I, K, T> {
+    fn from(op: host::ops::ConvTranspose<I, K, T>) -> Self {
+        Self::Host(op)
+    }
+}
+
+#[cfg(feature = "opencl")]
+impl<I, K, T> From<opencl::ops::ConvTranspose<I, K, T>> for ConvTranspose<I, K, T> {
+    fn from(op: opencl::ops::ConvTranspose<I, K, T>) -> Self {
+        Self::CL(op)
+    }
+}
+
+pub enum Dual<L, R, IT, OT> {
+    #[cfg(feature = "opencl")]
+    CL(opencl::ops::Dual<L, R, IT, OT>),
+    Host(host::ops::Dual<L, R, IT, OT>),
+}
+
+impl<L, R, IT, OT> Op for Dual<L, R, IT, OT>
+where
+    L: Access<IT>,
+    R: Access<IT>,
+    IT: CType,
+    OT: CType,
+{
+    fn size(&self) -> usize {
+        op_dispatch!(self, op, op.size())
+    }
+
+    fn name(&self) -> &'static str {
+        op_dispatch!(self, op, op.name())
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        op_dispatch!(self, op, op.inputs())
+    }
+}
+
+impl<L, R, IT, OT> Enqueue<Platform, OT> for Dual<L, R, IT, OT>
+where
+    L: Access<IT>,
+    R: Access<IT>,
+    IT: CType,
+    OT: CType,
+{
+    type Buffer = Buffer<OT>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        op_enqueue!(self, OT)
+    }
+}
+
+impl<L, R, IT, OT> ReadValue<Platform, OT> for Dual<L, R, IT, OT>
+where
+    L: Access<IT>,
+    R: Access<IT>,
+    IT: CType,
+    OT: CType,
+{
+    fn read_value(&self, offset: usize) -> Result<OT, Error> {
+        op_dispatch!(self, op, op.read_value(offset))
+    }
+}
+
+impl<L, R, IT: CType, OT> BroadcastDual for Dual<L, R, IT, OT> {
+    fn broadcast(self, broadcast: DualBroadcast) -> Result<Self, Error> {
+        match self {
+            #[cfg(feature = "opencl")]
+            Self::CL(op) => op.broadcast(broadcast).map(Self::CL),
+            Self::Host(op) => op.broadcast(broadcast).map(Self::Host),
+        }
+    }
+}
+
+#[cfg(feature = "opencl")]
+impl<L, R, IT, OT> From<opencl::ops::Dual<L, R, IT, OT>> for Dual<L, R, IT, OT> {
+    fn from(op: opencl::ops::Dual<L, R, IT, OT>) -> Self {
+        Self::CL(op)
+    }
+}
+
+impl<L, R, IT, OT> From<host::ops::Dual<L, R, IT, OT>> for Dual<L, R, IT, OT> {
+    fn from(op: host::ops::Dual<L, R, IT, OT>) -> Self {
+        Self::Host(op)
+    }
+}
+
+pub enum LayerNorm<A, S, B, T> {
+    #[cfg(feature = "opencl")]
+    CL(opencl::ops::LayerNorm<A, S, B, T>),
+    Host(host::ops::LayerNorm<A, S, B, T>),
+}
+
+impl<A, S, B, T> Op for LayerNorm<A, S, B, T>
+where
+    A: Access<T>,
+    S: Access<T>,
+    B: Access<T>,
+    T: Float,
+{
+    fn size(&self) -> usize {
+        op_dispatch!(self, op, op.size())
+    }
+
+    fn name(&self) -> &'static str {
+        op_dispatch!(self, op, op.name())
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        op_dispatch!(self, op, op.inputs())
+    }
+}
+
+impl<A, S, B, T> Enqueue<Platform, T> for LayerNorm<A, S, B, T>
+where
+    A: Access<T>,
+    S: Access<T>,
+    B: Access<T>,
+    T: Float,
+{
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        op_enqueue!(self, T)
+    }
+}
+
+impl<A, S, B, T> ReadValue<Platform, T> for LayerNorm<A, S, B, T>
+where
+    A: Access<T>,
+    S: Access<T>,
+    B: Access<T>,
+    T: Float,
+{
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        op_dispatch!(self, op, op.read_value(offset))
+    }
+}
+
+#[cfg(feature = "opencl")]
+impl<A, S, B, T> From<opencl::ops::LayerNorm<A, S, B, T>> for LayerNorm<A, S, B, T> {
+    fn from(op: opencl::ops::LayerNorm<A, S, B, T>) -> Self {
+        Self::CL(op)
+    }
+}
+
+impl<A, S, B, T> From<host::ops::LayerNorm<A, S, B, T>> for LayerNorm<A, S, B, T> {
+    fn from(op: host::ops::LayerNorm<A, S, B, T>) -> Self {
+        Self::Host(op)
+    }
+}
+
+pub enum Compact<A, P, T> {
+    #[cfg(feature = "opencl")]
+    CL(opencl::ops::Compact<A, P, T>),
+    Host(host::ops::Compact<A, P, T>),
+}
+
+impl<A, P, T> Op for Compact<A, P, T>
+where
+    A: Access<T>,
+    P: Access<u64>,
+    T: CType,
+{
+    fn size(&self) -> usize {
+        op_dispatch!(self, op, op.size())
+    }
+
+    fn name(&self) -> &'static str {
+        op_dispatch!(self, op, op.name())
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        op_dispatch!(self, op, op.inputs())
+    }
+}
+
+impl<A, P, T> Enqueue<Platform, T> for Compact<A, P, T>
+where
+    A: Access<T>,
+    P: Access<u64>,
+    T: CType,
+{
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        op_enqueue!(self, T)
+    }
+}
+
+impl<A, P, T> ReadValue<Platform, T> for Compact<A, P, T>
+where
+    A: Access<T>,
+    P: Access<u64>,
+    T: CType,
+{
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        op_dispatch!(self, op, op.read_value(offset))
+    }
+}
+
+impl<A, P, T> From<host::ops::Compact<A, P, T>> for Compact<A, P, T> {
+    fn from(op: host::ops::Compact<A, P, T>) -> Self {
+        Self::Host(op)
+    }
+}
+
+#[cfg(feature = "opencl")]
+impl<A, P, T> From<opencl::ops::Compact<A, P, T>> for Compact<A, P, T> {
+    fn from(op: opencl::ops::Compact<A, P, T>) -> Self {
+        Self::CL(op)
+    }
+}
+
+pub enum Gather<A, I, T> {
+    #[cfg(feature = "opencl")]
+    CL(opencl::ops::Gather<A, I, T>),
+    Host(host::ops::Gather<A, I, T>),
+}
+
+impl<A, I, T> Op for Gather<A, I, T>
+where
+    A: Access<T>,
+    I: Access<u64>,
+    T: CType,
+{
+    fn size(&self) -> usize {
+        op_dispatch!(self, op, op.size())
+    }
+
+    fn name(&self) -> &'static str {
+        op_dispatch!(self, op, op.name())
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        op_dispatch!(self, op, op.inputs())
+    }
+}
+
+impl<A, I, T> Enqueue<Platform, T> for Gather<A, I, T>
+where
+    A: Access<T>,
+    I: Access<u64>,
+    T: CType,
+{
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        op_enqueue!(self, T)
+    }
+}
+
+impl<A, I, T> ReadValue<Platform, T> for Gather<A, I, T>
+where
+    A: Access<T>,
+    I: Access<u64>,
+    T: CType,
+{
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        op_dispatch!(self, op, op.read_value(offset))
+    }
+}
+
+impl<A, I, T> From<host::ops::Gather<A, I, T>> for Gather<A, I, T> {
+    fn from(op: host::ops::Gather<A, I, T>) -> Self {
+        Self::Host(op)
+    }
+}
+
+#[cfg(feature = "opencl")]
+impl<A, I, T> From<opencl::ops::Gather<A, I, T>> for Gather<A, I, T> {
+    fn from(op: opencl::ops::Gather<A, I, T>) -> Self {
+        Self::CL(op)
+    }
+}
+
+pub enum Eye<T> {
+    #[cfg(feature = "opencl")]
+    CL(opencl::ops::Eye<T>),
+    Host(host::ops::Eye<T>),
+}
+
+#[cfg(feature = "opencl")]
+impl<T> From<opencl::ops::Eye<T>> for Eye<T> {
+    fn from(op: opencl::ops::Eye<T>) -> Self {
+        Self::CL(op)
+    }
+}
+
+impl<T> From<host::ops::Eye<T>> for Eye<T> {
+    fn from(op: host::ops::Eye<T>) -> Self {
+        Self::Host(op)
+    }
+}
+
+impl<T: Send + Sync> Op for Eye<T> {
+    fn size(&self) -> usize {
+        op_dispatch!(self, op, op.size())
+    }
+
+    fn name(&self) -> &'static str {
+        op_dispatch!(self, op, op.name())
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        op_dispatch!(self, op, op.inputs())
+    }
+}
+
+impl<T: CType> Enqueue<Platform, T> for Eye<T> {
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        op_enqueue!(self, T)
+    }
+}
+
+impl<T: CType> ReadValue<Platform, T> for Eye<T> {
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        op_dispatch!(self, op, op.read_value(offset))
+    }
+}
+
+pub enum Linear<T> {
+    #[cfg(feature = "opencl")]
+    CL(opencl::ops::Linear<T>),
+    Host(host::ops::Linear<T>),
+}
+
+#[cfg(feature = "opencl")]
+impl<T> From<opencl::ops::Linear<T>> for Linear<T> {
+    fn from(op: opencl::ops::Linear<T>) -> Self {
+        Self::CL(op)
+    }
+}
+
+impl<T> From<host::ops::Linear<T>> for Linear<T> {
+    fn from(op: host::ops::Linear<T>) -> Self {
+        Self::Host(op)
+    }
+}
+
+impl<T: Send + Sync> Op for Linear<T> {
+    fn size(&self) -> usize {
+        op_dispatch!(self, op, op.size())
+    }
+
+    fn name(&self) -> &'static str {
+        op_dispatch!(self, op, op.name())
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        op_dispatch!(self, op, op.inputs())
+    }
+}
+
+impl<T: CType> Enqueue<Platform, T> for Linear<T> {
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        op_enqueue!(self, T)
+    }
+}
+
+impl<T: CType> ReadValue<Platform, T> for Linear<T> {
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        op_dispatch!(self, op, op.read_value(offset))
+    }
+}
+
+pub enum CsrMatMul<I, V, D, T> {
+    #[cfg(feature = "opencl")]
+    CL(opencl::ops::CsrMatMul<I, V, D, T>),
+    Host(host::ops::CsrMatMul<I, V, D, T>),
+}
+
+impl<I, V, D, T> Op for CsrMatMul<I, V, D, T>
+where
+    I: Access<u64>,
+    V: Access<T>,
+    D: Access<T>,
+    T: CType,
+{
+    fn size(&self) -> usize {
+        op_dispatch!(self, op, op.size())
+    }
+
+    fn name(&self) -> &'static str {
+        op_dispatch!(self, op, op.name())
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        op_dispatch!(self, op, op.inputs())
+    }
+}
+
+impl<I, V, D, T> Enqueue<Platform, T> for CsrMatMul<I, V, D, T>
+where
+    I: Access<u64>,
+    V: Access<T>,
+    D: Access<T>,
+    T: CType,
+{
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        op_enqueue!(self, T)
+    }
+}
+
+impl<I, V, D, T> ReadValue<Platform, T> for CsrMatMul<I, V, D, T>
+where
+    I: Access<u64>,
+    V: Access<T>,
+    D: Access<T>,
+    T: CType,
+{
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        op_dispatch!(self, op, op.read_value(offset))
+    }
+}
+
+impl<I, V, D, T> From<host::ops::CsrMatMul<I, V, D, T>> for CsrMatMul<I, V, D, T> {
+    fn from(op: host::ops::CsrMatMul<I, V, D, T>) -> Self {
+        Self::Host(op)
+    }
+}
+
+#[cfg(feature = "opencl")]
+impl<I, V, D, T> From<opencl::ops::CsrMatMul<I, V, D, T>> for CsrMatMul<I, V, D, T> {
+    fn from(op: opencl::ops::CsrMatMul<I, V, D, T>) -> Self {
+        Self::CL(op)
+    }
+}
+
+pub enum MatchedFilter<I, K, T> {
+    #[cfg(feature = "opencl")]
+    CL(opencl::ops::MatchedFilter<I, K, T>),
+    Host(host::ops::MatchedFilter<I, K, T>),
+}
+
+impl<I, K, T> Op for MatchedFilter<I, K, T>
+where
+    I: Access<T>,
+    K: Access<T>,
+    T: CType,
+{
+    fn size(&self) -> usize {
+        op_dispatch!(self, op, op.size())
+    }
+
+    fn name(&self) -> &'static str {
+        op_dispatch!(self, op, op.name())
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        op_dispatch!(self, op, op.inputs())
+    }
+}
+
+impl<I, K, T> Enqueue<Platform, T::Float> for MatchedFilter<I, K, T>
+where
+    I: Access<T>,
+    K: Access<T>,
+    T: CType,
+{
+    type Buffer = Buffer<T::Float>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        op_enqueue!(self, T::Float)
+    }
+}
+
+impl<I, K, T> ReadValue<Platform, T::Float> for MatchedFilter<I, K, T>
+where
+    I: Access<T>,
+    K: Access<T>,
+    T: CType,
+{
+    fn read_value(&self, offset: usize) -> Result<T::Float, Error> {
+        op_dispatch!(self, op, op.read_value(offset))
+    }
+}
+
+impl<I, K, T> From<host::ops::MatchedFilter<I, K, T>> for MatchedFilter<I, K, T> {
+    fn from(op: host::ops::MatchedFilter<I, K, T>) -> Self {
+        Self::Host(op)
+    }
+}
+
+#[cfg(feature = "opencl")]
+impl<I, K, T> From<opencl::ops::MatchedFilter<I, K, T>> for MatchedFilter<I, K, T> {
+    fn from(op: opencl::ops::MatchedFilter<I, K, T>) -> Self {
+        Self::CL(op)
+    }
+}
+
+pub enum MatDiag<A, T> {
+    #[cfg(feature = "opencl")]
+    CL(opencl::ops::MatDiag<A, T>),
+    Host(host::ops::MatDiag<A, T>),
+}
+
+impl<A: Access<T>, T: CType> Op for MatDiag<A, T> {
+    fn size(&self) -> usize {
+        op_dispatch!(self, op, op.size())
+    }
+
+    fn name(&self) -> &'static str {
+        op_dispatch!(self, op, op.name())
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        op_dispatch!(self, op, op.inputs())
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<Platform, T> for MatDiag<A, T> {
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        op_enqueue!(self, T)
+    }
+}
+
+impl<A: Access<T>, T: CType> ReadValue<Platform, T> for MatDiag<A, T> {
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        op_dispatch!(self, op, op.read_value(offset))
+    }
+}
+
+impl<A, T> From<host::ops::MatDiag<A, T>> for MatDiag<A, T> {
+    fn from(op: host::ops::MatDiag<A, T>) -> Self {
+        Self::Host(op)
+    }
+}
+
+#[cfg(feature = "opencl")]
+impl<A, T> From<opencl::ops::MatDiag<A, T>> for MatDiag<A, T> {
+    fn from(op: opencl::ops::MatDiag<A, T>) -> Self {
+        Self::CL(op)
+    }
+}
+
+pub enum MatTrace<A, T> {
+    #[cfg(feature = "opencl")]
+    CL(opencl::ops::MatTrace<A, T>),
+    Host(host::ops::MatTrace<A, T>),
+}
+
+impl<A: Access<T>, T: CType> Op for MatTrace<A, T> {
+    fn size(&self) -> usize {
+        op_dispatch!(self, op, op.size())
+    }
+
+    fn name(&self) -> &'static str {
+        op_dispatch!(self, op, op.name())
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        op_dispatch!(self, op, op.inputs())
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<Platform, T> for MatTrace<A, T> {
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        op_enqueue!(self, T)
+    }
+}
+
+impl<A: Access<T>, T: CType> ReadValue<Platform, T> for MatTrace<A, T> {
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        op_dispatch!(self, op, op.read_value(offset))
+    }
+}
+
+impl<A, T> From<host::ops::MatTrace<A, T>> for MatTrace<A, T> {
+    fn from(op: host::ops::MatTrace<A, T>) -> Self {
+        Self::Host(op)
+    }
+}
+
+#[cfg(feature = "opencl")]
+impl<A, T> From<opencl::ops::MatTrace<A, T>> for MatTrace<A, T> {
+    fn from(op: opencl::ops::MatTrace<A, T>) -> Self {
+        Self::CL(op)
+    }
+}
+
+pub enum DiagFrom<A, T> {
+    #[cfg(feature = "opencl")]
+    CL(opencl::ops::DiagFrom<A, T>),
+    Host(host::ops::DiagFrom<A, T>),
+}
+
+impl<A: Access<T>, T: CType> Op for DiagFrom<A, T> {
+    fn size(&self) -> usize {
+        op_dispatch!(self, op, op.size())
+    }
+
+    fn name(&self) -> &'static str {
+        op_dispatch!(self, op, op.name())
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        op_dispatch!(self, op, op.inputs())
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<Platform, T> for DiagFrom<A, T> {
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        op_enqueue!(self, T)
+    }
+}
+
+impl<A: Access<T>, T: CType> ReadValue<Platform, T> for DiagFrom<A, T> {
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        op_dispatch!(self, op, op.read_value(offset))
+    }
+}
+
+impl<A, T> From<host::ops::DiagFrom<A, T>> for DiagFrom<A, T> {
+    fn from(op: host::ops::DiagFrom<A, T>) -> Self {
         Self::Host(op)
     }
 }
 
-pub enum Linear<T> {
-    #[cfg(feature = "opencl")]
-    CL(opencl::ops::Linear<T>),
-    Host(host::ops::Linear<T>),
-}
-
 #[cfg(feature = "opencl")]
-impl<T> From<opencl::ops::Linear<T>> for Linear<T> {
-    fn from(op: opencl::ops::Linear<T>) -> Self {
+impl<A, T> From<opencl::ops::DiagFrom<A, T>> for DiagFrom<A, T> {
+    fn from(op: opencl::ops::DiagFrom<A, T>) -> Self {
         Self::CL(op)
     }
 }
 
-impl<T> From<host::ops::Linear<T>> for Linear<T> {
-    fn from(op: host::ops::Linear<T>) -> Self {
-        Self::Host(op)
-    }
+pub enum Inverse<A, T> {
+    #[cfg(feature = "opencl")]
+    CL(opencl::ops::Inverse<A, T>),
+    Host(host::ops::Inverse<A, T>),
 }
 
-impl<T: Send + Sync> Op for Linear<T> {
+impl<A: Access<T>, T: CType> Op for Inverse<A, T> {
     fn size(&self) -> usize {
         op_dispatch!(self, op, op.size())
     }
+
+    fn name(&self) -> &'static str {
+        op_dispatch!(self, op, op.name())
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        op_dispatch!(self, op, op.inputs())
+    }
 }
 
-impl<T: CType> Enqueue<Platform, T> for Linear<T> {
-    type Buffer = Buffer<T>;
+impl<A: Access<T>, T: CType> Enqueue<Platform, T::Float> for Inverse<A, T> {
+    type Buffer = Buffer<T::Float>;
 
     fn enqueue(&self) -> Result<Self::Buffer, Error> {
-        op_enqueue!(self, T)
+        op_enqueue!(self, T::Float)
     }
 }
 
-impl<T: CType> ReadValue<Platform, T> for Linear<T> {
-    fn read_value(&self, offset: usize) -> Result<T, Error> {
+impl<A: Access<T>, T: CType> ReadValue<Platform, T::Float> for Inverse<A, T> {
+    fn read_value(&self, offset: usize) -> Result<T::Float, Error> {
         op_dispatch!(self, op, op.read_value(offset))
     }
 }
 
-pub enum MatDiag<A, T> {
+impl<A, T> From<host::ops::Inverse<A, T>> for Inverse<A, T> {
+    fn from(op: host::ops::Inverse<A, T>) -> Self {
+        Self::Host(op)
+    }
+}
+
+#[cfg(feature = "opencl")]
+impl<A, T> From<opencl::ops::Inverse<A, T>> for Inverse<A, T> {
+    fn from(op: opencl::ops::Inverse<A, T>) -> Self {
+        Self::CL(op)
+    }
+}
+
+pub enum Decompose<A, T: CType> {
     #[cfg(feature = "opencl")]
-    CL(opencl::ops::MatDiag<A, T>),
-    Host(host::ops::MatDiag<A, T>),
+    CL(opencl::ops::Decompose<A, T>),
+    Host(host::ops::Decompose<A, T>),
 }
 
-impl<A: Access<T>, T: CType> Op for MatDiag<A, T> {
+impl<A: Access<T>, T: CType> Op for Decompose<A, T> {
     fn size(&self) -> usize {
         op_dispatch!(self, op, op.size())
     }
+
+    fn name(&self) -> &'static str {
+        op_dispatch!(self, op, op.name())
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        op_dispatch!(self, op, op.inputs())
+    }
 }
 
-impl<A: Access<T>, T: CType> Enqueue<Platform, T> for MatDiag<A, T> {
-    type Buffer = Buffer<T>;
+impl<A: Access<T>, T: CType> Enqueue<Platform, T::Float> for Decompose<A, T> {
+    type Buffer = Buffer<T::Float>;
 
     fn enqueue(&self) -> Result<Self::Buffer, Error> {
-        op_enqueue!(self, T)
+        op_enqueue!(self, T::Float)
     }
 }
 
-impl<A: Access<T>, T: CType> ReadValue<Platform, T> for MatDiag<A, T> {
-    fn read_value(&self, offset: usize) -> Result<T, Error> {
+impl<A: Access<T>, T: CType> ReadValue<Platform, T::Float> for Decompose<A, T> {
+    fn read_value(&self, offset: usize) -> Result<T::Float, Error> {
         op_dispatch!(self, op, op.read_value(offset))
     }
 }
 
-impl<A, T> From<host::ops::MatDiag<A, T>> for MatDiag<A, T> {
-    fn from(op: host::ops::MatDiag<A, T>) -> Self {
+impl<A, T: CType> From<host::ops::Decompose<A, T>> for Decompose<A, T> {
+    fn from(op: host::ops::Decompose<A, T>) -> Self {
         Self::Host(op)
     }
 }
 
 #[cfg(feature = "opencl")]
-impl<A, T> From<opencl::ops::MatDiag<A, T>> for MatDiag<A, T> {
-    fn from(op: opencl::ops::MatDiag<A, T>) -> Self {
+impl<A, T: CType> From<opencl::ops::Decompose<A, T>> for Decompose<A, T> {
+    fn from(op: opencl::ops::Decompose<A, T>) -> Self {
         Self::CL(op)
     }
 }
@@ -592,6 +2090,14 @@ where
     fn size(&self) -> usize {
         op_dispatch!(self, op, op.size())
     }
+
+    fn name(&self) -> &'static str {
+        op_dispatch!(self, op, op.name())
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        op_dispatch!(self, op, op.inputs())
+    }
 }
 
 impl<L, R, T> Enqueue<Platform, T> for MatMul<L, R, T>
@@ -663,80 +2169,346 @@ impl From<opencl::ops::RandomUniform> for RandomUniform {
     }
 }
 
-impl From<host::ops::RandomUniform> for RandomUniform {
-    fn from(op: host::ops::RandomUniform) -> Self {
+impl From<host::ops::RandomUniform> for RandomUniform {
+    fn from(op: host::ops::RandomUniform) -> Self {
+        Self::Host(op)
+    }
+}
+
+macro_rules! impl_random {
+    ($t:ty) => {
+        impl Op for $t {
+            fn size(&self) -> usize {
+                op_dispatch!(self, op, op.size())
+            }
+
+            fn name(&self) -> &'static str {
+                op_dispatch!(self, op, op.name())
+            }
+
+            fn inputs(&self) -> Vec<OpPlan> {
+                op_dispatch!(self, op, op.inputs())
+            }
+        }
+
+        impl Enqueue<Platform, f32> for $t {
+            type Buffer = Buffer<f32>;
+
+            fn enqueue(&self) -> Result<Self::Buffer, Error> {
+                op_enqueue!(self, f32)
+            }
+        }
+
+        impl ReadValue<Platform, f32> for $t {
+            fn read_value(&self, offset: usize) -> Result<f32, Error> {
+                op_dispatch!(self, op, op.read_value(offset))
+            }
+        }
+    };
+}
+
+impl_random!(RandomNormal);
+impl_random!(RandomUniform);
+
+macro_rules! impl_unary {
+    ($op:ty, $t:ty) => {
+        impl<A: Access<T>, T: CType> Op for $op {
+            fn size(&self) -> usize {
+                op_dispatch!(self, op, op.size())
+            }
+
+            fn name(&self) -> &'static str {
+                op_dispatch!(self, op, op.name())
+            }
+
+            fn inputs(&self) -> Vec<OpPlan> {
+                op_dispatch!(self, op, op.inputs())
+            }
+        }
+
+        impl<A: Access<T>, T: CType> Enqueue<Platform, $t> for $op {
+            type Buffer = Buffer<$t>;
+
+            fn enqueue(&self) -> Result<Self::Buffer, Error> {
+                op_enqueue!(self, $t)
+            }
+        }
+
+        impl<A: Access<T>, T: CType> ReadValue<Platform, $t> for $op {
+            fn read_value(&self, offset: usize) -> Result<$t, Error> {
+                op_dispatch!(self, op, op.read_value(offset))
+            }
+        }
+    };
+}
+
+pub enum Reduce<A, T: CType> {
+    #[cfg(feature = "opencl")]
+    CL(opencl::ops::Reduce<A, T>),
+    Host(host::ops::Reduce<A, T>),
+}
+
+impl_unary!(Reduce<A, T>, T);
+
+impl<A, T: CType> From<host::ops::Reduce<A, T>> for Reduce<A, T> {
+    fn from(op: host::ops::Reduce<A, T>) -> Self {
+        Self::Host(op)
+    }
+}
+
+#[cfg(feature = "opencl")]
+impl<A, T: CType> From<opencl::ops::Reduce<A, T>> for Reduce<A, T> {
+    fn from(op: opencl::ops::Reduce<A, T>) -> Self {
+        Self::CL(op)
+    }
+}
+
+pub enum ReduceArg<A, T: CType> {
+    #[cfg(feature = "opencl")]
+    CL(opencl::ops::ReduceArg<A, T>),
+    Host(host::ops::ReduceArg<A, T>),
+}
+
+impl_unary!(ReduceArg<A, T>, u64);
+
+impl<A, T: CType> From<host::ops::ReduceArg<A, T>> for ReduceArg<A, T> {
+    fn from(op: host::ops::ReduceArg<A, T>) -> Self {
+        Self::Host(op)
+    }
+}
+
+#[cfg(feature = "opencl")]
+impl<A, T: CType> From<opencl::ops::ReduceArg<A, T>> for ReduceArg<A, T> {
+    fn from(op: opencl::ops::ReduceArg<A, T>) -> Self {
+        Self::CL(op)
+    }
+}
+
+pub enum ReduceMoment<A, T: CType> {
+    #[cfg(feature = "opencl")]
+    CL(opencl::ops::ReduceMoment<A, T>),
+    Host(host::ops::ReduceMoment<A, T>),
+}
+
+impl_unary!(ReduceMoment<A, T>, T::Float);
+
+impl<A, T: CType> From<host::ops::ReduceMoment<A, T>> for ReduceMoment<A, T> {
+    fn from(op: host::ops::ReduceMoment<A, T>) -> Self {
+        Self::Host(op)
+    }
+}
+
+#[cfg(feature = "opencl")]
+impl<A, T: CType> From<opencl::ops::ReduceMoment<A, T>> for ReduceMoment<A, T> {
+    fn from(op: opencl::ops::ReduceMoment<A, T>) -> Self {
+        Self::CL(op)
+    }
+}
+
+pub enum SegmentPad<O, A, T> {
+    #[cfg(feature = "opencl")]
+    CL(opencl::ops::SegmentPad<O, A, T>),
+    Host(host::ops::SegmentPad<O, A, T>),
+}
+
+impl<O, A, T> Op for SegmentPad<O, A, T>
+where
+    O: Access<u64>,
+    A: Access<T>,
+    T: CType,
+{
+    fn size(&self) -> usize {
+        op_dispatch!(self, op, op.size())
+    }
+
+    fn name(&self) -> &'static str {
+        op_dispatch!(self, op, op.name())
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        op_dispatch!(self, op, op.inputs())
+    }
+}
+
+impl<O, A, T> Enqueue<Platform, T> for SegmentPad<O, A, T>
+where
+    O: Access<u64>,
+    A: Access<T>,
+    T: CType,
+{
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        op_enqueue!(self, T)
+    }
+}
+
+impl<O, A, T> ReadValue<Platform, T> for SegmentPad<O, A, T>
+where
+    O: Access<u64>,
+    A: Access<T>,
+    T: CType,
+{
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        op_dispatch!(self, op, op.read_value(offset))
+    }
+}
+
+impl<O, A, T> From<host::ops::SegmentPad<O, A, T>> for SegmentPad<O, A, T> {
+    fn from(op: host::ops::SegmentPad<O, A, T>) -> Self {
+        Self::Host(op)
+    }
+}
+
+#[cfg(feature = "opencl")]
+impl<O, A, T> From<opencl::ops::SegmentPad<O, A, T>> for SegmentPad<O, A, T> {
+    fn from(op: opencl::ops::SegmentPad<O, A, T>) -> Self {
+        Self::CL(op)
+    }
+}
+
+pub enum SegmentReduce<O, A, T> {
+    #[cfg(feature = "opencl")]
+    CL(opencl::ops::SegmentReduce<O, A, T>),
+    Host(host::ops::SegmentReduce<O, A, T>),
+}
+
+impl<O, A, T> Op for SegmentReduce<O, A, T>
+where
+    O: Access<u64>,
+    A: Access<T>,
+    T: CType,
+{
+    fn size(&self) -> usize {
+        op_dispatch!(self, op, op.size())
+    }
+
+    fn name(&self) -> &'static str {
+        op_dispatch!(self, op, op.name())
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        op_dispatch!(self, op, op.inputs())
+    }
+}
+
+impl<O, A, T> Enqueue<Platform, T> for SegmentReduce<O, A, T>
+where
+    O: Access<u64>,
+    A: Access<T>,
+    T: CType,
+{
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        op_enqueue!(self, T)
+    }
+}
+
+impl<O, A, T> ReadValue<Platform, T> for SegmentReduce<O, A, T>
+where
+    O: Access<u64>,
+    A: Access<T>,
+    T: CType,
+{
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        op_dispatch!(self, op, op.read_value(offset))
+    }
+}
+
+impl<O, A, T> From<host::ops::SegmentReduce<O, A, T>> for SegmentReduce<O, A, T> {
+    fn from(op: host::ops::SegmentReduce<O, A, T>) -> Self {
+        Self::Host(op)
+    }
+}
+
+#[cfg(feature = "opencl")]
+impl<O, A, T> From<opencl::ops::SegmentReduce<O, A, T>> for SegmentReduce<O, A, T> {
+    fn from(op: opencl::ops::SegmentReduce<O, A, T>) -> Self {
+        Self::CL(op)
+    }
+}
+
+pub enum Cdf<A, T: CType> {
+    #[cfg(feature = "opencl")]
+    CL(opencl::ops::Cdf<A, T>),
+    Host(host::ops::Cdf<A, T>),
+}
+
+impl_unary!(Cdf<A, T>, T::Float);
+
+impl<A, T: CType> From<host::ops::Cdf<A, T>> for Cdf<A, T> {
+    fn from(op: host::ops::Cdf<A, T>) -> Self {
         Self::Host(op)
     }
 }
 
-macro_rules! impl_random {
-    ($t:ty) => {
-        impl Op for $t {
-            fn size(&self) -> usize {
-                op_dispatch!(self, op, op.size())
-            }
-        }
+#[cfg(feature = "opencl")]
+impl<A, T: CType> From<opencl::ops::Cdf<A, T>> for Cdf<A, T> {
+    fn from(op: opencl::ops::Cdf<A, T>) -> Self {
+        Self::CL(op)
+    }
+}
 
-        impl Enqueue<Platform, f32> for $t {
-            type Buffer = Buffer<f32>;
+pub enum Scan<A, T: CType> {
+    #[cfg(feature = "opencl")]
+    CL(opencl::ops::Scan<A, T>),
+    Host(host::ops::Scan<A, T>),
+}
 
-            fn enqueue(&self) -> Result<Self::Buffer, Error> {
-                op_enqueue!(self, f32)
-            }
-        }
+impl_unary!(Scan<A, T>, T);
 
-        impl ReadValue<Platform, f32> for $t {
-            fn read_value(&self, offset: usize) -> Result<f32, Error> {
-                op_dispatch!(self, op, op.read_value(offset))
-            }
-        }
-    };
+impl<A, T: CType> From<host::ops::Scan<A, T>> for Scan<A, T> {
+    fn from(op: host::ops::Scan<A, T>) -> Self {
+        Self::Host(op)
+    }
 }
 
-impl_random!(RandomNormal);
-impl_random!(RandomUniform);
+#[cfg(feature = "opencl")]
+impl<A, T: CType> From<opencl::ops::Scan<A, T>> for Scan<A, T> {
+    fn from(op: opencl::ops::Scan<A, T>) -> Self {
+        Self::CL(op)
+    }
+}
 
-macro_rules! impl_unary {
-    ($op:ty, $t:ty) => {
-        impl<A: Access<T>, T: CType> Op for $op {
-            fn size(&self) -> usize {
-                op_dispatch!(self, op, op.size())
-            }
-        }
+pub enum Sort<A, T: CType> {
+    #[cfg(feature = "opencl")]
+    CL(opencl::ops::Sort<A, T>),
+    Host(host::ops::Sort<A, T>),
+}
 
-        impl<A: Access<T>, T: CType> Enqueue<Platform, $t> for $op {
-            type Buffer = Buffer<$t>;
+impl_unary!(Sort<A, T>, T);
 
-            fn enqueue(&self) -> Result<Self::Buffer, Error> {
-                op_enqueue!(self, $t)
-            }
-        }
+impl<A, T: CType> From<host::ops::Sort<A, T>> for Sort<A, T> {
+    fn from(op: host::ops::Sort<A, T>) -> Self {
+        Self::Host(op)
+    }
+}
 
-        impl<A: Access<T>, T: CType> ReadValue<Platform, $t> for $op {
-            fn read_value(&self, offset: usize) -> Result<$t, Error> {
-                op_dispatch!(self, op, op.read_value(offset))
-            }
-        }
-    };
+#[cfg(feature = "opencl")]
+impl<A, T: CType> From<opencl::ops::Sort<A, T>> for Sort<A, T> {
+    fn from(op: opencl::ops::Sort<A, T>) -> Self {
+        Self::CL(op)
+    }
 }
 
-pub enum Reduce<A, T: CType> {
+pub enum ArgSort<A, T: CType> {
     #[cfg(feature = "opencl")]
-    CL(opencl::ops::Reduce<A, T>),
-    Host(host::ops::Reduce<A, T>),
+    CL(opencl::ops::ArgSort<A, T>),
+    Host(host::ops::ArgSort<A, T>),
 }
 
-impl_unary!(Reduce<A, T>, T);
+impl_unary!(ArgSort<A, T>, u64);
 
-impl<A, T: CType> From<host::ops::Reduce<A, T>> for Reduce<A, T> {
-    fn from(op: host::ops::Reduce<A, T>) -> Self {
+impl<A, T: CType> From<host::ops::ArgSort<A, T>> for ArgSort<A, T> {
+    fn from(op: host::ops::ArgSort<A, T>) -> Self {
         Self::Host(op)
     }
 }
 
 #[cfg(feature = "opencl")]
-impl<A, T: CType> From<opencl::ops::Reduce<A, T>> for Reduce<A, T> {
-    fn from(op: opencl::ops::Reduce<A, T>) -> Self {
+impl<A, T: CType> From<opencl::ops::ArgSort<A, T>> for ArgSort<A, T> {
+    fn from(op: opencl::ops::ArgSort<A, T>) -> Self {
         Self::CL(op)
     }
 }
@@ -765,8 +2537,15 @@ impl SliceSpec {
         }
     }
 
+    /// Construct a new [`SliceSpec`], or return an error if `range` is out of bounds.
+    /// Axes not specified by `range` are included in their entirety.
+    pub fn try_new(source_shape: &[usize], range: Range) -> Result<Self, Error> {
+        resolve_range(source_shape, range).map(|range| Self::new(source_shape, range))
+    }
+
+    /// Compute the offset in the source array of the given `offset` in this slice.
+    /// This does not allocate and is valid for any `offset` less than [`SliceSpec::size`].
     pub fn source_offset(&self, offset: usize) -> usize {
-        debug_assert!(!self.shape.is_empty());
         debug_assert_eq!(self.shape.len(), self.strides.len());
 
         let mut coord = self
@@ -792,6 +2571,7 @@ impl SliceSpec {
                     i
                 }
                 AxisRange::Of(indices) => indices[coord.next().expect("i")],
+                bound => unreachable!("unresolved axis range {bound:?}"),
             };
 
             offset += i * stride;
@@ -820,6 +2600,14 @@ where
     fn size(&self) -> usize {
         op_dispatch!(self, op, op.size())
     }
+
+    fn name(&self) -> &'static str {
+        op_dispatch!(self, op, op.name())
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        op_dispatch!(self, op, op.inputs())
+    }
 }
 
 impl<A, IT, OT> Enqueue<Platform, OT> for Scalar<A, IT, OT>
@@ -933,6 +2721,133 @@ impl<A, T> From<host::ops::Slice<A, T>> for Slice<A, T> {
     }
 }
 
+pub enum SolveTridiagonal<D, R, T> {
+    #[cfg(feature = "opencl")]
+    CL(opencl::ops::SolveTridiagonal<D, R, T>),
+    Host(host::ops::SolveTridiagonal<D, R, T>),
+}
+
+impl<D, R, T> Op for SolveTridiagonal<D, R, T>
+where
+    D: Access<T>,
+    R: Access<T>,
+    T: Float,
+{
+    fn size(&self) -> usize {
+        op_dispatch!(self, op, op.size())
+    }
+
+    fn name(&self) -> &'static str {
+        op_dispatch!(self, op, op.name())
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        op_dispatch!(self, op, op.inputs())
+    }
+}
+
+impl<D, R, T> Enqueue<Platform, T> for SolveTridiagonal<D, R, T>
+where
+    D: Access<T>,
+    R: Access<T>,
+    T: Float,
+{
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        op_enqueue!(self, T)
+    }
+}
+
+impl<D, R, T> ReadValue<Platform, T> for SolveTridiagonal<D, R, T>
+where
+    D: Access<T>,
+    R: Access<T>,
+    T: Float,
+{
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        op_dispatch!(self, op, op.read_value(offset))
+    }
+}
+
+impl<D, R, T> From<host::ops::SolveTridiagonal<D, R, T>> for SolveTridiagonal<D, R, T> {
+    fn from(op: host::ops::SolveTridiagonal<D, R, T>) -> Self {
+        Self::Host(op)
+    }
+}
+
+#[cfg(feature = "opencl")]
+impl<D, R, T> From<opencl::ops::SolveTridiagonal<D, R, T>> for SolveTridiagonal<D, R, T> {
+    fn from(op: opencl::ops::SolveTridiagonal<D, R, T>) -> Self {
+        Self::CL(op)
+    }
+}
+
+pub enum Ternary<A, B, C, T> {
+    #[cfg(feature = "opencl")]
+    CL(opencl::ops::Ternary<A, B, C, T>),
+    Host(host::ops::Ternary<A, B, C, T>),
+}
+
+impl<A, B, C, T> Op for Ternary<A, B, C, T>
+where
+    A: Access<T>,
+    B: Access<T>,
+    C: Access<T>,
+    T: CType,
+{
+    fn size(&self) -> usize {
+        op_dispatch!(self, op, op.size())
+    }
+
+    fn name(&self) -> &'static str {
+        op_dispatch!(self, op, op.name())
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        op_dispatch!(self, op, op.inputs())
+    }
+}
+
+impl<A, B, C, T> Enqueue<Platform, T> for Ternary<A, B, C, T>
+where
+    A: Access<T>,
+    B: Access<T>,
+    C: Access<T>,
+    T: CType,
+{
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        op_enqueue!(self, T)
+    }
+}
+
+impl<A, B, C, T> ReadValue<Platform, T> for Ternary<A, B, C, T>
+where
+    A: Access<T>,
+    B: Access<T>,
+    C: Access<T>,
+    T: CType,
+{
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        op_dispatch!(self, op, op.read_value(offset))
+    }
+}
+
+impl<A, B, C, T> From<host::ops::Ternary<A, B, C, T>> for Ternary<A, B, C, T> {
+    fn from(op: host::ops::Ternary<A, B, C, T>) -> Self {
+        Self::Host(op)
+    }
+}
+
+#[cfg(feature = "opencl")]
+impl<A, B, C, T> From<opencl::ops::Ternary<A, B, C, T>> for Ternary<A, B, C, T> {
+    fn from(op: opencl::ops::Ternary<A, B, C, T>) -> Self {
+        Self::CL(op)
+    }
+}
+
 pub enum Unary<A, IT, OT> {
     #[cfg(feature = "opencl")]
     CL(opencl::ops::Unary<A, IT, OT>),
@@ -948,6 +2863,14 @@ where
     fn size(&self) -> usize {
         op_dispatch!(self, op, op.size())
     }
+
+    fn name(&self) -> &'static str {
+        op_dispatch!(self, op, op.name())
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        op_dispatch!(self, op, op.inputs())
+    }
 }
 
 impl<A, IT, OT> Enqueue<Platform, OT> for Unary<A, IT, OT>
@@ -987,11 +2910,61 @@ impl<A, IT, OT> From<opencl::ops::Unary<A, IT, OT>> for Unary<A, IT, OT> {
     }
 }
 
+pub enum Upsample<A, T> {
+    #[cfg(feature = "opencl")]
+    CL(opencl::ops::Upsample<A, T>),
+    Host(host::ops::Upsample<A, T>),
+}
+
+impl<A: Access<T>, T: CType> Op for Upsample<A, T> {
+    fn size(&self) -> usize {
+        op_dispatch!(self, op, op.size())
+    }
+
+    fn name(&self) -> &'static str {
+        op_dispatch!(self, op, op.name())
+    }
+
+    fn inputs(&self) -> Vec<OpPlan> {
+        op_dispatch!(self, op, op.inputs())
+    }
+}
+
+impl<A: Access<T>, T: CType> Enqueue<Platform, T> for Upsample<A, T> {
+    type Buffer = Buffer<T>;
+
+    fn enqueue(&self) -> Result<Self::Buffer, Error> {
+        op_enqueue!(self, T)
+    }
+}
+
+impl<A: Access<T>, T: CType> ReadValue<Platform, T> for Upsample<A, T> {
+    fn read_value(&self, offset: usize) -> Result<T, Error> {
+        op_dispatch!(self, op, op.read_value(offset))
+    }
+}
+
+impl<A, T> From<host::ops::Upsample<A, T>> for Upsample<A, T> {
+    fn from(op: host::ops::Upsample<A, T>) -> Self {
+        Self::Host(op)
+    }
+}
+
+#[cfg(feature = "opencl")]
+impl<A, T> From<opencl::ops::Upsample<A, T>> for Upsample<A, T> {
+    fn from(op: opencl::ops::Upsample<A, T>) -> Self {
+        Self::CL(op)
+    }
+}
+
 #[derive(Clone, Eq, PartialEq, Hash)]
 pub struct ViewSpec {
     pub shape: Shape,
     pub strides: Strides,
     pub source_strides: Strides,
+    pub reversed: Axes,
+    pub shift: Shape,
+    pub offset: usize,
 }
 
 impl ViewSpec {
@@ -1002,19 +2975,129 @@ impl ViewSpec {
             shape,
             strides,
             source_strides,
+            reversed: Axes::new(),
+            shift: Shape::new(),
+            offset: 0,
+        }
+    }
+
+    /// Construct a new [`ViewSpec`] which reads the element at `coord` from the source offset
+    /// `offset + sum(coord * strides)`, without checking that this offset is in bounds.
+    pub fn strided(shape: Shape, strides: Strides, offset: usize) -> Self {
+        Self {
+            offset,
+            ..Self::new(shape, strides)
+        }
+    }
+
+    /// Construct a new [`ViewSpec`] to broadcast an array of shape `source_shape` into `shape`.
+    pub fn broadcast(source_shape: &[usize], shape: Shape) -> Self {
+        let source_strides = strides_for(source_shape, source_shape.len()).collect();
+        Self::new(shape, source_strides)
+    }
+
+    /// Construct a new [`ViewSpec`] to broadcast an array of shape `source_shape` into `shape`,
+    /// or return an error if this is not possible.
+    pub fn try_broadcast(source_shape: &[usize], shape: Shape) -> Result<Self, Error> {
+        let valid = source_shape.len() <= shape.len()
+            && source_shape
+                .iter()
+                .rev()
+                .zip(shape.iter().rev())
+                .all(|(source_dim, dim)| source_dim == dim || *source_dim == 1);
+
+        if valid {
+            Ok(Self::broadcast(source_shape, shape))
+        } else {
+            Err(Error::Bounds(format!(
+                "cannot broadcast {source_shape:?} into {shape:?}"
+            )))
+        }
+    }
+
+    /// Construct a new [`ViewSpec`] to permute the axes of an array of shape `source_shape`.
+    pub fn transpose(source_shape: &[usize], axes: &[usize]) -> Self {
+        let strides = strides_for(source_shape, source_shape.len()).collect::<Strides>();
+        let shape = axes.iter().copied().map(|x| source_shape[x]).collect();
+        let source_strides = axes.iter().copied().map(|x| strides[x]).collect();
+        Self::new(shape, source_strides)
+    }
+
+    /// Construct a new [`ViewSpec`] to reverse the given `axes` of an array of shape `source_shape`
+    /// by negating their strides.
+    pub fn reverse(source_shape: &[usize], axes: &[usize]) -> Self {
+        let source_strides = strides_for(source_shape, source_shape.len()).collect::<Strides>();
+
+        let offset = axes
+            .iter()
+            .copied()
+            .map(|x| source_shape[x].saturating_sub(1) * source_strides[x])
+            .sum();
+
+        Self {
+            reversed: Axes::from_slice(axes),
+            offset,
+            ..Self::new(Shape::from_slice(source_shape), source_strides)
+        }
+    }
+
+    /// Construct a new [`ViewSpec`] to roll the given `axis` of an array of shape `source_shape`
+    /// by `shift` elements, so that the element at `i` moves to `(i + shift) % dim`.
+    pub fn roll(source_shape: &[usize], shift: usize, axis: usize) -> Self {
+        let source_strides = strides_for(source_shape, source_shape.len()).collect();
+
+        let shift = source_shape
+            .iter()
+            .enumerate()
+            .map(|(x, dim)| {
+                if x == axis && *dim > 0 {
+                    (dim - shift % dim) % dim
+                } else {
+                    0
+                }
+            })
+            .collect();
+
+        Self {
+            shift,
+            ..Self::new(Shape::from_slice(source_shape), source_strides)
         }
     }
 
+    /// Construct a new [`ViewSpec`] to permute the axes of an array of shape `source_shape`,
+    /// or return an error if `axes` is not a permutation of its axes.
+    pub fn try_transpose(source_shape: &[usize], axes: &[usize]) -> Result<Self, Error> {
+        let ndim = source_shape.len();
+
+        let valid = axes.len() == ndim
+            && axes
+                .iter()
+                .enumerate()
+                .all(|(i, x)| *x < ndim && !axes[..i].contains(x));
+
+        if valid {
+            Ok(Self::transpose(source_shape, axes))
+        } else {
+            Err(Error::Bounds(format!(
+                "invalid permutation {axes:?} for shape {source_shape:?}"
+            )))
+        }
+    }
+
+    /// Compute the offset in the source array of the given `offset` in this view.
+    /// This does not allocate and is valid for any `offset` less than [`ViewSpec::size`].
     pub fn source_offset(&self, offset: usize) -> usize {
         debug_assert!(offset < self.size());
 
-        let source_offset = self
+        let ndim = self.source_strides.len();
+
+        let (forward, backward) = self
             .strides
             .iter()
             .copied()
             .zip(self.shape.iter().copied())
             .rev()
-            .take(self.source_strides.len())
+            .take(ndim)
             .map(|(stride, dim)| {
                 if stride == 0 {
                     0
@@ -1023,10 +3106,23 @@ impl ViewSpec {
                 }
             }) // coord
             .zip(self.source_strides.iter().rev().copied())
-            .map(|(i, source_stride)| i * source_stride)
-            .sum::<usize>();
+            .enumerate()
+            .fold((0, 0), |(forward, backward), (x, (i, source_stride))| {
+                let i = if self.shift.is_empty() {
+                    i
+                } else {
+                    let x_out = self.shape.len() - 1 - x;
+                    (i + self.shift[ndim - 1 - x]) % self.shape[x_out]
+                };
+
+                if self.reversed.contains(&(ndim - 1 - x)) {
+                    (forward, backward + i * source_stride)
+                } else {
+                    (forward + i * source_stride, backward)
+                }
+            });
 
-        source_offset
+        self.offset + forward - backward
     }
 
     pub fn size(&self) -> usize {
@@ -1034,6 +3130,35 @@ impl ViewSpec {
     }
 }
 
+/// The views, if any, through which the inputs of a dual op are broadcast into its output shape
+#[derive(Clone, Default, Eq, PartialEq, Hash)]
+pub struct DualBroadcast {
+    pub left: Option<ViewSpec>,
+    pub right: Option<ViewSpec>,
+}
+
+impl DualBroadcast {
+    /// Return `true` if neither input is broadcast.
+    pub fn is_none(&self) -> bool {
+        self.left.is_none() && self.right.is_none()
+    }
+
+    /// Compute the offsets in the left and right inputs of the given `offset` in the output.
+    pub fn offsets(&self, offset: usize) -> (usize, usize) {
+        let source_offset = |view: &Option<ViewSpec>| match view {
+            Some(view) => view.source_offset(offset),
+            None => offset,
+        };
+
+        (source_offset(&self.left), source_offset(&self.right))
+    }
+
+    /// Compute the size of the output of a dual op whose left input has the given `size`.
+    pub fn size(&self, size: usize) -> usize {
+        self.left.as_ref().map(ViewSpec::size).unwrap_or(size)
+    }
+}
+
 pub enum View<A, T> {
     #[cfg(feature = "opencl")]
     CL(opencl::ops::View<A, T>),
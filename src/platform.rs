@@ -1,11 +1,13 @@
 use std::fmt;
 
-use crate::access::{Access, AccessOp};
+use crate::access::{Access, AccessMut, AccessOp};
 use crate::buffer::{Buffer, BufferConverter, BufferInstance};
 #[cfg(feature = "opencl")]
+use crate::config::{self, PlatformPreference};
+#[cfg(feature = "opencl")]
 use crate::opencl;
 use crate::ops::*;
-use crate::{host, Axes, CType, Error, Float, Range, Shape};
+use crate::{host, Axes, CType, ConvSpec, Error, Float, Interpolation, Range, Shape, Strides};
 
 /// A ha-ndarray platform
 pub trait PlatformInstance: PartialEq + Eq + Clone + Copy + Send + Sync + fmt::Debug {
@@ -38,13 +40,35 @@ pub enum Platform {
     Host(host::Host),
 }
 
+impl Platform {
+    /// Fall back to the host platform if this platform does not support the data type `T`.
+    #[cfg(feature = "opencl")]
+    fn for_dtype<T: CType>(self) -> Self {
+        match self {
+            Self::CL(_) if !opencl::OpenCL::supports::<T>() => {
+                Self::Host(host::Host::Heap(host::Heap))
+            }
+            other => other,
+        }
+    }
+
+    #[cfg(not(feature = "opencl"))]
+    #[allow(clippy::extra_unused_type_parameters)]
+    fn for_dtype<T: CType>(self) -> Self {
+        self
+    }
+}
+
 #[cfg(feature = "opencl")]
 impl PlatformInstance for Platform {
     fn select(size_hint: usize) -> Self {
-        if size_hint < opencl::GPU_MIN_SIZE {
-            Self::Host(host::Host::select(size_hint))
-        } else {
-            Self::CL(opencl::OpenCL)
+        match config::platform() {
+            PlatformPreference::Host => Self::Host(host::Host::select(size_hint)),
+            PlatformPreference::OpenCL => Self::CL(opencl::OpenCL),
+            PlatformPreference::Auto if size_hint < config::gpu_min_size() => {
+                Self::Host(host::Host::select(size_hint))
+            }
+            PlatformPreference::Auto => Self::CL(opencl::OpenCL),
         }
     }
 }
@@ -106,8 +130,15 @@ impl<T: CType> Constant<T> for Platform {
 
 #[cfg(not(feature = "opencl"))]
 impl<T: CType> Construct<T> for Platform {
+    type Eye = Eye<T>;
     type Range = Linear<T>;
 
+    fn eye(self, batch_size: usize, dim: usize) -> Result<AccessOp<Self::Eye, Self>, Error> {
+        match self {
+            Self::Host(host) => host.eye(batch_size, dim).map(AccessOp::wrap),
+        }
+    }
+
     fn range(self, start: T, stop: T, size: usize) -> Result<AccessOp<Self::Range, Self>, Error> {
         match self {
             Self::Host(host) => host.range(start, stop, size).map(AccessOp::wrap),
@@ -117,16 +148,142 @@ impl<T: CType> Construct<T> for Platform {
 
 #[cfg(feature = "opencl")]
 impl<T: CType> Construct<T> for Platform {
+    type Eye = Eye<T>;
     type Range = Linear<T>;
 
+    fn eye(self, batch_size: usize, dim: usize) -> Result<AccessOp<Self::Eye, Self>, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => cl.eye(batch_size, dim).map(AccessOp::wrap),
+            Self::Host(host) => host.eye(batch_size, dim).map(AccessOp::wrap),
+        }
+    }
+
     fn range(self, start: T, stop: T, size: usize) -> Result<AccessOp<Self::Range, Self>, Error> {
-        match self {
+        // the range kernel computes its step in double precision
+        match self.for_dtype::<f64>() {
             Self::CL(cl) => cl.range(start, stop, size).map(AccessOp::wrap),
             Self::Host(host) => host.range(start, stop, size).map(AccessOp::wrap),
         }
     }
 }
 
+#[cfg(not(feature = "opencl"))]
+impl<I, K, T> Convolve<I, K, T> for Platform
+where
+    I: Access<T>,
+    K: Access<T>,
+    T: CType,
+{
+    type Conv = Conv<I, K, T>;
+    type ConvTranspose = ConvTranspose<I, K, T>;
+    type MatchedFilter = MatchedFilter<I, K, T>;
+
+    fn conv(
+        self,
+        input: I,
+        kernel: K,
+        input_shape: [usize; 5],
+        kernel_shape: [usize; 5],
+        spec: ConvSpec<3>,
+    ) -> Result<AccessOp<Self::Conv, Self>, Error> {
+        match self {
+            Self::Host(host) => host
+                .conv(input, kernel, input_shape, kernel_shape, spec)
+                .map(AccessOp::wrap),
+        }
+    }
+
+    fn conv_transpose2d(
+        self,
+        input: I,
+        kernel: K,
+        input_shape: [usize; 4],
+        kernel_shape: [usize; 4],
+        spec: ConvSpec<2>,
+    ) -> Result<AccessOp<Self::ConvTranspose, Self>, Error> {
+        match self {
+            Self::Host(host) => host
+                .conv_transpose2d(input, kernel, input_shape, kernel_shape, spec)
+                .map(AccessOp::wrap),
+        }
+    }
+
+    fn matched_filter(
+        self,
+        input: I,
+        template: K,
+        dims: [usize; 3],
+    ) -> Result<AccessOp<Self::MatchedFilter, Self>, Error> {
+        match self {
+            Self::Host(host) => host
+                .matched_filter(input, template, dims)
+                .map(AccessOp::wrap),
+        }
+    }
+}
+
+#[cfg(feature = "opencl")]
+impl<I, K, T> Convolve<I, K, T> for Platform
+where
+    I: Access<T>,
+    K: Access<T>,
+    T: CType,
+{
+    type Conv = Conv<I, K, T>;
+    type ConvTranspose = ConvTranspose<I, K, T>;
+    type MatchedFilter = MatchedFilter<I, K, T>;
+
+    fn conv(
+        self,
+        input: I,
+        kernel: K,
+        input_shape: [usize; 5],
+        kernel_shape: [usize; 5],
+        spec: ConvSpec<3>,
+    ) -> Result<AccessOp<Self::Conv, Self>, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => cl
+                .conv(input, kernel, input_shape, kernel_shape, spec)
+                .map(AccessOp::wrap),
+            Self::Host(host) => host
+                .conv(input, kernel, input_shape, kernel_shape, spec)
+                .map(AccessOp::wrap),
+        }
+    }
+
+    fn conv_transpose2d(
+        self,
+        input: I,
+        kernel: K,
+        input_shape: [usize; 4],
+        kernel_shape: [usize; 4],
+        spec: ConvSpec<2>,
+    ) -> Result<AccessOp<Self::ConvTranspose, Self>, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => cl
+                .conv_transpose2d(input, kernel, input_shape, kernel_shape, spec)
+                .map(AccessOp::wrap),
+            Self::Host(host) => host
+                .conv_transpose2d(input, kernel, input_shape, kernel_shape, spec)
+                .map(AccessOp::wrap),
+        }
+    }
+
+    fn matched_filter(
+        self,
+        input: I,
+        template: K,
+        dims: [usize; 3],
+    ) -> Result<AccessOp<Self::MatchedFilter, Self>, Error> {
+        match self.for_dtype::<T>().for_dtype::<T::Float>() {
+            Self::CL(cl) => cl.matched_filter(input, template, dims).map(AccessOp::wrap),
+            Self::Host(host) => host
+                .matched_filter(input, template, dims)
+                .map(AccessOp::wrap),
+        }
+    }
+}
+
 #[cfg(not(feature = "opencl"))]
 impl<L, R, T> ElementwiseBoolean<L, R, T> for Platform
 where
@@ -163,21 +320,21 @@ where
     type Op = Dual<L, R, T, u8>;
 
     fn and(self, left: L, right: R) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>() {
             Self::CL(cl) => cl.and(left, right).map(AccessOp::wrap),
             Self::Host(host) => host.and(left, right).map(AccessOp::wrap),
         }
     }
 
     fn or(self, left: L, right: R) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>() {
             Self::CL(cl) => cl.or(left, right).map(AccessOp::wrap),
             Self::Host(host) => host.or(left, right).map(AccessOp::wrap),
         }
     }
 
     fn xor(self, left: L, right: R) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>() {
             Self::CL(cl) => cl.xor(left, right).map(AccessOp::wrap),
             Self::Host(host) => host.xor(left, right).map(AccessOp::wrap),
         }
@@ -210,27 +367,50 @@ impl<A: Access<T>, T: CType> ElementwiseBooleanScalar<A, T> for Platform {
     type Op = Scalar<A, T, u8>;
 
     fn and_scalar(self, left: A, right: T) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>() {
             Self::CL(cl) => cl.and_scalar(left, right).map(AccessOp::wrap),
             Self::Host(host) => host.and_scalar(left, right).map(AccessOp::wrap),
         }
     }
 
     fn or_scalar(self, left: A, right: T) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>() {
             Self::CL(cl) => cl.or_scalar(left, right).map(AccessOp::wrap),
             Self::Host(host) => host.or_scalar(left, right).map(AccessOp::wrap),
         }
     }
 
     fn xor_scalar(self, left: A, right: T) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>() {
             Self::CL(cl) => cl.xor_scalar(left, right).map(AccessOp::wrap),
             Self::Host(host) => host.xor_scalar(left, right).map(AccessOp::wrap),
         }
     }
 }
 
+#[cfg(not(feature = "opencl"))]
+impl<A: Access<IT>, IT: CType, OT: CType> ElementwiseBitcast<A, IT, OT> for Platform {
+    type Op = Bitcast<A, IT, OT>;
+
+    fn bitcast(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self {
+            Self::Host(host) => host.bitcast(access).map(AccessOp::wrap),
+        }
+    }
+}
+
+#[cfg(feature = "opencl")]
+impl<A: Access<IT>, IT: CType, OT: CType> ElementwiseBitcast<A, IT, OT> for Platform {
+    type Op = Bitcast<A, IT, OT>;
+
+    fn bitcast(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self.for_dtype::<IT>().for_dtype::<OT>() {
+            Self::CL(cl) => cl.bitcast(access).map(AccessOp::wrap),
+            Self::Host(host) => host.bitcast(access).map(AccessOp::wrap),
+        }
+    }
+}
+
 #[cfg(not(feature = "opencl"))]
 impl<A: Access<IT>, IT: CType, OT: CType> ElementwiseCast<A, IT, OT> for Platform {
     type Op = Cast<A, IT, OT>;
@@ -247,7 +427,7 @@ impl<A: Access<IT>, IT: CType, OT: CType> ElementwiseCast<A, IT, OT> for Platfor
     type Op = Cast<A, IT, OT>;
 
     fn cast(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<IT>().for_dtype::<OT>() {
             Self::CL(cl) => cl.cast(access).map(AccessOp::wrap),
             Self::Host(host) => host.cast(access).map(AccessOp::wrap),
         }
@@ -310,42 +490,42 @@ where
     type Op = Dual<L, R, T, u8>;
 
     fn eq(self, left: L, right: R) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>() {
             Self::CL(cl) => cl.eq(left, right).map(AccessOp::wrap),
             Self::Host(host) => host.eq(left, right).map(AccessOp::wrap),
         }
     }
 
     fn ge(self, left: L, right: R) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>() {
             Self::CL(cl) => cl.ge(left, right).map(AccessOp::wrap),
             Self::Host(host) => host.ge(left, right).map(AccessOp::wrap),
         }
     }
 
     fn gt(self, left: L, right: R) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>() {
             Self::CL(cl) => cl.gt(left, right).map(AccessOp::wrap),
             Self::Host(host) => host.gt(left, right).map(AccessOp::wrap),
         }
     }
 
     fn le(self, left: L, right: R) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>() {
             Self::CL(cl) => cl.le(left, right).map(AccessOp::wrap),
             Self::Host(host) => host.le(left, right).map(AccessOp::wrap),
         }
     }
 
     fn lt(self, left: L, right: R) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>() {
             Self::CL(cl) => cl.lt(left, right).map(AccessOp::wrap),
             Self::Host(host) => host.lt(left, right).map(AccessOp::wrap),
         }
     }
 
     fn ne(self, left: L, right: R) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>() {
             Self::CL(cl) => cl.ne(left, right).map(AccessOp::wrap),
             Self::Host(host) => host.ne(left, right).map(AccessOp::wrap),
         }
@@ -398,42 +578,42 @@ impl<A: Access<T>, T: CType> ElementwiseScalarCompare<A, T> for Platform {
     type Op = Scalar<A, T, u8>;
 
     fn eq_scalar(self, left: A, right: T) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>() {
             Self::CL(cl) => cl.eq_scalar(left, right).map(AccessOp::wrap),
             Self::Host(host) => host.eq_scalar(left, right).map(AccessOp::wrap),
         }
     }
 
     fn ge_scalar(self, left: A, right: T) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>() {
             Self::CL(cl) => cl.ge_scalar(left, right).map(AccessOp::wrap),
             Self::Host(host) => host.ge_scalar(left, right).map(AccessOp::wrap),
         }
     }
 
     fn gt_scalar(self, left: A, right: T) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>() {
             Self::CL(cl) => cl.gt_scalar(left, right).map(AccessOp::wrap),
             Self::Host(host) => host.gt_scalar(left, right).map(AccessOp::wrap),
         }
     }
 
     fn le_scalar(self, left: A, right: T) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>() {
             Self::CL(cl) => cl.le_scalar(left, right).map(AccessOp::wrap),
             Self::Host(host) => host.le_scalar(left, right).map(AccessOp::wrap),
         }
     }
 
     fn lt_scalar(self, left: A, right: T) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>() {
             Self::CL(cl) => cl.lt_scalar(left, right).map(AccessOp::wrap),
             Self::Host(host) => host.lt_scalar(left, right).map(AccessOp::wrap),
         }
     }
 
     fn ne_scalar(self, left: A, right: T) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>() {
             Self::CL(cl) => cl.ne_scalar(left, right).map(AccessOp::wrap),
             Self::Host(host) => host.ne_scalar(left, right).map(AccessOp::wrap),
         }
@@ -467,6 +647,12 @@ where
         }
     }
 
+    fn logaddexp(self, left: L, right: R) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self {
+            Self::Host(host) => host.logaddexp(left, right).map(AccessOp::wrap),
+        }
+    }
+
     fn mul(self, left: L, right: R) -> Result<AccessOp<Self::Op, Self>, Error> {
         match self {
             Self::Host(host) => host.mul(left, right).map(AccessOp::wrap),
@@ -485,6 +671,12 @@ where
         }
     }
 
+    fn step(self, arg: L, threshold: R) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self {
+            Self::Host(host) => host.step(arg, threshold).map(AccessOp::wrap),
+        }
+    }
+
     fn sub(self, left: L, right: R) -> Result<AccessOp<Self::Op, Self>, Error> {
         match self {
             Self::Host(host) => host.sub(left, right).map(AccessOp::wrap),
@@ -502,49 +694,63 @@ where
     type Op = Dual<L, R, T, T>;
 
     fn add(self, left: L, right: R) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>() {
             Self::CL(cl) => cl.add(left, right).map(AccessOp::wrap),
             Self::Host(host) => host.add(left, right).map(AccessOp::wrap),
         }
     }
 
     fn div(self, left: L, right: R) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>() {
             Self::CL(cl) => cl.div(left, right).map(AccessOp::wrap),
             Self::Host(host) => host.div(left, right).map(AccessOp::wrap),
         }
     }
 
     fn log(self, arg: L, base: R) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>() {
             Self::CL(cl) => cl.log(arg, base).map(AccessOp::wrap),
             Self::Host(host) => host.log(arg, base).map(AccessOp::wrap),
         }
     }
 
+    fn logaddexp(self, left: L, right: R) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self.for_dtype::<T>().for_dtype::<T::Float>() {
+            Self::CL(cl) => cl.logaddexp(left, right).map(AccessOp::wrap),
+            Self::Host(host) => host.logaddexp(left, right).map(AccessOp::wrap),
+        }
+    }
+
     fn mul(self, left: L, right: R) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>() {
             Self::CL(cl) => cl.mul(left, right).map(AccessOp::wrap),
             Self::Host(host) => host.mul(left, right).map(AccessOp::wrap),
         }
     }
 
     fn pow(self, arg: L, exp: R) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>() {
             Self::CL(cl) => cl.pow(arg, exp).map(AccessOp::wrap),
             Self::Host(host) => host.pow(arg, exp).map(AccessOp::wrap),
         }
     }
 
     fn rem(self, left: L, right: R) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>() {
             Self::CL(cl) => cl.rem(left, right).map(AccessOp::wrap),
             Self::Host(host) => host.rem(left, right).map(AccessOp::wrap),
         }
     }
 
+    fn step(self, arg: L, threshold: R) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => cl.step(arg, threshold).map(AccessOp::wrap),
+            Self::Host(host) => host.step(arg, threshold).map(AccessOp::wrap),
+        }
+    }
+
     fn sub(self, left: L, right: R) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>() {
             Self::CL(cl) => cl.sub(left, right).map(AccessOp::wrap),
             Self::Host(host) => host.sub(left, right).map(AccessOp::wrap),
         }
@@ -603,49 +809,49 @@ impl<A: Access<T>, T: CType> ElementwiseScalar<A, T> for Platform {
     type Op = Scalar<A, T, T>;
 
     fn add_scalar(self, left: A, right: T) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>() {
             Self::CL(cl) => cl.add_scalar(left, right).map(AccessOp::wrap),
             Self::Host(host) => host.add_scalar(left, right).map(AccessOp::wrap),
         }
     }
 
     fn div_scalar(self, left: A, right: T) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>() {
             Self::CL(cl) => cl.div_scalar(left, right).map(AccessOp::wrap),
             Self::Host(host) => host.div_scalar(left, right).map(AccessOp::wrap),
         }
     }
 
     fn log_scalar(self, arg: A, base: T) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>() {
             Self::CL(cl) => cl.log_scalar(arg, base).map(AccessOp::wrap),
             Self::Host(host) => host.log_scalar(arg, base).map(AccessOp::wrap),
         }
     }
 
     fn mul_scalar(self, left: A, right: T) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>() {
             Self::CL(cl) => cl.mul_scalar(left, right).map(AccessOp::wrap),
             Self::Host(host) => host.mul_scalar(left, right).map(AccessOp::wrap),
         }
     }
 
     fn pow_scalar(self, arg: A, exp: T) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>() {
             Self::CL(cl) => cl.pow_scalar(arg, exp).map(AccessOp::wrap),
             Self::Host(host) => host.pow_scalar(arg, exp).map(AccessOp::wrap),
         }
     }
 
     fn rem_scalar(self, left: A, right: T) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>() {
             Self::CL(cl) => cl.rem_scalar(left, right).map(AccessOp::wrap),
             Self::Host(host) => host.rem_scalar(left, right).map(AccessOp::wrap),
         }
     }
 
     fn sub_scalar(self, left: A, right: T) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>() {
             Self::CL(cl) => cl.sub_scalar(left, right).map(AccessOp::wrap),
             Self::Host(host) => host.sub_scalar(left, right).map(AccessOp::wrap),
         }
@@ -674,20 +880,94 @@ impl<A: Access<T>, T: Float> ElementwiseNumeric<A, T> for Platform {
     type Op = Unary<A, T, u8>;
 
     fn is_inf(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>() {
             Self::CL(cl) => cl.is_inf(access).map(AccessOp::wrap),
             Self::Host(host) => host.is_inf(access).map(AccessOp::wrap),
         }
     }
 
     fn is_nan(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>() {
             Self::CL(cl) => cl.is_nan(access).map(AccessOp::wrap),
             Self::Host(host) => host.is_nan(access).map(AccessOp::wrap),
         }
     }
 }
 
+#[cfg(not(feature = "opencl"))]
+impl<A, B, C, T> ElementwiseTernary<A, B, C, T> for Platform
+where
+    A: Access<T>,
+    B: Access<T>,
+    C: Access<T>,
+    T: CType,
+{
+    type Op = Ternary<A, B, C, T>;
+
+    fn clamp(self, access: A, min: B, max: C) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self {
+            Self::Host(host) => host.clamp(access, min, max).map(AccessOp::wrap),
+        }
+    }
+
+    fn lerp(self, access: A, other: B, t: C) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self {
+            Self::Host(host) => host.lerp(access, other, t).map(AccessOp::wrap),
+        }
+    }
+
+    fn mul_add(self, access: A, mul: B, add: C) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self {
+            Self::Host(host) => host.mul_add(access, mul, add).map(AccessOp::wrap),
+        }
+    }
+
+    fn smoothstep(self, access: A, edge0: B, edge1: C) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self {
+            Self::Host(host) => host.smoothstep(access, edge0, edge1).map(AccessOp::wrap),
+        }
+    }
+}
+
+#[cfg(feature = "opencl")]
+impl<A, B, C, T> ElementwiseTernary<A, B, C, T> for Platform
+where
+    A: Access<T>,
+    B: Access<T>,
+    C: Access<T>,
+    T: CType,
+{
+    type Op = Ternary<A, B, C, T>;
+
+    fn clamp(self, access: A, min: B, max: C) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => cl.clamp(access, min, max).map(AccessOp::wrap),
+            Self::Host(host) => host.clamp(access, min, max).map(AccessOp::wrap),
+        }
+    }
+
+    fn lerp(self, access: A, other: B, t: C) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => cl.lerp(access, other, t).map(AccessOp::wrap),
+            Self::Host(host) => host.lerp(access, other, t).map(AccessOp::wrap),
+        }
+    }
+
+    fn mul_add(self, access: A, mul: B, add: C) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => cl.mul_add(access, mul, add).map(AccessOp::wrap),
+            Self::Host(host) => host.mul_add(access, mul, add).map(AccessOp::wrap),
+        }
+    }
+
+    fn smoothstep(self, access: A, edge0: B, edge1: C) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => cl.smoothstep(access, edge0, edge1).map(AccessOp::wrap),
+            Self::Host(host) => host.smoothstep(access, edge0, edge1).map(AccessOp::wrap),
+        }
+    }
+}
+
 #[cfg(not(feature = "opencl"))]
 impl<A: Access<T>, T: CType> ElementwiseTrig<A, T> for Platform {
     type Op = Unary<A, T, T::Float>;
@@ -745,6 +1025,18 @@ impl<A: Access<T>, T: CType> ElementwiseTrig<A, T> for Platform {
             Self::Host(host) => host.tanh(access).map(AccessOp::wrap),
         }
     }
+
+    fn to_degrees(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self {
+            Self::Host(host) => host.to_degrees(access).map(AccessOp::wrap),
+        }
+    }
+
+    fn to_radians(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self {
+            Self::Host(host) => host.to_radians(access).map(AccessOp::wrap),
+        }
+    }
 }
 
 #[cfg(feature = "opencl")]
@@ -752,67 +1044,127 @@ impl<A: Access<T>, T: CType> ElementwiseTrig<A, T> for Platform {
     type Op = Unary<A, T, T::Float>;
 
     fn sin(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>().for_dtype::<T::Float>() {
             Self::CL(cl) => cl.sin(access).map(AccessOp::wrap),
             Self::Host(host) => host.sin(access).map(AccessOp::wrap),
         }
     }
 
     fn asin(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>().for_dtype::<T::Float>() {
             Self::CL(cl) => cl.asin(access).map(AccessOp::wrap),
             Self::Host(host) => host.asin(access).map(AccessOp::wrap),
         }
     }
 
     fn sinh(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>().for_dtype::<T::Float>() {
             Self::CL(cl) => cl.sinh(access).map(AccessOp::wrap),
             Self::Host(host) => host.sinh(access).map(AccessOp::wrap),
         }
     }
 
     fn cos(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>().for_dtype::<T::Float>() {
             Self::CL(cl) => cl.cos(access).map(AccessOp::wrap),
             Self::Host(host) => host.cos(access).map(AccessOp::wrap),
         }
     }
 
     fn acos(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>().for_dtype::<T::Float>() {
             Self::CL(cl) => cl.acos(access).map(AccessOp::wrap),
             Self::Host(host) => host.acos(access).map(AccessOp::wrap),
         }
     }
 
     fn cosh(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>().for_dtype::<T::Float>() {
             Self::CL(cl) => cl.cosh(access).map(AccessOp::wrap),
             Self::Host(host) => host.cosh(access).map(AccessOp::wrap),
         }
     }
 
     fn tan(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>().for_dtype::<T::Float>() {
             Self::CL(cl) => cl.tan(access).map(AccessOp::wrap),
             Self::Host(host) => host.tan(access).map(AccessOp::wrap),
         }
     }
 
     fn atan(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>().for_dtype::<T::Float>() {
             Self::CL(cl) => cl.atan(access).map(AccessOp::wrap),
             Self::Host(host) => host.atan(access).map(AccessOp::wrap),
         }
     }
 
     fn tanh(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>().for_dtype::<T::Float>() {
             Self::CL(cl) => cl.tanh(access).map(AccessOp::wrap),
             Self::Host(host) => host.tanh(access).map(AccessOp::wrap),
         }
     }
+
+    fn to_degrees(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self.for_dtype::<T>().for_dtype::<T::Float>() {
+            Self::CL(cl) => cl.to_degrees(access).map(AccessOp::wrap),
+            Self::Host(host) => host.to_degrees(access).map(AccessOp::wrap),
+        }
+    }
+
+    fn to_radians(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self.for_dtype::<T>().for_dtype::<T::Float>() {
+            Self::CL(cl) => cl.to_radians(access).map(AccessOp::wrap),
+            Self::Host(host) => host.to_radians(access).map(AccessOp::wrap),
+        }
+    }
+}
+
+#[cfg(not(feature = "opencl"))]
+impl<L, R, T> ElementwiseTrigDual<L, R, T> for Platform
+where
+    L: Access<T>,
+    R: Access<T>,
+    T: CType,
+{
+    type Op = Dual<L, R, T, T::Float>;
+
+    fn atan2(self, y: L, x: R) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self {
+            Self::Host(host) => host.atan2(y, x).map(AccessOp::wrap),
+        }
+    }
+
+    fn hypot(self, left: L, right: R) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self {
+            Self::Host(host) => host.hypot(left, right).map(AccessOp::wrap),
+        }
+    }
+}
+
+#[cfg(feature = "opencl")]
+impl<L, R, T> ElementwiseTrigDual<L, R, T> for Platform
+where
+    L: Access<T>,
+    R: Access<T>,
+    T: CType,
+{
+    type Op = Dual<L, R, T, T::Float>;
+
+    fn atan2(self, y: L, x: R) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self.for_dtype::<T>().for_dtype::<T::Float>() {
+            Self::CL(cl) => cl.atan2(y, x).map(AccessOp::wrap),
+            Self::Host(host) => host.atan2(y, x).map(AccessOp::wrap),
+        }
+    }
+
+    fn hypot(self, left: L, right: R) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self.for_dtype::<T>().for_dtype::<T::Float>() {
+            Self::CL(cl) => cl.hypot(left, right).map(AccessOp::wrap),
+            Self::Host(host) => host.hypot(left, right).map(AccessOp::wrap),
+        }
+    }
 }
 
 #[cfg(not(feature = "opencl"))]
@@ -837,11 +1189,23 @@ impl<A: Access<T>, T: CType> ElementwiseUnary<A, T> for Platform {
         }
     }
 
+    fn reciprocal_approx(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self {
+            Self::Host(host) => host.reciprocal_approx(access).map(AccessOp::wrap),
+        }
+    }
+
     fn round(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
         match self {
             Self::Host(host) => host.round(access).map(AccessOp::wrap),
         }
     }
+
+    fn softplus(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self {
+            Self::Host(host) => host.softplus(access).map(AccessOp::wrap),
+        }
+    }
 }
 
 #[cfg(feature = "opencl")]
@@ -849,32 +1213,46 @@ impl<A: Access<T>, T: CType> ElementwiseUnary<A, T> for Platform {
     type Op = Unary<A, T, T>;
 
     fn abs(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>().for_dtype::<T::Float>() {
             Self::CL(cl) => cl.abs(access).map(AccessOp::wrap),
             Self::Host(host) => host.abs(access).map(AccessOp::wrap),
         }
     }
 
     fn exp(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>().for_dtype::<T::Float>() {
             Self::CL(cl) => cl.exp(access).map(AccessOp::wrap),
             Self::Host(host) => host.exp(access).map(AccessOp::wrap),
         }
     }
 
     fn ln(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>().for_dtype::<T::Float>() {
             Self::CL(cl) => cl.ln(access).map(AccessOp::wrap),
             Self::Host(host) => host.ln(access).map(AccessOp::wrap),
         }
     }
 
+    fn reciprocal_approx(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => cl.reciprocal_approx(access).map(AccessOp::wrap),
+            Self::Host(host) => host.reciprocal_approx(access).map(AccessOp::wrap),
+        }
+    }
+
     fn round(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>().for_dtype::<T::Float>() {
             Self::CL(cl) => cl.round(access).map(AccessOp::wrap),
             Self::Host(host) => host.round(access).map(AccessOp::wrap),
         }
     }
+
+    fn softplus(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self.for_dtype::<T>().for_dtype::<T::Float>() {
+            Self::CL(cl) => cl.softplus(access).map(AccessOp::wrap),
+            Self::Host(host) => host.softplus(access).map(AccessOp::wrap),
+        }
+    }
 }
 
 #[cfg(not(feature = "opencl"))]
@@ -893,7 +1271,7 @@ impl<A: Access<T>, T: CType> ElementwiseUnaryBoolean<A, T> for Platform {
     type Op = Unary<A, T, u8>;
 
     fn not(self, access: A) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>() {
             Self::CL(cl) => cl.not(access).map(AccessOp::wrap),
             Self::Host(host) => host.not(access).map(AccessOp::wrap),
         }
@@ -901,23 +1279,92 @@ impl<A: Access<T>, T: CType> ElementwiseUnaryBoolean<A, T> for Platform {
 }
 
 #[cfg(not(feature = "opencl"))]
-impl<A, L, R, T> GatherCond<A, L, R, T> for Platform
+impl<A, I, T> GatherAxis<A, I, T> for Platform
 where
-    A: Access<u8>,
-    L: Access<T>,
-    R: Access<T>,
+    A: Access<T>,
+    I: Access<u64>,
     T: CType,
 {
-    type Op = Cond<A, L, R, T>;
+    type Op = Gather<A, I, T>;
 
-    fn cond(self, cond: A, then: L, or_else: R) -> Result<AccessOp<Self::Op, Self>, Error> {
+    fn gather(
+        self,
+        access: A,
+        indices: I,
+        dims: [usize; 3],
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
         match self {
-            Self::Host(host) => host.cond(cond, then, or_else).map(AccessOp::wrap),
+            Self::Host(host) => host.gather(access, indices, dims).map(AccessOp::wrap),
+        }
+    }
+}
+
+#[cfg(feature = "opencl")]
+impl<A, I, T> GatherAxis<A, I, T> for Platform
+where
+    A: Access<T>,
+    I: Access<u64>,
+    T: CType,
+{
+    type Op = Gather<A, I, T>;
+
+    fn gather(
+        self,
+        access: A,
+        indices: I,
+        dims: [usize; 3],
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => cl.gather(access, indices, dims).map(AccessOp::wrap),
+            Self::Host(host) => host.gather(access, indices, dims).map(AccessOp::wrap),
+        }
+    }
+}
+
+#[cfg(not(feature = "opencl"))]
+impl<A, P, T> GatherMask<A, P, T> for Platform
+where
+    A: Access<T>,
+    P: Access<u64>,
+    T: CType,
+{
+    type Op = Compact<A, P, T>;
+
+    fn compact(
+        self,
+        access: A,
+        positions: P,
+        size: usize,
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self {
+            Self::Host(host) => host.compact(access, positions, size).map(AccessOp::wrap),
         }
     }
 }
 
 #[cfg(feature = "opencl")]
+impl<A, P, T> GatherMask<A, P, T> for Platform
+where
+    A: Access<T>,
+    P: Access<u64>,
+    T: CType,
+{
+    type Op = Compact<A, P, T>;
+
+    fn compact(
+        self,
+        access: A,
+        positions: P,
+        size: usize,
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => cl.compact(access, positions, size).map(AccessOp::wrap),
+            Self::Host(host) => host.compact(access, positions, size).map(AccessOp::wrap),
+        }
+    }
+}
+
+#[cfg(not(feature = "opencl"))]
 impl<A, L, R, T> GatherCond<A, L, R, T> for Platform
 where
     A: Access<u8>,
@@ -929,12 +1376,52 @@ where
 
     fn cond(self, cond: A, then: L, or_else: R) -> Result<AccessOp<Self::Op, Self>, Error> {
         match self {
+            Self::Host(host) => host.cond(cond, then, or_else).map(AccessOp::wrap),
+        }
+    }
+}
+
+#[cfg(not(feature = "opencl"))]
+impl<A: Access<u8>, T: CType> GatherCondScalar<A, T> for Platform {
+    type Op = CondScalar<A, T>;
+
+    fn cond_scalar(self, cond: A, then: T, or_else: T) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self {
+            Self::Host(host) => host.cond_scalar(cond, then, or_else).map(AccessOp::wrap),
+        }
+    }
+}
+
+#[cfg(feature = "opencl")]
+impl<A, L, R, T> GatherCond<A, L, R, T> for Platform
+where
+    A: Access<u8>,
+    L: Access<T>,
+    R: Access<T>,
+    T: CType,
+{
+    type Op = Cond<A, L, R, T>;
+
+    fn cond(self, cond: A, then: L, or_else: R) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self.for_dtype::<T>() {
             Self::CL(cl) => cl.cond(cond, then, or_else).map(AccessOp::wrap),
             Self::Host(host) => host.cond(cond, then, or_else).map(AccessOp::wrap),
         }
     }
 }
 
+#[cfg(feature = "opencl")]
+impl<A: Access<u8>, T: CType> GatherCondScalar<A, T> for Platform {
+    type Op = CondScalar<A, T>;
+
+    fn cond_scalar(self, cond: A, then: T, or_else: T) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => cl.cond_scalar(cond, then, or_else).map(AccessOp::wrap),
+            Self::Host(host) => host.cond_scalar(cond, then, or_else).map(AccessOp::wrap),
+        }
+    }
+}
+
 #[cfg(not(feature = "opencl"))]
 impl<L, R, T> LinAlgDual<L, R, T> for Platform
 where
@@ -954,247 +1441,1210 @@ where
             Self::Host(host) => host.matmul(left, right, dims).map(AccessOp::wrap),
         }
     }
+
+    fn matmul_strided(
+        self,
+        left: L,
+        right: R,
+        dims: [usize; 4],
+        strides: [usize; 2],
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self {
+            Self::Host(host) => host
+                .matmul_strided(left, right, dims, strides)
+                .map(AccessOp::wrap),
+        }
+    }
 }
 
 #[cfg(feature = "opencl")]
 impl<L, R, T> LinAlgDual<L, R, T> for Platform
 where
-    L: Access<T>,
-    R: Access<T>,
+    L: Access<T>,
+    R: Access<T>,
+    T: CType,
+{
+    type Op = MatMul<L, R, T>;
+
+    fn matmul(
+        self,
+        left: L,
+        right: R,
+        dims: [usize; 4],
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => cl.matmul(left, right, dims).map(AccessOp::wrap),
+            Self::Host(host) => host.matmul(left, right, dims).map(AccessOp::wrap),
+        }
+    }
+
+    fn matmul_strided(
+        self,
+        left: L,
+        right: R,
+        dims: [usize; 4],
+        strides: [usize; 2],
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => cl
+                .matmul_strided(left, right, dims, strides)
+                .map(AccessOp::wrap),
+            Self::Host(host) => host
+                .matmul_strided(left, right, dims, strides)
+                .map(AccessOp::wrap),
+        }
+    }
+}
+
+#[cfg(not(feature = "opencl"))]
+impl<D, R, T> LinAlgSolve<D, R, T> for Platform
+where
+    D: Access<T>,
+    R: Access<T>,
+    T: Float,
+{
+    type Op = SolveTridiagonal<D, R, T>;
+
+    fn solve_tridiagonal(
+        self,
+        diagonals: D,
+        rhs: R,
+        batch_size: usize,
+        dim: usize,
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self {
+            Self::Host(host) => host
+                .solve_tridiagonal(diagonals, rhs, batch_size, dim)
+                .map(AccessOp::wrap),
+        }
+    }
+}
+
+#[cfg(feature = "opencl")]
+impl<D, R, T> LinAlgSolve<D, R, T> for Platform
+where
+    D: Access<T>,
+    R: Access<T>,
+    T: Float,
+{
+    type Op = SolveTridiagonal<D, R, T>;
+
+    fn solve_tridiagonal(
+        self,
+        diagonals: D,
+        rhs: R,
+        batch_size: usize,
+        dim: usize,
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => cl
+                .solve_tridiagonal(diagonals, rhs, batch_size, dim)
+                .map(AccessOp::wrap),
+            Self::Host(host) => host
+                .solve_tridiagonal(diagonals, rhs, batch_size, dim)
+                .map(AccessOp::wrap),
+        }
+    }
+}
+
+#[cfg(not(feature = "opencl"))]
+impl<I, V, D, T> LinAlgSparse<I, V, D, T> for Platform
+where
+    I: Access<u64>,
+    V: Access<T>,
+    D: Access<T>,
+    T: CType,
+{
+    type Op = CsrMatMul<I, V, D, T>;
+
+    fn csr_matmul(
+        self,
+        offsets: I,
+        indices: I,
+        values: V,
+        dense: D,
+        dims: [usize; 3],
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self {
+            Self::Host(host) => host
+                .csr_matmul(offsets, indices, values, dense, dims)
+                .map(AccessOp::wrap),
+        }
+    }
+}
+
+#[cfg(feature = "opencl")]
+impl<I, V, D, T> LinAlgSparse<I, V, D, T> for Platform
+where
+    I: Access<u64>,
+    V: Access<T>,
+    D: Access<T>,
+    T: CType,
+{
+    type Op = CsrMatMul<I, V, D, T>;
+
+    fn csr_matmul(
+        self,
+        offsets: I,
+        indices: I,
+        values: V,
+        dense: D,
+        dims: [usize; 3],
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => cl
+                .csr_matmul(offsets, indices, values, dense, dims)
+                .map(AccessOp::wrap),
+            Self::Host(host) => host
+                .csr_matmul(offsets, indices, values, dense, dims)
+                .map(AccessOp::wrap),
+        }
+    }
+}
+
+#[cfg(not(feature = "opencl"))]
+impl<A: Access<T>, T: CType> LinAlgUnary<A, T> for Platform {
+    type Op = MatDiag<A, T>;
+    type DiagFrom = DiagFrom<A, T>;
+    type Inverse = Inverse<A, T>;
+    type Decompose = Decompose<A, T>;
+    type Trace = MatTrace<A, T>;
+
+    fn diag(
+        self,
+        access: A,
+        batch_size: usize,
+        dim: usize,
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self {
+            Self::Host(host) => host.diag(access, batch_size, dim).map(AccessOp::wrap),
+        }
+    }
+
+    fn diag_from(
+        self,
+        access: A,
+        batch_size: usize,
+        dim: usize,
+    ) -> Result<AccessOp<Self::DiagFrom, Self>, Error> {
+        match self {
+            Self::Host(host) => host.diag_from(access, batch_size, dim).map(AccessOp::wrap),
+        }
+    }
+
+    fn inv(
+        self,
+        access: A,
+        batch_size: usize,
+        dim: usize,
+    ) -> Result<AccessOp<Self::Inverse, Self>, Error> {
+        match self {
+            Self::Host(host) => host.inv(access, batch_size, dim).map(AccessOp::wrap),
+        }
+    }
+
+    fn trace(
+        self,
+        access: A,
+        batch_size: usize,
+        dim: usize,
+    ) -> Result<AccessOp<Self::Trace, Self>, Error> {
+        match self {
+            Self::Host(host) => host.trace(access, batch_size, dim).map(AccessOp::wrap),
+        }
+    }
+
+    fn decompose(
+        self,
+        access: A,
+        factor: Factor,
+        batch_size: usize,
+        dims: [usize; 2],
+        factorization: Factorization<T>,
+    ) -> Result<AccessOp<Self::Decompose, Self>, Error> {
+        match self {
+            Self::Host(host) => host
+                .decompose(access, factor, batch_size, dims, factorization)
+                .map(AccessOp::wrap),
+        }
+    }
+}
+
+#[cfg(feature = "opencl")]
+impl<A: Access<T>, T: CType> LinAlgUnary<A, T> for Platform {
+    type Op = MatDiag<A, T>;
+    type DiagFrom = DiagFrom<A, T>;
+    type Inverse = Inverse<A, T>;
+    type Decompose = Decompose<A, T>;
+    type Trace = MatTrace<A, T>;
+
+    fn diag(
+        self,
+        access: A,
+        batch_size: usize,
+        dim: usize,
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => cl.diag(access, batch_size, dim).map(AccessOp::wrap),
+            Self::Host(host) => host.diag(access, batch_size, dim).map(AccessOp::wrap),
+        }
+    }
+
+    fn diag_from(
+        self,
+        access: A,
+        batch_size: usize,
+        dim: usize,
+    ) -> Result<AccessOp<Self::DiagFrom, Self>, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => cl.diag_from(access, batch_size, dim).map(AccessOp::wrap),
+            Self::Host(host) => host.diag_from(access, batch_size, dim).map(AccessOp::wrap),
+        }
+    }
+
+    fn inv(
+        self,
+        access: A,
+        batch_size: usize,
+        dim: usize,
+    ) -> Result<AccessOp<Self::Inverse, Self>, Error> {
+        match self.for_dtype::<T>().for_dtype::<T::Float>() {
+            Self::CL(cl) => cl.inv(access, batch_size, dim).map(AccessOp::wrap),
+            Self::Host(host) => host.inv(access, batch_size, dim).map(AccessOp::wrap),
+        }
+    }
+
+    fn trace(
+        self,
+        access: A,
+        batch_size: usize,
+        dim: usize,
+    ) -> Result<AccessOp<Self::Trace, Self>, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => cl.trace(access, batch_size, dim).map(AccessOp::wrap),
+            Self::Host(host) => host.trace(access, batch_size, dim).map(AccessOp::wrap),
+        }
+    }
+
+    fn decompose(
+        self,
+        access: A,
+        factor: Factor,
+        batch_size: usize,
+        dims: [usize; 2],
+        factorization: Factorization<T>,
+    ) -> Result<AccessOp<Self::Decompose, Self>, Error> {
+        match self.for_dtype::<T>().for_dtype::<T::Float>() {
+            Self::CL(cl) => cl
+                .decompose(access, factor, batch_size, dims, factorization)
+                .map(AccessOp::wrap),
+            Self::Host(host) => host
+                .decompose(access, factor, batch_size, dims, factorization)
+                .map(AccessOp::wrap),
+        }
+    }
+}
+
+#[cfg(not(feature = "opencl"))]
+impl<A, S, B, T> Normalize<A, S, B, T> for Platform
+where
+    A: Access<T>,
+    S: Access<T>,
+    B: Access<T>,
+    T: Float,
+{
+    type Op = LayerNorm<A, S, B, T>;
+
+    fn layer_norm(
+        self,
+        access: A,
+        scale: S,
+        bias: B,
+        stride: usize,
+        eps: T,
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self {
+            Self::Host(host) => host
+                .layer_norm(access, scale, bias, stride, eps)
+                .map(AccessOp::wrap),
+        }
+    }
+}
+
+#[cfg(feature = "opencl")]
+impl<A, S, B, T> Normalize<A, S, B, T> for Platform
+where
+    A: Access<T>,
+    S: Access<T>,
+    B: Access<T>,
+    T: Float,
+{
+    type Op = LayerNorm<A, S, B, T>;
+
+    fn layer_norm(
+        self,
+        access: A,
+        scale: S,
+        bias: B,
+        stride: usize,
+        eps: T,
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => cl
+                .layer_norm(access, scale, bias, stride, eps)
+                .map(AccessOp::wrap),
+            Self::Host(host) => host
+                .layer_norm(access, scale, bias, stride, eps)
+                .map(AccessOp::wrap),
+        }
+    }
+}
+
+#[cfg(not(feature = "opencl"))]
+impl Random for Platform {
+    type Normal = RandomNormal;
+    type Uniform = RandomUniform;
+
+    fn random_normal(self, size: usize) -> Result<AccessOp<Self::Normal, Self>, Error> {
+        match self {
+            Self::Host(host) => host.random_normal(size).map(AccessOp::wrap),
+        }
+    }
+
+    fn random_uniform(self, size: usize) -> Result<AccessOp<Self::Uniform, Self>, Error> {
+        match self {
+            Self::Host(host) => host.random_uniform(size).map(AccessOp::wrap),
+        }
+    }
+}
+
+#[cfg(feature = "opencl")]
+impl Random for Platform {
+    type Normal = RandomNormal;
+    type Uniform = RandomUniform;
+
+    fn random_normal(self, size: usize) -> Result<AccessOp<Self::Normal, Self>, Error> {
+        match self {
+            Self::CL(cl) => cl.random_normal(size).map(AccessOp::wrap),
+            Self::Host(host) => host.random_normal(size).map(AccessOp::wrap),
+        }
+    }
+
+    fn random_uniform(self, size: usize) -> Result<AccessOp<Self::Uniform, Self>, Error> {
+        match self {
+            Self::CL(cl) => cl.random_uniform(size).map(AccessOp::wrap),
+            Self::Host(host) => host.random_uniform(size).map(AccessOp::wrap),
+        }
+    }
+}
+
+#[cfg(not(feature = "opencl"))]
+impl<A: Access<T>, T: CType> Resample<A, T> for Platform {
+    type Op = Upsample<A, T>;
+
+    fn upsample(
+        self,
+        access: A,
+        dims: [usize; 3],
+        scale: [usize; 2],
+        mode: Interpolation,
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self {
+            Self::Host(host) => host.upsample(access, dims, scale, mode).map(AccessOp::wrap),
+        }
+    }
+}
+
+#[cfg(feature = "opencl")]
+impl<A: Access<T>, T: CType> Resample<A, T> for Platform {
+    type Op = Upsample<A, T>;
+
+    fn upsample(
+        self,
+        access: A,
+        dims: [usize; 3],
+        scale: [usize; 2],
+        mode: Interpolation,
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => cl.upsample(access, dims, scale, mode).map(AccessOp::wrap),
+            Self::Host(host) => host.upsample(access, dims, scale, mode).map(AccessOp::wrap),
+        }
+    }
+}
+
+#[cfg(not(feature = "opencl"))]
+impl<A: Access<T>, T: CType> Accumulate<A, T> for Platform {
+    fn axpy<Y: AccessMut<T>>(self, y: &mut Y, alpha: T, x: A) -> Result<(), Error> {
+        match self {
+            Self::Host(host) => host.axpy(y, alpha, x),
+        }
+    }
+}
+
+#[cfg(feature = "opencl")]
+impl<A: Access<T>, T: CType> Accumulate<A, T> for Platform {
+    fn axpy<Y: AccessMut<T>>(self, y: &mut Y, alpha: T, x: A) -> Result<(), Error> {
+        // an in-place update must be executed wherever the data to update reside
+        match self.for_dtype::<T>() {
+            Self::CL(cl) if y.cl_buffer().is_ok() => cl.axpy(y, alpha, x),
+            Self::CL(_) => host::Host::select(y.size()).axpy(y, alpha, x),
+            Self::Host(host) => host.axpy(y, alpha, x),
+        }
+    }
+}
+
+#[cfg(not(feature = "opencl"))]
+impl<I, A, T> ScatterAxis<I, A, T> for Platform
+where
+    I: Access<u64>,
+    A: Access<T>,
+    T: CType,
+{
+    fn scatter<Y: AccessMut<T>>(
+        self,
+        y: &mut Y,
+        indices: I,
+        values: A,
+        dims: [usize; 3],
+    ) -> Result<(), Error> {
+        match self {
+            Self::Host(host) => host.scatter(y, indices, values, dims),
+        }
+    }
+
+    fn scatter_add<Y: AccessMut<T>>(
+        self,
+        y: &mut Y,
+        indices: I,
+        values: A,
+        dims: [usize; 3],
+    ) -> Result<(), Error> {
+        match self {
+            Self::Host(host) => host.scatter_add(y, indices, values, dims),
+        }
+    }
+}
+
+#[cfg(feature = "opencl")]
+impl<I, A, T> ScatterAxis<I, A, T> for Platform
+where
+    I: Access<u64>,
+    A: Access<T>,
+    T: CType,
+{
+    fn scatter<Y: AccessMut<T>>(
+        self,
+        y: &mut Y,
+        indices: I,
+        values: A,
+        dims: [usize; 3],
+    ) -> Result<(), Error> {
+        // an in-place update must be executed wherever the data to update reside
+        match self.for_dtype::<T>() {
+            Self::CL(cl) if y.cl_buffer().is_ok() => cl.scatter(y, indices, values, dims),
+            Self::CL(_) => host::Host::select(y.size()).scatter(y, indices, values, dims),
+            Self::Host(host) => host.scatter(y, indices, values, dims),
+        }
+    }
+
+    fn scatter_add<Y: AccessMut<T>>(
+        self,
+        y: &mut Y,
+        indices: I,
+        values: A,
+        dims: [usize; 3],
+    ) -> Result<(), Error> {
+        // OpenCL only supports atomic operations on 32- and 64-bit types
+        let atomic = matches!(std::mem::size_of::<T>(), 4 | 8);
+
+        match self.for_dtype::<T>() {
+            Self::CL(cl) if atomic && y.cl_buffer().is_ok() => {
+                cl.scatter_add(y, indices, values, dims)
+            }
+            Self::CL(_) => host::Host::select(y.size()).scatter_add(y, indices, values, dims),
+            Self::Host(host) => host.scatter_add(y, indices, values, dims),
+        }
+    }
+}
+
+#[cfg(not(feature = "opencl"))]
+impl<A: Access<T>, T: CType> ReduceAll<A, T> for Platform {
+    fn all(self, access: A) -> Result<bool, Error> {
+        match self {
+            Self::Host(host) => host.all(access),
+        }
+    }
+
+    fn any(self, access: A) -> Result<bool, Error> {
+        match self {
+            Self::Host(host) => host.any(access),
+        }
+    }
+
+    fn max(self, access: A) -> Result<T, Error> {
+        match self {
+            Self::Host(host) => ReduceAll::max(host, access),
+        }
+    }
+
+    fn min(self, access: A) -> Result<T, Error> {
+        match self {
+            Self::Host(host) => ReduceAll::min(host, access),
+        }
+    }
+
+    fn product(self, access: A) -> Result<T, Error> {
+        match self {
+            Self::Host(host) => ReduceAll::product(host, access),
+        }
+    }
+
+    fn sum(self, access: A) -> Result<T, Error> {
+        match self {
+            Self::Host(host) => ReduceAll::sum(host, access),
+        }
+    }
+}
+
+#[cfg(feature = "opencl")]
+impl<A, T> ReduceAll<A, T> for Platform
+where
+    A: Access<T>,
+    T: CType,
+{
+    fn all(self, access: A) -> Result<bool, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => cl.all(access),
+            Self::Host(host) => host.all(access),
+        }
+    }
+
+    fn any(self, access: A) -> Result<bool, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => cl.any(access),
+            Self::Host(host) => host.any(access),
+        }
+    }
+
+    fn max(self, access: A) -> Result<T, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => ReduceAll::max(cl, access),
+            Self::Host(host) => ReduceAll::max(host, access),
+        }
+    }
+
+    fn min(self, access: A) -> Result<T, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => ReduceAll::min(cl, access),
+            Self::Host(host) => ReduceAll::min(host, access),
+        }
+    }
+
+    fn product(self, access: A) -> Result<T, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => ReduceAll::product(cl, access),
+            Self::Host(host) => ReduceAll::product(host, access),
+        }
+    }
+
+    fn sum(self, access: A) -> Result<T, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => ReduceAll::sum(cl, access),
+            Self::Host(host) => ReduceAll::sum(host, access),
+        }
+    }
+}
+
+#[cfg(not(feature = "opencl"))]
+impl<A: Access<T>, T: CType> ReduceAxes<A, T> for Platform {
+    type Op = Reduce<A, T>;
+    type Moment = ReduceMoment<A, T>;
+    type Arg = ReduceArg<A, T>;
+
+    fn max(self, access: A, stride: usize) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self {
+            Self::Host(host) => ReduceAxes::max(host, access, stride).map(AccessOp::wrap),
+        }
+    }
+
+    fn min(self, access: A, stride: usize) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self {
+            Self::Host(host) => ReduceAxes::min(host, access, stride).map(AccessOp::wrap),
+        }
+    }
+
+    fn product(self, access: A, stride: usize) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self {
+            Self::Host(host) => ReduceAxes::product(host, access, stride).map(AccessOp::wrap),
+        }
+    }
+
+    fn sum(self, access: A, stride: usize) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self {
+            Self::Host(host) => ReduceAxes::sum(host, access, stride).map(AccessOp::wrap),
+        }
+    }
+
+    fn mean(self, access: A, stride: usize) -> Result<AccessOp<Self::Moment, Self>, Error> {
+        match self {
+            Self::Host(host) => ReduceAxes::mean(host, access, stride).map(AccessOp::wrap),
+        }
+    }
+
+    fn var(self, access: A, stride: usize) -> Result<AccessOp<Self::Moment, Self>, Error> {
+        match self {
+            Self::Host(host) => ReduceAxes::var(host, access, stride).map(AccessOp::wrap),
+        }
+    }
+
+    fn std(self, access: A, stride: usize) -> Result<AccessOp<Self::Moment, Self>, Error> {
+        match self {
+            Self::Host(host) => ReduceAxes::std(host, access, stride).map(AccessOp::wrap),
+        }
+    }
+
+    fn argmax(self, access: A, stride: usize) -> Result<AccessOp<Self::Arg, Self>, Error> {
+        match self {
+            Self::Host(host) => ReduceAxes::argmax(host, access, stride).map(AccessOp::wrap),
+        }
+    }
+
+    fn argmin(self, access: A, stride: usize) -> Result<AccessOp<Self::Arg, Self>, Error> {
+        match self {
+            Self::Host(host) => ReduceAxes::argmin(host, access, stride).map(AccessOp::wrap),
+        }
+    }
+
+    fn max_ignore_nan(self, access: A, stride: usize) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self {
+            Self::Host(host) => {
+                ReduceAxes::max_ignore_nan(host, access, stride).map(AccessOp::wrap)
+            }
+        }
+    }
+
+    fn min_ignore_nan(self, access: A, stride: usize) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self {
+            Self::Host(host) => {
+                ReduceAxes::min_ignore_nan(host, access, stride).map(AccessOp::wrap)
+            }
+        }
+    }
+
+    fn product_ignore_nan(
+        self,
+        access: A,
+        stride: usize,
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self {
+            Self::Host(host) => {
+                ReduceAxes::product_ignore_nan(host, access, stride).map(AccessOp::wrap)
+            }
+        }
+    }
+
+    fn sum_ignore_nan(self, access: A, stride: usize) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self {
+            Self::Host(host) => {
+                ReduceAxes::sum_ignore_nan(host, access, stride).map(AccessOp::wrap)
+            }
+        }
+    }
+
+    fn identity(self, access: A, size: usize, id: T) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self {
+            Self::Host(host) => ReduceAxes::identity(host, access, size, id).map(AccessOp::wrap),
+        }
+    }
+}
+
+#[cfg(feature = "opencl")]
+impl<A: Access<T>, T: CType> ReduceAxes<A, T> for Platform {
+    type Op = Reduce<A, T>;
+    type Moment = ReduceMoment<A, T>;
+    type Arg = ReduceArg<A, T>;
+
+    fn max(self, access: A, stride: usize) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => ReduceAxes::max(cl, access, stride).map(AccessOp::wrap),
+            Self::Host(host) => ReduceAxes::max(host, access, stride).map(AccessOp::wrap),
+        }
+    }
+
+    fn min(self, access: A, stride: usize) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => ReduceAxes::min(cl, access, stride).map(AccessOp::wrap),
+            Self::Host(host) => ReduceAxes::min(host, access, stride).map(AccessOp::wrap),
+        }
+    }
+
+    fn product(self, access: A, stride: usize) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => ReduceAxes::product(cl, access, stride).map(AccessOp::wrap),
+            Self::Host(host) => ReduceAxes::product(host, access, stride).map(AccessOp::wrap),
+        }
+    }
+
+    fn sum(self, access: A, stride: usize) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => ReduceAxes::sum(cl, access, stride).map(AccessOp::wrap),
+            Self::Host(host) => ReduceAxes::sum(host, access, stride).map(AccessOp::wrap),
+        }
+    }
+
+    fn mean(self, access: A, stride: usize) -> Result<AccessOp<Self::Moment, Self>, Error> {
+        match self.for_dtype::<T>().for_dtype::<T::Float>() {
+            Self::CL(cl) => ReduceAxes::mean(cl, access, stride).map(AccessOp::wrap),
+            Self::Host(host) => ReduceAxes::mean(host, access, stride).map(AccessOp::wrap),
+        }
+    }
+
+    fn var(self, access: A, stride: usize) -> Result<AccessOp<Self::Moment, Self>, Error> {
+        match self.for_dtype::<T>().for_dtype::<T::Float>() {
+            Self::CL(cl) => ReduceAxes::var(cl, access, stride).map(AccessOp::wrap),
+            Self::Host(host) => ReduceAxes::var(host, access, stride).map(AccessOp::wrap),
+        }
+    }
+
+    fn std(self, access: A, stride: usize) -> Result<AccessOp<Self::Moment, Self>, Error> {
+        match self.for_dtype::<T>().for_dtype::<T::Float>() {
+            Self::CL(cl) => ReduceAxes::std(cl, access, stride).map(AccessOp::wrap),
+            Self::Host(host) => ReduceAxes::std(host, access, stride).map(AccessOp::wrap),
+        }
+    }
+
+    fn argmax(self, access: A, stride: usize) -> Result<AccessOp<Self::Arg, Self>, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => ReduceAxes::argmax(cl, access, stride).map(AccessOp::wrap),
+            Self::Host(host) => ReduceAxes::argmax(host, access, stride).map(AccessOp::wrap),
+        }
+    }
+
+    fn argmin(self, access: A, stride: usize) -> Result<AccessOp<Self::Arg, Self>, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => ReduceAxes::argmin(cl, access, stride).map(AccessOp::wrap),
+            Self::Host(host) => ReduceAxes::argmin(host, access, stride).map(AccessOp::wrap),
+        }
+    }
+
+    fn max_ignore_nan(self, access: A, stride: usize) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => ReduceAxes::max_ignore_nan(cl, access, stride).map(AccessOp::wrap),
+            Self::Host(host) => {
+                ReduceAxes::max_ignore_nan(host, access, stride).map(AccessOp::wrap)
+            }
+        }
+    }
+
+    fn min_ignore_nan(self, access: A, stride: usize) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => ReduceAxes::min_ignore_nan(cl, access, stride).map(AccessOp::wrap),
+            Self::Host(host) => {
+                ReduceAxes::min_ignore_nan(host, access, stride).map(AccessOp::wrap)
+            }
+        }
+    }
+
+    fn product_ignore_nan(
+        self,
+        access: A,
+        stride: usize,
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => ReduceAxes::product_ignore_nan(cl, access, stride).map(AccessOp::wrap),
+            Self::Host(host) => {
+                ReduceAxes::product_ignore_nan(host, access, stride).map(AccessOp::wrap)
+            }
+        }
+    }
+
+    fn sum_ignore_nan(self, access: A, stride: usize) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => ReduceAxes::sum_ignore_nan(cl, access, stride).map(AccessOp::wrap),
+            Self::Host(host) => {
+                ReduceAxes::sum_ignore_nan(host, access, stride).map(AccessOp::wrap)
+            }
+        }
+    }
+
+    fn identity(self, access: A, size: usize, id: T) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => ReduceAxes::identity(cl, access, size, id).map(AccessOp::wrap),
+            Self::Host(host) => ReduceAxes::identity(host, access, size, id).map(AccessOp::wrap),
+        }
+    }
+}
+
+#[cfg(not(feature = "opencl"))]
+impl<O, A, T> Segments<O, A, T> for Platform
+where
+    O: Access<u64>,
+    A: Access<T>,
     T: CType,
 {
-    type Op = MatMul<L, R, T>;
+    type Pad = SegmentPad<O, A, T>;
+    type Reduce = SegmentReduce<O, A, T>;
 
-    fn matmul(
+    fn pad_segments(
         self,
-        left: L,
-        right: R,
-        dims: [usize; 4],
-    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        offsets: O,
+        values: A,
+        segments: usize,
+        max_len: usize,
+        pad: T,
+    ) -> Result<AccessOp<Self::Pad, Self>, Error> {
         match self {
-            Self::CL(cl) => cl.matmul(left, right, dims).map(AccessOp::wrap),
-            Self::Host(host) => host.matmul(left, right, dims).map(AccessOp::wrap),
+            Self::Host(host) => host
+                .pad_segments(offsets, values, segments, max_len, pad)
+                .map(AccessOp::wrap),
         }
     }
-}
-
-#[cfg(not(feature = "opencl"))]
-impl<A: Access<T>, T: CType> LinAlgUnary<A, T> for Platform {
-    type Op = MatDiag<A, T>;
 
-    fn diag(
+    fn segment_max(
         self,
-        access: A,
-        batch_size: usize,
-        dim: usize,
-    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        offsets: O,
+        values: A,
+        segments: usize,
+    ) -> Result<AccessOp<Self::Reduce, Self>, Error> {
         match self {
-            Self::Host(host) => host.diag(access, batch_size, dim).map(AccessOp::wrap),
+            Self::Host(host) => host
+                .segment_max(offsets, values, segments)
+                .map(AccessOp::wrap),
         }
     }
-}
-
-#[cfg(feature = "opencl")]
-impl<A: Access<T>, T: CType> LinAlgUnary<A, T> for Platform {
-    type Op = MatDiag<A, T>;
 
-    fn diag(
+    fn segment_min(
         self,
-        access: A,
-        batch_size: usize,
-        dim: usize,
-    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        offsets: O,
+        values: A,
+        segments: usize,
+    ) -> Result<AccessOp<Self::Reduce, Self>, Error> {
         match self {
-            Self::CL(cl) => cl.diag(access, batch_size, dim).map(AccessOp::wrap),
-            Self::Host(host) => host.diag(access, batch_size, dim).map(AccessOp::wrap),
+            Self::Host(host) => host
+                .segment_min(offsets, values, segments)
+                .map(AccessOp::wrap),
         }
     }
-}
-
-#[cfg(not(feature = "opencl"))]
-impl Random for Platform {
-    type Normal = RandomNormal;
-    type Uniform = RandomUniform;
 
-    fn random_normal(self, size: usize) -> Result<AccessOp<Self::Normal, Self>, Error> {
+    fn segment_product(
+        self,
+        offsets: O,
+        values: A,
+        segments: usize,
+    ) -> Result<AccessOp<Self::Reduce, Self>, Error> {
         match self {
-            Self::Host(host) => host.random_normal(size).map(AccessOp::wrap),
+            Self::Host(host) => host
+                .segment_product(offsets, values, segments)
+                .map(AccessOp::wrap),
         }
     }
 
-    fn random_uniform(self, size: usize) -> Result<AccessOp<Self::Uniform, Self>, Error> {
+    fn segment_sum(
+        self,
+        offsets: O,
+        values: A,
+        segments: usize,
+    ) -> Result<AccessOp<Self::Reduce, Self>, Error> {
         match self {
-            Self::Host(host) => host.random_uniform(size).map(AccessOp::wrap),
+            Self::Host(host) => host
+                .segment_sum(offsets, values, segments)
+                .map(AccessOp::wrap),
         }
     }
 }
 
 #[cfg(feature = "opencl")]
-impl Random for Platform {
-    type Normal = RandomNormal;
-    type Uniform = RandomUniform;
+impl<O, A, T> Segments<O, A, T> for Platform
+where
+    O: Access<u64>,
+    A: Access<T>,
+    T: CType,
+{
+    type Pad = SegmentPad<O, A, T>;
+    type Reduce = SegmentReduce<O, A, T>;
 
-    fn random_normal(self, size: usize) -> Result<AccessOp<Self::Normal, Self>, Error> {
-        match self {
-            Self::CL(cl) => cl.random_normal(size).map(AccessOp::wrap),
-            Self::Host(host) => host.random_normal(size).map(AccessOp::wrap),
+    fn pad_segments(
+        self,
+        offsets: O,
+        values: A,
+        segments: usize,
+        max_len: usize,
+        pad: T,
+    ) -> Result<AccessOp<Self::Pad, Self>, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => cl
+                .pad_segments(offsets, values, segments, max_len, pad)
+                .map(AccessOp::wrap),
+            Self::Host(host) => host
+                .pad_segments(offsets, values, segments, max_len, pad)
+                .map(AccessOp::wrap),
         }
     }
 
-    fn random_uniform(self, size: usize) -> Result<AccessOp<Self::Uniform, Self>, Error> {
-        match self {
-            Self::CL(cl) => cl.random_uniform(size).map(AccessOp::wrap),
-            Self::Host(host) => host.random_uniform(size).map(AccessOp::wrap),
+    fn segment_max(
+        self,
+        offsets: O,
+        values: A,
+        segments: usize,
+    ) -> Result<AccessOp<Self::Reduce, Self>, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => cl
+                .segment_max(offsets, values, segments)
+                .map(AccessOp::wrap),
+            Self::Host(host) => host
+                .segment_max(offsets, values, segments)
+                .map(AccessOp::wrap),
         }
     }
-}
 
-#[cfg(not(feature = "opencl"))]
-impl<A: Access<T>, T: CType> ReduceAll<A, T> for Platform {
-    fn all(self, access: A) -> Result<bool, Error> {
-        match self {
-            Self::Host(host) => host.all(access),
+    fn segment_min(
+        self,
+        offsets: O,
+        values: A,
+        segments: usize,
+    ) -> Result<AccessOp<Self::Reduce, Self>, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => cl
+                .segment_min(offsets, values, segments)
+                .map(AccessOp::wrap),
+            Self::Host(host) => host
+                .segment_min(offsets, values, segments)
+                .map(AccessOp::wrap),
         }
     }
 
-    fn any(self, access: A) -> Result<bool, Error> {
-        match self {
-            Self::Host(host) => host.any(access),
+    fn segment_product(
+        self,
+        offsets: O,
+        values: A,
+        segments: usize,
+    ) -> Result<AccessOp<Self::Reduce, Self>, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => cl
+                .segment_product(offsets, values, segments)
+                .map(AccessOp::wrap),
+            Self::Host(host) => host
+                .segment_product(offsets, values, segments)
+                .map(AccessOp::wrap),
         }
     }
 
-    fn max(self, access: A) -> Result<T, Error> {
-        match self {
-            Self::Host(host) => ReduceAll::max(host, access),
+    fn segment_sum(
+        self,
+        offsets: O,
+        values: A,
+        segments: usize,
+    ) -> Result<AccessOp<Self::Reduce, Self>, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => cl
+                .segment_sum(offsets, values, segments)
+                .map(AccessOp::wrap),
+            Self::Host(host) => host
+                .segment_sum(offsets, values, segments)
+                .map(AccessOp::wrap),
         }
     }
+}
 
-    fn min(self, access: A) -> Result<T, Error> {
-        match self {
-            Self::Host(host) => ReduceAll::min(host, access),
-        }
-    }
+#[cfg(not(feature = "opencl"))]
+impl<A: Access<T>, T: CType> ScanAxis<A, T> for Platform {
+    type Op = Scan<A, T>;
+    type Cdf = Cdf<A, T>;
 
-    fn product(self, access: A) -> Result<T, Error> {
+    fn cdf(self, access: A, dim: usize, inner: usize) -> Result<AccessOp<Self::Cdf, Self>, Error> {
         match self {
-            Self::Host(host) => ReduceAll::product(host, access),
+            Self::Host(host) => host.cdf(access, dim, inner).map(AccessOp::wrap),
         }
     }
 
-    fn sum(self, access: A) -> Result<T, Error> {
+    fn cumprod(
+        self,
+        access: A,
+        dim: usize,
+        inner: usize,
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
         match self {
-            Self::Host(host) => ReduceAll::sum(host, access),
+            Self::Host(host) => host.cumprod(access, dim, inner).map(AccessOp::wrap),
         }
     }
-}
 
-#[cfg(feature = "opencl")]
-impl<A, T> ReduceAll<A, T> for Platform
-where
-    A: Access<T>,
-    T: CType,
-{
-    fn all(self, access: A) -> Result<bool, Error> {
+    fn cumsum(
+        self,
+        access: A,
+        dim: usize,
+        inner: usize,
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
         match self {
-            Self::CL(cl) => cl.all(access),
-            Self::Host(host) => host.all(access),
+            Self::Host(host) => host.cumsum(access, dim, inner).map(AccessOp::wrap),
         }
     }
 
-    fn any(self, access: A) -> Result<bool, Error> {
+    fn logcumsumexp(
+        self,
+        access: A,
+        dim: usize,
+        inner: usize,
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
         match self {
-            Self::CL(cl) => cl.any(access),
-            Self::Host(host) => host.any(access),
+            Self::Host(host) => host.logcumsumexp(access, dim, inner).map(AccessOp::wrap),
         }
     }
+}
 
-    fn max(self, access: A) -> Result<T, Error> {
-        match self {
-            Self::CL(cl) => ReduceAll::max(cl, access),
-            Self::Host(host) => ReduceAll::max(host, access),
+#[cfg(feature = "opencl")]
+impl<A: Access<T>, T: CType> ScanAxis<A, T> for Platform {
+    type Op = Scan<A, T>;
+    type Cdf = Cdf<A, T>;
+
+    fn cdf(self, access: A, dim: usize, inner: usize) -> Result<AccessOp<Self::Cdf, Self>, Error> {
+        match self.for_dtype::<T>().for_dtype::<T::Float>() {
+            Self::CL(cl) => cl.cdf(access, dim, inner).map(AccessOp::wrap),
+            Self::Host(host) => host.cdf(access, dim, inner).map(AccessOp::wrap),
         }
     }
 
-    fn min(self, access: A) -> Result<T, Error> {
-        match self {
-            Self::CL(cl) => ReduceAll::min(cl, access),
-            Self::Host(host) => ReduceAll::min(host, access),
+    fn cumprod(
+        self,
+        access: A,
+        dim: usize,
+        inner: usize,
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => cl.cumprod(access, dim, inner).map(AccessOp::wrap),
+            Self::Host(host) => host.cumprod(access, dim, inner).map(AccessOp::wrap),
         }
     }
 
-    fn product(self, access: A) -> Result<T, Error> {
-        match self {
-            Self::CL(cl) => ReduceAll::product(cl, access),
-            Self::Host(host) => ReduceAll::product(host, access),
+    fn cumsum(
+        self,
+        access: A,
+        dim: usize,
+        inner: usize,
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => cl.cumsum(access, dim, inner).map(AccessOp::wrap),
+            Self::Host(host) => host.cumsum(access, dim, inner).map(AccessOp::wrap),
         }
     }
 
-    fn sum(self, access: A) -> Result<T, Error> {
-        match self {
-            Self::CL(cl) => ReduceAll::sum(cl, access),
-            Self::Host(host) => ReduceAll::sum(host, access),
+    fn logcumsumexp(
+        self,
+        access: A,
+        dim: usize,
+        inner: usize,
+    ) -> Result<AccessOp<Self::Op, Self>, Error> {
+        match self.for_dtype::<T>().for_dtype::<T::Float>() {
+            Self::CL(cl) => cl.logcumsumexp(access, dim, inner).map(AccessOp::wrap),
+            Self::Host(host) => host.logcumsumexp(access, dim, inner).map(AccessOp::wrap),
         }
     }
 }
 
 #[cfg(not(feature = "opencl"))]
-impl<A: Access<T>, T: CType> ReduceAxes<A, T> for Platform {
-    type Op = Reduce<A, T>;
+impl<A: Access<T>, T: CType> SortAxis<A, T> for Platform {
+    type Sort = Sort<A, T>;
+    type ArgSort = ArgSort<A, T>;
 
-    fn max(self, access: A, stride: usize) -> Result<AccessOp<Self::Op, Self>, Error> {
+    fn sort(
+        self,
+        access: A,
+        dim: usize,
+        inner: usize,
+        descending: bool,
+    ) -> Result<AccessOp<Self::Sort, Self>, Error> {
         match self {
-            Self::Host(host) => ReduceAxes::max(host, access, stride).map(AccessOp::wrap),
+            Self::Host(host) => host
+                .sort(access, dim, inner, descending)
+                .map(AccessOp::wrap),
         }
     }
 
-    fn min(self, access: A, stride: usize) -> Result<AccessOp<Self::Op, Self>, Error> {
+    fn argsort(
+        self,
+        access: A,
+        dim: usize,
+        inner: usize,
+    ) -> Result<AccessOp<Self::ArgSort, Self>, Error> {
         match self {
-            Self::Host(host) => ReduceAxes::min(host, access, stride).map(AccessOp::wrap),
+            Self::Host(host) => host.argsort(access, dim, inner).map(AccessOp::wrap),
         }
     }
+}
 
-    fn product(self, access: A, stride: usize) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
-            Self::Host(host) => ReduceAxes::product(host, access, stride).map(AccessOp::wrap),
+#[cfg(feature = "opencl")]
+impl<A: Access<T>, T: CType> SortAxis<A, T> for Platform {
+    type Sort = Sort<A, T>;
+    type ArgSort = ArgSort<A, T>;
+
+    fn sort(
+        self,
+        access: A,
+        dim: usize,
+        inner: usize,
+        descending: bool,
+    ) -> Result<AccessOp<Self::Sort, Self>, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => cl.sort(access, dim, inner, descending).map(AccessOp::wrap),
+            Self::Host(host) => host
+                .sort(access, dim, inner, descending)
+                .map(AccessOp::wrap),
         }
     }
 
-    fn sum(self, access: A, stride: usize) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
-            Self::Host(host) => ReduceAxes::sum(host, access, stride).map(AccessOp::wrap),
+    fn argsort(
+        self,
+        access: A,
+        dim: usize,
+        inner: usize,
+    ) -> Result<AccessOp<Self::ArgSort, Self>, Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => cl.argsort(access, dim, inner).map(AccessOp::wrap),
+            Self::Host(host) => host.argsort(access, dim, inner).map(AccessOp::wrap),
         }
     }
 }
 
-#[cfg(feature = "opencl")]
-impl<A: Access<T>, T: CType> ReduceAxes<A, T> for Platform {
-    type Op = Reduce<A, T>;
-
-    fn max(self, access: A, stride: usize) -> Result<AccessOp<Self::Op, Self>, Error> {
+#[cfg(not(feature = "opencl"))]
+impl<A: Access<T>, T: CType> ReduceAxesWithIndex<A, T> for Platform {
+    fn max_with_index(self, access: A, stride: usize) -> Result<(Buffer<T>, Buffer<u64>), Error> {
         match self {
-            Self::CL(cl) => ReduceAxes::max(cl, access, stride).map(AccessOp::wrap),
-            Self::Host(host) => ReduceAxes::max(host, access, stride).map(AccessOp::wrap),
+            Self::Host(host) => host
+                .max_with_index(access, stride)
+                .map(|(values, indices)| (Buffer::Host(values), Buffer::Host(indices))),
         }
     }
 
-    fn min(self, access: A, stride: usize) -> Result<AccessOp<Self::Op, Self>, Error> {
+    fn min_with_index(self, access: A, stride: usize) -> Result<(Buffer<T>, Buffer<u64>), Error> {
         match self {
-            Self::CL(cl) => ReduceAxes::min(cl, access, stride).map(AccessOp::wrap),
-            Self::Host(host) => ReduceAxes::min(host, access, stride).map(AccessOp::wrap),
+            Self::Host(host) => host
+                .min_with_index(access, stride)
+                .map(|(values, indices)| (Buffer::Host(values), Buffer::Host(indices))),
         }
     }
+}
 
-    fn product(self, access: A, stride: usize) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
-            Self::CL(cl) => ReduceAxes::product(cl, access, stride).map(AccessOp::wrap),
-            Self::Host(host) => ReduceAxes::product(host, access, stride).map(AccessOp::wrap),
+#[cfg(feature = "opencl")]
+impl<A: Access<T>, T: CType> ReduceAxesWithIndex<A, T> for Platform {
+    fn max_with_index(self, access: A, stride: usize) -> Result<(Buffer<T>, Buffer<u64>), Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => cl
+                .max_with_index(access, stride)
+                .map(|(values, indices)| (Buffer::CL(values), Buffer::CL(indices))),
+            Self::Host(host) => host
+                .max_with_index(access, stride)
+                .map(|(values, indices)| (Buffer::Host(values), Buffer::Host(indices))),
         }
     }
 
-    fn sum(self, access: A, stride: usize) -> Result<AccessOp<Self::Op, Self>, Error> {
-        match self {
-            Self::CL(cl) => ReduceAxes::sum(cl, access, stride).map(AccessOp::wrap),
-            Self::Host(host) => ReduceAxes::sum(host, access, stride).map(AccessOp::wrap),
+    fn min_with_index(self, access: A, stride: usize) -> Result<(Buffer<T>, Buffer<u64>), Error> {
+        match self.for_dtype::<T>() {
+            Self::CL(cl) => cl
+                .min_with_index(access, stride)
+                .map(|(values, indices)| (Buffer::CL(values), Buffer::CL(indices))),
+            Self::Host(host) => host
+                .min_with_index(access, stride)
+                .map(|(values, indices)| (Buffer::Host(values), Buffer::Host(indices))),
         }
     }
 }
@@ -1210,7 +2660,7 @@ impl<A: Access<T>, T: CType> Transform<A, T> for Platform {
         shape: Shape,
         broadcast: Shape,
     ) -> Result<AccessOp<Self::Broadcast, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>() {
             #[cfg(feature = "opencl")]
             Self::CL(cl) => cl.broadcast(access, shape, broadcast).map(AccessOp::wrap),
             Self::Host(host) => host.broadcast(access, shape, broadcast).map(AccessOp::wrap),
@@ -1223,7 +2673,7 @@ impl<A: Access<T>, T: CType> Transform<A, T> for Platform {
         shape: &[usize],
         range: Range,
     ) -> Result<AccessOp<Self::Slice, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>() {
             #[cfg(feature = "opencl")]
             Self::CL(cl) => cl.slice(access, shape, range).map(AccessOp::wrap),
             Self::Host(host) => host.slice(access, shape, range).map(AccessOp::wrap),
@@ -1236,7 +2686,7 @@ impl<A: Access<T>, T: CType> Transform<A, T> for Platform {
         shape: Shape,
         permutation: Axes,
     ) -> Result<AccessOp<Self::Transpose, Self>, Error> {
-        match self {
+        match self.for_dtype::<T>() {
             #[cfg(feature = "opencl")]
             Self::CL(cl) => cl.transpose(access, shape, permutation).map(AccessOp::wrap),
             Self::Host(host) => host
@@ -1244,4 +2694,49 @@ impl<A: Access<T>, T: CType> Transform<A, T> for Platform {
                 .map(AccessOp::wrap),
         }
     }
+
+    fn reverse(
+        self,
+        access: A,
+        shape: Shape,
+        axes: Axes,
+    ) -> Result<AccessOp<Self::Transpose, Self>, Error> {
+        match self.for_dtype::<T>() {
+            #[cfg(feature = "opencl")]
+            Self::CL(cl) => cl.reverse(access, shape, axes).map(AccessOp::wrap),
+            Self::Host(host) => host.reverse(access, shape, axes).map(AccessOp::wrap),
+        }
+    }
+
+    fn roll(
+        self,
+        access: A,
+        shape: Shape,
+        shift: usize,
+        axis: usize,
+    ) -> Result<AccessOp<Self::Transpose, Self>, Error> {
+        match self.for_dtype::<T>() {
+            #[cfg(feature = "opencl")]
+            Self::CL(cl) => cl.roll(access, shape, shift, axis).map(AccessOp::wrap),
+            Self::Host(host) => host.roll(access, shape, shift, axis).map(AccessOp::wrap),
+        }
+    }
+
+    unsafe fn as_strided(
+        self,
+        access: A,
+        shape: Shape,
+        strides: Strides,
+        offset: usize,
+    ) -> Result<AccessOp<Self::Transpose, Self>, Error> {
+        match self.for_dtype::<T>() {
+            #[cfg(feature = "opencl")]
+            Self::CL(cl) => cl
+                .as_strided(access, shape, strides, offset)
+                .map(AccessOp::wrap),
+            Self::Host(host) => host
+                .as_strided(access, shape, strides, offset)
+                .map(AccessOp::wrap),
+        }
+    }
 }
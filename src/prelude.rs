@@ -0,0 +1,28 @@
+//! The traits, types, and macros needed to construct an array and call any of its methods
+//!
+//! ```
+//! use ha_ndarray::prelude::*;
+//!
+//! # fn main() -> Result<(), Error> {
+//! let x = ArrayOp::range(0., 6., shape![2, 3])?;
+//! let y = x.sum(axes![1], false)?;
+//! assert_eq!(&*y.buffer()?.to_slice()?, &[3., 12.]);
+//! # Ok(())
+//! # }
+//! ```
+
+pub use crate::access::{Access, AccessBuf, AccessMut, Accessor};
+pub use crate::buffer::{BufferInstance, BufferMut};
+pub use crate::platform::{Platform, PlatformInstance};
+pub use crate::{axes, shape, slice};
+pub use crate::{
+    Array, ArrayAccess, ArrayBuf, ArrayOp, Axes, AxisRange, CType, Error, Float, Range, Shape,
+};
+pub use crate::{
+    MatrixDecompose, MatrixDual, MatrixSolve, MatrixUnary, NDArray, NDArrayBoolean,
+    NDArrayBooleanScalar, NDArrayCast, NDArrayCompare, NDArrayCompareScalar, NDArrayConv,
+    NDArrayGather, NDArrayMath, NDArrayMathScalar, NDArrayNormalize, NDArrayNumeric, NDArrayRead,
+    NDArrayReduce, NDArrayReduceAll, NDArrayReduceBoolean, NDArrayScan, NDArraySort,
+    NDArrayTernary, NDArrayTransform, NDArrayTrig, NDArrayTrigDual, NDArrayUnary,
+    NDArrayUnaryBoolean, NDArrayUpsample, NDArrayWhere, NDArrayWrite,
+};
@@ -0,0 +1,235 @@
+//! Ragged arrays
+
+use crate::access::{AccessOp, Accessor};
+use crate::array::Array;
+use crate::ops::{SegmentPad, SegmentReduce, Segments};
+use crate::platform::PlatformInstance;
+use crate::{shape, ArrayAccess, ArrayBuf, CType, Error, NDArray, NDArrayRead, Platform};
+
+/// The per-segment reduction of a [`RaggedArray`]
+pub type RaggedReduce<T> =
+    Array<T, AccessOp<SegmentReduce<Accessor<u64>, Accessor<T>, T>, Platform>, Platform>;
+
+/// The padded dense form of a [`RaggedArray`]
+pub type RaggedPad<T> =
+    Array<T, AccessOp<SegmentPad<Accessor<u64>, Accessor<T>, T>, Platform>, Platform>;
+
+/// A sequence of variable-length segments stored as contiguous `values` with row `offsets`
+#[derive(Clone)]
+pub struct RaggedArray<T: CType> {
+    offsets: ArrayAccess<u64>,
+    values: ArrayAccess<T>,
+    max_len: usize,
+}
+
+impl<T: CType> RaggedArray<T> {
+    /// Construct a new ragged array whose `i`th segment is `values[offsets[i]..offsets[i + 1]]`.
+    /// This will return an error if the `offsets` are not non-decreasing from zero to the number
+    /// of `values`.
+    pub fn new<OA, OP, VA, VP>(
+        offsets: Array<u64, OA, OP>,
+        values: Array<T, VA, VP>,
+    ) -> Result<Self, Error>
+    where
+        Accessor<u64>: From<OA>,
+        Accessor<T>: From<VA>,
+        Platform: From<OP> + From<VP>,
+    {
+        let offsets = ArrayAccess::from(offsets);
+        let values = ArrayAccess::from(values);
+
+        if offsets.ndim() != 1 || offsets.size() == 0 || values.ndim() != 1 {
+            return Err(Error::Bounds(format!(
+                "invalid ragged array: offsets {:?}, values {:?}",
+                offsets.shape(),
+                values.shape(),
+            )));
+        }
+
+        // check every offset now, since a kernel does not check its bounds
+        let max_len = {
+            let len = values.size() as u64;
+            let offsets = offsets.buffer()?.to_slice()?;
+
+            if offsets[0] != 0
+                || offsets[offsets.len() - 1] != len
+                || offsets.windows(2).any(|pair| pair[0] > pair[1])
+            {
+                return Err(Error::Bounds(format!(
+                    "the offsets of a ragged array must be non-decreasing from 0 to {len}"
+                )));
+            }
+
+            (offsets.windows(2))
+                .map(|pair| (pair[1] - pair[0]) as usize)
+                .max()
+                .unwrap_or(0)
+        };
+
+        Ok(Self {
+            offsets,
+            values,
+            max_len,
+        })
+    }
+
+    /// Construct a new ragged array by concatenating the given `segments`.
+    pub fn from_segments<I, S>(segments: I) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = S>,
+        S: IntoIterator<Item = T>,
+    {
+        let mut offsets = vec![0u64];
+        let mut values = Vec::new();
+
+        for segment in segments {
+            values.extend(segment);
+            offsets.push(values.len() as u64);
+        }
+
+        let (num_offsets, num_values) = (offsets.len(), values.len());
+        let offsets = ArrayBuf::new(offsets, shape![num_offsets])?;
+        let values = ArrayBuf::new(values, shape![num_values])?;
+
+        Self::new(offsets, values)
+    }
+
+    /// The number of segments in this array.
+    pub fn len(&self) -> usize {
+        self.offsets.size() - 1
+    }
+
+    /// Return `true` if this array has no segments.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The length of the longest segment in this array.
+    pub fn max_len(&self) -> usize {
+        self.max_len
+    }
+
+    /// Read the length of each segment in this array.
+    pub fn lengths(&self) -> Result<Vec<usize>, Error> {
+        let offsets = self.offsets.buffer()?.to_slice()?;
+
+        let lengths = (offsets.windows(2))
+            .map(|pair| (pair[1] - pair[0]) as usize)
+            .collect();
+
+        Ok(lengths)
+    }
+
+    /// Borrow the segment offsets of this array, with shape `[segments + 1]`.
+    pub fn offsets(&self) -> &ArrayAccess<u64> {
+        &self.offsets
+    }
+
+    /// Borrow the concatenated values of this array, with shape `[values]`.
+    pub fn values(&self) -> &ArrayAccess<T> {
+        &self.values
+    }
+
+    /// Apply `f` to the concatenated values of this array, keeping the same segments.
+    /// This will return an error if `f` does not preserve the shape of the values.
+    pub fn map<U, F, A, P>(self, f: F) -> Result<RaggedArray<U>, Error>
+    where
+        U: CType,
+        F: FnOnce(ArrayAccess<T>) -> Result<Array<U, A, P>, Error>,
+        Accessor<U>: From<A>,
+        Platform: From<P>,
+    {
+        let shape = self.values.shape().to_vec();
+        let values = f(self.values).map(ArrayAccess::from)?;
+
+        if values.shape() != shape {
+            return Err(Error::Bounds(format!(
+                "cannot map the values of a ragged array with shape {shape:?} to shape {:?}",
+                values.shape()
+            )));
+        }
+
+        Ok(RaggedArray {
+            offsets: self.offsets,
+            values,
+            max_len: self.max_len,
+        })
+    }
+
+    /// Construct an operation to find the maximum of each segment,
+    /// or the minimum value of `T` for an empty segment.
+    pub fn max(&self) -> Result<RaggedReduce<T>, Error> {
+        self.reduce(|platform, offsets, values, segments| {
+            platform.segment_max(offsets, values, segments)
+        })
+    }
+
+    /// Construct an operation to find the minimum of each segment,
+    /// or the maximum value of `T` for an empty segment.
+    pub fn min(&self) -> Result<RaggedReduce<T>, Error> {
+        self.reduce(|platform, offsets, values, segments| {
+            platform.segment_min(offsets, values, segments)
+        })
+    }
+
+    /// Construct an operation to find the product of each segment,
+    /// or one for an empty segment.
+    pub fn product(&self) -> Result<RaggedReduce<T>, Error> {
+        self.reduce(|platform, offsets, values, segments| {
+            platform.segment_product(offsets, values, segments)
+        })
+    }
+
+    /// Construct an operation to find the sum of each segment, or zero for an empty segment.
+    pub fn sum(&self) -> Result<RaggedReduce<T>, Error> {
+        self.reduce(|platform, offsets, values, segments| {
+            platform.segment_sum(offsets, values, segments)
+        })
+    }
+
+    /// Construct an operation to copy this array into a dense array with shape
+    /// `[segments, max_len]`, filling the end of each shorter segment with `pad`.
+    pub fn to_padded(&self, pad: T) -> Result<RaggedPad<T>, Error> {
+        let segments = self.len();
+        let platform = Platform::select(segments * self.max_len);
+
+        let access = platform.pad_segments(
+            self.offsets.access().clone(),
+            self.values.access().clone(),
+            segments,
+            self.max_len,
+            pad,
+        )?;
+
+        Ok(Array::from_op(
+            shape![segments, self.max_len],
+            access,
+            platform,
+        ))
+    }
+
+    fn reduce<F>(&self, reduce: F) -> Result<RaggedReduce<T>, Error>
+    where
+        F: FnOnce(
+            Platform,
+            Accessor<u64>,
+            Accessor<T>,
+            usize,
+        ) -> Result<
+            AccessOp<SegmentReduce<Accessor<u64>, Accessor<T>, T>, Platform>,
+            Error,
+        >,
+    {
+        let segments = self.len();
+        let platform = Platform::select(self.values.size());
+
+        let access = reduce(
+            platform,
+            self.offsets.access().clone(),
+            self.values.access().clone(),
+            segments,
+        )?;
+
+        Ok(Array::from_op(shape![segments], access, platform))
+    }
+}
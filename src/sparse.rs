@@ -0,0 +1,128 @@
+//! Sparse matrices
+
+use crate::access::{Access, AccessOp, Accessor};
+use crate::array::Array;
+use crate::ops::{CsrMatMul, LinAlgSparse};
+use crate::platform::PlatformInstance;
+use crate::{shape, ArrayAccess, CType, Error, NDArray, NDArrayRead, Platform};
+
+/// The product of a [`CsrMatrix`] and a dense array
+pub type CsrProduct<T> =
+    Array<T, AccessOp<CsrMatMul<Accessor<u64>, Accessor<T>, Accessor<T>, T>, Platform>, Platform>;
+
+/// A sparse matrix in compressed sparse row (CSR) format
+#[derive(Clone)]
+pub struct CsrMatrix<T: CType> {
+    shape: [usize; 2],
+    offsets: Accessor<u64>,
+    indices: Accessor<u64>,
+    values: Accessor<T>,
+}
+
+impl<T: CType> CsrMatrix<T> {
+    /// Construct a new sparse matrix with the given `shape` whose `i`th row has the non-zero
+    /// `values` at offsets `offsets[i]..offsets[i + 1]`, in the columns given by `indices`.
+    /// This will return an error if the `offsets` are not non-decreasing from zero to the number
+    /// of `values`, or if a column index is out of bounds.
+    pub fn new<OA, OP, IA, IP, VA, VP>(
+        shape: [usize; 2],
+        offsets: Array<u64, OA, OP>,
+        indices: Array<u64, IA, IP>,
+        values: Array<T, VA, VP>,
+    ) -> Result<Self, Error>
+    where
+        Accessor<u64>: From<OA> + From<IA>,
+        Accessor<T>: From<VA>,
+        Platform: From<OP> + From<IP> + From<VP>,
+    {
+        let [rows, columns] = shape;
+
+        let offsets = ArrayAccess::from(offsets);
+        let indices = ArrayAccess::from(indices);
+        let values = ArrayAccess::from(values);
+
+        if offsets.shape() != [rows + 1] || indices.shape() != values.shape() {
+            return Err(Error::Bounds(format!(
+                "invalid CSR matrix with shape {shape:?}: offsets {:?}, indices {:?}, values {:?}",
+                offsets.shape(),
+                indices.shape(),
+                values.shape(),
+            )));
+        }
+
+        // check every offset and index now, since a kernel does not check its bounds
+        let nnz = values.size() as u64;
+
+        {
+            let offsets = offsets.buffer()?.to_slice()?;
+
+            if offsets[0] != 0
+                || offsets[rows] != nnz
+                || offsets.windows(2).any(|pair| pair[0] > pair[1])
+            {
+                return Err(Error::Bounds(format!(
+                    "the row offsets of a CSR matrix must be non-decreasing from 0 to {nnz}"
+                )));
+            }
+        }
+
+        if let Some(index) =
+            (indices.buffer()?.to_slice()?.iter()).find(|index| **index >= columns as u64)
+        {
+            return Err(Error::Bounds(format!(
+                "column index {index} is out of bounds for a CSR matrix with shape {shape:?}"
+            )));
+        }
+
+        Ok(Self {
+            shape,
+            offsets: offsets.into_access(),
+            indices: indices.into_access(),
+            values: values.into_access(),
+        })
+    }
+
+    /// The number of non-zero elements in this matrix.
+    pub fn nnz(&self) -> usize {
+        self.values.size()
+    }
+
+    /// The shape of this matrix.
+    pub fn shape(&self) -> [usize; 2] {
+        self.shape
+    }
+
+    /// Construct an operation to multiply this matrix by a `dense` vector with shape `[columns]`,
+    /// or a `dense` matrix with shape `[columns, n]`.
+    pub fn matmul<A, P>(&self, dense: Array<T, A, P>) -> Result<CsrProduct<T>, Error>
+    where
+        Accessor<T>: From<A>,
+        Platform: From<P>,
+    {
+        let dense = ArrayAccess::from(dense);
+        let [rows, columns] = self.shape;
+
+        let (n, shape) = match dense.shape() {
+            [dim] if *dim == columns => (1, shape![rows]),
+            [dim, n] if *dim == columns => (*n, shape![rows, *n]),
+            other => {
+                return Err(Error::Bounds(format!(
+                    "cannot multiply a CSR matrix with shape {:?} by an array with shape {other:?}",
+                    self.shape
+                )))
+            }
+        };
+
+        let platform = Platform::select(rows * n);
+
+        let access = platform.csr_matmul(
+            self.offsets.clone(),
+            self.indices.clone(),
+            self.values.clone(),
+            dense.into_access(),
+            [rows, columns, n],
+        )?;
+
+        Ok(Array::from_op(shape, access, platform))
+    }
+}
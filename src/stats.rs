@@ -0,0 +1,342 @@
+//! Streaming statistics and array summaries
+
+use std::fmt;
+
+use rayon::prelude::*;
+
+use crate::access::{Access, Accessor};
+use crate::array::Array;
+use crate::host::ArrayBuf;
+use crate::platform::PlatformInstance;
+use crate::{BufferConverter, CType, Error, Float, NDArray, NDArrayRead, Platform, Shape};
+
+/// Below this number of elements, a chunk is accumulated without spawning parallel tasks.
+const PARALLEL_MIN_SIZE: usize = 4096;
+
+/// A running accumulator of the count, mean, variance, minimum, and maximum of a stream of values,
+/// computed using Welford's algorithm so that no concatenated buffer needs to be materialized.
+#[derive(Copy, Clone, Debug)]
+pub struct StatsAccumulator<T> {
+    count: usize,
+    mean: f64,
+    m2: f64,
+    min: T,
+    max: T,
+}
+
+impl<T: CType> Default for StatsAccumulator<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: CType> StatsAccumulator<T> {
+    /// Construct a new, empty accumulator.
+    pub fn new() -> Self {
+        Self {
+            count: 0,
+            mean: 0.,
+            m2: 0.,
+            min: T::MAX,
+            max: T::MIN,
+        }
+    }
+
+    /// Consume the elements of the given `array`.
+    pub fn update<A: NDArrayRead<DType = T>>(&mut self, array: &A) -> Result<(), Error> {
+        let slice = array.buffer()?.to_slice()?;
+        self.update_slice(&slice);
+        Ok(())
+    }
+
+    /// Consume the given `values`.
+    pub fn update_slice(&mut self, values: &[T]) {
+        let chunk = if values.len() < PARALLEL_MIN_SIZE {
+            values.iter().copied().fold(Self::new(), Self::push)
+        } else {
+            values
+                .par_iter()
+                .copied()
+                .fold(Self::new, Self::push)
+                .reduce(Self::new, Self::merge)
+        };
+
+        *self = self.merge(chunk);
+    }
+
+    /// Consume a single `value`.
+    pub fn push(mut self, value: T) -> Self {
+        let n = value.to_f64();
+
+        self.count += 1;
+
+        let delta = n - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (n - self.mean);
+
+        self.min = T::min(self.min, value);
+        self.max = T::max(self.max, value);
+
+        self
+    }
+
+    /// Combine the statistics of this accumulator with those of an `other` accumulator.
+    pub fn merge(self, other: Self) -> Self {
+        if self.count == 0 {
+            return other;
+        } else if other.count == 0 {
+            return self;
+        }
+
+        let count = self.count + other.count;
+        let delta = other.mean - self.mean;
+        let weight = other.count as f64 / count as f64;
+
+        Self {
+            count,
+            mean: self.mean + (delta * weight),
+            m2: self.m2 + other.m2 + (delta * delta * self.count as f64 * weight),
+            min: T::min(self.min, other.min),
+            max: T::max(self.max, other.max),
+        }
+    }
+
+    /// The number of values consumed so far.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// The mean of the values consumed so far, if any.
+    pub fn mean(&self) -> Option<f64> {
+        if self.count == 0 {
+            None
+        } else {
+            Some(self.mean)
+        }
+    }
+
+    /// The population variance of the values consumed so far, if any.
+    pub fn var(&self) -> Option<f64> {
+        if self.count == 0 {
+            None
+        } else {
+            Some(self.m2 / self.count as f64)
+        }
+    }
+
+    /// The sample variance of the values consumed so far, if there are at least two.
+    pub fn sample_var(&self) -> Option<f64> {
+        if self.count < 2 {
+            None
+        } else {
+            Some(self.m2 / (self.count - 1) as f64)
+        }
+    }
+
+    /// The population standard deviation of the values consumed so far, if any.
+    pub fn std(&self) -> Option<f64> {
+        self.var().map(f64::sqrt)
+    }
+
+    /// The minimum value consumed so far, if any.
+    pub fn min(&self) -> Option<T> {
+        if self.count == 0 {
+            None
+        } else {
+            Some(self.min)
+        }
+    }
+
+    /// The maximum value consumed so far, if any.
+    pub fn max(&self) -> Option<T> {
+        if self.count == 0 {
+            None
+        } else {
+            Some(self.max)
+        }
+    }
+}
+
+/// A summary of the elements of an array, for quick inspection, constructed by [`Array::describe`]
+#[derive(Clone, Debug)]
+pub struct Summary<T> {
+    /// The data type of the array
+    pub dtype: &'static str,
+    /// The shape of the array
+    pub shape: Shape,
+    /// The platform on which the elements of the array reside, `host` or `opencl`
+    pub platform: &'static str,
+    /// The minimum element which is not NaN, if any
+    pub min: Option<T>,
+    /// The maximum element which is not NaN, if any
+    pub max: Option<T>,
+    /// The mean of all elements (NaN if any element is NaN), if any
+    pub mean: Option<f64>,
+    /// The population standard deviation of all elements (NaN if any element is NaN), if any
+    pub std: Option<f64>,
+    /// The number of NaN elements
+    pub nan_count: u64,
+}
+
+impl<T: CType> fmt::Display for Summary<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn field<N: fmt::Display>(value: Option<N>) -> String {
+            value
+                .map(|n| n.to_string())
+                .unwrap_or_else(|| "-".to_string())
+        }
+
+        write!(
+            f,
+            "{} array of shape {:?} on {}: min {}, max {}, mean {}, std {}, {} NaN",
+            self.dtype,
+            self.shape,
+            self.platform,
+            field(self.min),
+            field(self.max),
+            field(self.mean),
+            field(self.std),
+            self.nan_count
+        )
+    }
+}
+
+impl<T, A, P> Array<T, A, P>
+where
+    T: CType,
+    A: Access<T>,
+    P: PlatformInstance,
+    Accessor<T>: From<A>,
+    Platform: From<P>,
+{
+    /// Summarize the elements of this array, e.g. to print in a log.
+    ///
+    /// This array is computed once, and every statistic is then computed in a single pass over
+    /// its elements on the host with a [`StatsAccumulator`], so an array which resides on
+    /// an OpenCL device is copied to the host once.
+    pub fn describe(self) -> Result<Summary<T>, Error> {
+        let shape = Shape::from_slice(self.shape());
+        let buffer = self.buffer()?;
+
+        let platform = match &buffer {
+            #[cfg(feature = "opencl")]
+            BufferConverter::CL(_) => "opencl",
+            BufferConverter::Host(_) => "host",
+        };
+
+        let slice = buffer.to_slice()?;
+        let (stats, nan_count) = summarize(&slice);
+
+        // the mean and standard deviation of an array with any NaN element are NaN
+        let (mean, std) = if slice.is_empty() {
+            (None, None)
+        } else if nan_count > 0 {
+            (Some(f64::NAN), Some(f64::NAN))
+        } else {
+            (stats.mean(), stats.std())
+        };
+
+        Ok(Summary {
+            dtype: T::TYPE,
+            shape,
+            platform,
+            min: stats.min(),
+            max: stats.max(),
+            mean,
+            std,
+            nan_count,
+        })
+    }
+}
+
+/// Accumulate the statistics of the given `values` which are not NaN, and count those which are.
+fn summarize<T: CType>(values: &[T]) -> (StatsAccumulator<T>, u64) {
+    let push = |(stats, nan_count): (StatsAccumulator<T>, u64), value: T| {
+        if CType::to_f64(value).is_nan() {
+            (stats, nan_count + 1)
+        } else {
+            (stats.push(value), nan_count)
+        }
+    };
+
+    let merge = |(l, l_nan): (StatsAccumulator<T>, u64), (r, r_nan): (StatsAccumulator<T>, u64)| {
+        (l.merge(r), l_nan + r_nan)
+    };
+
+    let empty = || (StatsAccumulator::new(), 0);
+
+    if values.len() < PARALLEL_MIN_SIZE {
+        values.iter().copied().fold(empty(), push)
+    } else {
+        values
+            .par_iter()
+            .copied()
+            .fold(empty, push)
+            .reduce(empty, merge)
+    }
+}
+
+/// The distances between the corresponding elements of two float arrays, in units in the last place
+pub struct UlpDiff {
+    distances: ArrayBuf<u64>,
+    stats: StatsAccumulator<u64>,
+}
+
+impl UlpDiff {
+    /// Compute the distance in ULPs between each element of `left` and `right`.
+    /// The distance between two NaN values is zero, and between NaN and a number is [`u64::MAX`].
+    pub fn new<T, L, R>(left: &L, right: &R) -> Result<Self, Error>
+    where
+        T: Float,
+        L: NDArrayRead<DType = T>,
+        R: NDArrayRead<DType = T>,
+    {
+        if left.shape() != right.shape() {
+            return Err(Error::Bounds(format!(
+                "cannot compare arrays with shapes {:?} and {:?}",
+                left.shape(),
+                right.shape()
+            )));
+        }
+
+        let l = left.buffer()?.to_slice()?;
+        let r = right.buffer()?.to_slice()?;
+
+        let distances = l
+            .par_iter()
+            .zip(r.par_iter())
+            .map(|(l, r)| match (l.is_nan(), r.is_nan()) {
+                (true, true) => 0,
+                (false, false) => l.to_ordered_bits().abs_diff(r.to_ordered_bits()),
+                _ => u64::MAX,
+            })
+            .collect::<Vec<u64>>();
+
+        let mut stats = StatsAccumulator::new();
+        stats.update_slice(&distances);
+
+        let distances = ArrayBuf::new(distances.into(), left.shape())?;
+
+        Ok(Self { distances, stats })
+    }
+
+    /// Borrow the distance between each pair of elements.
+    pub fn distances(&self) -> &ArrayBuf<u64> {
+        &self.distances
+    }
+
+    /// Unwrap the distance between each pair of elements.
+    pub fn into_distances(self) -> ArrayBuf<u64> {
+        self.distances
+    }
+
+    /// Summary statistics of the distances.
+    pub fn stats(&self) -> &StatsAccumulator<u64> {
+        &self.stats
+    }
+
+    /// The maximum distance between any pair of elements.
+    pub fn max(&self) -> u64 {
+        self.stats.max().unwrap_or_default()
+    }
+}
@@ -0,0 +1,52 @@
+//! Streamed reductions
+
+use crate::access::Accessor;
+use crate::array::Array;
+use crate::{ArrayAccess, ArrayBuf, CType, Error, NDArray, Platform};
+
+/// Combine a stream of same-shaped `arrays`, such as per-file partial results, into one array
+/// by applying `op` to the running result and each next array in turn,
+/// e.g. `reduce_stream(partials, |acc, next| acc.add(next))`.
+///
+/// The running result is materialized on its platform after each step, so the op graph does not
+/// grow with the length of the stream and no concatenated buffer is ever constructed.
+/// A stream of a single array returns that array unchanged.
+pub fn reduce_stream<T, A, P, I, F, OA, OP>(arrays: I, mut op: F) -> Result<ArrayAccess<T>, Error>
+where
+    T: CType,
+    I: IntoIterator<Item = Array<T, A, P>>,
+    F: FnMut(ArrayAccess<T>, ArrayAccess<T>) -> Result<Array<T, OA, OP>, Error>,
+    Accessor<T>: From<A> + From<OA>,
+    Platform: From<P> + From<OP>,
+{
+    let mut arrays = arrays.into_iter().map(ArrayAccess::from);
+
+    let mut reduced = arrays
+        .next()
+        .ok_or_else(|| Error::Bounds("cannot reduce an empty stream of arrays".to_string()))?;
+
+    for (i, array) in arrays.enumerate() {
+        if array.shape() != reduced.shape() {
+            return Err(Error::Bounds(format!(
+                "array {} in a reduced stream has shape {:?} but expected {:?}",
+                i + 1,
+                array.shape(),
+                reduced.shape()
+            )));
+        }
+
+        let shape = reduced.shape().to_vec();
+        let next = ArrayAccess::from(op(reduced, array)?);
+
+        if next.shape() != shape {
+            return Err(Error::Bounds(format!(
+                "a stream reduction must preserve the shape {shape:?}, not {:?}",
+                next.shape()
+            )));
+        }
+
+        reduced = ArrayAccess::from(ArrayBuf::copy(&next)?);
+    }
+
+    Ok(reduced)
+}
@@ -0,0 +1,100 @@
+//! Ahead-of-time initialization, to keep one-time setup costs out of latency-critical code
+//!
+//! The first op executed on a platform can be much slower than the next, because it must
+//! initialize the platform's thread pool or device queues and (for OpenCL) compile its kernels.
+//! Call [`warmup`] on startup to pay these costs up front.
+
+use std::fmt;
+
+#[cfg(feature = "opencl")]
+use crate::opencl::OpenCL;
+use crate::platform::{Platform, PlatformInstance};
+use crate::{host, CType, Error};
+
+/// A category of array ops to prepare for execution
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum OpKind {
+    /// Array constructors, e.g. `range` and `random_uniform`
+    Construct,
+    /// Elementwise arithmetic, e.g. `add` and `mul`
+    Dual,
+    /// Elementwise comparison, e.g. `eq` and `lt`
+    Compare,
+    /// Elementwise unary math, e.g. `exp` and `ln`
+    Unary,
+    /// Elementwise trigonometry, e.g. `sin` and `tanh`
+    Trig,
+    /// Reductions, both along an axis and of all elements
+    Reduce,
+    /// Matrix multiplication
+    MatMul,
+    /// The in-place weighted accumulation `y ← y + αx`
+    Accumulate,
+}
+
+impl OpKind {
+    /// Every kind of op.
+    pub const ALL: [Self; 8] = [
+        Self::Construct,
+        Self::Dual,
+        Self::Compare,
+        Self::Unary,
+        Self::Trig,
+        Self::Reduce,
+        Self::MatMul,
+        Self::Accumulate,
+    ];
+}
+
+impl fmt::Display for OpKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Construct => "construct",
+            Self::Dual => "dual",
+            Self::Compare => "compare",
+            Self::Unary => "unary",
+            Self::Trig => "trig",
+            Self::Reduce => "reduce",
+            Self::MatMul => "matmul",
+            Self::Accumulate => "accumulate",
+        })
+    }
+}
+
+/// Prepare the platforms which would execute the given kinds of `ops` on data of type `T`
+/// with the given input `sizes`: start the host thread pool, create the OpenCL device queues,
+/// allocate a device buffer of each size, and compile the OpenCL kernels.
+///
+/// This is idempotent, and calling it again after the first time is cheap.
+pub fn warmup<T: CType>(ops: &[OpKind], sizes: &[usize]) -> Result<(), Error> {
+    #[cfg(feature = "opencl")]
+    let mut cl_sizes = Vec::with_capacity(sizes.len());
+
+    for size in sizes.iter().copied() {
+        match Platform::select(size) {
+            #[cfg(feature = "opencl")]
+            Platform::CL(_) if OpenCL::supports::<T>() => cl_sizes.push(size),
+            #[cfg(feature = "opencl")]
+            Platform::CL(_) => warmup_host(host::Host::select(size)),
+            Platform::Host(host) => warmup_host(host),
+        }
+    }
+
+    #[cfg(feature = "opencl")]
+    if !cl_sizes.is_empty() {
+        OpenCL::warmup::<T>(ops, &cl_sizes)?;
+    }
+
+    #[cfg(not(feature = "opencl"))]
+    let _ = ops;
+
+    Ok(())
+}
+
+#[inline]
+fn warmup_host(host: host::Host) {
+    if let host::Host::Heap(_) = host {
+        // the global thread pool is initialized on first use
+        rayon::current_num_threads();
+    }
+}
@@ -23,3 +23,425 @@ fn test_expand_and_broadcast_and_sub() -> Result<(), Error> {
     assert!(expected.eq(actual)?.all()?);
     Ok(())
 }
+
+#[test]
+fn test_implicit_broadcast() -> Result<(), Error> {
+    let left = ArrayOp::range(0, 6, shape![2, 3])?;
+    let right = ArrayBuf::new(vec![0, 1], shape![2, 1])?;
+
+    let expected = ArrayBuf::new(vec![0, 1, 2, 2, 3, 4], shape![2, 3])?;
+    let actual = left.sub(right)?;
+    assert_eq!(actual.shape(), &[2, 3]);
+    assert!(expected.eq(actual)?.all()?);
+
+    // both operands are broadcast into their common shape
+    assert_eq!(&*broadcast_shape(&[2, 1], &[3])?, &[2, 3]);
+    assert_eq!(&*broadcast_shape(&[4], &[5, 2, 1])?, &[5, 2, 4]);
+    assert!(broadcast_shape(&[2, 3], &[2]).is_err());
+
+    let column = ArrayBuf::new(vec![10, 20], shape![2, 1])?;
+    let row = ArrayBuf::new(vec![1, 2, 3], shape![3])?;
+    let actual = column.clone().add(row.clone())?;
+    assert_eq!(actual.shape(), &[2, 3]);
+    assert_eq!(&*actual.buffer()?.to_slice()?, &[11, 12, 13, 21, 22, 23]);
+    assert_eq!(actual.read_value(&[1, 2])?, 23);
+
+    let large = ArrayOp::range(0f64, 262_144., shape![256, 1024])?;
+    let offset = ArrayBuf::new(
+        (0..1024).map(|i| i as f64).collect::<Vec<_>>(),
+        shape![1024],
+    )?;
+    let actual = large.sub(offset)?;
+    let expected = ArrayOp::range(0f64, 256., shape![256, 1])?.mul_scalar(1024.)?;
+    assert!(actual.eq(expected.broadcast(shape![256, 1024])?)?.all()?);
+
+    assert!(column
+        .add(ArrayBuf::new(vec![1; 6], shape![3, 2])?)
+        .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_add_f64_large() -> Result<(), Error> {
+    let shape = shape![256, 1024];
+
+    let left = ArrayOp::range(0., 262_144., shape.clone())?;
+    let right = ArrayBuf::constant(0.5f64, shape.clone())?;
+
+    let actual = left.add(right)?;
+    let expected = ArrayOp::range(0.5, 262_144.5, shape)?;
+    assert!(expected.eq(actual)?.all()?);
+    Ok(())
+}
+
+#[test]
+fn test_add_mixed_platforms() -> Result<(), Error> {
+    let shape = shape![2, 3];
+
+    let left = host::ArrayBuf::new(vec![1, 2, 3, 4, 5, 6].into(), shape.clone())?;
+    let right = ArrayBuf::constant(1, shape.clone())?;
+
+    let actual = left.add(right)?;
+    let expected = host::ArrayBuf::new(vec![2, 3, 4, 5, 6, 7].into(), shape)?;
+    assert!(expected.eq(actual)?.all()?);
+    Ok(())
+}
+
+#[test]
+fn test_add_constant_scalar() -> Result<(), Error> {
+    let shape = shape![5, 2];
+
+    let left = ArrayOp::range(0, 10, shape.clone())?;
+    let right = Array::<_, AccessConst<_>>::scalar(1, shape.clone())?;
+    assert_eq!(right.read_value(&[4, 1])?, 1);
+
+    let actual = left.add(right)?;
+    let expected = ArrayOp::range(1, 11, shape)?;
+    assert!(expected.eq(actual)?.all()?);
+    Ok(())
+}
+
+#[test]
+fn test_accumulate() -> Result<(), Error> {
+    let shape = shape![3, 2];
+
+    let mut y = ArrayBuf::new(vec![1., 1., 1., 1., 1., 1.], shape.clone())?;
+    y.accumulate(ArrayOp::range(0., 6., shape.clone())?, 2.)?;
+    y.accumulate(ArrayBuf::constant(1., shape.clone())?, -0.5)?;
+
+    let expected = ArrayBuf::new(vec![0.5, 2.5, 4.5, 6.5, 8.5, 10.5], shape.clone())?;
+    assert!(expected.eq(y)?.all()?);
+
+    let mut y = host::ArrayBuf::new(vec![1, 2, 3, 4, 5, 6].into(), shape.clone())?;
+    y.accumulate(ArrayOp::range(0, 6, shape)?, 3)?;
+    assert_eq!(y.read_value(&[2, 1])?, 21);
+
+    let mismatched = ArrayOp::range(0, 4, shape![2, 2])?;
+    assert!(y.accumulate(mismatched, 1).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_softplus_and_logaddexp() -> Result<(), Error> {
+    let x = ArrayBuf::new(vec![-1000., -1., 0., 1., 1000.], shape![5])?;
+    let actual = x.clone().softplus()?.buffer()?.to_slice()?.into_vec();
+    let expected = [
+        0.,
+        1f64.exp().recip().ln_1p(),
+        2f64.ln(),
+        1. + 1f64.exp().recip().ln_1p(),
+        1000.,
+    ];
+
+    for (a, e) in actual.iter().zip(expected) {
+        assert!((a - e).abs() < 1e-12, "expected {e} but found {a}");
+    }
+
+    let y = ArrayBuf::new(vec![-1000., 2., 0., f64::NEG_INFINITY, 1000.], shape![5])?;
+    let actual = x.logaddexp(y)?.buffer()?.to_slice()?.into_vec();
+    let expected = [
+        -1000. + 2f64.ln(),
+        2. + (-3f64).exp().ln_1p(),
+        2f64.ln(),
+        1.,
+        1000. + 2f64.ln(),
+    ];
+
+    for (a, e) in actual.iter().zip(expected) {
+        assert!((a - e).abs() < 1e-9, "expected {e} but found {a}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_logcumsumexp() -> Result<(), Error> {
+    let data = vec![0f32, 1000., -1., 2., 1000., 3.];
+    let x = ArrayBuf::new(data.clone(), shape![2, 3])?;
+
+    let actual = x.clone().logcumsumexp(1)?;
+    assert_eq!(actual.shape(), &[2, 3]);
+
+    let naive = |values: &[f32]| {
+        let max = values.iter().copied().fold(f32::MIN, f32::max);
+        max + values.iter().map(|n| (n - max).exp()).sum::<f32>().ln()
+    };
+
+    let expected = [
+        naive(&data[..1]),
+        naive(&data[..2]),
+        naive(&data[..3]),
+        naive(&data[3..4]),
+        naive(&data[3..5]),
+        naive(&data[3..6]),
+    ];
+
+    let values = actual.buffer()?.to_slice()?.into_vec();
+    for (a, e) in values.iter().zip(expected) {
+        assert!(a.is_finite());
+        assert!((a - e).abs() < 1e-4, "expected {e} but found {a}");
+    }
+
+    assert!((actual.read_value(&[1, 1])? - expected[4]).abs() < 1e-4);
+
+    let actual = x.clone().logcumsumexp(0)?;
+    assert_eq!(actual.read_value(&[0, 2])?, -1.);
+    assert!((actual.read_value(&[1, 1])? - (1000. + 2f32.ln())).abs() < 1e-3);
+    assert!((actual.read_value(&[1, 0])? - naive(&[0., 2.])).abs() < 1e-4);
+
+    assert!(x.logcumsumexp(2).is_err());
+
+    let large = ArrayBuf::constant(0f64, shape![4, 100])?.logcumsumexp(1)?;
+    assert!((large.read_value(&[3, 99])? - 100f64.ln()).abs() < 1e-9);
+    let sums = large.buffer()?.to_slice()?.into_vec();
+    assert!((sums[399] - 100f64.ln()).abs() < 1e-9);
+
+    Ok(())
+}
+
+#[test]
+fn test_cumsum_cumprod() -> Result<(), Error> {
+    let x = ArrayBuf::new(vec![1, 2, 3, 4, 5, 6], shape![2, 3])?;
+
+    let cumsum = x.clone().cumsum(1)?;
+    assert_eq!(cumsum.shape(), &[2, 3]);
+    assert_eq!(&*cumsum.buffer()?.to_slice()?, &[1, 3, 6, 4, 9, 15]);
+    assert_eq!(cumsum.read_value(&[1, 2])?, 15);
+
+    let cumsum = x.clone().cumsum(0)?;
+    assert_eq!(&*cumsum.buffer()?.to_slice()?, &[1, 2, 3, 5, 7, 9]);
+
+    let cumprod = x.clone().cumprod(1)?;
+    assert_eq!(&*cumprod.buffer()?.to_slice()?, &[1, 2, 6, 4, 20, 120]);
+    assert_eq!(x.clone().cumprod(0)?.read_value(&[1, 2])?, 18);
+
+    assert!(x.cumsum(2).is_err());
+
+    // a single long sequence is scanned in parallel
+    let ones = ArrayBuf::constant(1u64, shape![100_000])?;
+    let totals = ones.cumsum(0)?.buffer()?.to_slice()?.into_vec();
+    assert!(totals.iter().zip(1..).all(|(total, i)| *total == i));
+
+    let ones = ArrayBuf::constant(1u32, shape![2, 3000, 3])?;
+    let totals = ones.cumsum(1)?;
+    assert_eq!(totals.read_value(&[1, 2999, 2])?, 3000);
+    let totals = totals.buffer()?.to_slice()?.into_vec();
+    assert!((0..totals.len()).all(|i| totals[i] == ((i / 3) % 3000) as u32 + 1));
+
+    // a scan of an empty array is empty
+    let empty = ArrayBuf::new(Vec::<f32>::new(), shape![0])?;
+    assert!(empty.clone().cumsum(0)?.buffer()?.to_slice()?.is_empty());
+    assert!(empty.logcumsumexp(0)?.buffer()?.to_slice()?.is_empty());
+
+    for (shape, axis) in [(shape![2, 0], 1), (shape![2, 0], 0), (shape![0, 2], 1)] {
+        let empty = ArrayBuf::new(Vec::<i32>::new(), shape)?;
+        assert!(empty.cumsum(axis)?.buffer()?.to_slice()?.is_empty());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_cdf_normalize() -> Result<(), Error> {
+    let x = ArrayBuf::new(vec![3i32, 1, 2, 2, 10, 20, 40, 30], shape![2, 4])?;
+
+    let cdf = x.clone().cdf_normalize(1)?;
+    assert_eq!(cdf.shape(), &[2, 4]);
+    assert_eq!(
+        &*cdf.buffer()?.to_slice()?,
+        &[1f32, 0.25, 0.75, 0.75, 0.25, 0.5, 1., 0.75]
+    );
+    assert_eq!(cdf.read_value(&[0, 2])?, 0.75);
+
+    let cdf = x.clone().cdf_normalize(0)?;
+    assert_eq!(
+        &*cdf.buffer()?.to_slice()?,
+        &[0.5f32, 0.5, 0.5, 0.5, 1., 1., 1., 1.]
+    );
+    assert_eq!(cdf.read_value(&[1, 0])?, 1.);
+
+    assert!(x.cdf_normalize(2).is_err());
+
+    for axis in [0, 1] {
+        let empty = ArrayBuf::new(Vec::<i32>::new(), shape![2, 0])?;
+        assert!(empty.cdf_normalize(axis)?.buffer()?.to_slice()?.is_empty());
+    }
+
+    // every lane of a larger array has the same distribution regardless of its scale
+    let n = 500;
+    let x = ArrayOp::range(0f64, (3 * n) as f64, shape![3, n])?;
+    let x = x.mul(ArrayBuf::new(vec![1., -2., 0.5], shape![3, 1])?.broadcast(shape![3, n])?)?;
+    let cdf = x.cdf_normalize(1)?.buffer()?.to_slice()?.into_vec();
+
+    for (i, p) in cdf.iter().enumerate() {
+        let rank = if i / n == 1 { n - (i % n) } else { (i % n) + 1 };
+        assert_eq!(*p, rank as f64 / n as f64);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_ternary() -> Result<(), Error> {
+    let x = ArrayOp::range(-3i32, 3, shape![2, 3])?;
+    let min = ArrayBuf::constant(-1, shape![2, 3])?;
+    let max = ArrayBuf::new(vec![0, 0, 0, 1, 1, 5], shape![2, 3])?;
+
+    let clamped = x.clamp(min, max)?;
+    assert_eq!(clamped.shape(), &[2, 3]);
+    assert_eq!(&*clamped.buffer()?.to_slice()?, &[-1, -1, -1, 0, 1, 2]);
+    assert_eq!(clamped.read_value(&[1, 2])?, 2);
+
+    let a = ArrayBuf::new(vec![1., 2., 3., 4.], shape![4])?;
+    let b = ArrayBuf::constant(2., shape![4])?;
+    let c = ArrayBuf::new(vec![0.5, 0.5, 1., 1.], shape![4])?;
+
+    let fused = a.clone().mul_add(b.clone(), c.clone())?;
+    assert_eq!(&*fused.buffer()?.to_slice()?, &[2.5, 4.5, 7., 9.]);
+    assert_eq!(fused.read_value(&[3])?, 9.);
+
+    assert!(a.mul_add(b, ArrayBuf::constant(1., shape![2, 2])?).is_err());
+
+    let n = 10_000;
+    let x = ArrayOp::range(0f64, n as f64, shape![n])?;
+    let ones = ArrayBuf::constant(1f64, shape![n])?;
+    let fused = x.mul_add(ones.clone(), ones)?;
+    assert_eq!(fused.read_value(&[n - 1])?, n as f64);
+    assert_eq!(fused.sum_all()?, (n * (n + 1) / 2) as f64);
+
+    Ok(())
+}
+
+#[test]
+fn test_interpolate() -> Result<(), Error> {
+    let from = ArrayBuf::new(vec![0., 10., -2., 4.], shape![4])?;
+    let to = ArrayBuf::new(vec![1., 20., 2., 4.], shape![4])?;
+    let t = ArrayBuf::new(vec![0.5, 0.25, 1., 0.], shape![4])?;
+
+    let lerp = from.clone().lerp(to, t)?;
+    assert_eq!(&*lerp.buffer()?.to_slice()?, &[0.5, 12.5, 2., 4.]);
+    assert_eq!(lerp.read_value(&[1])?, 12.5);
+
+    let x = ArrayBuf::new(vec![-1., 0., 0.5, 1., 2.], shape![5])?;
+    let edge0 = ArrayBuf::constant(0., shape![5])?;
+    let edge1 = ArrayBuf::constant(1., shape![5])?;
+
+    let smooth = x.clone().smoothstep(edge0, edge1)?;
+    assert_eq!(&*smooth.buffer()?.to_slice()?, &[0., 0., 0.5, 1., 1.]);
+
+    let threshold = ArrayBuf::constant(0.5, shape![1])?;
+    let step = x.step(threshold)?;
+    assert_eq!(&*step.buffer()?.to_slice()?, &[0., 0., 1., 1., 1.]);
+    assert_eq!(step.read_value(&[1])?, 0.);
+
+    let x = ArrayOp::range(0u32, 5, shape![5])?;
+    let step = x.step(ArrayBuf::constant(3u32, shape![5])?)?;
+    assert_eq!(&*step.buffer()?.to_slice()?, &[0, 0, 0, 1, 1]);
+
+    assert!(from
+        .lerp(
+            ArrayBuf::constant(0., shape![4])?,
+            ArrayBuf::constant(0., shape![2])?
+        )
+        .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_angles() -> Result<(), Error> {
+    use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+    let y = ArrayBuf::new(vec![1f64, 1., -1., -1., 0.], shape![5])?;
+    let x = ArrayBuf::new(vec![1., -1., -1., 1., -1.], shape![5])?;
+
+    let angles = y.clone().atan2(x.clone())?;
+    let expected = [FRAC_PI_4, 3. * FRAC_PI_4, -3. * FRAC_PI_4, -FRAC_PI_4, PI];
+    assert_eq!(&*angles.buffer()?.to_slice()?, &expected);
+    assert_eq!(angles.read_value(&[1])?, 3. * FRAC_PI_4);
+
+    let degrees = ArrayBuf::new(expected.to_vec(), shape![5])?.to_degrees()?;
+    let degrees = degrees.buffer()?.to_slice()?;
+    for (actual, expected) in degrees.iter().zip([45., 135., -135., -45., 180.]) {
+        assert!((actual - expected).abs() < 1e-12);
+    }
+
+    let radians = ArrayBuf::new(vec![90i32, 180], shape![2])?.to_radians()?;
+    assert_eq!(
+        &*radians.buffer()?.to_slice()?,
+        &[FRAC_PI_2 as f32, PI as f32]
+    );
+
+    let a = ArrayBuf::new(vec![3u32, 5, 8], shape![3])?;
+    let b = ArrayBuf::new(vec![4u32, 12, 15], shape![3])?;
+    let hypot = a.hypot(b)?;
+    assert_eq!(&*hypot.buffer()?.to_slice()?, &[5f32, 13., 17.]);
+
+    let large = ArrayBuf::constant(1e300f64, shape![2])?;
+    let hypot = large.clone().hypot(large)?;
+    assert!(hypot.read_value(&[0])?.is_finite());
+
+    assert!(y.atan2(ArrayBuf::constant(1., shape![4])?).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_complex() -> Result<(), Error> {
+    use ha_ndarray::complex;
+    use std::f64::consts::FRAC_PI_2;
+
+    let re = ArrayBuf::new(vec![3f64, 0., -1.], shape![3])?;
+    let im = ArrayBuf::new(vec![4f64, 2., 0.], shape![3])?;
+
+    let z = complex::complex(re, im)?;
+    assert_eq!(z.shape(), &[3, 2]);
+    assert_eq!(&*z.buffer()?.to_slice()?, &[3., 4., 0., 2., -1., 0.]);
+
+    let real = complex::real(z.clone())?;
+    assert_eq!(&*real.buffer()?.to_slice()?, &[3., 0., -1.]);
+    let imag = complex::imag(z.clone())?;
+    assert_eq!(&*imag.buffer()?.to_slice()?, &[4., 2., 0.]);
+
+    let (r, theta) = complex::polar(z.clone())?;
+    assert_eq!(&*r.buffer()?.to_slice()?, &[5., 2., 1.]);
+    assert_eq!(theta.read_value(&[1])?, FRAC_PI_2);
+    assert_eq!(theta.read_value(&[2])?, std::f64::consts::PI);
+
+    let roundtrip = complex::from_polar(r, theta)?;
+    let expected = z.buffer()?.to_slice()?;
+    for (actual, expected) in roundtrip.buffer()?.to_slice()?.iter().zip(expected.iter()) {
+        assert!((actual - expected).abs() < 1e-12);
+    }
+
+    assert!(complex::abs(ArrayBuf::constant(1f32, shape![2, 3])?).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_reciprocal_approx() -> Result<(), Error> {
+    let x = ArrayBuf::new(vec![-4f32, -0.5, 0., 1., 3., 1e6], shape![6])?;
+    let actual = x.reciprocal_approx()?.buffer()?.to_slice()?.into_vec();
+
+    // the reciprocal of floating-point zero is not clamped
+    assert_eq!(actual[2], f32::INFINITY);
+
+    for (a, e) in actual
+        .iter()
+        .zip([-0.25, -2., f32::INFINITY, 1., 1. / 3., 1e-6])
+    {
+        assert!(
+            *a == e || ((a - e) / e).abs() < 1e-6,
+            "expected {e} but found {a}"
+        );
+    }
+
+    let x = ArrayBuf::new(vec![-1, 0, 1, 2], shape![4])?;
+    let actual = x.reciprocal_approx()?;
+    assert_eq!(&*actual.buffer()?.to_slice()?, &[-1, 0, 1, 0]);
+
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use ha_ndarray::backend::{self, Backend, DualOp, ReduceOp, UnaryOp};
+use ha_ndarray::*;
+
+/// A backend which executes ops on the host and counts how many it has executed
+#[derive(Default)]
+struct Counter {
+    ops: AtomicUsize,
+}
+
+impl Backend<i64> for Counter {
+    fn name(&self) -> &str {
+        "counter"
+    }
+
+    fn accepts(&self, size: usize) -> bool {
+        size > 2
+    }
+
+    fn dual(&self, op: DualOp, left: &[i64], right: &[i64]) -> Result<Vec<i64>, Error> {
+        self.ops.fetch_add(1, Ordering::Relaxed);
+        Ok(left
+            .iter()
+            .zip(right)
+            .map(|(l, r)| op.apply(*l, *r))
+            .collect())
+    }
+
+    fn unary(&self, op: UnaryOp, input: &[i64]) -> Result<Vec<i64>, Error> {
+        self.ops.fetch_add(1, Ordering::Relaxed);
+        Ok(input.iter().map(|n| op.apply(*n)).collect())
+    }
+
+    fn reduce(&self, op: ReduceOp, input: &[i64]) -> Result<i64, Error> {
+        self.ops.fetch_add(1, Ordering::Relaxed);
+
+        match op {
+            ReduceOp::Sum => Ok(input.iter().sum()),
+            other => Err(Error::Unsupported(format!("{other:?}"))),
+        }
+    }
+}
+
+#[test]
+fn test_external_backend() -> Result<(), Error> {
+    let counter = Arc::new(Counter::default());
+    backend::register::<i64>(counter.clone());
+    assert_eq!(backend::registered::<i64>(), ["counter"]);
+    assert!(backend::registered::<f32>().is_empty());
+
+    let left = backend::ArrayBuf::new(vec![1i64, 2, 3, 4], shape![2, 2])?;
+    let right = backend::ArrayBuf::new(vec![-5, -6, -7, -8], shape![2, 2])?;
+
+    let sum = left.clone().add(right)?.abs()?;
+    assert_eq!(sum.buffer()?.to_slice()?.into_vec(), vec![4, 4, 4, 4]);
+    assert_eq!(counter.ops.load(Ordering::Relaxed), 2);
+
+    assert_eq!(left.clone().sum_all()?, 10);
+    assert_eq!(counter.ops.load(Ordering::Relaxed), 3);
+
+    // the backend does not accept small arrays, so these ops fall back to the host
+    let small = backend::ArrayBuf::new(vec![1i64, 2], shape![2])?;
+    assert_eq!(small.clone().mul(small.clone())?.read_value(&[1])?, 4);
+    assert_eq!(small.max_all()?, 2);
+    assert_eq!(counter.ops.load(Ordering::Relaxed), 3);
+
+    Ok(())
+}
@@ -88,3 +88,53 @@ fn test_ne() -> Result<(), Error> {
 
     Ok(())
 }
+
+#[test]
+fn test_mask_select_scalar() -> Result<(), Error> {
+    let mask = ArrayBuf::new(vec![0u8, 1, 1, 0, 1, 0], shape![2, 3])?;
+
+    let ones = mask.clone().into_dtype::<f32>()?;
+    assert_eq!(
+        ones.buffer()?.to_slice()?.as_ref(),
+        &[0., 1., 1., 0., 1., 0.]
+    );
+
+    let selected = mask.select_scalar(3i64, -1)?;
+    assert_eq!(selected.read_value(&[1, 0])?, -1);
+    assert_eq!(
+        selected.buffer()?.to_slice()?.as_ref(),
+        &[-1, 3, 3, -1, 3, -1]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_ulp_diff() -> Result<(), Error> {
+    let one = 1f32;
+    let next = f32::from_bits(one.to_bits() + 3);
+
+    let left = ArrayBuf::new(vec![one, 0., -0., f32::NAN, -1., 2.], shape![2, 3])?;
+    let right = ArrayBuf::new(
+        vec![next, -0., f32::MIN_POSITIVE, f32::NAN, -1., f32::NAN],
+        shape![2, 3],
+    )?;
+
+    let diff = left.ulp_diff(&right)?;
+    let distances = diff.distances().buffer()?.to_slice()?.into_vec();
+    assert_eq!(distances, vec![3, 0, 1 << 23, 0, 0, u64::MAX]);
+    assert_eq!(diff.max(), u64::MAX);
+    assert_eq!(diff.stats().count(), 6);
+    assert_eq!(diff.stats().min(), Some(0));
+
+    let small = ArrayBuf::new(vec![1e-300f64, -1e-300], shape![2])?;
+    let tiny = ArrayBuf::new(vec![1e-300f64, 1e-300], shape![2])?;
+    let diff = small.ulp_diff(&tiny)?;
+    assert_eq!(diff.distances().read_value(&[0])?, 0);
+    assert!(diff.distances().read_value(&[1])? > 0);
+
+    assert!(left
+        .ulp_diff(&ArrayBuf::new(vec![0f32; 6], shape![3, 2])?)
+        .is_err());
+    Ok(())
+}
@@ -0,0 +1,71 @@
+#![cfg(feature = "zstd")]
+
+use std::env;
+use std::path::PathBuf;
+
+use ha_ndarray::io::compress;
+use ha_ndarray::*;
+
+/// A directory in the temp dir which is removed when dropped, even if a test fails
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn join(&self, name: &str) -> PathBuf {
+        self.0.join(name)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+#[test]
+fn test_save_compressed() -> Result<(), Error> {
+    let dir = env::temp_dir().join(format!("densor-compress-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).expect("dir");
+    let dir = TempDir(dir);
+
+    let data = (0..200_000)
+        .map(|n| (n % 100) as f32 * 0.5)
+        .collect::<Vec<_>>();
+    let array = ArrayBuf::new(data.clone(), shape![400, 500])?;
+
+    let path = dir.join("array.zst");
+    array.save_compressed(&path)?;
+
+    let size = std::fs::metadata(&path).expect("metadata").len() as usize;
+    assert!(size < data.len() * std::mem::size_of::<f32>() / 10);
+
+    let loaded = compress::load::<f32, _>(&path)?;
+    assert_eq!(loaded.shape(), &[400, 500]);
+    assert_eq!(loaded.buffer()?.to_slice()?.into_vec(), data);
+
+    assert!(compress::load::<f64, _>(&path).is_err());
+
+    let small = ArrayOp::range(0i64, 6, shape![2, 3])?;
+    let path = dir.join("small.zst");
+    compress::save(&small, &path, 19)?;
+    let loaded = compress::load::<i64, _>(&path)?;
+    assert_eq!(
+        loaded.buffer()?.to_slice()?.into_vec(),
+        vec![0, 1, 2, 3, 4, 5]
+    );
+
+    // a corrupt shape must result in an error, not an overflow or a huge allocation
+    let bytes = std::fs::read(&path).expect("read");
+    let shape_start = 8 + 1 + i64::TYPE.len() + 8;
+    let corrupt = dir.join("corrupt.zst");
+
+    for shape in [[1u64 << 62, 8], [1 << 40, 1], [2, 2]] {
+        let mut bytes = bytes.clone();
+        bytes[shape_start..shape_start + 8].copy_from_slice(&shape[0].to_le_bytes());
+        bytes[shape_start + 8..shape_start + 16].copy_from_slice(&shape[1].to_le_bytes());
+        std::fs::write(&corrupt, bytes).expect("write");
+
+        assert!(compress::load::<i64, _>(&corrupt).is_err());
+    }
+
+    Ok(())
+}
@@ -22,3 +22,73 @@ fn test_cond() -> Result<(), Error> {
 
     Ok(())
 }
+
+#[test]
+fn test_mask_select_and_write() -> Result<(), Error> {
+    let array = ArrayBuf::new((0..12).collect::<Vec<i32>>(), shape![3, 4])?;
+    let mask = ArrayBuf::copy(&array.clone().rem_scalar(3)?.eq_scalar(0)?)?;
+
+    let selected = array.clone().mask_select(mask.clone())?;
+    assert_eq!(selected.shape(), &[4]);
+    assert_eq!(&*selected.buffer()?.to_slice()?, &[0, 3, 6, 9]);
+    assert_eq!(selected.read_value(&[2])?, 6);
+
+    let mut buffer = array.clone();
+    let values = ArrayBuf::new(vec![-1, -2, -3, -4], shape![4])?;
+    buffer.mask_write(mask.clone(), values)?;
+    assert_eq!(
+        &*buffer.buffer()?.to_slice()?,
+        &[-1, 1, 2, -2, 4, 5, -3, 7, 8, -4, 10, 11]
+    );
+
+    let too_few = ArrayBuf::new(vec![0, 0], shape![2])?;
+    assert!(buffer.mask_write(mask, too_few).is_err());
+
+    let none = ArrayBuf::constant(0u8, shape![3, 4])?;
+    let selected = array.clone().mask_select(none)?;
+    assert_eq!(selected.size(), 0);
+
+    let n = 100_000;
+    let large = ArrayBuf::new((0..n).map(|i| i as f64).collect::<Vec<_>>(), shape![n])?;
+    let mask = large.clone().gt_scalar((n - 10) as f64)?;
+    let selected = large.mask_select(mask)?;
+    assert_eq!(selected.size(), 9);
+    assert_eq!(selected.read_value(&[0])?, (n - 9) as f64);
+    assert_eq!(selected.buffer()?.to_slice()?[8], (n - 1) as f64);
+
+    assert!(array
+        .mask_select(ArrayBuf::constant(1u8, shape![12])?)
+        .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_bool() -> Result<(), Error> {
+    // any nonzero value is true, not only one
+    let x = Bool::new(ArrayBuf::new(vec![0i16, 2, -3, 0], shape![4])?)?;
+    let y = Bool::new(ArrayBuf::new(vec![0u16, 0, 1, 1], shape![4])?)?;
+    assert_eq!(x.shape(), &[4]);
+    assert_eq!(&*x.as_array().buffer()?.to_slice()?, &[0, 1, 1, 0]);
+
+    let and = x.clone().and(y.clone())?;
+    assert_eq!(&*and.as_array().buffer()?.to_slice()?, &[0, 0, 1, 0]);
+    assert!(and.clone().any()?);
+    assert!(!and.all()?);
+
+    let or = x.clone().or(y.clone())?;
+    assert_eq!(&*or.into_inner().buffer()?.to_slice()?, &[0, 1, 1, 1]);
+
+    let xor = x.clone().xor(y)?;
+    assert_eq!(&*xor.as_array().buffer()?.to_slice()?, &[0, 1, 0, 1]);
+
+    let then = ArrayBuf::new(vec![1i8, 2, 3, 4], shape![4])?;
+    let or_else = ArrayBuf::constant(-1i8, shape![4])?;
+    let selected = x.clone().not()?.cond(then, or_else)?;
+    assert_eq!(&*selected.buffer()?.to_slice()?, &[1, -1, -1, 4]);
+
+    let wrong_shape = ArrayBuf::constant(0i8, shape![2, 2])?;
+    assert!(x.cond(wrong_shape.clone(), wrong_shape).is_err());
+
+    Ok(())
+}
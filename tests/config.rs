@@ -0,0 +1,217 @@
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use ha_ndarray::config::{self, OomPolicy, PlatformPreference, ReduceOrder};
+use ha_ndarray::*;
+
+static CONFIG: Mutex<()> = Mutex::new(());
+
+/// Serializes the tests which change the global config,
+/// and calls `reset` when dropped, so that a failed test can't leave its setting enabled.
+struct ConfigGuard {
+    _lock: MutexGuard<'static, ()>,
+    reset: fn(),
+}
+
+impl ConfigGuard {
+    fn new(set: fn(), reset: fn()) -> Self {
+        let lock = CONFIG.lock().unwrap_or_else(PoisonError::into_inner);
+        set();
+        Self { _lock: lock, reset }
+    }
+}
+
+impl Drop for ConfigGuard {
+    fn drop(&mut self) {
+        (self.reset)()
+    }
+}
+
+#[test]
+fn test_platform_preference() -> Result<(), Error> {
+    assert_eq!(
+        "host".parse::<PlatformPreference>()?,
+        PlatformPreference::Host
+    );
+    assert_eq!(
+        " OpenCL".parse::<PlatformPreference>()?,
+        PlatformPreference::OpenCL
+    );
+    assert!("gpu".parse::<PlatformPreference>().is_err());
+
+    let _guard = ConfigGuard::new(
+        || config::set_platform(PlatformPreference::Host),
+        || config::set_platform(PlatformPreference::Auto),
+    );
+
+    assert_eq!(config::platform(), PlatformPreference::Host);
+
+    let array = ArrayOp::range(0, 100_000, shape![100_000])?;
+    assert!(introspect::plan(&array).platform.starts_with("Host"));
+    Ok(())
+}
+
+#[test]
+fn test_warmup() -> Result<(), Error> {
+    warmup::<f32>(&OpKind::ALL, &[8, 1_000, 100_000])?;
+    warmup::<u8>(&[OpKind::Dual, OpKind::Compare], &[])?;
+
+    let array = ArrayOp::range(0., 8., shape![2, 4])?;
+    assert_eq!(array.sum_all()?, 28.);
+    Ok(())
+}
+
+#[test]
+fn test_kernel_cache() {
+    fn dir() -> std::path::PathBuf {
+        std::env::temp_dir().join("densor-kernel-cache")
+    }
+
+    {
+        let _guard = ConfigGuard::new(
+            || config::set_kernel_cache(Some(dir())),
+            || config::set_kernel_cache(None),
+        );
+
+        assert_eq!(config::kernel_cache(), Some(dir()));
+    }
+
+    assert_eq!(config::kernel_cache(), None);
+}
+
+#[test]
+fn test_autotune() -> Result<(), Error> {
+    let _guard = ConfigGuard::new(
+        || config::set_autotune(false),
+        || config::set_autotune(true),
+    );
+
+    assert!(!config::autotune());
+
+    config::set_autotune(true);
+    assert!(config::autotune());
+
+    // tuned launch parameters must not change the result of an op
+    let left = ArrayOp::range(0u32, 1_001, shape![1_001])?;
+    let right = ArrayOp::range(0u32, 1_001, shape![1_001])?;
+    assert_eq!(left.add(right)?.sum_all()?, 1_001_000);
+    Ok(())
+}
+
+#[test]
+fn test_oom_policy() -> Result<(), Error> {
+    assert_eq!("Retry".parse::<OomPolicy>()?, OomPolicy::Retry);
+    assert!("spill".parse::<OomPolicy>().is_err());
+
+    {
+        let _guard = ConfigGuard::new(
+            || config::set_oom_policy(OomPolicy::Fail),
+            || config::set_oom_policy(OomPolicy::default()),
+        );
+
+        assert_eq!(config::oom_policy(), OomPolicy::Fail);
+    }
+
+    assert_eq!(config::oom_policy(), OomPolicy::Host);
+    Ok(())
+}
+
+#[test]
+fn test_reduce_order() -> Result<(), Error> {
+    assert_eq!(" Pairwise".parse::<ReduceOrder>()?, ReduceOrder::Pairwise);
+    assert!("kahan".parse::<ReduceOrder>().is_err());
+
+    // the order of the OpenCL reduce kernel: a tree with a stride of 32, 16, ..., 1 per group of 64
+    fn pairwise_sum(values: &[f32]) -> f32 {
+        let partials = values
+            .chunks(64)
+            .map(|group| {
+                let mut group = group.to_vec();
+                let mut stride = 32;
+                while stride > 0 {
+                    for b in 0..stride {
+                        if b + stride < group.len() {
+                            group[b] += group[b + stride];
+                        }
+                    }
+
+                    stride /= 2;
+                }
+
+                group[0]
+            })
+            .collect::<Vec<_>>();
+
+        if partials.len() == 1 {
+            partials[0]
+        } else {
+            pairwise_sum(&partials)
+        }
+    }
+
+    let guard = ConfigGuard::new(
+        || config::set_reduce_order(ReduceOrder::Pairwise),
+        || config::set_reduce_order(ReduceOrder::default()),
+    );
+
+    assert_eq!(config::reduce_order(), ReduceOrder::Pairwise);
+
+    for size in [1, 7, 64, 65, 4_097, 100_003] {
+        let data = (0..size)
+            .map(|i| ((i % 997) as f32).sqrt() * 1e-3)
+            .collect::<Vec<f32>>();
+
+        let expected = pairwise_sum(&data);
+        let array = ArrayBuf::new(data, shape![size])?;
+        assert_eq!(array.sum_all()?.to_bits(), expected.to_bits());
+    }
+
+    std::mem::drop(guard);
+    assert_eq!(config::reduce_order(), ReduceOrder::Fast);
+    Ok(())
+}
+
+#[test]
+fn test_fast_math() -> Result<(), Error> {
+    let data = (0..256)
+        .map(|i| ((i % 17) as f32).sqrt() - 2.)
+        .collect::<Vec<f32>>();
+
+    let left = ArrayBuf::new(data.clone(), shape![16, 16])?;
+    let right = ArrayBuf::new(
+        data.iter().rev().copied().collect::<Vec<_>>(),
+        shape![16, 16],
+    )?;
+    let precise = left.clone().matmul(right.clone())?;
+
+    // each op reads the setting when it's constructed
+    let (fast, fused) = {
+        let _guard = ConfigGuard::new(
+            || config::set_fast_math(true),
+            || config::set_fast_math(false),
+        );
+
+        assert!(config::fast_math());
+
+        let fast = left.clone().matmul(right)?;
+        let fused = left.clone().mul_add(left.clone(), left)?;
+        (fast, fused)
+    };
+
+    assert!(!config::fast_math());
+
+    let precise = precise.buffer()?.to_slice()?.into_vec();
+    let fast = fast.buffer()?.to_slice()?.into_vec();
+
+    for (p, f) in precise.into_iter().zip(fast) {
+        assert!((p - f).abs() <= 1e-4 * p.abs().max(1.), "{p} != {f}");
+    }
+
+    let fused = fused.buffer()?.to_slice()?.into_vec();
+
+    for (f, n) in fused.into_iter().zip(data) {
+        let e = (n * n) + n;
+        assert!((f - e).abs() <= 1e-5, "{f} != {e}");
+    }
+
+    Ok(())
+}
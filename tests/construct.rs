@@ -21,6 +21,33 @@ fn test_range() -> Result<(), Error> {
     Ok(())
 }
 
+#[test]
+fn test_scalar_conversion() -> Result<(), Error> {
+    assert_eq!(u8::try_from_f64(255.9)?, 255);
+    assert_eq!(i8::try_from_f64(-128.5)?, -128);
+    assert!(u8::try_from_f64(256.).is_err());
+    assert!(u8::try_from_f64(-1.).is_err());
+    assert!(i32::try_from_f64(f64::NAN).is_err());
+    assert!(u64::try_from_f64(2f64.powi(64)).is_err());
+    assert!(f32::try_from_f64(1e300).is_err());
+    assert_eq!(f32::try_from_f64(f64::INFINITY)?, f32::INFINITY);
+    assert!(f64::try_from_f64(f64::NAN)?.is_nan());
+
+    assert_eq!(u64::from_usize(usize::MAX), u64::MAX);
+    assert_eq!(i64::from_i64(i64::MIN), i64::MIN);
+    assert_eq!(f32::from_i64(-3), -3.);
+
+    // an integer range is exact even where its values cannot be represented by an f64
+    let start = (1i64 << 53) + 1;
+    for size in [4, 1_000] {
+        let range = ArrayOp::range(start, start + size as i64, shape![size])?;
+        let expected = (0..size as i64).map(|n| start + n).collect::<Vec<_>>();
+        assert_eq!(&*range.buffer()?.to_slice()?, expected.as_slice());
+    }
+
+    Ok(())
+}
+
 #[test]
 fn test_random_normal() -> Result<(), Error> {
     let size = 1_000_000;
@@ -44,3 +71,142 @@ fn test_random_uniform() -> Result<(), Error> {
 
     Ok(())
 }
+
+#[test]
+fn test_zeros_and_ones() -> Result<(), Error> {
+    let zeros = ArrayBuf::<f32, _>::zeros(shape![2, 3])?;
+    assert!(!zeros.clone().any()?);
+
+    let ones = ArrayBuf::<f32, _>::ones_like(&zeros)?;
+    assert_eq!(ones.shape(), zeros.shape());
+    assert!(ones.all()?);
+
+    let range = ArrayOp::range(0i32, 6, shape![3, 2])?;
+    let full = ArrayBuf::full_like(&range, 5)?;
+    assert_eq!(full.shape(), &[3, 2]);
+    assert_eq!(full.buffer()?.to_slice()?.as_ref(), &[5; 6]);
+
+    let zeros = ArrayBuf::zeros_like(&range)?;
+    assert!(zeros.eq(ArrayBuf::constant(0, shape![3, 2])?)?.all()?);
+
+    Ok(())
+}
+
+#[test]
+fn test_element_access() -> Result<(), Error> {
+    let mut array = ArrayBuf::new(vec![0, 1, 2, 3, 4, 5], shape![2, 3])?;
+    assert_eq!(array.at(&[1, 2]).get()?, 5);
+    assert!(array.at(&[2, 0]).get().is_err());
+
+    array.at_mut(&[0, 1]).set(7)?;
+    assert_eq!(array[[0, 1]], 7);
+
+    array[[1, 0]] = 9;
+    assert_eq!(array.read_value(&[1, 0])?, 9);
+
+    let mut array = host::ArrayBuf::new(vec![0.; 4].into(), shape![2, 2])?;
+    array[&[1, 1][..]] = 1.5;
+    assert_eq!(array.at(&[1, 1]).get()?, 1.5);
+
+    Ok(())
+}
+
+#[test]
+fn test_content_hash() -> Result<(), Error> {
+    let data = (0..12).collect::<Vec<i32>>();
+
+    let buffer = ArrayBuf::new(data.clone(), shape![3, 4])?;
+    let op = ArrayOp::range(0, 12, shape![3, 4])?;
+    let host = host::ArrayBuf::new(data.clone().into(), shape![3, 4])?;
+    assert_eq!(buffer.content_hash()?, op.content_hash()?);
+    assert_eq!(buffer.content_hash()?, host.content_hash()?);
+
+    let reshaped = ArrayBuf::new(data.clone(), shape![4, 3])?;
+    assert_ne!(buffer.content_hash()?, reshaped.content_hash()?);
+
+    let cast = ArrayBuf::new(
+        data.iter().map(|n| *n as u32).collect::<Vec<_>>(),
+        shape![3, 4],
+    )?;
+    assert_ne!(buffer.content_hash()?, cast.content_hash()?);
+
+    let mut changed = data;
+    changed[11] = 0;
+    let changed = ArrayBuf::new(changed, shape![3, 4])?;
+    assert_ne!(buffer.content_hash()?, changed.content_hash()?);
+
+    // the hash is stable across processes and releases
+    let bytes = ArrayBuf::new(vec![1u8, 2, 3], shape![3])?;
+    assert_eq!(bytes.content_hash()?, 8697646034497922567);
+
+    Ok(())
+}
+
+#[test]
+fn test_le_be_bytes() -> Result<(), Error> {
+    let data = vec![1u32, 0x01020304, u32::MAX, 7];
+    let array = ArrayBuf::new(data.clone(), shape![2, 2])?;
+
+    let le = array.to_le_bytes()?;
+    assert_eq!(&le[4..8], &[4, 3, 2, 1]);
+
+    let be = array.to_be_bytes()?;
+    assert_eq!(&be[4..8], &[1, 2, 3, 4]);
+
+    // construct an array from an unaligned slice
+    let mut unaligned = vec![0u8];
+    unaligned.extend_from_slice(&le);
+    let from_le = ArrayBuf::<u32, _>::from_le_bytes(&unaligned[1..], shape![2, 2])?;
+    assert_eq!(from_le.buffer()?.to_slice()?.into_vec(), data);
+
+    let from_be = ArrayBuf::<u32, _>::from_be_bytes(&be, shape![2, 2])?;
+    assert_eq!(from_be.buffer()?.to_slice()?.into_vec(), data);
+
+    let floats = ArrayBuf::new(vec![0.5f64, -2.], shape![2])?;
+    let bytes = floats.to_be_bytes()?;
+    assert_eq!(&bytes[..8], &0.5f64.to_be_bytes());
+    let round_trip = ArrayBuf::<f64, _>::from_be_bytes(&bytes, shape![2])?;
+    assert_eq!(round_trip.buffer()?.to_slice()?.into_vec(), vec![0.5, -2.]);
+
+    assert!(ArrayBuf::<u32, _>::from_le_bytes(&le[1..], shape![2, 2]).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_convert_size_mismatch() -> Result<(), Error> {
+    let data = [0f32; 6];
+
+    let cause = ArrayBuf::<f32, Buffer<f32>>::convert(&data[..], shape![2, 4]).unwrap_err();
+    let message = cause.to_string();
+    assert!(message.contains("Array::convert"));
+    assert!(message.contains("shape [2, 4] (size 8) from a buffer of size 6"));
+
+    let cause = ArrayBuf::new(data.to_vec(), shape![]).unwrap_err();
+    assert!(cause.to_string().contains("Array::new"));
+
+    let array = ArrayBuf::<f32, Buffer<f32>>::convert(&data[..], shape![2, 3])?;
+    assert_eq!(array.shape(), &[2, 3]);
+
+    Ok(())
+}
+
+#[test]
+fn test_read_into() -> Result<(), Error> {
+    let array = ArrayBuf::new((0..6).map(|n| n as f32).collect::<Vec<_>>(), shape![2, 3])?;
+    let doubled = array.mul_scalar(2.)?;
+
+    let mut output = [0f32; 6];
+    doubled.read_into(&mut output)?;
+    assert_eq!(output, [0., 2., 4., 6., 8., 10.]);
+
+    // the output slice is reused rather than reallocated
+    let shifted = doubled.add_scalar(1.)?;
+    shifted.read_into(&mut output)?;
+    assert_eq!(output, [1., 3., 5., 7., 9., 11.]);
+
+    let mut short = vec![0f32; 5];
+    assert!(shifted.read_into(&mut short).is_err());
+
+    Ok(())
+}
@@ -0,0 +1,562 @@
+use ha_ndarray::*;
+
+#[test]
+fn test_conv_spec() -> Result<(), Error> {
+    let spec = ConvSpec::<2>::new();
+    assert_eq!(
+        spec.output_shape(&[2, 3, 8, 8], &[4, 3, 3, 3])?.as_slice(),
+        &[2, 4, 6, 6]
+    );
+
+    let spec = ConvSpec::<2>::new()
+        .with_stride([2, 2])
+        .with_padding([1, 1])
+        .with_dilation([2, 1]);
+
+    assert_eq!(
+        spec.output_shape(&[1, 3, 9, 8], &[6, 3, 3, 3])?.as_slice(),
+        &[1, 6, 4, 4]
+    );
+
+    // each of two groups of four input channels produces three of six output channels
+    let grouped = ConvSpec::<1>::new().with_groups(2);
+    assert_eq!(
+        grouped.output_shape(&[1, 8, 16], &[6, 4, 5])?.as_slice(),
+        &[1, 6, 12]
+    );
+    assert!(grouped.output_shape(&[1, 8, 16], &[6, 8, 5]).is_err());
+    assert!(grouped.output_shape(&[1, 8, 16], &[5, 4, 5]).is_err());
+
+    let depthwise = ConvSpec::<2>::depthwise(8);
+    assert_eq!(depthwise.groups(), 8);
+    assert_eq!(
+        depthwise
+            .output_shape(&[1, 8, 5, 5], &[8, 1, 3, 3])?
+            .as_slice(),
+        &[1, 8, 3, 3]
+    );
+
+    assert!(ConvSpec::<2>::new()
+        .output_shape(&[1, 3, 2, 2], &[1, 3, 3, 3])
+        .is_err());
+    assert!(ConvSpec::<2>::new()
+        .output_shape(&[1, 3, 8], &[1, 3, 3])
+        .is_err());
+    assert!(ConvSpec::<2>::new()
+        .with_stride([0, 1])
+        .output_shape(&[1, 3, 8, 8], &[1, 3, 3, 3])
+        .is_err());
+
+    Ok(())
+}
+
+fn conv_transpose2d_reference(
+    input: &[i32],
+    kernel: &[i32],
+    input_shape: [usize; 4],
+    kernel_shape: [usize; 4],
+    output_shape: &[usize],
+    spec: &ConvSpec<2>,
+) -> Vec<i32> {
+    let [batch, in_channels, height, width] = input_shape;
+    let [_, group_out, k_height, k_width] = kernel_shape;
+    let (o_height, o_width) = (output_shape[2], output_shape[3]);
+    let out_channels = output_shape[1];
+    let group_in = in_channels / spec.groups();
+
+    let mut output = vec![0; output_shape.iter().product()];
+
+    // scatter each input element across the output window of the kernel
+    for b in 0..batch {
+        for c_in in 0..in_channels {
+            let group = c_in / group_in;
+
+            for (iy, ix) in (0..height).flat_map(|y| (0..width).map(move |x| (y, x))) {
+                let n = input[(((b * in_channels) + c_in) * height + iy) * width + ix];
+
+                for c in 0..group_out {
+                    for (ky, kx) in (0..k_height).flat_map(|y| (0..k_width).map(move |x| (y, x))) {
+                        let oy = (iy * spec.stride()[0] + ky * spec.dilation()[0]) as isize
+                            - spec.padding()[0] as isize;
+
+                        let ox = (ix * spec.stride()[1] + kx * spec.dilation()[1]) as isize
+                            - spec.padding()[1] as isize;
+
+                        if oy < 0 || ox < 0 || oy as usize >= o_height || ox as usize >= o_width {
+                            continue;
+                        }
+
+                        let c_out = (group * group_out) + c;
+                        let o = (((b * out_channels) + c_out) * o_height + oy as usize) * o_width
+                            + ox as usize;
+
+                        let k = (((c_in * group_out) + c) * k_height + ky) * k_width + kx;
+                        output[o] += n * kernel[k];
+                    }
+                }
+            }
+        }
+    }
+
+    output
+}
+
+#[test]
+fn test_conv_transpose2d() -> Result<(), Error> {
+    let cases = [
+        ([1, 1, 2, 2], [1, 1, 2, 2], ConvSpec::new()),
+        (
+            [2, 2, 3, 3],
+            [2, 3, 3, 3],
+            ConvSpec::new().with_stride([2, 2]).with_padding([1, 1]),
+        ),
+        (
+            [1, 4, 3, 4],
+            [4, 1, 2, 3],
+            ConvSpec::new()
+                .with_groups(2)
+                .with_stride([2, 1])
+                .with_dilation([1, 2])
+                .with_output_padding([1, 0]),
+        ),
+        (
+            [2, 3, 4, 4],
+            [3, 1, 3, 3],
+            ConvSpec::depthwise(3)
+                .with_stride([3, 3])
+                .with_padding([1, 0]),
+        ),
+    ];
+
+    for (input_shape, kernel_shape, spec) in cases {
+        let input = (0..input_shape.iter().product::<usize>() as i32)
+            .map(|n| (n % 7) - 3)
+            .collect::<Vec<_>>();
+
+        let kernel = (0..kernel_shape.iter().product::<usize>() as i32)
+            .map(|n| (n % 5) - 2)
+            .collect::<Vec<_>>();
+
+        let x = ArrayBuf::new(input.to_vec(), input_shape)?;
+        let k = ArrayBuf::new(kernel.to_vec(), kernel_shape)?;
+
+        let actual = x.conv_transpose2d(k, spec)?;
+        let expected_shape = spec.transpose_output_shape(&input_shape, &kernel_shape)?;
+        assert_eq!(actual.shape(), expected_shape.as_slice());
+
+        let expected = conv_transpose2d_reference(
+            &input,
+            &kernel,
+            input_shape,
+            kernel_shape,
+            &expected_shape,
+            &spec,
+        );
+
+        assert_eq!(&*actual.buffer()?.to_slice()?, expected.as_slice());
+    }
+
+    // a stride of 2 doubles the spatial dimensions, less the kernel overlap
+    let spec = ConvSpec::new().with_stride([2, 2]);
+    assert_eq!(
+        spec.transpose_output_shape(&[1, 3, 4, 5], &[3, 8, 2, 2])?
+            .as_slice(),
+        &[1, 8, 8, 10]
+    );
+
+    // the kernel must have one row per input channel
+    let x = ArrayBuf::new(vec![0; 8], shape![1, 2, 2, 2])?;
+    let k = ArrayBuf::new(vec![0; 12], shape![3, 1, 2, 2])?;
+    assert!(x.conv_transpose2d(k, ConvSpec::new()).is_err());
+
+    // the output padding must be less than the stride
+    let x = ArrayBuf::new(vec![0; 8], shape![1, 2, 2, 2])?;
+    let k = ArrayBuf::new(vec![0; 8], shape![2, 1, 2, 2])?;
+    let spec = ConvSpec::new().with_output_padding([1, 1]);
+    assert!(x.conv_transpose2d(k, spec).is_err());
+
+    Ok(())
+}
+
+fn conv2d_reference(
+    input: &[i32],
+    kernel: &[i32],
+    input_shape: [usize; 4],
+    kernel_shape: [usize; 4],
+    output_shape: &[usize],
+    spec: &ConvSpec<2>,
+) -> Vec<i32> {
+    let [_, in_channels, height, width] = input_shape;
+    let [_, group_in, k_height, k_width] = kernel_shape;
+    let [batch, out_channels, o_height, o_width] = [
+        output_shape[0],
+        output_shape[1],
+        output_shape[2],
+        output_shape[3],
+    ];
+
+    let group_out = out_channels / spec.groups();
+
+    let mut output = Vec::with_capacity(output_shape.iter().product());
+
+    for b in 0..batch {
+        for c_out in 0..out_channels {
+            let group = c_out / group_out;
+
+            for (oy, ox) in (0..o_height).flat_map(|y| (0..o_width).map(move |x| (y, x))) {
+                let mut sum = 0;
+
+                for c in 0..group_in {
+                    let c_in = (group * group_in) + c;
+
+                    for (ky, kx) in (0..k_height).flat_map(|y| (0..k_width).map(move |x| (y, x))) {
+                        let iy = (oy * spec.stride()[0] + ky * spec.dilation()[0]) as isize
+                            - spec.padding()[0] as isize;
+
+                        let ix = (ox * spec.stride()[1] + kx * spec.dilation()[1]) as isize
+                            - spec.padding()[1] as isize;
+
+                        if iy < 0 || ix < 0 || iy as usize >= height || ix as usize >= width {
+                            continue;
+                        }
+
+                        let i = (((b * in_channels) + c_in) * height + iy as usize) * width
+                            + ix as usize;
+
+                        let k = (((c_out * group_in) + c) * k_height + ky) * k_width + kx;
+                        sum += input[i] * kernel[k];
+                    }
+                }
+
+                output.push(sum);
+            }
+        }
+    }
+
+    output
+}
+
+#[test]
+fn test_conv2d() -> Result<(), Error> {
+    let cases = [
+        ([1, 1, 3, 3], [1, 1, 2, 2], ConvSpec::new()),
+        (
+            [2, 3, 5, 5],
+            [4, 3, 3, 3],
+            ConvSpec::new().with_stride([2, 2]).with_padding([1, 1]),
+        ),
+        (
+            [1, 4, 6, 7],
+            [6, 2, 2, 3],
+            ConvSpec::new()
+                .with_groups(2)
+                .with_stride([2, 1])
+                .with_dilation([1, 2]),
+        ),
+        (
+            [2, 3, 4, 4],
+            [3, 1, 3, 3],
+            ConvSpec::depthwise(3).with_padding([1, 0]),
+        ),
+        // large enough to lower the input into a matrix and multiply on the heap
+        (
+            [3, 4, 17, 19],
+            [8, 2, 3, 3],
+            ConvSpec::new().with_groups(2).with_padding([2, 1]),
+        ),
+    ];
+
+    for (input_shape, kernel_shape, spec) in cases {
+        let input = (0..input_shape.iter().product::<usize>() as i32)
+            .map(|n| (n % 7) - 3)
+            .collect::<Vec<_>>();
+
+        let kernel = (0..kernel_shape.iter().product::<usize>() as i32)
+            .map(|n| (n % 5) - 2)
+            .collect::<Vec<_>>();
+
+        let x = ArrayBuf::new(input.to_vec(), input_shape)?;
+        let k = ArrayBuf::new(kernel.to_vec(), kernel_shape)?;
+
+        let actual = x.conv2d(k, spec)?;
+        let expected_shape = spec.output_shape(&input_shape, &kernel_shape)?;
+        assert_eq!(actual.shape(), expected_shape.as_slice());
+
+        let expected = conv2d_reference(
+            &input,
+            &kernel,
+            input_shape,
+            kernel_shape,
+            &expected_shape,
+            &spec,
+        );
+
+        let last = expected_shape.iter().map(|dim| dim - 1).collect::<Vec<_>>();
+        assert_eq!(actual.read_value(&last)?, expected[expected.len() - 1]);
+
+        assert_eq!(&*actual.buffer()?.to_slice()?, expected.as_slice());
+    }
+
+    // the kernel must have one column per input channel in each group
+    let x = ArrayBuf::new(vec![0; 8], shape![1, 2, 2, 2])?;
+    let k = ArrayBuf::new(vec![0; 12], shape![1, 3, 2, 2])?;
+    assert!(x.conv2d(k, ConvSpec::new()).is_err());
+
+    // the kernel must be 4D
+    let x = ArrayBuf::new(vec![0; 8], shape![1, 2, 2, 2])?;
+    let k = ArrayBuf::new(vec![0; 4], shape![2, 2])?;
+    assert!(x.conv2d(k, ConvSpec::new()).is_err());
+
+    Ok(())
+}
+
+fn conv3d_reference(
+    input: &[i32],
+    kernel: &[i32],
+    input_shape: [usize; 5],
+    kernel_shape: [usize; 5],
+    output_shape: [usize; 5],
+    spec: &ConvSpec<3>,
+) -> Vec<i32> {
+    let [_, in_channels, depth, height, width] = input_shape;
+    let [_, group_in, k_depth, k_height, k_width] = kernel_shape;
+    let [batch, out_channels, o_depth, o_height, o_width] = output_shape;
+
+    let group_out = out_channels / spec.groups();
+    let source = |axis: usize, o: usize, k: usize| {
+        (o * spec.stride()[axis] + k * spec.dilation()[axis]) as isize
+            - spec.padding()[axis] as isize
+    };
+
+    let mut output = Vec::with_capacity(output_shape.iter().product());
+
+    for b in 0..batch {
+        for c_out in 0..out_channels {
+            let group = c_out / group_out;
+
+            for o in 0..(o_depth * o_height * o_width) {
+                let (oz, oy, ox) = (
+                    o / (o_height * o_width),
+                    (o / o_width) % o_height,
+                    o % o_width,
+                );
+                let mut sum = 0;
+
+                for c in 0..group_in {
+                    let c_in = (group * group_in) + c;
+
+                    for k in 0..(k_depth * k_height * k_width) {
+                        let (kz, ky, kx) = (
+                            k / (k_height * k_width),
+                            (k / k_width) % k_height,
+                            k % k_width,
+                        );
+                        let (iz, iy, ix) =
+                            (source(0, oz, kz), source(1, oy, ky), source(2, ox, kx));
+
+                        if iz < 0 || iy < 0 || ix < 0 {
+                            continue;
+                        }
+
+                        let (iz, iy, ix) = (iz as usize, iy as usize, ix as usize);
+
+                        if iz >= depth || iy >= height || ix >= width {
+                            continue;
+                        }
+
+                        let i =
+                            ((((b * in_channels) + c_in) * depth + iz) * height + iy) * width + ix;
+                        sum += input[i]
+                            * kernel[((c_out * group_in) + c) * k_depth * k_height * k_width + k];
+                    }
+                }
+
+                output.push(sum);
+            }
+        }
+    }
+
+    output
+}
+
+#[test]
+fn test_conv1d() -> Result<(), Error> {
+    let cases = [
+        ([1, 1, 5], [1, 1, 3], ConvSpec::new()),
+        (
+            [2, 4, 40],
+            [6, 2, 5],
+            ConvSpec::new()
+                .with_groups(2)
+                .with_stride([3])
+                .with_padding([2])
+                .with_dilation([2]),
+        ),
+    ];
+
+    for (input_shape, kernel_shape, spec) in cases {
+        let input = (0..input_shape.iter().product::<usize>() as i32)
+            .map(|n| (n % 7) - 3)
+            .collect::<Vec<_>>();
+
+        let kernel = (0..kernel_shape.iter().product::<usize>() as i32)
+            .map(|n| (n % 5) - 2)
+            .collect::<Vec<_>>();
+
+        let x = ArrayBuf::new(input.to_vec(), input_shape)?;
+        let k = ArrayBuf::new(kernel.to_vec(), kernel_shape)?;
+
+        let actual = x.conv1d(k, spec)?;
+        let expected_shape = spec.output_shape(&input_shape, &kernel_shape)?;
+        assert_eq!(actual.shape(), expected_shape.as_slice());
+
+        let lift = |[n, c, len]: [usize; 3]| [n, c, 1, 1, len];
+
+        let expected = conv3d_reference(
+            &input,
+            &kernel,
+            lift(input_shape),
+            lift(kernel_shape),
+            lift([expected_shape[0], expected_shape[1], expected_shape[2]]),
+            &ConvSpec::new()
+                .with_groups(spec.groups())
+                .with_stride([1, 1, spec.stride()[0]])
+                .with_padding([0, 0, spec.padding()[0]])
+                .with_dilation([1, 1, spec.dilation()[0]]),
+        );
+
+        assert_eq!(&*actual.buffer()?.to_slice()?, expected.as_slice());
+    }
+
+    // a 1D convolution requires a 3D input
+    let x = ArrayBuf::new(vec![0; 8], shape![1, 2, 2, 2])?;
+    let k = ArrayBuf::new(vec![0; 4], shape![1, 2, 2])?;
+    assert!(x.conv1d(k, ConvSpec::new()).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_conv3d() -> Result<(), Error> {
+    let cases = [
+        ([1, 1, 2, 3, 3], [1, 1, 2, 2, 2], ConvSpec::new()),
+        (
+            [2, 2, 5, 4, 6],
+            [4, 1, 3, 2, 3],
+            ConvSpec::new()
+                .with_groups(2)
+                .with_stride([2, 1, 2])
+                .with_padding([1, 1, 0])
+                .with_dilation([1, 2, 1]),
+        ),
+    ];
+
+    for (input_shape, kernel_shape, spec) in cases {
+        let input = (0..input_shape.iter().product::<usize>() as i32)
+            .map(|n| (n % 7) - 3)
+            .collect::<Vec<_>>();
+
+        let kernel = (0..kernel_shape.iter().product::<usize>() as i32)
+            .map(|n| (n % 5) - 2)
+            .collect::<Vec<_>>();
+
+        let x = ArrayBuf::new(input.to_vec(), input_shape)?;
+        let k = ArrayBuf::new(kernel.to_vec(), kernel_shape)?;
+
+        let actual = x.conv3d(k, spec)?;
+        let expected_shape = spec.output_shape(&input_shape, &kernel_shape)?;
+        assert_eq!(actual.shape(), expected_shape.as_slice());
+
+        let expected = conv3d_reference(
+            &input,
+            &kernel,
+            input_shape,
+            kernel_shape,
+            expected_shape.as_slice().try_into().expect("5D shape"),
+            &spec,
+        );
+
+        let last = expected_shape.iter().map(|dim| dim - 1).collect::<Vec<_>>();
+        assert_eq!(actual.read_value(&last)?, expected[expected.len() - 1]);
+
+        assert_eq!(&*actual.buffer()?.to_slice()?, expected.as_slice());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_matched_filter() -> Result<(), Error> {
+    let signal = ArrayBuf::new(
+        vec![
+            0f32, 1., 2., 0., 0., 2., 4., 0., 3., 0., 0., 0., -1., -2., 0., 0.,
+        ],
+        shape![2, 8],
+    )?;
+
+    let template = ArrayBuf::new(vec![1f32, 2., 0.], shape![3])?;
+
+    let matched = signal.matched_filter(template.clone())?;
+    assert_eq!(matched.shape(), &[2, 6]);
+
+    let actual = matched.buffer()?.to_slice()?;
+    let r = 0.2f32.sqrt();
+    let expected = [0.4, 1., r, 0., 0.4, 1., r, 0., 0., -0.4, -1., -r];
+
+    for (actual, expected) in actual.iter().zip(expected) {
+        assert!((actual - expected).abs() < 1e-6, "{actual} != {expected}");
+    }
+
+    assert_eq!(matched.read_value(&[0, 1])?, 1.);
+
+    // a longer signal with a template longer than a single work group
+    let signal = ArrayOp::range(0., 512., shape![512])?;
+    let template = ArrayBuf::constant(1f64, shape![100])?;
+    let matched = signal.matched_filter(template.clone())?;
+    assert_eq!(matched.shape(), &[413]);
+    assert!(matched
+        .buffer()?
+        .to_slice()?
+        .iter()
+        .all(|n| *n > 0. && *n <= 1.));
+
+    let short = ArrayBuf::constant(1f64, shape![50])?;
+    assert!(short.matched_filter(template).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_upsample() -> Result<(), Error> {
+    let x = ArrayBuf::new(vec![1, 2, 3, 4], shape![1, 2, 2])?;
+    let actual = x.upsample([2, 3], Interpolation::Nearest)?;
+    assert_eq!(actual.shape(), &[1, 4, 6]);
+
+    #[rustfmt::skip]
+    let expected = [
+        1, 1, 1, 2, 2, 2,
+        1, 1, 1, 2, 2, 2,
+        3, 3, 3, 4, 4, 4,
+        3, 3, 3, 4, 4, 4,
+    ];
+
+    assert_eq!(&*actual.buffer()?.to_slice()?, expected.as_slice());
+
+    let x = ArrayBuf::new(vec![0., 4., 8., 12.], shape![2, 1, 2])?;
+    let actual = x.upsample([1, 2], Interpolation::Bilinear)?;
+    assert_eq!(actual.shape(), &[2, 1, 4]);
+
+    // the edges are clamped and the interior is interpolated between element centers
+    let expected = [0., 1., 3., 4., 8., 9., 11., 12.];
+    assert_eq!(&*actual.buffer()?.to_slice()?, expected.as_slice());
+
+    let x = ArrayBuf::new(vec![0f32, 2., 4., 6.], shape![2, 2])?;
+    let actual = x.upsample([2, 2], Interpolation::Bilinear)?;
+    assert_eq!(actual.read_value(&[1, 1])?, 1.5);
+    assert_eq!(actual.read_value(&[3, 3])?, 6.);
+
+    let x = ArrayBuf::new(vec![1, 2, 3], shape![3])?;
+    assert!(x.upsample([2, 2], Interpolation::Nearest).is_err());
+
+    Ok(())
+}
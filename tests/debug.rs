@@ -0,0 +1,35 @@
+use ha_ndarray::config::{self, DebugChecks};
+use ha_ndarray::*;
+
+#[test]
+fn test_debug_checks() -> Result<(), Error> {
+    assert_eq!("1".parse::<DebugChecks>()?, DebugChecks::Size);
+    assert_eq!(" NaN".parse::<DebugChecks>()?, DebugChecks::NonFinite);
+    assert!("all".parse::<DebugChecks>().is_err());
+
+    config::set_debug_checks(DebugChecks::NonFinite);
+    assert_eq!(config::debug_checks(), DebugChecks::NonFinite);
+
+    let finite = ArrayBuf::new(vec![1f32, 2., 4.], shape![3])?;
+    assert_eq!(finite.ln()?.buffer()?.to_slice()?.as_ref()[0], 0.);
+
+    // the error names the op which first produced a non-finite value, not the ops which read it
+    let x = ArrayBuf::new(vec![1f32, 0., 4.], shape![3])?;
+    let message = match x.ln()?.mul_scalar(2.)?.buffer() {
+        Ok(_) => panic!("expected a non-finite value error"),
+        Err(cause) => cause.to_string(),
+    };
+
+    assert!(message.contains("non-finite value -inf at offset 1"));
+    assert!(message.contains("Unary"));
+
+    // integer outputs are not scanned
+    let x = ArrayBuf::new(vec![1u32, 0, 4], shape![3])?;
+    assert_eq!(x.sum_all()?, 5);
+
+    config::set_debug_checks(DebugChecks::Off);
+    let x = ArrayBuf::new(vec![1f32, 0., 4.], shape![3])?;
+    assert!(x.ln()?.buffer()?.to_slice()?.as_ref()[1].is_infinite());
+
+    Ok(())
+}
@@ -0,0 +1,229 @@
+//! Differential tests which run each op on the host and on OpenCL over randomized inputs,
+//! and check that the results agree within a tolerance appropriate to their data type
+
+#![cfg(feature = "opencl")]
+
+use std::fmt;
+
+use rand::Rng;
+
+use ha_ndarray::opencl::OpenCL;
+use ha_ndarray::*;
+
+const TRIALS: usize = 8;
+
+trait Tolerance: CType + fmt::Display {
+    fn random<R: Rng>(rng: &mut R) -> Self;
+
+    fn close(expected: Self, actual: Self) -> bool;
+}
+
+macro_rules! tolerance_int {
+    ($($t:ty),+) => {
+        $(
+            impl Tolerance for $t {
+                fn random<R: Rng>(rng: &mut R) -> Self {
+                    rng.gen_range(1..16)
+                }
+
+                fn close(expected: Self, actual: Self) -> bool {
+                    expected == actual
+                }
+            }
+        )+
+    };
+}
+
+macro_rules! tolerance_float {
+    ($($t:ty: $tol:expr),+) => {
+        $(
+            impl Tolerance for $t {
+                fn random<R: Rng>(rng: &mut R) -> Self {
+                    rng.gen_range(0.5..8.)
+                }
+
+                fn close(expected: Self, actual: Self) -> bool {
+                    if expected.is_nan() || actual.is_nan() {
+                        expected.is_nan() && actual.is_nan()
+                    } else if expected == actual {
+                        true
+                    } else {
+                        let scale = expected.abs().max(actual.abs()).max(1.);
+                        (expected - actual).abs() <= $tol * scale
+                    }
+                }
+            }
+        )+
+    };
+}
+
+tolerance_int!(u8, i32, u32, i64);
+tolerance_float!(f32: 1e-4, f64: 1e-10);
+
+/// Run the given op on the host and on OpenCL with the same random inputs and compare the results.
+macro_rules! differential {
+    ($t:ty, $shape:expr, |$($arg:ident),+| $op:expr) => {{
+        let shape: Shape = $shape;
+        differential!($t, $($arg = shape.clone());+ => $op)
+    }};
+    ($t:ty, $($arg:ident = $shape:expr);+ => $op:expr) => {{
+        let mut rng = rand::thread_rng();
+        let shapes: Vec<Shape> = vec![$($shape),+];
+        let mut inputs = shapes.iter();
+        $(
+            let $arg = {
+                let shape = inputs.next().expect("shape").clone();
+                (random::<$t, _>(&mut rng, shape.iter().product()), shape)
+            };
+        )+
+
+        let expected = {
+            $(let $arg = host::ArrayBuf::new($arg.0.clone().into(), $arg.1.clone())?;)+
+            $op?
+        };
+
+        let actual = {
+            $(let $arg = opencl::ArrayBuf::new(OpenCL::copy_into_buffer(&$arg.0)?, $arg.1)?;)+
+            $op?
+        };
+
+        let label = format!("{} with inputs {:?}", stringify!($op), shapes);
+        assert_close(&label, &expected, &actual);
+    }};
+}
+
+fn random<T: Tolerance, R: Rng>(rng: &mut R, size: usize) -> Vec<T> {
+    (0..size).map(|_| T::random(rng)).collect()
+}
+
+fn random_shape() -> Shape {
+    let mut rng = rand::thread_rng();
+    let ndim = rng.gen_range(1..=4);
+    (0..ndim).map(|_| rng.gen_range(1..=16)).collect()
+}
+
+fn read<A: NDArrayRead>(array: A) -> Result<Vec<A::DType>, Error> {
+    array
+        .buffer()
+        .and_then(|buffer| buffer.to_slice())
+        .map(|slice| slice.into_vec())
+}
+
+fn assert_close<T: Tolerance>(label: &str, expected: &[T], actual: &[T]) {
+    assert_eq!(expected.len(), actual.len(), "{label}");
+
+    for (i, (e, a)) in expected.iter().zip(actual).enumerate() {
+        assert!(
+            T::close(*e, *a),
+            "{label} at offset {i}: host computed {e} but OpenCL computed {a}",
+        );
+    }
+}
+
+macro_rules! test_dual {
+    ($t:ty) => {{
+        for _ in 0..TRIALS {
+            differential!($t, random_shape(), |l, r| l.add(r).and_then(read));
+            differential!($t, random_shape(), |l, r| l.sub(r).and_then(read));
+            differential!($t, random_shape(), |l, r| l.mul(r).and_then(read));
+            differential!($t, random_shape(), |l, r| l.div(r).and_then(read));
+            differential!($t, random_shape(), |l, r| l.eq(r).and_then(read));
+            differential!($t, random_shape(), |l, r| l.lt(r).and_then(read));
+            differential!($t, random_shape(), |l, r| l.ge(r).and_then(read));
+        }
+    }};
+}
+
+#[test]
+fn test_dual() -> Result<(), Error> {
+    test_dual!(f32);
+    test_dual!(f64);
+    test_dual!(i32);
+    test_dual!(i64);
+    Ok(())
+}
+
+macro_rules! test_unary {
+    ($t:ty) => {{
+        for _ in 0..TRIALS {
+            differential!($t, random_shape(), |x| x.exp().and_then(read));
+            differential!($t, random_shape(), |x| x.ln().and_then(read));
+            differential!($t, random_shape(), |x| x.round().and_then(read));
+            differential!($t, random_shape(), |x| x.sin().and_then(read));
+            differential!($t, random_shape(), |x| x.cos().and_then(read));
+            differential!($t, random_shape(), |x| x.tanh().and_then(read));
+        }
+    }};
+}
+
+#[test]
+fn test_unary() -> Result<(), Error> {
+    test_unary!(f32);
+    test_unary!(f64);
+    Ok(())
+}
+
+macro_rules! test_reduce {
+    ($t:ty) => {{
+        for _ in 0..TRIALS {
+            differential!($t, random_shape(), |x| x.sum_all().map(|sum| vec![sum]));
+            differential!($t, random_shape(), |x| x.max_all().map(|max| vec![max]));
+            differential!($t, random_shape(), |x| x.min_all().map(|min| vec![min]));
+        }
+    }};
+}
+
+#[test]
+fn test_reduce() -> Result<(), Error> {
+    test_reduce!(f32);
+    test_reduce!(f64);
+    test_reduce!(i64);
+    test_reduce!(u32);
+    Ok(())
+}
+
+macro_rules! test_transform {
+    ($t:ty) => {{
+        for _ in 0..TRIALS {
+            differential!($t, random_shape(), |x| x.transpose(None).and_then(read));
+            differential!($t, random_shape(), |x| {
+                NDArrayCast::<f32>::cast(x).and_then(read)
+            });
+        }
+    }};
+}
+
+#[test]
+fn test_transform() -> Result<(), Error> {
+    test_transform!(f32);
+    test_transform!(f64);
+    test_transform!(i32);
+    test_transform!(u8);
+    Ok(())
+}
+
+macro_rules! test_matmul {
+    ($t:ty) => {{
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..TRIALS {
+            let batch = rng.gen_range(1..=4);
+            let [a, b, c] = [(); 3].map(|()| rng.gen_range(1..=32));
+
+            differential!(
+                $t,
+                l = shape![batch, a, b];
+                r = shape![batch, b, c] =>
+                l.matmul(r).and_then(read)
+            );
+        }
+    }};
+}
+
+#[test]
+fn test_matmul() -> Result<(), Error> {
+    test_matmul!(f32);
+    test_matmul!(f64);
+    test_matmul!(i32);
+    Ok(())
+}
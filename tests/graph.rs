@@ -0,0 +1,108 @@
+use ha_ndarray::*;
+
+#[test]
+fn test_graph_replay() -> Result<(), Error> {
+    let x = Placeholder::<f32>::new(shape![2, 3])?;
+    let w = Placeholder::<f32>::new(shape![3, 2])?;
+
+    let output = x.array().matmul(w.array())?.add_scalar(1.)?;
+    let graph = Graph::capture(output);
+    assert_eq!(graph.shape(), &[2, 2]);
+
+    // a graph cannot be replayed until every placeholder is bound
+    assert!(graph.replay().is_err());
+
+    w.bind(vec![1., 0., 0., 1., 1., 1.])?;
+
+    for i in 0..3 {
+        let data = (0..6).map(|n| (n + i) as f32).collect::<Vec<_>>();
+        x.bind(data)?;
+
+        let expected = match i {
+            0 => [3., 4., 9., 10.],
+            1 => [5., 6., 11., 12.],
+            _ => [7., 8., 13., 14.],
+        };
+
+        let actual = graph.replay()?;
+        assert_eq!(actual.shape(), &[2, 2]);
+        assert_eq!(&*actual.buffer()?.to_slice()?, &expected);
+    }
+
+    assert!(x.bind(vec![0.; 5]).is_err());
+
+    x.unbind();
+    assert!(graph.replay().is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_graph_def() -> Result<(), Error> {
+    use ha_ndarray::backend::{DualOp, ReduceOp, UnaryOp};
+
+    let mut def = GraphDef::<f32>::new();
+    let x = def.push(GraphNode::Input(shape![2, 3]))?;
+    let w = def.push(GraphNode::Input(shape![3, 2]))?;
+    let y = def.push(GraphNode::MatMul(x, w))?;
+    let y = def.push(GraphNode::Scalar(DualOp::Sub, y, 5.))?;
+    let y = def.push(GraphNode::Unary(UnaryOp::Abs, y))?;
+    let y = def.push(GraphNode::Slice(
+        y,
+        slice![AxisRange::Span(Some(-1), None, 1)],
+    ))?;
+    def.push(GraphNode::Reduce(ReduceOp::Sum, y, axes![1], false))?;
+
+    // a node can only read an earlier node
+    assert!(def.push(GraphNode::Transpose(9, None)).is_err());
+
+    let bytes = def.encode();
+    let loaded = GraphDef::<f32>::decode(&bytes)?;
+    assert_eq!(loaded, def);
+    assert_eq!(loaded.input_shapes(), vec![&[2, 3][..], &[3, 2][..]]);
+
+    assert!(GraphDef::<f64>::decode(&bytes).is_err());
+    assert!(GraphDef::<f32>::decode(&bytes[..bytes.len() - 1]).is_err());
+
+    let x = ArrayBuf::new((0..6).map(|n| n as f32).collect::<Vec<_>>(), shape![2, 3])?;
+    let w = ArrayBuf::new(vec![1., 0., 0., 1., 1., 1.], shape![3, 2])?;
+
+    // [[2, 3], [8, 9]] - 5 = [[-3, -2], [3, 4]], the last row of which sums to 7
+    let output = loaded.load(vec![ArrayAccess::from(x), ArrayAccess::from(w.clone())])?;
+    assert_eq!(output.shape(), &[1]);
+    assert_eq!(&*output.buffer()?.to_slice()?, &[7.]);
+
+    assert!(loaded.load(vec![ArrayAccess::from(w.clone())]).is_err());
+
+    let (inputs, graph) = loaded.compile()?;
+    inputs[0].bind(vec![1.; 6])?;
+    inputs[1].bind(vec![1., 0., 0., 1., 1., 1.])?;
+    assert_eq!(&*graph.replay()?.buffer()?.to_slice()?, &[6.]);
+
+    Ok(())
+}
+
+#[test]
+fn test_execute() -> Result<(), Error> {
+    let x = Placeholder::<u32>::new(shape![3])?;
+    let w = Placeholder::<u32>::new(shape![3])?;
+    let graph = Graph::capture(x.array().mul(w.array())?);
+
+    // a weight can be bound once, and the input fed to each execution
+    w.bind(vec![1, 2, 3])?;
+
+    let output = execute(&graph, vec![(&x, vec![4, 5, 6])])?;
+    assert_eq!(&*output.buffer()?.to_slice()?, &[4, 10, 18]);
+
+    // a feed overrides a binding only for one execution
+    let output = execute(&graph, vec![(&x, vec![1, 1, 1]), (&w, vec![0, 0, 7])])?;
+    assert_eq!(&*output.buffer()?.to_slice()?, &[0, 0, 7]);
+    assert!(execute(&graph, Vec::<(&Placeholder<u32>, Vec<u32>)>::new()).is_err());
+
+    x.bind(vec![2, 2, 2])?;
+    assert_eq!(&*graph.replay()?.buffer()?.to_slice()?, &[2, 4, 6]);
+
+    assert!(execute(&graph, vec![(&x, vec![1, 2])]).is_err());
+
+    Ok(())
+}
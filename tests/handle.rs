@@ -0,0 +1,39 @@
+use std::sync::mpsc;
+use std::thread;
+
+use ha_ndarray::*;
+
+#[test]
+fn test_handle_send() -> Result<(), Error> {
+    let (send, recv) = mpsc::channel();
+
+    let array = ArrayOp::range(0, 6, shape![2, 3])?.add(ArrayBuf::constant(1, shape![2, 3])?)?;
+    send.send(ArrayHandle::new(array)).expect("send");
+
+    let sum = thread::spawn(move || {
+        let handle = recv.recv().expect("recv");
+        assert_eq!(handle.shape(), &[2, 3]);
+        handle.into_inner().sum_all()
+    })
+    .join()
+    .expect("thread")?;
+
+    assert_eq!(sum, 21);
+    Ok(())
+}
+
+#[test]
+fn test_handle_materialize() -> Result<(), Error> {
+    let data = vec![1., 2., 3., 4.];
+    let borrowed = ArrayBuf::new(data.as_slice(), shape![2, 2])?;
+    let handle = ArrayHandle::materialize(&borrowed)?;
+    drop(data);
+
+    let handle = thread::spawn(move || handle.clone())
+        .join()
+        .expect("thread");
+
+    assert_eq!(handle.read_value(&[1, 0])?, 3.);
+    assert_eq!(handle.into_inner().sum_all()?, 10.);
+    Ok(())
+}
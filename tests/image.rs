@@ -0,0 +1,25 @@
+#![cfg(feature = "image")]
+
+use ha_ndarray::io::image::{self, ImageFormat};
+use ha_ndarray::*;
+
+#[test]
+fn test_image_roundtrip() -> Result<(), Error> {
+    let data = (0..24).map(|n| n * 10).collect::<Vec<u8>>();
+    let array = host::ArrayBuf::new(data.clone().into(), shape![2, 4, 3])?;
+
+    let png = image::encode(&array, ImageFormat::Png)?;
+    let decoded = image::decode(&png)?;
+    assert_eq!(decoded.shape(), &[2, 4, 3]);
+    assert_eq!(decoded.buffer()?.to_slice()?.into_vec(), data);
+
+    let gray = host::ArrayBuf::new(vec![128u8; 64].into(), shape![8, 8, 1])?;
+    let jpeg = image::encode(&gray, ImageFormat::Jpeg(90))?;
+    assert_eq!(image::decode(&jpeg)?.shape(), &[8, 8, 1]);
+
+    let invalid = host::ArrayBuf::new(vec![0u8; 10].into(), shape![1, 2, 5])?;
+    assert!(image::encode(&invalid, ImageFormat::Png).is_err());
+    assert!(image::decode(&[0, 1, 2, 3]).is_err());
+
+    Ok(())
+}
@@ -0,0 +1,157 @@
+use ha_ndarray::*;
+
+#[test]
+fn test_plan() -> Result<(), Error> {
+    let left = ArrayBuf::new(vec![1f32; 6], shape![2, 3])?;
+    let right = ArrayBuf::new(vec![2f32; 6], shape![2, 3])?;
+    let sum = left.add(right)?.exp()?;
+
+    let plan = introspect::plan(&sum);
+    assert_eq!(plan.shape.as_slice(), &[2, 3]);
+    assert_eq!(plan.dtype, f32::TYPE);
+    assert_eq!(plan.op_count(), 2);
+    assert_eq!(plan.memory_required(), 2 * 6 * std::mem::size_of::<f32>());
+
+    assert_eq!(plan.root.name, "Unary");
+    assert_eq!(plan.root.platform, Some("host"));
+    assert_eq!(plan.root.device, Some("stack"));
+    assert_eq!(plan.root.inputs.len(), 1);
+
+    let dual = &plan.root.inputs[0];
+    assert_eq!(dual.name, "Dual");
+    assert_eq!(dual.inputs.len(), 2);
+    assert!(dual.inputs.iter().all(|input| input.is_buffer()));
+
+    assert!(plan.to_string().contains("Dual"));
+
+    Ok(())
+}
+
+#[test]
+fn test_error_breadcrumb() -> Result<(), Error> {
+    let left = ArrayBuf::new(vec![1f32; 6], shape![2, 3])?;
+    let right = ArrayBuf::new(vec![2f32; 6], shape![2, 3])?;
+    let sum = left.add(right)?;
+    let other = ArrayBuf::new(vec![1f32; 6], shape![3, 2])?;
+
+    let cause = sum.mul(other).expect_err("shape mismatch");
+    let message = cause.to_string();
+    assert!(message.contains("left: [2, 3] produced by Dual[6](buffer[6], buffer[6])"));
+    assert!(message.contains("right: [3, 2] produced by buffer[6]"));
+
+    Ok(())
+}
+
+#[test]
+fn test_metadata() -> Result<(), Error> {
+    let weights = ArrayBuf::new(vec![1f32; 64 * 128], shape![64, 128])?
+        .with_name("weights")
+        .with_axis_labels(["in", "out"])?;
+
+    assert_eq!(weights.name(), Some("weights"));
+    assert!(format!("{weights:?}").contains("\"weights\""));
+    assert!(format!("{weights:?}").contains("[64 (in), 128 (out)]"));
+
+    let labels = ArrayBuf::new(vec![1f32; 2], shape![2])?.with_axis_labels(["x", "y"]);
+    assert!(labels.is_err());
+
+    let transposed = weights.clone().transpose(None)?;
+    assert_eq!(transposed.name(), Some("weights"));
+    assert_eq!(transposed.meta().axis_label(0), Some("out"));
+    assert_eq!(transposed.meta().axis_label(1), Some("in"));
+
+    let sum = weights.clone().sum(axes![0], false)?;
+    assert_eq!(sum.meta().axis_label(0), Some("out"));
+
+    let scaled = weights.clone().mul_scalar(2.)?;
+    assert_eq!(scaled.name(), Some("weights"));
+
+    let reshaped = weights.clone().reshape(shape![128, 64])?;
+    assert_eq!(reshaped.name(), Some("weights"));
+    assert_eq!(reshaped.meta().axis_label(0), None);
+
+    let inputs = ArrayBuf::new(vec![1f32; 64 * 128], shape![64, 128])?
+        .with_name("inputs")
+        .with_axis_labels(["batch", "features"])?;
+
+    let cause = inputs
+        .matmul(weights)
+        .expect_err("shape mismatch")
+        .to_string();
+    assert!(
+        cause.contains("\"inputs\" [64 (batch), 128 (features)]"),
+        "{cause}"
+    );
+    assert!(
+        cause.contains("\"weights\" [64 (in), 128 (out)]"),
+        "{cause}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_named_axes() -> Result<(), Error> {
+    let array = ArrayBuf::new(
+        (0..24).map(|n| n as f32).collect::<Vec<_>>(),
+        shape![2, 3, 4],
+    )?
+    .with_axis_labels(["time", "batch", "channel"])?;
+
+    assert_eq!(array.axes_by(&["channel", "time"])?.as_slice(), &[2, 0]);
+    assert!(array.axes_by(&["height"]).is_err());
+    assert!(array.axes_by(&["time", "time"]).is_err());
+
+    let transposed = array.clone().transpose_by(&["batch", "channel", "time"])?;
+    let expected = array.clone().transpose(Some(axes![1, 2, 0]))?;
+    assert_eq!(transposed.shape(), &[3, 4, 2]);
+    assert_eq!(transposed.meta().axis_label(2), Some("time"));
+    assert_eq!(
+        &*transposed.buffer()?.to_slice()?,
+        &*expected.buffer()?.to_slice()?
+    );
+
+    assert!(array.clone().transpose_by(&["batch", "channel"]).is_err());
+
+    let sum = array.clone().sum_by(&["time"], false)?;
+    let expected = array.clone().sum(axes![0], false)?;
+    assert_eq!(sum.shape(), &[3, 4]);
+    assert_eq!(sum.meta().axis_label(0), Some("batch"));
+    assert_eq!(
+        &*sum.buffer()?.to_slice()?,
+        &*expected.buffer()?.to_slice()?
+    );
+
+    let max = array.max_by(&["batch", "channel"], true)?;
+    assert_eq!(max.shape(), &[2, 1, 1]);
+    assert_eq!(max.buffer()?.to_slice()?.as_ref(), &[11., 23.]);
+
+    let duplicate = ArrayBuf::new(vec![0f32; 4], shape![2, 2])?.with_axis_labels(["x", "x"]);
+    assert!(duplicate.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_expect_shape() -> Result<(), Error> {
+    let array = ArrayBuf::new(vec![1f32; 12], shape![3, 4])?.with_name("weights");
+
+    let array = array
+        .expect_shape(&[Some(3), None])?
+        .expect_dtype("float")?
+        .expect_same_shape(&ArrayBuf::new(vec![0f32; 12], shape![3, 4])?)?;
+
+    let cause = array.clone().expect_shape(&[None, Some(3)]).unwrap_err();
+    assert!(cause.to_string().contains("[_, 3]"));
+    assert!(cause.to_string().contains("weights"));
+
+    assert!(array.clone().expect_shape(&[Some(3)]).is_err());
+    assert!(array.clone().expect_square().is_err());
+    assert!(array.clone().expect_dtype("ulong").is_err());
+
+    let square = array.matmul(ArrayBuf::new(vec![1f32; 12], shape![4, 3])?)?;
+    let square = square.expect_square()?;
+    assert_eq!(&*square.buffer()?.to_slice()?, &[4.; 9]);
+
+    Ok(())
+}
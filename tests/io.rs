@@ -0,0 +1,207 @@
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+
+use ha_ndarray::io::loader::{Loader, Options};
+use ha_ndarray::io::text;
+use ha_ndarray::*;
+
+/// A file in the temp dir which is removed when dropped, even if a test fails
+struct TempFile(PathBuf);
+
+impl Deref for TempFile {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for TempFile {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+fn temp_file(name: &str, contents: &[u8]) -> TempFile {
+    let path = std::env::temp_dir().join(format!("densor-{}-{name}", std::process::id()));
+    fs::write(&path, contents).expect("write");
+    TempFile(path)
+}
+
+fn read<A: NDArrayRead>(array: A) -> Result<Vec<A::DType>, Error> {
+    array.buffer()?.to_slice().map(|slice| slice.into_vec())
+}
+
+#[test]
+fn test_load_csv() -> Result<(), Error> {
+    let path = temp_file("load.csv", b"a; b; c\n1; 2; 3\n\n4; 5.5; 6\n7; 8; 9\n");
+
+    let options = Options {
+        rows_per_chunk: 2,
+        delimiter: ';',
+        header: true,
+        ..Default::default()
+    };
+
+    let chunks = Loader::<f32>::csv(&path, options)?.collect::<Result<Vec<_>, Error>>()?;
+    assert_eq!(chunks.len(), 2);
+    assert_eq!(chunks[0].shape(), &[2, 3]);
+    assert_eq!(chunks[1].shape(), &[1, 3]);
+
+    let data = chunks
+        .into_iter()
+        .map(read)
+        .collect::<Result<Vec<_>, Error>>()?;
+    assert_eq!(data.concat(), vec![1., 2., 3., 4., 5.5, 6., 7., 8., 9.]);
+
+    let path = temp_file("ragged.csv", b"1,2\n3\n");
+    let mut loader = Loader::<i32>::csv(&path, Options::default())?;
+    assert!(loader.next().expect("chunk").is_err());
+    assert!(loader.next().is_none());
+
+    assert!(Loader::<i32>::csv(path.with_extension("missing"), Options::default()).is_err());
+
+    // a value which does not fit in the dtype is an error rather than wrapping around
+    let path = temp_file("overflow.csv", b"255,256\n");
+    let mut loader = Loader::<u8>::csv(&path, Options::default())?;
+    assert!(loader.next().expect("chunk").is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_load_binary() -> Result<(), Error> {
+    let data = (0..30u16).collect::<Vec<_>>();
+    let bytes = data
+        .iter()
+        .flat_map(|n| n.to_ne_bytes())
+        .collect::<Vec<u8>>();
+    let path = temp_file("load.bin", &bytes);
+
+    let options = Options {
+        rows_per_chunk: 4,
+        ..Default::default()
+    };
+
+    let mut total = 0;
+    for chunk in Loader::<u16>::binary(&path, 3, options)? {
+        let chunk = chunk?;
+        assert_eq!(chunk.shape()[1], 3);
+        total += chunk.sum_all()? as usize;
+    }
+
+    assert_eq!(total, data.iter().map(|n| *n as usize).sum::<usize>());
+
+    let path = temp_file("partial.bin", &bytes[..7]);
+    let mut loader = Loader::<u16>::binary(&path, 2, options)?;
+    assert!(loader.next().expect("chunk").is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_csv_round_trip() -> Result<(), Error> {
+    let array = ArrayBuf::new((0..15).map(|n| n as f32 * 0.5).collect::<Vec<_>>(), [5, 3])?;
+
+    let mut csv = Vec::new();
+    text::write_csv(&array, &mut csv, ';')?;
+    assert!(csv.starts_with(b"0;0.5;1\n1.5;2;2.5\n"));
+
+    let path = temp_file("round_trip.csv", &csv);
+    let options = Options {
+        rows_per_chunk: 2,
+        delimiter: ';',
+        ..Default::default()
+    };
+
+    let loaded = text::read_csv::<f32, _>(&path, options)?;
+    assert_eq!(loaded.shape(), &[5, 3]);
+    assert_eq!(read(loaded)?, read(array)?);
+
+    let vector = ArrayBuf::new(vec![1, 2, 3], [3])?;
+    assert!(text::write_csv(&vector, &mut Vec::new(), ',').is_err());
+
+    for shape in [[0, 3], [3, 0]] {
+        let empty = ArrayBuf::new(Vec::<f32>::new(), shape)?;
+        assert!(text::write_csv(&empty, &mut Vec::new(), ',').is_err());
+        assert!(text::write_mtx(&empty, &mut Vec::new()).is_err());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_matrix_market() -> Result<(), Error> {
+    let mtx = b"%%MatrixMarket matrix coordinate real general
+% a comment
+3 4 4
+1 1 1.5
+3 4 -2
+2 2 1e1
+1 1 0.5
+";
+
+    let matrix = text::read_mtx::<f64, _>(&mtx[..])?;
+    assert_eq!(matrix.shape(), &[3, 4]);
+    assert_eq!(
+        read(matrix)?,
+        vec![2., 0., 0., 0., 0., 10., 0., 0., 0., 0., 0., -2.]
+    );
+
+    let mtx = b"%%MatrixMarket matrix coordinate pattern symmetric\n2 2 2\n1 1\n2 1\n";
+    let matrix = text::read_mtx::<u8, _>(&mtx[..])?;
+    assert_eq!(read(matrix)?, vec![1, 1, 1, 0]);
+
+    // the lower triangle of a skew-symmetric matrix, in column-major order
+    let mtx = b"%%MatrixMarket matrix array integer skew-symmetric\n3 3\n1\n2\n3\n";
+    let matrix = text::read_mtx::<i32, _>(&mtx[..])?;
+    assert_eq!(read(matrix)?, vec![0, -1, -2, 1, 0, -3, 2, 3, 0]);
+    assert!(text::read_mtx::<u32, _>(&mtx[..]).is_err());
+
+    // a dense matrix is written in column-major order
+    let array = ArrayBuf::new((0..6).collect::<Vec<i64>>(), [2, 3])?;
+    let mut mtx = Vec::new();
+    text::write_mtx(&array, &mut mtx)?;
+    assert_eq!(
+        String::from_utf8(mtx.clone()).expect("text"),
+        "%%MatrixMarket matrix array integer general\n2 3\n0\n3\n1\n4\n2\n5\n"
+    );
+
+    let loaded = text::read_mtx::<i64, _>(&mtx[..])?;
+    assert_eq!(read(loaded)?, read(array)?);
+
+    // integers are parsed exactly, not through an f64
+    let mtx = b"%%MatrixMarket matrix array integer general\n2 1\n9007199254740993\n-9223372036854775808\n";
+    let matrix = text::read_mtx::<i64, _>(&mtx[..])?;
+    assert_eq!(read(matrix)?, vec![(1 << 53) + 1, i64::MIN]);
+
+    let mtx = b"%%MatrixMarket matrix array integer general\n1 1\n18446744073709551615\n";
+    assert_eq!(read(text::read_mtx::<u64, _>(&mtx[..])?)?, vec![u64::MAX]);
+
+    let mtx = b"%%MatrixMarket matrix array integer general\n1 1\n18446744073709551616\n";
+    assert!(text::read_mtx::<u64, _>(&mtx[..]).is_err());
+
+    let invalid = [
+        &b"%%MatrixMarket matrix coordinate real general\n2 2 1\n3 1 1\n"[..],
+        b"%%MatrixMarket matrix coordinate real general\n2 2 2\n1 1 1\n",
+        b"%%MatrixMarket matrix coordinate real general\n2 2 1\n1 1 1\n2 2 2\n",
+        b"%%MatrixMarket matrix coordinate complex general\n2 2 1\n1 1 1 0\n",
+        b"%%MatrixMarket matrix array integer general\n1 1\n256\n",
+        b"1 1\n1\n",
+        b"%%MatrixMarket matrix coordinate real general\n99999999999 99999999999 1\n1 1 1\n",
+        b"%%MatrixMarket matrix coordinate real general\n1073741824 1073741824 1\n1 1 1\n",
+    ];
+
+    for mtx in invalid {
+        assert!(text::read_mtx::<u8, _>(mtx).is_err());
+    }
+
+    Ok(())
+}
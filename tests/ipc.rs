@@ -0,0 +1,78 @@
+use std::io::Cursor;
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+
+use ha_ndarray::io::ipc;
+use ha_ndarray::*;
+
+#[test]
+fn test_ipc_message() -> Result<(), Error> {
+    let array = ArrayOp::range(0., 6., shape![2, 3])?;
+    let message = array.to_ipc_message()?;
+
+    let (header, data) = ipc::Header::decode(&message)?;
+    assert_eq!(header.dtype(), f64::TYPE);
+    assert_eq!(header.shape(), &[2, 3]);
+    assert_eq!(data.len(), 6 * 8);
+    assert_eq!(&data[8..16], &1f64.to_le_bytes());
+
+    let decoded = ArrayBuf::<f64, _>::from_ipc_message(&message)?;
+    assert_eq!(decoded.shape(), &[2, 3]);
+    assert_eq!(
+        decoded.buffer()?.to_slice()?.into_vec(),
+        vec![0., 1., 2., 3., 4., 5.]
+    );
+
+    let read = ipc::read::<f64, _>(&mut Cursor::new(&message))?;
+    assert_eq!(
+        read.buffer()?.to_slice()?.into_vec(),
+        vec![0., 1., 2., 3., 4., 5.]
+    );
+
+    assert!(ArrayBuf::<f32, _>::from_ipc_message(&message).is_err());
+    assert!(ArrayBuf::<f64, _>::from_ipc_message(&message[..message.len() - 1]).is_err());
+    assert!(ArrayBuf::<f64, _>::from_ipc_message(&message[1..]).is_err());
+
+    // a hostile header must not overflow or allocate the size it claims
+    for shape in [shape![1 << 62, 8], shape![1 << 40]] {
+        let message = ipc::Header::new::<f64>(shape).encode();
+        assert!(ipc::read::<f64, _>(&mut Cursor::new(&message)).is_err());
+        assert!(ArrayBuf::<f64, _>::from_ipc_message(&message).is_err());
+    }
+
+    let array = ArrayOp::range(0u32, 100_000, shape![100_000])?;
+    let read = ipc::read::<u32, _>(&mut Cursor::new(array.to_ipc_message()?))?;
+    assert_eq!(
+        read.buffer()?.to_slice()?.into_vec(),
+        (0..100_000).collect::<Vec<u32>>()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_ipc_socket() -> Result<(), Error> {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("listener");
+    let address = listener.local_addr().expect("address");
+
+    let sender = thread::spawn(move || -> Result<(), Error> {
+        let mut stream = TcpStream::connect(address).expect("connect");
+
+        for i in 0..3u32 {
+            let array = ArrayBuf::constant(i, shape![i as usize + 1, 1024])?;
+            ipc::write(&array, &mut stream)?;
+        }
+
+        Ok(())
+    });
+
+    let (mut stream, _) = listener.accept().expect("accept");
+
+    for i in 0..3u32 {
+        let array = ipc::read::<u32, _>(&mut stream)?;
+        assert_eq!(array.shape(), &[i as usize + 1, 1024]);
+        assert!(array.eq_scalar(i)?.all()?);
+    }
+
+    sender.join().expect("sender")
+}
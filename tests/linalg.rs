@@ -1,5 +1,47 @@
 use ha_ndarray::*;
 
+#[test]
+fn test_fixed_shape() -> Result<(), Error> {
+    use ha_ndarray::fixed::{Matrix, Vector};
+
+    let a = Matrix::<i32, 2, 3>::new(ArrayOp::range(0, 6, shape![2, 3])?)?;
+    let b = Matrix::<i32, 3, 2>::new(ArrayBuf::new(vec![1, 0, 0, 1, 1, 1], shape![3, 2])?)?;
+
+    let product = a.clone().matmul(b)?;
+    assert_eq!(product.as_array().shape(), &[2, 2]);
+    assert_eq!(&*product.as_array().buffer()?.to_slice()?, &[2, 3, 8, 9]);
+
+    let transposed = a.clone().transpose()?;
+    assert_eq!(
+        &*transposed.as_array().buffer()?.to_slice()?,
+        &[0, 3, 1, 4, 2, 5]
+    );
+
+    let row = Vector::<i32, 3>::new(ArrayBuf::new(vec![10, 20, 30], shape![3])?)?;
+    let column = Vector::<i32, 2>::new(ArrayBuf::new(vec![100, 200], shape![2])?)?;
+
+    let shifted = a.clone().add_row(row.clone())?.add_column(column)?;
+    assert_eq!(
+        &*shifted.into_inner().buffer()?.to_slice()?,
+        &[110, 121, 132, 213, 224, 235]
+    );
+
+    let doubled = (a.clone() + a.clone())?;
+    assert_eq!(
+        &*doubled.as_array().buffer()?.to_slice()?,
+        &[0, 2, 4, 6, 8, 10]
+    );
+
+    let product = a.matvec(row.clone())?;
+    assert_eq!(&*product.as_array().buffer()?.to_slice()?, &[80, 260]);
+
+    assert_eq!(row.clone().dot(row)?, 1400);
+
+    assert!(Matrix::<i32, 3, 2>::new(ArrayOp::range(0, 6, shape![2, 3])?).is_err());
+
+    Ok(())
+}
+
 #[test]
 fn test_diag() -> Result<(), Error> {
     let x = ArrayOp::range(0, 9, shape![3, 3])?;
@@ -8,6 +50,311 @@ fn test_diag() -> Result<(), Error> {
     Ok(())
 }
 
+#[test]
+fn test_trace() -> Result<(), Error> {
+    let x = ArrayOp::range(0, 9, shape![3, 3])?;
+    let trace = x.trace()?;
+    assert_eq!(trace.shape(), &[1]);
+    assert_eq!(&*trace.buffer()?.to_slice()?, &[12]);
+
+    let batch = ArrayOp::range(0., 24., shape![2, 3, 2, 2])?;
+    let trace = batch.trace()?;
+    assert_eq!(trace.shape(), &[2, 3]);
+    assert_eq!(
+        &*trace.buffer()?.to_slice()?,
+        &[3., 11., 19., 27., 35., 43.]
+    );
+
+    assert_eq!(trace.read_value(&[1, 1])?, 35.);
+
+    let x = ArrayOp::range(0, 6, shape![2, 3])?;
+    assert!(x.trace().is_err());
+
+    // the trace of an empty matrix is zero
+    let empty = ArrayBuf::new(Vec::<i32>::new(), shape![0, 0])?.trace()?;
+    assert_eq!(&*empty.buffer()?.to_slice()?, &[0]);
+    assert_eq!(empty.read_value(&[0])?, 0);
+
+    let empty = ArrayBuf::new(Vec::<f32>::new(), shape![3, 0, 0])?.trace()?;
+    assert_eq!(&*empty.buffer()?.to_slice()?, &[0., 0., 0.]);
+
+    Ok(())
+}
+
+#[test]
+fn test_eye_and_diag_from() -> Result<(), Error> {
+    let eye = ArrayOp::<f32, _>::eye(3)?;
+    assert_eq!(eye.shape(), &[3, 3]);
+    assert_eq!(
+        &*eye.buffer()?.to_slice()?,
+        &[1., 0., 0., 0., 1., 0., 0., 0., 1.]
+    );
+    assert_eq!(eye.read_value(&[2, 2])?, 1.);
+    assert_eq!(eye.read_value(&[2, 1])?, 0.);
+
+    let batch = ArrayAccess::from(ArrayOp::<u32, _>::eye_batch(shape![2, 3], 2)?);
+    assert_eq!(batch.shape(), &[2, 3, 2, 2]);
+    assert_eq!(batch.clone().sum_all()?, 12);
+    assert!(batch.diag()?.eq_scalar(1)?.all()?);
+
+    let x = ArrayOp::range(1, 4, shape![3])?;
+    let diag = Array::diag_from(x)?;
+    assert_eq!(diag.shape(), &[3, 3]);
+    assert_eq!(&*diag.buffer()?.to_slice()?, &[1, 0, 0, 0, 2, 0, 0, 0, 3]);
+    assert_eq!(diag.read_value(&[1, 1])?, 2);
+    assert_eq!(diag.read_value(&[1, 2])?, 0);
+
+    // the diagonal of a diagonal matrix is the vector it was constructed from
+    let x = ArrayAccess::from(ArrayOp::range(0., 100., shape![4, 25])?);
+    let diag = ArrayAccess::from(x.clone().diag_from()?);
+    assert_eq!(diag.shape(), &[4, 25, 25]);
+    assert!(diag.clone().diag()?.eq(x)?.all()?);
+    assert_eq!(diag.read_value(&[3, 24, 24])?, 99.);
+    assert_eq!(diag.read_value(&[3, 24, 23])?, 0.);
+
+    let product = ArrayOp::<f32, _>::eye_batch(shape![4], 25)?.matmul(diag.clone())?;
+    assert!(product.eq(diag)?.all()?);
+
+    assert!(ArrayOp::<f32, _>::eye(0).is_err());
+    assert!(ArrayBuf::new(Vec::<f32>::new(), shape![0])?
+        .diag_from()
+        .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_inv() -> Result<(), Error> {
+    let x = ArrayBuf::new(vec![4f64, 7., 2., 6.], shape![2, 2])?;
+    let inverse = x.inv()?;
+    let expected = [0.6, -0.7, -0.2, 0.4];
+    let actual = inverse.buffer()?.to_slice()?;
+    assert!(actual
+        .iter()
+        .zip(expected)
+        .all(|(a, e)| (a - e).abs() < 1e-12));
+
+    // a zero on the diagonal requires pivoting, and an integer matrix has a float inverse
+    let swap = ArrayBuf::new(vec![0i32, 1, 1, 0], shape![2, 2])?.inv()?;
+    assert_eq!(&*swap.buffer()?.to_slice()?, &[0f32, 1., 1., 0.]);
+    assert_eq!(swap.read_value(&[1, 0])?, 1f32);
+
+    // a batch large enough to invert on the heap
+    let (batch_size, dim) = (4, 8);
+    let data = (0..batch_size * dim * dim)
+        .map(|n| {
+            let (b, i, j) = (n / (dim * dim), (n / dim) % dim, n % dim);
+            if i == j {
+                (dim + b) as f64
+            } else {
+                ((i * 3 + j * 5 + b) % 7) as f64 * 0.25 - 0.75
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let x = ArrayAccess::from(ArrayBuf::new(data, shape![batch_size, dim, dim])?);
+    let inverse = ArrayAccess::from(x.clone().inv()?);
+    assert_eq!(inverse.shape(), &[batch_size, dim, dim]);
+
+    let identity = x.matmul(inverse.clone())?;
+    let eye = ArrayOp::<f64, _>::eye_batch(shape![batch_size], dim)?;
+    let error = identity.sub(eye)?.abs()?.max_all()?;
+    assert!(error < 1e-12, "error: {error}");
+
+    let value = inverse.read_value(&[3, 5, 2])?;
+    assert_eq!(
+        value,
+        inverse.buffer()?.to_slice()?[(3 * dim * dim) + (5 * dim) + 2]
+    );
+
+    let singular = ArrayBuf::new(vec![1., 2., 2., 4.], shape![2, 2])?.inv()?;
+    assert!(singular
+        .buffer()?
+        .to_slice()?
+        .iter()
+        .any(|n: &f64| !n.is_finite()));
+
+    assert!(ArrayBuf::new(vec![1.; 6], shape![2, 3])?.inv().is_err());
+
+    // the inverse of an empty matrix, or of an empty batch, is empty
+    for shape in [shape![0, 0], shape![0, 2, 2], shape![3, 0, 0]] {
+        let empty = ArrayBuf::new(Vec::<f64>::new(), shape.clone())?.inv()?;
+        assert_eq!(empty.shape(), shape.as_slice());
+        assert!(empty.buffer()?.to_slice()?.is_empty());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_decompose() -> Result<(), Error> {
+    let max_error = |actual: ArrayAccess<f64>, expected: ArrayAccess<f64>| -> Result<f64, Error> {
+        actual.sub(expected)?.abs()?.max_all()
+    };
+
+    // a zero on the diagonal requires pivoting
+    let (p, l, u) = ArrayBuf::new(vec![0i32, 1, 1, 0], shape![2, 2])?.lu()?;
+    assert_eq!(&*p.buffer()?.to_slice()?, &[0f32, 1., 1., 0.]);
+    assert_eq!(&*l.buffer()?.to_slice()?, &[1f32, 0., 0., 1.]);
+    assert_eq!(&*u.buffer()?.to_slice()?, &[1f32, 0., 0., 1.]);
+
+    // a batch large enough to decompose on the heap
+    let (batch_size, dim) = (4, 8);
+    let data = (0..batch_size * dim * dim)
+        .map(|n| {
+            let (b, i, j) = (n / (dim * dim), (n / dim) % dim, n % dim);
+            ((i * 3 + j * 5 + b) % 7) as f64 * 0.25 - 0.75 + (i * j) as f64
+        })
+        .collect::<Vec<_>>();
+
+    let x = ArrayAccess::from(ArrayBuf::new(data, shape![batch_size, dim, dim])?);
+
+    let (p, l, u) = x.clone().lu()?;
+    assert_eq!(u.shape(), &[batch_size, dim, dim]);
+    assert_eq!(l.read_value(&[2, 3, 3])?, 1.);
+    assert_eq!(u.read_value(&[2, 3, 1])?, 0.);
+
+    let plu = ArrayAccess::from(p.matmul(l)?.matmul(u)?);
+    let error = max_error(plu, x.clone())?;
+    assert!(error < 1e-12, "error: {error}");
+
+    // QR decomposition of a tall and a wide matrix
+    for (rows, cols) in [(5, 3), (3, 5)] {
+        let data = (0..rows * cols)
+            .map(|n| ((n * 7) % 11) as f64 - 5.)
+            .collect::<Vec<_>>();
+
+        let x = ArrayAccess::from(ArrayBuf::new(data, shape![rows, cols])?);
+        let (q, r) = x.clone().qr()?;
+        let k = rows.min(cols);
+        assert_eq!(q.shape(), &[rows, k]);
+        assert_eq!(r.shape(), &[k, cols]);
+        assert_eq!(r.read_value(&[1, 0])?, 0.);
+
+        let q = ArrayAccess::from(q);
+        let qr = ArrayAccess::from(q.clone().matmul(r)?);
+        let error = max_error(qr, x)?;
+        assert!(error < 1e-12, "error: {error}");
+
+        let qtq = ArrayAccess::from(q.clone().transpose(None)?.matmul(q)?);
+        let error = max_error(qtq, ArrayAccess::from(ArrayOp::<f64, _>::eye(k)?))?;
+        assert!(error < 1e-12, "error: {error}");
+    }
+
+    let l = ArrayBuf::new(vec![4f64, 2., 2., 3.], shape![2, 2])?.cholesky()?;
+    assert_eq!(&*l.buffer()?.to_slice()?, &[2., 0., 1., 2f64.sqrt()]);
+
+    let indefinite = ArrayBuf::new(vec![1f64, 2., 2., 1.], shape![2, 2])?.cholesky()?;
+    assert!(indefinite.buffer()?.to_slice()?[3].is_nan());
+
+    let wide = ArrayBuf::new(vec![0f32; 6], shape![2, 3])?;
+    assert!(wide.clone().lu().is_err());
+    assert!(wide.cholesky().is_err());
+    assert!(ArrayBuf::new(vec![1f32; 3], shape![3])?.qr().is_err());
+
+    // the factors of one decomposition share a factorization, which a new input invalidates
+    let x = Placeholder::<f64>::new(shape![2, 2])?;
+    let (p, l, u) = x.array().lu()?;
+    let (q, r) = x.array().qr()?;
+
+    for data in [
+        vec![0., 1., 1., 0.],
+        vec![4., 3., 6., 3.],
+        vec![4., 3., 6., 3.5],
+    ] {
+        x.bind(data.clone())?;
+
+        let expected = ArrayBuf::new(data, shape![2, 2])?;
+        let (expected_p, expected_l, expected_u) = expected.clone().lu()?;
+        let (expected_q, expected_r) = expected.qr()?;
+
+        assert_eq!(
+            &*l.buffer()?.to_slice()?,
+            &*expected_l.buffer()?.to_slice()?
+        );
+        assert_eq!(
+            &*u.buffer()?.to_slice()?,
+            &*expected_u.buffer()?.to_slice()?
+        );
+        assert_eq!(
+            &*p.buffer()?.to_slice()?,
+            &*expected_p.buffer()?.to_slice()?
+        );
+        assert_eq!(r.read_value(&[0, 1])?, expected_r.read_value(&[0, 1])?);
+        assert_eq!(q.read_value(&[1, 0])?, expected_q.read_value(&[1, 0])?);
+        assert_eq!(
+            &*q.buffer()?.to_slice()?,
+            &*expected_q.buffer()?.to_slice()?
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_eigh() -> Result<(), Error> {
+    let max_error = |actual: ArrayAccess<f64>, expected: ArrayAccess<f64>| -> Result<f64, Error> {
+        actual.sub(expected)?.abs()?.max_all()
+    };
+
+    // only the lower triangle is read
+    let (w, v) = ArrayBuf::new(vec![2i32, 99, 1, 2], shape![2, 2])?.eigh()?;
+    assert_eq!(w.shape(), &[2]);
+    assert_eq!(v.shape(), &[2, 2]);
+
+    let w = w.buffer()?.to_slice()?;
+    assert!(
+        (w[0] - 1.).abs() < 1e-6 && (w[1] - 3.).abs() < 1e-6,
+        "{w:?}"
+    );
+
+    // a batch large enough to decompose on the heap
+    let (batch_size, dim) = (4, 8);
+    let data = (0..batch_size * dim * dim)
+        .map(|n| {
+            let (b, i, j) = (n / (dim * dim), (n / dim) % dim, n % dim);
+            let (i, j) = (i.max(j), i.min(j));
+            ((i * 3 + j * 5 + b) % 7) as f64 - 3. + (i * j) as f64 * 0.5
+        })
+        .collect::<Vec<_>>();
+
+    let x = ArrayAccess::from(ArrayBuf::new(data, shape![batch_size, dim, dim])?);
+
+    let (w, v) = x.clone().eigh()?;
+    assert_eq!(w.shape(), &[batch_size, dim]);
+    assert_eq!(v.shape(), &[batch_size, dim, dim]);
+
+    let w = ArrayAccess::from(ArrayBuf::copy(&w)?);
+    let values = w.buffer()?.to_slice()?;
+    for eigenvalues in values.chunks_exact(dim) {
+        assert!(eigenvalues.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+
+    // A = V diag(w) V^T
+    let v = ArrayAccess::from(ArrayBuf::copy(&v)?);
+    let vt = ArrayAccess::from(v.clone().transpose(Some(axes![0, 2, 1]))?);
+    let scaled = w
+        .unsqueeze(axes![1])?
+        .broadcast(shape![batch_size, dim, dim])?;
+    let vwvt = v
+        .clone()
+        .mul(ArrayAccess::from(scaled))?
+        .matmul(vt.clone())?;
+    let error = max_error(ArrayAccess::from(vwvt), x)?;
+    assert!(error < 1e-10, "error: {error}");
+
+    // V^T V = I
+    let eye = ArrayOp::<f64, _>::eye(dim)?.unsqueeze(axes![0])?;
+    let eye = eye.broadcast(shape![batch_size, dim, dim])?;
+    let vtv = ArrayAccess::from(vt.matmul(v)?);
+    let error = max_error(vtv, ArrayAccess::from(eye))?;
+    assert!(error < 1e-10, "error: {error}");
+
+    assert!(ArrayBuf::new(vec![0f32; 6], shape![2, 3])?.eigh().is_err());
+
+    Ok(())
+}
+
 #[test]
 fn test_matmul_12x20() -> Result<(), Error> {
     let l = ArrayBuf::new((0..12).into_iter().collect::<Vec<_>>(), shape![3, 4])?;
@@ -29,3 +376,362 @@ fn test_matmul_12x20() -> Result<(), Error> {
 
     Ok(())
 }
+
+fn matmul_reference(
+    left: &[i32],
+    right: &[i32],
+    batch_size: usize,
+    [a, b, c]: [usize; 3],
+    [left_stride, right_stride]: [usize; 2],
+) -> Vec<i32> {
+    let mut product = Vec::with_capacity(batch_size * a * c);
+
+    for i in 0..batch_size {
+        for x in 0..a {
+            for z in 0..c {
+                let sum = (0..b)
+                    .map(|y| {
+                        left[(i * left_stride) + (x * b) + y]
+                            * right[(i * right_stride) + (y * c) + z]
+                    })
+                    .sum();
+
+                product.push(sum);
+            }
+        }
+    }
+
+    product
+}
+
+#[test]
+fn test_matmul_strided() -> Result<(), Error> {
+    // share one right-hand matrix across the batch
+    let left = (0..24).collect::<Vec<i32>>();
+    let right = (0..20).collect::<Vec<i32>>();
+    let l = ArrayBuf::new(left.to_vec(), shape![3, 2, 4])?;
+    let r = ArrayBuf::new(right.to_vec(), shape![4, 5])?;
+
+    let actual = l.matmul_strided(r, 3, [8, 0])?;
+    assert_eq!(actual.shape(), &[3, 2, 5]);
+
+    let expected = matmul_reference(&left, &right, 3, [2, 4, 5], [8, 0]);
+    assert_eq!(&*actual.buffer()?.to_slice()?, expected.as_slice());
+
+    // skip every other right-hand matrix, with a batch large enough to run in parallel
+    let left = (0..64).map(|n| n % 7).collect::<Vec<i32>>();
+    let right = (0..512).map(|n| n % 5).collect::<Vec<i32>>();
+    let l = ArrayBuf::new(left.to_vec(), shape![8, 8])?;
+    let r = ArrayBuf::new(right.to_vec(), shape![8, 8, 8])?;
+
+    let actual = l.matmul_strided(r, 4, [0, 128])?;
+    assert_eq!(actual.shape(), &[4, 8, 8]);
+
+    let expected = matmul_reference(&left, &right, 4, [8, 8, 8], [0, 128]);
+    assert_eq!(&*actual.buffer()?.to_slice()?, expected.as_slice());
+
+    // a matrix which extends past the end of its array is an error
+    let l = ArrayBuf::new(vec![0; 24], shape![3, 2, 4])?;
+    let r = ArrayBuf::new(vec![0; 20], shape![4, 5])?;
+    assert!(l.matmul_strided(r, 4, [8, 0]).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_einsum() -> Result<(), Error> {
+    let range = |n: usize| (0..n).map(|i| i as f32).collect::<Vec<_>>();
+    let read = |array: ArrayAccess<f32>| -> Result<Vec<f32>, Error> {
+        Ok(array.buffer()?.to_slice()?.into_vec())
+    };
+
+    let a = ArrayBuf::new(range(6), shape![2, 3])?;
+    let b = ArrayBuf::new(range(12), shape![3, 4])?;
+    let c = ArrayBuf::new(range(8), shape![4, 2])?;
+
+    let expected = ArrayBuf::copy(&a.clone().matmul(b.clone())?)?;
+    let product = einsum("ij,jk->ik", [a.clone(), b.clone()])?;
+    assert_eq!(product.shape(), &[2, 4]);
+    assert_eq!(read(product)?, expected.buffer()?.to_slice()?.into_vec());
+
+    let implicit = einsum("ij,jk", [a.clone(), b.clone()])?;
+    assert_eq!(read(implicit)?, expected.buffer()?.to_slice()?.into_vec());
+
+    let transposed = einsum("ij,jk->ki", [a.clone(), b.clone()])?;
+    let expected_t = expected.clone().transpose(None)?;
+    assert_eq!(
+        read(transposed)?,
+        expected_t.buffer()?.to_slice()?.into_vec()
+    );
+
+    let chain = einsum("ij,jk,kl->il", [a.clone(), b.clone(), c.clone()])?;
+    let expected_chain = expected.matmul(c)?;
+    assert_eq!(
+        read(chain)?,
+        expected_chain.buffer()?.to_slice()?.into_vec()
+    );
+
+    let total = einsum("ij->", [a.clone()])?;
+    assert_eq!(total.shape(), &[1]);
+    assert_eq!(read(total)?, vec![15.]);
+
+    let rows = einsum("ij->i", [a.clone()])?;
+    assert_eq!(read(rows)?, vec![3., 12.]);
+
+    let square = ArrayBuf::new(range(9), shape![3, 3])?;
+    assert_eq!(read(einsum("ii->i", [square.clone()])?)?, vec![0., 4., 8.]);
+    assert_eq!(read(einsum("ii", [square])?)?, vec![12.]);
+
+    let x = ArrayBuf::new(vec![1f32, 2., 3.], shape![3])?;
+    let y = ArrayBuf::new(vec![4f32, 5.], shape![2])?;
+    assert_eq!(read(einsum("i,i->", [x.clone(), x.clone()])?)?, vec![14.]);
+    assert_eq!(
+        read(einsum(
+            "i,j->ij",
+            [x.clone(), ArrayBuf::new(vec![4f32, 5., 6.], shape![3])?]
+        )?)?,
+        vec![4., 5., 6., 8., 10., 12., 12., 15., 18.]
+    );
+
+    let outer = einsum("i,j->ji", [x.clone(), y.clone()])?;
+    assert_eq!(outer.shape(), &[2, 3]);
+    assert_eq!(read(outer)?, vec![4., 8., 12., 5., 10., 15.]);
+
+    let left = ArrayBuf::new(range(12), shape![2, 2, 3])?;
+    let right = ArrayBuf::new(range(12), shape![2, 3, 2])?;
+    let batched = einsum("bij,bjk->bik", [left.clone(), right.clone()])?;
+    let expected_batch = left.matmul(right)?;
+    assert_eq!(
+        read(batched)?,
+        expected_batch.buffer()?.to_slice()?.into_vec()
+    );
+
+    assert!(einsum("ij,jk->ik", [a.clone(), a.clone()]).is_err());
+    assert!(einsum("ij,jk->ik", [a.clone()]).is_err());
+    assert!(einsum("ij->iz", [a.clone()]).is_err());
+    assert!(einsum("...j->j", [a]).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_outer() -> Result<(), Error> {
+    let x = ArrayBuf::new(vec![1, 2, 3], shape![3])?;
+    let y = ArrayBuf::new(vec![4, 5], shape![2])?;
+
+    let outer = x.clone().outer(y.clone())?;
+    assert_eq!(outer.shape(), &[3, 2]);
+    assert_eq!(&*outer.buffer()?.to_slice()?, &[4, 5, 8, 10, 12, 15]);
+
+    let left = ArrayBuf::new(vec![1, 2, 3, 4], shape![2, 2])?;
+    let right = ArrayBuf::new(vec![1, 0, 1, 2, 3, 4], shape![2, 3])?;
+    let batched = left.outer(right)?;
+    assert_eq!(batched.shape(), &[2, 2, 3]);
+    assert_eq!(
+        &*batched.buffer()?.to_slice()?,
+        &[1, 0, 1, 2, 0, 2, 6, 9, 12, 8, 12, 16]
+    );
+
+    let batch = ArrayBuf::new(vec![1, 2, 3, 4, 5, 6], shape![2, 3])?;
+    assert!(batch.outer(y).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_dot() -> Result<(), Error> {
+    let x = ArrayBuf::new(vec![1, 2, 3], shape![3])?;
+    let m = ArrayBuf::new(vec![1, 2, 3, 4, 5, 6], shape![2, 3])?;
+
+    let dot = x.clone().dot(x.clone())?;
+    assert_eq!(dot.shape(), &[1]);
+    assert_eq!(&*dot.buffer()?.to_slice()?, &[14]);
+
+    let dot = m.clone().dot(x.clone())?;
+    assert_eq!(dot.shape(), &[2]);
+    assert_eq!(&*dot.buffer()?.to_slice()?, &[14, 32]);
+
+    let y = ArrayBuf::new(vec![1, 1], shape![2])?;
+    let dot = y.clone().dot(m.clone())?;
+    assert_eq!(dot.shape(), &[3]);
+    assert_eq!(&*dot.buffer()?.to_slice()?, &[5, 7, 9]);
+
+    let dot = m.clone().dot(m.clone().transpose(None)?)?;
+    assert_eq!(dot.shape(), &[2, 2]);
+    assert_eq!(&*dot.buffer()?.to_slice()?, &[14, 32, 32, 77]);
+
+    let batch = ArrayBuf::new(vec![1, 0, 0, 1, 2, 0, 0, 2], shape![2, 2, 2])?;
+    let vectors = ArrayBuf::new(vec![1, 2, 3, 4], shape![2, 2])?;
+    let dot = batch.dot(vectors)?;
+    assert_eq!(dot.shape(), &[2, 2]);
+    assert_eq!(&*dot.buffer()?.to_slice()?, &[1, 2, 6, 8]);
+
+    assert!(x.clone().dot(y).is_err());
+    assert_eq!(m.dot(x.reshape([3, 1])?)?.shape(), &[2, 1]);
+
+    Ok(())
+}
+
+#[test]
+fn test_tensordot() -> Result<(), Error> {
+    let range = |n: usize| (0..n).map(|i| i as f32).collect::<Vec<_>>();
+    let read = |array: ArrayAccess<f32>| -> Result<Vec<f32>, Error> {
+        Ok(array.buffer()?.to_slice()?.into_vec())
+    };
+
+    let a = ArrayBuf::new(range(6), shape![2, 3])?;
+    let b = ArrayBuf::new(range(12), shape![3, 4])?;
+
+    let product = tensordot(a.clone(), b.clone(), [[1], [0]])?;
+    let expected = einsum("ij,jk->ik", [a.clone(), b.clone()])?;
+    assert_eq!(product.shape(), &[2, 4]);
+    assert_eq!(read(product)?, read(expected)?);
+
+    // contract the first axis of each operand, which requires a transpose of the left
+    let c = ArrayBuf::new(range(8), shape![2, 4])?;
+    let product = tensordot(a.clone(), c.clone(), [[0], [0]])?;
+    let expected = einsum("ij,ik->jk", [a.clone(), c])?;
+    assert_eq!(product.shape(), &[3, 4]);
+    assert_eq!(read(product)?, read(expected)?);
+
+    // contract two axis pairs in a different order in each operand
+    let x = ArrayBuf::new(range(24), shape![2, 3, 4])?;
+    let y = ArrayBuf::new(range(30), shape![3, 5, 2])?;
+    let product = tensordot(x.clone(), y.clone(), [vec![0, 1], vec![2, 0]])?;
+    let expected = einsum("ijk,jli->kl", [x.clone(), y.clone()])?;
+    assert_eq!(product.shape(), &[4, 5]);
+    assert_eq!(read(product)?, read(expected)?);
+
+    // with no axes to contract, the result is an outer product
+    let outer = tensordot(a.clone(), a.clone(), [[0usize; 0], []])?;
+    assert_eq!(outer.shape(), &[2, 3, 2, 3]);
+
+    let total = tensordot(a.clone(), a.clone(), [[0, 1], [0, 1]])?;
+    assert_eq!(total.shape(), &[1]);
+    assert_eq!(read(total)?, vec![55.]);
+
+    assert!(tensordot(a.clone(), b.clone(), [[0], [0]]).is_err());
+    assert!(tensordot(a.clone(), b.clone(), [[2], [0]]).is_err());
+    assert!(tensordot(x.clone(), y.clone(), [vec![0, 0], vec![2, 2]]).is_err());
+    assert!(tensordot(a, b, [vec![1], vec![]]).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_solve_tridiagonal() -> Result<(), Error> {
+    let (batch_size, dim) = (3, 5);
+
+    let mut diagonals = Vec::with_capacity(batch_size * 3 * dim);
+    let mut solution = Vec::with_capacity(batch_size * dim);
+
+    for b in 0..batch_size {
+        diagonals.extend((0..dim).map(|i| -1. - (b + i) as f64 * 0.1));
+        diagonals.extend((0..dim).map(|i| 4. + (b * i) as f64));
+        diagonals.extend((0..dim).map(|i| 0.5 * i as f64 - 1.));
+        solution.extend((0..dim).map(|i| (b * dim + i) as f64 - 7.));
+    }
+
+    // multiply each system by its known solution to construct the right-hand side
+    let mut rhs = vec![0.; batch_size * dim];
+    for b in 0..batch_size {
+        let (diag, x) = (&diagonals[b * 3 * dim..], &solution[b * dim..]);
+
+        for i in 0..dim {
+            let mut sum = diag[dim + i] * x[i];
+            if i > 0 {
+                sum += diag[i] * x[i - 1];
+            }
+            if i + 1 < dim {
+                sum += diag[2 * dim + i] * x[i + 1];
+            }
+
+            rhs[b * dim + i] = sum;
+        }
+    }
+
+    let diagonals = ArrayBuf::new(diagonals, shape![batch_size, 3, dim])?;
+    let rhs = ArrayBuf::new(rhs, shape![batch_size, dim])?;
+
+    let actual = diagonals.clone().solve_tridiagonal(rhs.clone())?;
+    assert_eq!(actual.shape(), &[batch_size, dim]);
+
+    let actual = actual.buffer()?.to_slice()?.into_vec();
+    for (actual, expected) in actual.into_iter().zip(solution) {
+        assert!((actual - expected).abs() < 1e-9, "{actual} != {expected}");
+    }
+
+    assert!(diagonals
+        .solve_tridiagonal(rhs.reshape(shape![dim, batch_size])?)
+        .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_csr_matmul() -> Result<(), Error> {
+    // [[1, 0, 2, 0], [0, 0, 0, 0], [0, 3, 0, 4]]
+    let matrix = CsrMatrix::new(
+        [3, 4],
+        ArrayBuf::new(vec![0u64, 2, 2, 4], shape![4])?,
+        ArrayBuf::new(vec![0u64, 2, 1, 3], shape![4])?,
+        ArrayBuf::new(vec![1., 2., 3., 4.], shape![4])?,
+    )?;
+
+    assert_eq!(matrix.nnz(), 4);
+
+    let vector = ArrayBuf::new(vec![1., 2., 3., 4.], shape![4])?;
+    let product = matrix.matmul(vector)?;
+    assert_eq!(product.shape(), &[3]);
+    assert_eq!(&*product.buffer()?.to_slice()?, &[7., 0., 22.]);
+
+    let dense = ArrayOp::range(0., 8., shape![4, 2])?;
+    let product = matrix.matmul(dense)?;
+    assert_eq!(product.shape(), &[3, 2]);
+    assert_eq!(product.read_value(&[2, 1])?, 37.);
+    assert_eq!(
+        &*product.buffer()?.to_slice()?,
+        &[8., 11., 0., 0., 30., 37.]
+    );
+
+    assert!(matrix
+        .matmul(ArrayBuf::new(vec![1.; 3], shape![3])?)
+        .is_err());
+
+    // a tridiagonal matrix of ones, large enough to be multiplied in parallel
+    let n = 3000u64;
+    let offsets = (0..=n).map(|i| (3 * i).saturating_sub(1).min(3 * n - 2));
+    let indices = (0..n).flat_map(|i| i.saturating_sub(1)..(i + 2).min(n));
+    let offsets = offsets.collect::<Vec<_>>();
+    let indices = indices.collect::<Vec<_>>();
+    let values = vec![1f32; indices.len()];
+
+    let matrix = CsrMatrix::new(
+        [n as usize, n as usize],
+        ArrayBuf::new(offsets, shape![n as usize + 1])?,
+        ArrayBuf::new(indices, shape![values.len()])?,
+        ArrayBuf::new(values.clone(), shape![values.len()])?,
+    )?;
+
+    let product = matrix.matmul(ArrayBuf::constant(1f32, shape![n as usize])?)?;
+    let product = product.buffer()?.to_slice()?.into_vec();
+    assert_eq!(product[0], 2.);
+    assert!(product[1..(n as usize - 1)].iter().all(|sum| *sum == 3.));
+    assert_eq!(product[n as usize - 1], 2.);
+
+    let invalid = |offsets: Vec<u64>, indices: Vec<u64>| {
+        CsrMatrix::new(
+            [2, 2],
+            ArrayBuf::new(offsets, shape![3])?,
+            ArrayBuf::new(indices, shape![2])?,
+            ArrayBuf::new(vec![1i32, 2], shape![2])?,
+        )
+    };
+
+    assert!(invalid(vec![0, 1, 2], vec![0, 1]).is_ok());
+    assert!(invalid(vec![0, 2, 1], vec![0, 1]).is_err());
+    assert!(invalid(vec![0, 1, 3], vec![0, 1]).is_err());
+    assert!(invalid(vec![0, 1, 2], vec![0, 2]).is_err());
+
+    Ok(())
+}
@@ -46,3 +46,562 @@ fn test_reduce_sum_axis() -> Result<(), Error> {
 
     Ok(())
 }
+
+#[test]
+fn test_arg_reduce_nan() -> Result<(), Error> {
+    let read = |array: ArrayAccess<u64>| -> Result<Vec<u64>, Error> {
+        Ok(array.buffer()?.to_slice()?.into_vec())
+    };
+
+    // the index of the first NaN in a lane is returned, like a max or min which propagates NaN
+    let data = vec![
+        3.,
+        f32::NAN,
+        1.,
+        2.,
+        f32::NAN,
+        5.,
+        -f32::NAN,
+        0.,
+        1.,
+        4.,
+        0.,
+        2.,
+    ];
+    let x = ArrayAccess::from(ArrayBuf::new(data, shape![3, 4])?);
+
+    let argmax = x.clone().argmax(axes![1], false)?;
+    assert_eq!(read(ArrayAccess::from(argmax))?, [1, 0, 1]);
+
+    let argmin = x.clone().argmin(axes![1], false)?;
+    assert_eq!(read(ArrayAccess::from(argmin))?, [1, 0, 2]);
+
+    let (values, indices) = x.clone().max_with_index(axes![1], false)?;
+    let values = values.buffer()?.to_slice()?.into_vec();
+    assert!(values[0].is_nan() && values[1].is_nan());
+    assert_eq!(values[2], 4.);
+    assert_eq!(&*indices.buffer()?.to_slice()?, &[1, 0, 1]);
+
+    let (values, indices) = x.min_with_index(axes![1], false)?;
+    let values = values.buffer()?.to_slice()?.into_vec();
+    assert!(values[0].is_nan() && values[1].is_nan());
+    assert_eq!(values[2], 0.);
+    assert_eq!(&*indices.buffer()?.to_slice()?, &[1, 0, 2]);
+
+    Ok(())
+}
+
+#[test]
+fn test_reduce_nan() -> Result<(), Error> {
+    let read = |array: ArrayAccess<f32>| -> Result<Vec<f32>, Error> {
+        Ok(array.buffer()?.to_slice()?.into_vec())
+    };
+
+    let nan = |values: &[f32]| values.iter().map(|n| n.is_nan()).collect::<Vec<_>>();
+
+    // NaN propagates regardless of its sign
+    let data = vec![1., f32::NAN, 3., -f32::NAN, 2., 4., f32::NAN, f32::NAN];
+    let x = ArrayAccess::from(ArrayBuf::new(data, shape![4, 2])?);
+
+    for (actual, expected) in [
+        (x.clone().max(axes![1], false)?, [true, true, false, true]),
+        (x.clone().min(axes![1], false)?, [true, true, false, true]),
+        (x.clone().sum(axes![1], false)?, [true, true, false, true]),
+        (
+            x.clone().product(axes![1], false)?,
+            [true, true, false, true],
+        ),
+    ] {
+        assert_eq!(nan(&read(ArrayAccess::from(actual))?), expected);
+    }
+
+    let max = read(ArrayAccess::from(
+        x.clone().max_ignore_nan(axes![1], false)?,
+    ))?;
+    assert_eq!(&max[..3], &[1., 3., 4.]);
+    assert!(max[3].is_nan());
+
+    let min = read(ArrayAccess::from(
+        x.clone().min_ignore_nan(axes![1], false)?,
+    ))?;
+    assert_eq!(&min[..3], &[1., 3., 2.]);
+    assert!(min[3].is_nan());
+
+    let sum = x.clone().sum_ignore_nan(axes![1], false)?;
+    assert_eq!(read(ArrayAccess::from(sum))?, vec![1., 3., 6., 0.]);
+
+    let product = x.clone().product_ignore_nan(axes![1], false)?;
+    assert_eq!(read(ArrayAccess::from(product))?, vec![1., 3., 8., 1.]);
+
+    assert!(x.clone().max_all()?.is_nan());
+    assert!(x.clone().min_all()?.is_nan());
+    assert!(x.sum_all()?.is_nan());
+
+    // a long lane, which is reduced in parallel
+    let mut data = vec![1.; 10_000];
+    data[7_777] = -f32::NAN;
+    let x = ArrayBuf::new(data, shape![10_000])?;
+    assert!(x.clone().max(axes![0], false)?.read_value(&[0])?.is_nan());
+    assert!(x.clone().sum(axes![0], false)?.read_value(&[0])?.is_nan());
+    assert_eq!(
+        &*x.clone()
+            .sum_ignore_nan(axes![0], false)?
+            .buffer()?
+            .to_slice()?,
+        &[9_999.]
+    );
+    assert_eq!(
+        x.clone()
+            .max_ignore_nan(axes![0], false)?
+            .read_value(&[0])?,
+        1.
+    );
+
+    // an all-negative lane reduces to its maximum, not to the minimum finite value
+    let x = ArrayBuf::new(vec![f32::NEG_INFINITY; 3], shape![3])?;
+    assert_eq!(x.clone().max_all()?, f32::NEG_INFINITY);
+    assert_eq!(
+        &*x.max(axes![0], false)?.buffer()?.to_slice()?,
+        &[f32::NEG_INFINITY]
+    );
+
+    // the NaN-ignoring reductions of an integer type are the plain reductions
+    let x = ArrayBuf::new(vec![3u8, 0, 2, 5], shape![2, 2])?;
+    let max = x.clone().max_ignore_nan(axes![1], false)?;
+    assert_eq!(&*max.buffer()?.to_slice()?, &[3, 5]);
+    let product = x.product_ignore_nan(axes![0], false)?;
+    assert_eq!(&*product.buffer()?.to_slice()?, &[6, 0]);
+
+    Ok(())
+}
+
+#[test]
+fn test_reduce_empty() -> Result<(), Error> {
+    let x = ArrayBuf::new(Vec::<f32>::new(), shape![0, 3])?;
+
+    // a reduction with an identity reduces each empty lane to it
+    let sum = x.clone().sum(axes![0], false)?;
+    assert_eq!(sum.shape(), &[3]);
+    assert_eq!(&*sum.buffer()?.to_slice()?, &[0., 0., 0.]);
+
+    let product = x.clone().product(axes![0], true)?;
+    assert_eq!(product.shape(), &[1, 3]);
+    assert_eq!(&*product.buffer()?.to_slice()?, &[1., 1., 1.]);
+    assert_eq!(product.read_value(&[0, 2])?, 1.);
+
+    let sum = x.clone().sum_ignore_nan(axes![0, 1], false)?;
+    assert_eq!(&*sum.buffer()?.to_slice()?, &[0.]);
+
+    // reducing the non-empty axis leaves an empty array
+    let sum = x.clone().sum(axes![1], false)?;
+    assert_eq!(sum.shape(), &[0]);
+    assert_eq!(sum.size(), 0);
+
+    assert_eq!(x.clone().sum_all()?, 0.);
+    assert_eq!(x.clone().product_all()?, 1.);
+
+    // a reduction with no identity is an error
+    assert!(x.clone().max(axes![0], false).is_err());
+    assert!(x.clone().min_ignore_nan(axes![0], false).is_err());
+    assert!(x.clone().mean(axes![0], false).is_err());
+    assert!(x.clone().argmax(axes![0], false).is_err());
+    assert!(x.clone().max_all().is_err());
+    assert!(x.min_all().is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_layer_norm() -> Result<(), Error> {
+    let array = ArrayBuf::new(vec![1., 2., 3., 4., 2., 2., 2., 2.], shape![2, 4])?;
+    let scale = ArrayBuf::constant(2., shape![4])?;
+    let bias = ArrayBuf::constant(1., shape![4])?;
+
+    let actual = array.layer_norm(axes![1], 1e-9, scale, bias)?;
+
+    let std = 1.25f64.sqrt();
+    let expected = [-1.5, -0.5, 0.5, 1.5, 0., 0., 0., 0.]
+        .into_iter()
+        .map(|n| ((n / std) * 2.) + 1.)
+        .collect::<Vec<f64>>();
+
+    let actual = actual.buffer()?.to_slice()?;
+    for (a, e) in actual.iter().zip(expected) {
+        assert!((a - e).abs() < 1e-6, "{a} != {e}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_layer_norm_invalid_axes() -> Result<(), Error> {
+    let array = ArrayBuf::constant(1f32, shape![2, 3])?;
+    let scale = ArrayBuf::constant(1f32, shape![2])?;
+    let bias = ArrayBuf::constant(0f32, shape![2])?;
+    assert!(array.layer_norm(axes![0], 1e-5, scale, bias).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_stats_accumulator() -> Result<(), Error> {
+    let mut stats = StatsAccumulator::new();
+    assert_eq!(stats.mean(), None);
+
+    stats.update(&ArrayOp::range(0., 10., shape![2, 5])?)?;
+    stats.update(&ArrayBuf::new(
+        (10..10_000).map(|n| n as f64).collect::<Vec<_>>(),
+        shape![9990],
+    )?)?;
+
+    let n = 10_000.;
+    assert_eq!(stats.count(), 10_000);
+    assert_eq!(stats.min(), Some(0.));
+    assert_eq!(stats.max(), Some(9999.));
+    assert!((stats.mean().unwrap() - 4999.5).abs() < 1e-9);
+    assert!((stats.var().unwrap() - (n * n - 1.) / 12.).abs() < 1e-6);
+
+    Ok(())
+}
+
+#[test]
+fn test_describe() -> Result<(), Error> {
+    let summary = ArrayOp::range(0f32, 8., shape![2, 4])?.describe()?;
+    assert_eq!(summary.dtype, "float");
+    assert_eq!(summary.shape.as_slice(), &[2, 4]);
+    assert_eq!(summary.platform, "host");
+    assert_eq!((summary.min, summary.max), (Some(0.), Some(7.)));
+    assert_eq!(summary.mean, Some(3.5));
+    assert!((summary.std.unwrap() - 5.25f64.sqrt()).abs() < 1e-6);
+    assert_eq!(summary.nan_count, 0);
+
+    let summary = ArrayBuf::new(vec![1f32, 1., 3., 3.], shape![2, 2])?.describe()?;
+    assert_eq!(
+        summary.to_string(),
+        "float array of shape [2, 2] on host: min 1, max 3, mean 2, std 1, 0 NaN"
+    );
+
+    let summary = ArrayBuf::new(vec![1., f64::NAN, -2., f64::NAN], shape![4])?.describe()?;
+    assert_eq!((summary.min, summary.max), (Some(-2.), Some(1.)));
+    assert!(summary.mean.unwrap().is_nan());
+    assert_eq!(summary.nan_count, 2);
+
+    let summary = ArrayBuf::new(vec![f32::NAN], shape![1])?.describe()?;
+    assert_eq!((summary.min, summary.max), (None, None));
+
+    let summary = ArrayBuf::new(vec![3u8, 1, 2], shape![3])?.describe()?;
+    assert_eq!(
+        (summary.min, summary.max, summary.nan_count),
+        (Some(1), Some(3), 0)
+    );
+
+    let summary = ArrayBuf::new(Vec::<i32>::new(), shape![0, 3])?.describe()?;
+    assert_eq!((summary.min, summary.mean), (None, None));
+
+    let mut data = (0..10_000).map(|n| n as f64).collect::<Vec<_>>();
+    data[5_000] = f64::NAN;
+    let summary = ArrayBuf::new(data, shape![100, 100])?.describe()?;
+    assert_eq!((summary.min, summary.max), (Some(0.), Some(9_999.)));
+    assert!(summary.std.unwrap().is_nan());
+    assert_eq!(summary.nan_count, 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_max_min_with_index() -> Result<(), Error> {
+    let data = vec![3, 9, 1, 9, 4, 0, 2, 7, 5, 0, 6, 8];
+    let array = ArrayBuf::new(data, shape![2, 2, 3])?;
+
+    let (values, indices) = array.clone().max_with_index(axes![2], false)?;
+    assert_eq!(values.shape(), &[2, 2]);
+    assert_eq!(values.buffer()?.to_slice()?.into_vec(), vec![9, 9, 7, 8]);
+    assert_eq!(indices.buffer()?.to_slice()?.into_vec(), vec![1, 0, 1, 2]);
+
+    let (values, indices) = array.clone().min_with_index(axes![0, 2], true)?;
+    assert_eq!(values.shape(), &[1, 2, 1]);
+    assert_eq!(values.buffer()?.to_slice()?.into_vec(), vec![1, 0]);
+    assert_eq!(indices.buffer()?.to_slice()?.into_vec(), vec![2, 2]);
+
+    let (values, indices) = array.max_with_index(axes![1], false)?;
+    assert_eq!(
+        values.buffer()?.to_slice()?.into_vec(),
+        vec![9, 9, 1, 2, 7, 8]
+    );
+    assert_eq!(
+        indices.buffer()?.to_slice()?.into_vec(),
+        vec![1, 0, 0, 0, 0, 1]
+    );
+
+    let large = ArrayOp::range(0f32, 1000., shape![10, 100]).map(ArrayAccess::from)?;
+    let (values, indices) = large.max_with_index(axes![1], false)?;
+    assert_eq!(values.read_value(&[3])?, 399.);
+    assert!(indices.eq_scalar(99)?.all()?);
+
+    Ok(())
+}
+
+#[test]
+fn test_argmax_argmin() -> Result<(), Error> {
+    let data = vec![3, 9, 1, 9, 4, 0, 2, 7, 5, 0, 6, 8];
+    let array = ArrayBuf::new(data, shape![2, 2, 3])?;
+
+    let argmax = array.clone().argmax(axes![2], false)?;
+    assert_eq!(argmax.shape(), &[2, 2]);
+    assert_eq!(&*argmax.buffer()?.to_slice()?, &[1, 0, 1, 2]);
+    assert_eq!(argmax.read_value(&[1, 1])?, 2);
+
+    let argmin = array.clone().argmin(axes![0, 2], true)?;
+    assert_eq!(argmin.shape(), &[1, 2, 1]);
+    assert_eq!(&*argmin.buffer()?.to_slice()?, &[2, 2]);
+
+    let argmin = array.argmin(axes![1], false)?;
+    assert_eq!(&*argmin.buffer()?.to_slice()?, &[0, 1, 1, 1, 1, 0]);
+
+    // a classifier's predicted label for each of many samples
+    let logits = ArrayOp::range(0f32, 3000., shape![1000, 3]).map(ArrayAccess::from)?;
+    let labels = logits.clone().argmax(axes![1], false)?;
+    assert!(labels.eq_scalar(2)?.all()?);
+    assert!(logits.argmin(axes![1], false)?.eq_scalar(0)?.all()?);
+
+    Ok(())
+}
+
+#[test]
+fn test_sum_to() -> Result<(), Error> {
+    let mask = ArrayBuf::new(vec![1u8; 300 * 2], shape![300, 2])?;
+
+    let wrapped = mask.clone().sum(axes![0], false)?;
+    assert_eq!(&*wrapped.buffer()?.to_slice()?, &[44, 44]);
+
+    let counts = mask.clone().sum_to::<u64>(axes![0], false)?;
+    assert_eq!(&*counts.buffer()?.to_slice()?, &[300, 300]);
+
+    assert_eq!(mask.clone().sum_all()?, 88);
+    assert_eq!(mask.sum_all_to::<u64>()?, 600);
+
+    let signed = ArrayBuf::new(vec![-100i8; 4], shape![4])?;
+    assert_eq!(signed.sum_all_to::<i32>()?, -400);
+
+    Ok(())
+}
+
+#[test]
+fn test_mean_var_std() -> Result<(), Error> {
+    let x = ArrayBuf::new(vec![1u8, 2, 3, 4, 5, 9], shape![2, 3])?;
+
+    let mean = x.clone().mean(axes![1], false)?;
+    assert_eq!(mean.shape(), &[2]);
+    assert_eq!(&*mean.buffer()?.to_slice()?, &[2f32, 6.]);
+
+    let mean = x.clone().mean(axes![0], true)?;
+    assert_eq!(mean.shape(), &[1, 3]);
+    assert_eq!(&*mean.buffer()?.to_slice()?, &[2.5f32, 3.5, 6.]);
+
+    let var = x
+        .clone()
+        .var(axes![1], false)?
+        .buffer()?
+        .to_slice()?
+        .into_vec();
+    let std = x
+        .clone()
+        .std(axes![1], false)?
+        .buffer()?
+        .to_slice()?
+        .into_vec();
+
+    for ((var, std), expected) in var.into_iter().zip(std).zip([2. / 3., 14. / 3.]) {
+        assert!((var - expected).abs() < 1e-6, "{var} != {expected}");
+        assert!(
+            (std - expected.sqrt()).abs() < 1e-6,
+            "{std} != {}",
+            expected.sqrt()
+        );
+    }
+
+    let all = x.std(axes![0, 1], false)?;
+    assert_eq!(all.shape(), &[1]);
+    assert!((all.read_value(&[0])? - (20f32 / 3.).sqrt()).abs() < 1e-6);
+
+    let large = ArrayOp::range(0f64, 150_000., shape![300, 500])?;
+    let mean = large.mean(axes![1], false)?;
+    let expected = ArrayOp::range(249.5f64, 150_249.5, shape![300])?;
+    assert!(mean.eq(expected)?.all()?);
+
+    Ok(())
+}
+
+#[test]
+fn test_ragged() -> Result<(), Error> {
+    let ragged = RaggedArray::from_segments([vec![1, 2, 3], vec![], vec![4], vec![5, 6]])?;
+
+    assert_eq!(ragged.len(), 4);
+    assert_eq!(ragged.max_len(), 3);
+    assert_eq!(ragged.lengths()?, vec![3, 0, 1, 2]);
+
+    assert_eq!(&*ragged.sum()?.buffer()?.to_slice()?, &[6, 0, 4, 11]);
+    assert_eq!(&*ragged.product()?.buffer()?.to_slice()?, &[6, 1, 4, 30]);
+    assert_eq!(ragged.max()?.read_value(&[3])?, 6);
+    assert_eq!(ragged.min()?.read_value(&[1])?, i32::MAX);
+
+    let padded = ragged.to_padded(-1)?;
+    assert_eq!(padded.shape(), &[4, 3]);
+    assert_eq!(padded.read_value(&[2, 1])?, -1);
+    assert_eq!(
+        &*padded.buffer()?.to_slice()?,
+        &[1, 2, 3, -1, -1, -1, 4, -1, -1, 5, 6, -1]
+    );
+
+    let doubled = ragged.clone().map(|values| values.mul_scalar(2))?;
+    assert_eq!(&*doubled.sum()?.buffer()?.to_slice()?, &[12, 0, 8, 22]);
+
+    assert!(ragged.map(|values| values.reshape(shape![2, 3])).is_err());
+
+    let offsets = ArrayBuf::new(vec![0u64, 2, 1, 3], shape![4])?;
+    let values = ArrayBuf::new(vec![1., 2., 3.], shape![3])?;
+    assert!(RaggedArray::new(offsets, values).is_err());
+
+    // enough segments to be reduced in parallel
+    let segments = (0..2000).map(|i| vec![1f32; i % 7]);
+    let ragged = RaggedArray::from_segments(segments)?;
+    let sums = ragged.sum()?.buffer()?.to_slice()?.into_vec();
+    assert!((0..2000).all(|i| sums[i] == (i % 7) as f32));
+
+    let padded = ragged.to_padded(0.)?;
+    assert_eq!(padded.shape(), &[2000, 6]);
+    assert_eq!(padded.sum_all()?, sums.iter().sum::<f32>());
+
+    Ok(())
+}
+
+#[test]
+fn test_reduce_stream() -> Result<(), Error> {
+    let partials = (0..10).map(|i| ArrayBuf::constant(i as f32, shape![2, 3]));
+    let partials = partials.collect::<Result<Vec<_>, Error>>()?;
+
+    let total = reduce_stream(partials.clone(), |acc, next| acc.add(next))?;
+    assert_eq!(total.shape(), &[2, 3]);
+    assert_eq!(&*total.buffer()?.to_slice()?, &[45.; 6]);
+
+    let product = reduce_stream(partials[1..4].to_vec(), |acc, next| acc.mul(next))?;
+    assert_eq!(product.read_value(&[1, 2])?, 6.);
+
+    let single = reduce_stream(partials[..1].to_vec(), |acc, next| acc.add(next))?;
+    assert_eq!(&*single.buffer()?.to_slice()?, &[0.; 6]);
+
+    let empty = Vec::<ArrayBuf<f32, Vec<f32>>>::new();
+    assert!(reduce_stream(empty, |acc, next| acc.add(next)).is_err());
+
+    let mismatched = vec![
+        ArrayAccess::from(ArrayBuf::constant(1., shape![2, 3])?),
+        ArrayAccess::from(ArrayBuf::constant(1., shape![3])?),
+    ];
+    assert!(reduce_stream(mismatched, |acc, next| acc.add(next)).is_err());
+
+    // a long stream of large partial results
+    let partials = (0..100).map(|_| ArrayBuf::constant(1u64, shape![10_000]).expect("partial"));
+    let counts = reduce_stream(partials, |acc, next| acc.add(next))?;
+    assert!(counts.eq_scalar(100)?.all()?);
+
+    Ok(())
+}
+
+#[test]
+fn test_group_aggregate() -> Result<(), Error> {
+    let keys = ArrayBuf::new(vec![3u32, 1, 3, 2, 1, 3], shape![6])?;
+    let values = ArrayBuf::new(vec![1f32, 2., 3., 4., 5., 6.], shape![6])?;
+
+    let (unique, sums) = group_aggregate(keys.clone(), values.clone(), Aggregate::Sum)?;
+    assert_eq!(&*unique.buffer()?.to_slice()?, &[1, 2, 3]);
+    assert_eq!(&*sums.buffer()?.to_slice()?, &[7., 4., 10.]);
+
+    let expected = [
+        (Aggregate::Count, [2., 1., 3.]),
+        (Aggregate::Max, [5., 4., 6.]),
+        (Aggregate::Mean, [3.5, 4., 10. / 3.]),
+        (Aggregate::Min, [2., 4., 1.]),
+    ];
+
+    for (agg, expected) in expected {
+        let (_, actual) = group_aggregate(keys.clone(), values.clone(), agg)?;
+        assert_eq!(&*actual.buffer()?.to_slice()?, &expected, "{agg:?}");
+    }
+
+    let (unique, counts) = group_aggregate(
+        ArrayBuf::constant(7i64, shape![4])?,
+        ArrayBuf::new(vec![1u64, 2, 3, 4], shape![4])?,
+        Aggregate::Count,
+    )?;
+
+    assert_eq!(&*unique.buffer()?.to_slice()?, &[7]);
+    assert_eq!(&*counts.buffer()?.to_slice()?, &[4]);
+
+    let short = ArrayBuf::new(vec![1f32, 2.], shape![2])?;
+    assert!(group_aggregate(keys, short, Aggregate::Sum).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_group_aggregate_one_group() -> Result<(), Error> {
+    let (unique, sums) = group_aggregate(
+        ArrayBuf::new(vec![5u32], shape![1])?,
+        ArrayBuf::new(vec![2.5f32], shape![1])?,
+        Aggregate::Sum,
+    )?;
+
+    assert_eq!(&*unique.buffer()?.to_slice()?, &[5]);
+    assert_eq!(&*sums.buffer()?.to_slice()?, &[2.5]);
+
+    let keys = ArrayBuf::constant(-1i32, shape![5])?;
+    let values = ArrayBuf::new(vec![1f64, 2., 3., 4., 5.], shape![5])?;
+
+    for (agg, expected) in [
+        (Aggregate::Count, 5.),
+        (Aggregate::Max, 5.),
+        (Aggregate::Mean, 3.),
+        (Aggregate::Min, 1.),
+        (Aggregate::Sum, 15.),
+    ] {
+        let (unique, actual) = group_aggregate(keys.clone(), values.clone(), agg)?;
+        assert_eq!(&*unique.buffer()?.to_slice()?, &[-1], "{agg:?}");
+        assert_eq!(&*actual.buffer()?.to_slice()?, &[expected], "{agg:?}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_inner_join() -> Result<(), Error> {
+    let left = ArrayBuf::new(vec![3u32, 1, 4, 1, 5], shape![5])?;
+    let right = ArrayBuf::new(vec![1u32, 5, 9, 1, 3, 3], shape![6])?;
+
+    let (l, r) = inner_join(left.clone(), right.clone())?;
+    assert_eq!(&*l.buffer()?.to_slice()?, &[1, 1, 3, 3, 0, 0, 4]);
+    assert_eq!(&*r.buffer()?.to_slice()?, &[0, 3, 0, 3, 4, 5, 1]);
+
+    // the indices align the keys of both sides
+    let l_keys = left.gather(l, 0)?;
+    let r_keys = right.gather(r, 0)?;
+    assert_eq!(
+        &*l_keys.buffer()?.to_slice()?,
+        &*r_keys.buffer()?.to_slice()?
+    );
+
+    let left = ArrayBuf::new(vec![f32::NAN, 2., 1.], shape![3])?;
+    let right = ArrayBuf::new(vec![1., f32::NAN, 0.], shape![3])?;
+    let (l, r) = inner_join(left, right)?;
+    assert_eq!(&*l.buffer()?.to_slice()?, &[2]);
+    assert_eq!(&*r.buffer()?.to_slice()?, &[0]);
+
+    let left = ArrayBuf::new(vec![1i16, 2], shape![2])?;
+    let right = ArrayBuf::new(vec![3i16], shape![1])?;
+    let (l, r) = inner_join(left, right)?;
+    assert_eq!(l.size(), 0);
+    assert_eq!(r.size(), 0);
+
+    let matrix = ArrayBuf::new(vec![1i16, 2], shape![1, 2])?;
+    assert!(inner_join(matrix, ArrayBuf::new(vec![1i16], shape![1])?).is_err());
+
+    Ok(())
+}
@@ -0,0 +1,55 @@
+#![cfg(unix)]
+
+use std::thread;
+
+use ha_ndarray::host::shared;
+use ha_ndarray::*;
+
+#[test]
+fn test_shared_memory() -> Result<(), Error> {
+    let name = format!("densor-test-{}", std::process::id());
+
+    let source = ArrayOp::range(0f32, 12., shape![3, 4])?;
+    let mut producer = shared::share(&name, &source)?;
+    assert!(shared::create::<f32>(&name, shape![3, 4]).is_err());
+
+    let consumer = {
+        let name = name.clone();
+        thread::spawn(move || shared::open::<f32>(&name).and_then(|array| array.sum_all()))
+    };
+
+    assert_eq!(consumer.join().expect("consumer")?, 66.);
+
+    // a write to one mapping is visible in another
+    let opened = shared::open::<f32>(&name)?;
+    producer.write_value_at(&[2, 3], 100.)?;
+    assert_eq!(opened.read_value(&[2, 3])?, 100.);
+    assert_eq!(opened.shape(), &[3, 4]);
+
+    assert!(shared::open::<f64>(&name).is_err());
+
+    shared::unlink(&name)?;
+    assert!(shared::open::<f32>(&name).is_err());
+
+    // existing mappings remain valid after the segment is unlinked
+    assert_eq!(opened.sum_all()?, 66. - 11. + 100.);
+
+    assert!(shared::create::<f32>("not/valid", shape![1]).is_err());
+    assert!(shared::create::<f32>(&name, shape![1 << 62, 8]).is_err());
+
+    // the header of a segment written by another process must not be trusted
+    #[cfg(target_os = "linux")]
+    for shape in [shape![1 << 62, 8], shape![1 << 40]] {
+        let mut segment = io::ipc::Header::new::<f32>(shape).encode();
+        segment.resize(4096 + 16, 0);
+
+        let path = format!("/dev/shm/{name}");
+        std::fs::write(&path, segment).expect("segment");
+        let opened = shared::open::<f32>(&name);
+        std::fs::remove_file(&path).expect("cleanup");
+
+        assert!(opened.is_err());
+    }
+
+    Ok(())
+}
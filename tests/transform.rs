@@ -24,7 +24,7 @@ fn test_slice_1d() -> Result<(), Error> {
 
     let expected = ArrayOp::range(1, 3, shape![2])?;
 
-    let actual = input.slice(range![(1..3).into()])?;
+    let actual = input.slice(range![1..3])?;
 
     assert_eq!(expected.shape(), actual.shape());
     assert!(expected.eq(actual)?.all()?);
@@ -32,12 +32,73 @@ fn test_slice_1d() -> Result<(), Error> {
     Ok(())
 }
 
+#[test]
+fn test_slice_syntax() -> Result<(), Error> {
+    let input = ArrayBuf::new((0..24).collect::<Vec<_>>(), shape![2, 3, 4])?;
+
+    let actual = input.clone().slice(range![-1, .., (0..4).step_by(2)])?;
+    let expected = ArrayBuf::new(vec![12, 14, 16, 18, 20, 22], shape![3, 2])?;
+    assert_eq!(expected.shape(), actual.shape());
+    assert!(expected.eq(actual)?.all()?);
+
+    let actual = input.clone().slice(range![0, 1..=2, -2..])?;
+    let expected = ArrayBuf::new(vec![6, 7, 10, 11], shape![2, 2])?;
+    assert_eq!(expected.shape(), actual.shape());
+    assert!(expected.eq(actual)?.all()?);
+
+    let actual = input.clone().slice(range![.., ..-1, 3])?;
+    let expected = ArrayBuf::new(vec![3, 7, 15, 19], shape![2, 2])?;
+    assert_eq!(expected.shape(), actual.shape());
+    assert!(expected.eq(actual)?.all()?);
+
+    // an open range from the end of an axis is empty
+    let actual = input.clone().slice(range![.., 3.., 4..])?;
+    assert_eq!(actual.shape(), &[2, 0, 0]);
+    assert!(actual.buffer()?.to_slice()?.is_empty());
+
+    let actual = input.clone().slice(range![1, 1..1])?;
+    assert_eq!(actual.shape(), &[0, 4]);
+
+    assert!(input.clone().slice(range![.., 4..]).is_err());
+    assert!(input.clone().slice(range![-3]).is_err());
+    assert!(input.slice(range![0, 0, 0, 0]).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_read_range() -> Result<(), Error> {
+    let input = ArrayOp::range(0u64, 1_000_000, shape![1000, 1000])?;
+
+    // a single row of an elementwise op is computed one element at a time
+    let row = input.add_scalar(1)?.read_range(range![2, 10..15])?;
+    assert_eq!(row.shape(), &[5]);
+    assert_eq!(&*row.buffer()?.to_slice()?, &[2011, 2012, 2013, 2014, 2015]);
+
+    // a larger region is sliced on its platform before it's read back
+    let input = ArrayOp::range(0u64, 1_000_000, shape![1000, 1000])?;
+    let block = input.mul_scalar(2)?.read_range(range![1..3, 0..100])?;
+    assert_eq!(block.shape(), &[2, 100]);
+    assert_eq!(block.read_value(&[1, 99])?, 2 * 2099);
+
+    // a matrix product cannot compute an individual element, so its slice is computed instead
+    let left = ArrayBuf::new((0..6).collect::<Vec<u32>>(), shape![2, 3])?;
+    let right = ArrayBuf::new((0..6).collect::<Vec<u32>>(), shape![3, 2])?;
+    let product = left.matmul(right)?.read_range(range![1])?;
+    assert_eq!(&*product.buffer()?.to_slice()?, &[28, 40]);
+
+    let input = ArrayOp::range(0u64, 4, shape![2, 2])?;
+    assert!(input.read_range(range![2]).is_err());
+
+    Ok(())
+}
+
 #[test]
 fn test_slice_2d() -> Result<(), Error> {
     let input = ArrayOp::range(0, 12, shape![4, 3])?;
     let expected = ArrayOp::range(3, 9, shape![2, 3])?;
 
-    let actual = input.slice(range![(1..3).into()])?;
+    let actual = input.slice(range![1..3])?;
 
     assert_eq!(expected.shape(), actual.shape());
     assert!(
@@ -53,7 +114,7 @@ fn test_slice_2d() -> Result<(), Error> {
 #[test]
 fn test_slice_and_write() -> Result<(), Error> {
     let mut input = ArrayBuf::constant(0, shape![512])?;
-    let mut slice = input.as_mut().slice(range![(128..256).into()])?;
+    let mut slice = input.as_mut().slice(range![128..256])?;
     slice.write_value(1)?;
 
     let expected = iter::repeat(0)
@@ -75,7 +136,7 @@ fn test_slice_3d() -> Result<(), Error> {
 
     let expected = ArrayBuf::new(vec![8, 9, 10, 11], shape![2, 2])?;
 
-    let actual = input.slice(range![1.into(), (1..3).into()])?;
+    let actual = input.slice(range![1, 1..3])?;
 
     assert_eq!(expected.shape(), actual.shape());
     assert!(expected.eq(actual)?.all()?);
@@ -128,12 +189,465 @@ fn test_transpose_3d() -> Result<(), Error> {
     Ok(())
 }
 
+#[test]
+fn test_reverse() -> Result<(), Error> {
+    let input = ArrayBuf::new((0..6u32).collect::<Vec<_>>(), shape![2, 3])?;
+
+    let rows = input.clone().reverse(axes![0])?;
+    assert_eq!(rows.shape(), &[2, 3]);
+    assert_eq!(&*rows.buffer()?.to_slice()?, &[3, 4, 5, 0, 1, 2]);
+
+    let both = input.clone().reverse(axes![0, 1])?;
+    assert_eq!(&*both.buffer()?.to_slice()?, &[5, 4, 3, 2, 1, 0]);
+
+    // reversal composes with other views
+    let transposed = input.clone().reverse(axes![1])?.transpose(None)?;
+    assert_eq!(&*transposed.buffer()?.to_slice()?, &[2, 5, 1, 4, 0, 3]);
+
+    let broadcast = ArrayOp::range(0u32, 3, shape![3])?
+        .reverse(axes![0])?
+        .broadcast(shape![2, 3])?;
+    assert_eq!(&*broadcast.buffer()?.to_slice()?, &[2, 1, 0, 2, 1, 0]);
+
+    let twice = input.clone().reverse(axes![1])?.reverse(axes![1])?;
+    assert!(twice.eq(input.clone())?.all()?);
+
+    assert!(input.clone().reverse(axes![2]).is_err());
+    assert!(input.reverse(axes![1, 1]).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_roll_and_flip() -> Result<(), Error> {
+    let input = ArrayBuf::new((0..6u32).collect::<Vec<_>>(), shape![2, 3])?;
+
+    let cols = input.clone().roll(1, 1)?;
+    assert_eq!(cols.shape(), &[2, 3]);
+    assert_eq!(&*cols.buffer()?.to_slice()?, &[2, 0, 1, 5, 3, 4]);
+
+    let back = input.clone().roll(-1, 1)?;
+    assert_eq!(&*back.buffer()?.to_slice()?, &[1, 2, 0, 4, 5, 3]);
+
+    let rows = input.clone().roll(3, 0)?;
+    assert_eq!(&*rows.buffer()?.to_slice()?, &[3, 4, 5, 0, 1, 2]);
+
+    // a full rotation is the identity
+    let full = input.clone().roll(6, 1)?;
+    assert!(full.eq(input.clone())?.all()?);
+
+    // rolling composes with other views, e.g. to shift a spectrum
+    let flipped = input.clone().flip(axes![1])?.roll(1, 1)?;
+    assert_eq!(&*flipped.buffer()?.to_slice()?, &[0, 2, 1, 3, 5, 4]);
+
+    let broadcast = ArrayOp::range(0u32, 4, shape![4])?
+        .roll(2, 0)?
+        .broadcast(shape![2, 4])?;
+    assert_eq!(&*broadcast.buffer()?.to_slice()?, &[2, 3, 0, 1, 2, 3, 0, 1]);
+
+    let large = ArrayOp::range(0u64, 100_000, shape![100_000])?.roll(-10, 0)?;
+    assert_eq!(large.read_value(&[0])?, 10);
+    assert_eq!(large.read_value(&[99_999])?, 9);
+    assert_eq!(large.buffer()?.to_slice()?[99_990], 0);
+
+    assert!(input.roll(1, 2).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_as_strided() -> Result<(), Error> {
+    let input = ArrayBuf::new((0..6u32).collect::<Vec<_>>(), shape![6])?;
+
+    // a Hankel matrix, whose anti-diagonals are constant
+    let hankel = unsafe { input.clone().as_strided(shape![3, 3], stackvec![1, 1], 1)? };
+    assert_eq!(hankel.shape(), &[3, 3]);
+    assert_eq!(&*hankel.buffer()?.to_slice()?, &[1, 2, 3, 2, 3, 4, 3, 4, 5]);
+
+    // overlapping windows of size 3 with a step of 2
+    let windows = unsafe { input.clone().as_strided(shape![2, 3], stackvec![2, 1], 0)? };
+    assert_eq!(&*windows.buffer()?.to_slice()?, &[0, 1, 2, 2, 3, 4]);
+
+    // a zero stride repeats the source, like a broadcast
+    let repeated = unsafe { input.clone().as_strided(shape![2, 2], stackvec![0, 3], 2)? };
+    assert_eq!(&*repeated.buffer()?.to_slice()?, &[2, 5, 2, 5]);
+
+    assert!(unsafe { input.as_strided(shape![2, 3], stackvec![1], 0) }.is_err());
+
+    Ok(())
+}
+
 #[test]
 fn test_offsets_to_coords() -> Result<(), Error> {
     let coords = ArrayBuf::new(stackvec![0, 1], shape![1, 2])?;
-    let strides = ArrayBuf::new(vec![5, 1], shape![2])?.broadcast(coords.shape().into())?;
+    let strides = ArrayBuf::new(vec![5, 1], shape![2])?.broadcast(coords.shape())?;
     let offsets = coords.mul(strides).map(ArrayAccess::from)?;
     let offsets = offsets.sum(axes![1], false)?;
     assert_eq!(offsets.buffer()?.to_slice()?.into_vec(), vec![1]);
     Ok(())
 }
+
+#[test]
+fn test_spec_offsets() -> Result<(), Error> {
+    let spec = ops::SliceSpec::try_new(&[4, 5], range![AxisRange::In(1, 4, 2)])?;
+    assert_eq!(spec.shape.as_slice(), &[2, 5]);
+    assert_eq!(spec.source_offset(3), 8);
+
+    let spec = ops::SliceSpec::try_new(&[4, 5], range![AxisRange::At(2), AxisRange::At(3)])?;
+    assert_eq!(spec.source_offset(0), 13);
+
+    assert!(ops::SliceSpec::try_new(&[4, 5], range![AxisRange::In(0, 4, 0)]).is_err());
+    assert!(ops::SliceSpec::try_new(&[4], range![AxisRange::At(0), AxisRange::At(0)]).is_err());
+
+    let spec = ops::ViewSpec::try_transpose(&[2, 3], &[1, 0])?;
+    assert_eq!(spec.source_offset(1), 3);
+    assert!(ops::ViewSpec::try_transpose(&[2, 3], &[1, 1]).is_err());
+
+    let spec = ops::ViewSpec::try_broadcast(&[3, 1], shape![2, 3, 4])?;
+    assert_eq!(spec.source_offset(17), 1);
+    assert!(ops::ViewSpec::try_broadcast(&[3, 2], shape![3, 4]).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_chunks() -> Result<(), Error> {
+    let input = ArrayBuf::new((0..10).collect::<Vec<_>>(), shape![5, 2])?;
+
+    let chunks = input.clone().chunks(2, 0)?;
+    assert_eq!(chunks.len(), 3);
+
+    let expected = [vec![0, 1, 2, 3], vec![4, 5, 6, 7], vec![8, 9]];
+    for (chunk, expected) in chunks.zip(expected) {
+        let chunk = chunk?;
+        assert_eq!(chunk.shape()[1], 2);
+        assert_eq!(chunk.buffer()?.to_slice()?.into_vec(), expected);
+    }
+
+    let chunks = input.clone().chunks(1, 1)?.collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(chunks.len(), 2);
+    assert_eq!(chunks[1].shape(), &[5, 1]);
+    assert_eq!(
+        chunks[1].buffer()?.to_slice()?.into_vec(),
+        vec![1, 3, 5, 7, 9]
+    );
+
+    assert!(input.clone().chunks(0, 0).is_err());
+    assert!(input.chunks(1, 2).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_split() -> Result<(), Error> {
+    let input = ArrayBuf::new((0..12).collect::<Vec<_>>(), shape![2, 6])?;
+
+    let slices = input.clone().split(&[1, 2, 3], 1)?;
+    assert_eq!(slices.len(), 3);
+    assert_eq!(slices[0].shape(), &[2, 1]);
+    assert_eq!(slices[1].buffer()?.to_slice()?.into_vec(), vec![1, 2, 7, 8]);
+    assert_eq!(
+        slices[2].buffer()?.to_slice()?.into_vec(),
+        vec![3, 4, 5, 9, 10, 11]
+    );
+
+    assert!(input.clone().split(&[1, 2], 1).is_err());
+    assert!(input.split(&[2], 2).is_err());
+
+    // a part of size zero is an empty slice, wherever it is
+    let input = ArrayBuf::new(vec![1, 2, 3], shape![3])?;
+
+    for sizes in [[3, 0], [0, 3]] {
+        let slices = input.clone().split(&sizes, 0)?;
+        assert_eq!(slices.len(), 2);
+
+        for (slice, size) in slices.into_iter().zip(sizes) {
+            assert_eq!(slice.shape(), &[size]);
+            assert_eq!(slice.buffer()?.to_slice()?.len(), size);
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_bitcast() -> Result<(), Error> {
+    let floats = vec![1.0f32, -2.5, 0., f32::INFINITY];
+    let bits = floats.iter().map(|f| f.to_bits()).collect::<Vec<u32>>();
+
+    let array = ArrayBuf::new(floats.clone(), shape![2, 2])?;
+    let cast = array.bitcast::<u32>()?;
+    assert_eq!(cast.shape(), &[2, 2]);
+    assert_eq!(cast.read_value(&[0, 1])?, (-2.5f32).to_bits());
+    assert_eq!(cast.buffer()?.to_slice()?.into_vec(), bits);
+
+    let roundtrip = ArrayBuf::new(bits, shape![4])?
+        .bitcast::<f32>()?
+        .bitcast::<i32>()?
+        .bitcast::<f32>()?;
+
+    assert_eq!(roundtrip.buffer()?.to_slice()?.into_vec(), floats);
+
+    let large = ArrayOp::range(0., 1000., shape![1000])?.bitcast::<u64>()?;
+    assert_eq!(large.read_value(&[3])?, 3f64.to_bits());
+    assert_eq!(large.buffer()?.to_slice()?[999], 999f64.to_bits());
+
+    assert!(ArrayBuf::new(vec![0f32; 4], shape![4])?
+        .bitcast::<u64>()
+        .is_err());
+    assert!(ArrayBuf::new(vec![0u8; 4], shape![4])?
+        .bitcast::<i64>()
+        .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_sel() -> Result<(), Error> {
+    let data = (0..12).map(|n| n as f32).collect::<Vec<_>>();
+    let array = ArrayBuf::new(data, shape![4, 3])?
+        .with_axis_labels(["year", "region"])?
+        .with_coords(0, vec![1990., 2000., 2010., 2020.])?;
+
+    let decade = array.clone().sel(0, 1995.0..=2010.0)?;
+    assert_eq!(decade.shape(), &[2, 3]);
+    assert_eq!(decade.meta().coords(0), Some(&[2000., 2010.][..]));
+    assert_eq!(decade.meta().axis_label(0), Some("year"));
+    assert_eq!(&*decade.buffer()?.to_slice()?, &[3., 4., 5., 6., 7., 8.]);
+
+    let recent = array.clone().sel(0, 2010.0..)?;
+    assert_eq!(recent.shape(), &[2, 3]);
+
+    let first = array.clone().sel(0, ..2000.0)?;
+    assert_eq!(&*first.buffer()?.to_slice()?, &[0., 1., 2.]);
+
+    assert!(array.clone().sel(0, 2030.0..).is_err());
+    assert!(array.clone().sel(1, 0.0..1.0).is_err());
+
+    let transposed = array.clone().transpose(None)?;
+    assert_eq!(transposed.meta().coords(1).map(|c| c.len()), Some(4));
+
+    // a reverse reverses the coordinates of the reversed axes
+    let reversed = array.clone().reverse(axes![0])?;
+    let coords = [2020., 2010., 2000., 1990.];
+    assert_eq!(reversed.meta().coords(0), Some(&coords[..]));
+    let last = reversed.sel(0, 2015.0..)?;
+    assert_eq!(&*last.buffer()?.to_slice()?, &[9., 10., 11.]);
+
+    // a roll drops the coordinates of the rolled axis, which are no longer monotonic
+    let rolled = array.clone().roll(1, 0)?;
+    assert_eq!(rolled.meta().coords(0), None);
+    assert!(rolled.sel(0, 2015.0..).is_err());
+    assert_eq!(
+        array.clone().roll(4, 0)?.meta().coords(0).map(|c| c.len()),
+        Some(4)
+    );
+
+    // an op which resizes an axis drops its coordinates
+    let upsampled = array.clone().upsample([2, 1], Interpolation::Nearest)?;
+    assert_eq!(upsampled.meta().coords(0), None);
+    assert_eq!(upsampled.meta().axis_label(0), Some("year"));
+
+    let descending = ArrayBuf::new(vec![1f32, 2., 3., 4.], shape![4])?
+        .with_coords(0, vec![40., 30., 20., 10.])?;
+
+    let middle = descending.sel(0, 15.0..35.0)?;
+    assert_eq!(&*middle.buffer()?.to_slice()?, &[2., 3.]);
+    assert_eq!(middle.meta().coords(0), Some(&[30., 20.][..]));
+
+    let unsorted = ArrayBuf::new(vec![0f32; 3], shape![3])?.with_coords(0, vec![1., 3., 2.]);
+    assert!(unsorted.is_err());
+
+    let short = ArrayBuf::new(vec![0f32; 3], shape![3])?.with_coords(0, vec![1., 2.]);
+    assert!(short.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_gather() -> Result<(), Error> {
+    let data = (0..12).map(|n| n as f32).collect::<Vec<_>>();
+    let array = ArrayBuf::new(data, shape![3, 4])?;
+
+    let rows = ArrayBuf::new(vec![2u64, 0, 2], shape![3])?;
+    let gathered = array.clone().gather(rows, 0)?;
+    assert_eq!(gathered.shape(), &[3, 4]);
+    assert_eq!(
+        &*gathered.buffer()?.to_slice()?,
+        &[8., 9., 10., 11., 0., 1., 2., 3., 8., 9., 10., 11.]
+    );
+
+    let cols = ArrayBuf::new(vec![3u64, 1, 0, 0], shape![2, 2])?;
+    let gathered = array.clone().gather(cols, 1)?;
+    assert_eq!(gathered.shape(), &[3, 2, 2]);
+    assert_eq!(gathered.read_value(&[1, 0, 0])?, 7.);
+    assert_eq!(
+        &*gathered.buffer()?.to_slice()?,
+        &[3., 1., 0., 0., 7., 5., 4., 4., 11., 9., 8., 8.]
+    );
+
+    let large = ArrayBuf::new((0..100_000).collect::<Vec<u64>>(), shape![100_000])?;
+    let reversed = ArrayBuf::new((0..100_000).rev().collect::<Vec<u64>>(), shape![100_000])?;
+    let gathered = large.clone().gather(reversed, 0)?;
+    assert_eq!(gathered.read_value(&[0])?, 99_999);
+    assert_eq!(gathered.buffer()?.to_slice()?[99_999], 0);
+
+    // an index out of bounds is an error on every platform
+    let mut indices = (0..100_000).collect::<Vec<u64>>();
+    indices[50_000] = 100_000;
+    let indices = ArrayBuf::new(indices, shape![100_000])?;
+    assert!(large.gather(indices, 0)?.buffer().is_err());
+
+    let invalid = ArrayBuf::new(vec![4u64], shape![1])?;
+    assert!(array.clone().gather(invalid, 1)?.buffer().is_err());
+    assert!(array
+        .gather(ArrayBuf::new(vec![0u64], shape![1])?, 2)
+        .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_scatter() -> Result<(), Error> {
+    let mut array = ArrayBuf::constant(0i32, shape![3, 4])?;
+
+    let rows = ArrayBuf::new(vec![2u64, 0], shape![2])?;
+    let values = ArrayOp::range(1, 9, shape![2, 4])?;
+    array.scatter(rows, values, 0)?;
+    assert_eq!(
+        &*array.buffer()?.to_slice()?,
+        &[5, 6, 7, 8, 0, 0, 0, 0, 1, 2, 3, 4]
+    );
+
+    let cols = ArrayBuf::new(vec![1u64, 1, 3], shape![3])?;
+    let values = ArrayBuf::constant(10, shape![3, 3])?;
+    array.scatter_add(cols, values, 1)?;
+    assert_eq!(
+        &*array.buffer()?.to_slice()?,
+        &[5, 26, 7, 18, 0, 20, 0, 10, 1, 22, 3, 14]
+    );
+
+    // scatter_add is the adjoint of gather
+    let mut counts = ArrayBuf::constant(0f32, shape![5])?;
+    let labels = ArrayBuf::new(vec![4u64, 0, 4, 4, 2, 0], shape![6])?;
+    counts.scatter_add(labels, ArrayBuf::constant(1f32, shape![6])?, 0)?;
+    assert_eq!(&*counts.buffer()?.to_slice()?, &[2., 0., 1., 0., 3.]);
+
+    let invalid = ArrayBuf::new(vec![0u64, 5], shape![2])?;
+    assert!(counts
+        .scatter(invalid, ArrayBuf::constant(9f32, shape![2])?, 0)
+        .is_err());
+    assert_eq!(&*counts.buffer()?.to_slice()?, &[2., 0., 1., 0., 3.]);
+
+    // an index out of bounds leaves the array unchanged on every platform
+    let mut large = ArrayBuf::constant(0u32, shape![100_000])?;
+    let mut indices = (0..100_000).collect::<Vec<u64>>();
+    indices[50_000] = 100_000;
+    let indices = ArrayBuf::new(indices, shape![100_000])?;
+    let values = ArrayBuf::constant(1u32, shape![100_000])?;
+    assert!(large.scatter(indices, values, 0).is_err());
+    assert_eq!(large.sum_all()?, 0);
+
+    let indices = ArrayBuf::new(vec![0u64], shape![1])?;
+    let values = ArrayBuf::constant(1f32, shape![2])?;
+    assert!(counts.scatter(indices, values, 0).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_sort() -> Result<(), Error> {
+    let x = ArrayBuf::new(vec![3i32, 1, 2, 1, 9, 7, 8, 7], shape![2, 4])?;
+
+    let sorted = x.clone().sort(1, false)?;
+    assert_eq!(sorted.shape(), &[2, 4]);
+    assert_eq!(&*sorted.buffer()?.to_slice()?, &[1, 1, 2, 3, 7, 7, 8, 9]);
+    assert_eq!(sorted.read_value(&[1, 3])?, 9);
+
+    let sorted = x.clone().sort(1, true)?;
+    assert_eq!(&*sorted.buffer()?.to_slice()?, &[3, 2, 1, 1, 9, 8, 7, 7]);
+
+    let sorted = x.clone().sort(0, true)?;
+    assert_eq!(&*sorted.buffer()?.to_slice()?, &[9, 7, 8, 7, 3, 1, 2, 1]);
+
+    // ties keep their original order
+    let indices = x.clone().argsort(1)?;
+    assert_eq!(&*indices.buffer()?.to_slice()?, &[1, 3, 2, 0, 1, 3, 2, 0]);
+    assert_eq!(indices.read_value(&[0, 1])?, 3);
+
+    let indices = x.clone().argsort(0)?;
+    assert_eq!(&*indices.buffer()?.to_slice()?, &[0, 0, 0, 0, 1, 1, 1, 1]);
+
+    assert!(x.sort(2, false).is_err());
+
+    let x = ArrayBuf::new(vec![2., f32::NAN, -1., 0.5], shape![4])?;
+    let sorted = x.clone().sort(0, false)?.buffer()?.to_slice()?.into_vec();
+    assert_eq!(&sorted[..3], &[-1., 0.5, 2.]);
+    assert!(sorted[3].is_nan());
+    let sorted = x.clone().sort(0, true)?.buffer()?.to_slice()?.into_vec();
+    assert!(sorted[0].is_nan());
+    assert_eq!(&sorted[1..], &[2., 0.5, -1.]);
+    assert_eq!(&*x.argsort(0)?.buffer()?.to_slice()?, &[2, 3, 0, 1]);
+
+    // a single long lane, and many short strided lanes
+    let n = 10_000u64;
+    let reversed = ArrayOp::range(0., n as f64, shape![n as usize])?.mul_scalar(-1.)?;
+    let indices = reversed.argsort(0)?.buffer()?.to_slice()?.into_vec();
+    assert!(indices
+        .iter()
+        .zip((0..n).rev())
+        .all(|(i, expected)| *i == expected));
+
+    let x = ArrayOp::range(0., 600., shape![200, 3])?.mul_scalar(-1.)?;
+    let sorted = x.sort(0, false)?;
+    assert_eq!(sorted.read_value(&[0, 2])?, -599.);
+    let sorted = sorted.buffer()?.to_slice()?.into_vec();
+    assert!((0..3).all(|i| (1..200).all(|j| sorted[(j - 1) * 3 + i] < sorted[j * 3 + i])));
+
+    // an empty array has nothing to sort
+    for (shape, axis) in [
+        (shape![0], 0),
+        (shape![2, 0], 1),
+        (shape![0, 2], 1),
+        (shape![0, 2], 0),
+    ] {
+        let empty = ArrayBuf::new(Vec::<f32>::new(), shape)?;
+        assert!(empty
+            .clone()
+            .sort(axis, false)?
+            .buffer()?
+            .to_slice()?
+            .is_empty());
+        assert!(empty.argsort(axis)?.buffer()?.to_slice()?.is_empty());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_plain_dims() -> Result<(), Error> {
+    let array = ArrayBuf::new((0..24).collect::<Vec<i32>>(), [2, 3, 4])?;
+    assert_eq!(array.shape(), &[2, 3, 4]);
+
+    let reshaped = array.clone().reshape(vec![6, 4])?;
+    assert_eq!(reshaped.shape(), &[6, 4]);
+
+    let sliced = array
+        .clone()
+        .slice([AxisRange::At(1), AxisRange::In(0, 3, 2)])?;
+    assert_eq!(sliced.shape(), &[2, 4]);
+
+    let sum = array.clone().sum([0, 2], false)?;
+    assert_eq!(&*sum.buffer()?.to_slice()?, &[60, 92, 124]);
+
+    let dims = [1, 3, 4];
+    let broadcast = ArrayBuf::new(vec![1; 12], &dims[..])?.broadcast([2, 3, 4])?;
+    assert!(broadcast.eq(ArrayBuf::ones(vec![2, 3, 4])?)?.all()?);
+
+    let unsqueezed = sliced.unsqueeze([0])?.squeeze(vec![0])?;
+    assert_eq!(unsqueezed.shape(), &[2, 4]);
+
+    assert!(array.reshape(vec![5, 5]).is_err());
+
+    Ok(())
+}